@@ -15,6 +15,7 @@
 use datagen::error::Error as DatagenError;
 use numa_gpu::error::Error as NumaGpuError;
 use rayon::ThreadPoolBuildError;
+#[cfg(feature = "cuda")]
 use rustacuda::error::CudaError;
 use std::convert::From;
 
@@ -23,6 +24,7 @@ pub type Result<T> = std::result::Result<T, Error>;
 #[derive(Debug)]
 pub enum ErrorKind {
     CsvError(csv::Error),
+    #[cfg(feature = "cuda")]
     CudaError(CudaError),
     DatagenError(DatagenError),
     IntegerOverflow(String),
@@ -56,6 +58,7 @@ impl From<csv::Error> for Error {
     }
 }
 
+#[cfg(feature = "cuda")]
 impl From<CudaError> for Error {
     fn from(error: CudaError) -> Self {
         Self {
@@ -106,6 +109,7 @@ impl std::fmt::Display for ErrorKind {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             ErrorKind::CsvError(ref e) => e.fmt(f),
+            #[cfg(feature = "cuda")]
             ErrorKind::CudaError(ref e) => e.fmt(f),
             ErrorKind::DatagenError(ref e) => e.fmt(f),
             ErrorKind::IntegerOverflow(ref s) => write!(f, "Integer overflow: {}", s),
@@ -14,23 +14,117 @@
 
 use crate::error::{ErrorKind, Result};
 use csv::{ByteRecord, ReaderBuilder};
+use datagen::columnar::{self, ColumnarHeader};
 use flate2::read::GzDecoder;
 use numa_gpu::runtime::allocator::{self, DerefMemType};
 use numa_gpu::runtime::memory::*;
-use rustacuda::memory::DeviceCopy;
+use numa_gpu::runtime::numa::{NodeRatio, PageResidency};
+use rayon::prelude::*;
 use serde::de::DeserializeOwned;
 use std::collections::vec_deque::VecDeque;
+use std::convert::TryInto;
 use std::fs::File;
 use std::io::Read;
+use std::mem;
+use std::os::unix::fs::FileExt;
 use std::time::{Duration, Instant};
 
-pub type JoinDataGenFn<T> = Box<dyn FnMut(&mut [T], &mut [T], &mut [T], &mut [T]) -> Result<()>>;
+/// Number of bytes per positional read when loading a columnar file.
+///
+/// Each read is executed by a thread of the global Rayon thread pool.
+const COLUMNAR_READ_BYTES: usize = 16 * 1024 * 1024;
+
+pub type JoinDataGenFn<K, V = K> =
+    Box<dyn FnMut(&mut [K], &mut [V], &mut [K], &mut [V]) -> Result<()>>;
+
+/// The inner and outer relations of a join.
+///
+/// The keys have type `K` and the payloads have type `V`. The payload type
+/// defaults to the key type.
+pub struct JoinData<K: DeviceCopy, V: DeviceCopy = K> {
+    pub build_relation_key: Mem<K>,
+    pub build_relation_payload: Mem<V>,
+    pub probe_relation_key: Mem<K>,
+    pub probe_relation_payload: Mem<V>,
+}
+
+impl<K: DeviceCopy, V: DeviceCopy> JoinData<K, V> {
+    /// Queries the NUMA nodes on which the build relation actually resides.
+    ///
+    /// Returns `None` if the relation is in CUDA unified or device memory,
+    /// because its pages can reside on the GPU.
+    pub fn build_relation_residency(&self) -> Result<Option<PageResidency>> {
+        relation_residency(&self.build_relation_key, &self.build_relation_payload)
+    }
+
+    /// Queries the NUMA nodes on which the probe relation actually resides.
+    ///
+    /// Returns `None` if the relation is in CUDA unified or device memory,
+    /// because its pages can reside on the GPU.
+    pub fn probe_relation_residency(&self) -> Result<Option<PageResidency>> {
+        relation_residency(&self.probe_relation_key, &self.probe_relation_payload)
+    }
+
+    /// Returns the number of build relation bytes that are backed by huge
+    /// pages.
+    ///
+    /// Returns `None` if the relation is in CUDA unified or device memory.
+    pub fn build_relation_huge_page_bytes(&self) -> Result<Option<usize>> {
+        relation_huge_page_bytes(&self.build_relation_key, &self.build_relation_payload)
+    }
+
+    /// Returns the number of probe relation bytes that are backed by huge
+    /// pages.
+    ///
+    /// Returns `None` if the relation is in CUDA unified or device memory.
+    pub fn probe_relation_huge_page_bytes(&self) -> Result<Option<usize>> {
+        relation_huge_page_bytes(&self.probe_relation_key, &self.probe_relation_payload)
+    }
+
+    /// Migrates the build relation to the NUMA nodes.
+    ///
+    /// See `Mem::migrate` for details.
+    pub fn migrate_build_relation(&mut self, node_ratios: &[NodeRatio]) -> Result<()> {
+        self.build_relation_key.migrate(node_ratios)?;
+        self.build_relation_payload.migrate(node_ratios)?;
+        Ok(())
+    }
 
-pub struct JoinData<T: DeviceCopy> {
-    pub build_relation_key: Mem<T>,
-    pub build_relation_payload: Mem<T>,
-    pub probe_relation_key: Mem<T>,
-    pub probe_relation_payload: Mem<T>,
+    /// Migrates the probe relation to the NUMA nodes.
+    ///
+    /// See `Mem::migrate` for details.
+    pub fn migrate_probe_relation(&mut self, node_ratios: &[NodeRatio]) -> Result<()> {
+        self.probe_relation_key.migrate(node_ratios)?;
+        self.probe_relation_payload.migrate(node_ratios)?;
+        Ok(())
+    }
+}
+
+/// Queries the NUMA nodes on which the key and payload columns reside.
+fn relation_residency<K: DeviceCopy, V: DeviceCopy>(
+    key: &Mem<K>,
+    payload: &Mem<V>,
+) -> Result<Option<PageResidency>> {
+    if !key.has_page_residency() || !payload.has_page_residency() {
+        return Ok(None);
+    }
+
+    let mut residency = key.page_residency()?;
+    residency.merge(&payload.page_residency()?);
+    Ok(Some(residency))
+}
+
+/// Counts the bytes of the key and payload columns that are backed by huge
+/// pages.
+fn relation_huge_page_bytes<K: DeviceCopy, V: DeviceCopy>(
+    key: &Mem<K>,
+    payload: &Mem<V>,
+) -> Result<Option<usize>> {
+    if !key.has_page_residency() || !payload.has_page_residency() {
+        return Ok(None);
+    }
+
+    Ok(Some(key.huge_page_bytes()? + payload.huge_page_bytes()?))
 }
 
 pub struct JoinDataBuilder {
@@ -79,56 +173,21 @@ impl JoinDataBuilder {
         self
     }
 
-    fn allocate_relations<T>(
+    fn allocate_relations<K, V>(
         &self,
-    ) -> Result<(DerefMem<T>, DerefMem<T>, DerefMem<T>, DerefMem<T>, Duration)>
+    ) -> Result<(DerefMem<K>, DerefMem<V>, DerefMem<K>, DerefMem<V>, Duration)>
     where
-        T: Clone + Default + DeviceCopy,
+        K: Clone + Default + DeviceCopy,
+        V: Clone + Default + DeviceCopy,
     {
         // Allocate memory for data sets
         let malloc_timer = Instant::now();
-        let mut memory: VecDeque<_> = [
-            (self.inner_len, self.inner_mem_type.clone()),
-            (self.inner_len, self.inner_mem_type.clone()),
-            (self.outer_len, self.outer_mem_type.clone()),
-            (self.outer_len, self.outer_mem_type.clone()),
-        ]
-        .iter()
-        .cloned()
-        .map(|(len, mem_type)| {
-            let mut mem = allocator::Allocator::alloc_deref_mem(mem_type, len);
-
-            // Force the OS to physically allocate the memory
-            if self.do_mlock {
-                mem.mlock()?;
-            }
-
-            Ok(mem)
-        })
-        .collect::<Result<_>>()?;
+        let inner_key = self.allocate_relation(self.inner_len, self.inner_mem_type.clone())?;
+        let inner_payload = self.allocate_relation(self.inner_len, self.inner_mem_type.clone())?;
+        let outer_key = self.allocate_relation(self.outer_len, self.outer_mem_type.clone())?;
+        let outer_payload = self.allocate_relation(self.outer_len, self.outer_mem_type.clone())?;
         let malloc_time = malloc_timer.elapsed();
 
-        let inner_key = memory.pop_front().ok_or_else(|| {
-            ErrorKind::LogicError(
-                "Failed to get primary key relation. Is it allocated?".to_string(),
-            )
-        })?;
-        let inner_payload = memory.pop_front().ok_or_else(|| {
-            ErrorKind::LogicError(
-                "Failed to get primary key relation. Is it allocated?".to_string(),
-            )
-        })?;
-        let outer_key = memory.pop_front().ok_or_else(|| {
-            ErrorKind::LogicError(
-                "Failed to get foreign key relation. Is it allocated?".to_string(),
-            )
-        })?;
-        let outer_payload = memory.pop_front().ok_or_else(|| {
-            ErrorKind::LogicError(
-                "Failed to get foreign key relation. Is it allocated?".to_string(),
-            )
-        })?;
-
         Ok((
             inner_key,
             inner_payload,
@@ -138,12 +197,27 @@ impl JoinDataBuilder {
         ))
     }
 
-    pub fn build_with_data_gen<T>(
+    fn allocate_relation<T>(&self, len: usize, mem_type: DerefMemType) -> Result<DerefMem<T>>
+    where
+        T: Clone + Default + DeviceCopy,
+    {
+        let mut mem = allocator::Allocator::try_alloc_deref_mem(mem_type, len)?;
+
+        // Force the OS to physically allocate the memory
+        if self.do_mlock {
+            mem.mlock()?;
+        }
+
+        Ok(mem)
+    }
+
+    pub fn build_with_data_gen<K, V>(
         &mut self,
-        mut data_gen_fn: JoinDataGenFn<T>,
-    ) -> Result<(JoinData<T>, Duration, Duration)>
+        mut data_gen_fn: JoinDataGenFn<K, V>,
+    ) -> Result<(JoinData<K, V>, Duration, Duration)>
     where
-        T: Copy + Default + DeviceCopy,
+        K: Copy + Default + DeviceCopy,
+        V: Copy + Default + DeviceCopy,
     {
         let (mut inner_key, mut inner_payload, mut outer_key, mut outer_payload, malloc_time) =
             self.allocate_relations()?;
@@ -170,13 +244,136 @@ impl JoinDataBuilder {
         ))
     }
 
-    pub fn build_with_files<T: DeserializeOwned>(
+    /// Loads the inner and outer relations from files.
+    ///
+    /// The files are either in the binary columnar format (see
+    /// `datagen::columnar`), or text files with space-delimited "key value"
+    /// pairs. Text files ending in "gz" are decompressed automatically. Both
+    /// files must have the same format.
+    ///
+    /// Columnar files are read in parallel directly into the memory of the
+    /// requested `DerefMemType`. Their key and payload sizes must match the
+    /// sizes of `K` and `V`.
+    pub fn build_with_files<K, V>(
+        &mut self,
+        inner_relation_path: &str,
+        outer_relation_path: &str,
+    ) -> Result<(JoinData<K, V>, Duration, Duration)>
+    where
+        K: Copy + Default + DeserializeOwned + DeviceCopy,
+        V: Copy + Default + DeserializeOwned + DeviceCopy,
+    {
+        match (
+            columnar::is_columnar_file(inner_relation_path)?,
+            columnar::is_columnar_file(outer_relation_path)?,
+        ) {
+            (true, true) => {
+                self.build_with_columnar_files(inner_relation_path, outer_relation_path)
+            }
+            (false, false) => self.build_with_text_files(inner_relation_path, outer_relation_path),
+            _ => Err(ErrorKind::InvalidArgument(
+                "Inner and outer relation files must have the same format".to_string(),
+            ))?,
+        }
+    }
+
+    fn build_with_columnar_files<K, V>(
+        &mut self,
+        inner_relation_path: &str,
+        outer_relation_path: &str,
+    ) -> Result<(JoinData<K, V>, Duration, Duration)>
+    where
+        K: Copy + Default + DeviceCopy,
+        V: Copy + Default + DeviceCopy,
+    {
+        columnar::ensure_little_endian()?;
+
+        let io_timer = Instant::now();
+
+        let mut files = [&inner_relation_path, &outer_relation_path]
+            .iter()
+            .map(|path| {
+                let mut file = File::open(path)?;
+                let header = ColumnarHeader::read_from(&mut file)?;
+
+                if header.key_bytes as usize != mem::size_of::<K>()
+                    || header.payload_bytes as usize != mem::size_of::<V>()
+                {
+                    Err(ErrorKind::InvalidArgument(format!(
+                        "Relation file {} has {}-byte keys and {}-byte payloads, but expected {}-byte keys and {}-byte payloads",
+                        path,
+                        header.key_bytes,
+                        header.payload_bytes,
+                        mem::size_of::<K>(),
+                        mem::size_of::<V>()
+                    )))?;
+                }
+
+                if file.metadata()?.len() < header.file_len() {
+                    Err(ErrorKind::InvalidArgument(format!(
+                        "Relation file {} is truncated",
+                        path
+                    )))?;
+                }
+
+                Ok((file, header))
+            })
+            .collect::<Result<VecDeque<_>>>()?;
+
+        let (inner_file, inner_header) = files.pop_front().unwrap();
+        let (outer_file, outer_header) = files.pop_front().unwrap();
+
+        self.inner_len = inner_header
+            .tuples
+            .try_into()
+            .map_err(|_| ErrorKind::IntegerOverflow("Inner relation is too large".to_string()))?;
+        self.outer_len = outer_header
+            .tuples
+            .try_into()
+            .map_err(|_| ErrorKind::IntegerOverflow("Outer relation is too large".to_string()))?;
+
+        let io_header_time = io_timer.elapsed();
+
+        let (mut inner_key, mut inner_payload, mut outer_key, mut outer_payload, malloc_time) =
+            self.allocate_relations()?;
+
+        let io_timer = Instant::now();
+
+        read_column(&inner_file, inner_header.key_offset(), &mut inner_key)?;
+        read_column(
+            &inner_file,
+            inner_header.payload_offset(),
+            &mut inner_payload,
+        )?;
+        read_column(&outer_file, outer_header.key_offset(), &mut outer_key)?;
+        read_column(
+            &outer_file,
+            outer_header.payload_offset(),
+            &mut outer_payload,
+        )?;
+
+        let io_read_time = io_timer.elapsed();
+
+        Ok((
+            JoinData {
+                build_relation_key: inner_key.into(),
+                build_relation_payload: inner_payload.into(),
+                probe_relation_key: outer_key.into(),
+                probe_relation_payload: outer_payload.into(),
+            },
+            malloc_time,
+            io_header_time + io_read_time,
+        ))
+    }
+
+    fn build_with_text_files<K, V>(
         &mut self,
         inner_relation_path: &str,
         outer_relation_path: &str,
-    ) -> Result<(JoinData<T>, Duration, Duration)>
+    ) -> Result<(JoinData<K, V>, Duration, Duration)>
     where
-        T: Copy + Default + DeviceCopy,
+        K: Copy + Default + DeserializeOwned + DeviceCopy,
+        V: Copy + Default + DeserializeOwned + DeviceCopy,
     {
         let mut reader_spec = ReaderBuilder::new();
         reader_spec
@@ -244,7 +441,7 @@ impl JoinDataBuilder {
         let mut inner_key_iter = inner_key.iter_mut();
         let mut inner_payload_iter = inner_payload.iter_mut();
         while inner_reader.read_byte_record(&mut record)? {
-            let (key, value): (T, T) = record.deserialize(None)?;
+            let (key, value): (K, V) = record.deserialize(None)?;
             *inner_key_iter
                 .next()
                 .expect("Allocated length is too short") = key;
@@ -256,7 +453,7 @@ impl JoinDataBuilder {
         let mut outer_key_iter = outer_key.iter_mut();
         let mut outer_payload_iter = outer_payload.iter_mut();
         while outer_reader.read_byte_record(&mut record)? {
-            let (key, value): (T, T) = record.deserialize(None)?;
+            let (key, value): (K, V) = record.deserialize(None)?;
             *outer_key_iter
                 .next()
                 .expect("Allocated length is too short") = key;
@@ -279,3 +476,21 @@ impl JoinDataBuilder {
         ))
     }
 }
+
+/// Reads a column from a columnar file, starting at the byte `offset`.
+///
+/// The column is read in parallel with positional reads. Thus, the memory is
+/// written by multiple threads.
+fn read_column<T: Copy>(file: &File, offset: u64, column: &mut [T]) -> Result<()> {
+    // Safety: the column types are integers, for which all bit patterns are valid.
+    let bytes = unsafe { columnar::as_bytes_mut(column) };
+
+    bytes
+        .par_chunks_mut(COLUMNAR_READ_BYTES)
+        .enumerate()
+        .try_for_each(|(i, chunk)| {
+            file.read_exact_at(chunk, offset + (i * COLUMNAR_READ_BYTES) as u64)
+        })?;
+
+    Ok(())
+}
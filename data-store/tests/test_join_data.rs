@@ -0,0 +1,126 @@
+// Copyright 2022 Clemens Lutz
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use data_store::join_data::JoinDataBuilder;
+use datagen::columnar;
+use numa_gpu::runtime::allocator::DerefMemType;
+use std::convert::TryInto;
+use std::error::Error;
+use std::fs::{self, File};
+use std::io::BufWriter;
+use std::path::PathBuf;
+use std::process;
+use std::result::Result;
+
+fn temp_path(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("test_join_data_{}_{}", process::id(), name))
+}
+
+fn write_relation(path: &PathBuf, keys: &[i32]) -> Result<(), Box<dyn Error>> {
+    let mut writer = BufWriter::new(File::create(path)?);
+    columnar::write_columnar(&mut writer, keys, |i| -(i as i32))?;
+    Ok(())
+}
+
+#[test]
+fn build_with_columnar_files() -> Result<(), Box<dyn Error>> {
+    let inner_path = temp_path("inner.col");
+    let outer_path = temp_path("outer.col");
+
+    let inner_keys: Vec<i32> = (0..1000).collect();
+    let outer_keys: Vec<i32> = (0..(5 << 20)).map(|x| x % 1000).collect();
+    write_relation(&inner_path, &inner_keys)?;
+    write_relation(&outer_path, &outer_keys)?;
+
+    let result = JoinDataBuilder::default()
+        .inner_mem_type(DerefMemType::SysMem)
+        .outer_mem_type(DerefMemType::SysMem)
+        .build_with_files::<i32, i32>(inner_path.to_str().unwrap(), outer_path.to_str().unwrap());
+
+    fs::remove_file(&inner_path)?;
+    fs::remove_file(&outer_path)?;
+
+    let (join_data, _, _) = result?;
+
+    let build_key: &[i32] = (&join_data.build_relation_key).try_into().unwrap();
+    let build_payload: &[i32] = (&join_data.build_relation_payload).try_into().unwrap();
+    let probe_key: &[i32] = (&join_data.probe_relation_key).try_into().unwrap();
+    let probe_payload: &[i32] = (&join_data.probe_relation_payload).try_into().unwrap();
+
+    assert_eq!(inner_keys.as_slice(), build_key);
+    assert_eq!(outer_keys.as_slice(), probe_key);
+    assert!(build_payload
+        .iter()
+        .enumerate()
+        .all(|(i, &p)| p == -(i as i32)));
+    assert!(probe_payload
+        .iter()
+        .enumerate()
+        .all(|(i, &p)| p == -(i as i32)));
+
+    Ok(())
+}
+
+#[test]
+fn build_with_files_rejects_mixed_formats() -> Result<(), Box<dyn Error>> {
+    let inner_path = temp_path("mixed_inner.col");
+    let outer_path = temp_path("mixed_outer.tsv");
+
+    write_relation(&inner_path, &[1, 2, 3])?;
+    fs::write(&outer_path, "key value\n1 1\n")?;
+
+    let result = JoinDataBuilder::default()
+        .build_with_files::<i32, i32>(inner_path.to_str().unwrap(), outer_path.to_str().unwrap());
+
+    fs::remove_file(&inner_path)?;
+    fs::remove_file(&outer_path)?;
+
+    assert!(result.is_err());
+    Ok(())
+}
+
+#[test]
+fn build_with_columnar_files_mixed_types() -> Result<(), Box<dyn Error>> {
+    let inner_path = temp_path("mixed_types_inner.col");
+    let outer_path = temp_path("mixed_types_outer.col");
+
+    let inner_keys: Vec<i32> = (0..1000).collect();
+    let outer_keys: Vec<i32> = (0..10_000).map(|x| x % 1000).collect();
+    for (path, keys) in [(&inner_path, &inner_keys), (&outer_path, &outer_keys)].iter() {
+        let mut writer = BufWriter::new(File::create(path)?);
+        columnar::write_columnar(&mut writer, keys.as_slice(), |i| (i as i64) << 32)?;
+    }
+
+    let result = JoinDataBuilder::default()
+        .build_with_files::<i32, i64>(inner_path.to_str().unwrap(), outer_path.to_str().unwrap());
+    let mismatch = JoinDataBuilder::default()
+        .build_with_files::<i32, i32>(inner_path.to_str().unwrap(), outer_path.to_str().unwrap());
+
+    fs::remove_file(&inner_path)?;
+    fs::remove_file(&outer_path)?;
+
+    let (join_data, _, _) = result?;
+
+    let build_key: &[i32] = (&join_data.build_relation_key).try_into().unwrap();
+    let probe_payload: &[i64] = (&join_data.probe_relation_payload).try_into().unwrap();
+
+    assert_eq!(inner_keys.as_slice(), build_key);
+    assert!(probe_payload
+        .iter()
+        .enumerate()
+        .all(|(i, &p)| p == (i as i64) << 32));
+    assert!(mismatch.is_err());
+
+    Ok(())
+}
@@ -13,15 +13,16 @@
 // limitations under the License.
 
 use crossbeam_utils::thread;
+use datagen::columnar;
 use datagen::popular;
-use datagen::relation::{KeyAttribute, UniformRelation, ZipfRelation};
+use datagen::relation::{derive_seed, KeyAttribute, UniformRelation, ZipfRelation};
 use flate2::write::GzEncoder;
 use flate2::Compression;
 use rand::distributions::uniform::SampleUniform;
 use serde::ser::Serialize;
 use serde_derive::Serialize;
 use std::fs::File;
-use std::io::Write;
+use std::io::{BufWriter, Write};
 use std::path::PathBuf;
 use std::time::Instant;
 use structopt::clap::arg_enum;
@@ -50,8 +51,11 @@ fn main() -> Result<()> {
             let (inner_rel_writer, outer_rel_writer): (
                 Box<dyn Write + Send>,
                 Box<dyn Write + Send>,
-            ) = if join_cmd.no_compress {
-                (Box::new(inner_rel_file), Box::new(outer_rel_file))
+            ) = if join_cmd.no_compress || join_cmd.file_type == ArgFileType::Columnar {
+                (
+                    Box::new(BufWriter::new(inner_rel_file)),
+                    Box::new(BufWriter::new(outer_rel_file)),
+                )
             } else {
                 (
                     Box::new(GzEncoder::new(inner_rel_file, Compression::default())),
@@ -59,67 +63,39 @@ fn main() -> Result<()> {
                 )
             };
 
-            // Generate relations
-            match join_cmd.tuple_bytes {
-                ArgTupleBytes::Bytes8 => {
-                    let (inner_rel, outer_rel) = if let (Some(inner), Some(outer)) =
-                        (join_cmd.inner_rel_tuples, join_cmd.outer_rel_tuples)
-                    {
-                        generate::<i32>(inner, outer, distribution, Some(join_cmd.selectivity))?
-                    } else if let Some(data_set) = join_cmd.data_set {
-                        generate_popular::<i32>(data_set, Some(join_cmd.selectivity))?
-                    } else {
-                        unreachable!()
-                    };
-
-                    // Write the relations to file
-                    thread::scope(|s| {
-                        s.spawn(|_| {
-                            let pk_timer = Instant::now();
-                            write_file(inner_rel.as_slice(), inner_rel_writer, join_cmd.file_type)
-                                .expect("Failed to write PK file");
-                            let pk_time = Instant::now().duration_since(pk_timer).as_millis();
-                            println!("PK write time: {}", pk_time as f64 / 1000.0);
-                        });
-                        s.spawn(|_| {
-                            let fk_timer = Instant::now();
-                            write_file(outer_rel.as_slice(), outer_rel_writer, join_cmd.file_type)
-                                .expect("Failed to write FK file");
-                            let fk_time = Instant::now().duration_since(fk_timer).as_millis();
-                            println!("FK write time: {}", fk_time as f64 / 1000.0);
-                        });
-                    })
-                    .expect("Failure inside thread scope");
+            // Generate relations and write them to file
+            match (join_cmd.key_bytes, join_cmd.payload_bytes) {
+                (ArgAttributeBytes::Bytes4, ArgAttributeBytes::Bytes4) => {
+                    generate_join::<i32, i32>(
+                        join_cmd,
+                        distribution,
+                        inner_rel_writer,
+                        outer_rel_writer,
+                    )?
+                }
+                (ArgAttributeBytes::Bytes4, ArgAttributeBytes::Bytes8) => {
+                    generate_join::<i32, i64>(
+                        join_cmd,
+                        distribution,
+                        inner_rel_writer,
+                        outer_rel_writer,
+                    )?
+                }
+                (ArgAttributeBytes::Bytes8, ArgAttributeBytes::Bytes4) => {
+                    generate_join::<i64, i32>(
+                        join_cmd,
+                        distribution,
+                        inner_rel_writer,
+                        outer_rel_writer,
+                    )?
                 }
-                ArgTupleBytes::Bytes16 => {
-                    let (inner_rel, outer_rel) = if let (Some(inner), Some(outer)) =
-                        (join_cmd.inner_rel_tuples, join_cmd.outer_rel_tuples)
-                    {
-                        generate::<i64>(inner, outer, distribution, Some(join_cmd.selectivity))?
-                    } else if let Some(data_set) = join_cmd.data_set {
-                        generate_popular::<i64>(data_set, Some(join_cmd.selectivity))?
-                    } else {
-                        unreachable!()
-                    };
-
-                    // Write the relations to file
-                    thread::scope(|s| {
-                        s.spawn(|_| {
-                            let pk_timer = Instant::now();
-                            write_file(inner_rel.as_slice(), inner_rel_writer, join_cmd.file_type)
-                                .expect("Failed to write PK file");
-                            let pk_time = Instant::now().duration_since(pk_timer).as_millis();
-                            println!("PK write time: {}", pk_time as f64 / 1000.0);
-                        });
-                        s.spawn(|_| {
-                            let fk_timer = Instant::now();
-                            write_file(outer_rel.as_slice(), outer_rel_writer, join_cmd.file_type)
-                                .expect("Failed to write FK file");
-                            let fk_time = Instant::now().duration_since(fk_timer).as_millis();
-                            println!("FK write time: {}", fk_time as f64 / 1000.0);
-                        });
-                    })
-                    .expect("Failure inside thread scope");
+                (ArgAttributeBytes::Bytes8, ArgAttributeBytes::Bytes8) => {
+                    generate_join::<i64, i64>(
+                        join_cmd,
+                        distribution,
+                        inner_rel_writer,
+                        outer_rel_writer,
+                    )?
                 }
             }
         }
@@ -153,9 +129,9 @@ enum DataDistribution {
 arg_enum! {
     #[derive(Copy, Clone, Debug, PartialEq)]
     #[repr(usize)]
-    enum ArgTupleBytes {
+    enum ArgAttributeBytes {
+        Bytes4 = 4,
         Bytes8 = 8,
-        Bytes16 = 16,
     }
 }
 
@@ -165,6 +141,7 @@ arg_enum! {
     enum ArgFileType {
         Csv,
         Tsv,
+        Columnar,
     }
 }
 
@@ -195,16 +172,27 @@ struct CmdPkFkJoin {
     #[structopt(long = "no-compress")]
     no_compress: bool,
 
-    /// Set the tuple size (bytes)
+    /// Set the key size (bytes)
     #[structopt(
-        long = "tuple-bytes",
-        default_value = "Bytes8",
+        long = "key-bytes",
+        default_value = "Bytes4",
         raw(
-            possible_values = "&ArgTupleBytes::variants()",
+            possible_values = "&ArgAttributeBytes::variants()",
             case_insensitive = "true"
         )
     )]
-    tuple_bytes: ArgTupleBytes,
+    key_bytes: ArgAttributeBytes,
+
+    /// Set the payload size (bytes)
+    #[structopt(
+        long = "payload-bytes",
+        default_value = "Bytes4",
+        raw(
+            possible_values = "&ArgAttributeBytes::variants()",
+            case_insensitive = "true"
+        )
+    )]
+    payload_bytes: ArgAttributeBytes,
 
     /// Outer relation's data distribution
     #[structopt(
@@ -229,7 +217,12 @@ struct CmdPkFkJoin {
     )]
     selectivity: u32,
 
-    /// Set the output file type
+    /// Seed of the random number generator (default: random)
+    //   Generating with the same seed always results in the same relations.
+    #[structopt(long = "seed")]
+    seed: Option<u64>,
+
+    /// Set the output file type (columnar files are never compressed)
     #[structopt(
         long = "file-type",
         default_value = "Tsv",
@@ -279,11 +272,69 @@ struct Record<K, V> {
     value: V,
 }
 
+/// Generates the inner and outer relations with keys of type `K`, and writes
+/// them to file with payloads of type `V`.
+fn generate_join<K, V>(
+    join_cmd: &CmdPkFkJoin,
+    distribution: DataDistribution,
+    inner_rel_writer: Box<dyn Write + Send>,
+    outer_rel_writer: Box<dyn Write + Send>,
+) -> Result<()>
+where
+    K: Copy
+        + Default
+        + Send
+        + Sync
+        + Serialize
+        + KeyAttribute
+        + num_traits::FromPrimitive
+        + SampleUniform,
+    V: Copy + Default + Serialize + num_traits::FromPrimitive,
+{
+    let (inner_rel, outer_rel) = if let (Some(inner), Some(outer)) =
+        (join_cmd.inner_rel_tuples, join_cmd.outer_rel_tuples)
+    {
+        generate::<K>(
+            inner,
+            outer,
+            distribution,
+            Some(join_cmd.selectivity),
+            join_cmd.seed,
+        )?
+    } else if let Some(data_set) = join_cmd.data_set {
+        generate_popular::<K>(data_set, Some(join_cmd.selectivity), join_cmd.seed)?
+    } else {
+        unreachable!()
+    };
+
+    // Write the relations to file
+    thread::scope(|s| {
+        s.spawn(|_| {
+            let pk_timer = Instant::now();
+            write_file::<K, V>(inner_rel.as_slice(), inner_rel_writer, join_cmd.file_type)
+                .expect("Failed to write PK file");
+            let pk_time = Instant::now().duration_since(pk_timer).as_millis();
+            println!("PK write time: {}", pk_time as f64 / 1000.0);
+        });
+        s.spawn(|_| {
+            let fk_timer = Instant::now();
+            write_file::<K, V>(outer_rel.as_slice(), outer_rel_writer, join_cmd.file_type)
+                .expect("Failed to write FK file");
+            let fk_time = Instant::now().duration_since(fk_timer).as_millis();
+            println!("FK write time: {}", fk_time as f64 / 1000.0);
+        });
+    })
+    .expect("Failure inside thread scope");
+
+    Ok(())
+}
+
 fn generate<T>(
     inner_len: usize,
     outer_len: usize,
     dist: DataDistribution,
     selectivity: Option<u32>,
+    seed: Option<u64>,
 ) -> Result<(Vec<T>, Vec<T>)>
 where
     T: Copy + Default + Send + KeyAttribute + num_traits::FromPrimitive + SampleUniform,
@@ -292,16 +343,18 @@ where
     let mut outer_rel = vec![T::default(); outer_len];
 
     let pk_timer = Instant::now();
-    UniformRelation::gen_primary_key_par(&mut inner_rel, selectivity)?;
+    UniformRelation::gen_primary_key_par(&mut inner_rel, selectivity, derive_seed(seed, 0))?;
     let pk_time = Instant::now().duration_since(pk_timer).as_millis();
     println!("PK gen time: {}", pk_time as f64 / 1000.0);
 
     let fk_timer = Instant::now();
     match dist {
         DataDistribution::Uniform => {
-            UniformRelation::gen_attr_par(&mut outer_rel, 0..inner_rel.len())?
+            UniformRelation::gen_attr_par(&mut outer_rel, 0..inner_rel.len(), derive_seed(seed, 1))?
+        }
+        DataDistribution::Zipf(exp) => {
+            ZipfRelation::gen_attr_par(&mut outer_rel, inner_len, exp, derive_seed(seed, 1))?
         }
-        DataDistribution::Zipf(exp) => ZipfRelation::gen_attr_par(&mut outer_rel, inner_len, exp)?,
     };
     let fk_time = Instant::now().duration_since(fk_timer).as_millis();
     println!("FK gen time: {}", fk_time as f64 / 1000.0);
@@ -312,6 +365,7 @@ where
 fn generate_popular<T: Send + KeyAttribute>(
     data_set: ArgDataSet,
     selectivity: Option<u32>,
+    seed: Option<u64>,
 ) -> Result<(Vec<T>, Vec<T>)>
 where
     T: Copy + Default + num_traits::FromPrimitive,
@@ -323,13 +377,15 @@ where
             popular::Blanas::primary_key_len(),
             popular::Blanas::foreign_key_len(),
             Box::new(move |pk_rel, fk_rel| {
-                datagen::popular::Blanas::gen(pk_rel, fk_rel, selectivity)
+                datagen::popular::Blanas::gen(pk_rel, fk_rel, selectivity, seed)
             }),
         ),
         ArgDataSet::Kim => (
             popular::Kim::primary_key_len(),
             popular::Kim::foreign_key_len(),
-            Box::new(move |pk_rel, fk_rel| datagen::popular::Kim::gen(pk_rel, fk_rel, selectivity)),
+            Box::new(move |pk_rel, fk_rel| {
+                datagen::popular::Kim::gen(pk_rel, fk_rel, selectivity, seed)
+            }),
         ),
     };
 
@@ -340,8 +396,18 @@ where
     Ok((inner_rel, outer_rel))
 }
 
-fn write_file(rel: &[impl Serialize], writer: impl Write, file_type: ArgFileType) -> Result<()> {
+fn write_file<K, V>(rel: &[K], mut writer: impl Write, file_type: ArgFileType) -> Result<()>
+where
+    K: Copy + Serialize,
+    V: Copy + Default + Serialize + num_traits::FromPrimitive,
+{
     let mut ser_writer: Box<csv::Writer<_>> = match file_type {
+        ArgFileType::Columnar => {
+            columnar::write_columnar(&mut writer, rel, |value| {
+                V::from_usize(value + 1).expect("Failed to convert payload from usize")
+            })?;
+            return Ok(());
+        }
         ArgFileType::Csv => {
             let mut spec = csv::WriterBuilder::new();
             spec.has_headers(true).delimiter(b',');
@@ -359,7 +425,7 @@ fn write_file(rel: &[impl Serialize], writer: impl Write, file_type: ArgFileType
         .map(|(value, key)| {
             let record = Record {
                 key,
-                value: value + 1,
+                value: V::from_usize(value + 1).ok_or("Failed to convert payload from usize")?,
             };
             ser_writer.serialize(&record)?;
             Ok(())
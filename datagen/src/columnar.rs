@@ -0,0 +1,217 @@
+// Copyright 2022 Clemens Lutz
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A binary columnar file format for relations.
+//!
+//! Text formats such as CSV must be parsed tuple-by-tuple, which is much slower
+//! than executing a join on the loaded data. In contrast, the columnar format
+//! stores the raw attribute values. A reader can thus load a column directly
+//! into memory, e.g., with parallel positional reads.
+//!
+//! ## Layout
+//!
+//! A file consists of a fixed-size header, followed by the key column and the
+//! payload column. All integers are stored in little-endian byte order.
+//!
+//! | Offset | Bytes | Field                                    |
+//! |--------|-------|------------------------------------------|
+//! | 0      | 8     | Magic number `NGCOLREL`                  |
+//! | 8      | 4     | Format version, currently 1              |
+//! | 12     | 4     | Key size in bytes                        |
+//! | 16     | 4     | Payload size in bytes                    |
+//! | 20     | 4     | Reserved, must be 0                      |
+//! | 24     | 8     | Number of tuples                         |
+//! | 32     | ...   | Key column, followed by payload column   |
+//!
+//! The key column starts at `HEADER_BYTES`. The payload column starts
+//! immediately after the key column.
+//!
+//! ## Byte order
+//!
+//! Columns are copied to and from memory without conversion. Therefore, the
+//! reader and writer functions return an error on big-endian machines.
+
+use crate::error::{ErrorKind, Result};
+use std::convert::TryInto;
+use std::fs::File;
+use std::io::{Read, Write};
+use std::mem;
+use std::path::Path;
+use std::slice;
+
+/// Magic number that identifies a columnar relation file.
+pub const MAGIC: [u8; 8] = *b"NGCOLREL";
+
+/// Version of the file format.
+pub const VERSION: u32 = 1;
+
+/// Length of the file header in bytes.
+pub const HEADER_BYTES: usize = 32;
+
+/// Number of tuples written per write call.
+const WRITE_BATCH_LEN: usize = 1 << 16;
+
+/// The header of a columnar relation file.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct ColumnarHeader {
+    /// Size of a key in bytes.
+    pub key_bytes: u32,
+
+    /// Size of a payload in bytes.
+    pub payload_bytes: u32,
+
+    /// Number of tuples in the relation.
+    pub tuples: u64,
+}
+
+impl ColumnarHeader {
+    /// Creates a header for a relation with key type `K` and payload type `P`.
+    pub fn new<K, P>(tuples: usize) -> Self {
+        Self {
+            key_bytes: mem::size_of::<K>() as u32,
+            payload_bytes: mem::size_of::<P>() as u32,
+            tuples: tuples as u64,
+        }
+    }
+
+    /// Byte offset of the key column in the file.
+    pub fn key_offset(&self) -> u64 {
+        HEADER_BYTES as u64
+    }
+
+    /// Byte offset of the payload column in the file.
+    pub fn payload_offset(&self) -> u64 {
+        self.key_offset() + self.tuples * self.key_bytes as u64
+    }
+
+    /// Total length of the file in bytes.
+    pub fn file_len(&self) -> u64 {
+        self.payload_offset() + self.tuples * self.payload_bytes as u64
+    }
+
+    /// Serializes the header.
+    pub fn write_to<W: Write>(&self, writer: &mut W) -> Result<()> {
+        let mut bytes = [0_u8; HEADER_BYTES];
+        bytes[0..8].copy_from_slice(&MAGIC);
+        bytes[8..12].copy_from_slice(&VERSION.to_le_bytes());
+        bytes[12..16].copy_from_slice(&self.key_bytes.to_le_bytes());
+        bytes[16..20].copy_from_slice(&self.payload_bytes.to_le_bytes());
+        bytes[24..32].copy_from_slice(&self.tuples.to_le_bytes());
+
+        writer.write_all(&bytes)?;
+        Ok(())
+    }
+
+    /// Deserializes and validates the header.
+    ///
+    /// Returns an error if the magic number or the version don't match.
+    pub fn read_from<R: Read>(reader: &mut R) -> Result<Self> {
+        let mut bytes = [0_u8; HEADER_BYTES];
+        reader.read_exact(&mut bytes)?;
+
+        if bytes[0..8] != MAGIC {
+            Err(ErrorKind::InvalidArgument(
+                "Not a columnar relation file".to_string(),
+            ))?;
+        }
+
+        let version = u32::from_le_bytes(bytes[8..12].try_into().unwrap());
+        if version != VERSION {
+            Err(ErrorKind::InvalidArgument(format!(
+                "Unsupported columnar file version {}",
+                version
+            )))?;
+        }
+
+        Ok(Self {
+            key_bytes: u32::from_le_bytes(bytes[12..16].try_into().unwrap()),
+            payload_bytes: u32::from_le_bytes(bytes[16..20].try_into().unwrap()),
+            tuples: u64::from_le_bytes(bytes[24..32].try_into().unwrap()),
+        })
+    }
+}
+
+/// Checks if the file at `path` starts with the columnar magic number.
+pub fn is_columnar_file<P: AsRef<Path>>(path: P) -> Result<bool> {
+    let mut file = File::open(path)?;
+    let mut magic = [0_u8; 8];
+
+    match file.read_exact(&mut magic) {
+        Ok(()) => Ok(magic == MAGIC),
+        Err(ref e) if e.kind() == std::io::ErrorKind::UnexpectedEof => Ok(false),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Returns an error on big-endian machines.
+pub fn ensure_little_endian() -> Result<()> {
+    if cfg!(target_endian = "big") {
+        Err(ErrorKind::InvalidArgument(
+            "The columnar file format requires a little-endian machine".to_string(),
+        ))?;
+    }
+
+    Ok(())
+}
+
+/// Reinterprets a column as raw bytes.
+///
+/// The column type must be a primitive type without padding, e.g., an integer.
+fn as_bytes<T: Copy>(column: &[T]) -> &[u8] {
+    unsafe { slice::from_raw_parts(column.as_ptr() as *const u8, mem::size_of_val(column)) }
+}
+
+/// Reinterprets a mutable column as raw bytes.
+///
+/// Used to read a column from a file directly into its memory.
+///
+/// # Safety
+///
+/// All bit patterns must be valid values of `T`, which holds for the integer
+/// types.
+pub unsafe fn as_bytes_mut<T: Copy>(column: &mut [T]) -> &mut [u8] {
+    slice::from_raw_parts_mut(column.as_mut_ptr() as *mut u8, mem::size_of_val(column))
+}
+
+/// Writes a relation in the columnar format.
+///
+/// The key and payload types must be primitive types without padding, e.g.,
+/// integers. The payload of each tuple is computed by `payload_fn` from the
+/// tuple's position, which avoids materializing the payload column in memory.
+pub fn write_columnar<K, P, W, F>(writer: &mut W, keys: &[K], payload_fn: F) -> Result<()>
+where
+    K: Copy,
+    P: Copy + Default,
+    W: Write,
+    F: Fn(usize) -> P,
+{
+    ensure_little_endian()?;
+
+    ColumnarHeader::new::<K, P>(keys.len()).write_to(writer)?;
+    writer.write_all(as_bytes(keys))?;
+
+    let mut batch = vec![P::default(); WRITE_BATCH_LEN];
+    for begin in (0..keys.len()).step_by(WRITE_BATCH_LEN) {
+        let end = keys.len().min(begin + WRITE_BATCH_LEN);
+        let batch = &mut batch[0..(end - begin)];
+        batch
+            .iter_mut()
+            .zip(begin..end)
+            .for_each(|(payload, i)| *payload = payload_fn(i));
+        writer.write_all(as_bytes(batch))?;
+    }
+
+    writer.flush()?;
+    Ok(())
+}
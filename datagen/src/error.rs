@@ -21,6 +21,7 @@ pub enum ErrorKind {
     Msg(String),
     IntegerOverflow(String),
     InvalidArgument(String),
+    IoError(std::io::Error),
 }
 
 #[derive(Debug)]
@@ -39,12 +40,16 @@ impl std::error::Error for Error {
         match self.kind {
             ErrorKind::IntegerOverflow(ref s) => s.as_str(),
             ErrorKind::InvalidArgument(ref s) => s.as_str(),
+            ErrorKind::IoError(_) => "I/O error",
             ErrorKind::Msg(ref s) => s.as_str(),
         }
     }
 
     fn cause(&self) -> Option<&dyn std::error::Error> {
-        None
+        match self.kind {
+            ErrorKind::IoError(ref e) => Some(e),
+            _ => None,
+        }
     }
 }
 
@@ -54,6 +59,14 @@ impl std::fmt::Display for Error {
     }
 }
 
+impl From<std::io::Error> for Error {
+    fn from(error: std::io::Error) -> Self {
+        Self {
+            kind: ErrorKind::IoError(error),
+        }
+    }
+}
+
 impl From<ErrorKind> for Error {
     fn from(kind: ErrorKind) -> Self {
         Self { kind }
@@ -65,6 +78,7 @@ impl std::fmt::Display for ErrorKind {
         match self {
             ErrorKind::IntegerOverflow(ref s) => write!(f, "IntegerOverflow: {}", s),
             ErrorKind::InvalidArgument(ref s) => write!(f, "InvalidArgument: {}", s),
+            ErrorKind::IoError(ref e) => write!(f, "IoError: {}", e),
             ErrorKind::Msg(ref s) => write!(f, "Msg: {}", s),
         }
     }
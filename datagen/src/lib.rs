@@ -12,6 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+pub mod columnar;
 pub mod error;
 pub mod popular;
 pub mod relation;
@@ -15,7 +15,7 @@
 //! A collection of data set generators for data sets frequently found in
 //! published papers.
 
-use super::relation::{KeyAttribute, UniformRelation};
+use super::relation::{derive_seed, KeyAttribute, UniformRelation};
 use crate::error::Result;
 use num_traits::FromPrimitive;
 
@@ -47,16 +47,20 @@ impl Kim {
     /// `selectivity` specifies the join selectivity in percent. An according
     /// percentage of keys are set to the `NULL` value. By default (`None`), the
     /// selectivity is 100%.
+    ///
+    /// `seed` seeds the random number generators. By default (`None`), the
+    /// seed is random.
     pub fn gen<T: Copy + Send + KeyAttribute + FromPrimitive>(
         pk_attr: &mut [T],
         fk_attr: &mut [T],
         selectivity: Option<u32>,
+        seed: Option<u64>,
     ) -> Result<()> {
         assert!(pk_attr.len() == Self::primary_key_len());
         assert!(fk_attr.len() == Self::foreign_key_len());
 
-        UniformRelation::gen_primary_key_par(pk_attr, selectivity)?;
-        UniformRelation::gen_attr_par(fk_attr, 0..pk_attr.len())?;
+        UniformRelation::gen_primary_key_par(pk_attr, selectivity, derive_seed(seed, 0))?;
+        UniformRelation::gen_attr_par(fk_attr, 0..pk_attr.len(), derive_seed(seed, 1))?;
         Ok(())
     }
 }
@@ -90,16 +94,20 @@ impl Blanas {
     /// `selectivity` specifies the join selectivity in percent. An according
     /// percentage of keys are set to the `NULL` value. By default (`None`), the
     /// selectivity is 100%.
+    ///
+    /// `seed` seeds the random number generators. By default (`None`), the
+    /// seed is random.
     pub fn gen<T: Copy + Send + KeyAttribute + FromPrimitive>(
         pk_attr: &mut [T],
         fk_attr: &mut [T],
         selectivity: Option<u32>,
+        seed: Option<u64>,
     ) -> Result<()> {
         assert!(pk_attr.len() == Self::primary_key_len());
         assert!(fk_attr.len() == Self::foreign_key_len());
 
-        UniformRelation::gen_primary_key_par(pk_attr, selectivity)?;
-        UniformRelation::gen_attr_par(fk_attr, 0..pk_attr.len())?;
+        UniformRelation::gen_primary_key_par(pk_attr, selectivity, derive_seed(seed, 0))?;
+        UniformRelation::gen_attr_par(fk_attr, 0..pk_attr.len(), derive_seed(seed, 1))?;
         Ok(())
     }
 }
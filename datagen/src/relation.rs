@@ -15,6 +15,20 @@
 //! Data set generators for generating database relations.
 //!
 //! The generators produce relation attributes following a random distribution.
+//!
+//! ## Reproducibility
+//!
+//! All generators take an optional `seed`. Given the same seed, a generator
+//! produces the same attribute in every run. By default (`None`), the seed is
+//! chosen at random.
+//!
+//! The parallel generators split the attribute into blocks of `PAR_BLOCK_LEN`
+//! elements, and seed each block's random number generator from the seed and
+//! the block's position. Thus, the generated attribute doesn't depend on the
+//! number of threads.
+//!
+//! Generating multiple attributes with the same seed results in correlated
+//! attributes. Instead, derive one seed per attribute with `derive_seed`.
 
 use num_traits::FromPrimitive;
 
@@ -24,8 +38,9 @@ use std::convert::TryFrom;
 use std::ops::Range;
 
 use rand::distributions::{Distribution, Uniform};
+use rand::rngs::StdRng;
 use rand::seq::SliceRandom;
-use rand::{thread_rng, Rng};
+use rand::{thread_rng, Rng, SeedableRng};
 
 use rayon::prelude::*;
 
@@ -39,7 +54,8 @@ use zipf::ZipfDistribution;
 ///
 /// The `NULL` value is expected to have a binary representation of all ones. For
 /// signed integers, that value equals -1, for unsigned integers, the value
-/// equals 0xF...F.
+/// equals 0xF...F. Composite keys are `NULL` if all of their attributes are
+/// `NULL`.
 ///
 /// The `NULL` value in Rust must be kept in sync with the `NULL` value in C++
 /// and CUDA.
@@ -72,6 +88,64 @@ impl KeyAttribute for i64 {
     }
 }
 
+impl KeyAttribute for u32 {
+    fn null_key() -> Self {
+        Self::MAX
+    }
+
+    fn try_from_usize(x: usize) -> Result<Self> {
+        Self::try_from(x).map_err(|_| {
+            ErrorKind::IntegerOverflow("Failed to covnert from usize".to_string()).into()
+        })
+    }
+}
+
+impl KeyAttribute for u64 {
+    fn null_key() -> Self {
+        Self::MAX
+    }
+
+    fn try_from_usize(x: usize) -> Result<Self> {
+        Self::try_from(x).map_err(|_| {
+            ErrorKind::IntegerOverflow("Failed to covnert from usize".to_string()).into()
+        })
+    }
+}
+
+/// Number of elements per block in the parallel generators.
+///
+/// Each block is generated by its own random number generator. See the module
+/// documentation for details.
+pub const PAR_BLOCK_LEN: usize = 1 << 16;
+
+/// Derives a seed for the attribute with ID `stream` from `seed`.
+///
+/// The derived seeds are uncorrelated for different streams. Returns `None` if
+/// `seed` is `None`.
+pub fn derive_seed(seed: Option<u64>, stream: u64) -> Option<u64> {
+    seed.map(|seed| mix_seed(seed, stream))
+}
+
+/// Mixes the stream ID into the seed.
+///
+/// The mixing function is the finalizer of SplitMix64.
+fn mix_seed(seed: u64, stream: u64) -> u64 {
+    let mut z = seed.wrapping_add(stream.wrapping_add(1).wrapping_mul(0x9E37_79B9_7F4A_7C15));
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+/// Returns the seed, or a random seed if no seed is given.
+fn seed_or_random(seed: Option<u64>) -> u64 {
+    seed.unwrap_or_else(|| thread_rng().gen())
+}
+
+/// Creates the random number generator of a parallel generator's block.
+fn block_rng(seed: u64, block: usize) -> StdRng {
+    StdRng::seed_from_u64(mix_seed(seed, block as u64))
+}
+
 /// Generator for relations with uniform distribution.
 pub struct UniformRelation;
 
@@ -85,13 +159,17 @@ impl UniformRelation {
     /// `selectivity` specifies the join selectivity in percent. An according
     /// percentage of keys are set to the `NULL` value. By default (`None`), the
     /// selectivity is 100%.
+    ///
+    /// `seed` seeds the random number generator. By default (`None`), the seed
+    /// is random.
     pub fn gen_primary_key<T: KeyAttribute>(
         attr: &mut [T],
         selectivity: Option<u32>,
+        seed: Option<u64>,
     ) -> Result<()> {
         let selectivity = selectivity.unwrap_or_else(|| 100);
         let percent = Uniform::from(0..=100);
-        let mut rng = thread_rng();
+        let mut rng = StdRng::seed_from_u64(seed_or_random(seed));
 
         attr.iter_mut()
             .by_ref()
@@ -121,27 +199,44 @@ impl UniformRelation {
     /// `selectivity` specifies the join selectivity in percent. An according
     /// percentage of keys are set to the `NULL` value. By default (`None`), the
     /// selectivity is 100%.
+    ///
+    /// `seed` seeds the random number generators. The generated attribute does
+    /// not depend on the number of threads.
     pub fn gen_primary_key_par<T: Clone + Send + KeyAttribute>(
         attr: &mut [T],
         selectivity: Option<u32>,
+        seed: Option<u64>,
     ) -> Result<()> {
         let selectivity = selectivity.unwrap_or_else(|| 100);
         let percent = Uniform::from(0..=100);
-        let mut shuffled: Vec<(usize, T)> = (0..(attr.len()))
+        let seed = seed_or_random(seed);
+        let len = attr.len();
+        let blocks = (len + PAR_BLOCK_LEN - 1) / PAR_BLOCK_LEN;
+
+        let mut shuffled: Vec<(usize, T)> = (0..blocks)
             .into_par_iter()
-            .map_init(thread_rng, |mut rng, i| {
-                T::try_from_usize(i).map(|i| {
-                    let val = if percent.sample(&mut rng) <= selectivity {
-                        i
-                    } else {
-                        T::null_key()
-                    };
-                    (rng.gen(), val)
-                })
+            .flat_map(|block| {
+                let mut rng = block_rng(seed, block);
+                let begin = block * PAR_BLOCK_LEN;
+                let end = len.min(begin + PAR_BLOCK_LEN);
+
+                (begin..end)
+                    .map(|i| {
+                        T::try_from_usize(i).map(|i| {
+                            let val = if percent.sample(&mut rng) <= selectivity {
+                                i
+                            } else {
+                                T::null_key()
+                            };
+                            (rng.gen(), val)
+                        })
+                    })
+                    .collect::<Vec<_>>()
             })
             .collect::<Result<_>>()?;
 
-        shuffled.as_mut_slice().par_sort_unstable_by_key(|x| x.0);
+        // Sort stably, so that the order of equal sort keys is deterministic
+        shuffled.as_mut_slice().par_sort_by_key(|x| x.0);
 
         attr.par_iter_mut()
             .zip_eq(shuffled.into_par_iter())
@@ -155,8 +250,15 @@ impl UniformRelation {
     /// The generated keys are sampled from the primary key attribute, that is,
     /// they follow a foreign-key relationship. If the primary keys are unique,
     /// then the generated foreign keys follow a uniform distribution.
-    pub fn gen_foreign_key_from_primary_key<T: Copy>(fk_attr: &mut [T], pk_attr: &[T]) {
-        let mut rng = thread_rng();
+    ///
+    /// `seed` seeds the random number generator. By default (`None`), the seed
+    /// is random.
+    pub fn gen_foreign_key_from_primary_key<T: Copy>(
+        fk_attr: &mut [T],
+        pk_attr: &[T],
+        seed: Option<u64>,
+    ) {
+        let mut rng = StdRng::seed_from_u64(seed_or_random(seed));
 
         fk_attr
             .iter_mut()
@@ -169,8 +271,15 @@ impl UniformRelation {
     /// Generates a uniformly distributed attribute.
     ///
     /// The generated values are sampled from `range`.
-    pub fn gen_attr<T: FromPrimitive>(attr: &mut [T], range: Range<usize>) -> Result<()> {
-        let mut rng = thread_rng();
+    ///
+    /// `seed` seeds the random number generator. By default (`None`), the seed
+    /// is random.
+    pub fn gen_attr<T: FromPrimitive>(
+        attr: &mut [T],
+        range: Range<usize>,
+        seed: Option<u64>,
+    ) -> Result<()> {
+        let mut rng = StdRng::seed_from_u64(seed_or_random(seed));
         let between = Uniform::from(range);
 
         attr.iter_mut()
@@ -191,24 +300,35 @@ impl UniformRelation {
     /// Generates a uniformly distributed attribute in parallel.
     ///
     /// The generated values are sampled from `range`.
+    ///
+    /// `seed` seeds the random number generators. The generated attribute does
+    /// not depend on the number of threads.
     pub fn gen_attr_par<T: FromPrimitive + Send>(
         attr: &mut [T],
         range: Range<usize>,
+        seed: Option<u64>,
     ) -> Result<()> {
         let between = Uniform::from(range);
-
-        attr.par_iter_mut()
-            .map_init(
-                || thread_rng(),
-                |mut rng, x| {
-                    FromPrimitive::from_usize(between.sample(&mut rng))
-                        .ok_or_else(|| {
-                            ErrorKind::IntegerOverflow("Failed to convert from usize".to_string())
+        let seed = seed_or_random(seed);
+
+        attr.par_chunks_mut(PAR_BLOCK_LEN)
+            .enumerate()
+            .map(|(block, chunk)| {
+                let mut rng = block_rng(seed, block);
+                chunk
+                    .iter_mut()
+                    .map(|x| {
+                        FromPrimitive::from_usize(between.sample(&mut rng))
+                            .ok_or_else(|| {
+                                ErrorKind::IntegerOverflow(
+                                    "Failed to convert from usize".to_string(),
+                                )
                                 .into()
-                        })
-                        .map(|r| *x = r)
-                },
-            )
+                            })
+                            .map(|r| *x = r)
+                    })
+                    .collect::<Result<()>>()
+            })
             .collect::<Result<()>>()?;
 
         Ok(())
@@ -225,12 +345,16 @@ impl ZipfRelation {
     /// Note that the exponent must be greather than 0.
     ///
     /// In the literature, num_elements is also called the alphabet size.
+    ///
+    /// `seed` seeds the random number generator. By default (`None`), the seed
+    /// is random.
     pub fn gen_attr<T: FromPrimitive>(
         attr: &mut [T],
         num_elements: usize,
         exponent: f64,
+        seed: Option<u64>,
     ) -> Result<()> {
-        let mut rng = thread_rng();
+        let mut rng = StdRng::seed_from_u64(seed_or_random(seed));
         let between = ZipfDistribution::new(num_elements, exponent).map_err(|_| {
             ErrorKind::InvalidArgument(
                 "ZipfDistribution requires num_elements and exponent greater than 0".to_string(),
@@ -258,10 +382,14 @@ impl ZipfRelation {
     /// Note that the exponent must be greather than 0.
     ///
     /// In the literature, num_elements is also called the alphabet size.
+    ///
+    /// `seed` seeds the random number generators. The generated attribute does
+    /// not depend on the number of threads.
     pub fn gen_attr_par<T: FromPrimitive + Send>(
         attr: &mut [T],
         num_elements: usize,
         exponent: f64,
+        seed: Option<u64>,
     ) -> Result<()> {
         let between = ZipfDistribution::new(num_elements, exponent).map_err(|_| {
             ErrorKind::InvalidArgument(
@@ -271,18 +399,25 @@ impl ZipfRelation {
 
         // ZipfDistribution generates elements in range [1, num_elements]. Thus,
         // need to substract 1 to get a range [0, num_elements[.
-        attr.par_iter_mut()
-            .map_init(
-                || thread_rng(),
-                |mut rng, x| {
-                    FromPrimitive::from_usize(between.sample(&mut rng) - 1)
-                        .ok_or_else(|| {
-                            ErrorKind::IntegerOverflow("Failed to convert from usize".to_string())
+        let seed = seed_or_random(seed);
+        attr.par_chunks_mut(PAR_BLOCK_LEN)
+            .enumerate()
+            .map(|(block, chunk)| {
+                let mut rng = block_rng(seed, block);
+                chunk
+                    .iter_mut()
+                    .map(|x| {
+                        FromPrimitive::from_usize(between.sample(&mut rng) - 1)
+                            .ok_or_else(|| {
+                                ErrorKind::IntegerOverflow(
+                                    "Failed to convert from usize".to_string(),
+                                )
                                 .into()
-                        })
-                        .map(|r| *x = r)
-                },
-            )
+                            })
+                            .map(|r| *x = r)
+                    })
+                    .collect::<Result<()>>()
+            })
             .collect::<Result<()>>()?;
 
         Ok(())
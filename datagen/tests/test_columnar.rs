@@ -0,0 +1,65 @@
+// Copyright 2022 Clemens Lutz
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use datagen::columnar::{self, ColumnarHeader, HEADER_BYTES};
+use std::convert::TryInto;
+use std::error::Error;
+use std::io::Cursor;
+use std::result::Result;
+
+#[test]
+fn header_round_trip() -> Result<(), Box<dyn Error>> {
+    let header = ColumnarHeader::new::<i32, i64>(1234);
+
+    let mut bytes = Vec::new();
+    header.write_to(&mut bytes)?;
+    assert_eq!(HEADER_BYTES, bytes.len());
+
+    let parsed = ColumnarHeader::read_from(&mut Cursor::new(bytes))?;
+    assert_eq!(header, parsed);
+    assert_eq!(4, parsed.key_bytes);
+    assert_eq!(8, parsed.payload_bytes);
+
+    Ok(())
+}
+
+#[test]
+fn header_rejects_bad_magic() {
+    let bytes = vec![0_u8; HEADER_BYTES];
+    assert!(ColumnarHeader::read_from(&mut Cursor::new(bytes)).is_err());
+}
+
+#[test]
+fn write_columnar_layout() -> Result<(), Box<dyn Error>> {
+    let keys: Vec<i64> = (0..100_000).map(|x| x * 3).collect();
+
+    let mut bytes = Vec::new();
+    columnar::write_columnar(&mut bytes, &keys, |i| i as i64 + 1)?;
+
+    let header = ColumnarHeader::read_from(&mut Cursor::new(&bytes))?;
+    assert_eq!(keys.len() as u64, header.tuples);
+    assert_eq!(header.file_len(), bytes.len() as u64);
+
+    let column = |offset: u64, i: usize| {
+        let begin = offset as usize + i * 8;
+        i64::from_le_bytes(bytes[begin..begin + 8].try_into().unwrap())
+    };
+
+    for (i, &key) in keys.iter().enumerate() {
+        assert_eq!(key, column(header.key_offset(), i));
+        assert_eq!(i as i64 + 1, column(header.payload_offset(), i));
+    }
+
+    Ok(())
+}
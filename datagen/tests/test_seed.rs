@@ -0,0 +1,126 @@
+// Copyright 2022 Clemens Lutz
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use datagen::relation::{derive_seed, UniformRelation, ZipfRelation, PAR_BLOCK_LEN};
+use rayon::ThreadPoolBuilder;
+use std::error::Error;
+use std::result::Result;
+
+const LEN: usize = 3 * PAR_BLOCK_LEN + 123;
+const SEED: Option<u64> = Some(42);
+
+/// Runs `f` in a thread pool with `threads` threads.
+fn with_threads<T: Send>(
+    threads: usize,
+    f: impl FnOnce() -> T + Send,
+) -> Result<T, Box<dyn Error>> {
+    let pool = ThreadPoolBuilder::new().num_threads(threads).build()?;
+    Ok(pool.install(f))
+}
+
+#[test]
+fn gen_primary_key_is_reproducible() -> Result<(), Box<dyn Error>> {
+    let mut first = vec![0_i32; LEN];
+    let mut second = vec![0_i32; LEN];
+
+    UniformRelation::gen_primary_key(&mut first, None, SEED)?;
+    UniformRelation::gen_primary_key(&mut second, None, SEED)?;
+
+    assert_eq!(first, second);
+    Ok(())
+}
+
+#[test]
+fn gen_primary_key_par_is_independent_of_threads() -> Result<(), Box<dyn Error>> {
+    let mut first = vec![0_i64; LEN];
+    let mut second = vec![0_i64; LEN];
+
+    with_threads(1, || {
+        UniformRelation::gen_primary_key_par(&mut first, None, SEED)
+    })??;
+    with_threads(4, || {
+        UniformRelation::gen_primary_key_par(&mut second, None, SEED)
+    })??;
+
+    assert_eq!(first, second);
+
+    first.sort();
+    assert!(first.iter().zip(0..).all(|(&key, i)| key == i));
+
+    Ok(())
+}
+
+#[test]
+fn gen_foreign_key_from_primary_key_is_reproducible() -> Result<(), Box<dyn Error>> {
+    let pk: Vec<i32> = (0..LEN as i32).collect();
+    let mut first = vec![0_i32; LEN];
+    let mut second = vec![0_i32; LEN];
+
+    UniformRelation::gen_foreign_key_from_primary_key(&mut first, &pk, SEED);
+    UniformRelation::gen_foreign_key_from_primary_key(&mut second, &pk, SEED);
+
+    assert_eq!(first, second);
+    Ok(())
+}
+
+#[test]
+fn gen_attr_is_reproducible() -> Result<(), Box<dyn Error>> {
+    let mut first = vec![0_i32; LEN];
+    let mut second = vec![0_i32; LEN];
+
+    UniformRelation::gen_attr(&mut first, 0..1000, SEED)?;
+    UniformRelation::gen_attr(&mut second, 0..1000, SEED)?;
+
+    assert_eq!(first, second);
+    Ok(())
+}
+
+#[test]
+fn gen_attr_par_is_independent_of_threads() -> Result<(), Box<dyn Error>> {
+    let mut first = vec![0_i32; LEN];
+    let mut second = vec![0_i32; LEN];
+
+    with_threads(1, || {
+        UniformRelation::gen_attr_par(&mut first, 0..1000, SEED)
+    })??;
+    with_threads(4, || {
+        UniformRelation::gen_attr_par(&mut second, 0..1000, SEED)
+    })??;
+
+    assert_eq!(first, second);
+    Ok(())
+}
+
+#[test]
+fn zipf_gen_attr_par_is_independent_of_threads() -> Result<(), Box<dyn Error>> {
+    let mut first = vec![0_i32; LEN];
+    let mut second = vec![0_i32; LEN];
+
+    with_threads(1, || {
+        ZipfRelation::gen_attr_par(&mut first, 1000, 1.5, SEED)
+    })??;
+    with_threads(4, || {
+        ZipfRelation::gen_attr_par(&mut second, 1000, 1.5, SEED)
+    })??;
+
+    assert_eq!(first, second);
+    Ok(())
+}
+
+#[test]
+fn derived_seeds_differ() {
+    assert_ne!(derive_seed(SEED, 0), derive_seed(SEED, 1));
+    assert_ne!(derive_seed(SEED, 0), SEED);
+    assert_eq!(derive_seed(None, 0), None);
+}
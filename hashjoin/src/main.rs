@@ -21,17 +21,19 @@ use crate::measurement::data_point::DataPoint;
 use crate::measurement::harness;
 use crate::measurement::hash_join_bench::{HashJoinBenchBuilder, HashJoinPoint};
 use crate::types::*;
-use data_store::join_data::{JoinDataBuilder, JoinDataGenFn};
-use datagen::relation::KeyAttribute;
+use data_store::join_data::{JoinData, JoinDataBuilder, JoinDataGenFn};
+use datagen::relation::{derive_seed, KeyAttribute};
 use likwid;
 use num_rational::Ratio;
 use num_traits::cast::AsPrimitive;
 use numa_gpu::runtime::allocator;
 use numa_gpu::runtime::cpu_affinity::CpuAffinity;
 use numa_gpu::runtime::dispatcher::{MorselSpec, WorkerCpuAffinity};
+use numa_gpu::runtime::huge_pages;
 use numa_gpu::runtime::hw_info::NvidiaDriverInfo;
 use numa_gpu::runtime::linux_wrapper;
 use numa_gpu::runtime::numa::{self, NodeRatio};
+use numa_gpu::runtime::trace;
 use rustacuda::context::CurrentContext;
 use rustacuda::device::DeviceAttribute;
 use rustacuda::function::{BlockSize, GridSize};
@@ -42,12 +44,19 @@ use sql_ops::join::{no_partitioning_join, HashingScheme, HtEntry};
 use std::mem::size_of;
 use std::os::raw::c_uint;
 use std::path::PathBuf;
+use std::time::Duration;
 use structopt::StructOpt;
 
 fn main() -> Result<()> {
     // Parse commandline arguments
     let mut cmd = CmdOpt::from_args();
 
+    // Fall back to another page type if huge pages are unavailable
+    huge_pages::set_fallback(cmd.huge_page_fallback.into());
+
+    // Trace execution phases with the selected backend
+    trace::set_backend(cmd.trace_backend.into());
+
     // Initialize CUDA
     rustacuda::init(CudaFlags::empty())?;
     let device = Device::get_device(cmd.device_id.into())?;
@@ -67,17 +76,29 @@ fn main() -> Result<()> {
     };
     cmd.set_spill_hash_table(cache_node, overflow_node)?;
 
-    match cmd.tuple_bytes {
-        ArgTupleBytes::Bytes8 => {
+    match (cmd.key_bytes, cmd.payload_bytes) {
+        (ArgAttributeBytes::Bytes4, ArgAttributeBytes::Bytes4) => {
             let (hjc, dp) = args_to_bench::<i32>(&cmd, device)?;
             harness::measure("hash_join_kim", cmd.repeat, cmd.csv, dp, hjc)?;
         }
-        ArgTupleBytes::Bytes16 => {
+        (ArgAttributeBytes::Bytes8, ArgAttributeBytes::Bytes8) => {
             let (hjc, dp) = args_to_bench::<i64>(&cmd, device)?;
             harness::measure("hash_join_kim", cmd.repeat, cmd.csv, dp, hjc)?;
         }
+        (ArgAttributeBytes::Bytes4, ArgAttributeBytes::Bytes8) => {
+            let (hjc, dp) = args_to_cpu_bench::<i32, i64>(&cmd)?;
+            harness::measure("hash_join_kim", cmd.repeat, cmd.csv, dp, hjc)?;
+        }
+        (ArgAttributeBytes::Bytes8, ArgAttributeBytes::Bytes4) => {
+            let (hjc, dp) = args_to_cpu_bench::<i64, i32>(&cmd)?;
+            harness::measure("hash_join_kim", cmd.repeat, cmd.csv, dp, hjc)?;
+        }
     };
 
+    if let Some(trace_file) = &cmd.trace_file {
+        trace::write_chrome_trace(trace_file)?;
+    }
+
     Ok(())
 }
 
@@ -106,6 +127,7 @@ struct CmdOpt {
     /// Hashing scheme to use in hash table.
     //   linearprobing: Linear probing (default)
     //   perfect: Perfect hashing for unique primary keys
+    //   bucketchaining: Bucket chaining (CPU only)
     #[structopt(
         long = "hashing-scheme",
         default_value = "LinearProbing",
@@ -114,6 +136,22 @@ struct CmdOpt {
     )]
     hashing_scheme: ArgHashingScheme,
 
+    /// Hash function applied to the keys in the hash table.
+    ///
+    /// Defaults to multiplicative hashing, because the identity clusters
+    /// dense keys in linear probing and bucket chaining hash tables.
+    //   multiplicative: Multiplicative (Fibonacci) hashing (default)
+    //   identity: Use the key as hash value
+    //   murmur3: MurmurHash3 finalizer
+    //   crc32: CRC32-C checksum
+    #[structopt(
+        long = "hash-function",
+        default_value = "Multiplicative",
+        possible_values = &ArgHashFunction::variants(),
+        case_insensitive = true
+    )]
+    hash_function: ArgHashFunction,
+
     /// Memory type with which to allocate hash table.
     //   unified: CUDA Unified memory (default)
     //   numa: NUMA-local memory on node specified with hash-table-location
@@ -172,6 +210,38 @@ struct CmdOpt {
     )]
     page_type: ArgPageType,
 
+    /// Page type to allocate if the requested huge pages are unavailable
+    //   fail: abort with an error
+    //   transparenthuge: fall back to transparent huge pages
+    //   small: fall back to small pages
+    #[structopt(
+        long = "huge-page-fallback",
+        default_value = "Fail",
+        possible_values = &ArgHugePageFallback::variants(),
+        case_insensitive = true
+    )]
+    huge_page_fallback: ArgHugePageFallback,
+
+    /// Backend with which to trace the execution phases
+    //   noop: disable tracing
+    //   nvtx: annotate NVTX ranges for the NVIDIA profilers
+    //   chrome: write Chrome trace-event JSON to the trace file
+    #[structopt(
+        long = "trace-backend",
+        default_value = "Nvtx",
+        possible_values = &ArgTraceBackend::variants(),
+        case_insensitive = true
+    )]
+    trace_backend: ArgTraceBackend,
+
+    /// Output filename for Chrome trace events; required for `--trace-backend Chrome`
+    #[structopt(
+        long = "trace-file",
+        parse(from_os_str),
+        required_if("trace_backend", "Chrome")
+    )]
+    trace_file: Option<PathBuf>,
+
     /// Use a pre-defined or custom data set.
     //   blanas: Blanas et al. "Main memory hash join algorithms for multi-core CPUs"
     //   blanas4mb: Blanas, but with a 4 MiB inner relation
@@ -207,7 +277,11 @@ struct CmdOpt {
     )]
     selectivity: u32,
 
-    /// Load data set from a TSV file with "key value" pairs and automatic gzip decompression
+    /// Seed of the data generator's random number generator (default: random)
+    #[structopt(long = "seed")]
+    seed: Option<u64>,
+
+    /// Load data set from a columnar file, or a TSV file with "key value" pairs and automatic gzip decompression
     #[structopt(
         long = "inner-rel-file",
         parse(from_os_str),
@@ -216,7 +290,7 @@ struct CmdOpt {
     )]
     inner_rel_file: Option<PathBuf>,
 
-    /// Load data set from a TSV file with "key value" pairs and automatic gzip decompression
+    /// Load data set from a columnar file, or a TSV file with "key value" pairs and automatic gzip decompression
     #[structopt(
         long = "outer-rel-file",
         parse(from_os_str),
@@ -225,14 +299,23 @@ struct CmdOpt {
     )]
     outer_rel_file: Option<PathBuf>,
 
-    /// Set the tuple size (bytes)
+    /// Set the key size (bytes)
     #[structopt(
-        long = "tuple-bytes",
-        default_value = "Bytes8",
-        possible_values = &ArgTupleBytes::variants(),
+        long = "key-bytes",
+        default_value = "Bytes4",
+        possible_values = &ArgAttributeBytes::variants(),
         case_insensitive = true
     )]
-    tuple_bytes: ArgTupleBytes,
+    key_bytes: ArgAttributeBytes,
+
+    /// Set the payload size (bytes); must equal the key size, except for the CPU execution method
+    #[structopt(
+        long = "payload-bytes",
+        default_value = "Bytes4",
+        possible_values = &ArgAttributeBytes::variants(),
+        case_insensitive = true
+    )]
+    payload_bytes: ArgAttributeBytes,
 
     /// Set the inner relation size (tuples); required for `-data-set Custom`
     #[structopt(long = "inner-rel-tuples", required_if("data_set", "Custom"))]
@@ -277,6 +360,23 @@ struct CmdOpt {
     #[structopt(long = "cpu-affinity", parse(from_os_str))]
     cpu_affinity: Option<PathBuf>,
 
+    /// Generate the CPU affinities from the NUMA topology (in place of a map file)
+    //   compact: fill all hardware threads of a core before the next core
+    //   scatter: round-robin over NUMA nodes, physical cores first
+    //   nodelocal: only CPUs of the node specified with cpu-affinity-node
+    //   physicalcores: one hardware thread per physical core
+    #[structopt(
+        long = "cpu-affinity-policy",
+        possible_values = &ArgCpuAffinityPolicy::variants(),
+        case_insensitive = true,
+        conflicts_with = "cpu-affinity"
+    )]
+    cpu_affinity_policy: Option<ArgCpuAffinityPolicy>,
+
+    /// NUMA node of the node-local CPU affinity policy
+    #[structopt(long = "cpu-affinity-node", default_value = "0")]
+    cpu_affinity_node: u16,
+
     /// Path to CPU affinity map file for GPU workers
     #[structopt(long = "gpu-affinity", parse(from_os_str))]
     gpu_affinity: Option<PathBuf>,
@@ -310,6 +410,24 @@ impl CmdOpt {
 
         Ok(())
     }
+
+    fn load_cpu_affinity(&self) -> Result<CpuAffinity> {
+        let cpu_affinity = if let Some(ref cpu_affinity_file) = self.cpu_affinity {
+            CpuAffinity::from_file(cpu_affinity_file.as_path())?
+        } else if let Some(policy) = self.cpu_affinity_policy {
+            CpuAffinity::from_policy(
+                ArgCpuAffinityPolicyHelper {
+                    policy,
+                    node: self.cpu_affinity_node,
+                }
+                .into(),
+            )?
+        } else {
+            CpuAffinity::default()
+        };
+
+        Ok(cpu_affinity)
+    }
 }
 
 fn is_percent(x: String) -> std::result::Result<(), String> {
@@ -347,6 +465,14 @@ where
         + num_traits::FromPrimitive
         + DeserializeOwned,
 {
+    if cmd.hashing_scheme == ArgHashingScheme::BucketChaining
+        && cmd.execution_method != ArgExecutionMethod::Cpu
+    {
+        Err(ErrorKind::InvalidArgument(
+            "Bucket chaining is only supported by the CPU execution method".to_string(),
+        ))?;
+    }
+
     // Bind main thread to the CPU node closest to the GPU. This improves NVLink latency.
     match cmd.execution_method {
         ArgExecutionMethod::Gpu
@@ -371,11 +497,7 @@ where
         _ => {}
     };
 
-    // Convert ArgHashingScheme to HashingScheme
-    let (hashing_scheme, hash_table_load_factor) = match cmd.hashing_scheme {
-        ArgHashingScheme::Perfect => (HashingScheme::Perfect, 1),
-        ArgHashingScheme::LinearProbing => (HashingScheme::LinearProbing, 2),
-    };
+    let (hashing_scheme, hash_table_load_factor) = hashing_scheme_with_load_factor(cmd);
 
     // Device tuning
     let multiprocessors = device.get_attribute(DeviceAttribute::MultiprocessorCount)? as u32;
@@ -407,32 +529,6 @@ where
         );
     }
 
-    let mut data_builder = JoinDataBuilder::default();
-    data_builder
-        .mlock(true)
-        .inner_mem_type(
-            ArgMemTypeHelper {
-                mem_type: cmd.mem_type,
-                node_ratios: Box::new([NodeRatio {
-                    node: cmd.inner_rel_location,
-                    ratio: Ratio::from_integer(1),
-                }]),
-                page_type: cmd.page_type,
-            }
-            .into(),
-        )
-        .outer_mem_type(
-            ArgMemTypeHelper {
-                mem_type: cmd.mem_type,
-                node_ratios: Box::new([NodeRatio {
-                    node: cmd.outer_rel_location,
-                    ratio: Ratio::from_integer(1),
-                }]),
-                page_type: cmd.page_type,
-            }
-            .into(),
-        );
-
     // Select the operator to run, depending on the device type
     let exec_method = cmd.execution_method.clone();
     let transfer_strategy = cmd.transfer_strategy.clone();
@@ -459,34 +555,12 @@ where
         .collect();
 
     // Load file or generate data set
-    let (mut join_data, malloc_time, data_gen_time) =
-        if let (Some(inner_rel_path), Some(outer_rel_path)) = (
-            cmd.inner_rel_file.as_ref().and_then(|p| p.to_str()),
-            cmd.outer_rel_file.as_ref().and_then(|p| p.to_str()),
-        ) {
-            data_builder.build_with_files::<T>(inner_rel_path, outer_rel_path)?
-        } else {
-            let data_distribution = match cmd.data_distribution {
-                ArgDataDistribution::Uniform => DataDistribution::Uniform,
-                ArgDataDistribution::Zipf => DataDistribution::Zipf(cmd.zipf_exponent.unwrap()),
-            };
-
-            let (inner_relation_len, outer_relation_len, data_gen) = data_gen_fn::<_>(
-                cmd.data_set,
-                cmd.inner_rel_tuples,
-                cmd.outer_rel_tuples,
-                data_distribution,
-                Some(cmd.selectivity),
-            );
-            data_builder
-                .inner_len(inner_relation_len)
-                .outer_len(outer_relation_len)
-                .build_with_data_gen(data_gen)?
-        };
+    let (mut join_data, malloc_time, data_gen_time) = load_join_data::<T, T>(cmd)?;
 
     let mut hjb_builder = HashJoinBenchBuilder::default();
     let hjb = hjb_builder
         .hashing_scheme(hashing_scheme)
+        .hash_function(cmd.hash_function.into())
         .is_selective(cmd.selectivity != 100)
         .hash_table_load_factor(hash_table_load_factor)
         .build(join_data.build_relation_key.len())?;
@@ -494,17 +568,13 @@ where
     // Construct data point template for CSV
     let dp = DataPoint::new()?
         .fill_from_cmd_options(cmd)?
-        .fill_from_join_data(&join_data)
+        .fill_from_join_data(&join_data)?
         .fill_from_hash_join_bench(&hjb)
         .set_init_time(malloc_time, data_gen_time)
         .set_gpu_threads(&grid_size, &block_size);
 
     let worker_cpu_affinity = {
-        let cpu_workers = if let Some(ref cpu_affinity_file) = cmd.cpu_affinity {
-            CpuAffinity::from_file(cpu_affinity_file.as_path())?
-        } else {
-            CpuAffinity::default()
-        };
+        let cpu_workers = cmd.load_cpu_affinity()?;
         let gpu_workers = if let Some(ref gpu_affinity_file) = cmd.gpu_affinity {
             CpuAffinity::from_file(gpu_affinity_file.as_path())?
         } else {
@@ -519,7 +589,7 @@ where
     // Create closure that wraps a hash join benchmark function
     let hjc: Box<dyn FnMut() -> Result<HashJoinPoint>> = match exec_method {
         ArgExecutionMethod::Cpu => Box::new(move || {
-            let ht_alloc = allocator::Allocator::deref_mem_alloc_fn::<HtEntry<T, T>>(
+            let ht_alloc = allocator::Allocator::try_deref_mem_alloc_fn::<HtEntry<T, T>>(
                 ArgMemTypeHelper {
                     mem_type,
                     node_ratios: node_ratios.clone(),
@@ -579,7 +649,7 @@ where
         }),
         ArgExecutionMethod::GpuStream if transfer_strategy == ArgTransferStrategy::Unified => {
             Box::new(move || {
-                let ht_alloc = allocator::Allocator::mem_alloc_fn::<HtEntry<T, T>>(
+                let ht_alloc = allocator::Allocator::try_mem_alloc_fn::<HtEntry<T, T>>(
                     ArgMemTypeHelper {
                         mem_type,
                         node_ratios: node_ratios.clone(),
@@ -597,7 +667,7 @@ where
             })
         }
         ArgExecutionMethod::GpuStream => Box::new(move || {
-            let ht_alloc = allocator::Allocator::mem_alloc_fn::<HtEntry<T, T>>(
+            let ht_alloc = allocator::Allocator::try_mem_alloc_fn::<HtEntry<T, T>>(
                 ArgMemTypeHelper {
                     mem_type,
                     node_ratios: node_ratios.clone(),
@@ -617,7 +687,7 @@ where
             )
         }),
         ArgExecutionMethod::Het => Box::new(move || {
-            let ht_alloc = allocator::Allocator::mem_alloc_fn::<HtEntry<T, T>>(
+            let ht_alloc = allocator::Allocator::try_mem_alloc_fn::<HtEntry<T, T>>(
                 ArgMemTypeHelper {
                     mem_type,
                     node_ratios: node_ratios.clone(),
@@ -644,7 +714,7 @@ where
                     .thread_to_cpu(0)
                     .expect("Couldn't map thread to a core"),
             )?;
-            let cpu_ht_alloc = allocator::Allocator::mem_alloc_fn::<HtEntry<T, T>>(
+            let cpu_ht_alloc = allocator::Allocator::try_mem_alloc_fn::<HtEntry<T, T>>(
                 ArgMemTypeHelper {
                     mem_type: ArgMemType::Numa,
                     node_ratios: Box::new([NodeRatio {
@@ -657,7 +727,7 @@ where
             );
 
             // Allocate GPU memory as specified on the commandline
-            let gpu_ht_alloc = allocator::Allocator::mem_alloc_fn::<HtEntry<T, T>>(
+            let gpu_ht_alloc = allocator::Allocator::try_mem_alloc_fn::<HtEntry<T, T>>(
                 ArgMemTypeHelper {
                     mem_type,
                     node_ratios: node_ratios.clone(),
@@ -683,35 +753,198 @@ where
     Ok((hjc, dp))
 }
 
-fn data_gen_fn<T>(
+/// Converts the hashing scheme argument, and returns the scheme's hash table
+/// load factor.
+fn hashing_scheme_with_load_factor(cmd: &CmdOpt) -> (HashingScheme, usize) {
+    match cmd.hashing_scheme {
+        ArgHashingScheme::Perfect => (HashingScheme::Perfect, 1),
+        ArgHashingScheme::LinearProbing => (HashingScheme::LinearProbing, 2),
+        ArgHashingScheme::BucketChaining => (HashingScheme::BucketChaining, 2),
+    }
+}
+
+/// Loads the relations from files, or generates the specified data set.
+fn load_join_data<K, V>(cmd: &CmdOpt) -> Result<(JoinData<K, V>, Duration, Duration)>
+where
+    K: Copy
+        + Default
+        + DeviceCopy
+        + Send
+        + KeyAttribute
+        + num_traits::FromPrimitive
+        + DeserializeOwned,
+    V: Copy + Default + DeviceCopy + Send + DeserializeOwned,
+{
+    let mut data_builder = JoinDataBuilder::default();
+    data_builder
+        .mlock(true)
+        .inner_mem_type(
+            ArgMemTypeHelper {
+                mem_type: cmd.mem_type,
+                node_ratios: Box::new([NodeRatio {
+                    node: cmd.inner_rel_location,
+                    ratio: Ratio::from_integer(1),
+                }]),
+                page_type: cmd.page_type,
+            }
+            .into(),
+        )
+        .outer_mem_type(
+            ArgMemTypeHelper {
+                mem_type: cmd.mem_type,
+                node_ratios: Box::new([NodeRatio {
+                    node: cmd.outer_rel_location,
+                    ratio: Ratio::from_integer(1),
+                }]),
+                page_type: cmd.page_type,
+            }
+            .into(),
+        );
+
+    // Load file or generate data set
+    let join_data = if let (Some(inner_rel_path), Some(outer_rel_path)) = (
+        cmd.inner_rel_file.as_ref().and_then(|p| p.to_str()),
+        cmd.outer_rel_file.as_ref().and_then(|p| p.to_str()),
+    ) {
+        data_builder.build_with_files::<K, V>(inner_rel_path, outer_rel_path)?
+    } else {
+        let data_distribution = match cmd.data_distribution {
+            ArgDataDistribution::Uniform => DataDistribution::Uniform,
+            ArgDataDistribution::Zipf => DataDistribution::Zipf(cmd.zipf_exponent.unwrap()),
+        };
+
+        let (inner_relation_len, outer_relation_len, data_gen) = data_gen_fn::<_, _>(
+            cmd.data_set,
+            cmd.inner_rel_tuples,
+            cmd.outer_rel_tuples,
+            data_distribution,
+            Some(cmd.selectivity),
+            cmd.seed,
+        );
+        data_builder
+            .inner_len(inner_relation_len)
+            .outer_len(outer_relation_len)
+            .build_with_data_gen(data_gen)?
+    };
+
+    Ok(join_data)
+}
+
+/// Sets up a CPU hash join with different key and payload types.
+///
+/// The GPU execution methods only support keys and payloads of the same type,
+/// and thus return an error.
+fn args_to_cpu_bench<K, V>(
+    cmd: &CmdOpt,
+) -> Result<(Box<dyn FnMut() -> Result<HashJoinPoint>>, DataPoint)>
+where
+    K: Default
+        + Copy
+        + DeviceCopy
+        + Sync
+        + Send
+        + KeyAttribute
+        + no_partitioning_join::CpuHashJoinable<V>
+        + num_traits::FromPrimitive
+        + DeserializeOwned,
+    V: Default + Copy + DeviceCopy + Sync + Send + KeyAttribute + DeserializeOwned,
+{
+    if cmd.execution_method != ArgExecutionMethod::Cpu {
+        Err(ErrorKind::InvalidArgument(
+            "Different key and payload sizes are only supported by the CPU execution method"
+                .to_string(),
+        ))?;
+    }
+
+    let (hashing_scheme, hash_table_load_factor) = hashing_scheme_with_load_factor(cmd);
+
+    let (mut join_data, malloc_time, data_gen_time) = load_join_data::<K, V>(cmd)?;
+
+    let mut hjb_builder = HashJoinBenchBuilder::default();
+    let hjb = hjb_builder
+        .hashing_scheme(hashing_scheme)
+        .hash_function(cmd.hash_function.into())
+        .is_selective(cmd.selectivity != 100)
+        .hash_table_load_factor(hash_table_load_factor)
+        .build(join_data.build_relation_key.len())?;
+
+    // Construct data point template for CSV
+    let dp = DataPoint::new()?
+        .fill_from_cmd_options(cmd)?
+        .fill_from_join_data(&join_data)?
+        .fill_from_hash_join_bench(&hjb)
+        .set_init_time(malloc_time, data_gen_time);
+
+    let cpu_affinity = cmd.load_cpu_affinity()?;
+
+    let mem_type = cmd.hash_table_mem_type;
+    let threads = cmd.threads;
+    let page_type = cmd.page_type;
+    let node_ratios: Box<[NodeRatio]> = cmd
+        .hash_table_location
+        .iter()
+        .zip(cmd.hash_table_proportions.iter())
+        .map(|(node, pct)| NodeRatio {
+            node: *node,
+            ratio: Ratio::new(*pct, 100),
+        })
+        .collect();
+
+    let hjc: Box<dyn FnMut() -> Result<HashJoinPoint>> = Box::new(move || {
+        let ht_alloc = allocator::Allocator::try_deref_mem_alloc_fn::<HtEntry<K, V>>(
+            ArgMemTypeHelper {
+                mem_type,
+                node_ratios: node_ratios.clone(),
+                page_type,
+            }
+            .into(),
+        );
+        hjb.cpu_hash_join(&mut join_data, threads, &cpu_affinity, ht_alloc)
+    });
+
+    Ok((hjc, dp))
+}
+
+fn data_gen_fn<K, V>(
     description: ArgDataSet,
     inner_rel_tuples: Option<usize>,
     outer_rel_tuples: Option<usize>,
     data_distribution: DataDistribution,
     selectivity: Option<u32>,
-) -> (usize, usize, JoinDataGenFn<T>)
+    seed: Option<u64>,
+) -> (usize, usize, JoinDataGenFn<K, V>)
 where
-    T: Copy + Send + KeyAttribute + num_traits::FromPrimitive,
+    K: Copy + Send + KeyAttribute + num_traits::FromPrimitive,
+    V: Copy + Send + 'static,
 {
     match description {
         ArgDataSet::Blanas => (
             datagen::popular::Blanas::primary_key_len(),
             datagen::popular::Blanas::foreign_key_len(),
             Box::new(move |pk_rel, _, fk_rel, _| {
-                datagen::popular::Blanas::gen(pk_rel, fk_rel, selectivity).map_err(|e| e.into())
+                datagen::popular::Blanas::gen(pk_rel, fk_rel, selectivity, seed)
+                    .map_err(|e| e.into())
             }),
         ),
         ArgDataSet::Kim => (
             datagen::popular::Kim::primary_key_len(),
             datagen::popular::Kim::foreign_key_len(),
             Box::new(move |pk_rel, _, fk_rel, _| {
-                datagen::popular::Kim::gen(pk_rel, fk_rel, selectivity).map_err(|e| e.into())
+                datagen::popular::Kim::gen(pk_rel, fk_rel, selectivity, seed).map_err(|e| e.into())
             }),
         ),
         ArgDataSet::Blanas4MB => {
             let gen = move |pk_rel: &mut [_], _: &mut [_], fk_rel: &mut [_], _: &mut [_]| {
-                datagen::relation::UniformRelation::gen_primary_key_par(pk_rel, selectivity)?;
-                datagen::relation::UniformRelation::gen_attr_par(fk_rel, 0..pk_rel.len())?;
+                datagen::relation::UniformRelation::gen_primary_key_par(
+                    pk_rel,
+                    selectivity,
+                    derive_seed(seed, 0),
+                )?;
+                datagen::relation::UniformRelation::gen_attr_par(
+                    fk_rel,
+                    0..pk_rel.len(),
+                    derive_seed(seed, 1),
+                )?;
                 Ok(())
             };
 
@@ -719,9 +952,15 @@ where
         }
         ArgDataSet::Test => {
             let gen = move |pk_rel: &mut [_], _: &mut [_], fk_rel: &mut [_], _: &mut [_]| {
-                datagen::relation::UniformRelation::gen_primary_key(pk_rel, selectivity)?;
+                datagen::relation::UniformRelation::gen_primary_key(
+                    pk_rel,
+                    selectivity,
+                    derive_seed(seed, 0),
+                )?;
                 datagen::relation::UniformRelation::gen_foreign_key_from_primary_key(
-                    fk_rel, pk_rel,
+                    fk_rel,
+                    pk_rel,
+                    derive_seed(seed, 1),
                 );
                 Ok(())
             };
@@ -730,40 +969,64 @@ where
         }
         ArgDataSet::Lutz2Gv32G => {
             let gen = move |pk_rel: &mut [_], _: &mut [_], fk_rel: &mut [_], _: &mut [_]| {
-                datagen::relation::UniformRelation::gen_primary_key_par(pk_rel, selectivity)?;
-                datagen::relation::UniformRelation::gen_attr_par(fk_rel, 0..pk_rel.len())?;
+                datagen::relation::UniformRelation::gen_primary_key_par(
+                    pk_rel,
+                    selectivity,
+                    derive_seed(seed, 0),
+                )?;
+                datagen::relation::UniformRelation::gen_attr_par(
+                    fk_rel,
+                    0..pk_rel.len(),
+                    derive_seed(seed, 1),
+                )?;
                 Ok(())
             };
 
             (
-                2 * 2_usize.pow(30) / (2 * size_of::<T>()),
-                32 * 2_usize.pow(30) / (2 * size_of::<T>()),
+                2 * 2_usize.pow(30) / (size_of::<K>() + size_of::<V>()),
+                32 * 2_usize.pow(30) / (size_of::<K>() + size_of::<V>()),
                 Box::new(gen),
             )
         }
         ArgDataSet::Lutz32Gv32G => {
             let gen = move |pk_rel: &mut [_], _: &mut [_], fk_rel: &mut [_], _: &mut [_]| {
-                datagen::relation::UniformRelation::gen_primary_key_par(pk_rel, selectivity)?;
-                datagen::relation::UniformRelation::gen_attr_par(fk_rel, 0..pk_rel.len())?;
+                datagen::relation::UniformRelation::gen_primary_key_par(
+                    pk_rel,
+                    selectivity,
+                    derive_seed(seed, 0),
+                )?;
+                datagen::relation::UniformRelation::gen_attr_par(
+                    fk_rel,
+                    0..pk_rel.len(),
+                    derive_seed(seed, 1),
+                )?;
                 Ok(())
             };
 
             (
-                32 * 2_usize.pow(30) / (2 * size_of::<T>()),
-                32 * 2_usize.pow(30) / (2 * size_of::<T>()),
+                32 * 2_usize.pow(30) / (size_of::<K>() + size_of::<V>()),
+                32 * 2_usize.pow(30) / (size_of::<K>() + size_of::<V>()),
                 Box::new(gen),
             )
         }
         ArgDataSet::Custom => {
             let uniform_gen = Box::new(
                 move |pk_rel: &mut [_], _: &mut [_], fk_rel: &mut [_], _: &mut [_]| {
-                    datagen::relation::UniformRelation::gen_primary_key_par(pk_rel, selectivity)?;
-                    datagen::relation::UniformRelation::gen_attr_par(fk_rel, 0..pk_rel.len())?;
+                    datagen::relation::UniformRelation::gen_primary_key_par(
+                        pk_rel,
+                        selectivity,
+                        derive_seed(seed, 0),
+                    )?;
+                    datagen::relation::UniformRelation::gen_attr_par(
+                        fk_rel,
+                        0..pk_rel.len(),
+                        derive_seed(seed, 1),
+                    )?;
                     Ok(())
                 },
             );
 
-            let gen: JoinDataGenFn<T> = match data_distribution {
+            let gen: JoinDataGenFn<K, V> = match data_distribution {
                 DataDistribution::Uniform => uniform_gen,
                 DataDistribution::Zipf(exp) if !(exp > 0.0) => uniform_gen,
                 DataDistribution::Zipf(exp) => Box::new(
@@ -771,8 +1034,14 @@ where
                         datagen::relation::UniformRelation::gen_primary_key_par(
                             pk_rel,
                             selectivity,
+                            derive_seed(seed, 0),
+                        )?;
+                        datagen::relation::ZipfRelation::gen_attr_par(
+                            fk_rel,
+                            pk_rel.len(),
+                            exp,
+                            derive_seed(seed, 1),
                         )?;
-                        datagen::relation::ZipfRelation::gen_attr_par(fk_rel, pk_rel.len(), exp)?;
                         Ok(())
                     },
                 ),
@@ -18,7 +18,7 @@ use crate::CmdOpt;
 use data_store::join_data::JoinData;
 use numa_gpu::error::Result;
 use numa_gpu::runtime::hw_info::cpu_codename;
-use numa_gpu::runtime::nvtx::RangeId;
+use numa_gpu::runtime::trace::SpanId;
 use rustacuda::device::Device;
 use rustacuda::function::{BlockSize, GridSize};
 use rustacuda::memory::DeviceCopy;
@@ -42,6 +42,7 @@ pub struct DataPoint {
     pub grid_size: Option<u32>,
     pub block_size: Option<u32>,
     pub hashing_scheme: Option<ArgHashingScheme>,
+    pub hash_function: Option<ArgHashFunction>,
     pub hash_table_memory_type: Option<ArgMemType>,
     #[serde(serialize_with = "serialize_vec")]
     pub hash_table_memory_location: Option<Vec<u16>>,
@@ -49,11 +50,23 @@ pub struct DataPoint {
     pub hash_table_proportions: Option<Vec<usize>>,
     pub hash_table_tuples: Option<usize>,
     pub cached_hash_table_tuples: Option<usize>,
-    pub tuple_bytes: Option<ArgTupleBytes>,
+    pub key_bytes: Option<ArgAttributeBytes>,
+    pub payload_bytes: Option<ArgAttributeBytes>,
     pub relation_memory_type: Option<ArgMemType>,
     pub page_type: Option<ArgPageType>,
+    pub huge_page_fallback: Option<ArgHugePageFallback>,
     pub inner_relation_memory_location: Option<u16>,
     pub outer_relation_memory_location: Option<u16>,
+    #[serde(serialize_with = "serialize_vec")]
+    pub inner_relation_resident_nodes: Option<Vec<u16>>,
+    #[serde(serialize_with = "serialize_vec")]
+    pub inner_relation_resident_bytes: Option<Vec<usize>>,
+    #[serde(serialize_with = "serialize_vec")]
+    pub outer_relation_resident_nodes: Option<Vec<u16>>,
+    #[serde(serialize_with = "serialize_vec")]
+    pub outer_relation_resident_bytes: Option<Vec<usize>>,
+    pub inner_relation_huge_page_bytes: Option<usize>,
+    pub outer_relation_huge_page_bytes: Option<usize>,
     pub build_tuples: Option<usize>,
     pub build_bytes: Option<usize>,
     pub probe_tuples: Option<usize>,
@@ -61,8 +74,9 @@ pub struct DataPoint {
     pub data_distribution: Option<ArgDataDistribution>,
     pub zipf_exponent: Option<f64>,
     pub join_selectivity: Option<f64>,
+    pub data_seed: Option<u64>,
     pub warm_up: Option<bool>,
-    pub nvtx_run_id: Option<RangeId>,
+    pub trace_run_id: Option<SpanId>,
     pub build_ns: Option<f64>,
     pub probe_ns: Option<f64>,
     pub build_warm_up_ns: Option<f64>,
@@ -73,6 +87,18 @@ pub struct DataPoint {
     pub probe_compute_ns: Option<f64>,
     pub build_cool_down_ns: Option<f64>,
     pub probe_cool_down_ns: Option<f64>,
+    pub build_cpu_tuples: Option<usize>,
+    pub build_gpu_tuples: Option<usize>,
+    pub probe_cpu_tuples: Option<usize>,
+    pub probe_gpu_tuples: Option<usize>,
+    pub build_cpu_busy_ns: Option<f64>,
+    pub build_gpu_busy_ns: Option<f64>,
+    pub probe_cpu_busy_ns: Option<f64>,
+    pub probe_gpu_busy_ns: Option<f64>,
+    pub build_cpu_idle_ns: Option<f64>,
+    pub build_gpu_idle_ns: Option<f64>,
+    pub probe_cpu_idle_ns: Option<f64>,
+    pub probe_gpu_idle_ns: Option<f64>,
     pub hash_table_malloc_ns: Option<f64>,
     pub relation_malloc_ns: Option<f64>,
     pub relation_gen_ns: Option<f64>,
@@ -136,12 +162,15 @@ impl DataPoint {
                 None
             },
             hashing_scheme: Some(cmd.hashing_scheme),
+            hash_function: Some(cmd.hash_function),
             hash_table_memory_type: Some(cmd.hash_table_mem_type),
             hash_table_memory_location: Some(cmd.hash_table_location.clone()),
             hash_table_proportions: Some(cmd.hash_table_proportions.clone()),
-            tuple_bytes: Some(cmd.tuple_bytes),
+            key_bytes: Some(cmd.key_bytes),
+            payload_bytes: Some(cmd.payload_bytes),
             relation_memory_type: Some(cmd.mem_type),
             page_type: Some(cmd.page_type),
+            huge_page_fallback: Some(cmd.huge_page_fallback),
             inner_relation_memory_location: Some(cmd.inner_rel_location),
             outer_relation_memory_location: Some(cmd.outer_rel_location),
             data_distribution: Some(cmd.data_distribution),
@@ -151,6 +180,7 @@ impl DataPoint {
                 None
             },
             join_selectivity: Some(cmd.selectivity as f64 / 100.0),
+            data_seed: cmd.seed,
             ..self.clone()
         };
 
@@ -164,20 +194,36 @@ impl DataPoint {
         }
     }
 
-    pub fn fill_from_join_data<T: DeviceCopy>(&self, join_data: &JoinData<T>) -> DataPoint {
-        DataPoint {
+    pub fn fill_from_join_data<K: DeviceCopy, V: DeviceCopy>(
+        &self,
+        join_data: &JoinData<K, V>,
+    ) -> data_store::error::Result<DataPoint> {
+        // The residency and huge pages are unknown for memory that can reside on
+        // the GPU
+        let build_residency = join_data.build_relation_residency()?;
+        let probe_residency = join_data.probe_relation_residency()?;
+        let build_huge_page_bytes = join_data.build_relation_huge_page_bytes()?;
+        let probe_huge_page_bytes = join_data.probe_relation_huge_page_bytes()?;
+
+        Ok(DataPoint {
+            inner_relation_resident_nodes: build_residency.as_ref().map(|r| r.nodes()),
+            inner_relation_resident_bytes: build_residency.as_ref().map(|r| r.bytes()),
+            outer_relation_resident_nodes: probe_residency.as_ref().map(|r| r.nodes()),
+            outer_relation_resident_bytes: probe_residency.as_ref().map(|r| r.bytes()),
+            inner_relation_huge_page_bytes: build_huge_page_bytes,
+            outer_relation_huge_page_bytes: probe_huge_page_bytes,
             build_tuples: Some(join_data.build_relation_key.len()),
             build_bytes: Some(
-                (join_data.build_relation_key.len() + join_data.build_relation_payload.len())
-                    * size_of::<T>(),
+                join_data.build_relation_key.len() * size_of::<K>()
+                    + join_data.build_relation_payload.len() * size_of::<V>(),
             ),
             probe_tuples: Some(join_data.probe_relation_key.len()),
             probe_bytes: Some(
-                (join_data.probe_relation_key.len() + join_data.probe_relation_payload.len())
-                    * size_of::<T>(),
+                join_data.probe_relation_key.len() * size_of::<K>()
+                    + join_data.probe_relation_payload.len() * size_of::<V>(),
             ),
             ..self.clone()
-        }
+        })
     }
 
     pub fn set_init_time(&self, malloc: Duration, data_gen: Duration) -> DataPoint {
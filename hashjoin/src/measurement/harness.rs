@@ -16,8 +16,7 @@ use super::data_point::DataPoint;
 use super::hash_join_bench::HashJoinPoint;
 use crate::error::Result;
 use error_chain::ensure;
-use numa_gpu::runtime::nvtx::Range;
-use std::ffi::CString;
+use numa_gpu::runtime::trace::Span;
 use std::path::PathBuf;
 
 pub fn measure(
@@ -30,16 +29,13 @@ pub fn measure(
     let measurements = (0..repeat)
         .zip(std::iter::once(true).chain(std::iter::repeat(false)))
         .map(|(run, warm_up)| {
-            let range_message =
-                CString::new(format!("Measurement run {}", run)).expect("Failed to format string");
-
-            let range = Range::new(&range_message);
+            let span = Span::new(&format!("Measurement run {}", run));
             let result = func();
-            let run_id = range.end();
+            let run_id = span.end();
 
             result.map(|p| DataPoint {
                 warm_up: Some(warm_up),
-                nvtx_run_id: Some(run_id),
+                trace_run_id: Some(run_id),
                 relation_malloc_ns: if warm_up {
                     template.relation_malloc_ns
                 } else {
@@ -62,6 +58,18 @@ pub fn measure(
                 build_cool_down_ns: p.build_cool_down_ns,
                 probe_cool_down_ns: p.probe_cool_down_ns,
                 cached_hash_table_tuples: p.cached_hash_table_tuples,
+                build_cpu_tuples: p.build_cpu_tuples,
+                build_gpu_tuples: p.build_gpu_tuples,
+                probe_cpu_tuples: p.probe_cpu_tuples,
+                probe_gpu_tuples: p.probe_gpu_tuples,
+                build_cpu_busy_ns: p.build_cpu_busy_ns,
+                build_gpu_busy_ns: p.build_gpu_busy_ns,
+                probe_cpu_busy_ns: p.probe_cpu_busy_ns,
+                probe_gpu_busy_ns: p.probe_gpu_busy_ns,
+                build_cpu_idle_ns: p.build_cpu_idle_ns,
+                build_gpu_idle_ns: p.build_gpu_idle_ns,
+                probe_cpu_idle_ns: p.probe_cpu_idle_ns,
+                probe_gpu_idle_ns: p.probe_gpu_idle_ns,
                 ..template.clone()
             })
         })
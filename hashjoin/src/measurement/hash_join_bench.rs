@@ -16,21 +16,25 @@ use crate::error::{ErrorKind, Result};
 use data_store::join_data::JoinData;
 use datagen::relation::KeyAttribute;
 use num_traits::cast::AsPrimitive;
+use numa_gpu::error::{Error as NumaGpuError, ErrorKind as NumaGpuErrorKind};
 use numa_gpu::runtime::allocator;
 use numa_gpu::runtime::cpu_affinity::CpuAffinity;
 use numa_gpu::runtime::cuda::{
     CudaTransferStrategy, IntoCudaIterator, IntoCudaIteratorWithStrategy,
 };
 use numa_gpu::runtime::dispatcher::{
-    HetMorselExecutorBuilder, IntoHetMorselIterator, MorselSpec, WorkerCpuAffinity,
+    ExecutorStats, HetMorselExecutorBuilder, IntoHetMorselIterator, MorselSpec, WorkerCpuAffinity,
 };
 use numa_gpu::runtime::memory::*;
+use numa_gpu::runtime::trace::Span;
 use numa_gpu::runtime::{cuda_wrapper, linux_wrapper};
 use numa_gpu::utils::CachePadded;
 use rustacuda::event::{Event, EventFlags};
 use rustacuda::function::{BlockSize, GridSize};
 use rustacuda::memory::{AsyncCopyDestination, DeviceBuffer, DeviceCopy};
 use rustacuda::stream::{Stream, StreamFlags};
+use sql_ops::error::Error as SqlOpsError;
+use sql_ops::hash_function::HashFunction;
 use sql_ops::join::{no_partitioning_join, HashingScheme, HtEntry};
 use std::cell::RefCell;
 use std::convert::TryInto;
@@ -48,8 +52,17 @@ use std::{cmp, mem};
 /// Instead of allocating every last byte of GPU memory, leave some slack space.
 const GPU_MEM_SLACK_BYTES: usize = 32 * 1024 * 1024;
 
+/// Converts an operator error into the error type of the `numa_gpu` executors.
+///
+/// The executors cannot return `sql_ops` errors, because `sql_ops` depends on
+/// `numa_gpu`.
+fn morsel_error(error: SqlOpsError) -> NumaGpuError {
+    NumaGpuErrorKind::RuntimeError(error.to_string()).into()
+}
+
 pub struct HashJoinBench<T> {
     pub hashing_scheme: HashingScheme,
+    pub hash_function: HashFunction,
     pub is_selective: bool,
     pub hash_table_len: usize,
     _phantom_data: std::marker::PhantomData<T>,
@@ -58,6 +71,7 @@ pub struct HashJoinBench<T> {
 pub struct HashJoinBenchBuilder {
     hash_table_load_factor: usize,
     hashing_scheme: HashingScheme,
+    hash_function: HashFunction,
     is_selective: bool,
 }
 
@@ -75,6 +89,50 @@ pub struct HashJoinPoint {
     pub build_cool_down_ns: Option<f64>,
     pub probe_cool_down_ns: Option<f64>,
     pub cached_hash_table_tuples: Option<usize>,
+    pub build_cpu_tuples: Option<usize>,
+    pub build_gpu_tuples: Option<usize>,
+    pub probe_cpu_tuples: Option<usize>,
+    pub probe_gpu_tuples: Option<usize>,
+    pub build_cpu_busy_ns: Option<f64>,
+    pub build_gpu_busy_ns: Option<f64>,
+    pub probe_cpu_busy_ns: Option<f64>,
+    pub probe_gpu_busy_ns: Option<f64>,
+    pub build_cpu_idle_ns: Option<f64>,
+    pub build_gpu_idle_ns: Option<f64>,
+    pub probe_cpu_idle_ns: Option<f64>,
+    pub probe_gpu_idle_ns: Option<f64>,
+}
+
+impl HashJoinPoint {
+    /// Records the CPU/GPU work split and the worker times of the build.
+    ///
+    /// Busy and idle times are summed over all workers of a device type.
+    fn with_build_stats(self, stats: &ExecutorStats) -> Self {
+        Self {
+            build_cpu_tuples: Some(stats.cpu_tuples()),
+            build_gpu_tuples: Some(stats.gpu_tuples()),
+            build_cpu_busy_ns: Some(stats.cpu_busy_time().as_nanos() as f64),
+            build_gpu_busy_ns: Some(stats.gpu_busy_time().as_nanos() as f64),
+            build_cpu_idle_ns: Some(stats.cpu_idle_time().as_nanos() as f64),
+            build_gpu_idle_ns: Some(stats.gpu_idle_time().as_nanos() as f64),
+            ..self
+        }
+    }
+
+    /// Records the CPU/GPU work split and the worker times of the probe.
+    ///
+    /// Busy and idle times are summed over all workers of a device type.
+    fn with_probe_stats(self, stats: &ExecutorStats) -> Self {
+        Self {
+            probe_cpu_tuples: Some(stats.cpu_tuples()),
+            probe_gpu_tuples: Some(stats.gpu_tuples()),
+            probe_cpu_busy_ns: Some(stats.cpu_busy_time().as_nanos() as f64),
+            probe_gpu_busy_ns: Some(stats.gpu_busy_time().as_nanos() as f64),
+            probe_cpu_idle_ns: Some(stats.cpu_idle_time().as_nanos() as f64),
+            probe_gpu_idle_ns: Some(stats.gpu_idle_time().as_nanos() as f64),
+            ..self
+        }
+    }
 }
 
 impl Default for HashJoinBenchBuilder {
@@ -82,6 +140,7 @@ impl Default for HashJoinBenchBuilder {
         HashJoinBenchBuilder {
             hash_table_load_factor: 2,
             hashing_scheme: HashingScheme::LinearProbing,
+            hash_function: HashFunction::Multiplicative,
             is_selective: false,
         }
     }
@@ -98,6 +157,11 @@ impl HashJoinBenchBuilder {
         self
     }
 
+    pub fn hash_function(&mut self, hash_function: HashFunction) -> &mut Self {
+        self.hash_function = hash_function;
+        self
+    }
+
     pub fn is_selective(&mut self, is_selective: bool) -> &mut Self {
         self.is_selective = is_selective;
         self
@@ -105,14 +169,15 @@ impl HashJoinBenchBuilder {
 
     fn get_hash_table_len(&self, inner_relation_len: usize) -> Result<usize> {
         let hash_table_len = match self.hashing_scheme {
-            HashingScheme::LinearProbing => inner_relation_len
+            // Bucket chaining stores its buckets and chained entries within the
+            // hash table, and thus requires the same headroom as linear probing
+            HashingScheme::LinearProbing | HashingScheme::BucketChaining => inner_relation_len
                 .checked_next_power_of_two()
                 .and_then(|x| x.checked_mul(self.hash_table_load_factor))
                 .ok_or_else(|| {
                     ErrorKind::IntegerOverflow("Failed to compute hash table length".to_string())
                 })?,
             HashingScheme::Perfect => inner_relation_len,
-            HashingScheme::BucketChaining => unimplemented!(),
         };
 
         Ok(hash_table_len)
@@ -121,6 +186,7 @@ impl HashJoinBenchBuilder {
     pub fn build<T>(&mut self, inner_relation_len: usize) -> Result<HashJoinBench<T>> {
         Ok(HashJoinBench {
             hashing_scheme: self.hashing_scheme,
+            hash_function: self.hash_function,
             is_selective: self.is_selective,
             hash_table_len: self.get_hash_table_len(inner_relation_len)?,
             _phantom_data: std::marker::PhantomData::<T>,
@@ -199,6 +265,7 @@ where
 
         let hj_op = hj_op_builder
             .hashing_scheme(self.hashing_scheme)
+            .hash_function(self.hash_function)
             .is_selective(self.is_selective)
             .build_dim(build_dim.0.clone(), build_dim.1.clone())
             .probe_dim(probe_dim.0.clone(), probe_dim.1.clone())
@@ -251,7 +318,7 @@ where
     pub fn cuda_streaming_hash_join(
         &self,
         data: &mut JoinData<T>,
-        hash_table_alloc: allocator::MemAllocFn<HtEntry<T, T>>,
+        hash_table_alloc: allocator::TryMemAllocFn<HtEntry<T, T>>,
         build_dim: (GridSize, BlockSize),
         probe_dim: (GridSize, BlockSize),
         transfer_strategy: CudaTransferStrategy,
@@ -262,7 +329,7 @@ where
         let stream = Stream::new(StreamFlags::NON_BLOCKING, None)?;
 
         let ht_malloc_timer = Instant::now();
-        let mut hash_table_mem = hash_table_alloc(self.hash_table_len);
+        let mut hash_table_mem = hash_table_alloc(self.hash_table_len)?;
         if let CudaUniMem(ref mut _mem) = hash_table_mem {
             // mem_advise(
             //     mem.as_unified_ptr(),
@@ -309,6 +376,7 @@ where
 
         let hj_op = no_partitioning_join::CudaHashJoinBuilder::<T>::default()
             .hashing_scheme(self.hashing_scheme)
+            .hash_function(self.hash_function)
             .is_selective(self.is_selective)
             .build_dim(build_dim.0.clone(), build_dim.1.clone())
             .probe_dim(probe_dim.0.clone(), probe_dim.1.clone())
@@ -330,23 +398,25 @@ where
             cpu_affinity,
         )?;
 
+        let build_span = Span::new("phase_build");
         let build_timer = Instant::now();
         let build_mnts = build_iter.fold(|(key, val), stream| {
-            hj_op
-                .build(key, val, stream)
-                .expect("Failed to run hash join build");
+            hj_op.build(key, val, stream).map_err(morsel_error)?;
             Ok(())
         })?;
         let build_time = build_timer.elapsed();
+        build_span.end();
 
+        let probe_span = Span::new("phase_probe");
         let probe_timer = Instant::now();
         let probe_mnts = probe_iter.fold(|(key, val), stream| {
             hj_op
                 .probe_sum(key, val, &result_sums, stream)
-                .expect("Failed to run hash join probe");
+                .map_err(morsel_error)?;
             Ok(())
         })?;
         let probe_time = probe_timer.elapsed();
+        probe_span.end();
 
         let mut result_sums_host = vec![0; result_sums.len()];
         if let Mem::CudaDevMem(results) = result_sums {
@@ -375,7 +445,7 @@ where
     pub fn cuda_streaming_unified_hash_join(
         &self,
         data: &mut JoinData<T>,
-        hash_table_alloc: allocator::MemAllocFn<HtEntry<T, T>>,
+        hash_table_alloc: allocator::TryMemAllocFn<HtEntry<T, T>>,
         build_dim: (GridSize, BlockSize),
         probe_dim: (GridSize, BlockSize),
         gpu_morsel_bytes: usize,
@@ -383,7 +453,7 @@ where
         let stream = Stream::new(StreamFlags::NON_BLOCKING, None)?;
 
         let ht_malloc_timer = Instant::now();
-        let hash_table_mem = hash_table_alloc(self.hash_table_len);
+        let hash_table_mem = hash_table_alloc(self.hash_table_len)?;
         let mut hash_table =
             no_partitioning_join::HashTable::new_on_gpu(hash_table_mem, self.hash_table_len)?;
         hash_table.mlock()?;
@@ -418,6 +488,7 @@ where
 
         let hj_op = no_partitioning_join::CudaHashJoinBuilder::<T>::default()
             .hashing_scheme(self.hashing_scheme)
+            .hash_function(self.hash_function)
             .is_selective(self.is_selective)
             .build_dim(build_dim.0.clone(), build_dim.1.clone())
             .probe_dim(probe_dim.0.clone(), probe_dim.1.clone())
@@ -427,18 +498,19 @@ where
         let mut build_relation = (build_rel_key, build_rel_pay);
         let mut probe_relation = (probe_rel_key, probe_rel_pay);
 
+        let build_span = Span::new("phase_build");
         let build_timer = Instant::now();
         let build_mnts =
             build_relation
                 .into_cuda_iter(gpu_morsel_bytes)?
                 .fold(|(key, val), stream| {
-                    hj_op
-                        .build(key, val, stream)
-                        .expect("Failed to run hash join build");
+                    hj_op.build(key, val, stream).map_err(morsel_error)?;
                     Ok(())
                 })?;
         let build_time = build_timer.elapsed();
+        build_span.end();
 
+        let probe_span = Span::new("phase_probe");
         let probe_timer = Instant::now();
         let probe_mnts =
             probe_relation
@@ -446,10 +518,11 @@ where
                 .fold(|(key, val), stream| {
                     hj_op
                         .probe_sum(key, val, &result_sums, stream)
-                        .expect("Failed to run hash join probe");
+                        .map_err(morsel_error)?;
                     Ok(())
                 })?;
         let probe_time = probe_timer.elapsed();
+        probe_span.end();
 
         let mut result_sums_host = vec![0; result_sums.len()];
         if let Mem::CudaDevMem(results) = result_sums {
@@ -475,107 +548,10 @@ where
         })
     }
 
-    pub fn cpu_hash_join(
-        &self,
-        data: &mut JoinData<T>,
-        threads: usize,
-        cpu_affinity: &CpuAffinity,
-        hash_table_alloc: allocator::DerefMemAllocFn<HtEntry<T, T>>,
-    ) -> Result<HashJoinPoint> {
-        let ht_malloc_timer = Instant::now();
-        let hash_table_mem = hash_table_alloc(self.hash_table_len);
-        let mut hash_table =
-            no_partitioning_join::HashTable::new_on_cpu(hash_table_mem, self.hash_table_len)?;
-        hash_table.mlock()?;
-        let hash_table = hash_table;
-        let ht_malloc_time = ht_malloc_timer.elapsed();
-
-        let mut result_sums = vec![CachePadded { value: 0 }; threads];
-
-        let boxed_cpu_affinity = Arc::new(cpu_affinity.clone());
-        let thread_pool = rayon::ThreadPoolBuilder::new()
-            .num_threads(threads)
-            .start_handler(move |tid| {
-                boxed_cpu_affinity
-                    .clone()
-                    .set_affinity(tid as u16)
-                    .expect("Couldn't set CPU core affinity");
-                likwid::thread_init();
-            })
-            .build()
-            .map_err(|_| ErrorKind::RuntimeError("Failed to create thread pool".to_string()))?;
-        let build_chunk_size = (data.build_relation_key.len() + threads - 1) / threads;
-        let probe_chunk_size = (data.probe_relation_key.len() + threads - 1) / threads;
-
-        let build_rel_key: &[T] = (&data.build_relation_key)
-            .try_into()
-            .map_err(|(err, _)| err)
-            .expect("Can't use CUDA device memory on CPU!");
-        let build_rel_chunks: Vec<_> = build_rel_key.chunks(build_chunk_size).collect();
-
-        let build_rel_pay: &[T] = (&data.build_relation_payload)
-            .try_into()
-            .map_err(|(err, _)| err)
-            .expect("Can't use CUDA device memory on CPU!");
-        let build_pay_chunks: Vec<_> = build_rel_pay.chunks(build_chunk_size).collect();
-
-        let probe_rel_key: &[T] = (&data.probe_relation_key)
-            .try_into()
-            .map_err(|(err, _)| err)
-            .expect("Can't use CUDA device memory on CPU!");
-        let probe_rel_chunks: Vec<_> = probe_rel_key.chunks(probe_chunk_size).collect();
-
-        let probe_rel_pay: &[T] = (&data.probe_relation_payload)
-            .try_into()
-            .map_err(|(err, _)| err)
-            .expect("Can't use CUDA device memory on CPU!");
-        let probe_pay_chunks: Vec<_> = probe_rel_pay.chunks(probe_chunk_size).collect();
-
-        let hj_builder = no_partitioning_join::CpuHashJoinBuilder::default()
-            .hashing_scheme(self.hashing_scheme)
-            .is_selective(self.is_selective)
-            .hash_table(Arc::new(hash_table));
-
-        let build_timer = Instant::now();
-        thread_pool.scope(|s| {
-            for ((_tid, rel), pay) in (0..threads).zip(build_rel_chunks).zip(build_pay_chunks) {
-                let mut hj_op = hj_builder.build();
-                s.spawn(move |_| {
-                    hj_op.build(rel, pay).expect("Couldn't build hash table");
-                });
-            }
-        });
-        let build_time = build_timer.elapsed();
-
-        let probe_timer = Instant::now();
-        thread_pool.scope(|s| {
-            for (((_tid, rel), pay), res) in (0..threads)
-                .zip(probe_rel_chunks)
-                .zip(probe_pay_chunks)
-                .zip(result_sums.iter_mut())
-            {
-                let mut hj_op = hj_builder.build();
-                s.spawn(move |_| {
-                    hj_op
-                        .probe_sum(rel, pay, &mut res.value)
-                        .expect("Couldn't execute hash table probe");
-                });
-            }
-        });
-        let probe_time = probe_timer.elapsed();
-
-        Ok(HashJoinPoint {
-            build_ns: Some(build_time.as_nanos() as f64),
-            probe_ns: Some(probe_time.as_nanos() as f64),
-            hash_table_malloc_ns: Some(ht_malloc_time.as_nanos() as f64),
-            ..Default::default()
-        })
-    }
-
     pub fn hetrogeneous_hash_join(
         &self,
         data: &mut JoinData<T>,
-        hash_table_alloc: allocator::MemAllocFn<HtEntry<T, T>>,
+        hash_table_alloc: allocator::TryMemAllocFn<HtEntry<T, T>>,
         cpu_threads: usize,
         worker_cpu_affinity: &WorkerCpuAffinity,
         gpu_ids: Vec<u16>,
@@ -587,7 +563,7 @@ where
 
         // FIXME: specify load factor as argument
         let ht_malloc_timer = Instant::now();
-        let hash_table_mem = hash_table_alloc(self.hash_table_len);
+        let hash_table_mem = hash_table_alloc(self.hash_table_len)?;
         let mut hash_table =
             no_partitioning_join::HashTable::new_on_gpu(hash_table_mem, self.hash_table_len)?;
         hash_table.mlock()?;
@@ -623,11 +599,13 @@ where
 
         let cpu_hj_builder = no_partitioning_join::CpuHashJoinBuilder::default()
             .hashing_scheme(self.hashing_scheme)
+            .hash_function(self.hash_function)
             .is_selective(self.is_selective)
             .hash_table(hash_table.clone());
 
         let gpu_hj_builder = no_partitioning_join::CudaHashJoinBuilder::<T>::default()
             .hashing_scheme(self.hashing_scheme)
+            .hash_function(self.hash_function)
             .is_selective(self.is_selective)
             .build_dim(build_dim.0.clone(), build_dim.1.clone())
             .probe_dim(probe_dim.0.clone(), probe_dim.1.clone())
@@ -640,31 +618,30 @@ where
             .morsel_spec(morsel_spec.clone())
             .build()?;
 
+        let build_span = Span::new("phase_build");
         let build_timer = Instant::now();
-        (build_rel_key, build_rel_pay)
+        let build_stats = (build_rel_key, build_rel_pay)
             .into_het_morsel_iter(&mut executor)
-            .fold(
+            .fold_with_stats(
                 |(rel, pay)| {
                     let mut hj_op = cpu_hj_builder.build();
-                    hj_op.build(rel, pay).expect("Couldn't build hash table");
+                    hj_op.build(rel, pay).map_err(morsel_error)?;
                     Ok(())
                 },
                 |(rel, pay), stream| {
-                    let hj_op = gpu_hj_builder
-                        .build()
-                        .expect("Failed to build GPU hash join");
-                    hj_op
-                        .build(rel, pay, stream)
-                        .expect("Failed to run GPU hash join build");
+                    let hj_op = gpu_hj_builder.build().map_err(morsel_error)?;
+                    hj_op.build(rel, pay, stream).map_err(morsel_error)?;
                     Ok(())
                 },
             )?;
         let build_time = build_timer.elapsed();
+        build_span.end();
 
+        let probe_span = Span::new("phase_probe");
         let probe_timer = Instant::now();
-        (probe_rel_key, probe_rel_pay)
+        let probe_stats = (probe_rel_key, probe_rel_pay)
             .into_het_morsel_iter(&mut executor)
-            .fold(
+            .fold_with_stats(
                 |(rel, pay)| {
                     let mut hj_op = cpu_hj_builder.build();
 
@@ -672,24 +649,23 @@ where
                     let mut result_sum = CachePadded { value: 0 };
                     hj_op
                         .probe_sum(rel, pay, &mut result_sum.value)
-                        .expect("Failed to run CPU hash join probe");
+                        .map_err(morsel_error)?;
 
                     Ok(())
                 },
                 |(rel, pay), stream| {
-                    let hj_op = gpu_hj_builder
-                        .build()
-                        .expect("Failed to build GPU hash join");
+                    let hj_op = gpu_hj_builder.build().map_err(morsel_error)?;
 
                     hj_op
                         .probe_sum(rel, pay, &result_sums, stream)
-                        .expect("Failed to run GPU hash join probe");
+                        .map_err(morsel_error)?;
 
                     Ok(())
                 },
             )?;
 
         let probe_time = probe_timer.elapsed();
+        probe_span.end();
 
         let mut result_sums_host = vec![0; result_sums.len()];
         if let Mem::CudaDevMem(results) = result_sums {
@@ -704,15 +680,17 @@ where
             probe_ns: Some(probe_time.as_nanos() as f64),
             hash_table_malloc_ns: Some(ht_malloc_time.as_nanos() as f64),
             ..Default::default()
-        })
+        }
+        .with_build_stats(&build_stats)
+        .with_probe_stats(&probe_stats))
     }
 
     #[allow(dead_code)]
     pub fn gpu_build_heterogeneous_probe(
         &self,
         data: &mut JoinData<T>,
-        cpu_hash_table_alloc: allocator::MemAllocFn<HtEntry<T, T>>,
-        gpu_hash_table_alloc: allocator::MemAllocFn<HtEntry<T, T>>,
+        cpu_hash_table_alloc: allocator::TryMemAllocFn<HtEntry<T, T>>,
+        gpu_hash_table_alloc: allocator::TryMemAllocFn<HtEntry<T, T>>,
         cpu_threads: usize,
         worker_cpu_affinity: &WorkerCpuAffinity,
         gpu_ids: Vec<u16>,
@@ -724,13 +702,13 @@ where
 
         let ht_malloc_timer = Instant::now();
 
-        let gpu_hash_table_mem = gpu_hash_table_alloc(self.hash_table_len);
+        let gpu_hash_table_mem = gpu_hash_table_alloc(self.hash_table_len)?;
         let mut gpu_hash_table =
             no_partitioning_join::HashTable::new_on_gpu(gpu_hash_table_mem, self.hash_table_len)?;
         gpu_hash_table.mlock()?;
         let gpu_hash_table = Arc::new(gpu_hash_table);
 
-        let mut cpu_hash_table_mem = cpu_hash_table_alloc(self.hash_table_len);
+        let mut cpu_hash_table_mem = cpu_hash_table_alloc(self.hash_table_len)?;
         cpu_hash_table_mem.mlock()?;
         let cpu_hash_table_mem = cpu_hash_table_mem;
 
@@ -757,6 +735,7 @@ where
 
         let gpu_hj_builder = no_partitioning_join::CudaHashJoinBuilder::<T>::default()
             .hashing_scheme(self.hashing_scheme)
+            .hash_function(self.hash_function)
             .is_selective(self.is_selective)
             .build_dim(build_dim.0.clone(), build_dim.1.clone())
             .probe_dim(probe_dim.0.clone(), probe_dim.1.clone())
@@ -769,6 +748,7 @@ where
             .morsel_spec(morsel_spec.clone())
             .build()?;
 
+        let build_span = Span::new("phase_build");
         let build_timer = Instant::now();
         let stream = Stream::new(StreamFlags::NON_BLOCKING, None)?;
         let gpu_hj_op = gpu_hj_builder.build()?;
@@ -784,15 +764,18 @@ where
             &gpu_hash_table,
         )?);
         let build_time = build_timer.elapsed();
+        build_span.end();
 
+        let probe_span = Span::new("phase_probe");
         let probe_timer = Instant::now();
         let cpu_hj_builder = no_partitioning_join::CpuHashJoinBuilder::default()
             .hashing_scheme(self.hashing_scheme)
+            .hash_function(self.hash_function)
             .is_selective(self.is_selective)
             .hash_table(cpu_hash_table.clone());
-        (probe_rel_key, probe_rel_pay)
+        let probe_stats = (probe_rel_key, probe_rel_pay)
             .into_het_morsel_iter(&mut executor)
-            .fold(
+            .fold_with_stats(
                 |(rel, pay)| {
                     let mut hj_op = cpu_hj_builder.build();
 
@@ -800,23 +783,22 @@ where
                     let mut result_sum = CachePadded { value: 0 };
                     hj_op
                         .probe_sum(rel, pay, &mut result_sum.value)
-                        .expect("Failed to run CPU hash join probe");
+                        .map_err(morsel_error)?;
 
                     Ok(())
                 },
                 |(rel, pay), stream| {
-                    let hj_op = gpu_hj_builder
-                        .build()
-                        .expect("Failed to run GPU hash join build");
+                    let hj_op = gpu_hj_builder.build().map_err(morsel_error)?;
 
                     hj_op
                         .probe_sum(rel, pay, &result_sums, stream)
-                        .expect("Failed to run GPU hash join probe");
+                        .map_err(morsel_error)?;
 
                     Ok(())
                 },
             )?;
         let probe_time = probe_timer.elapsed();
+        probe_span.end();
 
         let mut result_sums_host = vec![0; result_sums.len()];
         if let Mem::CudaDevMem(results) = result_sums {
@@ -826,6 +808,127 @@ where
         stream.synchronize()?;
         let _sum: u64 = result_sums_host.iter().sum();
 
+        Ok(HashJoinPoint {
+            build_ns: Some(build_time.as_nanos() as f64),
+            probe_ns: Some(probe_time.as_nanos() as f64),
+            hash_table_malloc_ns: Some(ht_malloc_time.as_nanos() as f64),
+            ..Default::default()
+        }
+        .with_probe_stats(&probe_stats))
+    }
+}
+
+impl<K> HashJoinBench<K>
+where
+    K: Copy + Default + DeviceCopy + Sync + Send + KeyAttribute,
+{
+    pub fn cpu_hash_join<V>(
+        &self,
+        data: &mut JoinData<K, V>,
+        threads: usize,
+        cpu_affinity: &CpuAffinity,
+        hash_table_alloc: allocator::TryDerefMemAllocFn<HtEntry<K, V>>,
+    ) -> Result<HashJoinPoint>
+    where
+        K: no_partitioning_join::CpuHashJoinable<V>,
+        V: Copy + Default + DeviceCopy + Sync + Send + KeyAttribute,
+    {
+        let ht_malloc_timer = Instant::now();
+        let hash_table_mem = hash_table_alloc(self.hash_table_len)?;
+        let mut hash_table =
+            no_partitioning_join::HashTable::new_on_cpu(hash_table_mem, self.hash_table_len)?;
+        hash_table.mlock()?;
+        let hash_table = hash_table;
+        let ht_malloc_time = ht_malloc_timer.elapsed();
+
+        let mut result_sums = vec![CachePadded { value: 0 }; threads];
+
+        let boxed_cpu_affinity = Arc::new(cpu_affinity.clone());
+        let thread_pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(threads)
+            .start_handler(move |tid| {
+                boxed_cpu_affinity
+                    .clone()
+                    .set_affinity(tid as u16)
+                    .expect("Couldn't set CPU core affinity");
+                likwid::thread_init();
+            })
+            .build()
+            .map_err(|_| ErrorKind::RuntimeError("Failed to create thread pool".to_string()))?;
+        let build_chunk_size = (data.build_relation_key.len() + threads - 1) / threads;
+        let probe_chunk_size = (data.probe_relation_key.len() + threads - 1) / threads;
+
+        let build_rel_key: &[K] = (&data.build_relation_key)
+            .try_into()
+            .map_err(|(err, _)| err)
+            .expect("Can't use CUDA device memory on CPU!");
+        let build_rel_chunks: Vec<_> = build_rel_key.chunks(build_chunk_size).collect();
+
+        let build_rel_pay: &[V] = (&data.build_relation_payload)
+            .try_into()
+            .map_err(|(err, _)| err)
+            .expect("Can't use CUDA device memory on CPU!");
+        let build_pay_chunks: Vec<_> = build_rel_pay.chunks(build_chunk_size).collect();
+
+        let probe_rel_key: &[K] = (&data.probe_relation_key)
+            .try_into()
+            .map_err(|(err, _)| err)
+            .expect("Can't use CUDA device memory on CPU!");
+        let probe_rel_chunks: Vec<_> = probe_rel_key.chunks(probe_chunk_size).collect();
+
+        let probe_rel_pay: &[V] = (&data.probe_relation_payload)
+            .try_into()
+            .map_err(|(err, _)| err)
+            .expect("Can't use CUDA device memory on CPU!");
+        let probe_pay_chunks: Vec<_> = probe_rel_pay.chunks(probe_chunk_size).collect();
+
+        let hj_builder = no_partitioning_join::CpuHashJoinBuilder::default()
+            .hashing_scheme(self.hashing_scheme)
+            .hash_function(self.hash_function)
+            .is_selective(self.is_selective)
+            .hash_table(Arc::new(hash_table));
+
+        let build_span = Span::new("phase_build");
+        let build_timer = Instant::now();
+        let mut thread_results: Vec<Result<()>> = (0..threads).map(|_| Ok(())).collect();
+        thread_pool.scope(|s| {
+            for ((rel, pay), result) in build_rel_chunks
+                .into_iter()
+                .zip(build_pay_chunks)
+                .zip(thread_results.iter_mut())
+            {
+                let mut hj_op = hj_builder.build();
+                s.spawn(move |_| {
+                    *result = hj_op.build(rel, pay).map_err(|e| e.into());
+                });
+            }
+        });
+        thread_results.into_iter().collect::<Result<()>>()?;
+        let build_time = build_timer.elapsed();
+        build_span.end();
+
+        let probe_span = Span::new("phase_probe");
+        let probe_timer = Instant::now();
+        let mut thread_results: Vec<Result<()>> = (0..threads).map(|_| Ok(())).collect();
+        thread_pool.scope(|s| {
+            for (((rel, pay), res), result) in probe_rel_chunks
+                .into_iter()
+                .zip(probe_pay_chunks)
+                .zip(result_sums.iter_mut())
+                .zip(thread_results.iter_mut())
+            {
+                let mut hj_op = hj_builder.build();
+                s.spawn(move |_| {
+                    *result = hj_op
+                        .probe_sum(rel, pay, &mut res.value)
+                        .map_err(|e| e.into());
+                });
+            }
+        });
+        thread_results.into_iter().collect::<Result<()>>()?;
+        let probe_time = probe_timer.elapsed();
+        probe_span.end();
+
         Ok(HashJoinPoint {
             build_ns: Some(build_time.as_nanos() as f64),
             probe_ns: Some(probe_time.as_nanos() as f64),
@@ -14,9 +14,13 @@
 
 use numa_gpu::runtime::allocator;
 use numa_gpu::runtime::cuda::CudaTransferStrategy;
+use numa_gpu::runtime::huge_pages::HugePageFallback;
 use numa_gpu::runtime::numa::{NodeRatio, PageType};
+pub use numa_gpu::runtime::topology::{ArgCpuAffinityPolicy, ArgCpuAffinityPolicyHelper};
+use numa_gpu::runtime::trace::TraceBackend;
 use serde_derive::Serialize;
 use serde_repr::Serialize_repr;
+pub use sql_ops::hash_function::ArgHashFunction;
 use sql_ops::join::HashingScheme;
 use structopt::clap::arg_enum;
 
@@ -73,6 +77,24 @@ arg_enum! {
     }
 }
 
+arg_enum! {
+    #[derive(Copy, Clone, Debug, PartialEq, Serialize)]
+    pub enum ArgHugePageFallback {
+        Fail,
+        TransparentHuge,
+        Small,
+    }
+}
+
+arg_enum! {
+    #[derive(Copy, Clone, Debug, PartialEq, Serialize)]
+    pub enum ArgTraceBackend {
+        Noop,
+        Nvtx,
+        Chrome,
+    }
+}
+
 arg_enum! {
     #[derive(Copy, Clone, Debug, PartialEq, Serialize)]
     pub enum ArgExecutionMethod {
@@ -100,15 +122,16 @@ arg_enum! {
     pub enum ArgHashingScheme {
         Perfect,
         LinearProbing,
+        BucketChaining,
     }
 }
 
 arg_enum! {
     #[derive(Copy, Clone, Debug, PartialEq, Serialize_repr)]
     #[repr(usize)]
-    pub enum ArgTupleBytes {
+    pub enum ArgAttributeBytes {
+        Bytes4 = 4,
         Bytes8 = 8,
-        Bytes16 = 16,
     }
 }
 
@@ -191,6 +214,26 @@ impl From<ArgPageType> for PageType {
     }
 }
 
+impl From<ArgHugePageFallback> for HugePageFallback {
+    fn from(fallback: ArgHugePageFallback) -> Self {
+        match fallback {
+            ArgHugePageFallback::Fail => HugePageFallback::Fail,
+            ArgHugePageFallback::TransparentHuge => HugePageFallback::TransparentHuge,
+            ArgHugePageFallback::Small => HugePageFallback::Small,
+        }
+    }
+}
+
+impl From<ArgTraceBackend> for TraceBackend {
+    fn from(backend: ArgTraceBackend) -> Self {
+        match backend {
+            ArgTraceBackend::Noop => TraceBackend::Noop,
+            ArgTraceBackend::Nvtx => TraceBackend::Nvtx,
+            ArgTraceBackend::Chrome => TraceBackend::Chrome,
+        }
+    }
+}
+
 impl From<ArgTransferStrategy> for CudaTransferStrategy {
     fn from(asm: ArgTransferStrategy) -> Self {
         match asm {
@@ -210,6 +253,7 @@ impl From<ArgHashingScheme> for HashingScheme {
         match ahs {
             ArgHashingScheme::Perfect => HashingScheme::Perfect,
             ArgHashingScheme::LinearProbing => HashingScheme::LinearProbing,
+            ArgHashingScheme::BucketChaining => HashingScheme::BucketChaining,
         }
     }
 }
@@ -12,8 +12,10 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+#[cfg(feature = "cuda")]
 use cuda_driver_sys::CUresult;
 use error_chain::error_chain;
+#[cfg(feature = "cuda")]
 use rustacuda::error::CudaError;
 
 error_chain! {
@@ -22,6 +24,10 @@ error_chain! {
             description("Invalid argument error")
             display("Aborted with: {}", msg)
         }
+        InvalidNode(node: u16) {
+            description("Invalid NUMA node error")
+            display("Aborted with: NUMA node {} does not exist", node)
+        }
         InvalidConversion(msg: &'static str) {
             description("Conversion error")
             display("Aborting with: {}", msg)
@@ -34,6 +40,18 @@ error_chain! {
             description("Logic error")
             display("Aborting with: {}", msg)
         }
+        MbindRefused(msg: String) {
+            description("NUMA memory binding error")
+            display("Aborted with: {}", msg)
+        }
+        NoHugePages(msg: String) {
+            description("Huge pages unavailable error")
+            display("Aborted with: {}", msg)
+        }
+        OutOfMemory(msg: String) {
+            description("Out of memory error")
+            display("Aborted with: {}", msg)
+        }
         RuntimeError(msg: String) {
             description("Runtime error")
             display("Aborting with: {}", msg)
@@ -41,7 +59,7 @@ error_chain! {
     }
 
     foreign_links {
-        Cuda(CudaError);
+        Cuda(CudaError) #[cfg(feature = "cuda")];
         Io(::std::io::Error);
         ProcFs(procfs::ProcError);
         RayonThreadPoolBuild(rayon::ThreadPoolBuildError);
@@ -51,10 +69,12 @@ error_chain! {
 /// Converts raw C CUresult into Rust-ified Result type
 ///
 /// Copied from Rustacuda, because visibility is constrained to crate scope.
+#[cfg(feature = "cuda")]
 pub trait ToResult {
     fn to_result(self) -> Result<()>;
 }
 
+#[cfg(feature = "cuda")]
 impl ToResult for CUresult {
     fn to_result(self) -> Result<()> {
         match self {
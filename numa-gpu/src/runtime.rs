@@ -14,12 +14,19 @@
 
 pub mod allocator;
 pub mod cpu_affinity;
+#[cfg(feature = "cuda")]
 pub mod cuda;
+#[cfg(feature = "cuda")]
 pub mod cuda_wrapper;
 pub mod dispatcher;
+pub mod huge_pages;
 pub mod hw_info;
 pub mod linux_wrapper;
 pub mod memory;
 pub mod numa;
+#[cfg(feature = "cuda")]
 pub mod nvml;
+#[cfg(feature = "cuda")]
 pub mod nvtx;
+pub mod topology;
+pub mod trace;
@@ -20,8 +20,15 @@
 //!
 //! The allocated memory is of type Mem, and specialized to DerefMem whenever
 //! possible.
+//!
+//! The `try_*` allocators return an error if the allocation fails, e.g., if
+//! not enough huge pages are available or the NUMA node doesn't exist. Their
+//! infallible counterparts panic instead.
 
-use rustacuda::memory::{DeviceBuffer, DeviceCopy, LockedBuffer, UnifiedBuffer};
+#[cfg(feature = "cuda")]
+use rustacuda::error::CudaError;
+#[cfg(feature = "cuda")]
+use rustacuda::memory::{DeviceBuffer, LockedBuffer, UnifiedBuffer};
 
 use std::alloc::{self, Layout};
 use std::cell::RefCell;
@@ -32,7 +39,9 @@ use std::rc::Rc;
 use std::slice;
 
 use super::hw_info::ProcessorCache;
-use super::memory::{DerefMem, Mem, PageLock};
+#[cfg(feature = "cuda")]
+use super::memory::PageLock;
+use super::memory::{DerefMem, DeviceCopy, Mem};
 use super::numa::{DistributedNumaMemory, NodeLen, NodeRatio, NumaMemory, PageType};
 use crate::error::{Error, ErrorKind, Result};
 
@@ -53,6 +62,7 @@ pub enum MemType {
     /// NUMA memory allocated on the specified NUMA node and with the specified page type
     NumaMem { node: u16, page_type: PageType },
     /// NUMA memory allocated on the specified NUMA node and pinned with CUDA
+    #[cfg(feature = "cuda")]
     NumaPinnedMem { node: u16, page_type: PageType },
     /// NUMA memory distributed in proportion to a ratio over multiple NUMA nodes
     DistributedNumaMem {
@@ -65,10 +75,13 @@ pub enum MemType {
         page_type: PageType,
     },
     /// CUDA pinned memory (using cudaHostAlloc())
+    #[cfg(feature = "cuda")]
     CudaPinnedMem,
     /// CUDA unified memory
+    #[cfg(feature = "cuda")]
     CudaUniMem,
     /// CUDA device memory
+    #[cfg(feature = "cuda")]
     CudaDevMem,
 }
 
@@ -86,6 +99,7 @@ pub enum DerefMemType {
     /// NUMA memory allocated on the specified NUMA node and with the specified page type
     NumaMem { node: u16, page_type: PageType },
     /// NUMA memory allocated on the specified NUMA node, with the specified page type, and pinned with CUDA
+    #[cfg(feature = "cuda")]
     NumaPinnedMem { node: u16, page_type: PageType },
     /// NUMA memory distributed in proportion to a ratio over multiple NUMA nodes
     DistributedNumaMem {
@@ -98,8 +112,10 @@ pub enum DerefMemType {
         page_type: PageType,
     },
     /// CUDA pinned memory (using cudaHostAlloc())
+    #[cfg(feature = "cuda")]
     CudaPinnedMem,
     /// CUDA unified memory
+    #[cfg(feature = "cuda")]
     CudaUniMem,
 }
 
@@ -117,13 +133,14 @@ impl MemType {
     pub fn page_type(&self) -> PageType {
         match *self {
             MemType::NumaMem { page_type, .. } => page_type,
+            #[cfg(feature = "cuda")]
             MemType::NumaPinnedMem { page_type, .. } => page_type,
             MemType::DistributedNumaMem { page_type, .. } => page_type,
             MemType::DistributedNumaMemWithLen { page_type, .. } => page_type,
-            MemType::SysMem
-            | MemType::AlignedSysMem { .. }
-            | MemType::CudaPinnedMem
-            | MemType::CudaUniMem => PageType::Default,
+            MemType::SysMem | MemType::AlignedSysMem { .. } => PageType::Default,
+            #[cfg(feature = "cuda")]
+            MemType::CudaPinnedMem | MemType::CudaUniMem => PageType::Default,
+            #[cfg(feature = "cuda")]
             MemType::CudaDevMem => PageType::Default,
         }
     }
@@ -133,13 +150,13 @@ impl DerefMemType {
     pub fn page_type(&self) -> PageType {
         match *self {
             DerefMemType::NumaMem { page_type, .. } => page_type,
+            #[cfg(feature = "cuda")]
             DerefMemType::NumaPinnedMem { page_type, .. } => page_type,
             DerefMemType::DistributedNumaMem { page_type, .. } => page_type,
             DerefMemType::DistributedNumaMemWithLen { page_type, .. } => page_type,
-            DerefMemType::SysMem
-            | DerefMemType::AlignedSysMem { .. }
-            | DerefMemType::CudaPinnedMem
-            | DerefMemType::CudaUniMem => PageType::Default,
+            DerefMemType::SysMem | DerefMemType::AlignedSysMem { .. } => PageType::Default,
+            #[cfg(feature = "cuda")]
+            DerefMemType::CudaPinnedMem | DerefMemType::CudaUniMem => PageType::Default,
         }
     }
 }
@@ -150,6 +167,7 @@ impl From<DerefMemType> for MemType {
             DerefMemType::SysMem => MemType::SysMem,
             DerefMemType::AlignedSysMem { align_bytes } => MemType::AlignedSysMem { align_bytes },
             DerefMemType::NumaMem { node, page_type } => MemType::NumaMem { node, page_type },
+            #[cfg(feature = "cuda")]
             DerefMemType::NumaPinnedMem { node, page_type } => {
                 MemType::NumaPinnedMem { node, page_type }
             }
@@ -159,7 +177,9 @@ impl From<DerefMemType> for MemType {
             DerefMemType::DistributedNumaMemWithLen { nodes, page_type } => {
                 MemType::DistributedNumaMemWithLen { nodes, page_type }
             }
+            #[cfg(feature = "cuda")]
             DerefMemType::CudaPinnedMem => MemType::CudaPinnedMem,
+            #[cfg(feature = "cuda")]
             DerefMemType::CudaUniMem => MemType::CudaUniMem,
         }
     }
@@ -181,6 +201,7 @@ impl TryFrom<MemType> for DerefMemType {
                 Ok(DerefMemType::AlignedSysMem { align_bytes })
             }
             MemType::NumaMem { node, page_type } => Ok(DerefMemType::NumaMem { node, page_type }),
+            #[cfg(feature = "cuda")]
             MemType::NumaPinnedMem { node, page_type } => {
                 Ok(DerefMemType::NumaPinnedMem { node, page_type })
             }
@@ -190,8 +211,11 @@ impl TryFrom<MemType> for DerefMemType {
             MemType::DistributedNumaMemWithLen { nodes, page_type } => {
                 Ok(DerefMemType::DistributedNumaMemWithLen { nodes, page_type })
             }
+            #[cfg(feature = "cuda")]
             MemType::CudaPinnedMem => Ok(DerefMemType::CudaPinnedMem),
+            #[cfg(feature = "cuda")]
             MemType::CudaUniMem => Ok(DerefMemType::CudaUniMem),
+            #[cfg(feature = "cuda")]
             MemType::CudaDevMem => Err(ErrorKind::InvalidConversion(
                 "Cannot convert device memory to &[T] slice",
             )
@@ -214,6 +238,17 @@ pub type MemAllocFn<T> = Box<dyn Fn(usize) -> Mem<T>>;
 /// This allows the callee to generalize over all memory types.
 pub type DerefMemAllocFn<T> = Box<dyn Fn(usize) -> DerefMem<T>>;
 
+/// Fallible generic memory allocator for Mem that hides concrete memory type
+///
+/// Returns an error instead of panicking if the allocation fails.
+pub type TryMemAllocFn<T> = Box<dyn Fn(usize) -> Result<Mem<T>>>;
+
+/// Fallible generic memory allocator for DerefMem that hides concrete memory
+/// type
+///
+/// Returns an error instead of panicking if the allocation fails.
+pub type TryDerefMemAllocFn<T> = Box<dyn Fn(usize) -> Result<DerefMem<T>>>;
+
 /// A curried memory allocator for caching and spilling memory
 ///
 /// Takes as an argument the maximum GPU cache length.
@@ -221,116 +256,147 @@ pub type MemSpillAllocFn<T> = Box<dyn Fn(usize) -> MemAllocFn<T>>;
 
 impl Allocator {
     /// Allocates memory of the specified type
+    ///
+    /// Panics if the allocation fails. See `try_alloc_mem` for the fallible
+    /// variant.
     pub fn alloc_mem<T: Clone + Default + DeviceCopy>(mem_type: MemType, len: usize) -> Mem<T> {
-        match mem_type {
-            MemType::SysMem => Self::alloc_system(len).into(),
-            MemType::AlignedSysMem { align_bytes } => Self::alloc_aligned(len, align_bytes).into(),
-            MemType::NumaMem { node, page_type } => Self::alloc_numa(len, node, page_type).into(),
+        Self::try_alloc_mem(mem_type, len).expect("Failed to allocate memory")
+    }
+
+    /// Allocates host-dereferencable memory of the specified type
+    ///
+    /// Panics if the allocation fails. See `try_alloc_deref_mem` for the
+    /// fallible variant.
+    pub fn alloc_deref_mem<T: Clone + Default + DeviceCopy>(
+        mem_type: DerefMemType,
+        len: usize,
+    ) -> DerefMem<T> {
+        Self::try_alloc_deref_mem(mem_type, len).expect("Failed to allocate memory")
+    }
+
+    /// Tries to allocate memory of the specified type
+    pub fn try_alloc_mem<T: Clone + Default + DeviceCopy>(
+        mem_type: MemType,
+        len: usize,
+    ) -> Result<Mem<T>> {
+        let mem = match mem_type {
+            MemType::SysMem => Self::alloc_system(len)?.into(),
+            MemType::AlignedSysMem { align_bytes } => Self::alloc_aligned(len, align_bytes)?.into(),
+            MemType::NumaMem { node, page_type } => Self::alloc_numa(len, node, page_type)?.into(),
+            #[cfg(feature = "cuda")]
             MemType::NumaPinnedMem { node, page_type } => {
-                Self::alloc_numa_pinned(len, node, page_type).into()
+                Self::alloc_numa_pinned(len, node, page_type)?.into()
             }
             MemType::DistributedNumaMem { nodes, page_type } => {
-                Self::alloc_distributed_numa(len, nodes, page_type).into()
+                Self::alloc_distributed_numa(len, nodes, page_type)?.into()
             }
             MemType::DistributedNumaMemWithLen { nodes, page_type } => {
-                Self::alloc_distributed_numa_with_len(len, nodes, page_type).into()
+                Self::alloc_distributed_numa_with_len(len, nodes, page_type)?.into()
             }
-            MemType::CudaPinnedMem => Self::alloc_cuda_pinned(len).into(),
-            MemType::CudaUniMem => Self::alloc_cuda_unified(len).into(),
-            MemType::CudaDevMem => Self::alloc_cuda_device(len),
-        }
+            #[cfg(feature = "cuda")]
+            MemType::CudaPinnedMem => Self::alloc_cuda_pinned(len)?.into(),
+            #[cfg(feature = "cuda")]
+            MemType::CudaUniMem => Self::alloc_cuda_unified(len)?.into(),
+            #[cfg(feature = "cuda")]
+            MemType::CudaDevMem => Self::alloc_cuda_device(len)?,
+        };
+
+        Ok(mem)
     }
 
-    /// Allocates host-dereferencable memory of the specified type
-    pub fn alloc_deref_mem<T: Clone + Default + DeviceCopy>(
+    /// Tries to allocate host-dereferencable memory of the specified type
+    pub fn try_alloc_deref_mem<T: Clone + Default + DeviceCopy>(
         mem_type: DerefMemType,
         len: usize,
-    ) -> DerefMem<T> {
+    ) -> Result<DerefMem<T>> {
         match mem_type {
             DerefMemType::SysMem => Self::alloc_system(len),
-            DerefMemType::AlignedSysMem { align_bytes } => {
-                Self::alloc_aligned(len, align_bytes).into()
-            }
+            DerefMemType::AlignedSysMem { align_bytes } => Self::alloc_aligned(len, align_bytes),
             DerefMemType::NumaMem { node, page_type } => Self::alloc_numa(len, node, page_type),
+            #[cfg(feature = "cuda")]
             DerefMemType::NumaPinnedMem { node, page_type } => {
-                Self::alloc_numa_pinned(len, node, page_type).into()
+                Self::alloc_numa_pinned(len, node, page_type)
             }
             DerefMemType::DistributedNumaMem { nodes, page_type } => {
-                Self::alloc_distributed_numa(len, nodes, page_type).into()
+                Self::alloc_distributed_numa(len, nodes, page_type)
             }
             DerefMemType::DistributedNumaMemWithLen { nodes, page_type } => {
-                Self::alloc_distributed_numa_with_len(len, nodes, page_type).into()
+                Self::alloc_distributed_numa_with_len(len, nodes, page_type)
             }
+            #[cfg(feature = "cuda")]
             DerefMemType::CudaPinnedMem => Self::alloc_cuda_pinned(len),
+            #[cfg(feature = "cuda")]
             DerefMemType::CudaUniMem => Self::alloc_cuda_unified(len),
         }
     }
 
     /// Returns a generic 'Mem' memory allocator that allocates memory of the
     /// specified 'Mem' type.
+    ///
+    /// The allocator panics if the allocation fails.
     pub fn mem_alloc_fn<T: Clone + Default + DeviceCopy>(mem_type: MemType) -> MemAllocFn<T> {
-        match mem_type {
-            MemType::SysMem => Box::new(|len| Self::alloc_system(len).into()),
-            MemType::AlignedSysMem { align_bytes } => {
-                Box::new(move |len| Self::alloc_aligned(len, align_bytes).into())
-            }
-            MemType::NumaMem { node, page_type } => {
-                Box::new(move |len| Self::alloc_numa(len, node, page_type).into())
-            }
-            MemType::NumaPinnedMem { node, page_type } => {
-                Box::new(move |len| Self::alloc_numa_pinned(len, node, page_type).into())
-            }
-            MemType::DistributedNumaMem { nodes, page_type } => Box::new(move |len| {
-                Self::alloc_distributed_numa(len, nodes.clone(), page_type).into()
-            }),
-            MemType::DistributedNumaMemWithLen { nodes, page_type } => Box::new(move |len| {
-                Self::alloc_distributed_numa_with_len(len, nodes.clone(), page_type).into()
-            }),
-            MemType::CudaPinnedMem => Box::new(|len| Self::alloc_cuda_pinned(len).into()),
-            MemType::CudaUniMem => Box::new(|len| Self::alloc_cuda_unified(len).into()),
-            MemType::CudaDevMem => Box::new(|len| Self::alloc_cuda_device(len)),
-        }
+        Box::new(move |len| Self::alloc_mem(mem_type.clone(), len))
     }
 
     /// Returns a generic 'DerefMem' memory allocator that allocates memory of
     /// the specified 'DerefMem' type.
+    ///
+    /// The allocator panics if the allocation fails.
     pub fn deref_mem_alloc_fn<T: Clone + Default + DeviceCopy>(
         mem_type: DerefMemType,
     ) -> DerefMemAllocFn<T> {
-        match mem_type {
-            DerefMemType::SysMem => Box::new(|len| Self::alloc_system(len)),
-            DerefMemType::AlignedSysMem { align_bytes } => {
-                Box::new(move |len| Self::alloc_aligned(len, align_bytes).into())
-            }
-            DerefMemType::NumaMem { node, page_type } => {
-                Box::new(move |len| Self::alloc_numa(len, node, page_type))
-            }
-            DerefMemType::NumaPinnedMem { node, page_type } => {
-                Box::new(move |len| Self::alloc_numa_pinned(len, node, page_type))
-            }
-            DerefMemType::DistributedNumaMem { nodes, page_type } => Box::new(move |len| {
-                Self::alloc_distributed_numa(len, nodes.clone(), page_type).into()
-            }),
-            DerefMemType::DistributedNumaMemWithLen { nodes, page_type } => Box::new(move |len| {
-                Self::alloc_distributed_numa_with_len(len, nodes.clone(), page_type).into()
-            }),
-            DerefMemType::CudaPinnedMem => Box::new(|len| Self::alloc_cuda_pinned(len)),
-            DerefMemType::CudaUniMem => Box::new(|len| Self::alloc_cuda_unified(len)),
-        }
+        Box::new(move |len| Self::alloc_deref_mem(mem_type.clone(), len))
+    }
+
+    /// Returns a fallible generic 'Mem' memory allocator that allocates memory
+    /// of the specified 'Mem' type.
+    pub fn try_mem_alloc_fn<T: Clone + Default + DeviceCopy>(
+        mem_type: MemType,
+    ) -> TryMemAllocFn<T> {
+        Box::new(move |len| Self::try_alloc_mem(mem_type.clone(), len))
+    }
+
+    /// Returns a fallible generic 'DerefMem' memory allocator that allocates
+    /// memory of the specified 'DerefMem' type.
+    pub fn try_deref_mem_alloc_fn<T: Clone + Default + DeviceCopy>(
+        mem_type: DerefMemType,
+    ) -> TryDerefMemAllocFn<T> {
+        Box::new(move |len| Self::try_alloc_deref_mem(mem_type.clone(), len))
     }
 
     /// Allocates system memory using Rust's global allocator.
-    fn alloc_system<T: Clone + Default + DeviceCopy>(len: usize) -> DerefMem<T> {
-        DerefMem::SysMem(vec![T::default(); len])
+    fn alloc_system<T: Clone + Default + DeviceCopy>(len: usize) -> Result<DerefMem<T>> {
+        let mut mem = Vec::new();
+        mem.try_reserve_exact(len).map_err(|_| {
+            ErrorKind::OutOfMemory(format!(
+                "Failed to allocate {} bytes of system memory",
+                len * size_of::<T>()
+            ))
+        })?;
+        mem.resize(len, T::default());
+
+        Ok(DerefMem::SysMem(mem))
     }
 
     /// Allocates aligned system memory using Rust's global allocator.
-    fn alloc_aligned<T: Clone + Default + DeviceCopy>(len: usize, alignment: usize) -> DerefMem<T> {
+    fn alloc_aligned<T: Clone + Default + DeviceCopy>(
+        len: usize,
+        alignment: usize,
+    ) -> Result<DerefMem<T>> {
         let mem = unsafe {
-            let layout = Layout::from_size_align(len * size_of::<T>(), alignment)
-                .expect("Memory alignment must be at least size of T");
+            let layout =
+                Layout::from_size_align(len * size_of::<T>(), alignment).map_err(|_| {
+                    ErrorKind::InvalidArgument(
+                        "Memory alignment must be at least size of T".to_string(),
+                    )
+                })?;
             let ptr = alloc::alloc(layout) as *mut T;
-            assert!(!ptr.is_null(), "Failed to allocate aligned memory");
+            if ptr.is_null() {
+                Err(ErrorKind::OutOfMemory(format!(
+                    "Failed to allocate {} bytes of aligned memory",
+                    layout.size()
+                )))?;
+            }
 
             let slice = slice::from_raw_parts_mut(ptr, len);
             slice.iter_mut().for_each(|x| *x = T::default());
@@ -338,19 +404,30 @@ impl Allocator {
             let output: Box<[T]> = Box::from_raw(slice);
             output
         };
-        DerefMem::BoxedSysMem(mem)
+        Ok(DerefMem::BoxedSysMem(mem))
     }
 
     /// Allocates memory on the specified NUMA node.
-    fn alloc_numa<T: DeviceCopy>(len: usize, node: u16, page_type: PageType) -> DerefMem<T> {
-        DerefMem::NumaMem(NumaMemory::new(len, node, page_type))
+    fn alloc_numa<T: DeviceCopy>(
+        len: usize,
+        node: u16,
+        page_type: PageType,
+    ) -> Result<DerefMem<T>> {
+        Ok(DerefMem::NumaMem(NumaMemory::try_new(
+            len, node, page_type,
+        )?))
     }
 
     /// Allocates pinned memory on the specified NUMA node.
-    fn alloc_numa_pinned<T: DeviceCopy>(len: usize, node: u16, page_type: PageType) -> DerefMem<T> {
-        let mut mem = NumaMemory::new(len, node, page_type);
-        mem.page_lock().expect("Failed to pin memory");
-        DerefMem::NumaMem(mem)
+    #[cfg(feature = "cuda")]
+    fn alloc_numa_pinned<T: DeviceCopy>(
+        len: usize,
+        node: u16,
+        page_type: PageType,
+    ) -> Result<DerefMem<T>> {
+        let mut mem = NumaMemory::try_new(len, node, page_type)?;
+        mem.page_lock()?;
+        Ok(DerefMem::NumaMem(mem))
     }
 
     /// Allocates memory on multiple, specified NUMA nodes.
@@ -358,8 +435,10 @@ impl Allocator {
         len: usize,
         nodes: Box<[NodeRatio]>,
         page_type: PageType,
-    ) -> DerefMem<T> {
-        DerefMem::DistributedNumaMem(DistributedNumaMemory::new_with_ratio(len, nodes, page_type))
+    ) -> Result<DerefMem<T>> {
+        Ok(DerefMem::DistributedNumaMem(
+            DistributedNumaMemory::try_new_with_ratio(len, nodes, page_type)?,
+        ))
     }
 
     /// Allocates memory on multiple, specified NUMA nodes.
@@ -367,8 +446,10 @@ impl Allocator {
         len: usize,
         nodes: Box<[NodeLen]>,
         page_type: PageType,
-    ) -> DerefMem<T> {
-        DerefMem::DistributedNumaMem(DistributedNumaMemory::new_with_len(len, nodes, page_type))
+    ) -> Result<DerefMem<T>> {
+        Ok(DerefMem::DistributedNumaMem(
+            DistributedNumaMemory::try_new_with_len(len, nodes, page_type)?,
+        ))
     }
 
     /// Allocates CUDA pinned memory using cudaHostAlloc
@@ -376,11 +457,11 @@ impl Allocator {
     /// Warning: Returns uninitialized memory. The reason is that CUDA allocates
     /// the memory local to the processor that first touches the memory. This
     /// decision is left to the user.
-    fn alloc_cuda_pinned<T: Clone + Default + DeviceCopy>(len: usize) -> DerefMem<T> {
-        DerefMem::CudaPinnedMem(LockedBuffer::<T>::new(&T::default(), len).expect(&format!(
-            "Failed dot allocate {} bytes of CUDA pinned memory",
-            len * size_of::<T>()
-        )))
+    #[cfg(feature = "cuda")]
+    fn alloc_cuda_pinned<T: Clone + Default + DeviceCopy>(len: usize) -> Result<DerefMem<T>> {
+        let mem = LockedBuffer::<T>::new(&T::default(), len)
+            .map_err(|e| Self::cuda_alloc_error::<T>(e, len, "CUDA pinned"))?;
+        Ok(DerefMem::CudaPinnedMem(mem))
     }
 
     /// Allocates CUDA unified memory.
@@ -388,13 +469,11 @@ impl Allocator {
     /// Warning: Returns uninitialized memory. The reason is that CUDA allocates
     /// the memory local to the processor that first touches the memory. This
     /// decision is left to the user.
-    fn alloc_cuda_unified<T: Clone + Default + DeviceCopy>(len: usize) -> DerefMem<T> {
-        unsafe {
-            DerefMem::CudaUniMem(UnifiedBuffer::<T>::uninitialized(len).expect(&format!(
-                "Failed dot allocate {} bytes of CUDA unified memory",
-                len * size_of::<T>()
-            )))
-        }
+    #[cfg(feature = "cuda")]
+    fn alloc_cuda_unified<T: Clone + Default + DeviceCopy>(len: usize) -> Result<DerefMem<T>> {
+        let mem = unsafe { UnifiedBuffer::<T>::uninitialized(len) }
+            .map_err(|e| Self::cuda_alloc_error::<T>(e, len, "CUDA unified"))?;
+        Ok(DerefMem::CudaUniMem(mem))
     }
 
     /// Allocates CUDA device memory.
@@ -405,12 +484,25 @@ impl Allocator {
     /// Warning: Returns uninitialized memory. The reason is that the allocator
     /// cannot initialize the memory asynchronously, due to the user not
     /// providing a CUDA stream in the API.
-    fn alloc_cuda_device<T: DeviceCopy>(len: usize) -> Mem<T> {
-        unsafe {
-            Mem::CudaDevMem(DeviceBuffer::<T>::uninitialized(len).expect(&format!(
-                "Failed to allocate {} bytes of CUDA device memory",
-                len * size_of::<T>()
-            )))
+    #[cfg(feature = "cuda")]
+    fn alloc_cuda_device<T: DeviceCopy>(len: usize) -> Result<Mem<T>> {
+        let mem = unsafe { DeviceBuffer::<T>::uninitialized(len) }
+            .map_err(|e| Self::cuda_alloc_error::<T>(e, len, "CUDA device"))?;
+        Ok(Mem::CudaDevMem(mem))
+    }
+
+    /// Converts a CUDA allocation error into an `OutOfMemory` error, if CUDA
+    /// ran out of memory.
+    #[cfg(feature = "cuda")]
+    fn cuda_alloc_error<T>(err: CudaError, len: usize, mem_name: &str) -> Error {
+        let msg = format!(
+            "Failed to allocate {} bytes of {} memory",
+            len * size_of::<T>(),
+            mem_name
+        );
+        match err {
+            CudaError::OutOfMemory => Error::with_chain(err, ErrorKind::OutOfMemory(msg)),
+            _ => Error::with_chain(err, msg),
         }
     }
 
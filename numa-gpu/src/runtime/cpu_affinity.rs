@@ -15,7 +15,8 @@
 //! Set the CPU core affinity of a thread.
 
 use crate::error::{ErrorKind, Result};
-use crate::runtime::linux_wrapper::CpuSet;
+use crate::runtime::linux_wrapper::{self, CpuSet};
+use crate::runtime::topology::{AffinityPolicy, Topology};
 use std::default::Default;
 use std::fs::File;
 use std::io::Error as IoError;
@@ -62,6 +63,17 @@ impl CpuAffinity {
         Ok(Self { affinity_list })
     }
 
+    /// Generates CPU core affinities from the machine's NUMA topology.
+    ///
+    /// Only the CPU cores that the process is allowed to run on are included.
+    /// See `AffinityPolicy` for the thread placement.
+    pub fn from_policy(policy: AffinityPolicy) -> Result<Self> {
+        let topology = Topology::from_sysfs()?;
+        let allowed = linux_wrapper::sched_getaffinity()?;
+
+        topology.cpu_affinity(policy, &allowed)
+    }
+
     /// Maps a thread ID to a CPU core ID.
     ///
     /// Returns a CPU core ID, or `None` if the thread ID is out-of-bounds.
@@ -116,19 +128,8 @@ impl CpuAffinity {
 
 impl Default for CpuAffinity {
     fn default() -> Self {
-        let mut cpu_set = CpuSet::new();
-
-        unsafe {
-            if libc::sched_getaffinity(
-                0,
-                cpu_set.bytes(),
-                cpu_set.as_mut_slice().as_mut_ptr() as *mut libc::cpu_set_t,
-            ) == -1
-            {
-                Err(ErrorKind::Io(IoError::last_os_error()))
-                    .expect("Couldn't get the list of available CPU affinities from the OS")
-            }
-        }
+        let cpu_set = linux_wrapper::sched_getaffinity()
+            .expect("Couldn't get the list of available CPU affinities from the OS");
 
         let affinity_list: Vec<_> = (0..cpu_set.max_id())
             .filter(|&cpu_id| cpu_set.is_set(cpu_id))
@@ -15,6 +15,9 @@
 mod het_morsel_executor;
 mod het_morsel_iterator;
 mod morsel_dispatcher;
+mod worker_stats;
 
 pub use het_morsel_executor::*;
 pub use het_morsel_iterator::*;
+pub use morsel_dispatcher::{MorselCounts, WorkerMorselCounts};
+pub use worker_stats::{ExecutorStats, WorkerStats};
@@ -12,15 +12,32 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use super::WorkerMorselCounts;
+#[cfg(not(feature = "cuda"))]
+use crate::error::ErrorKind;
 use crate::error::Result;
 use crate::runtime::cpu_affinity::CpuAffinity;
 use rayon::{ThreadPool, ThreadPoolBuilder};
-use rustacuda::context::CurrentContext;
+#[cfg(feature = "cuda")]
+use rustacuda::context::{CurrentContext, UnownedContext};
+#[cfg(feature = "cuda")]
 use rustacuda::error::CudaError;
-use rustacuda::stream::{Stream, StreamFlags};
+#[cfg(feature = "cuda")]
+use rustacuda::stream::StreamFlags;
 use std::default::Default;
 use std::sync::Arc;
 
+/// The CUDA stream type passed to GPU workers.
+#[cfg(feature = "cuda")]
+pub use rustacuda::stream::Stream;
+
+/// A placeholder for CUDA streams in CPU-only builds.
+///
+/// Without the `cuda` feature, an executor has no GPU workers. Thus, GPU
+/// closures are never called, and a `Stream` value can never exist.
+#[cfg(not(feature = "cuda"))]
+pub enum Stream {}
+
 #[derive(Clone)]
 pub struct MorselSpec {
     pub cpu_morsel_bytes: usize,
@@ -40,6 +57,18 @@ pub struct HetMorselExecutor {
     pub(super) cpu_thread_pool: ThreadPool,
     pub(super) gpu_thread_pool: ThreadPool,
     pub(super) streams: Vec<Stream>,
+    pub(super) morsel_counts: WorkerMorselCounts,
+}
+
+impl HetMorselExecutor {
+    /// Returns the number of local and remote morsels per worker in the last
+    /// `fold`.
+    ///
+    /// Morsels are only counted with NUMA-aware dispatch. See
+    /// `HetMorselIterator::numa_aware` for details.
+    pub fn morsel_counts(&self) -> &WorkerMorselCounts {
+        &self.morsel_counts
+    }
 }
 
 pub struct HetMorselExecutorBuilder {
@@ -89,25 +118,50 @@ impl HetMorselExecutorBuilder {
         self
     }
 
+    /// Sets the GPUs on which GPU workers run.
+    ///
+    /// Without any GPUs, the executor runs only CPU workers and does not
+    /// require a CUDA context.
     pub fn gpu_ids(mut self, gpu_ids: Vec<u16>) -> Self {
         self.gpu_ids = gpu_ids;
         self
     }
 
+    /// Builds the executor.
+    ///
+    /// GPU workers require a current CUDA context, which is shared with all
+    /// worker threads. Without GPU workers, the executor only inherits a
+    /// context if one happens to be current.
     pub fn build(self) -> Result<HetMorselExecutor> {
         let cpu_workers = self.cpu_threads;
         let gpu_workers = self.gpu_ids.len();
         let cpu_affinity = self.cpu_worker_affinity.clone();
         let gpu_affinity = self.gpu_worker_affinity.clone();
 
-        let unowned_context_cpu_pool = CurrentContext::get_current()?;
+        #[cfg(feature = "cuda")]
+        let unowned_context_cpu_pool = Self::current_context(gpu_workers)?;
+        #[cfg(feature = "cuda")]
         let unowned_context_gpu_pool = unowned_context_cpu_pool.clone();
 
+        #[cfg(not(feature = "cuda"))]
+        {
+            if gpu_workers != 0 {
+                Err(ErrorKind::InvalidArgument(
+                    "GPU workers require the cuda feature".to_string(),
+                ))?;
+            }
+        }
+
         let cpu_thread_pool = ThreadPoolBuilder::new()
             .num_threads(cpu_workers)
             .start_handler(move |tid| {
-                CurrentContext::set_current(&unowned_context_cpu_pool)
-                    .expect("Failed to set CUDA context in CPU worker thread");
+                #[cfg(feature = "cuda")]
+                {
+                    if let Some(ref context) = unowned_context_cpu_pool {
+                        CurrentContext::set_current(context)
+                            .expect("Failed to set CUDA context in CPU worker thread");
+                    }
+                }
 
                 cpu_affinity
                     .clone()
@@ -116,11 +170,18 @@ impl HetMorselExecutorBuilder {
             })
             .build()?;
 
+        // Rayon interprets zero threads as the default number of threads, thus
+        // spawn a single idle thread if there are no GPU workers
         let gpu_thread_pool = ThreadPoolBuilder::new()
-            .num_threads(gpu_workers)
+            .num_threads(gpu_workers.max(1))
             .start_handler(move |tid| {
-                CurrentContext::set_current(&unowned_context_gpu_pool)
-                    .expect("Failed to set CUDA context in GPU worker thread");
+                #[cfg(feature = "cuda")]
+                {
+                    if let Some(ref context) = unowned_context_gpu_pool {
+                        CurrentContext::set_current(context)
+                            .expect("Failed to set CUDA context in GPU worker thread");
+                    }
+                }
 
                 gpu_affinity
                     .clone()
@@ -129,9 +190,12 @@ impl HetMorselExecutorBuilder {
             })
             .build()?;
 
+        #[cfg(feature = "cuda")]
         let streams = (0..gpu_workers)
             .map(|_| Stream::new(StreamFlags::NON_BLOCKING, None))
             .collect::<std::result::Result<Vec<_>, CudaError>>()?;
+        #[cfg(not(feature = "cuda"))]
+        let streams = Vec::new();
 
         Ok(HetMorselExecutor {
             morsel_spec: self.morsel_spec,
@@ -140,6 +204,21 @@ impl HetMorselExecutorBuilder {
             cpu_thread_pool,
             gpu_thread_pool,
             streams,
+            morsel_counts: WorkerMorselCounts::default(),
         })
     }
+
+    /// Returns the current CUDA context.
+    ///
+    /// The context is mandatory for GPU workers. CPU workers might use CUDA
+    /// as well, e.g., to allocate pinned memory. Thus, they share the current
+    /// context if the CUDA driver is initialized.
+    #[cfg(feature = "cuda")]
+    fn current_context(gpu_workers: usize) -> Result<Option<UnownedContext>> {
+        if gpu_workers == 0 {
+            Ok(CurrentContext::get_current().ok())
+        } else {
+            Ok(Some(CurrentContext::get_current()?))
+        }
+    }
 }
@@ -13,14 +13,17 @@
 // limitations under the License.
 
 use super::morsel_dispatcher::MorselDispatcher;
-use super::HetMorselExecutor;
+use super::{ExecutorStats, HetMorselExecutor, Stream, WorkerStats};
 use crate::error::*;
+use crate::runtime::cpu_affinity::CpuAffinity;
+use crate::runtime::memory::DeviceCopy;
 use crate::runtime::memory::LaunchableMem;
 use crate::runtime::memory::LaunchableSlice;
-use rustacuda::memory::DeviceCopy;
-use rustacuda::stream::Stream;
-use std::mem::{self, size_of, MaybeUninit};
+use crate::runtime::numa::{self, NodeRange};
+use std::mem::{self, size_of};
+use std::ops::Range;
 use std::sync::Arc;
+use std::time::Instant;
 
 pub trait IntoHetMorselIterator<'a> {
     /// The type of the iterator to produce.
@@ -28,53 +31,183 @@ pub trait IntoHetMorselIterator<'a> {
 
     /// Create an iterator from a value.
     ///
-    /// See module-level documentation for details.
+    /// The value is a tuple of one to eight mutable column slices. All
+    /// columns must have the same length.
     fn into_het_morsel_iter<'h: 'a>(
         &'a mut self,
         executor: &'h mut HetMorselExecutor,
     ) -> Self::Iter;
 }
 
-impl<'a, 'r, 's, R, S> IntoHetMorselIterator<'a> for (&'r mut [R], &'s mut [S])
-where
-    'r: 'a,
-    's: 'a,
-    R: 'a + Copy + DeviceCopy + Send + Sync,
-    S: 'a + Copy + DeviceCopy + Send + Sync,
-{
-    type Iter = HetMorselIterator2<'a, R, S>;
+/// A fixed set of equal-length columns that is split into morsels.
+///
+/// `MorselColumns` is implemented for tuples of one to eight column slices.
+/// CPU workers receive a morsel as a tuple of sub-slices, and GPU workers
+/// receive a morsel as a tuple of `LaunchableSlice`s.
+pub trait MorselColumns: Copy + Send + Sync {
+    /// The morsel type passed to GPU workers.
+    type GpuMorsel;
 
-    fn into_het_morsel_iter<'h: 'a>(
-        &'a mut self,
-        executor: &'h mut HetMorselExecutor,
-    ) -> Self::Iter {
-        assert_eq!(self.0.len(), self.1.len());
+    /// Returns the number of tuples, i.e., the length of each column.
+    fn len(&self) -> usize;
+
+    /// Returns the size of a tuple summed over all columns, in bytes.
+    fn tuple_bytes() -> usize;
+
+    /// Returns the morsel's sub-slices of all columns.
+    fn cpu_morsel(self, morsel: Range<usize>) -> Self;
+
+    /// Returns the morsel's launchable sub-slices of all columns.
+    fn gpu_morsel(self, morsel: Range<usize>) -> Self::GpuMorsel;
+}
+
+macro_rules! impl_morsel_columns {
+    ($(($T:ident, $col:ident, $life:lifetime)),+) => {
+        impl<'a, $($T),+> MorselColumns for ($(&'a [$T],)+)
+        where
+            $($T: DeviceCopy + Send + Sync),+
+        {
+            type GpuMorsel = ($(LaunchableSlice<'a, $T>,)+);
+
+            fn len(&self) -> usize {
+                let ($($col,)+) = self;
+                let lens = [$($col.len()),+];
+                debug_assert!(lens.iter().all(|&len| len == lens[0]));
+                lens[0]
+            }
+
+            fn tuple_bytes() -> usize {
+                0 $(+ size_of::<$T>())+
+            }
+
+            fn cpu_morsel(self, morsel: Range<usize>) -> Self {
+                let ($($col,)+) = self;
+                ($(&$col[morsel.clone()],)+)
+            }
+
+            fn gpu_morsel(self, morsel: Range<usize>) -> Self::GpuMorsel {
+                let ($($col,)+) = self;
+                ($($col[morsel.clone()].as_launchable_slice(),)+)
+            }
+        }
 
-        Self::Iter {
-            data: (self.0, self.1),
-            executor,
+        impl<'a, $($life,)+ $($T),+> IntoHetMorselIterator<'a> for ($(&$life mut [$T],)+)
+        where
+            $($life: 'a,)+
+            $($T: 'a + Copy + DeviceCopy + Send + Sync),+
+        {
+            type Iter = HetMorselIterator<'a, ($(&'a [$T],)+)>;
+
+            fn into_het_morsel_iter<'h: 'a>(
+                &'a mut self,
+                executor: &'h mut HetMorselExecutor,
+            ) -> Self::Iter {
+                let ($($col,)+) = self;
+                let lens = [$($col.len()),+];
+                assert!(
+                    lens.iter().all(|&len| len == lens[0]),
+                    "Columns must have the same length"
+                );
+
+                Self::Iter {
+                    data: ($(&**$col,)+),
+                    executor,
+                    node_ranges: None,
+                }
+            }
         }
-    }
+    };
 }
 
-pub struct HetMorselIterator2<'a, R, S> {
-    data: (&'a mut [R], &'a mut [S]),
+impl_morsel_columns!((A, a, 'ra));
+impl_morsel_columns!((A, a, 'ra), (B, b, 'rb));
+impl_morsel_columns!((A, a, 'ra), (B, b, 'rb), (C, c, 'rc));
+impl_morsel_columns!((A, a, 'ra), (B, b, 'rb), (C, c, 'rc), (D, d, 'rd));
+impl_morsel_columns!(
+    (A, a, 'ra),
+    (B, b, 'rb),
+    (C, c, 'rc),
+    (D, d, 'rd),
+    (E, e, 're)
+);
+impl_morsel_columns!(
+    (A, a, 'ra),
+    (B, b, 'rb),
+    (C, c, 'rc),
+    (D, d, 'rd),
+    (E, e, 're),
+    (F, f, 'rf)
+);
+impl_morsel_columns!(
+    (A, a, 'ra),
+    (B, b, 'rb),
+    (C, c, 'rc),
+    (D, d, 'rd),
+    (E, e, 're),
+    (F, f, 'rf),
+    (G, g, 'rg)
+);
+impl_morsel_columns!(
+    (A, a, 'ra),
+    (B, b, 'rb),
+    (C, c, 'rc),
+    (D, d, 'rd),
+    (E, e, 're),
+    (F, f, 'rf),
+    (G, g, 'rg),
+    (H, h, 'rh)
+);
+
+pub struct HetMorselIterator<'a, C> {
+    data: C,
     executor: &'a mut HetMorselExecutor,
+    node_ranges: Option<Vec<NodeRange>>,
 }
 
-pub struct StatefulHetMorselIterator2<'a, R, S, CWS: Send, GWS: Send> {
-    data: (&'a mut [R], &'a mut [S]),
+pub struct StatefulHetMorselIterator<'a, C, CWS: Send, GWS: Send> {
+    data: C,
     executor: &'a mut HetMorselExecutor,
+    node_ranges: Option<Vec<NodeRange>>,
     cpu_worker_states: Vec<CWS>,
     gpu_worker_states: Vec<GWS>,
 }
 
-impl<'a, R, S> HetMorselIterator2<'a, R, S> {
+/// A heterogeneous morsel iterator over two columns.
+pub type HetMorselIterator2<'a, R, S> = HetMorselIterator<'a, (&'a [R], &'a [S])>;
+
+/// A stateful heterogeneous morsel iterator over two columns.
+pub type StatefulHetMorselIterator2<'a, R, S, CWS, GWS> =
+    StatefulHetMorselIterator<'a, (&'a [R], &'a [S]), CWS, GWS>;
+
+impl<'a, C> HetMorselIterator<'a, C>
+where
+    C: MorselColumns,
+{
+    /// Enables NUMA-aware morsel dispatch.
+    ///
+    /// Workers prefer the morsels located on their own NUMA node, and steal
+    /// morsels from other nodes only after their local morsels are exhausted.
+    /// A worker's node is the node of the CPU core that its thread runs on.
+    ///
+    /// The node ranges are specified in tuples, e.g., from
+    /// `DistributedNumaMemory::node_ranges` or `numa::node_ranges`, and must
+    /// cover all tuples. All columns are assumed to have the same placement.
+    ///
+    /// The number of local and remote morsels per worker are available from
+    /// `HetMorselExecutor::morsel_counts` after each `fold`.
+    pub fn numa_aware(mut self, node_ranges: Vec<NodeRange>) -> Self {
+        self.node_ranges = Some(node_ranges);
+        self
+    }
+
+    /// Initializes a state for each CPU and GPU worker.
+    ///
+    /// Returns the first error if the initialization of any worker fails.
     pub fn with_state<CpuF, GpuF, CWS, GWS>(
         self,
         cpu_init: CpuF,
         gpu_init: GpuF,
-    ) -> Result<StatefulHetMorselIterator2<'a, R, S, CWS, GWS>>
+    ) -> Result<StatefulHetMorselIterator<'a, C, CWS, GWS>>
     where
         CpuF: Fn(u16) -> Result<CWS> + Send + Sync,
         GpuF: Fn(u16, &Stream) -> Result<GWS> + Send + Sync,
@@ -88,57 +221,54 @@ impl<'a, R, S> HetMorselIterator2<'a, R, S> {
         let gpu_workers = self.executor.gpu_workers;
         let streams = &mut self.executor.streams;
 
-        let mut cpu_uninit_states: Vec<MaybeUninit<CWS>> =
-            (0..cpu_workers).map(|_| MaybeUninit::uninit()).collect();
-        let mut gpu_uninit_states: Vec<MaybeUninit<GWS>> =
-            (0..gpu_workers).map(|_| MaybeUninit::uninit()).collect();
+        let mut cpu_init_results: Vec<Option<Result<CWS>>> =
+            (0..cpu_workers).map(|_| None).collect();
+        let mut gpu_init_results: Vec<Option<Result<GWS>>> =
+            (0..gpu_workers).map(|_| None).collect();
 
-        let cus = &mut cpu_uninit_states;
-        let gus = &mut gpu_uninit_states;
+        let cir = &mut cpu_init_results;
+        let gir = &mut gpu_init_results;
 
         cpu_thread_pool.scope(move |cpu_scope| {
             gpu_thread_pool.scope(move |gpu_scope| {
                 let cpu_af = Arc::new(cpu_init);
                 let gpu_af = Arc::new(gpu_init);
 
-                for (thread_id, uninit_state) in (0..).zip(cus.iter_mut()) {
+                for (thread_id, init_result) in (0..).zip(cir.iter_mut()) {
                     let af = cpu_af.clone();
 
                     cpu_scope.spawn(move |_| {
-                        let state: CWS = af(thread_id).expect("Failed to run CPU worker");
-                        *uninit_state = MaybeUninit::new(state);
+                        *init_result = Some(af(thread_id));
                     });
                 }
 
-                for ((thread_id, uninit_state), stream) in
-                    (0..).zip(gus.iter_mut()).zip(streams.iter_mut())
+                for ((thread_id, init_result), stream) in
+                    (0..).zip(gir.iter_mut()).zip(streams.iter_mut())
                 {
                     let af = gpu_af.clone();
 
                     gpu_scope.spawn(move |_| {
-                        let state: GWS = af(thread_id, stream).expect("Failed to run GPU worker");
-                        *uninit_state = MaybeUninit::new(state);
+                        *init_result = Some(af(thread_id, stream));
                     });
                 }
             });
         });
 
-        let cpu_worker_states: Vec<CWS> = unsafe {
-            cpu_uninit_states
-                .into_iter()
-                .map(|state| state.assume_init())
-                .collect()
-        };
-        let gpu_worker_states: Vec<GWS> = unsafe {
-            gpu_uninit_states
-                .into_iter()
-                .map(|state| state.assume_init())
-                .collect()
-        };
-
-        Ok(StatefulHetMorselIterator2 {
+        // Return the first error. The states of successfully initialized
+        // workers are dropped on the calling thread.
+        let cpu_worker_states = cpu_init_results
+            .into_iter()
+            .map(|init_result| init_result.expect("CPU worker didn't run"))
+            .collect::<Result<Vec<CWS>>>()?;
+        let gpu_worker_states = gpu_init_results
+            .into_iter()
+            .map(|init_result| init_result.expect("GPU worker didn't run"))
+            .collect::<Result<Vec<GWS>>>()?;
+
+        Ok(StatefulHetMorselIterator {
             data: self.data,
             executor: self.executor,
+            node_ranges: self.node_ranges,
             cpu_worker_states,
             gpu_worker_states,
         })
@@ -146,49 +276,79 @@ impl<'a, R, S> HetMorselIterator2<'a, R, S> {
 
     pub fn fold<CpuF, GpuF>(&mut self, cpu_f: CpuF, gpu_f: GpuF) -> Result<()>
     where
-        R: Copy + DeviceCopy + Send + Sync,
-        S: Copy + DeviceCopy + Send + Sync,
-        CpuF: Fn((&[R], &[S])) -> Result<()> + Send + Sync,
-        GpuF: Fn((LaunchableSlice<'_, R>, LaunchableSlice<'_, S>), &Stream) -> Result<()>
-            + Send
-            + Sync,
+        CpuF: Fn(C) -> Result<()> + Send + Sync,
+        GpuF: Fn(C::GpuMorsel, &Stream) -> Result<()> + Send + Sync,
+    {
+        self.fold_with_stats(cpu_f, gpu_f).map(|_| ())
+    }
+
+    /// Processes all morsels like `fold`, and returns the workers' execution
+    /// statistics.
+    pub fn fold_with_stats<CpuF, GpuF>(&mut self, cpu_f: CpuF, gpu_f: GpuF) -> Result<ExecutorStats>
+    where
+        CpuF: Fn(C) -> Result<()> + Send + Sync,
+        GpuF: Fn(C::GpuMorsel, &Stream) -> Result<()> + Send + Sync,
     {
         // Create dummy state
         let cpu_worker_states = vec![(); self.executor.cpu_workers];
         let gpu_worker_states = vec![(); self.executor.gpu_workers];
 
-        let r: &mut [R] = self.data.0;
-        let s: &mut [S] = self.data.1;
-        let data = (r, s);
-
-        let mut iter = StatefulHetMorselIterator2 {
-            data,
+        let mut iter = StatefulHetMorselIterator {
+            data: self.data,
             executor: self.executor,
+            node_ranges: self.node_ranges.clone(),
             cpu_worker_states,
             gpu_worker_states,
         };
 
-        // Wrap StatefulHetMorselIterator2::fold with dummy state
-        iter.fold(|data, _| cpu_f(data), |data, _, stream| gpu_f(data, stream))
+        // Wrap StatefulHetMorselIterator::fold_with_stats with dummy state
+        iter.fold_with_stats(|data, _| cpu_f(data), |data, _, stream| gpu_f(data, stream))
     }
 }
 
-impl<'a, R, S, CWS: Send, GWS: Send> StatefulHetMorselIterator2<'a, R, S, CWS, GWS> {
+impl<'a, C, CWS: Send, GWS: Send> StatefulHetMorselIterator<'a, C, CWS, GWS>
+where
+    C: MorselColumns,
+{
+    /// Processes all morsels with the CPU and GPU workers.
+    ///
+    /// If a worker fails, the dispatcher stops handing out morsels. The first
+    /// error is returned after all workers have finished their current morsel.
     pub fn fold<CpuF, GpuF>(&mut self, cpu_f: CpuF, gpu_f: GpuF) -> Result<()>
     where
-        R: Copy + DeviceCopy + Send + Sync,
-        S: Copy + DeviceCopy + Send + Sync,
-        CpuF: Fn((&[R], &[S]), &mut CWS) -> Result<()> + Send + Sync,
-        GpuF: Fn((LaunchableSlice<'_, R>, LaunchableSlice<'_, S>), &mut GWS, &Stream) -> Result<()>
-            + Send
-            + Sync,
+        CpuF: Fn(C, &mut CWS) -> Result<()> + Send + Sync,
+        GpuF: Fn(C::GpuMorsel, &mut GWS, &Stream) -> Result<()> + Send + Sync,
+    {
+        self.fold_with_stats(cpu_f, gpu_f).map(|_| ())
+    }
+
+    /// Processes all morsels like `fold`, and returns the workers' execution
+    /// statistics.
+    pub fn fold_with_stats<CpuF, GpuF>(&mut self, cpu_f: CpuF, gpu_f: GpuF) -> Result<ExecutorStats>
+    where
+        CpuF: Fn(C, &mut CWS) -> Result<()> + Send + Sync,
+        GpuF: Fn(C::GpuMorsel, &mut GWS, &Stream) -> Result<()> + Send + Sync,
     {
-        let cpu_morsel_len =
-            self.executor.morsel_spec.cpu_morsel_bytes / (size_of::<R>() + size_of::<S>());
-        let gpu_morsel_len =
-            self.executor.morsel_spec.gpu_morsel_bytes / (size_of::<R>() + size_of::<S>());
-        let dispatcher = MorselDispatcher::new(self.data.0.len(), cpu_morsel_len, gpu_morsel_len);
+        let fold_timer = Instant::now();
+
+        let cpu_morsel_len = self.executor.morsel_spec.cpu_morsel_bytes / C::tuple_bytes();
+        let gpu_morsel_len = self.executor.morsel_spec.gpu_morsel_bytes / C::tuple_bytes();
+        let dispatcher = match self.node_ranges {
+            Some(ref node_ranges) => MorselDispatcher::with_node_ranges(
+                self.data.len(),
+                node_ranges,
+                cpu_morsel_len,
+                gpu_morsel_len,
+            )?,
+            None => MorselDispatcher::new(self.data.len(), cpu_morsel_len, gpu_morsel_len),
+        };
         let dispatcher_ref = &dispatcher;
+        let numa_aware = self.node_ranges.is_some();
+
+        let mut cpu_worker_stats = vec![WorkerStats::default(); self.cpu_worker_states.len()];
+        let mut gpu_worker_stats = vec![WorkerStats::default(); self.gpu_worker_states.len()];
+        let cpu_stats = &mut cpu_worker_stats;
+        let gpu_stats = &mut gpu_worker_stats;
 
         let cpu_thread_pool = &mut self.executor.cpu_thread_pool;
         let gpu_thread_pool = &mut self.executor.gpu_thread_pool;
@@ -197,81 +357,139 @@ impl<'a, R, S, CWS: Send, GWS: Send> StatefulHetMorselIterator2<'a, R, S, CWS, G
         let gpu_worker_states = &mut self.gpu_worker_states;
         let streams = &mut self.executor.streams;
 
-        let ro_data = (self.data.0.as_ref(), self.data.1.as_ref());
+        let data = self.data;
 
         cpu_thread_pool.scope(move |cpu_scope| {
             gpu_thread_pool.scope(move |gpu_scope| {
                 let cpu_af = Arc::new(cpu_f);
                 let gpu_af = Arc::new(gpu_f);
 
-                for state in cpu_worker_states.iter_mut() {
+                for (state, stats) in cpu_worker_states.iter_mut().zip(cpu_stats.iter_mut()) {
                     let af = cpu_af.clone();
 
                     cpu_scope.spawn(move |_| {
-                        Self::cpu_worker(dispatcher_ref, ro_data, af, state)
-                            .expect("Failed to run CPU worker");
+                        if let Err(error) =
+                            Self::cpu_worker(dispatcher_ref, numa_aware, data, af, state, stats)
+                        {
+                            dispatcher_ref.cancel(error);
+                        }
                     });
                 }
 
-                for (state, stream) in gpu_worker_states.iter_mut().zip(streams.iter_mut()) {
+                for ((state, stream), stats) in gpu_worker_states
+                    .iter_mut()
+                    .zip(streams.iter_mut())
+                    .zip(gpu_stats.iter_mut())
+                {
                     let af = gpu_af.clone();
 
                     gpu_scope.spawn(move |_| {
-                        Self::gpu_worker(dispatcher_ref, ro_data, af, state, stream)
-                            .expect("Failed to run GPU worker");
+                        if let Err(error) = Self::gpu_worker(
+                            dispatcher_ref,
+                            numa_aware,
+                            data,
+                            af,
+                            state,
+                            stream,
+                            stats,
+                        ) {
+                            dispatcher_ref.cancel(error);
+                        }
                     });
                 }
             });
         });
 
-        Ok(())
+        let wall_time = fold_timer.elapsed();
+        for stats in cpu_worker_stats
+            .iter_mut()
+            .chain(gpu_worker_stats.iter_mut())
+        {
+            stats.idle_time = wall_time.saturating_sub(stats.busy_time);
+        }
+
+        let stats = ExecutorStats {
+            cpu_workers: cpu_worker_stats,
+            gpu_workers: gpu_worker_stats,
+            wall_time,
+        };
+        self.executor.morsel_counts = stats.morsel_counts();
+
+        dispatcher.into_result().map(|_| stats)
+    }
+
+    /// Returns the NUMA node of the calling worker thread if NUMA-aware
+    /// dispatch is enabled.
+    fn worker_node(numa_aware: bool) -> Result<Option<u16>> {
+        if numa_aware {
+            Ok(Some(numa::node_of_cpu(CpuAffinity::get_cpu()?)?))
+        } else {
+            Ok(None)
+        }
     }
 
     fn cpu_worker<F>(
         dispatcher: &MorselDispatcher,
-        data: (&[R], &[S]),
+        numa_aware: bool,
+        data: C,
         f: Arc<F>,
         state: &mut CWS,
+        stats: &mut WorkerStats,
     ) -> Result<()>
     where
-        F: Fn((&[R], &[S]), &mut CWS) -> Result<()>,
+        F: Fn(C, &mut CWS) -> Result<()>,
     {
-        for morsel in dispatcher.cpu_iter() {
-            f((&data.0[morsel.clone()], &data.1[morsel.clone()]), state)?;
-        }
+        let mut morsels = dispatcher.cpu_iter(Self::worker_node(numa_aware)?);
+        let result = morsels.by_ref().try_for_each(|morsel| {
+            let tuples = morsel.len();
+            let timer = Instant::now();
+            let result = f(data.cpu_morsel(morsel), state);
+            stats.busy_time += timer.elapsed();
+            stats.morsels += 1;
+            stats.tuples += tuples;
+            result
+        });
+        stats.morsel_counts = morsels.counts();
 
-        Ok(())
+        result
     }
 
     fn gpu_worker<F>(
         dispatcher: &MorselDispatcher,
-        data: (&[R], &[S]),
+        numa_aware: bool,
+        data: C,
         f: Arc<F>,
         state: &mut GWS,
         stream: &Stream,
+        stats: &mut WorkerStats,
     ) -> Result<()>
     where
-        F: Fn((LaunchableSlice<'_, R>, LaunchableSlice<'_, S>), &mut GWS, &Stream) -> Result<()>,
+        F: Fn(C::GpuMorsel, &mut GWS, &Stream) -> Result<()>,
     {
-        for morsel in dispatcher.gpu_iter() {
-            f(
-                (
-                    data.0[morsel.clone()].as_launchable_slice(),
-                    data.1[morsel.clone()].as_launchable_slice(),
-                ),
-                state,
-                stream,
-            )?
-        }
+        let mut morsels = dispatcher.gpu_iter(Self::worker_node(numa_aware)?);
+        let result = morsels.by_ref().try_for_each(|morsel| {
+            let tuples = morsel.len();
+            let timer = Instant::now();
+            let result = f(data.gpu_morsel(morsel), state, stream);
+            stats.busy_time += timer.elapsed();
+            stats.morsels += 1;
+            stats.tuples += tuples;
+            result
+        });
+        stats.morsel_counts = morsels.counts();
+        result?;
 
         // synchronize to not queue up _all_ morsels on the stream
+        let timer = Instant::now();
+        #[cfg(feature = "cuda")]
         stream.synchronize()?;
+        stats.busy_time += timer.elapsed();
 
         Ok(())
     }
 }
 
-impl<'a, R, S, CWS: Send, GWS: Send> Drop for StatefulHetMorselIterator2<'a, R, S, CWS, GWS> {
+impl<'a, C, CWS: Send, GWS: Send> Drop for StatefulHetMorselIterator<'a, C, CWS, GWS> {
     fn drop(&mut self) {
         let executor = &mut self.executor;
 
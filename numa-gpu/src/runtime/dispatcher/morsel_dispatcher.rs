@@ -12,41 +12,153 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use crate::error::{Error, ErrorKind, Result};
+use crate::runtime::numa::NodeRange;
 use std::cmp;
 use std::iter::Iterator;
 use std::ops::Range;
-use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Mutex, PoisonError};
 
-pub(super) struct MorselDispatcher {
+/// The number of morsels that a worker processed from local and remote NUMA
+/// nodes.
+///
+/// Morsels are only counted with NUMA-aware dispatch, i.e., if the data's node
+/// ranges are known.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct MorselCounts {
+    /// The number of morsels located on the worker's NUMA node
+    pub local: usize,
+
+    /// The number of morsels stolen from other NUMA nodes
+    pub remote: usize,
+}
+
+/// The morsel counts of all workers in the last `fold`.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct WorkerMorselCounts {
+    /// The counts of the CPU workers, in the order of their thread IDs
+    pub cpu_workers: Vec<MorselCounts>,
+
+    /// The counts of the GPU workers, in the order of their GPU IDs
+    pub gpu_workers: Vec<MorselCounts>,
+}
+
+/// A contiguous range of the data from which morsels are handed out.
+struct Partition {
+    node: Option<u16>,
+    end: usize,
     offset: AtomicUsize,
-    data_len: usize,
+}
+
+pub(super) struct MorselDispatcher {
+    partitions: Vec<Partition>,
+    cancelled: AtomicBool,
+    first_error: Mutex<Option<Error>>,
     cpu_morsel_len: usize,
     gpu_morsel_len: usize,
 }
 
 impl MorselDispatcher {
     pub(super) fn new(data_len: usize, cpu_morsel_len: usize, gpu_morsel_len: usize) -> Self {
-        let offset = AtomicUsize::new(0);
+        let partition = Partition {
+            node: None,
+            end: data_len,
+            offset: AtomicUsize::new(0),
+        };
+
+        Self::with_partitions(vec![partition], cpu_morsel_len, gpu_morsel_len)
+    }
+
+    /// Creates a dispatcher that prefers morsels local to the worker.
+    ///
+    /// The node ranges must cover `0..data_len` without gaps or overlaps, in
+    /// ascending order.
+    pub(super) fn with_node_ranges(
+        data_len: usize,
+        node_ranges: &[NodeRange],
+        cpu_morsel_len: usize,
+        gpu_morsel_len: usize,
+    ) -> Result<Self> {
+        let covered = node_ranges
+            .iter()
+            .try_fold(0, |next, NodeRange { range, .. }| {
+                if range.start == next && range.start <= range.end {
+                    Some(range.end)
+                } else {
+                    None
+                }
+            });
+        if covered != Some(data_len) {
+            Err(ErrorKind::InvalidArgument(format!(
+                "The node ranges must cover the data length {} in ascending order",
+                data_len
+            )))?;
+        }
+
+        let partitions = node_ranges
+            .iter()
+            .map(|NodeRange { node, range }| Partition {
+                node: Some(*node),
+                end: range.end,
+                offset: AtomicUsize::new(range.start),
+            })
+            .collect();
+
+        Ok(Self::with_partitions(
+            partitions,
+            cpu_morsel_len,
+            gpu_morsel_len,
+        ))
+    }
 
+    fn with_partitions(
+        partitions: Vec<Partition>,
+        cpu_morsel_len: usize,
+        gpu_morsel_len: usize,
+    ) -> Self {
         Self {
-            offset,
-            data_len,
+            partitions,
+            cancelled: AtomicBool::new(false),
+            first_error: Mutex::new(None),
             cpu_morsel_len,
             gpu_morsel_len,
         }
     }
 
-    pub(super) fn cpu_iter(&self) -> MorselDispatcherIter<'_> {
-        MorselDispatcherIter {
-            dispatcher: &self,
-            morsel_len: self.cpu_morsel_len,
+    pub(super) fn cpu_iter(&self, worker_node: Option<u16>) -> MorselDispatcherIter<'_> {
+        MorselDispatcherIter::new(self, self.cpu_morsel_len, worker_node)
+    }
+
+    pub(super) fn gpu_iter(&self, worker_node: Option<u16>) -> MorselDispatcherIter<'_> {
+        MorselDispatcherIter::new(self, self.gpu_morsel_len, worker_node)
+    }
+
+    /// Cancels the dispatch of further morsels due to a failed worker.
+    ///
+    /// Only the first error is kept, because subsequent errors might be
+    /// caused by the cancellation.
+    pub(super) fn cancel(&self, error: Error) {
+        self.cancelled.store(true, Ordering::SeqCst);
+
+        let mut first_error = self
+            .first_error
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        if first_error.is_none() {
+            *first_error = Some(error);
         }
     }
 
-    pub(super) fn gpu_iter(&self) -> MorselDispatcherIter<'_> {
-        MorselDispatcherIter {
-            dispatcher: &self,
-            morsel_len: self.gpu_morsel_len,
+    /// Returns the first worker error, if any worker failed.
+    pub(super) fn into_result(self) -> Result<()> {
+        match self
+            .first_error
+            .into_inner()
+            .unwrap_or_else(PoisonError::into_inner)
+        {
+            Some(error) => Err(error),
+            None => Ok(()),
         }
     }
 }
@@ -54,6 +166,44 @@ impl MorselDispatcher {
 pub(super) struct MorselDispatcherIter<'a> {
     dispatcher: &'a MorselDispatcher,
     morsel_len: usize,
+    worker_node: Option<u16>,
+    partition_order: Vec<usize>,
+    position: usize,
+    counts: MorselCounts,
+}
+
+impl<'a> MorselDispatcherIter<'a> {
+    fn new(dispatcher: &'a MorselDispatcher, morsel_len: usize, worker_node: Option<u16>) -> Self {
+        let partitions = &dispatcher.partitions;
+        let is_local = |p: &Partition| p.node.is_some() && p.node == worker_node;
+
+        // Visit the local partitions first. Then, steal from the remote
+        // partitions, starting after the local ones to spread out the workers
+        // of different nodes.
+        let first_local = partitions.iter().position(is_local).unwrap_or(0);
+        let partition_order = (0..partitions.len())
+            .filter(|&i| is_local(&partitions[i]))
+            .chain(
+                (0..partitions.len())
+                    .map(|i| (first_local + i) % partitions.len())
+                    .filter(|&i| !is_local(&partitions[i])),
+            )
+            .collect();
+
+        Self {
+            dispatcher,
+            morsel_len,
+            worker_node,
+            partition_order,
+            position: 0,
+            counts: MorselCounts::default(),
+        }
+    }
+
+    /// Returns the number of local and remote morsels handed out so far.
+    pub(super) fn counts(&self) -> MorselCounts {
+        self.counts
+    }
 }
 
 impl<'a> Iterator for MorselDispatcherIter<'a> {
@@ -61,28 +211,172 @@ impl<'a> Iterator for MorselDispatcherIter<'a> {
 
     #[inline]
     fn next(&mut self) -> Option<Self::Item> {
-        let morsel = self
-            .dispatcher
-            .offset
-            .fetch_add(self.morsel_len, Ordering::SeqCst);
-        if morsel >= self.dispatcher.data_len {
-            return None;
+        while let Some(&index) = self.partition_order.get(self.position) {
+            if self.dispatcher.cancelled.load(Ordering::SeqCst) {
+                return None;
+            }
+
+            let partition = &self.dispatcher.partitions[index];
+            let morsel = partition
+                .offset
+                .fetch_add(self.morsel_len, Ordering::SeqCst);
+            if morsel >= partition.end {
+                // The partition is exhausted, continue with the next one
+                self.position += 1;
+                continue;
+            }
+            let morsel_len = cmp::min(partition.end - morsel, self.morsel_len);
+
+            if let Some(node) = partition.node {
+                if Some(node) == self.worker_node {
+                    self.counts.local += 1;
+                } else {
+                    self.counts.remote += 1;
+                }
+            }
+
+            return Some(Range {
+                start: morsel,
+                end: morsel + morsel_len,
+            });
         }
-        let morsel_len = cmp::min(self.dispatcher.data_len - morsel, self.morsel_len);
 
-        Some(Range {
-            start: morsel,
-            end: morsel + morsel_len,
-        })
+        None
     }
 
     #[inline]
     fn size_hint(&self) -> (usize, Option<usize>) {
-        let offset = self.dispatcher.offset.load(Ordering::Relaxed);
-        let remaining_morsels = (self.dispatcher.data_len - offset) / self.morsel_len;
+        if self.dispatcher.cancelled.load(Ordering::Relaxed) {
+            return (0, Some(0));
+        }
+
+        let remaining_morsels = self.partition_order[self.position..]
+            .iter()
+            .map(|&index| {
+                let partition = &self.dispatcher.partitions[index];
+                let offset = partition.offset.load(Ordering::Relaxed);
+                (partition.end.saturating_sub(offset) + self.morsel_len - 1) / self.morsel_len
+            })
+            .sum();
 
         // Lower bound is zero, because we don't know how many morsels the
         // calling worker will receive
         (0, Some(remaining_morsels))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn node_ranges() -> Vec<NodeRange> {
+        vec![
+            NodeRange {
+                node: 0,
+                range: 0..100,
+            },
+            NodeRange {
+                node: 1,
+                range: 100..150,
+            },
+        ]
+    }
+
+    #[test]
+    fn local_morsels_before_remote_morsels() -> Result<()> {
+        let dispatcher = MorselDispatcher::with_node_ranges(150, &node_ranges(), 30, 30)?;
+
+        let mut morsels = dispatcher.cpu_iter(Some(1));
+        let dispatched: Vec<_> = morsels.by_ref().collect();
+        assert_eq!(
+            dispatched,
+            vec![100..130, 130..150, 0..30, 30..60, 60..90, 90..100]
+        );
+        assert_eq!(
+            morsels.counts(),
+            MorselCounts {
+                local: 2,
+                remote: 4
+            }
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn workers_share_node_ranges() -> Result<()> {
+        let dispatcher = MorselDispatcher::with_node_ranges(150, &node_ranges(), 40, 40)?;
+
+        let mut node_0 = dispatcher.cpu_iter(Some(0));
+        let mut node_1 = dispatcher.gpu_iter(Some(1));
+        assert_eq!(node_0.next(), Some(0..40));
+        assert_eq!(node_1.next(), Some(100..140));
+        assert_eq!(node_1.next(), Some(140..150));
+
+        // Node 1 is exhausted, thus steal from node 0
+        assert_eq!(node_1.next(), Some(40..80));
+        assert_eq!(node_0.next(), Some(80..100));
+        assert_eq!(node_0.next(), None);
+        assert_eq!(node_1.next(), None);
+
+        assert_eq!(
+            node_0.counts(),
+            MorselCounts {
+                local: 2,
+                remote: 0
+            }
+        );
+        assert_eq!(
+            node_1.counts(),
+            MorselCounts {
+                local: 2,
+                remote: 1
+            }
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn without_node_ranges_morsels_are_not_counted() {
+        let dispatcher = MorselDispatcher::new(100, 30, 30);
+
+        let mut morsels = dispatcher.cpu_iter(None);
+        assert_eq!(morsels.by_ref().count(), 4);
+        assert_eq!(morsels.counts(), MorselCounts::default());
+    }
+
+    #[test]
+    fn node_ranges_must_cover_data() {
+        let gap = vec![
+            NodeRange {
+                node: 0,
+                range: 0..50,
+            },
+            NodeRange {
+                node: 1,
+                range: 60..150,
+            },
+        ];
+
+        for (data_len, node_ranges) in [(150, gap), (200, node_ranges())].iter() {
+            let result = MorselDispatcher::with_node_ranges(*data_len, node_ranges, 10, 10);
+            assert!(matches!(
+                result.map(|_| ()).map_err(|e| e.0),
+                Err(ErrorKind::InvalidArgument(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn cancel_stops_dispatch() {
+        let dispatcher = MorselDispatcher::new(100, 10, 10);
+
+        let mut morsels = dispatcher.cpu_iter(None);
+        assert_eq!(morsels.next(), Some(0..10));
+
+        dispatcher.cancel(ErrorKind::RuntimeError("Failed".to_string()).into());
+        assert_eq!(morsels.next(), None);
+        assert!(dispatcher.into_result().is_err());
+    }
+}
@@ -0,0 +1,147 @@
+// Copyright 2022 Clemens Lutz
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::{MorselCounts, WorkerMorselCounts};
+use std::time::Duration;
+
+/// The execution statistics of a single worker.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct WorkerStats {
+    /// The number of processed morsels
+    pub morsels: usize,
+
+    /// The number of processed tuples
+    pub tuples: usize,
+
+    /// The time spent processing morsels
+    ///
+    /// For GPU workers, this includes the time to synchronize the stream after
+    /// the last morsel.
+    pub busy_time: Duration,
+
+    /// The time during the `fold` that the worker didn't process morsels
+    ///
+    /// This includes waiting for a thread, for the dispatcher, and for the
+    /// other workers to finish.
+    pub idle_time: Duration,
+
+    /// The local and remote morsels with NUMA-aware dispatch
+    pub morsel_counts: MorselCounts,
+}
+
+/// The execution statistics of all workers in a `fold`.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct ExecutorStats {
+    /// The statistics of the CPU workers, in the order of their thread IDs
+    pub cpu_workers: Vec<WorkerStats>,
+
+    /// The statistics of the GPU workers, in the order of their GPU IDs
+    pub gpu_workers: Vec<WorkerStats>,
+
+    /// The wall-clock time of the `fold`
+    pub wall_time: Duration,
+}
+
+impl ExecutorStats {
+    /// Returns the number of tuples processed by all CPU workers.
+    pub fn cpu_tuples(&self) -> usize {
+        self.cpu_workers.iter().map(|w| w.tuples).sum()
+    }
+
+    /// Returns the number of tuples processed by all GPU workers.
+    pub fn gpu_tuples(&self) -> usize {
+        self.gpu_workers.iter().map(|w| w.tuples).sum()
+    }
+
+    /// Returns the busy time summed over all CPU workers.
+    pub fn cpu_busy_time(&self) -> Duration {
+        self.cpu_workers.iter().map(|w| w.busy_time).sum()
+    }
+
+    /// Returns the busy time summed over all GPU workers.
+    pub fn gpu_busy_time(&self) -> Duration {
+        self.gpu_workers.iter().map(|w| w.busy_time).sum()
+    }
+
+    /// Returns the idle time summed over all CPU workers.
+    pub fn cpu_idle_time(&self) -> Duration {
+        self.cpu_workers.iter().map(|w| w.idle_time).sum()
+    }
+
+    /// Returns the idle time summed over all GPU workers.
+    pub fn gpu_idle_time(&self) -> Duration {
+        self.gpu_workers.iter().map(|w| w.idle_time).sum()
+    }
+
+    /// Returns the fraction of tuples processed by the CPU workers.
+    ///
+    /// Returns `None` if no tuples were processed.
+    pub fn cpu_work_fraction(&self) -> Option<f64> {
+        let total = self.cpu_tuples() + self.gpu_tuples();
+        if total == 0 {
+            None
+        } else {
+            Some(self.cpu_tuples() as f64 / total as f64)
+        }
+    }
+
+    /// Returns the fraction of tuples processed by the GPU workers.
+    ///
+    /// Returns `None` if no tuples were processed.
+    pub fn gpu_work_fraction(&self) -> Option<f64> {
+        self.cpu_work_fraction().map(|cpu| 1.0 - cpu)
+    }
+
+    /// Returns the local and remote morsels of all workers.
+    pub fn morsel_counts(&self) -> WorkerMorselCounts {
+        WorkerMorselCounts {
+            cpu_workers: self.cpu_workers.iter().map(|w| w.morsel_counts).collect(),
+            gpu_workers: self.gpu_workers.iter().map(|w| w.morsel_counts).collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn work_split() {
+        let worker = |tuples| WorkerStats {
+            morsels: 1,
+            tuples,
+            busy_time: Duration::from_millis(tuples as u64),
+            ..WorkerStats::default()
+        };
+        let stats = ExecutorStats {
+            cpu_workers: vec![worker(10), worker(20)],
+            gpu_workers: vec![worker(90)],
+            wall_time: Duration::from_millis(100),
+        };
+
+        assert_eq!(stats.cpu_tuples(), 30);
+        assert_eq!(stats.gpu_tuples(), 90);
+        assert_eq!(stats.cpu_busy_time(), Duration::from_millis(30));
+        assert_eq!(stats.cpu_work_fraction(), Some(0.25));
+        assert_eq!(stats.gpu_work_fraction(), Some(0.75));
+    }
+
+    #[test]
+    fn work_split_without_tuples() {
+        let stats = ExecutorStats::default();
+
+        assert_eq!(stats.cpu_work_fraction(), None);
+        assert_eq!(stats.gpu_work_fraction(), None);
+    }
+}
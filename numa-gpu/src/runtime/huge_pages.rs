@@ -0,0 +1,427 @@
+// Copyright 2022 Clemens Lutz
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Checks the availability of huge pages before and after an allocation.
+//!
+//! HugeTLB pages must be reserved by the system administrator, and transparent
+//! huge pages can be disabled in the kernel. Before allocating NUMA memory,
+//! the requested page type is checked against the free HugeTLB pages of each
+//! NUMA node and the THP settings. If the page type is unavailable, the
+//! allocation falls back to another page type according to the process-wide
+//! `HugePageFallback` policy.
+//!
+//! After an allocation, `huge_page_bytes` reports how much of a memory region
+//! is actually backed by huge pages. This is the only way to verify that
+//! transparent huge pages were allocated, because `madvise` is only a hint.
+
+use super::hw_info::ProcessorCache;
+use super::numa::{NodeLen, PageType};
+use crate::error::{ErrorKind, Result, ResultExt};
+use std::cmp;
+use std::fs;
+use std::mem::size_of;
+use std::path::Path;
+use std::sync::atomic::{AtomicU8, Ordering};
+
+/// The process-wide fallback policy
+static FALLBACK: AtomicU8 = AtomicU8::new(HugePageFallback::Fail as u8);
+
+/// Specifies what to allocate if the requested huge pages are unavailable
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+#[repr(u8)]
+pub enum HugePageFallback {
+    /// Fail the allocation with a `NoHugePages` error
+    Fail,
+
+    /// Fall back to transparent huge pages
+    ///
+    /// Fails if transparent huge pages are disabled as well.
+    TransparentHuge,
+
+    /// Fall back to small pages
+    Small,
+}
+
+impl Default for HugePageFallback {
+    fn default() -> Self {
+        HugePageFallback::Fail
+    }
+}
+
+/// Sets the fallback policy of all subsequent NUMA memory allocations.
+///
+/// The default policy is `HugePageFallback::Fail`.
+pub fn set_fallback(fallback: HugePageFallback) {
+    FALLBACK.store(fallback as u8, Ordering::SeqCst);
+}
+
+/// Returns the current fallback policy.
+pub fn fallback() -> HugePageFallback {
+    match FALLBACK.load(Ordering::SeqCst) {
+        x if x == HugePageFallback::TransparentHuge as u8 => HugePageFallback::TransparentHuge,
+        x if x == HugePageFallback::Small as u8 => HugePageFallback::Small,
+        _ => HugePageFallback::Fail,
+    }
+}
+
+/// The system-wide transparent huge page setting
+///
+/// See `/sys/kernel/mm/transparent_hugepage/enabled`.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum ThpMode {
+    /// Huge pages are used for all anonymous memory
+    Always,
+
+    /// Huge pages are only used for memory regions advised with `madvise`
+    Madvise,
+
+    /// Transparent huge pages are disabled
+    Never,
+}
+
+/// Reads the transparent huge page setting of the kernel.
+///
+/// Returns `ThpMode::Never` if the kernel doesn't support transparent huge
+/// pages.
+pub fn thp_mode() -> Result<ThpMode> {
+    let path = Path::new("/sys/kernel/mm/transparent_hugepage/enabled");
+    if !path.exists() {
+        return Ok(ThpMode::Never);
+    }
+
+    parse_thp_mode(&read_sysfs(path)?)
+}
+
+/// Parses the selected mode, which the kernel marks with brackets, e.g.:
+/// `always [madvise] never`.
+fn parse_thp_mode(s: &str) -> Result<ThpMode> {
+    let selected = s
+        .split_whitespace()
+        .find(|mode| mode.starts_with('['))
+        .map(|mode| mode.trim_matches(|c| c == '[' || c == ']'));
+
+    match selected {
+        Some("always") => Ok(ThpMode::Always),
+        Some("madvise") => Ok(ThpMode::Madvise),
+        Some("never") => Ok(ThpMode::Never),
+        _ => Err(ErrorKind::RuntimeError(format!(
+            "Failed to parse the THP mode: {}",
+            s.trim()
+        )))?,
+    }
+}
+
+/// Returns the number of free HugeTLB pages of the page type.
+///
+/// If `node` is specified, only the free pages of that NUMA node are counted.
+/// Returns zero if the kernel doesn't support the page size.
+pub fn free_huge_pages(page_type: PageType, node: Option<u16>) -> Result<usize> {
+    let dir = match node {
+        Some(node) => format!(
+            "/sys/devices/system/node/node{}/hugepages/{}",
+            node,
+            hugepages_dir_name(page_type)?
+        ),
+        None => format!(
+            "/sys/kernel/mm/hugepages/{}",
+            hugepages_dir_name(page_type)?
+        ),
+    };
+
+    read_counter(Path::new(&dir).join("free_hugepages").as_path())
+}
+
+/// Returns the number of HugeTLB pages that can be allocated on the NUMA node.
+///
+/// These are the free pages of the node, plus the surplus pages that the
+/// kernel may allocate on demand (see `nr_overcommit_hugepages`). Surplus
+/// pages are limited system-wide, and can fail to allocate if the node runs
+/// out of memory. Thus, the result is an estimate.
+///
+/// On machines without NUMA support, the system-wide free pages are counted.
+pub fn available_huge_pages(page_type: PageType, node: u16) -> Result<usize> {
+    let node_dir = format!("/sys/devices/system/node/node{}", node);
+    let free = if Path::new(&node_dir).exists() {
+        free_huge_pages(page_type, Some(node))?
+    } else {
+        free_huge_pages(page_type, None)?
+    };
+
+    let global_dir = Path::new("/sys/kernel/mm/hugepages").join(hugepages_dir_name(page_type)?);
+    let overcommit = read_counter(global_dir.join("nr_overcommit_hugepages").as_path())?;
+    let surplus = read_counter(global_dir.join("surplus_hugepages").as_path())?;
+
+    Ok(free + overcommit.saturating_sub(surplus))
+}
+
+/// Checks if the page type is available for an allocation.
+///
+/// `node_bytes` specifies how many bytes will be allocated on each NUMA node.
+/// Returns the page type to allocate, which is either `page_type` or the
+/// fallback page type. Returns `NoHugePages` if the page type is unavailable
+/// and the fallback policy is `HugePageFallback::Fail`.
+pub fn preflight(page_type: PageType, node_bytes: &[NodeLen]) -> Result<PageType> {
+    let is_available = match page_type {
+        PageType::Default | PageType::Small => true,
+        PageType::TransparentHuge => thp_mode()? != ThpMode::Never,
+        PageType::Huge2MB | PageType::Huge16MB | PageType::Huge1GB | PageType::Huge16GB => {
+            let page_size = page_type.page_size()?;
+            let mut is_available = true;
+            for &NodeLen { node, len } in node_bytes {
+                let pages = (len + page_size - 1) / page_size;
+                is_available &= available_huge_pages(page_type, node)? >= pages;
+            }
+            is_available
+        }
+    };
+
+    if is_available {
+        return Ok(page_type);
+    }
+
+    match fallback() {
+        HugePageFallback::TransparentHuge if page_type != PageType::TransparentHuge => {
+            preflight(PageType::TransparentHuge, node_bytes)
+        }
+        HugePageFallback::Small => Ok(PageType::Small),
+        HugePageFallback::Fail | HugePageFallback::TransparentHuge => {
+            Err(ErrorKind::NoHugePages(format!(
+                "Not enough {:?} pages available; are enough huge pages reserved?",
+                page_type
+            )))?
+        }
+    }
+}
+
+/// Returns the number of bytes of `data` that are backed by huge pages.
+///
+/// Both HugeTLB pages and transparent huge pages are counted. The count is
+/// read from `/proc/self/smaps`, which reports huge pages per memory mapping.
+/// If a mapping extends beyond `data`, its huge pages are attributed to `data`
+/// up to the size of the overlap. Thus, the result is exact for memory that is
+/// allocated by `NumaMemory` or `DistributedNumaMemory`, but can be an
+/// overestimate for other memory.
+pub fn huge_page_bytes<T>(data: &[T]) -> Result<usize> {
+    let path = Path::new("/proc/self/smaps");
+    let smaps = fs::read_to_string(path)
+        .chain_err(|| ErrorKind::RuntimeError(format!("Failed to read {}", path.display())))?;
+
+    let start = data.as_ptr() as usize;
+    let end = start + data.len() * size_of::<T>();
+
+    parse_smaps(&smaps, start, end, ProcessorCache::page_size())
+}
+
+/// A memory mapping in `/proc/self/smaps`
+#[derive(Default)]
+struct Mapping {
+    start: usize,
+    end: usize,
+    kernel_page_size: usize,
+    anon_huge_pages: usize,
+    hugetlb: usize,
+}
+
+impl Mapping {
+    /// Returns the bytes backed by huge pages within `start..end`.
+    fn huge_page_bytes(&self, start: usize, end: usize, small_page_size: usize) -> usize {
+        let overlap = cmp::min(self.end, end).saturating_sub(cmp::max(self.start, start));
+        let huge_bytes = if self.kernel_page_size > small_page_size {
+            self.hugetlb
+        } else {
+            self.anon_huge_pages
+        };
+
+        cmp::min(huge_bytes, overlap)
+    }
+}
+
+/// Sums up the huge page bytes of all mappings that overlap with `start..end`.
+fn parse_smaps(smaps: &str, start: usize, end: usize, small_page_size: usize) -> Result<usize> {
+    let mut bytes = 0;
+    let mut mapping: Option<Mapping> = None;
+
+    for line in smaps.lines() {
+        let mut fields = line.split_whitespace();
+        let first = match fields.next() {
+            Some(first) => first,
+            None => continue,
+        };
+
+        if let Some(key) = first.strip_suffix(':') {
+            // A field of the current mapping, e.g.: `AnonHugePages: 2048 kB`
+            if let Some(ref mut m) = mapping {
+                let mut value = || -> Result<usize> {
+                    let kb: usize =
+                        fields.next().and_then(|v| v.parse().ok()).ok_or_else(|| {
+                            ErrorKind::RuntimeError(format!("Failed to parse smaps line: {}", line))
+                        })?;
+                    Ok(kb * 1024)
+                };
+
+                match key {
+                    "KernelPageSize" => m.kernel_page_size = value()?,
+                    "AnonHugePages" => m.anon_huge_pages = value()?,
+                    "Private_Hugetlb" | "Shared_Hugetlb" => m.hugetlb += value()?,
+                    _ => {}
+                }
+            }
+        } else {
+            // The header of the next mapping, e.g.:
+            // `7f2c4a000000-7f2c4a200000 rw-p 00000000 00:00 0`
+            if let Some(m) = mapping.take() {
+                bytes += m.huge_page_bytes(start, end, small_page_size);
+            }
+
+            let mut range = first.splitn(2, '-');
+            let parse_address = |s: Option<&str>| {
+                s.and_then(|s| usize::from_str_radix(s, 16).ok())
+                    .ok_or_else(|| {
+                        ErrorKind::RuntimeError(format!("Failed to parse smaps line: {}", line))
+                    })
+            };
+            let m_start = parse_address(range.next())?;
+            let m_end = parse_address(range.next())?;
+
+            if m_start < end && start < m_end {
+                mapping = Some(Mapping {
+                    start: m_start,
+                    end: m_end,
+                    ..Mapping::default()
+                });
+            }
+        }
+    }
+
+    if let Some(m) = mapping.take() {
+        bytes += m.huge_page_bytes(start, end, small_page_size);
+    }
+
+    Ok(bytes)
+}
+
+/// Returns the sysfs directory name of the HugeTLB page size, e.g.,
+/// `hugepages-2048kB`.
+fn hugepages_dir_name(page_type: PageType) -> Result<String> {
+    match page_type {
+        PageType::Huge2MB | PageType::Huge16MB | PageType::Huge1GB | PageType::Huge16GB => {
+            Ok(format!("hugepages-{}kB", page_type.page_size()? / 1024))
+        }
+        _ => Err(ErrorKind::InvalidArgument(format!(
+            "{:?} is not a HugeTLB page type",
+            page_type
+        )))?,
+    }
+}
+
+/// Reads a sysfs counter, or returns zero if the counter doesn't exist.
+fn read_counter(path: &Path) -> Result<usize> {
+    if !path.exists() {
+        return Ok(0);
+    }
+
+    let s = read_sysfs(path)?;
+    s.trim().parse().map_err(|_| {
+        ErrorKind::RuntimeError(format!("Failed to parse {}: {}", path.display(), s.trim())).into()
+    })
+}
+
+/// Reads a sysfs file.
+fn read_sysfs(path: &Path) -> Result<String> {
+    fs::read_to_string(path)
+        .chain_err(|| ErrorKind::RuntimeError(format!("Failed to read {}", path.display())))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parse_selected_thp_mode() -> Result<()> {
+        assert_eq!(parse_thp_mode("[always] madvise never\n")?, ThpMode::Always);
+        assert_eq!(
+            parse_thp_mode("always [madvise] never\n")?,
+            ThpMode::Madvise
+        );
+        assert_eq!(parse_thp_mode("always madvise [never]\n")?, ThpMode::Never);
+        assert!(parse_thp_mode("always madvise never\n").is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn parse_smaps_counts_overlapping_mappings() -> Result<()> {
+        let smaps = "\
+00400000-00600000 r-xp 00000000 08:01 1234 /usr/bin/true
+Size:               2048 kB
+KernelPageSize:        4 kB
+AnonHugePages:         0 kB
+7f0000000000-7f0000800000 rw-p 00000000 00:00 0
+Size:               8192 kB
+KernelPageSize:        4 kB
+AnonHugePages:      4096 kB
+Private_Hugetlb:       0 kB
+7f0000800000-7f0001000000 rw-p 00000000 00:00 0
+Size:               8192 kB
+KernelPageSize:     2048 kB
+AnonHugePages:         0 kB
+Shared_Hugetlb:        0 kB
+Private_Hugetlb:    6144 kB
+VmFlags: rd wr mr mw me ac ht
+";
+        let small_page_size = 4096;
+
+        // THP mapping only
+        let bytes = parse_smaps(smaps, 0x7f0000000000, 0x7f0000800000, small_page_size)?;
+        assert_eq!(bytes, 4096 * 1024);
+
+        // THP and HugeTLB mappings
+        let bytes = parse_smaps(smaps, 0x7f0000000000, 0x7f0001000000, small_page_size)?;
+        assert_eq!(bytes, (4096 + 6144) * 1024);
+
+        // A 1 MiB region within the THP mapping is capped at its size
+        let bytes = parse_smaps(smaps, 0x7f0000000000, 0x7f0000100000, small_page_size)?;
+        assert_eq!(bytes, 1024 * 1024);
+
+        // Unrelated region
+        let bytes = parse_smaps(smaps, 0x00400000, 0x00600000, small_page_size)?;
+        assert_eq!(bytes, 0);
+
+        Ok(())
+    }
+
+    #[test]
+    fn preflight_accepts_small_pages() -> Result<()> {
+        let node_bytes = [NodeLen {
+            node: 0,
+            len: 1 << 30,
+        }];
+        assert_eq!(preflight(PageType::Small, &node_bytes)?, PageType::Small);
+        assert_eq!(
+            preflight(PageType::Default, &node_bytes)?,
+            PageType::Default
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn small_page_type_is_not_a_hugetlb_dir() {
+        assert!(hugepages_dir_name(PageType::Small).is_err());
+        assert_eq!(
+            hugepages_dir_name(PageType::Huge2MB).ok(),
+            Some("hugepages-2048kB".to_string())
+        );
+    }
+}
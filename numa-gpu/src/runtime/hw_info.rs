@@ -13,16 +13,24 @@
 // limitations under the License.
 
 use crate::error::{ErrorKind, Result};
-use crate::runtime::linux_wrapper;
 use procfs::CpuInfo;
-use rustacuda::device::{Device, DeviceAttribute};
 use std::fmt;
 use std::fs::File;
-use std::io::BufReader;
 use std::io::Read;
+
+#[cfg(feature = "cuda")]
+use crate::runtime::linux_wrapper;
+#[cfg(feature = "cuda")]
+use rustacuda::device::{Device, DeviceAttribute};
+#[cfg(feature = "cuda")]
+use std::io::BufReader;
+#[cfg(feature = "cuda")]
 use std::mem;
+#[cfg(feature = "cuda")]
 use std::os::raw::c_int;
+#[cfg(feature = "cuda")]
 use std::path::PathBuf;
+#[cfg(feature = "cuda")]
 use std::str::FromStr;
 
 pub struct ProcessorCache {}
@@ -118,6 +126,7 @@ pub fn cpu_codename() -> Result<String> {
 
 /// Extends Rustacuda's Device with methods that provide additional hardware
 /// information.
+#[cfg(feature = "cuda")]
 pub trait CudaDeviceInfo {
     /// Returns the number of cores per streaming multiprocessor
     fn sm_cores(&self) -> Result<u32>;
@@ -135,6 +144,7 @@ pub trait CudaDeviceInfo {
     fn memory_clock_rate(&self) -> Result<u32>;
 }
 
+#[cfg(feature = "cuda")]
 impl CudaDeviceInfo for Device {
     fn sm_cores(&self) -> Result<u32> {
         let major = self.get_attribute(DeviceAttribute::ComputeCapabilityMajor)?;
@@ -184,6 +194,7 @@ impl CudaDeviceInfo for Device {
 ///
 /// Specifically, `NvidiaDriverInfo` maps the GPU device to the NUMA node on
 /// IBM POWER systems with NVLink.
+#[cfg(feature = "cuda")]
 pub trait NvidiaDriverInfo {
     /// Returns the NUMA node associated with this GPU device
     ///
@@ -274,6 +285,7 @@ pub trait NvidiaDriverInfo {
     fn numa_memory_affinity(&self) -> Result<u16>;
 }
 
+#[cfg(feature = "cuda")]
 impl NvidiaDriverInfo for Device {
     fn numa_node(&self) -> Result<u16> {
         let nvidia_info = NvidiaDriverInternal::from_device(self)?;
@@ -296,12 +308,14 @@ impl NvidiaDriverInfo for Device {
 }
 
 /// A private helper struct to load the GPU driver information
+#[cfg(feature = "cuda")]
 struct NvidiaDriverInternal {
     numa_node: u16,
     is_mem_online: bool,
     mem_size: usize,
 }
 
+#[cfg(feature = "cuda")]
 impl NvidiaDriverInternal {
     fn from_device(device: &Device) -> Result<Self> {
         let device_id = unsafe { mem::transmute_copy::<Device, c_int>(device) };
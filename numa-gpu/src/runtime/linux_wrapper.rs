@@ -22,6 +22,7 @@ use std::io::Error as IoError;
 use std::io::{BufRead, BufReader};
 use std::mem::{size_of, size_of_val};
 use std::os::raw::{c_int, c_long, c_uint, c_ulong, c_void};
+use std::ptr;
 
 mod bindings {
     use super::*;
@@ -36,6 +37,14 @@ mod bindings {
             maxnode: c_ulong,
             flags: c_uint,
         ) -> c_long;
+        pub fn move_pages(
+            pid: c_int,
+            count: c_ulong,
+            pages: *mut *mut c_void,
+            nodes: *const c_int,
+            status: *mut c_int,
+            flags: c_int,
+        ) -> c_long;
         pub fn numa_set_preferred(node: c_int);
         pub fn numa_run_on_node(node: c_int) -> c_int;
         pub fn numa_set_strict(strict: c_int);
@@ -134,7 +143,9 @@ pub struct CpuSet {
 impl CpuSet {
     const MASK_LEN: u16 = 16;
     const ENTRY_LEN: u16 = 64;
-    const MAX_LEN: u16 = Self::MASK_LEN * Self::ENTRY_LEN;
+
+    /// The maximum number of IDs in the set. IDs must be smaller.
+    pub const MAX_LEN: u16 = Self::MASK_LEN * Self::ENTRY_LEN;
 
     /// Create an empty CPU set.
     pub fn new() -> Self {
@@ -257,6 +268,26 @@ impl std::ops::BitXor for CpuSet {
     }
 }
 
+/// Returns the set of CPUs that the current process is allowed to run on.
+///
+/// The set reflects restrictions by `taskset`, `numactl`, and cgroup CPU sets.
+pub fn sched_getaffinity() -> Result<CpuSet> {
+    let mut cpu_set = CpuSet::new();
+
+    unsafe {
+        if libc::sched_getaffinity(
+            0,
+            cpu_set.bytes(),
+            cpu_set.as_mut_slice().as_mut_ptr() as *mut libc::cpu_set_t,
+        ) == -1
+        {
+            Err(ErrorKind::Io(IoError::last_os_error()))?;
+        }
+    }
+
+    Ok(cpu_set)
+}
+
 pub fn mbind<T>(
     data: &[T],
     mode: MemPolicyModes,
@@ -281,6 +312,45 @@ pub fn mbind<T>(
     }
 }
 
+/// Moves pages of the current process to NUMA nodes.
+///
+/// Each page is specified by an address within the page. If `nodes` is
+/// `None`, the pages are not moved. Instead, `status` returns the node on which
+/// each page currently resides. Otherwise, each page is moved to its node in
+/// `nodes`, and `status` returns the node after the move.
+///
+/// A page that fails returns a negative error number in `status`. For
+/// example, `-ENOENT` is returned for pages that are not yet mapped.
+///
+/// Refer to `man move_pages` for details.
+pub fn move_pages(
+    pages: &[*const c_void],
+    nodes: Option<&[c_int]>,
+    status: &mut [c_int],
+    flags: MemBindFlags,
+) -> Result<()> {
+    assert_eq!(pages.len(), status.len());
+    if let Some(nodes) = nodes {
+        assert_eq!(pages.len(), nodes.len());
+    }
+
+    unsafe {
+        if bindings::move_pages(
+            0,
+            pages.len() as c_ulong,
+            pages.as_ptr() as *mut *mut c_void,
+            nodes.map_or_else(ptr::null, |n| n.as_ptr()),
+            status.as_mut_ptr(),
+            flags.bits() as c_int,
+        ) == -1
+        {
+            Err(ErrorKind::Io(IoError::last_os_error()))?;
+        }
+    }
+
+    Ok(())
+}
+
 /// Defines the `mprotect` system call for the type
 pub trait MemProtect {
     /// Sets the protection flags of a memory region
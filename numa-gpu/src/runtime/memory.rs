@@ -12,9 +12,10 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+#[cfg(feature = "cuda")]
 use rustacuda::memory::{
-    CopyDestination, DeviceBuffer, DeviceCopy, DevicePointer, DeviceSlice, LockedBuffer,
-    UnifiedBuffer, UnifiedPointer,
+    CopyDestination, DeviceBuffer, DevicePointer, DeviceSlice, LockedBuffer, UnifiedBuffer,
+    UnifiedPointer,
 };
 
 use std::convert::{TryFrom, TryInto};
@@ -23,10 +24,73 @@ use std::ops::Deref;
 use std::ops::DerefMut;
 use std::ptr;
 
+use super::huge_pages;
 use super::linux_wrapper::{MemProtect, MemProtectFlags};
-use super::numa::{DistributedNumaMemory, NumaMemory};
+use super::numa::{self, DistributedNumaMemory, NodeRatio, NumaMemory, PageResidency};
 use crate::error::{Error, ErrorKind, Result};
 
+/// Marks a type as safe to copy to and from the GPU.
+///
+/// With the `cuda` feature enabled, this is RUSTACuda's `DeviceCopy` trait.
+/// Without the feature, an equivalent marker trait is provided, so that
+/// CPU-only code can keep the same trait bounds.
+#[cfg(feature = "cuda")]
+pub use rustacuda::memory::DeviceCopy;
+
+/// Marks a type as safe to copy to and from the GPU.
+///
+/// With the `cuda` feature enabled, this is RUSTACuda's `DeviceCopy` trait.
+/// Without the feature, an equivalent marker trait is provided, so that
+/// CPU-only code can keep the same trait bounds.
+///
+/// # Safety
+///
+/// The type must not contain references or pointers to host memory that are
+/// dereferenced after copying, i.e., it must be valid to copy it bit-for-bit.
+#[cfg(not(feature = "cuda"))]
+pub unsafe trait DeviceCopy {}
+
+#[cfg(not(feature = "cuda"))]
+macro_rules! impl_device_copy {
+    ($($t:ty)*) => {
+        $(
+            unsafe impl DeviceCopy for $t {}
+        )*
+    }
+}
+
+#[cfg(not(feature = "cuda"))]
+impl_device_copy!(
+    usize u8 u16 u32 u64 u128
+    isize i8 i16 i32 i64 i128
+    f32 f64
+    bool char
+    std::num::Wrapping<usize> std::num::Wrapping<u8> std::num::Wrapping<u16>
+    std::num::Wrapping<u32> std::num::Wrapping<u64> std::num::Wrapping<u128>
+    std::num::Wrapping<isize> std::num::Wrapping<i8> std::num::Wrapping<i16>
+    std::num::Wrapping<i32> std::num::Wrapping<i64> std::num::Wrapping<i128>
+);
+
+#[cfg(not(feature = "cuda"))]
+unsafe impl<T: DeviceCopy> DeviceCopy for Option<T> {}
+#[cfg(not(feature = "cuda"))]
+unsafe impl<T: DeviceCopy> DeviceCopy for std::mem::ManuallyDrop<T> {}
+#[cfg(not(feature = "cuda"))]
+unsafe impl<T: ?Sized> DeviceCopy for std::marker::PhantomData<T> {}
+#[cfg(not(feature = "cuda"))]
+unsafe impl DeviceCopy for () {}
+#[cfg(not(feature = "cuda"))]
+unsafe impl<A: DeviceCopy, B: DeviceCopy> DeviceCopy for (A, B) {}
+#[cfg(not(feature = "cuda"))]
+unsafe impl<A: DeviceCopy, B: DeviceCopy, C: DeviceCopy> DeviceCopy for (A, B, C) {}
+#[cfg(not(feature = "cuda"))]
+unsafe impl<A: DeviceCopy, B: DeviceCopy, C: DeviceCopy, D: DeviceCopy> DeviceCopy
+    for (A, B, C, D)
+{
+}
+#[cfg(not(feature = "cuda"))]
+unsafe impl<T: DeviceCopy, const N: usize> DeviceCopy for [T; N] {}
+
 /// A trait for locking pages in memory
 ///
 /// Exposes the `mlock` and `munlock` functions of Linux. Locking prevents the
@@ -64,8 +128,11 @@ pub enum MemType {
     BoxedSysMem,
     NumaMem,
     DistributedNumaMem,
+    #[cfg(feature = "cuda")]
     CudaPinnedMem,
+    #[cfg(feature = "cuda")]
     CudaDevMem,
+    #[cfg(feature = "cuda")]
     CudaUniMem,
 }
 
@@ -86,10 +153,13 @@ pub enum Mem<T: DeviceCopy> {
     /// NUMA memory distributed over multiple NUMA nodes
     DistributedNumaMem(DistributedNumaMemory<T>),
     /// CUDA pinned memory (using cudaHostAlloc())
+    #[cfg(feature = "cuda")]
     CudaPinnedMem(LockedBuffer<T>),
     /// CUDA unified memory
+    #[cfg(feature = "cuda")]
     CudaDevMem(DeviceBuffer<T>),
     /// CUDA device memory
+    #[cfg(feature = "cuda")]
     CudaUniMem(UnifiedBuffer<T>),
 }
 
@@ -100,8 +170,11 @@ impl<T: DeviceCopy> Mem<T> {
             BoxedSysMem(ref m) => m.len(),
             NumaMem(ref m) => m.len(),
             DistributedNumaMem(ref m) => m.len(),
+            #[cfg(feature = "cuda")]
             CudaPinnedMem(ref m) => m.len(),
+            #[cfg(feature = "cuda")]
             CudaDevMem(ref m) => m.len(),
+            #[cfg(feature = "cuda")]
             CudaUniMem(ref m) => m.len(),
         }
     }
@@ -112,8 +185,11 @@ impl<T: DeviceCopy> Mem<T> {
             BoxedSysMem(_) => MemType::BoxedSysMem,
             NumaMem(_) => MemType::NumaMem,
             DistributedNumaMem(_) => MemType::DistributedNumaMem,
+            #[cfg(feature = "cuda")]
             CudaPinnedMem(_) => MemType::CudaPinnedMem,
+            #[cfg(feature = "cuda")]
             CudaDevMem(_) => MemType::CudaDevMem,
+            #[cfg(feature = "cuda")]
             CudaUniMem(_) => MemType::CudaUniMem,
         }
     }
@@ -124,8 +200,11 @@ impl<T: DeviceCopy> Mem<T> {
             BoxedSysMem(m) => m.as_ptr(),
             NumaMem(m) => m.as_ptr(),
             DistributedNumaMem(m) => m.as_ptr(),
+            #[cfg(feature = "cuda")]
             CudaPinnedMem(m) => m.as_ptr(),
+            #[cfg(feature = "cuda")]
             CudaDevMem(m) => m.as_ptr(),
+            #[cfg(feature = "cuda")]
             CudaUniMem(m) => m.as_ptr(),
         }
     }
@@ -136,8 +215,11 @@ impl<T: DeviceCopy> Mem<T> {
             BoxedSysMem(m) => m.as_mut_ptr(),
             NumaMem(m) => m.as_mut_ptr(),
             DistributedNumaMem(m) => m.as_mut_ptr(),
+            #[cfg(feature = "cuda")]
             CudaPinnedMem(m) => m.as_mut_ptr(),
+            #[cfg(feature = "cuda")]
             CudaDevMem(m) => m.as_mut_ptr(),
+            #[cfg(feature = "cuda")]
             CudaUniMem(m) => m.as_mut_ptr(),
         }
     }
@@ -177,14 +259,18 @@ impl<T: DeviceCopy> Mem<T> {
             BoxedSysMem(m) => LaunchableMutPtr(m.as_mut_ptr()),
             NumaMem(m) => LaunchableMutPtr(m.as_mut_ptr()),
             DistributedNumaMem(m) => LaunchableMutPtr(m.as_mut_ptr()),
+            #[cfg(feature = "cuda")]
             CudaPinnedMem(m) => LaunchableMutPtr(m.as_mut_ptr()),
+            #[cfg(feature = "cuda")]
             CudaDevMem(m) => m.as_device_ptr().into(),
+            #[cfg(feature = "cuda")]
             CudaUniMem(m) => m.as_unified_ptr().into(),
         }
     }
 }
 
 impl<T: Copy + DeviceCopy> Mem<T> {
+    #[cfg(feature = "cuda")]
     pub fn copy_from_mem(&mut self, src: &Self) -> Result<()> {
         assert!(
             self.len() == src.len(),
@@ -217,10 +303,27 @@ impl<T: Copy + DeviceCopy> Mem<T> {
 
         Ok(())
     }
+
+    #[cfg(not(feature = "cuda"))]
+    pub fn copy_from_mem(&mut self, src: &Self) -> Result<()> {
+        assert!(
+            self.len() == src.len(),
+            "Copy destination length must be equal to source"
+        );
+
+        let dst_slice: &mut [T] = self.try_into()?;
+        let src_slice: &[T] = src.try_into()?;
+        dst_slice.copy_from_slice(src_slice);
+
+        Ok(())
+    }
 }
 
 impl<'t, T: DeviceCopy> TryInto<&'t [T]> for &'t Mem<T> {
+    #[cfg(feature = "cuda")]
     type Error = (Error, &'t DeviceBuffer<T>);
+    #[cfg(not(feature = "cuda"))]
+    type Error = Error;
 
     fn try_into(self) -> std::result::Result<&'t [T], Self::Error> {
         match self {
@@ -228,8 +331,11 @@ impl<'t, T: DeviceCopy> TryInto<&'t [T]> for &'t Mem<T> {
             Mem::BoxedSysMem(m) => Ok(m.as_ref()),
             Mem::NumaMem(m) => Ok(m.as_slice()),
             Mem::DistributedNumaMem(m) => Ok(m.as_slice()),
+            #[cfg(feature = "cuda")]
             Mem::CudaPinnedMem(m) => Ok(m.as_slice()),
+            #[cfg(feature = "cuda")]
             Mem::CudaUniMem(m) => Ok(m.as_slice()),
+            #[cfg(feature = "cuda")]
             Mem::CudaDevMem(m) => Err((
                 ErrorKind::InvalidConversion("Cannot convert device memory to &[T] slice").into(),
                 m,
@@ -239,7 +345,10 @@ impl<'t, T: DeviceCopy> TryInto<&'t [T]> for &'t Mem<T> {
 }
 
 impl<'t, T: DeviceCopy> TryInto<&'t mut [T]> for &'t mut Mem<T> {
+    #[cfg(feature = "cuda")]
     type Error = (Error, &'t mut DeviceBuffer<T>);
+    #[cfg(not(feature = "cuda"))]
+    type Error = Error;
 
     fn try_into(self) -> std::result::Result<&'t mut [T], Self::Error> {
         match self {
@@ -247,8 +356,11 @@ impl<'t, T: DeviceCopy> TryInto<&'t mut [T]> for &'t mut Mem<T> {
             Mem::BoxedSysMem(m) => Ok(m.as_mut()),
             Mem::NumaMem(m) => Ok(m.as_mut_slice()),
             Mem::DistributedNumaMem(m) => Ok(m.as_mut_slice()),
+            #[cfg(feature = "cuda")]
             Mem::CudaPinnedMem(m) => Ok(m.as_mut_slice()),
+            #[cfg(feature = "cuda")]
             Mem::CudaUniMem(m) => Ok(m.as_mut_slice()),
+            #[cfg(feature = "cuda")]
             Mem::CudaDevMem(m) => Err((
                 ErrorKind::InvalidConversion("Cannot convert device memory to &mut [T] slice")
                     .into(),
@@ -265,12 +377,100 @@ impl<T: DeviceCopy> From<DerefMem<T>> for Mem<T> {
             DerefMem::BoxedSysMem(m) => Mem::BoxedSysMem(m),
             DerefMem::NumaMem(m) => Mem::NumaMem(m),
             DerefMem::DistributedNumaMem(m) => Mem::DistributedNumaMem(m),
+            #[cfg(feature = "cuda")]
             DerefMem::CudaPinnedMem(m) => Mem::CudaPinnedMem(m),
+            #[cfg(feature = "cuda")]
             DerefMem::CudaUniMem(m) => Mem::CudaUniMem(m),
         }
     }
 }
 
+impl<T: DeviceCopy> Mem<T> {
+    /// Returns true if the page residency can be queried.
+    ///
+    /// CUDA unified and device memory don't have a page residency, because
+    /// their pages can reside on the GPU.
+    pub fn has_page_residency(&self) -> bool {
+        match self {
+            Mem::SysMem(_) | Mem::BoxedSysMem(_) | Mem::NumaMem(_) | Mem::DistributedNumaMem(_) => {
+                true
+            }
+            #[cfg(feature = "cuda")]
+            Mem::CudaPinnedMem(_) => true,
+            #[cfg(feature = "cuda")]
+            Mem::CudaUniMem(_) | Mem::CudaDevMem(_) => false,
+        }
+    }
+
+    /// Queries the NUMA nodes on which the pages actually reside.
+    ///
+    /// Fails for CUDA unified and device memory, because their pages can
+    /// reside on the GPU.
+    pub fn page_residency(&self) -> Result<PageResidency> {
+        match self {
+            Mem::SysMem(m) => numa::page_residency(m.as_slice()),
+            Mem::BoxedSysMem(m) => numa::page_residency(m.as_ref()),
+            Mem::NumaMem(m) => m.page_residency(),
+            Mem::DistributedNumaMem(m) => m.page_residency(),
+            #[cfg(feature = "cuda")]
+            Mem::CudaPinnedMem(m) => numa::page_residency(m.as_slice()),
+            #[cfg(feature = "cuda")]
+            Mem::CudaUniMem(_) | Mem::CudaDevMem(_) => Err(ErrorKind::InvalidArgument(format!(
+                "Cannot query the page residency of {:?} memory",
+                self.mem_type()
+            )))?,
+        }
+    }
+
+    /// Returns the number of bytes that are backed by huge pages.
+    ///
+    /// Fails for CUDA unified and device memory, because their pages can
+    /// reside on the GPU.
+    pub fn huge_page_bytes(&self) -> Result<usize> {
+        match self {
+            Mem::SysMem(m) => huge_pages::huge_page_bytes(m.as_slice()),
+            Mem::BoxedSysMem(m) => huge_pages::huge_page_bytes(m.as_ref()),
+            Mem::NumaMem(m) => huge_pages::huge_page_bytes(m.as_slice()),
+            Mem::DistributedNumaMem(m) => huge_pages::huge_page_bytes(m.as_slice()),
+            #[cfg(feature = "cuda")]
+            Mem::CudaPinnedMem(m) => huge_pages::huge_page_bytes(m.as_slice()),
+            #[cfg(feature = "cuda")]
+            Mem::CudaUniMem(_) | Mem::CudaDevMem(_) => Err(ErrorKind::InvalidArgument(format!(
+                "Cannot query the huge pages of {:?} memory",
+                self.mem_type()
+            )))?,
+        }
+    }
+
+    /// Migrates the pages to the NUMA nodes.
+    ///
+    /// The pages are distributed proportionally to the node ratios. NUMA
+    /// memory can only be migrated to a single node. CUDA memory cannot be
+    /// migrated.
+    pub fn migrate(&mut self, node_ratios: &[NodeRatio]) -> Result<()> {
+        match self {
+            Mem::SysMem(m) => numa::migrate_pages(m.as_slice(), node_ratios),
+            Mem::BoxedSysMem(m) => numa::migrate_pages(m.as_ref(), node_ratios),
+            Mem::NumaMem(m) => migrate_numa_mem(m, node_ratios),
+            Mem::DistributedNumaMem(m) => m.migrate(node_ratios),
+            #[cfg(feature = "cuda")]
+            Mem::CudaPinnedMem(_) | Mem::CudaUniMem(_) | Mem::CudaDevMem(_) => Err(
+                ErrorKind::InvalidArgument(format!("Cannot migrate {:?} memory", self.mem_type())),
+            )?,
+        }
+    }
+}
+
+/// Migrates NUMA memory, which is bound to exactly one node.
+fn migrate_numa_mem<T>(mem: &mut NumaMemory<T>, node_ratios: &[NodeRatio]) -> Result<()> {
+    match node_ratios {
+        [NodeRatio { node, .. }] => mem.migrate(*node),
+        _ => Err(ErrorKind::InvalidArgument(
+            "NUMA memory can only be migrated to a single node".to_string(),
+        ))?,
+    }
+}
+
 impl<T: DeviceCopy> MemLock for Mem<T> {
     fn mlock(&mut self) -> Result<()> {
         match self {
@@ -322,8 +522,10 @@ pub enum DerefMem<T: DeviceCopy> {
     /// NUMA memory distributed over multiple NUMA nodes
     DistributedNumaMem(DistributedNumaMemory<T>),
     /// CUDA pinned memory (using cudaHostAlloc())
+    #[cfg(feature = "cuda")]
     CudaPinnedMem(LockedBuffer<T>),
     /// CUDA unified memory
+    #[cfg(feature = "cuda")]
     CudaUniMem(UnifiedBuffer<T>),
 }
 
@@ -334,7 +536,9 @@ impl<T: DeviceCopy> DerefMem<T> {
             DerefMem::BoxedSysMem(_) => MemType::BoxedSysMem,
             DerefMem::NumaMem(_) => MemType::NumaMem,
             DerefMem::DistributedNumaMem(_) => MemType::DistributedNumaMem,
+            #[cfg(feature = "cuda")]
             DerefMem::CudaPinnedMem(_) => MemType::CudaPinnedMem,
+            #[cfg(feature = "cuda")]
             DerefMem::CudaUniMem(_) => MemType::CudaUniMem,
         }
     }
@@ -345,7 +549,9 @@ impl<T: DeviceCopy> DerefMem<T> {
             DerefMem::BoxedSysMem(m) => m.as_ref(),
             DerefMem::NumaMem(m) => m.as_slice(),
             DerefMem::DistributedNumaMem(m) => m.as_slice(),
+            #[cfg(feature = "cuda")]
             DerefMem::CudaPinnedMem(m) => m.as_slice(),
+            #[cfg(feature = "cuda")]
             DerefMem::CudaUniMem(m) => m.as_slice(),
         }
     }
@@ -356,7 +562,9 @@ impl<T: DeviceCopy> DerefMem<T> {
             DerefMem::BoxedSysMem(m) => m.as_mut(),
             DerefMem::NumaMem(m) => m.as_mut_slice(),
             DerefMem::DistributedNumaMem(m) => m.as_mut_slice(),
+            #[cfg(feature = "cuda")]
             DerefMem::CudaPinnedMem(m) => m.as_mut_slice(),
+            #[cfg(feature = "cuda")]
             DerefMem::CudaUniMem(m) => m.as_mut_slice(),
         }
     }
@@ -396,12 +604,49 @@ impl<T: DeviceCopy> DerefMem<T> {
             Self::BoxedSysMem(m) => LaunchableMutPtr(m.as_mut_ptr()),
             Self::NumaMem(m) => LaunchableMutPtr(m.as_mut_ptr()),
             Self::DistributedNumaMem(m) => LaunchableMutPtr(m.as_mut_ptr()),
+            #[cfg(feature = "cuda")]
             Self::CudaPinnedMem(m) => LaunchableMutPtr(m.as_mut_ptr()),
+            #[cfg(feature = "cuda")]
             Self::CudaUniMem(m) => m.as_unified_ptr().into(),
         }
     }
 }
 
+impl<T: DeviceCopy> DerefMem<T> {
+    /// Queries the NUMA nodes on which the pages actually reside.
+    ///
+    /// Fails for CUDA unified memory, because its pages can reside on the
+    /// GPU.
+    pub fn page_residency(&self) -> Result<PageResidency> {
+        match self {
+            DerefMem::NumaMem(m) => m.page_residency(),
+            DerefMem::DistributedNumaMem(m) => m.page_residency(),
+            #[cfg(feature = "cuda")]
+            DerefMem::CudaUniMem(_) => Err(ErrorKind::InvalidArgument(format!(
+                "Cannot query the page residency of {:?} memory",
+                self.mem_type()
+            )))?,
+            mem @ _ => numa::page_residency(mem.as_slice()),
+        }
+    }
+
+    /// Migrates the pages to the NUMA nodes.
+    ///
+    /// See `Mem::migrate` for details.
+    pub fn migrate(&mut self, node_ratios: &[NodeRatio]) -> Result<()> {
+        match self {
+            DerefMem::SysMem(m) => numa::migrate_pages(m.as_slice(), node_ratios),
+            DerefMem::BoxedSysMem(m) => numa::migrate_pages(m.as_ref(), node_ratios),
+            DerefMem::NumaMem(m) => migrate_numa_mem(m, node_ratios),
+            DerefMem::DistributedNumaMem(m) => m.migrate(node_ratios),
+            #[cfg(feature = "cuda")]
+            DerefMem::CudaPinnedMem(_) | DerefMem::CudaUniMem(_) => Err(
+                ErrorKind::InvalidArgument(format!("Cannot migrate {:?} memory", self.mem_type())),
+            )?,
+        }
+    }
+}
+
 impl<T: DeviceCopy> Deref for DerefMem<T> {
     type Target = [T];
 
@@ -411,7 +656,9 @@ impl<T: DeviceCopy> Deref for DerefMem<T> {
             DerefMem::BoxedSysMem(m) => m.as_ref(),
             DerefMem::NumaMem(m) => m.as_slice(),
             DerefMem::DistributedNumaMem(m) => m.as_slice(),
+            #[cfg(feature = "cuda")]
             DerefMem::CudaPinnedMem(m) => m.as_slice(),
+            #[cfg(feature = "cuda")]
             DerefMem::CudaUniMem(m) => m.as_slice(),
         }
     }
@@ -424,7 +671,9 @@ impl<T: DeviceCopy> DerefMut for DerefMem<T> {
             DerefMem::BoxedSysMem(m) => m.as_mut(),
             DerefMem::NumaMem(m) => m.as_mut_slice(),
             DerefMem::DistributedNumaMem(m) => m.as_mut_slice(),
+            #[cfg(feature = "cuda")]
             DerefMem::CudaPinnedMem(m) => m.as_mut_slice(),
+            #[cfg(feature = "cuda")]
             DerefMem::CudaUniMem(m) => m.as_mut_slice(),
         }
     }
@@ -439,8 +688,11 @@ impl<T: DeviceCopy> TryFrom<Mem<T>> for DerefMem<T> {
             Mem::BoxedSysMem(m) => Ok(DerefMem::BoxedSysMem(m)),
             Mem::NumaMem(m) => Ok(DerefMem::NumaMem(m)),
             Mem::DistributedNumaMem(m) => Ok(DerefMem::DistributedNumaMem(m)),
+            #[cfg(feature = "cuda")]
             Mem::CudaPinnedMem(m) => Ok(DerefMem::CudaPinnedMem(m)),
+            #[cfg(feature = "cuda")]
             Mem::CudaUniMem(m) => Ok(DerefMem::CudaUniMem(m)),
+            #[cfg(feature = "cuda")]
             Mem::CudaDevMem(_) => Err((
                 ErrorKind::InvalidConversion("Cannot convert device memory to DerefMem").into(),
                 mem,
@@ -524,6 +776,7 @@ impl<'a, T> LaunchableMem for [T] {
     }
 }
 
+#[cfg(feature = "cuda")]
 impl<'a, T> LaunchableMem for DeviceBuffer<T> {
     type Item = T;
 
@@ -549,6 +802,7 @@ impl<'a, T> LaunchableMem for DeviceBuffer<T> {
     }
 }
 
+#[cfg(feature = "cuda")]
 impl<'a, T> LaunchableMem for DeviceSlice<T> {
     type Item = T;
 
@@ -574,6 +828,7 @@ impl<'a, T> LaunchableMem for DeviceSlice<T> {
     }
 }
 
+#[cfg(feature = "cuda")]
 impl<'a, T: DeviceCopy> LaunchableMem for UnifiedBuffer<T> {
     type Item = T;
 
@@ -641,12 +896,14 @@ impl<T> Clone for LaunchablePtr<T> {
 
 unsafe impl<T: DeviceCopy> DeviceCopy for LaunchablePtr<T> {}
 
+#[cfg(feature = "cuda")]
 impl<T: DeviceCopy> From<UnifiedPointer<T>> for LaunchablePtr<T> {
     fn from(unified_ptr: UnifiedPointer<T>) -> Self {
         Self(unified_ptr.as_raw())
     }
 }
 
+#[cfg(feature = "cuda")]
 impl<T> From<DevicePointer<T>> for LaunchablePtr<T> {
     fn from(device_ptr: DevicePointer<T>) -> Self {
         Self(device_ptr.as_raw())
@@ -695,12 +952,14 @@ impl<T> Clone for LaunchableMutPtr<T> {
 
 unsafe impl<T: DeviceCopy> DeviceCopy for LaunchableMutPtr<T> {}
 
+#[cfg(feature = "cuda")]
 impl<T: DeviceCopy> From<UnifiedPointer<T>> for LaunchableMutPtr<T> {
     fn from(mut unified_ptr: UnifiedPointer<T>) -> Self {
         Self(unified_ptr.as_raw_mut())
     }
 }
 
+#[cfg(feature = "cuda")]
 impl<T> From<DevicePointer<T>> for LaunchableMutPtr<T> {
     fn from(mut device_ptr: DevicePointer<T>) -> Self {
         Self(device_ptr.as_raw_mut())
@@ -14,19 +14,27 @@
 
 //! Rust bindings to Linux's 'numa' library.
 
+#[cfg(feature = "cuda")]
 use super::cuda_wrapper::{host_register, host_unregister};
+use super::huge_pages;
 use super::hw_info::ProcessorCache;
 use super::linux_wrapper::{
-    mbind, mprotect, CpuSet, MemBindFlags, MemPolicyModes, MemProtect, MemProtectFlags,
+    mbind, move_pages, mprotect, CpuSet, MemBindFlags, MemPolicyModes, MemProtect, MemProtectFlags,
 };
-use super::memory::{MemLock, PageLock};
-use crate::error::{ErrorKind, Result, ResultExt};
+use super::memory::MemLock;
+#[cfg(feature = "cuda")]
+use super::memory::PageLock;
+#[cfg(feature = "cuda")]
+use crate::error::ResultExt;
+use crate::error::{Error, ErrorKind, Result};
 
 use libc::{madvise, mlock, mmap, munlock, munmap};
 
+use std::cmp;
+use std::collections::BTreeMap;
 use std::io::Error as IoError;
 use std::mem::size_of;
-use std::ops::{Deref, DerefMut};
+use std::ops::{Deref, DerefMut, Range};
 use std::ptr;
 use std::slice;
 
@@ -105,7 +113,7 @@ pub enum PageType {
 
 impl PageType {
     /// Returns the page size in bytes
-    fn page_size(&self) -> Result<usize> {
+    pub(crate) fn page_size(&self) -> Result<usize> {
         match self {
             PageType::Default | PageType::Small => Ok(ProcessorCache::page_size()),
             // THP use small page size instead of ProcessorCache::huge_page_size(),
@@ -117,6 +125,31 @@ impl PageType {
             PageType::Huge16GB => Ok(1 << 34),
         }
     }
+
+    /// Returns the `mmap` flags for HugeTLB pages
+    fn hugetlb_flags(&self) -> libc::c_int {
+        match self {
+            PageType::Huge2MB => libc::MAP_HUGETLB | libc::MAP_HUGE_2MB,
+            PageType::Huge16MB => libc::MAP_HUGETLB | libc::MAP_HUGE_16MB,
+            PageType::Huge1GB => libc::MAP_HUGETLB | libc::MAP_HUGE_1GB,
+            PageType::Huge16GB => libc::MAP_HUGETLB | libc::MAP_HUGE_16GB,
+            PageType::Default | PageType::Small | PageType::TransparentHuge => 0,
+        }
+    }
+
+    /// Returns the `madvise` advice that enables or disables transparent huge
+    /// pages
+    fn advice(&self) -> Option<libc::c_int> {
+        match self {
+            PageType::Small => Some(libc::MADV_NOHUGEPAGE),
+            PageType::TransparentHuge => Some(libc::MADV_HUGEPAGE),
+            PageType::Default
+            | PageType::Huge2MB
+            | PageType::Huge16MB
+            | PageType::Huge1GB
+            | PageType::Huge16GB => None,
+        }
+    }
 }
 
 /// Returns `x` rounded up to the page size
@@ -125,6 +158,98 @@ fn round_to_next_page(x: usize, page_size: usize) -> usize {
     (x + page_size - 1) & align_mask
 }
 
+/// Maps `size` bytes of anonymous memory with the specified page type.
+///
+/// `mmap` fails with `ENOMEM` if not enough huge pages are reserved, and with
+/// `EINVAL` if the kernel doesn't support the huge page size. Both cases are
+/// reported as `NoHugePages`.
+fn map_anonymous(size: usize, page_type: PageType) -> Result<*mut libc::c_void> {
+    let ptr = unsafe {
+        mmap(
+            ptr::null_mut(),
+            size,
+            libc::PROT_READ | libc::PROT_WRITE,
+            libc::MAP_PRIVATE | libc::MAP_ANONYMOUS | page_type.hugetlb_flags(),
+            0,
+            0,
+        )
+    };
+
+    if ptr == libc::MAP_FAILED {
+        let err = IoError::last_os_error();
+        let kind = match err.raw_os_error() {
+            Some(libc::ENOMEM) | Some(libc::EINVAL) if page_type.hugetlb_flags() != 0 => {
+                ErrorKind::NoHugePages(format!(
+                    "Failed to mmap {} bytes with {:?} pages; are enough huge pages reserved?",
+                    size, page_type
+                ))
+            }
+            Some(libc::ENOMEM) => ErrorKind::OutOfMemory(format!("Failed to mmap {} bytes", size)),
+            _ => ErrorKind::RuntimeError(format!("Failed to mmap {} bytes", size)),
+        };
+        Err(Error::with_chain(err, kind))?;
+    }
+
+    Ok(ptr)
+}
+
+/// Enables or disables transparent huge pages for the memory region.
+fn advise(ptr: *mut libc::c_void, size: usize, page_type: PageType) -> Result<()> {
+    if let Some(advice_flag) = page_type.advice() {
+        if unsafe { madvise(ptr, size, advice_flag) } == -1 {
+            let err = IoError::last_os_error();
+            let kind = match err.raw_os_error() {
+                Some(libc::EINVAL) if page_type == PageType::TransparentHuge => {
+                    ErrorKind::NoHugePages(
+                        "Transparent huge pages are not supported by the kernel".to_string(),
+                    )
+                }
+                _ => ErrorKind::RuntimeError("Failed to madvise memory".to_string()),
+            };
+            Err(Error::with_chain(err, kind))?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Binds the memory region to the NUMA node.
+///
+/// The region must be aligned to the page size. Thus, `mbind` fails with
+/// `EINVAL` only if the node doesn't exist. With the `MOVE` flag, pages that
+/// are already mapped are migrated to the node.
+fn bind_to_node<T>(data: &[T], node: u16, flags: MemBindFlags) -> Result<()> {
+    if node >= CpuSet::MAX_LEN {
+        Err(ErrorKind::InvalidNode(node))?;
+    }
+
+    let mut node_set = CpuSet::new();
+    node_set.add(node);
+
+    mbind(data, MemPolicyModes::BIND, node_set, flags).map_err(|err| {
+        let os_error = match err.kind() {
+            ErrorKind::Io(io_err) => io_err.raw_os_error(),
+            _ => None,
+        };
+        let kind = match os_error {
+            Some(libc::EINVAL) => ErrorKind::InvalidNode(node),
+            _ => ErrorKind::MbindRefused(format!("Failed to bind memory to NUMA node {}", node)),
+        };
+        Error::with_chain(err, kind)
+    })
+}
+
+/// Returns the `mlock` error, and hints at the cause of `ENOMEM`.
+fn mlock_error(err: IoError) -> Error {
+    let kind = match err.raw_os_error() {
+        Some(libc::ENOMEM) => ErrorKind::OutOfMemory(
+            "mlock() failed with ENOMEM; try setting 'memlock' to 'unlimited' in /etc/security/limits.conf".to_string(),
+        ),
+        _ => ErrorKind::RuntimeError("Failed to mlock memory".to_string()),
+    };
+    Error::with_chain(err, kind)
+}
+
 /// A contiguous memory region that is dynamically allocated on the specified
 /// NUMA node.
 #[derive(Debug)]
@@ -134,6 +259,7 @@ pub struct NumaMemory<T> {
     node: u16,
     page_type: PageType,
     is_memory_locked: bool,
+    #[cfg(feature = "cuda")]
     is_page_locked: bool,
 }
 
@@ -164,79 +290,65 @@ impl<T> NumaMemory<T> {
     ///
     /// `mmap` with `MMAP_ANONYMOUS` allocates pages. Separate alignment for cacheline alignment is
     /// not necessary.
+    ///
+    /// == Panics ==
+    ///
+    /// Panics if the allocation fails. See `try_new` for the fallible variant.
     pub fn new(len: usize, node: u16, page_type: PageType) -> Self {
-        assert_ne!(len, 0);
+        Self::try_new(len, node, page_type).expect("Failed to allocate NUMA memory")
+    }
 
-        let hugetlb_flags = match page_type {
-            PageType::Huge2MB => libc::MAP_HUGETLB | libc::MAP_HUGE_2MB,
-            PageType::Huge16MB => libc::MAP_HUGETLB | libc::MAP_HUGE_16MB,
-            PageType::Huge1GB => libc::MAP_HUGETLB | libc::MAP_HUGE_1GB,
-            PageType::Huge16GB => libc::MAP_HUGETLB | libc::MAP_HUGE_16GB,
-            PageType::Default | PageType::Small | PageType::TransparentHuge => 0,
-        };
+    /// Tries to allocate a new memory region with the specified capacity on the specified NUMA
+    /// node.
+    ///
+    /// Returns `OutOfMemory` if the memory cannot be mapped, `NoHugePages` if the requested huge
+    /// pages are not available, `InvalidNode` if the NUMA node doesn't exist, and `MbindRefused`
+    /// if the kernel refuses to bind the memory to the node.
+    ///
+    /// Huge pages are checked for availability before the allocation. If they are unavailable,
+    /// the allocation falls back to the page type set with `huge_pages::set_fallback`.
+    ///
+    /// See `new` for details on the page types.
+    pub fn try_new(len: usize, node: u16, page_type: PageType) -> Result<Self> {
+        if len == 0 {
+            Err(ErrorKind::InvalidArgument(
+                "Cannot allocate zero-length NUMA memory".to_string(),
+            ))?;
+        }
 
-        // Allocate memory with mmap
+        // Fall back to another page type if the huge pages are unavailable
         let size = len * size_of::<T>();
-        let pointer = unsafe {
-            mmap(
-                ptr::null_mut(),
-                size,
-                libc::PROT_READ | libc::PROT_WRITE,
-                libc::MAP_PRIVATE | libc::MAP_ANONYMOUS | hugetlb_flags,
-                0,
-                0,
-            )
-        };
-        if pointer == libc::MAP_FAILED {
-            std::result::Result::Err::<(), _>(IoError::last_os_error())
-                .expect("Failed to mmap memory");
-        }
+        let page_type = huge_pages::preflight(page_type, &[NodeLen { node, len: size }])?;
 
-        // Enable or disable transparent transparent huge pages
-        let advice = match page_type {
-            PageType::Small => Some(libc::MADV_NOHUGEPAGE),
-            PageType::TransparentHuge => Some(libc::MADV_HUGEPAGE),
-            PageType::Default
-            | PageType::Huge2MB
-            | PageType::Huge16MB
-            | PageType::Huge1GB
-            | PageType::Huge16GB => None,
-        };
+        // Allocate memory with mmap
+        let pointer = map_anonymous(size, page_type)?;
 
-        if let Some(advice_flag) = advice {
-            unsafe {
-                if madvise(pointer, size, advice_flag) == -1 {
-                    let err = IoError::last_os_error();
-                    std::result::Result::Err::<(), _>(err).expect("Failed to madvise memory");
-                }
-            }
-        }
+        // From here on, drop() unmaps the memory if an error occurs
+        let mem = Self {
+            pointer: pointer as *mut T,
+            len,
+            node,
+            page_type,
+            is_memory_locked: false,
+            #[cfg(feature = "cuda")]
+            is_page_locked: false,
+        };
 
-        // Set up the NUMA node set for mbind
-        let mut node_set = CpuSet::new();
-        node_set.add(node);
+        // Enable or disable transparent transparent huge pages
+        advise(pointer, size, page_type)?;
 
         // mbind fails with `EINVAL` for HugeTLB mappings if `size` isn't a
         // multiple of the page size
-        let page_size = page_type.page_size().expect("Failed to get the page size");
+        let page_size = page_type.page_size()?;
         let aligned_size = round_to_next_page(size, page_size);
 
         // Bind to the NUMA node
         unsafe {
-            let slice = slice::from_raw_parts(pointer, aligned_size);
-
-            mbind(slice, MemPolicyModes::BIND, node_set, MemBindFlags::STRICT)
-                .expect("Failed to bind memory to NUMA node.");
+            let slice = slice::from_raw_parts(pointer as *const u8, aligned_size);
+            bind_to_node(slice, node, MemBindFlags::STRICT)?;
         }
 
-        Self {
-            pointer: pointer as *mut T,
-            len,
-            node,
-            page_type,
-            is_memory_locked: false,
-            is_page_locked: false,
-        }
+        Ok(mem)
     }
 
     /// Extracts a slice of the entire memory region.
@@ -253,6 +365,40 @@ impl<T> NumaMemory<T> {
     pub fn node(&self) -> u16 {
         self.node
     }
+
+    /// Returns the page type that the memory region is allocated with.
+    ///
+    /// The page type differs from the requested page type if the allocation
+    /// fell back to another page type.
+    pub fn page_type(&self) -> PageType {
+        self.page_type
+    }
+
+    /// Queries the NUMA nodes on which the pages actually reside.
+    pub fn page_residency(&self) -> Result<PageResidency> {
+        let page_size = self.page_type.page_size()?;
+        page_residency_with_page_size(self.as_slice(), page_size)
+    }
+
+    /// Migrates the memory region to another NUMA node.
+    ///
+    /// Pages that are already mapped are moved to the node, and pages that
+    /// are mapped in future are allocated on the node. Migration fails with
+    /// `MbindRefused` if a page cannot be moved, e.g., because it is
+    /// page-locked by CUDA.
+    pub fn migrate(&mut self, node: u16) -> Result<()> {
+        let size = self.len * size_of::<T>();
+        let page_size = self.page_type.page_size()?;
+        let aligned_size = round_to_next_page(size, page_size);
+
+        unsafe {
+            let slice = slice::from_raw_parts(self.pointer as *const u8, aligned_size);
+            bind_to_node(slice, node, MemBindFlags::STRICT | MemBindFlags::MOVE)?;
+        }
+        self.node = node;
+
+        Ok(())
+    }
 }
 
 impl<T> Deref for NumaMemory<T> {
@@ -275,14 +421,7 @@ impl<T> MemLock for NumaMemory<T> {
             let size = self.len * size_of::<T>();
             unsafe {
                 if mlock(self.pointer as *mut libc::c_void, size) == -1 {
-                    let err = IoError::last_os_error();
-                    if let Some(code) = err.raw_os_error() {
-                        if code == libc::ENOMEM {
-                            eprintln!("mlock() failed with ENOMEM; try setting 'memlock' to 'unlimited' in /etc/security/limits.conf");
-                        }
-                    }
-
-                    std::result::Result::Err::<(), _>(err).expect("Failed to mlock memory");
+                    Err(mlock_error(IoError::last_os_error()))?;
                 }
             }
             self.is_memory_locked = true;
@@ -296,8 +435,10 @@ impl<T> MemLock for NumaMemory<T> {
             let size = self.len * size_of::<T>();
             unsafe {
                 if munlock(self.pointer as *mut libc::c_void, size) == -1 {
-                    std::result::Result::Err::<(), _>(IoError::last_os_error())
-                        .expect("Failed to munlock memory");
+                    Err(Error::with_chain(
+                        IoError::last_os_error(),
+                        "Failed to munlock memory",
+                    ))?;
                 }
             }
         }
@@ -313,6 +454,7 @@ impl<T> MemProtect for NumaMemory<T> {
     }
 }
 
+#[cfg(feature = "cuda")]
 impl<T> PageLock for NumaMemory<T> {
     fn page_lock(&mut self) -> Result<()> {
         unsafe {
@@ -339,6 +481,7 @@ impl<T> Drop for NumaMemory<T> {
     fn drop(&mut self) {
         // Unregister if memory is page-locked to uphold the invariant.
         // In drop() method, we can only handle error by panicking.
+        #[cfg(feature = "cuda")]
         if self.is_page_locked {
             unsafe {
                 host_unregister(self.as_slice()).unwrap();
@@ -386,6 +529,291 @@ pub struct NodeLen {
     pub len: usize,
 }
 
+/// Specifies a range of elements that is allocated on the NUMA node
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct NodeRange {
+    /// The NUMA node
+    pub node: u16,
+
+    /// The range of element indices
+    pub range: Range<usize>,
+}
+
+impl NodeRange {
+    /// Splits `len` elements into contiguous ranges proportionally to the node
+    /// ratios.
+    ///
+    /// The ranges are in the order of the ratios, which matches the page
+    /// placement of `DistributedNumaMemory`.
+    pub fn from_node_ratios(len: usize, node_ratios: &[NodeRatio]) -> Vec<Self> {
+        node_ratios
+            .iter()
+            .enumerate()
+            .scan(
+                (Ratio::<usize>::from_integer(0), 0),
+                |(ratio_seen, start), (i, &NodeRatio { node, ratio })| {
+                    *ratio_seen += ratio;
+                    let end = if i + 1 == node_ratios.len() {
+                        // The last node gets the remainder
+                        len
+                    } else {
+                        cmp::min((*ratio_seen * len).to_integer(), len)
+                    };
+                    let range = *start..end;
+                    *start = end;
+
+                    Some(NodeRange { node, range })
+                },
+            )
+            .collect()
+    }
+}
+
+/// Returns an error if the node ratios don't sum up to 1.
+fn check_node_ratios(node_ratios: &[NodeRatio]) -> Result<()> {
+    let total: Ratio<usize> = node_ratios.iter().map(|n| n.ratio).sum();
+    if total != 1.into() {
+        Err(ErrorKind::InvalidArgument(format!(
+            "The node ratios sum up to {}, but must sum up to 1",
+            total
+        )))?;
+    }
+
+    Ok(())
+}
+
+/// Splits `pages` into the number of pages per node.
+fn ratios_to_pages(node_ratios: &[NodeRatio], pages: usize) -> Box<[NodeLen]> {
+    // Scale the specified ratios by the number of pages and round down to
+    // the nearest integer
+    let mut scaled_ratios: Box<[NodeRatio]> = node_ratios
+        .iter()
+        .map(|NodeRatio { node, ratio }| NodeRatio {
+            node: *node,
+            ratio: (*ratio * pages).trunc(),
+        })
+        .collect();
+
+    // Ensure that we still account for all pages after rounding down above
+    let pages_diff = Ratio::<usize>::from_integer(pages)
+        - scaled_ratios
+            .iter()
+            .map(|node| node.ratio)
+            .sum::<Ratio<usize>>();
+    if pages_diff != 0.into() {
+        scaled_ratios[0].ratio += pages_diff;
+    }
+
+    scaled_ratios
+        .iter()
+        .map(|&NodeRatio { node, ratio }| NodeLen {
+            node,
+            len: ratio.to_integer(),
+        })
+        .collect()
+}
+
+/// The NUMA nodes on which the pages of a memory region actually reside
+///
+/// In contrast to the requested placement, the residency is measured by asking
+/// the kernel for the node of each page.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct PageResidency {
+    /// The number of bytes that reside on each NUMA node
+    pub node_bytes: BTreeMap<u16, usize>,
+
+    /// The number of bytes in pages that are not yet mapped, i.e., that
+    /// haven't been touched since the allocation
+    pub unmapped_bytes: usize,
+}
+
+impl PageResidency {
+    /// Returns the NUMA nodes that hold at least one page, in ascending order.
+    pub fn nodes(&self) -> Vec<u16> {
+        self.node_bytes.keys().copied().collect()
+    }
+
+    /// Returns the number of bytes per node, in the order of `nodes()`.
+    pub fn bytes(&self) -> Vec<usize> {
+        self.node_bytes.values().copied().collect()
+    }
+
+    /// Adds the residency of another memory region.
+    pub fn merge(&mut self, other: &Self) {
+        for (&node, &bytes) in other.node_bytes.iter() {
+            *self.node_bytes.entry(node).or_insert(0) += bytes;
+        }
+        self.unmapped_bytes += other.unmapped_bytes;
+    }
+}
+
+/// The maximum number of pages per `move_pages` call
+///
+/// Batching bounds the size of the page and status arrays for large memory
+/// regions.
+const MOVE_PAGES_BATCH: usize = 1 << 16;
+
+/// Returns the start address of each page that overlaps with `data`.
+fn page_addresses<T>(data: &[T], page_size: usize) -> Vec<*const libc::c_void> {
+    if data.is_empty() {
+        return Vec::new();
+    }
+
+    let start = data.as_ptr() as usize;
+    let end = start + data.len() * size_of::<T>();
+    let first_page = start & !(page_size - 1);
+
+    (first_page..end)
+        .step_by(page_size)
+        .map(|page| page as *const libc::c_void)
+        .collect()
+}
+
+/// Converts a negative page status of `move_pages` into an error.
+fn page_status_error(status: libc::c_int, page: *const libc::c_void) -> Error {
+    Error::with_chain(
+        IoError::from_raw_os_error(-status),
+        ErrorKind::RuntimeError(format!("Failed to move page {:p}", page)),
+    )
+}
+
+/// Queries the NUMA nodes on which the pages of `data` actually reside.
+///
+/// Works for any host memory, including memory that isn't allocated by
+/// `NumaMemory` or `DistributedNumaMemory`. Pages that are only partially
+/// covered by `data` count only the covered bytes.
+pub fn page_residency<T>(data: &[T]) -> Result<PageResidency> {
+    page_residency_with_page_size(data, ProcessorCache::page_size())
+}
+
+fn page_residency_with_page_size<T>(data: &[T], page_size: usize) -> Result<PageResidency> {
+    let start = data.as_ptr() as usize;
+    let end = start + data.len() * size_of::<T>();
+    let pages = page_addresses(data, page_size);
+
+    let mut residency = PageResidency::default();
+    let mut status = vec![0; cmp::min(pages.len(), MOVE_PAGES_BATCH)];
+
+    for batch in pages.chunks(MOVE_PAGES_BATCH) {
+        let status = &mut status[0..batch.len()];
+        move_pages(batch, None, status, MemBindFlags::DEFAULT)?;
+
+        for (&page, &node) in batch.iter().zip(status.iter()) {
+            let page_start = cmp::max(page as usize, start);
+            let page_end = cmp::min(page as usize + page_size, end);
+            let bytes = page_end - page_start;
+
+            match node {
+                node if node >= 0 => *residency.node_bytes.entry(node as u16).or_insert(0) += bytes,
+                status if status == -libc::ENOENT => residency.unmapped_bytes += bytes,
+                status => Err(page_status_error(status, page))?,
+            }
+        }
+    }
+
+    Ok(residency)
+}
+
+/// Queries the contiguous ranges of `data` that reside on the same NUMA node.
+///
+/// In contrast to `page_residency`, the ranges retain the location of the
+/// pages within `data`. An element belongs to the page that it starts in.
+/// Pages that are not yet mapped are attributed to the preceding range, or to
+/// the following range at the start of `data`. Thus, the ranges cover all of
+/// `data`, unless no page is mapped at all.
+pub fn node_ranges<T>(data: &[T]) -> Result<Vec<NodeRange>> {
+    node_ranges_with_page_size(data, ProcessorCache::page_size())
+}
+
+fn node_ranges_with_page_size<T>(data: &[T], page_size: usize) -> Result<Vec<NodeRange>> {
+    let start = data.as_ptr() as usize;
+    let element_size = cmp::max(size_of::<T>(), 1);
+    let pages = page_addresses(data, page_size);
+
+    let mut ranges: Vec<NodeRange> = Vec::new();
+    let mut status = vec![0; cmp::min(pages.len(), MOVE_PAGES_BATCH)];
+
+    for batch in pages.chunks(MOVE_PAGES_BATCH) {
+        let status = &mut status[0..batch.len()];
+        move_pages(batch, None, status, MemBindFlags::DEFAULT)?;
+
+        for (&page, &node) in batch.iter().zip(status.iter()) {
+            // The first element that starts in the page
+            let first = cmp::min(
+                ((page as usize).saturating_sub(start) + element_size - 1) / element_size,
+                data.len(),
+            );
+
+            match node {
+                node if node >= 0 => {
+                    let node = node as u16;
+                    match ranges.last_mut() {
+                        Some(last) if last.node == node => {}
+                        Some(last) if last.range.start == first => last.node = node,
+                        Some(last) => {
+                            last.range.end = first;
+                            ranges.push(NodeRange {
+                                node,
+                                range: first..first,
+                            });
+                        }
+                        None => ranges.push(NodeRange { node, range: 0..0 }),
+                    }
+                }
+                status if status == -libc::ENOENT => {}
+                status => Err(page_status_error(status, page))?,
+            }
+        }
+    }
+
+    if let Some(last) = ranges.last_mut() {
+        last.range.end = data.len();
+    }
+
+    Ok(ranges)
+}
+
+/// Migrates the pages of `data` to the NUMA nodes.
+///
+/// The pages are distributed proportionally to the node ratios. In contrast
+/// to `NumaMemory::migrate`, `data` need not be aligned to the page size.
+/// Thus, the first and last pages can contain memory outside of `data`, which
+/// is migrated as well. Pages that are not yet mapped are skipped, and the
+/// memory policy of `data` is left unchanged.
+pub fn migrate_pages<T>(data: &[T], node_ratios: &[NodeRatio]) -> Result<()> {
+    check_node_ratios(node_ratios)?;
+    if let Some(&NodeRatio { node, .. }) = node_ratios.iter().find(|n| n.node >= CpuSet::MAX_LEN) {
+        Err(ErrorKind::InvalidNode(node))?;
+    }
+
+    let page_size = ProcessorCache::page_size();
+    let pages = page_addresses(data, page_size);
+    let nodes: Vec<libc::c_int> = ratios_to_pages(node_ratios, pages.len())
+        .iter()
+        .flat_map(|&NodeLen { node, len }| std::iter::repeat(node.into()).take(len))
+        .collect();
+
+    let mut status = vec![0; cmp::min(pages.len(), MOVE_PAGES_BATCH)];
+
+    for (batch, batch_nodes) in pages
+        .chunks(MOVE_PAGES_BATCH)
+        .zip(nodes.chunks(MOVE_PAGES_BATCH))
+    {
+        let status = &mut status[0..batch.len()];
+        move_pages(batch, Some(batch_nodes), status, MemBindFlags::MOVE)?;
+
+        if let Some((&page, &status)) = batch
+            .iter()
+            .zip(status.iter())
+            .find(|(_, &status)| status < 0 && status != -libc::ENOENT)
+        {
+            Err(page_status_error(status, page))?;
+        }
+    }
+
+    Ok(())
+}
+
 /// A contiguous memory region that is dynamically allocated on multiple NUMA
 /// nodes.
 ///
@@ -398,6 +826,7 @@ pub struct DistributedNumaMemory<T> {
     node_ratios: Box<[NodeRatio]>,
     page_type: PageType,
     is_memory_locked: bool,
+    #[cfg(feature = "cuda")]
     is_page_locked: bool,
 }
 
@@ -410,15 +839,51 @@ impl<T> DistributedNumaMemory<T> {
     /// lengths are rounded to a page.
     ///
     /// Note that the sum of all node lengths must equal `len`.
+    ///
+    /// Panics if the allocation fails. See `try_new_with_len` for the fallible
+    /// variant.
     pub fn new_with_len(len: usize, node_lengths: Box<[NodeLen]>, page_type: PageType) -> Self {
-        assert_ne!(len, 0);
+        Self::try_new_with_len(len, node_lengths, page_type)
+            .expect("Failed to allocate distributed NUMA memory")
+    }
+
+    /// Tries to allocate a new memory region.
+    ///
+    /// Returns an error instead of panicking if the allocation fails. See
+    /// `new_with_len` for details, and `NumaMemory::try_new` for the error
+    /// kinds.
+    pub fn try_new_with_len(
+        len: usize,
+        node_lengths: Box<[NodeLen]>,
+        page_type: PageType,
+    ) -> Result<Self> {
+        if len == 0 {
+            Err(ErrorKind::InvalidArgument(
+                "Cannot allocate zero-length NUMA memory".to_string(),
+            ))?;
+        }
         {
             let total: usize = node_lengths.iter().map(|n| n.len).sum();
-            assert_eq!(total, len);
+            if total != len {
+                Err(ErrorKind::InvalidArgument(format!(
+                    "The node lengths sum up to {}, but the total length is {}",
+                    total, len
+                )))?;
+            }
         }
 
+        // Fall back to another page type if the huge pages are unavailable
+        let node_bytes: Box<[NodeLen]> = node_lengths
+            .iter()
+            .map(|&NodeLen { node, len }| NodeLen {
+                node,
+                len: len * size_of::<T>(),
+            })
+            .collect();
+        let page_type = huge_pages::preflight(page_type, &node_bytes)?;
+
         let size = len * size_of::<T>();
-        let page_size = page_type.page_size().expect("Failed to get the page size");
+        let page_size = page_type.page_size()?;
         let total_pages = (size + page_size - 1) / page_size;
 
         // Round number of pages up
@@ -440,7 +905,7 @@ impl<T> DistributedNumaMemory<T> {
             })
             .collect();
 
-        Self::new_with_pages(len, node_pages, page_type)
+        Self::try_new_with_pages(len, node_pages, page_type)
     }
 
     /// Allocates a new memory region.
@@ -450,97 +915,76 @@ impl<T> DistributedNumaMemory<T> {
     /// ratios, because the allocation granularity is a page.
     ///
     /// Note that the sum of all ratios must equal 1.
+    ///
+    /// Panics if the allocation fails. See `try_new_with_ratio` for the
+    /// fallible variant.
     pub fn new_with_ratio(len: usize, node_ratios: Box<[NodeRatio]>, page_type: PageType) -> Self {
-        assert_ne!(len, 0);
-        {
-            let total: Ratio<usize> = node_ratios.iter().map(|n| n.ratio).sum();
-            assert_eq!(total, 1.into());
-        }
-
-        // Calculate number of pages, rounded up
-        let size = len * size_of::<T>();
-        let page_size = page_type.page_size().expect("Failed to get the page size");
-        let pages = (size + page_size - 1) / page_size;
-
-        // Scale the specified ratios by the number of pages and round down to
-        // the nearest integer
-        let mut scaled_ratios: Box<[NodeRatio]> = node_ratios
-            .iter()
-            .map(|NodeRatio { node, ratio }| NodeRatio {
-                node: *node,
-                ratio: (*ratio * pages).trunc(),
-            })
-            .collect();
+        Self::try_new_with_ratio(len, node_ratios, page_type)
+            .expect("Failed to allocate distributed NUMA memory")
+    }
 
-        // Ensure that we still account for all pages after rounding down above
-        let pages_diff = Ratio::<usize>::from_integer(pages)
-            - scaled_ratios
-                .iter()
-                .map(|node| node.ratio)
-                .sum::<Ratio<usize>>();
-        if pages_diff != 0.into() {
-            scaled_ratios[0].ratio += pages_diff;
+    /// Tries to allocate a new memory region.
+    ///
+    /// Returns an error instead of panicking if the allocation fails. See
+    /// `new_with_ratio` for details, and `NumaMemory::try_new` for the error
+    /// kinds.
+    pub fn try_new_with_ratio(
+        len: usize,
+        node_ratios: Box<[NodeRatio]>,
+        page_type: PageType,
+    ) -> Result<Self> {
+        if len == 0 {
+            Err(ErrorKind::InvalidArgument(
+                "Cannot allocate zero-length NUMA memory".to_string(),
+            ))?;
         }
+        check_node_ratios(&node_ratios)?;
 
-        let node_pages: Box<[NodeLen]> = scaled_ratios
+        // Fall back to another page type if the huge pages are unavailable
+        let size = len * size_of::<T>();
+        let node_bytes: Box<[NodeLen]> = node_ratios
             .iter()
             .map(|&NodeRatio { node, ratio }| NodeLen {
                 node,
-                len: ratio.to_integer(),
+                len: (ratio * size).ceil().to_integer(),
             })
             .collect();
+        let page_type = huge_pages::preflight(page_type, &node_bytes)?;
 
-        Self::new_with_pages(len, node_pages, page_type)
-    }
+        // Calculate number of pages, rounded up
+        let page_size = page_type.page_size()?;
+        let pages = (size + page_size - 1) / page_size;
 
-    fn new_with_pages(len: usize, node_pages: Box<[NodeLen]>, page_type: PageType) -> Self {
-        let hugetlb_flags = match page_type {
-            PageType::Huge2MB => libc::MAP_HUGETLB | libc::MAP_HUGE_2MB,
-            PageType::Huge16MB => libc::MAP_HUGETLB | libc::MAP_HUGE_16MB,
-            PageType::Huge1GB => libc::MAP_HUGETLB | libc::MAP_HUGE_1GB,
-            PageType::Huge16GB => libc::MAP_HUGETLB | libc::MAP_HUGE_16GB,
-            PageType::Default | PageType::Small | PageType::TransparentHuge => 0,
-        };
+        let node_pages = ratios_to_pages(&node_ratios, pages);
+
+        Self::try_new_with_pages(len, node_pages, page_type)
+    }
 
+    fn try_new_with_pages(
+        len: usize,
+        node_pages: Box<[NodeLen]>,
+        page_type: PageType,
+    ) -> Result<Self> {
         // Allocate memory with mmap
         let size = len * size_of::<T>();
-        let ptr = unsafe {
-            mmap(
-                ptr::null_mut(),
-                size,
-                libc::PROT_READ | libc::PROT_WRITE,
-                libc::MAP_PRIVATE | libc::MAP_ANONYMOUS | hugetlb_flags,
-                0,
-                0,
-            )
-        };
-        if ptr == libc::MAP_FAILED {
-            std::result::Result::Err::<(), _>(IoError::last_os_error())
-                .expect("Failed to mmap memory");
-        }
+        let ptr = map_anonymous(size, page_type)?;
 
-        // Enable or disable transparent transparent huge pages
-        let advice = match page_type {
-            PageType::Small => Some(libc::MADV_NOHUGEPAGE),
-            PageType::TransparentHuge => Some(libc::MADV_HUGEPAGE),
-            PageType::Default
-            | PageType::Huge2MB
-            | PageType::Huge16MB
-            | PageType::Huge1GB
-            | PageType::Huge16GB => None,
+        // From here on, drop() unmaps the memory if an error occurs
+        let mut mem = Self {
+            ptr: ptr as *mut T,
+            len,
+            node_ratios: Box::new([]),
+            page_type,
+            is_memory_locked: false,
+            #[cfg(feature = "cuda")]
+            is_page_locked: false,
         };
 
-        if let Some(advice_flag) = advice {
-            unsafe {
-                if madvise(ptr, size, advice_flag) == -1 {
-                    let err = IoError::last_os_error();
-                    std::result::Result::Err::<(), _>(err).expect("Failed to madvise memory");
-                }
-            }
-        }
+        // Enable or disable transparent transparent huge pages
+        advise(ptr, size, page_type)?;
 
         // Calculate number of pages, rounded up
-        let page_size = page_type.page_size().expect("Failed to get the page size");
+        let page_size = page_type.page_size()?;
         let aligned_size = round_to_next_page(size, page_size);
         let pages = aligned_size / page_size;
 
@@ -549,8 +993,18 @@ impl<T> DistributedNumaMemory<T> {
             assert_eq!(pages, pages_sum);
         }
 
-        // Bind all pages to their NUMA nodes, and calculate the actual ratios
-        let final_node_ratios = node_pages
+        mem.node_ratios = mem.bind_pages(&node_pages, MemBindFlags::STRICT)?;
+
+        Ok(mem)
+    }
+
+    /// Binds all pages to their NUMA nodes, and returns the actual ratios.
+    fn bind_pages(&self, node_pages: &[NodeLen], flags: MemBindFlags) -> Result<Box<[NodeRatio]>> {
+        let ptr = self.ptr as *const u8;
+        let page_size = self.page_type.page_size()?;
+        let pages: usize = node_pages.iter().map(|n| n.len).sum();
+
+        node_pages
             .iter()
             .scan(
                 0,
@@ -565,9 +1019,6 @@ impl<T> DistributedNumaMemory<T> {
                 },
             )
             .map(|(node, page_offset, page_len)| {
-                let mut node_set = CpuSet::new();
-                node_set.add(node);
-
                 unsafe {
                     let slice = slice::from_raw_parts(
                         ptr.add(page_offset as usize * page_size),
@@ -576,7 +1027,7 @@ impl<T> DistributedNumaMemory<T> {
 
                     // Note that mbind fails with `EINVAL` for HugeTLB mappings if `size`
                     // isn't a multiple of the page size
-                    mbind(slice, MemPolicyModes::BIND, node_set, MemBindFlags::STRICT)?;
+                    bind_to_node(slice, node, flags)?;
                 }
 
                 Ok(NodeRatio {
@@ -585,17 +1036,6 @@ impl<T> DistributedNumaMemory<T> {
                 })
             })
             .collect::<Result<Box<[NodeRatio]>>>()
-            .expect("Failed to mbind memory to the specified NUMA nodes");
-
-        // Return self
-        Self {
-            ptr: ptr as *mut T,
-            len,
-            node_ratios: final_node_ratios,
-            page_type,
-            is_memory_locked: false,
-            is_page_locked: false,
-        }
     }
 
     /// Extracts a slice of the entire memory region.
@@ -612,12 +1052,53 @@ impl<T> DistributedNumaMemory<T> {
     pub fn node_ratios(&self) -> &[NodeRatio] {
         &self.node_ratios
     }
+
+    /// Returns the element ranges that are allocated on each NUMA node.
+    ///
+    /// The ranges are derived from the node ratios, and are thus accurate up
+    /// to the page granularity. See `node_ranges` to query the actual
+    /// placement.
+    pub fn node_ranges(&self) -> Vec<NodeRange> {
+        NodeRange::from_node_ratios(self.len, &self.node_ratios)
+    }
+
+    /// Returns the page type that the memory region is allocated with.
+    ///
+    /// See `NumaMemory::page_type` for details.
+    pub fn page_type(&self) -> PageType {
+        self.page_type
+    }
+
+    /// Queries the NUMA nodes on which the pages actually reside.
+    pub fn page_residency(&self) -> Result<PageResidency> {
+        let page_size = self.page_type.page_size()?;
+        page_residency_with_page_size(self.as_slice(), page_size)
+    }
+
+    /// Migrates the memory region to the NUMA nodes.
+    ///
+    /// The memory is redistributed proportionally to the node ratios, in the
+    /// same way as by `new_with_ratio`. See `NumaMemory::migrate` for details.
+    pub fn migrate(&mut self, node_ratios: &[NodeRatio]) -> Result<()> {
+        check_node_ratios(node_ratios)?;
+
+        let size = self.len * size_of::<T>();
+        let page_size = self.page_type.page_size()?;
+        let pages = (size + page_size - 1) / page_size;
+
+        let node_pages = ratios_to_pages(node_ratios, pages);
+        self.node_ratios =
+            self.bind_pages(&node_pages, MemBindFlags::STRICT | MemBindFlags::MOVE)?;
+
+        Ok(())
+    }
 }
 
 impl<T> Drop for DistributedNumaMemory<T> {
     fn drop(&mut self) {
         // Unregister if memory is page-locked to uphold the invariant.
         // In drop() method, we can only handle error by panicking.
+        #[cfg(feature = "cuda")]
         if self.is_page_locked {
             unsafe {
                 host_unregister(self.as_slice()).unwrap();
@@ -665,14 +1146,7 @@ impl<T> MemLock for DistributedNumaMemory<T> {
             let size = self.len * size_of::<T>();
             unsafe {
                 if mlock(self.ptr as *mut libc::c_void, size) == -1 {
-                    let err = IoError::last_os_error();
-                    if let Some(code) = err.raw_os_error() {
-                        if code == libc::ENOMEM {
-                            eprintln!("mlock() failed with ENOMEM; try setting 'memlock' to 'unlimited' in /etc/security/limits.conf");
-                        }
-                    }
-
-                    std::result::Result::Err::<(), _>(err).expect("Failed to mlock memory");
+                    Err(mlock_error(IoError::last_os_error()))?;
                 }
             }
             self.is_memory_locked = true;
@@ -686,8 +1160,10 @@ impl<T> MemLock for DistributedNumaMemory<T> {
             let size = self.len * size_of::<T>();
             unsafe {
                 if munlock(self.ptr as *mut libc::c_void, size) == -1 {
-                    std::result::Result::Err::<(), _>(IoError::last_os_error())
-                        .expect("Failed to munlock memory");
+                    Err(Error::with_chain(
+                        IoError::last_os_error(),
+                        "Failed to munlock memory",
+                    ))?;
                 }
             }
         }
@@ -703,6 +1179,7 @@ impl<T> MemProtect for DistributedNumaMemory<T> {
     }
 }
 
+#[cfg(feature = "cuda")]
 impl<T> PageLock for DistributedNumaMemory<T> {
     fn page_lock(&mut self) -> Result<()> {
         unsafe {
@@ -724,3 +1201,196 @@ impl<T> PageLock for DistributedNumaMemory<T> {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn try_new_on_node_0() -> Result<()> {
+        let mut mem = NumaMemory::<u64>::try_new(1024, 0, PageType::Default)?;
+        mem.iter_mut().enumerate().for_each(|(i, x)| *x = i as u64);
+
+        assert_eq!(mem.node(), 0);
+        assert_eq!(mem[1023], 1023);
+
+        Ok(())
+    }
+
+    #[test]
+    fn try_new_with_zero_len_fails() {
+        let result = NumaMemory::<u64>::try_new(0, 0, PageType::Default);
+
+        assert!(matches!(
+            result.map_err(|e| e.0),
+            Err(ErrorKind::InvalidArgument(_))
+        ));
+    }
+
+    #[test]
+    fn try_new_on_invalid_node_fails() {
+        // Larger than the maximum node ID supported by the kernel
+        let result = NumaMemory::<u64>::try_new(1024, 1000, PageType::Default);
+        assert!(matches!(
+            result.map_err(|e| e.0),
+            Err(ErrorKind::InvalidNode(1000))
+        ));
+
+        // Larger than the maximum node ID supported by `CpuSet`
+        let result = NumaMemory::<u64>::try_new(1024, u16::MAX, PageType::Default);
+        assert!(matches!(
+            result.map_err(|e| e.0),
+            Err(ErrorKind::InvalidNode(u16::MAX))
+        ));
+    }
+
+    #[test]
+    fn try_new_with_ratio_on_invalid_node_fails() {
+        let nodes = Box::new([
+            NodeRatio {
+                node: 0,
+                ratio: Ratio::new(1, 2),
+            },
+            NodeRatio {
+                node: 1000,
+                ratio: Ratio::new(1, 2),
+            },
+        ]);
+        let len = 4 * ProcessorCache::page_size() / size_of::<u64>();
+
+        let result = DistributedNumaMemory::<u64>::try_new_with_ratio(len, nodes, PageType::Small);
+        assert!(matches!(
+            result.map_err(|e| e.0),
+            Err(ErrorKind::InvalidNode(1000))
+        ));
+    }
+
+    #[test]
+    fn try_new_with_ratio_rejects_invalid_ratios() {
+        let nodes = Box::new([NodeRatio {
+            node: 0,
+            ratio: Ratio::new(1, 2),
+        }]);
+
+        let result =
+            DistributedNumaMemory::<u64>::try_new_with_ratio(1024, nodes, PageType::Default);
+        assert!(matches!(
+            result.map_err(|e| e.0),
+            Err(ErrorKind::InvalidArgument(_))
+        ));
+    }
+
+    #[test]
+    fn page_residency_counts_mapped_and_unmapped_bytes() -> Result<()> {
+        let page_size = ProcessorCache::page_size();
+        let len = 4 * page_size / size_of::<u64>();
+        let mut mem = NumaMemory::<u64>::try_new(len, 0, PageType::Small)?;
+
+        let residency = mem.page_residency()?;
+        assert!(residency.node_bytes.is_empty());
+        assert_eq!(residency.unmapped_bytes, 4 * page_size);
+
+        // Touch the first two pages
+        mem[0..(len / 2)].iter_mut().for_each(|x| *x = 1);
+
+        let residency = mem.page_residency()?;
+        assert_eq!(residency.nodes(), vec![0]);
+        assert_eq!(residency.bytes(), vec![2 * page_size]);
+        assert_eq!(residency.unmapped_bytes, 2 * page_size);
+
+        Ok(())
+    }
+
+    #[test]
+    fn page_residency_of_unaligned_slice() -> Result<()> {
+        let data = vec![1_u8; 3 * ProcessorCache::page_size() + 7];
+        let slice = &data[5..];
+
+        let residency = page_residency(slice)?;
+        let resident_bytes: usize = residency.node_bytes.values().sum();
+        assert_eq!(resident_bytes + residency.unmapped_bytes, slice.len());
+
+        Ok(())
+    }
+
+    #[test]
+    fn migrate_to_node_0() -> Result<()> {
+        let page_size = ProcessorCache::page_size();
+        let len = 4 * page_size / size_of::<u64>();
+        let mut mem = NumaMemory::<u64>::try_new(len, 0, PageType::Small)?;
+        mem.iter_mut().for_each(|x| *x = 1);
+
+        mem.migrate(0)?;
+
+        assert_eq!(mem.node(), 0);
+        assert_eq!(mem.page_residency()?.bytes(), vec![4 * page_size]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn node_ranges_from_node_ratios() {
+        let nodes = [
+            NodeRatio {
+                node: 1,
+                ratio: Ratio::new(1, 3),
+            },
+            NodeRatio {
+                node: 0,
+                ratio: Ratio::new(2, 3),
+            },
+        ];
+
+        let ranges = NodeRange::from_node_ratios(100, &nodes);
+        assert_eq!(
+            ranges,
+            vec![
+                NodeRange {
+                    node: 1,
+                    range: 0..33
+                },
+                NodeRange {
+                    node: 0,
+                    range: 33..100
+                }
+            ]
+        );
+    }
+
+    #[test]
+    fn node_ranges_cover_touched_memory() -> Result<()> {
+        let page_size = ProcessorCache::page_size();
+        let len = 4 * page_size / size_of::<u64>();
+        let mut mem = NumaMemory::<u64>::try_new(len, 0, PageType::Small)?;
+
+        assert!(node_ranges(mem.as_slice())?.is_empty());
+
+        // Touch the last two pages
+        mem[(len / 2)..].iter_mut().for_each(|x| *x = 1);
+
+        assert_eq!(
+            node_ranges(mem.as_slice())?,
+            vec![NodeRange {
+                node: 0,
+                range: 0..len
+            }]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn migrate_pages_rejects_invalid_node() {
+        let data = vec![1_u64; 1024];
+        let nodes = [NodeRatio {
+            node: u16::MAX,
+            ratio: Ratio::from_integer(1),
+        }];
+
+        let result = migrate_pages(&data, &nodes);
+        assert!(matches!(
+            result.map_err(|e| e.0),
+            Err(ErrorKind::InvalidNode(u16::MAX))
+        ));
+    }
+}
@@ -139,6 +139,12 @@ impl Range {
     }
 }
 
+impl From<RangeId> for u64 {
+    fn from(id: RangeId) -> Self {
+        id.0
+    }
+}
+
 impl fmt::Display for RangeId {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         self.0.fmt(f)
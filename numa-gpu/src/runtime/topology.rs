@@ -0,0 +1,549 @@
+// Copyright 2022 Clemens Lutz
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Discovers the NUMA topology of the machine.
+//!
+//! The topology consists of the NUMA nodes, their CPUs, the SMT siblings of
+//! each CPU, and the distances between nodes. It is parsed from Linux's sysfs,
+//! i.e., from `/sys/devices/system/node` and `/sys/devices/system/cpu`.
+//!
+//! Based on the topology, an `AffinityPolicy` generates a `CpuAffinity`. Thus,
+//! a machine doesn't require a hand-written affinity file. The generated
+//! affinity only contains the CPUs that the process is allowed to run on. This
+//! mask is set by `taskset`, `numactl`, or a cgroup CPU set.
+
+use super::cpu_affinity::CpuAffinity;
+use super::linux_wrapper::CpuSet;
+use crate::error::{ErrorKind, Result, ResultExt};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+use structopt::clap::arg_enum;
+
+/// The sysfs directory that contains the `node` and `cpu` directories
+const SYSFS_SYSTEM_PATH: &str = "/sys/devices/system";
+
+/// The distance of a node to itself, as defined by the ACPI SLIT table
+const LOCAL_DISTANCE: u32 = 10;
+
+/// A NUMA node.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Node {
+    /// The node ID
+    pub id: u16,
+
+    /// The IDs of the node's CPUs, in ascending order
+    pub cpus: Vec<u16>,
+
+    /// The distances to all nodes, in the order of `Topology::nodes()`
+    pub distances: Vec<u32>,
+}
+
+/// A logical CPU, i.e., a hardware thread.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Cpu {
+    /// The CPU ID
+    pub id: u16,
+
+    /// The NUMA node that the CPU belongs to
+    pub node: u16,
+
+    /// The physical package (i.e., socket) ID
+    pub package_id: u16,
+
+    /// The physical core ID, which is unique within the package
+    pub core_id: u16,
+
+    /// The IDs of all hardware threads of the physical core, including this CPU
+    pub siblings: Vec<u16>,
+}
+
+/// Specifies how threads are placed onto CPUs.
+///
+/// The policies map thread IDs to CPU IDs in the order described below.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum AffinityPolicy {
+    /// Fills all hardware threads of a core, then the next core, and then the
+    /// next node
+    Compact,
+
+    /// Distributes threads round-robin over the nodes, and within each node
+    /// over the physical cores before their SMT siblings
+    Scatter,
+
+    /// Uses only the CPUs of the specified node, physical cores before their
+    /// SMT siblings
+    NodeLocal(u16),
+
+    /// Uses only one hardware thread per physical core, node by node
+    PhysicalCores,
+}
+
+arg_enum! {
+    /// Command-line argument that selects an `AffinityPolicy`.
+    ///
+    /// The node of `NodeLocal` is a separate argument. Both are combined with
+    /// an `ArgCpuAffinityPolicyHelper`.
+    #[derive(Copy, Clone, Debug, PartialEq, Serialize)]
+    pub enum ArgCpuAffinityPolicy {
+        Compact,
+        Scatter,
+        NodeLocal,
+        PhysicalCores,
+    }
+}
+
+/// Combines the affinity policy argument with the node argument.
+#[derive(Debug)]
+pub struct ArgCpuAffinityPolicyHelper {
+    pub policy: ArgCpuAffinityPolicy,
+    pub node: u16,
+}
+
+impl From<ArgCpuAffinityPolicyHelper> for AffinityPolicy {
+    fn from(ArgCpuAffinityPolicyHelper { policy, node }: ArgCpuAffinityPolicyHelper) -> Self {
+        match policy {
+            ArgCpuAffinityPolicy::Compact => AffinityPolicy::Compact,
+            ArgCpuAffinityPolicy::Scatter => AffinityPolicy::Scatter,
+            ArgCpuAffinityPolicy::NodeLocal => AffinityPolicy::NodeLocal(node),
+            ArgCpuAffinityPolicy::PhysicalCores => AffinityPolicy::PhysicalCores,
+        }
+    }
+}
+
+/// The NUMA topology of the machine.
+#[derive(Clone, Debug)]
+pub struct Topology {
+    nodes: Vec<Node>,
+    cpus: Vec<Cpu>,
+}
+
+impl Topology {
+    /// Reads the topology from sysfs.
+    pub fn from_sysfs() -> Result<Self> {
+        Self::from_sysfs_path(Path::new(SYSFS_SYSTEM_PATH))
+    }
+
+    /// Reads the topology from a sysfs tree at `system_path`.
+    ///
+    /// The path corresponds to `/sys/devices/system`, and must contain the
+    /// `cpu` directory. Without the `node` directory, i.e., on kernels without
+    /// NUMA support, all CPUs belong to node 0.
+    pub fn from_sysfs_path(system_path: &Path) -> Result<Self> {
+        let cpu_path = system_path.join("cpu");
+        let node_path = system_path.join("node");
+
+        let cpu_ids = parse_id_list(&read_sysfs(&cpu_path.join("online"))?)?;
+
+        let nodes = if node_path.exists() {
+            let node_ids = parse_id_list(&read_sysfs(&node_path.join("online"))?)?;
+            node_ids
+                .iter()
+                .map(|&id| {
+                    let path = node_path.join(format!("node{}", id));
+                    let cpus = parse_id_list(&read_sysfs(&path.join("cpulist"))?)?;
+                    let distances = read_sysfs(&path.join("distance"))?
+                        .split_whitespace()
+                        .map(parse_id)
+                        .collect::<Result<Vec<u32>>>()?;
+
+                    if distances.len() != node_ids.len() {
+                        Err(ErrorKind::RuntimeError(format!(
+                            "Expected {} distances for NUMA node {}, but found {}",
+                            node_ids.len(),
+                            id,
+                            distances.len()
+                        )))?;
+                    }
+
+                    Ok(Node {
+                        id,
+                        cpus,
+                        distances,
+                    })
+                })
+                .collect::<Result<Vec<_>>>()?
+        } else {
+            vec![Node {
+                id: 0,
+                cpus: cpu_ids.clone(),
+                distances: vec![LOCAL_DISTANCE],
+            }]
+        };
+
+        let cpus = cpu_ids
+            .iter()
+            .map(|&id| {
+                let path = cpu_path.join(format!("cpu{}", id)).join("topology");
+                let node = nodes
+                    .iter()
+                    .find(|node| node.cpus.contains(&id))
+                    .map_or(0, |node| node.id);
+
+                Ok(Cpu {
+                    id,
+                    node,
+                    package_id: parse_id(&read_sysfs(&path.join("physical_package_id"))?)?,
+                    core_id: parse_id(&read_sysfs(&path.join("core_id"))?)?,
+                    siblings: parse_id_list(&read_sysfs(&path.join("thread_siblings_list"))?)?,
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Self { nodes, cpus })
+    }
+
+    /// Returns the online NUMA nodes, in ascending order of their IDs.
+    pub fn nodes(&self) -> &[Node] {
+        &self.nodes
+    }
+
+    /// Returns the online CPUs, in ascending order of their IDs.
+    pub fn cpus(&self) -> &[Cpu] {
+        &self.cpus
+    }
+
+    /// Returns the NUMA node with the specified ID.
+    pub fn node(&self, id: u16) -> Option<&Node> {
+        self.nodes.iter().find(|node| node.id == id)
+    }
+
+    /// Returns the distance between two NUMA nodes.
+    ///
+    /// The distance is relative, with 10 being the distance of a node to
+    /// itself.
+    pub fn distance(&self, from: u16, to: u16) -> Option<u32> {
+        let to_index = self.nodes.iter().position(|node| node.id == to)?;
+        self.node(from)
+            .and_then(|node| node.distances.get(to_index).copied())
+    }
+
+    /// Generates a CPU affinity with the specified policy.
+    ///
+    /// Only CPUs in the `allowed` set are used. Returns an error if the policy
+    /// leaves no CPUs, or if a `NodeLocal` node doesn't exist.
+    pub fn cpu_affinity(&self, policy: AffinityPolicy, allowed: &CpuSet) -> Result<CpuAffinity> {
+        let node_cores: Vec<Vec<Vec<u16>>> = match policy {
+            AffinityPolicy::NodeLocal(id) => {
+                self.node(id).ok_or(ErrorKind::InvalidNode(id))?;
+                vec![self.physical_cores(id, allowed)]
+            }
+            _ => self
+                .nodes
+                .iter()
+                .map(|node| self.physical_cores(node.id, allowed))
+                .collect(),
+        };
+
+        let affinity_list: Vec<u16> = match policy {
+            AffinityPolicy::Compact => node_cores.iter().flatten().flatten().copied().collect(),
+            AffinityPolicy::PhysicalCores => node_cores
+                .iter()
+                .flatten()
+                .map(|threads| threads[0])
+                .collect(),
+            AffinityPolicy::NodeLocal(_) => node_cores
+                .iter()
+                .flat_map(|cores| cores_before_siblings(cores))
+                .collect(),
+            AffinityPolicy::Scatter => {
+                let node_lists: Vec<Vec<u16>> = node_cores
+                    .iter()
+                    .map(|cores| cores_before_siblings(cores))
+                    .collect();
+                let max_len = node_lists.iter().map(|l| l.len()).max().unwrap_or(0);
+
+                (0..max_len)
+                    .flat_map(|i| node_lists.iter().filter_map(move |l| l.get(i).copied()))
+                    .collect()
+            }
+        };
+
+        if affinity_list.is_empty() {
+            Err(ErrorKind::InvalidArgument(format!(
+                "No allowed CPUs are available for the {:?} affinity policy",
+                policy
+            )))?;
+        }
+
+        Ok(CpuAffinity::from_slice(&affinity_list))
+    }
+
+    /// Returns the allowed CPUs of a node grouped by their physical cores.
+    ///
+    /// The cores are ordered by their lowest CPU ID.
+    fn physical_cores(&self, node: u16, allowed: &CpuSet) -> Vec<Vec<u16>> {
+        let mut cores: BTreeMap<(u16, u16), Vec<u16>> = BTreeMap::new();
+        self.cpus
+            .iter()
+            .filter(|cpu| cpu.node == node)
+            .filter(|cpu| cpu.id < CpuSet::MAX_LEN && allowed.is_set(cpu.id))
+            .for_each(|cpu| {
+                cores
+                    .entry((cpu.package_id, cpu.core_id))
+                    .or_insert_with(Vec::new)
+                    .push(cpu.id)
+            });
+
+        let mut cores: Vec<Vec<u16>> = cores.into_values().collect();
+        cores.sort_by_key(|threads| threads[0]);
+        cores
+    }
+}
+
+/// Orders the CPUs such that the first hardware thread of each core comes
+/// before the second hardware thread of any core, and so on.
+fn cores_before_siblings(cores: &[Vec<u16>]) -> Vec<u16> {
+    let max_threads = cores.iter().map(|threads| threads.len()).max().unwrap_or(0);
+
+    (0..max_threads)
+        .flat_map(|i| {
+            cores
+                .iter()
+                .filter_map(move |threads| threads.get(i).copied())
+        })
+        .collect()
+}
+
+/// Reads a sysfs file.
+fn read_sysfs(path: &Path) -> Result<String> {
+    fs::read_to_string(path)
+        .chain_err(|| ErrorKind::RuntimeError(format!("Failed to read {}", path.display())))
+}
+
+/// Parses a single ID.
+fn parse_id<T: std::str::FromStr>(s: &str) -> Result<T> {
+    s.trim()
+        .parse()
+        .map_err(|_| ErrorKind::RuntimeError(format!("Failed to parse ID: {}", s.trim())).into())
+}
+
+/// Parses a sysfs ID list.
+///
+/// The list format is defined in the `cpuset` manual page. For example,
+/// `0-3,8,10-11` expands to `0 1 2 3 8 10 11`. An empty list is valid.
+fn parse_id_list(s: &str) -> Result<Vec<u16>> {
+    let ranges = s
+        .trim()
+        .split(',')
+        .filter(|range| !range.is_empty())
+        .map(|range| {
+            let mut bounds = range.splitn(2, '-');
+            let first: u16 = parse_id(bounds.next().unwrap_or(""))?;
+            let last: u16 = bounds.next().map_or(Ok(first), parse_id)?;
+            Ok(first..=last)
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(ranges.into_iter().flatten().collect())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::path::PathBuf;
+    use std::process;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    static TREE_ID: AtomicUsize = AtomicUsize::new(0);
+
+    /// A fake sysfs tree in a temporary directory that is removed on drop.
+    struct SysfsTree {
+        path: PathBuf,
+    }
+
+    impl SysfsTree {
+        /// Creates a tree with 2 nodes, each with 2 cores of 2 hardware
+        /// threads. Like Linux on x86_64, CPUs 0-3 are the first threads and
+        /// CPUs 4-7 are their siblings.
+        fn two_nodes() -> Self {
+            let path = std::env::temp_dir().join(format!(
+                "numa-gpu-topology-{}-{}",
+                process::id(),
+                TREE_ID.fetch_add(1, Ordering::SeqCst)
+            ));
+            let tree = Self { path };
+
+            tree.write("cpu/online", "0-7\n");
+            tree.write("node/online", "0-1\n");
+            tree.write("node/node0/cpulist", "0-1,4-5\n");
+            tree.write("node/node0/distance", "10 21\n");
+            tree.write("node/node1/cpulist", "2-3,6-7\n");
+            tree.write("node/node1/distance", "21 10\n");
+
+            for cpu in 0..8_u16 {
+                let first = cpu % 4;
+                tree.write(
+                    &format!("cpu/cpu{}/topology/physical_package_id", cpu),
+                    &format!("{}\n", first / 2),
+                );
+                tree.write(
+                    &format!("cpu/cpu{}/topology/core_id", cpu),
+                    &format!("{}\n", first % 2),
+                );
+                tree.write(
+                    &format!("cpu/cpu{}/topology/thread_siblings_list", cpu),
+                    &format!("{},{}\n", first, first + 4),
+                );
+            }
+
+            tree
+        }
+
+        fn write(&self, file: &str, contents: &str) {
+            let path = self.path.join(file);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, contents).unwrap();
+        }
+
+        fn topology(&self) -> Result<Topology> {
+            Topology::from_sysfs_path(&self.path)
+        }
+    }
+
+    impl Drop for SysfsTree {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.path);
+        }
+    }
+
+    fn all_cpus() -> CpuSet {
+        let mut cpu_set = CpuSet::new();
+        (0..8).for_each(|cpu| cpu_set.add(cpu));
+        cpu_set
+    }
+
+    fn affinity_list(affinity: &CpuAffinity) -> Vec<u16> {
+        (0..affinity.len() as u16)
+            .map(|tid| affinity.thread_to_cpu(tid).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn parse_id_list_with_ranges() -> Result<()> {
+        assert_eq!(parse_id_list("0-3,8,10-11\n")?, vec![0, 1, 2, 3, 8, 10, 11]);
+        assert_eq!(parse_id_list("5")?, vec![5]);
+        assert_eq!(parse_id_list("\n")?, Vec::<u16>::new());
+        assert!(parse_id_list("0-x").is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn sysfs_nodes_cpus_and_distances() -> Result<()> {
+        let tree = SysfsTree::two_nodes();
+        let topology = tree.topology()?;
+
+        assert_eq!(topology.nodes().len(), 2);
+        assert_eq!(topology.node(1).unwrap().cpus, vec![2, 3, 6, 7]);
+        assert_eq!(topology.distance(0, 0), Some(10));
+        assert_eq!(topology.distance(0, 1), Some(21));
+        assert_eq!(topology.distance(0, 2), None);
+
+        assert_eq!(topology.cpus().len(), 8);
+        let cpu = &topology.cpus()[6];
+        assert_eq!(cpu.node, 1);
+        assert_eq!(cpu.package_id, 1);
+        assert_eq!(cpu.core_id, 0);
+        assert_eq!(cpu.siblings, vec![2, 6]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn sysfs_without_numa_is_single_node() -> Result<()> {
+        let tree = SysfsTree::two_nodes();
+        fs::remove_dir_all(tree.path.join("node")).unwrap();
+        let topology = tree.topology()?;
+
+        assert_eq!(topology.nodes().len(), 1);
+        assert_eq!(topology.node(0).unwrap().cpus, (0..8).collect::<Vec<_>>());
+        assert!(topology.cpus().iter().all(|cpu| cpu.node == 0));
+
+        Ok(())
+    }
+
+    #[test]
+    fn compact_policy() -> Result<()> {
+        let tree = SysfsTree::two_nodes();
+        let affinity = tree
+            .topology()?
+            .cpu_affinity(AffinityPolicy::Compact, &all_cpus())?;
+
+        assert_eq!(affinity_list(&affinity), vec![0, 4, 1, 5, 2, 6, 3, 7]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn scatter_policy() -> Result<()> {
+        let tree = SysfsTree::two_nodes();
+        let affinity = tree
+            .topology()?
+            .cpu_affinity(AffinityPolicy::Scatter, &all_cpus())?;
+
+        assert_eq!(affinity_list(&affinity), vec![0, 2, 1, 3, 4, 6, 5, 7]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn node_local_policy() -> Result<()> {
+        let tree = SysfsTree::two_nodes();
+        let topology = tree.topology()?;
+        let affinity = topology.cpu_affinity(AffinityPolicy::NodeLocal(1), &all_cpus())?;
+
+        assert_eq!(affinity_list(&affinity), vec![2, 3, 6, 7]);
+        assert!(topology
+            .cpu_affinity(AffinityPolicy::NodeLocal(2), &all_cpus())
+            .is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn physical_cores_policy() -> Result<()> {
+        let tree = SysfsTree::two_nodes();
+        let affinity = tree
+            .topology()?
+            .cpu_affinity(AffinityPolicy::PhysicalCores, &all_cpus())?;
+
+        assert_eq!(affinity_list(&affinity), vec![0, 1, 2, 3]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn policies_respect_allowed_cpus() -> Result<()> {
+        let tree = SysfsTree::two_nodes();
+        let topology = tree.topology()?;
+
+        // E.g., `taskset -c 1,3,5`
+        let mut allowed = CpuSet::new();
+        [1, 3, 5].iter().for_each(|&cpu| allowed.add(cpu));
+
+        let compact = topology.cpu_affinity(AffinityPolicy::Compact, &allowed)?;
+        assert_eq!(affinity_list(&compact), vec![1, 5, 3]);
+
+        let physical = topology.cpu_affinity(AffinityPolicy::PhysicalCores, &allowed)?;
+        assert_eq!(affinity_list(&physical), vec![1, 3]);
+
+        assert!(topology
+            .cpu_affinity(AffinityPolicy::Compact, &CpuSet::new())
+            .is_err());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,461 @@
+// Copyright 2022 Clemens Lutz
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Traces the execution phases of operators and measurement runs.
+//!
+//! A `Span` denotes the time that a thread spends in a phase, e.g., in the
+//! prefix sum, partitioning, build, or probe phase. Where spans are recorded
+//! is determined by the process-wide `TraceBackend`:
+//!
+//!  - `Noop` discards all spans.
+//!  - `Nvtx` annotates spans as NVTX ranges for the NVIDIA profilers.
+//!  - `Chrome` records spans in memory. `write_chrome_trace` writes the
+//!    recorded spans as Chrome trace-event JSON, which can be opened in
+//!    `chrome://tracing` or Perfetto.
+//!
+//! The Chrome backend records one span per call. Thus, spans inside of a
+//! morsel-driven operator produce one event per morsel and thread. To bound
+//! the memory usage of long runs, at most `MAX_CHROME_EVENTS` spans are
+//! recorded. Further spans are dropped, and their number is written to the
+//! trace file.
+
+use crate::error::Result;
+#[cfg(feature = "cuda")]
+use crate::runtime::nvtx;
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use std::collections::BTreeMap;
+#[cfg(feature = "cuda")]
+use std::ffi::CString;
+use std::fmt;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, AtomicU8, Ordering};
+use std::sync::Mutex;
+use std::time::Instant;
+
+/// The maximum number of spans recorded by the Chrome backend
+///
+/// An event takes about 100 bytes including its name. Thus, the events take
+/// at most about 100 MiB of memory.
+pub const MAX_CHROME_EVENTS: usize = 1 << 20;
+
+/// The process-wide tracing backend
+#[cfg(feature = "cuda")]
+static BACKEND: AtomicU8 = AtomicU8::new(TraceBackend::Nvtx as u8);
+
+/// The process-wide tracing backend
+#[cfg(not(feature = "cuda"))]
+static BACKEND: AtomicU8 = AtomicU8::new(TraceBackend::Noop as u8);
+
+/// The recorder of the Chrome backend
+static RECORDER: Lazy<ChromeRecorder> =
+    Lazy::new(|| ChromeRecorder::new(MAX_CHROME_EVENTS, std::process::id()));
+
+/// The reference point of all event timestamps
+static EPOCH: Lazy<Instant> = Lazy::new(Instant::now);
+
+/// The next span ID of the Chrome and no-op backends
+static NEXT_SPAN_ID: AtomicU64 = AtomicU64::new(1);
+
+/// The next trace thread ID
+static NEXT_THREAD_ID: AtomicU64 = AtomicU64::new(1);
+
+thread_local! {
+    /// The trace ID of the current thread
+    ///
+    /// OS thread IDs are not portably accessible, thus threads are numbered
+    /// in the order in which they record their first span.
+    static THREAD_ID: u64 = NEXT_THREAD_ID.fetch_add(1, Ordering::Relaxed);
+}
+
+/// Specifies where spans are recorded
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+#[repr(u8)]
+pub enum TraceBackend {
+    /// Discard all spans
+    Noop,
+
+    /// Annotate spans as NVTX ranges
+    #[cfg(feature = "cuda")]
+    Nvtx,
+
+    /// Record spans as Chrome trace events
+    Chrome,
+}
+
+impl Default for TraceBackend {
+    #[cfg(feature = "cuda")]
+    fn default() -> Self {
+        TraceBackend::Nvtx
+    }
+
+    #[cfg(not(feature = "cuda"))]
+    fn default() -> Self {
+        TraceBackend::Noop
+    }
+}
+
+/// Sets the backend of all subsequently started spans.
+///
+/// The default backend is `TraceBackend::Nvtx` if CUDA is enabled, and
+/// `TraceBackend::Noop` otherwise.
+pub fn set_backend(backend: TraceBackend) {
+    BACKEND.store(backend as u8, Ordering::SeqCst);
+}
+
+/// Returns the current tracing backend.
+pub fn backend() -> TraceBackend {
+    match BACKEND.load(Ordering::Relaxed) {
+        #[cfg(feature = "cuda")]
+        x if x == TraceBackend::Nvtx as u8 => TraceBackend::Nvtx,
+        x if x == TraceBackend::Chrome as u8 => TraceBackend::Chrome,
+        _ => TraceBackend::Noop,
+    }
+}
+
+/// Writes the spans recorded by the Chrome backend to a file.
+///
+/// The file contains a JSON object in the Chrome trace-event format. Spans are
+/// complete events (`"ph": "X"`) with microsecond timestamps relative to the
+/// first recorded span. Each thread that recorded a span is named by a
+/// metadata event. The number of dropped spans is stored in `otherData`.
+pub fn write_chrome_trace(path: &Path) -> Result<()> {
+    let mut writer = BufWriter::new(File::create(path)?);
+    RECORDER.write(&mut writer)?;
+    writer.flush()?;
+
+    Ok(())
+}
+
+/// A span identifier.
+///
+/// With the NVTX backend, the span ID is the NVTX range ID. Thus, the ID can be
+/// matched to the output of a profiler.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize)]
+pub struct SpanId(u64);
+
+impl fmt::Display for SpanId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+/// A span denoting the time that a thread spends in a phase.
+///
+/// A span is started by `Span::new` and ended either by `Span::end` or when it
+/// is dropped.
+///
+/// # Example
+///
+/// ```
+/// # use numa_gpu::runtime::trace::Span;
+/// #
+/// let span = Span::new("partition");
+/// // ...
+/// let span_id = span.end();
+/// println!("Span {} ended", span_id);
+/// ```
+#[derive(Debug)]
+pub struct Span {
+    id: SpanId,
+    inner: Option<SpanInner>,
+}
+
+#[derive(Debug)]
+enum SpanInner {
+    Noop,
+    #[cfg(feature = "cuda")]
+    Nvtx(nvtx::Range),
+    Chrome {
+        name: String,
+        start: Instant,
+    },
+}
+
+impl Span {
+    /// Starts a new span on the current thread.
+    pub fn new(name: &str) -> Self {
+        match backend() {
+            TraceBackend::Noop => Self {
+                id: SpanId(NEXT_SPAN_ID.fetch_add(1, Ordering::Relaxed)),
+                inner: Some(SpanInner::Noop),
+            },
+            #[cfg(feature = "cuda")]
+            TraceBackend::Nvtx => {
+                let message = CString::new(name.replace('\0', ""))
+                    .expect("Failed to strip nul bytes from span name");
+                let range = nvtx::Range::new(&message);
+
+                Self {
+                    id: SpanId(range.id().into()),
+                    inner: Some(SpanInner::Nvtx(range)),
+                }
+            }
+            TraceBackend::Chrome => {
+                // Initialize the epoch before taking the start time
+                Lazy::force(&EPOCH);
+
+                Self {
+                    id: SpanId(NEXT_SPAN_ID.fetch_add(1, Ordering::Relaxed)),
+                    inner: Some(SpanInner::Chrome {
+                        name: name.to_string(),
+                        start: Instant::now(),
+                    }),
+                }
+            }
+        }
+    }
+
+    /// Returns the ID of the span.
+    pub fn id(&self) -> SpanId {
+        self.id
+    }
+
+    /// Ends the span.
+    pub fn end(mut self) -> SpanId {
+        self.finish();
+        self.id
+    }
+
+    fn finish(&mut self) {
+        match self.inner.take() {
+            None | Some(SpanInner::Noop) => {}
+            #[cfg(feature = "cuda")]
+            Some(SpanInner::Nvtx(range)) => {
+                range.end();
+            }
+            Some(SpanInner::Chrome { name, start }) => {
+                let end = Instant::now();
+                let thread = std::thread::current();
+
+                RECORDER.record(
+                    name,
+                    THREAD_ID.with(|id| *id),
+                    thread.name(),
+                    start.duration_since(*EPOCH).as_secs_f64() * 1_000_000.0,
+                    end.duration_since(start).as_secs_f64() * 1_000_000.0,
+                );
+            }
+        }
+    }
+}
+
+impl Drop for Span {
+    fn drop(&mut self) {
+        self.finish();
+    }
+}
+
+/// An event in the Chrome trace-event format
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(tag = "ph")]
+enum TraceEvent {
+    /// Names a thread
+    #[serde(rename = "M")]
+    Metadata {
+        name: &'static str,
+        pid: u32,
+        tid: u64,
+        args: ThreadNameArgs,
+    },
+
+    /// A span with a start time and a duration, both in microseconds
+    #[serde(rename = "X")]
+    Complete {
+        name: String,
+        pid: u32,
+        tid: u64,
+        ts: f64,
+        dur: f64,
+    },
+}
+
+/// The arguments of a thread name metadata event
+#[derive(Clone, Debug, PartialEq, Serialize)]
+struct ThreadNameArgs {
+    name: String,
+}
+
+/// A trace file in the Chrome trace-event format
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ChromeTrace {
+    trace_events: Vec<TraceEvent>,
+    display_time_unit: &'static str,
+    other_data: ChromeTraceOtherData,
+}
+
+/// The trace file's additional information
+#[derive(Debug, Serialize)]
+struct ChromeTraceOtherData {
+    dropped_events: u64,
+}
+
+/// Records the spans of the Chrome backend in memory
+#[derive(Debug)]
+struct ChromeRecorder {
+    capacity: usize,
+    pid: u32,
+    buffer: Mutex<ChromeBuffer>,
+}
+
+/// The recorded spans, and the names of the threads that recorded them
+#[derive(Debug, Default)]
+struct ChromeBuffer {
+    events: Vec<TraceEvent>,
+    thread_names: BTreeMap<u64, String>,
+    dropped: u64,
+}
+
+impl ChromeRecorder {
+    /// Creates a recorder that keeps at most `capacity` spans of process `pid`.
+    fn new(capacity: usize, pid: u32) -> Self {
+        Self {
+            capacity,
+            pid,
+            buffer: Mutex::new(ChromeBuffer::default()),
+        }
+    }
+
+    /// Records a span, or counts it as dropped if the recorder is full.
+    ///
+    /// Threads without a name are named by their trace thread ID.
+    fn record(
+        &self,
+        name: String,
+        tid: u64,
+        thread_name: Option<&str>,
+        start_us: f64,
+        duration_us: f64,
+    ) {
+        let mut buffer = self.buffer.lock().unwrap();
+
+        if buffer.events.len() >= self.capacity {
+            buffer.dropped += 1;
+            return;
+        }
+
+        buffer.thread_names.entry(tid).or_insert_with(|| {
+            thread_name.map_or_else(|| format!("thread {}", tid), |name| name.to_string())
+        });
+        buffer.events.push(TraceEvent::Complete {
+            name,
+            pid: self.pid,
+            tid,
+            ts: start_us,
+            dur: duration_us,
+        });
+    }
+
+    /// Writes the recorded spans as a Chrome trace.
+    fn write<W: Write>(&self, writer: W) -> io::Result<()> {
+        let buffer = self.buffer.lock().unwrap();
+
+        let metadata = buffer
+            .thread_names
+            .iter()
+            .map(|(&tid, name)| TraceEvent::Metadata {
+                name: "thread_name",
+                pid: self.pid,
+                tid,
+                args: ThreadNameArgs { name: name.clone() },
+            });
+
+        let trace = ChromeTrace {
+            trace_events: metadata.chain(buffer.events.iter().cloned()).collect(),
+            display_time_unit: "ms",
+            other_data: ChromeTraceOtherData {
+                dropped_events: buffer.dropped,
+            },
+        };
+
+        serde_json::to_writer(writer, &trace)?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn write_chrome_trace_events() {
+        let recorder = ChromeRecorder::new(MAX_CHROME_EVENTS, 42);
+        recorder.record("build".to_string(), 2, None, 1.5, 10.0);
+        recorder.record("probe".to_string(), 1, Some("main"), 12.0, 0.25);
+        recorder.record("probe".to_string(), 2, None, 12.0, 3.0);
+
+        let mut buffer = Vec::new();
+        recorder.write(&mut buffer).unwrap();
+        let json = String::from_utf8(buffer).unwrap();
+
+        let expected = concat!(
+            "{\"traceEvents\":[",
+            "{\"ph\":\"M\",\"name\":\"thread_name\",\"pid\":42,\"tid\":1,\"args\":{\"name\":\"main\"}},",
+            "{\"ph\":\"M\",\"name\":\"thread_name\",\"pid\":42,\"tid\":2,\"args\":{\"name\":\"thread 2\"}},",
+            "{\"ph\":\"X\",\"name\":\"build\",\"pid\":42,\"tid\":2,\"ts\":1.5,\"dur\":10.0},",
+            "{\"ph\":\"X\",\"name\":\"probe\",\"pid\":42,\"tid\":1,\"ts\":12.0,\"dur\":0.25},",
+            "{\"ph\":\"X\",\"name\":\"probe\",\"pid\":42,\"tid\":2,\"ts\":12.0,\"dur\":3.0}",
+            "],\"displayTimeUnit\":\"ms\",\"otherData\":{\"dropped_events\":0}}"
+        );
+
+        assert_eq!(json, expected);
+    }
+
+    #[test]
+    fn write_chrome_trace_escapes_names() {
+        let recorder = ChromeRecorder::new(MAX_CHROME_EVENTS, 42);
+        recorder.record("a \"b\" \\c\n".to_string(), 1, Some("main"), 0.0, 1.0);
+
+        let mut buffer = Vec::new();
+        recorder.write(&mut buffer).unwrap();
+        let json = String::from_utf8(buffer).unwrap();
+
+        assert!(json.contains("\"name\":\"a \\\"b\\\" \\\\c\\n\""));
+    }
+
+    #[test]
+    fn chrome_recorder_drops_spans_when_full() {
+        let recorder = ChromeRecorder::new(2, 42);
+        for _ in 0..5 {
+            recorder.record("span".to_string(), 1, None, 0.0, 1.0);
+        }
+
+        let buffer = recorder.buffer.lock().unwrap();
+        assert_eq!(buffer.events.len(), 2);
+        assert_eq!(buffer.dropped, 3);
+    }
+
+    #[test]
+    fn span_ids_are_unique() {
+        // NVTX range IDs are only unique if a profiler is attached
+        let previous_backend = backend();
+        set_backend(TraceBackend::Noop);
+
+        let first = Span::new("first_span");
+        let second = Span::new("second_span");
+        let first_id = first.id();
+        let second_id = second.id();
+        let end_id = first.end();
+        drop(second);
+
+        set_backend(previous_backend);
+
+        assert_ne!(first_id, second_id);
+        assert_eq!(end_id, first_id);
+    }
+}
@@ -14,6 +14,7 @@
 
 mod cache_padded;
 mod device_type;
+#[cfg(feature = "cuda")]
 pub mod gpu_utils;
 
 pub use cache_padded::CachePadded;
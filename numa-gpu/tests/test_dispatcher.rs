@@ -13,17 +13,26 @@
 // limitations under the License.
 
 use assert_approx_eq::assert_approx_eq;
+use numa_gpu::error::{Error as NumaGpuError, ErrorKind};
+use numa_gpu::runtime::cpu_affinity::CpuAffinity;
 use numa_gpu::runtime::dispatcher::{
-    HetMorselExecutorBuilder, IntoHetMorselIterator, MorselSpec, WorkerCpuAffinity,
+    HetMorselExecutorBuilder, IntoHetMorselIterator, MorselCounts, MorselSpec, WorkerCpuAffinity,
 };
+use numa_gpu::runtime::numa::{self, NodeRange};
+#[cfg(feature = "cuda")]
 use rustacuda::memory::{CopyDestination, DeviceBox, LockedBuffer};
+#[cfg(feature = "cuda")]
 use rustacuda::module::Module;
+#[cfg(feature = "cuda")]
 use rustacuda::{launch, quick_init};
 use std::error::Error;
+#[cfg(feature = "cuda")]
 use std::ffi::CString;
 use std::mem::size_of;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Mutex;
 
+#[cfg(feature = "cuda")]
 #[test]
 fn test_iter() -> Result<(), Box<dyn Error>> {
     let _ctx = quick_init()?;
@@ -89,6 +98,7 @@ fn test_iter() -> Result<(), Box<dyn Error>> {
     Ok(())
 }
 
+#[cfg(feature = "cuda")]
 #[test]
 fn test_iter_non_divisor_morsel_len() -> Result<(), Box<dyn Error>> {
     let _ctx = quick_init()?;
@@ -151,3 +161,405 @@ fn test_iter_non_divisor_morsel_len() -> Result<(), Box<dyn Error>> {
 
     Ok(())
 }
+
+#[test]
+fn test_cpu_only_iter() -> Result<(), Box<dyn Error>> {
+    let data_len = 2_usize.pow(20);
+    let morsel_len = 1024;
+    let morsel_spec = MorselSpec {
+        cpu_morsel_bytes: morsel_len * 2 * size_of::<f32>(),
+        gpu_morsel_bytes: morsel_len * 2 * size_of::<f32>(),
+    };
+    assert_eq!(data_len % morsel_len, 0);
+
+    let mut data_0 = vec![1.0_f32; data_len];
+    let mut data_1 = vec![1.0_f32; data_len];
+    let cpu_result = Mutex::new(0.0_f32);
+
+    let mut executor = HetMorselExecutorBuilder::new()
+        .morsel_spec(morsel_spec)
+        .cpu_threads(4)
+        .worker_cpu_affinity(WorkerCpuAffinity::default())
+        .build()?;
+
+    (data_0.as_mut_slice(), data_1.as_mut_slice())
+        .into_het_morsel_iter(&mut executor)
+        .fold(
+            |(x, y)| {
+                assert_eq!(x.len(), morsel_len);
+                assert_eq!(x.len(), y.len());
+
+                let sum: f32 = x.iter().zip(y.iter()).map(|(x, y)| x * y).sum();
+                let mut total = cpu_result.lock().unwrap();
+                *total += sum;
+                Ok(())
+            },
+            |_, _| unreachable!("GPU worker called without GPUs"),
+        )?;
+
+    let result = cpu_result.into_inner()?;
+
+    eprintln!("Expecting {}, got {}", data_len, result);
+    assert_approx_eq!(data_len as f32, result);
+
+    Ok(())
+}
+
+#[test]
+fn test_cpu_only_iter_non_divisor_morsel_len() -> Result<(), Box<dyn Error>> {
+    let data_len = 2_usize.pow(20);
+    let morsel_len = 1023;
+    let morsel_spec = MorselSpec {
+        cpu_morsel_bytes: morsel_len * 2 * size_of::<u64>(),
+        gpu_morsel_bytes: morsel_len * 2 * size_of::<u64>(),
+    };
+
+    let mut data_0: Vec<u64> = (0..data_len as u64).collect();
+    let mut data_1 = vec![1_u64; data_len];
+    let cpu_result = Mutex::new(0_u64);
+
+    let mut executor = HetMorselExecutorBuilder::new()
+        .morsel_spec(morsel_spec)
+        .cpu_threads(3)
+        .worker_cpu_affinity(WorkerCpuAffinity::default())
+        .build()?;
+
+    (data_0.as_mut_slice(), data_1.as_mut_slice())
+        .into_het_morsel_iter(&mut executor)
+        .fold(
+            |(x, y)| {
+                assert_ne!(x.len(), 0);
+                assert!(x.len() <= morsel_len);
+                assert_eq!(x.len(), y.len());
+
+                let sum: u64 = x.iter().zip(y.iter()).map(|(x, y)| x * y).sum();
+                let mut total = cpu_result.lock().unwrap();
+                *total += sum;
+                Ok(())
+            },
+            |_, _| unreachable!("GPU worker called without GPUs"),
+        )?;
+
+    let expected = (data_len as u64 - 1) * data_len as u64 / 2;
+    assert_eq!(expected, cpu_result.into_inner()?);
+
+    Ok(())
+}
+
+#[test]
+fn test_cpu_only_with_state() -> Result<(), Box<dyn Error>> {
+    let data_len = 100_000;
+    let morsel_len = 1000;
+    let cpu_threads = 4;
+    let morsel_spec = MorselSpec {
+        cpu_morsel_bytes: morsel_len * 2 * size_of::<u32>(),
+        gpu_morsel_bytes: morsel_len * 2 * size_of::<u32>(),
+    };
+
+    let mut data_0 = vec![1_u32; data_len];
+    let mut data_1 = vec![2_u32; data_len];
+    let initialized = AtomicUsize::new(0);
+    let processed = AtomicUsize::new(0);
+
+    let mut executor = HetMorselExecutorBuilder::new()
+        .morsel_spec(morsel_spec)
+        .cpu_threads(cpu_threads)
+        .worker_cpu_affinity(WorkerCpuAffinity::default())
+        .build()?;
+
+    let mut data = (data_0.as_mut_slice(), data_1.as_mut_slice());
+    let mut iter = data.into_het_morsel_iter(&mut executor).with_state(
+        |_| {
+            initialized.fetch_add(1, Ordering::SeqCst);
+            Ok(0_usize)
+        },
+        |_, _| -> Result<(), _> { unreachable!("GPU worker initialized without GPUs") },
+    )?;
+
+    iter.fold(
+        |(x, y), tuples: &mut usize| {
+            assert!(x.iter().zip(y.iter()).all(|(&x, &y)| x == 1 && y == 2));
+
+            *tuples += x.len();
+            processed.fetch_add(x.len(), Ordering::SeqCst);
+            Ok(())
+        },
+        |_, _, _| unreachable!("GPU worker called without GPUs"),
+    )?;
+
+    assert_eq!(cpu_threads, initialized.load(Ordering::SeqCst));
+    assert_eq!(data_len, processed.load(Ordering::SeqCst));
+
+    Ok(())
+}
+
+#[test]
+fn test_cpu_only_iter_single_column() -> Result<(), Box<dyn Error>> {
+    let data_len = 100_000;
+    let morsel_len = 999;
+    let morsel_spec = MorselSpec {
+        cpu_morsel_bytes: morsel_len * size_of::<u64>(),
+        gpu_morsel_bytes: morsel_len * size_of::<u64>(),
+    };
+
+    let mut data: Vec<u64> = (0..data_len as u64).collect();
+    let cpu_result = Mutex::new(0_u64);
+
+    let mut executor = HetMorselExecutorBuilder::new()
+        .morsel_spec(morsel_spec)
+        .cpu_threads(2)
+        .worker_cpu_affinity(WorkerCpuAffinity::default())
+        .build()?;
+
+    (data.as_mut_slice(),)
+        .into_het_morsel_iter(&mut executor)
+        .fold(
+            |(x,)| {
+                assert!(x.len() <= morsel_len);
+
+                let sum: u64 = x.iter().sum();
+                let mut total = cpu_result.lock().unwrap();
+                *total += sum;
+                Ok(())
+            },
+            |_, _| unreachable!("GPU worker called without GPUs"),
+        )?;
+
+    let expected = (data_len as u64 - 1) * data_len as u64 / 2;
+    assert_eq!(expected, cpu_result.into_inner()?);
+
+    Ok(())
+}
+
+#[test]
+fn test_cpu_only_iter_four_columns() -> Result<(), Box<dyn Error>> {
+    let data_len = 100_000;
+    let morsel_len = 1000;
+    let tuple_bytes = 2 * size_of::<i64>() + size_of::<u32>() + size_of::<f32>();
+    let morsel_spec = MorselSpec {
+        cpu_morsel_bytes: morsel_len * tuple_bytes,
+        gpu_morsel_bytes: morsel_len * tuple_bytes,
+    };
+
+    let mut quantity: Vec<u32> = (0..data_len as u32).map(|i| i % 50).collect();
+    let mut price = vec![2_i64; data_len];
+    let mut discount = vec![0.5_f32; data_len];
+    let mut ship_date: Vec<i64> = (0..data_len as i64).collect();
+    let cpu_result = Mutex::new(0_i64);
+
+    let mut executor = HetMorselExecutorBuilder::new()
+        .morsel_spec(morsel_spec)
+        .cpu_threads(4)
+        .worker_cpu_affinity(WorkerCpuAffinity::default())
+        .build()?;
+
+    (
+        quantity.as_mut_slice(),
+        price.as_mut_slice(),
+        discount.as_mut_slice(),
+        ship_date.as_mut_slice(),
+    )
+        .into_het_morsel_iter(&mut executor)
+        .fold(
+            |(quantity, price, discount, ship_date)| {
+                assert_eq!(quantity.len(), morsel_len);
+                assert_eq!(quantity.len(), price.len());
+                assert_eq!(quantity.len(), discount.len());
+                assert_eq!(quantity.len(), ship_date.len());
+
+                let sum: i64 = quantity
+                    .iter()
+                    .zip(price.iter())
+                    .zip(discount.iter())
+                    .zip(ship_date.iter())
+                    .filter(|(((&q, _), _), &d)| q < 25 && d % 2 == 0)
+                    .map(|(((_, &p), &f), _)| (p as f32 * f) as i64)
+                    .sum();
+                let mut total = cpu_result.lock().unwrap();
+                *total += sum;
+                Ok(())
+            },
+            |_, _| unreachable!("GPU worker called without GPUs"),
+        )?;
+
+    let expected = (0..data_len).filter(|&i| i % 50 < 25 && i % 2 == 0).count() as i64;
+    assert_eq!(expected, cpu_result.into_inner()?);
+
+    Ok(())
+}
+
+#[test]
+fn test_cpu_only_error_cancels_dispatch() -> Result<(), Box<dyn Error>> {
+    let data_len = 100_000;
+    let morsel_len = 1000;
+    let failing_morsel = 3;
+    let morsel_spec = MorselSpec {
+        cpu_morsel_bytes: morsel_len * size_of::<u64>(),
+        gpu_morsel_bytes: morsel_len * size_of::<u64>(),
+    };
+
+    let mut data = vec![1_u64; data_len];
+    let processed = AtomicUsize::new(0);
+
+    let mut executor = HetMorselExecutorBuilder::new()
+        .morsel_spec(morsel_spec)
+        .cpu_threads(1)
+        .worker_cpu_affinity(WorkerCpuAffinity::default())
+        .build()?;
+
+    let result = (data.as_mut_slice(),)
+        .into_het_morsel_iter(&mut executor)
+        .fold(
+            |_| {
+                if processed.fetch_add(1, Ordering::SeqCst) + 1 == failing_morsel {
+                    Err(ErrorKind::RuntimeError("Failed morsel".to_string()))?;
+                }
+                Ok(())
+            },
+            |_, _| unreachable!("GPU worker called without GPUs"),
+        );
+
+    match result {
+        Err(NumaGpuError(ErrorKind::RuntimeError(_), _)) => {}
+        _ => panic!("Expected the worker's error, got {:?}", result),
+    }
+    assert_eq!(failing_morsel, processed.load(Ordering::SeqCst));
+
+    Ok(())
+}
+
+#[test]
+fn test_cpu_only_with_state_init_error() -> Result<(), Box<dyn Error>> {
+    let mut data = vec![1_u64; 1000];
+
+    let mut executor = HetMorselExecutorBuilder::new()
+        .cpu_threads(4)
+        .worker_cpu_affinity(WorkerCpuAffinity::default())
+        .build()?;
+
+    let mut columns = (data.as_mut_slice(),);
+    let result = columns.into_het_morsel_iter(&mut executor).with_state(
+        |thread_id| {
+            if thread_id == 2 {
+                Err(ErrorKind::RuntimeError("Failed init".to_string()))?;
+            }
+            Ok(thread_id)
+        },
+        |_, _| -> Result<(), _> { unreachable!("GPU worker initialized without GPUs") },
+    );
+
+    assert!(result.is_err());
+
+    Ok(())
+}
+
+#[test]
+fn test_cpu_only_numa_aware_dispatch() -> Result<(), Box<dyn Error>> {
+    let data_len = 100_000;
+    let morsel_len = 1000;
+    let morsel_spec = MorselSpec {
+        cpu_morsel_bytes: morsel_len * size_of::<u64>(),
+        gpu_morsel_bytes: morsel_len * size_of::<u64>(),
+    };
+
+    // Pin the worker, so that its NUMA node is known
+    let cpu = CpuAffinity::default()
+        .thread_to_cpu(0)
+        .expect("Failed to get an allowed CPU");
+    let local_node = numa::node_of_cpu(cpu)?;
+    let remote_node = local_node + 1;
+    let node_ranges = vec![
+        NodeRange {
+            node: remote_node,
+            range: 0..(data_len / 4),
+        },
+        NodeRange {
+            node: local_node,
+            range: (data_len / 4)..data_len,
+        },
+    ];
+
+    let mut data: Vec<u64> = (0..data_len as u64).collect();
+    let cpu_result = Mutex::new(0_u64);
+
+    let mut executor = HetMorselExecutorBuilder::new()
+        .morsel_spec(morsel_spec)
+        .cpu_threads(1)
+        .worker_cpu_affinity(WorkerCpuAffinity {
+            cpu_workers: CpuAffinity::from_slice(&[cpu]),
+            gpu_workers: CpuAffinity::default(),
+        })
+        .build()?;
+
+    (data.as_mut_slice(),)
+        .into_het_morsel_iter(&mut executor)
+        .numa_aware(node_ranges)
+        .fold(
+            |(x,)| {
+                let sum: u64 = x.iter().sum();
+                let mut total = cpu_result.lock().unwrap();
+                *total += sum;
+                Ok(())
+            },
+            |_, _| unreachable!("GPU worker called without GPUs"),
+        )?;
+
+    let expected = (data_len as u64 - 1) * data_len as u64 / 2;
+    assert_eq!(expected, cpu_result.into_inner()?);
+
+    let morsels = data_len / morsel_len;
+    assert_eq!(
+        executor.morsel_counts().cpu_workers,
+        vec![MorselCounts {
+            local: 3 * morsels / 4,
+            remote: morsels / 4,
+        }]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_cpu_only_fold_with_stats() -> Result<(), Box<dyn Error>> {
+    let data_len = 100_000;
+    let morsel_len = 1000;
+    let cpu_threads = 4;
+    let morsel_spec = MorselSpec {
+        cpu_morsel_bytes: morsel_len * size_of::<u64>(),
+        gpu_morsel_bytes: morsel_len * size_of::<u64>(),
+    };
+
+    let mut data = vec![1_u64; data_len];
+
+    let mut executor = HetMorselExecutorBuilder::new()
+        .morsel_spec(morsel_spec)
+        .cpu_threads(cpu_threads)
+        .worker_cpu_affinity(WorkerCpuAffinity::default())
+        .build()?;
+
+    let stats = (data.as_mut_slice(),)
+        .into_het_morsel_iter(&mut executor)
+        .fold_with_stats(
+            |(x,)| {
+                assert!(x.iter().all(|&x| x == 1));
+                Ok(())
+            },
+            |_, _| unreachable!("GPU worker called without GPUs"),
+        )?;
+
+    assert_eq!(stats.cpu_workers.len(), cpu_threads);
+    assert!(stats.gpu_workers.is_empty());
+    assert_eq!(stats.cpu_tuples(), data_len);
+    assert_eq!(stats.gpu_tuples(), 0);
+    assert_eq!(
+        stats.cpu_workers.iter().map(|w| w.morsels).sum::<usize>(),
+        data_len / morsel_len
+    );
+    assert_eq!(stats.cpu_work_fraction(), Some(1.0));
+    assert!(stats
+        .cpu_workers
+        .iter()
+        .all(|w| w.busy_time + w.idle_time <= stats.wall_time));
+
+    Ok(())
+}
@@ -15,6 +15,7 @@
 use data_store::error::Error as DataStoreError;
 use numa_gpu::error::Error as NumaGpuError;
 use rayon::ThreadPoolBuildError;
+#[cfg(feature = "cuda")]
 use rustacuda::error::CudaError;
 use sql_ops::error::Error as SqlOpsError;
 use std::convert::From;
@@ -25,6 +26,7 @@ pub type Result<T> = std::result::Result<T, Error>;
 #[allow(dead_code)]
 pub enum ErrorKind {
     CsvError(csv::Error),
+    #[cfg(feature = "cuda")]
     CudaError(CudaError),
     DataStoreError(DataStoreError),
     IntegerOverflow(String),
@@ -59,6 +61,7 @@ impl From<csv::Error> for Error {
     }
 }
 
+#[cfg(feature = "cuda")]
 impl From<CudaError> for Error {
     fn from(error: CudaError) -> Self {
         Self {
@@ -117,6 +120,7 @@ impl std::fmt::Display for ErrorKind {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             ErrorKind::CsvError(ref e) => e.fmt(f),
+            #[cfg(feature = "cuda")]
             ErrorKind::CudaError(ref e) => e.fmt(f),
             ErrorKind::DataStoreError(ref e) => e.fmt(f),
             ErrorKind::IntegerOverflow(ref s) => write!(f, "Integer overflow: {}", s),
@@ -13,5 +13,7 @@
 // limitations under the License.
 
 pub mod cpu_partitioned_radix_join;
+pub mod cpu_radix_join;
 pub mod gpu_radix_join;
 pub mod gpu_triton_join;
+mod skew_join;
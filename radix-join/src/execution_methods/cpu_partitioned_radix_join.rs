@@ -12,12 +12,14 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use super::skew_join;
 use crate::error::{ErrorKind, Result};
 use crate::measurement::harness::RadixJoinPoint;
-use cstr::cstr;
+use crate::types::SkewHandling;
 use data_store::join_data::JoinData;
 use datagen::relation::KeyAttribute;
 use itertools::izip;
+use num_traits::AsPrimitive;
 use numa_gpu::error::Result as NumaGpuResult;
 use numa_gpu::runtime::allocator::{Allocator, DerefMemType, MemType};
 use numa_gpu::runtime::cpu_affinity::CpuAffinity;
@@ -26,13 +28,14 @@ use numa_gpu::runtime::hw_info::NvidiaDriverInfo;
 use numa_gpu::runtime::linux_wrapper;
 use numa_gpu::runtime::memory::*;
 use numa_gpu::runtime::numa::PageType;
-use numa_gpu::runtime::nvtx::Range;
+use numa_gpu::runtime::trace::Span;
 use numa_gpu::utils::DeviceType;
 use rustacuda::context::{CacheConfig, CurrentContext, SharedMemoryConfig};
 use rustacuda::event::{Event, EventFlags};
 use rustacuda::function::{BlockSize, GridSize};
 use rustacuda::memory::{CopyDestination, DeviceBuffer, DeviceCopy};
 use rustacuda::stream::{Stream, StreamFlags, StreamWaitEventFlags};
+use sql_ops::hash_function::HashFunction;
 use sql_ops::join::{cuda_radix_join, no_partitioning_join, HashingScheme};
 use sql_ops::partition::cpu_radix_partition::{
     CpuHistogramAlgorithm, CpuRadixPartitionAlgorithm, CpuRadixPartitionable, CpuRadixPartitioner,
@@ -41,13 +44,14 @@ use sql_ops::partition::gpu_radix_partition::{
     GpuHistogramAlgorithm, GpuRadixPartitionAlgorithm, GpuRadixPartitionable, GpuRadixPartitioner,
 };
 use sql_ops::partition::{
-    PartitionOffsets, PartitionedRelation, RadixBits, RadixPartitionInputChunkable, RadixPass,
-    Tuple,
+    skew, PartitionOffsets, PartitionedRelation, RadixBits, RadixPartitionInputChunkable,
+    RadixPass, Tuple,
 };
 use std::cmp;
 use std::convert::TryInto;
 use std::iter;
 use std::mem;
+use std::os::raw::c_uint;
 use std::sync::Arc;
 use std::time::Instant;
 
@@ -118,6 +122,8 @@ impl<T: DeviceCopy> MemLock for StreamState<T> {
 pub fn cpu_partitioned_radix_join<T>(
     data: &mut JoinData<T>,
     hashing_scheme: HashingScheme,
+    hash_function: HashFunction,
+    skew_handling: SkewHandling,
     histogram_algorithm_fst: DeviceType<CpuHistogramAlgorithm, GpuHistogramAlgorithm>,
     histogram_algorithm_snd: DeviceType<CpuHistogramAlgorithm, GpuHistogramAlgorithm>,
     partition_algorithm_fst: DeviceType<CpuRadixPartitionAlgorithm, GpuRadixPartitionAlgorithm>,
@@ -142,6 +148,7 @@ where
         + CpuRadixPartitionable
         + GpuRadixPartitionable
         + KeyAttribute
+        + AsPrimitive<c_uint>
         + no_partitioning_join::CudaHashJoinable
         + no_partitioning_join::CpuHashJoinable
         + cuda_radix_join::CudaRadixJoinable,
@@ -192,41 +199,42 @@ where
                 DerefMemType::AlignedSysMem {
                     align_bytes: sql_ops::CPU_CACHE_LINE_SIZE as usize,
                 },
-            ))
+            )
+            .with_hash_function(hash_function))
         })
         .collect::<Result<_>>()?;
 
-    let mut inner_rel_partitions = PartitionedRelation::new(
+    let mut inner_rel_partitions = PartitionedRelation::try_new(
         data.build_relation_key.len(),
         histogram_algorithm_fst.either(|cpu| cpu.into(), |gpu| gpu.into()),
         radix_bits.pass_radix_bits(RadixPass::First).unwrap(),
         max_chunks_1st,
-        Allocator::mem_alloc_fn(partitions_mem_type.clone()),
-        Allocator::mem_alloc_fn(partitions_mem_type.clone()),
-    );
+        Allocator::try_mem_alloc_fn(partitions_mem_type.clone()),
+        Allocator::try_mem_alloc_fn(partitions_mem_type.clone()),
+    )?;
 
-    let mut outer_rel_partitions = PartitionedRelation::new(
+    let mut outer_rel_partitions = PartitionedRelation::try_new(
         data.probe_relation_key.len(),
         histogram_algorithm_fst.either(|cpu| cpu.into(), |gpu| gpu.into()),
         radix_bits.pass_radix_bits(RadixPass::First).unwrap(),
         max_chunks_1st,
-        Allocator::mem_alloc_fn(partitions_mem_type.clone()),
-        Allocator::mem_alloc_fn(partitions_mem_type.clone()),
-    );
+        Allocator::try_mem_alloc_fn(partitions_mem_type.clone()),
+        Allocator::try_mem_alloc_fn(partitions_mem_type.clone()),
+    )?;
 
-    let mut inner_rel_partition_offsets = PartitionOffsets::new(
+    let mut inner_rel_partition_offsets = PartitionOffsets::try_new(
         histogram_algorithm_fst.either(|cpu| cpu.into(), |gpu| gpu.into()),
         max_chunks_1st,
         radix_bits.pass_radix_bits(RadixPass::First).unwrap(),
-        Allocator::mem_alloc_fn(partitions_mem_type.clone()),
-    );
+        Allocator::try_mem_alloc_fn(partitions_mem_type.clone()),
+    )?;
 
-    let mut outer_rel_partition_offsets = PartitionOffsets::new(
+    let mut outer_rel_partition_offsets = PartitionOffsets::try_new(
         histogram_algorithm_fst.either(|cpu| cpu.into(), |gpu| gpu.into()),
         max_chunks_1st,
         radix_bits.pass_radix_bits(RadixPass::First).unwrap(),
-        Allocator::mem_alloc_fn(partitions_mem_type.clone()),
-    );
+        Allocator::try_mem_alloc_fn(partitions_mem_type.clone()),
+    )?;
 
     inner_rel_partitions.mlock()?;
     outer_rel_partitions.mlock()?;
@@ -235,7 +243,7 @@ where
 
     let partitions_malloc_time = partitions_malloc_timer.elapsed();
 
-    let prefix_sum_range = Range::new(cstr!("phase_prefix_sum"));
+    let prefix_sum_span = Span::new("phase_prefix_sum");
     let prefix_sum_timer = Instant::now();
 
     let inner_key_slice: &[T] = (&data.build_relation_key).try_into().map_err(|_| {
@@ -287,22 +295,39 @@ where
     });
 
     let prefix_sum_time = prefix_sum_timer.elapsed().as_nanos() as f64;
-    prefix_sum_range.end();
+    prefix_sum_span.end();
+
+    // Detect skewed partitions using the histograms of the prefix sum. Skewed
+    // partitions are joined on the CPU instead of the GPU.
+    let skewed_partition_ids = match skew_handling {
+        SkewHandling::Disabled => Vec::new(),
+        SkewHandling::Broadcast(skew_factor) => {
+            let mut ids = skew::skewed_partitions(&inner_rel_partition_offsets, skew_factor)?;
+            ids.extend(skew::skewed_partitions(
+                &outer_rel_partition_offsets,
+                skew_factor,
+            )?);
+            ids.sort_unstable();
+            ids.dedup();
+            ids
+        }
+    };
+    let gpu_partition_ids: Vec<u32> = (0..radix_bits.pass_fanout(RadixPass::First).unwrap())
+        .filter(|partition_id| skewed_partition_ids.binary_search(partition_id).is_err())
+        .collect();
 
     let state_malloc_timer = Instant::now();
 
-    let max_inner_partition_len =
-        (0..inner_rel_partitions.fanout()).try_fold(0, |max, partition_id| {
-            inner_rel_partition_offsets
-                .partition_len(partition_id)
-                .map(|len| cmp::max(max, len))
-        })?;
-    let max_outer_partition_len =
-        (0..outer_rel_partitions.fanout()).try_fold(0, |max, partition_id| {
-            outer_rel_partition_offsets
-                .partition_len(partition_id)
-                .map(|len| cmp::max(max, len))
-        })?;
+    let max_inner_partition_len = gpu_partition_ids.iter().try_fold(0, |max, &partition_id| {
+        inner_rel_partition_offsets
+            .partition_len(partition_id)
+            .map(|len| cmp::max(max, len))
+    })?;
+    let max_outer_partition_len = gpu_partition_ids.iter().try_fold(0, |max, &partition_id| {
+        outer_rel_partition_offsets
+            .partition_len(partition_id)
+            .map(|len| cmp::max(max, len))
+    })?;
 
     // Memory allocations occur asynchronously in parallel to partitioning
     let mut stream_states = iter::repeat_with(|| {
@@ -320,62 +345,64 @@ where
                 stream_grid_size,
                 stream_block_size,
                 dmem_buffer_bytes,
-            )?,
+            )?
+            .with_hash_function(hash_function),
             radix_join: cuda_radix_join::CudaRadixJoin::new(
                 RadixPass::Second,
                 radix_bits.clone(),
                 hashing_scheme,
                 stream_grid_size,
                 stream_block_size,
-            )?,
-            cached_inner_key: Allocator::alloc_mem(
+            )?
+            .with_hash_function(hash_function),
+            cached_inner_key: Allocator::try_alloc_mem(
                 stream_state_mem_type.clone(),
                 max_inner_partition_len,
-            ),
-            cached_inner_pay: Allocator::alloc_mem(
+            )?,
+            cached_inner_pay: Allocator::try_alloc_mem(
                 stream_state_mem_type.clone(),
                 max_inner_partition_len,
-            ),
-            cached_outer_key: Allocator::alloc_mem(
+            )?,
+            cached_outer_key: Allocator::try_alloc_mem(
                 stream_state_mem_type.clone(),
                 max_outer_partition_len,
-            ),
-            cached_outer_pay: Allocator::alloc_mem(
+            )?,
+            cached_outer_pay: Allocator::try_alloc_mem(
                 stream_state_mem_type.clone(),
                 max_outer_partition_len,
-            ),
-            inner_rel_partition_offsets_2nd: PartitionOffsets::new(
+            )?,
+            inner_rel_partition_offsets_2nd: PartitionOffsets::try_new(
                 histogram_algorithm_snd.into(),
                 max_chunks_2nd,
                 radix_bits.pass_radix_bits(RadixPass::Second).unwrap(),
-                Allocator::mem_alloc_fn(stream_state_mem_type.clone()),
-            ),
-            outer_rel_partition_offsets_2nd: PartitionOffsets::new(
+                Allocator::try_mem_alloc_fn(stream_state_mem_type.clone()),
+            )?,
+            outer_rel_partition_offsets_2nd: PartitionOffsets::try_new(
                 histogram_algorithm_snd.into(),
                 max_chunks_2nd,
                 radix_bits.pass_radix_bits(RadixPass::Second).unwrap(),
-                Allocator::mem_alloc_fn(stream_state_mem_type.clone()),
-            ),
-            inner_rel_partitions_2nd: PartitionedRelation::new(
+                Allocator::try_mem_alloc_fn(stream_state_mem_type.clone()),
+            )?,
+            inner_rel_partitions_2nd: PartitionedRelation::try_new(
                 max_inner_partition_len,
                 histogram_algorithm_snd.into(),
                 radix_bits.pass_radix_bits(RadixPass::Second).unwrap(),
                 max_chunks_2nd,
-                Allocator::mem_alloc_fn(stream_state_mem_type.clone()),
-                Allocator::mem_alloc_fn(stream_state_mem_type.clone()),
-            ),
-            outer_rel_partitions_2nd: PartitionedRelation::new(
+                Allocator::try_mem_alloc_fn(stream_state_mem_type.clone()),
+                Allocator::try_mem_alloc_fn(stream_state_mem_type.clone()),
+            )?,
+            outer_rel_partitions_2nd: PartitionedRelation::try_new(
                 max_outer_partition_len,
                 histogram_algorithm_snd.into(),
                 radix_bits.pass_radix_bits(RadixPass::Second).unwrap(),
                 max_chunks_2nd,
-                Allocator::mem_alloc_fn(stream_state_mem_type.clone()),
-                Allocator::mem_alloc_fn(stream_state_mem_type.clone()),
-            ),
-            join_task_assignments: Allocator::alloc_mem(
+                Allocator::try_mem_alloc_fn(stream_state_mem_type.clone()),
+                Allocator::try_mem_alloc_fn(stream_state_mem_type.clone()),
+            )?,
+            join_task_assignments: Allocator::try_alloc_mem(
                 stream_state_mem_type.clone(),
                 join_dim.0.x as usize + 1,
-            ),
+            )?,
             join_result_sums,
         })
     })
@@ -426,7 +453,7 @@ where
     // partitioning with transfer and GPU partitioning. The partitions result
     // from the 2nd pass on the GPU.
     let num_cached_inner_partitions = cmp::min(
-        radix_bits.pass_fanout(RadixPass::First).unwrap(),
+        gpu_partition_ids.len() as u32,
         // FIXME: use the exact length instead of the maximum essentially compute the inclusive
         // prefix sum, and take the maximum that fits into the cache. Or even better: make an
         // actual schedule using the knapsack algorithm
@@ -436,14 +463,14 @@ where
     );
     let mut cached_inner_partitions = (0..num_cached_inner_partitions)
         .map(|_| {
-            let mut p = PartitionedRelation::<Tuple<T, T>>::new(
+            let mut p = PartitionedRelation::<Tuple<T, T>>::try_new(
                 max_inner_partition_len,
                 histogram_algorithm_snd.into(),
                 radix_bits.pass_radix_bits(RadixPass::Second).unwrap(),
                 max_chunks_2nd,
-                Allocator::mem_alloc_fn(stream_state_mem_type.clone()),
-                Allocator::mem_alloc_fn(stream_state_mem_type.clone()),
-            );
+                Allocator::try_mem_alloc_fn(stream_state_mem_type.clone()),
+                Allocator::try_mem_alloc_fn(stream_state_mem_type.clone()),
+            )?;
             p.mlock()?;
             Ok(p)
         })
@@ -452,7 +479,7 @@ where
 
     let state_malloc_time = state_malloc_timer.elapsed();
 
-    let partition_range = Range::new(cstr!("phase_partition"));
+    let partition_span = Span::new("phase_partition");
     let partition_timer = Instant::now();
 
     // Partition inner relation
@@ -475,8 +502,8 @@ where
 
     // Enqueue GPU kernels that copy and 2nd pass partition the inner relation
     // to the cache
-    for (partition_id, stream_id, inner_rel_partitions_2nd) in izip!(
-        0..num_cached_inner_partitions,
+    for (&partition_id, stream_id, inner_rel_partitions_2nd) in izip!(
+        gpu_partition_ids.iter(),
         (0..stream_states.len()).cycle(),
         cached_inner_partitions.iter_mut()
     ) {
@@ -547,11 +574,11 @@ where
     });
 
     let partition_time = partition_timer.elapsed();
-    partition_range.end();
+    partition_span.end();
 
     let join_timer = Instant::now();
 
-    let join_range = Range::new(cstr!("phase_join"));
+    let join_span = Span::new("phase_join");
     let join_start_event = Event::new(EventFlags::DEFAULT)?;
     stream_states
         .iter()
@@ -561,8 +588,8 @@ where
     // Note: Outer relation partitions must be enqueued on the same stream as
     // their respective inner relation partition. Thus, need to match up the
     // stream_state cycle.
-    for (partition_id, stream_id, inner_rel_partitions_2nd) in izip!(
-        0..num_cached_inner_partitions,
+    for (&partition_id, stream_id, inner_rel_partitions_2nd) in izip!(
+        gpu_partition_ids.iter(),
         (0..stream_states.len()).cycle(),
         cached_inner_partitions.iter_mut()
     ) {
@@ -628,8 +655,9 @@ where
         event.record(stream)?;
     }
 
-    for (partition_id, stream_id) in izip!(
-        (0..radix_bits.pass_fanout(RadixPass::First).unwrap())
+    for (&partition_id, stream_id) in izip!(
+        gpu_partition_ids
+            .iter()
             .skip(num_cached_inner_partitions as usize),
         // Skip the first N stream_states to balance the queues and avoid stragglers
         (0..stream_states.len())
@@ -726,6 +754,14 @@ where
         event.record(stream)?;
     }
 
+    // Join the skewed partitions on the CPU in parallel to the GPU
+    let skewed_sum = skew_join::cpu_broadcast_join(
+        &thread_pool,
+        &inner_rel_partitions,
+        &outer_rel_partitions,
+        &skewed_partition_ids,
+    )?;
+
     stream_states
         .iter()
         .try_for_each::<_, Result<_>>(|StreamState { stream, .. }| {
@@ -734,7 +770,7 @@ where
         })?;
 
     let join_time = join_timer.elapsed();
-    join_range.end();
+    join_span.end();
 
     let mut result_sums_host = vec![0; join_result_sums_len * NUM_STREAMS];
     stream_states
@@ -753,7 +789,7 @@ where
         )
         .collect::<Result<()>>()?;
 
-    let sum = result_sums_host.iter().sum();
+    let sum = result_sums_host.iter().sum::<i64>() + skewed_sum;
 
     let data_point = RadixJoinPoint {
         prefix_sum_ns: Some(prefix_sum_time),
@@ -763,6 +799,10 @@ where
         state_malloc_ns: Some(state_malloc_time.as_nanos() as f64),
         cached_build_tuples: Some(cached_build_tuples),
         cached_probe_tuples: None,
+        skewed_partitions: match skew_handling {
+            SkewHandling::Disabled => None,
+            SkewHandling::Broadcast(_) => Some(skewed_partition_ids.len()),
+        },
     };
 
     Ok((sum, data_point))
@@ -0,0 +1,560 @@
+// Copyright 2022 Clemens Lutz
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::error::{Error, ErrorKind, Result};
+use crate::measurement::harness::RadixJoinPoint;
+use crate::types::SkewHandling;
+use data_store::join_data::JoinData;
+use datagen::relation::KeyAttribute;
+use itertools::izip;
+use numa_gpu::error::{Error as NumaGpuError, Result as NumaGpuResult};
+use numa_gpu::runtime::allocator::{Allocator, DerefMemType, MemType};
+use numa_gpu::runtime::cpu_affinity::CpuAffinity;
+use numa_gpu::runtime::memory::*;
+use numa_gpu::runtime::trace::Span;
+use numa_gpu::utils::DeviceType;
+use sql_ops::hash_function::HashFunction;
+use sql_ops::join::cpu_radix_join::{CpuRadixJoin, CpuRadixJoinable};
+use sql_ops::join::HashingScheme;
+use sql_ops::partition::cpu_radix_partition::{
+    CpuHistogramAlgorithm, CpuRadixPartitionAlgorithm, CpuRadixPartitionable, CpuRadixPartitioner,
+};
+use sql_ops::partition::gpu_radix_partition::{GpuHistogramAlgorithm, GpuRadixPartitionAlgorithm};
+use sql_ops::partition::{
+    PartitionOffsets, PartitionedRelation, RadixBits, RadixPartitionInputChunkable, RadixPass,
+    Tuple,
+};
+use std::cmp;
+use std::convert::{TryFrom, TryInto};
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::Instant;
+
+// Helper struct that stores the state of a CPU thread in the 2nd partitioning
+// pass and the join.
+struct ThreadState<T: DeviceCopy> {
+    radix_prnr_2nd: CpuRadixPartitioner,
+    radix_join: CpuRadixJoin,
+    partition_key: DerefMem<T>,
+    partition_pay: DerefMem<T>,
+    partition_offsets_2nd: PartitionOffsets<Tuple<T, T>>,
+    join_result_sum: i64,
+}
+
+impl<T: DeviceCopy> MemLock for ThreadState<T> {
+    fn mlock(&mut self) -> NumaGpuResult<()> {
+        self.partition_key.mlock()?;
+        self.partition_pay.mlock()?;
+        self.partition_offsets_2nd.mlock()?;
+
+        Ok(())
+    }
+
+    fn munlock(&mut self) -> NumaGpuResult<()> {
+        self.partition_key.munlock()?;
+        self.partition_pay.munlock()?;
+        self.partition_offsets_2nd.munlock()?;
+
+        Ok(())
+    }
+}
+
+impl<T> ThreadState<T>
+where
+    T: DeviceCopy + CpuRadixPartitionable,
+{
+    /// Partitions one 1st pass partition in a 2nd pass.
+    ///
+    /// The partition is first gathered from all chunks into the key and payload
+    /// buffers, because the CPU partitioner takes columns as input.
+    fn partition_2nd_pass(
+        &mut self,
+        partition_id: u32,
+        src: &PartitionedRelation<Tuple<T, T>>,
+        dst: &mut PartitionedRelation<Tuple<T, T>>,
+    ) -> Result<()> {
+        let partition_len = src.partition_len(partition_id)?;
+        dst.resize(partition_len)?;
+
+        let key_slice = &mut self.partition_key[0..partition_len];
+        let pay_slice = &mut self.partition_pay[0..partition_len];
+
+        let tuples =
+            (0..src.num_chunks()).flat_map(|chunk_id| src[(chunk_id, partition_id)].iter());
+        for (key, pay, tuple) in izip!(key_slice.iter_mut(), pay_slice.iter_mut(), tuples) {
+            *key = tuple.key;
+            *pay = tuple.value;
+        }
+
+        let key_slice: &[T] = key_slice;
+        let pay_slice: &[T] = pay_slice;
+
+        let key_chunk = key_slice
+            .input_chunks::<T>(1)?
+            .into_iter()
+            .next()
+            .ok_or_else(|| ErrorKind::RuntimeError("Failed to get key chunk".into()))?;
+        let offsets_chunk = self
+            .partition_offsets_2nd
+            .chunks_mut()
+            .next()
+            .ok_or_else(|| ErrorKind::RuntimeError("Failed to get offsets chunk".into()))?;
+        self.radix_prnr_2nd.prefix_sum(key_chunk, offsets_chunk)?;
+
+        let key_chunk = key_slice
+            .input_chunks::<T>(1)?
+            .into_iter()
+            .next()
+            .ok_or_else(|| ErrorKind::RuntimeError("Failed to get key chunk".into()))?;
+        let pay_chunk = pay_slice
+            .input_chunks::<T>(1)?
+            .into_iter()
+            .next()
+            .ok_or_else(|| ErrorKind::RuntimeError("Failed to get payload chunk".into()))?;
+        let offsets_chunk = self
+            .partition_offsets_2nd
+            .chunks_mut()
+            .next()
+            .ok_or_else(|| ErrorKind::RuntimeError("Failed to get offsets chunk".into()))?;
+        let partitioned_chunk = dst
+            .chunks_mut()
+            .next()
+            .ok_or_else(|| ErrorKind::RuntimeError("Failed to get partitioned chunk".into()))?;
+        self.radix_prnr_2nd
+            .partition(key_chunk, pay_chunk, offsets_chunk, partitioned_chunk)?;
+
+        Ok(())
+    }
+}
+
+/// Returns the first CPU affinity error recorded by the thread pool's start
+/// handler, if setting the affinity of any thread failed.
+fn affinity_result(affinity_error: &Mutex<Option<NumaGpuError>>) -> Result<()> {
+    match affinity_error
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .take()
+    {
+        Some(error) => Err(error.into()),
+        None => Ok(()),
+    }
+}
+
+/// Returns a result slot for each thread.
+///
+/// Each thread writes its result into its slot, because errors cannot be
+/// returned from within a thread pool scope.
+fn thread_results(threads: usize) -> Vec<Result<()>> {
+    (0..threads).map(|_| Ok(())).collect()
+}
+
+/// Runs a radix join entirely on the CPU.
+///
+/// Both partitioning passes use `CpuRadixPartitioner`. The join builds and
+/// probes one cache-resident hash table per partition using `CpuRadixJoin`.
+/// Threads process disjunct, contiguous ranges of 1st pass partitions.
+pub fn cpu_radix_join<T>(
+    data: &mut JoinData<T>,
+    hashing_scheme: HashingScheme,
+    hash_function: HashFunction,
+    skew_handling: SkewHandling,
+    histogram_algorithm_fst: DeviceType<CpuHistogramAlgorithm, GpuHistogramAlgorithm>,
+    histogram_algorithm_snd: DeviceType<CpuHistogramAlgorithm, GpuHistogramAlgorithm>,
+    partition_algorithm_fst: DeviceType<CpuRadixPartitionAlgorithm, GpuRadixPartitionAlgorithm>,
+    partition_algorithm_snd: DeviceType<CpuRadixPartitionAlgorithm, GpuRadixPartitionAlgorithm>,
+    radix_bits: &RadixBits,
+    threads: usize,
+    cpu_affinity: CpuAffinity,
+    partitions_mem_type: MemType,
+    state_mem_type: MemType,
+) -> Result<(i64, RadixJoinPoint)>
+where
+    T: Default
+        + Clone
+        + DeviceCopy
+        + Sync
+        + Send
+        + CpuRadixPartitionable
+        + CpuRadixJoinable
+        + KeyAttribute,
+{
+    // Precondition checks
+    let histogram_algorithm_fst = histogram_algorithm_fst.cpu().ok_or_else(|| {
+        ErrorKind::InvalidArgument("Only CPU prefix sum is supported in 1st pass".to_string())
+    })?;
+    let histogram_algorithm_snd = histogram_algorithm_snd.cpu().ok_or_else(|| {
+        ErrorKind::InvalidArgument("Only CPU prefix sum is supported in 2nd pass".to_string())
+    })?;
+    let partition_algorithm_fst = partition_algorithm_fst.cpu().ok_or_else(|| {
+        ErrorKind::InvalidArgument("Only CPU partitioning is supported in 1st pass".to_string())
+    })?;
+    let partition_algorithm_snd = partition_algorithm_snd.cpu().ok_or_else(|| {
+        ErrorKind::InvalidArgument("Only CPU partitioning is supported in 2nd pass".to_string())
+    })?;
+    let deref_state_mem_type = DerefMemType::try_from(state_mem_type.clone()).map_err(|_| {
+        ErrorKind::InvalidArgument("State memory must be accessible by the CPU".to_string())
+    })?;
+    if skew_handling != SkewHandling::Disabled {
+        Err(ErrorKind::InvalidArgument(
+            "Skew handling is not supported by this execution method".to_string(),
+        ))?;
+    }
+
+    // The start handler cannot return an error. Instead, it records the first
+    // error, which is checked after each phase.
+    let boxed_cpu_affinity = Arc::new(cpu_affinity);
+    let affinity_error = Arc::new(Mutex::new(None));
+    let start_handler_error = affinity_error.clone();
+    let thread_pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(threads)
+        .start_handler(move |tid| {
+            if let Err(error) = boxed_cpu_affinity.set_affinity(tid as u16) {
+                let mut first_error = start_handler_error
+                    .lock()
+                    .unwrap_or_else(PoisonError::into_inner);
+                if first_error.is_none() {
+                    *first_error = Some(error);
+                }
+            }
+        })
+        .build()?;
+
+    let partitions_malloc_timer = Instant::now();
+
+    let max_chunks_1st = threads as u32;
+    let fanout_1st = radix_bits.pass_fanout(RadixPass::First).unwrap();
+    let partitions_per_thread = (fanout_1st as usize + threads - 1) / threads;
+
+    let mut radix_prnrs: Vec<_> = (0..threads)
+        .map(|_| {
+            Ok(CpuRadixPartitioner::new(
+                histogram_algorithm_fst,
+                partition_algorithm_fst,
+                radix_bits.pass_radix_bits(RadixPass::First).unwrap(),
+                DerefMemType::AlignedSysMem {
+                    align_bytes: sql_ops::CPU_CACHE_LINE_SIZE as usize,
+                },
+            )?
+            .with_hash_function(hash_function))
+        })
+        .collect::<Result<_>>()?;
+
+    let mut inner_rel_partitions = PartitionedRelation::try_new(
+        data.build_relation_key.len(),
+        histogram_algorithm_fst.into(),
+        radix_bits.pass_radix_bits(RadixPass::First).unwrap(),
+        max_chunks_1st,
+        Allocator::try_mem_alloc_fn(partitions_mem_type.clone()),
+        Allocator::try_mem_alloc_fn(partitions_mem_type.clone()),
+    )?;
+
+    let mut outer_rel_partitions = PartitionedRelation::try_new(
+        data.probe_relation_key.len(),
+        histogram_algorithm_fst.into(),
+        radix_bits.pass_radix_bits(RadixPass::First).unwrap(),
+        max_chunks_1st,
+        Allocator::try_mem_alloc_fn(partitions_mem_type.clone()),
+        Allocator::try_mem_alloc_fn(partitions_mem_type.clone()),
+    )?;
+
+    let mut inner_rel_partition_offsets = PartitionOffsets::try_new(
+        histogram_algorithm_fst.into(),
+        max_chunks_1st,
+        radix_bits.pass_radix_bits(RadixPass::First).unwrap(),
+        Allocator::try_mem_alloc_fn(partitions_mem_type.clone()),
+    )?;
+
+    let mut outer_rel_partition_offsets = PartitionOffsets::try_new(
+        histogram_algorithm_fst.into(),
+        max_chunks_1st,
+        radix_bits.pass_radix_bits(RadixPass::First).unwrap(),
+        Allocator::try_mem_alloc_fn(partitions_mem_type.clone()),
+    )?;
+
+    inner_rel_partitions.mlock()?;
+    outer_rel_partitions.mlock()?;
+    inner_rel_partition_offsets.mlock()?;
+    outer_rel_partition_offsets.mlock()?;
+
+    let partitions_malloc_time = partitions_malloc_timer.elapsed();
+
+    let prefix_sum_span = Span::new("phase_prefix_sum");
+    let prefix_sum_timer = Instant::now();
+
+    let inner_key_slice: &[T] = (&data.build_relation_key).try_into().map_err(|_| {
+        ErrorKind::RuntimeError("Failed to run CPU prefix sum on device memory".into())
+    })?;
+    let inner_pay_slice: &[T] = (&data.build_relation_payload).try_into().map_err(|_| {
+        ErrorKind::RuntimeError("Failed to run CPU prefix sum on device memory".into())
+    })?;
+    let inner_key_chunks = inner_key_slice.input_chunks::<T>(max_chunks_1st)?;
+    let inner_pay_chunks = inner_pay_slice.input_chunks::<T>(max_chunks_1st)?;
+
+    let outer_key_slice: &[T] = (&data.probe_relation_key).try_into().map_err(|_| {
+        ErrorKind::RuntimeError("Failed to run CPU prefix sum on device memory".into())
+    })?;
+    let outer_pay_slice: &[T] = (&data.probe_relation_payload).try_into().map_err(|_| {
+        ErrorKind::RuntimeError("Failed to run CPU prefix sum on device memory".into())
+    })?;
+    let outer_key_chunks = outer_key_slice.input_chunks::<T>(max_chunks_1st)?;
+    let outer_pay_chunks = outer_pay_slice.input_chunks::<T>(max_chunks_1st)?;
+
+    // Prefix sum on inner relation
+    let mut results = thread_results(threads);
+    thread_pool.scope(|s| {
+        for (radix_prnr, key_chunk, offsets_chunk, result) in izip!(
+            radix_prnrs.iter_mut(),
+            inner_key_chunks.into_iter(),
+            inner_rel_partition_offsets.chunks_mut(),
+            results.iter_mut()
+        ) {
+            s.spawn(move |_| {
+                *result = radix_prnr
+                    .prefix_sum(key_chunk, offsets_chunk)
+                    .map_err(Error::from);
+            })
+        }
+    });
+    results.into_iter().collect::<Result<()>>()?;
+
+    // Prefix sum on outer relation
+    let mut results = thread_results(threads);
+    thread_pool.scope(|s| {
+        for (radix_prnr, key_chunk, offsets_chunk, result) in izip!(
+            radix_prnrs.iter_mut(),
+            outer_key_chunks.into_iter(),
+            outer_rel_partition_offsets.chunks_mut(),
+            results.iter_mut()
+        ) {
+            s.spawn(move |_| {
+                *result = radix_prnr
+                    .prefix_sum(key_chunk, offsets_chunk)
+                    .map_err(Error::from);
+            })
+        }
+    });
+    results.into_iter().collect::<Result<()>>()?;
+
+    affinity_result(&affinity_error)?;
+
+    let prefix_sum_time = prefix_sum_timer.elapsed().as_nanos() as f64;
+    prefix_sum_span.end();
+
+    let state_malloc_timer = Instant::now();
+
+    let inner_partition_lens = (0..fanout_1st)
+        .map(|partition_id| inner_rel_partition_offsets.partition_len(partition_id))
+        .collect::<sql_ops::error::Result<Vec<_>>>()?;
+    let outer_partition_lens = (0..fanout_1st)
+        .map(|partition_id| outer_rel_partition_offsets.partition_len(partition_id))
+        .collect::<sql_ops::error::Result<Vec<_>>>()?;
+    let max_partition_len = inner_partition_lens
+        .iter()
+        .chain(outer_partition_lens.iter())
+        .fold(0, |max, &len| cmp::max(max, len));
+
+    let mut thread_states = (0..threads)
+        .map(|_| {
+            Ok(ThreadState {
+                radix_prnr_2nd: CpuRadixPartitioner::new_with_pass(
+                    histogram_algorithm_snd,
+                    partition_algorithm_snd,
+                    radix_bits,
+                    RadixPass::Second,
+                    DerefMemType::AlignedSysMem {
+                        align_bytes: sql_ops::CPU_CACHE_LINE_SIZE as usize,
+                    },
+                )?
+                .with_hash_function(hash_function),
+                radix_join: CpuRadixJoin::new(
+                    RadixPass::Second,
+                    radix_bits.clone(),
+                    hashing_scheme,
+                )?
+                .with_hash_function(hash_function),
+                partition_key: Allocator::try_alloc_deref_mem(
+                    deref_state_mem_type.clone(),
+                    max_partition_len,
+                )?,
+                partition_pay: Allocator::try_alloc_deref_mem(
+                    deref_state_mem_type.clone(),
+                    max_partition_len,
+                )?,
+                partition_offsets_2nd: PartitionOffsets::try_new(
+                    histogram_algorithm_snd.into(),
+                    1,
+                    radix_bits.pass_radix_bits(RadixPass::Second).unwrap(),
+                    Allocator::try_mem_alloc_fn(state_mem_type.clone()),
+                )?,
+                join_result_sum: 0,
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    // Populate the pages with mlock(); ideally this would be taken care of by a
+    // NUMA-aware malloc implementation
+    thread_states
+        .iter_mut()
+        .try_for_each(|state| state.mlock())?;
+
+    // Allocate the 2nd pass partitions. Each 1st pass partition is
+    // repartitioned into a separate relation, so that the join phase can be
+    // measured separately from the partitioning phase.
+    let alloc_partitions_2nd = |lens: &[usize]| {
+        lens.iter()
+            .map(|&len| {
+                let mut p = PartitionedRelation::<Tuple<T, T>>::try_new(
+                    len,
+                    histogram_algorithm_snd.into(),
+                    radix_bits.pass_radix_bits(RadixPass::Second).unwrap(),
+                    1,
+                    Allocator::try_mem_alloc_fn(partitions_mem_type.clone()),
+                    Allocator::try_mem_alloc_fn(partitions_mem_type.clone()),
+                )?;
+                p.mlock()?;
+                Ok(p)
+            })
+            .collect::<Result<Vec<_>>>()
+    };
+    let mut inner_rel_partitions_2nd = alloc_partitions_2nd(&inner_partition_lens)?;
+    let mut outer_rel_partitions_2nd = alloc_partitions_2nd(&outer_partition_lens)?;
+
+    let state_malloc_time = state_malloc_timer.elapsed();
+
+    let partition_span = Span::new("phase_partition");
+    let partition_timer = Instant::now();
+
+    // Partition inner relation
+    let inner_key_chunks = inner_key_slice.input_chunks::<T>(max_chunks_1st)?;
+    let mut results = thread_results(threads);
+    thread_pool.scope(|s| {
+        for (radix_prnr, key_chunk, pay_chunk, offsets_chunk, partitioned_chunk, result) in izip!(
+            radix_prnrs.iter_mut(),
+            inner_key_chunks.into_iter(),
+            inner_pay_chunks.into_iter(),
+            inner_rel_partition_offsets.chunks_mut(),
+            inner_rel_partitions.chunks_mut(),
+            results.iter_mut()
+        ) {
+            s.spawn(move |_| {
+                *result = radix_prnr
+                    .partition(key_chunk, pay_chunk, offsets_chunk, partitioned_chunk)
+                    .map_err(Error::from);
+            })
+        }
+    });
+    results.into_iter().collect::<Result<()>>()?;
+
+    // Partition outer relation
+    let outer_key_chunks = outer_key_slice.input_chunks::<T>(max_chunks_1st)?;
+    let mut results = thread_results(threads);
+    thread_pool.scope(|s| {
+        for (radix_prnr, key_chunk, pay_chunk, offsets_chunk, partitioned_chunk, result) in izip!(
+            radix_prnrs.iter_mut(),
+            outer_key_chunks.into_iter(),
+            outer_pay_chunks.into_iter(),
+            outer_rel_partition_offsets.chunks_mut(),
+            outer_rel_partitions.chunks_mut(),
+            results.iter_mut()
+        ) {
+            s.spawn(move |_| {
+                *result = radix_prnr
+                    .partition(key_chunk, pay_chunk, offsets_chunk, partitioned_chunk)
+                    .map_err(Error::from);
+            })
+        }
+    });
+    results.into_iter().collect::<Result<()>>()?;
+
+    // 2nd pass partition both relations
+    let mut results = thread_results(threads);
+    thread_pool.scope(|s| {
+        for (thread_id, state, inner_2nd_chunk, outer_2nd_chunk, result) in izip!(
+            0..threads,
+            thread_states.iter_mut(),
+            inner_rel_partitions_2nd.chunks_mut(partitions_per_thread),
+            outer_rel_partitions_2nd.chunks_mut(partitions_per_thread),
+            results.iter_mut()
+        ) {
+            let inner_rel_partitions = &inner_rel_partitions;
+            let outer_rel_partitions = &outer_rel_partitions;
+
+            s.spawn(move |_| {
+                *result = izip!(0.., inner_2nd_chunk.iter_mut(), outer_2nd_chunk.iter_mut())
+                    .try_for_each(|(i, inner_2nd, outer_2nd)| {
+                        let partition_id = (thread_id * partitions_per_thread + i) as u32;
+
+                        state.partition_2nd_pass(partition_id, inner_rel_partitions, inner_2nd)?;
+                        state.partition_2nd_pass(partition_id, outer_rel_partitions, outer_2nd)
+                    });
+            })
+        }
+    });
+    results.into_iter().collect::<Result<()>>()?;
+    affinity_result(&affinity_error)?;
+
+    let partition_time = partition_timer.elapsed();
+    partition_span.end();
+
+    let join_span = Span::new("phase_join");
+    let join_timer = Instant::now();
+
+    let mut results = thread_results(threads);
+    thread_pool.scope(|s| {
+        for (state, inner_2nd_chunk, outer_2nd_chunk, result) in izip!(
+            thread_states.iter_mut(),
+            inner_rel_partitions_2nd.chunks(partitions_per_thread),
+            outer_rel_partitions_2nd.chunks(partitions_per_thread),
+            results.iter_mut()
+        ) {
+            s.spawn(move |_| {
+                let ThreadState {
+                    radix_join,
+                    join_result_sum,
+                    ..
+                } = state;
+
+                *result = inner_2nd_chunk
+                    .iter()
+                    .zip(outer_2nd_chunk.iter())
+                    .try_for_each(|(inner_2nd, outer_2nd)| {
+                        radix_join.join(inner_2nd, outer_2nd, join_result_sum)
+                    })
+                    .map_err(Error::from);
+            })
+        }
+    });
+    results.into_iter().collect::<Result<()>>()?;
+    affinity_result(&affinity_error)?;
+
+    let join_time = join_timer.elapsed();
+    join_span.end();
+
+    let sum = thread_states
+        .iter()
+        .map(|state| state.join_result_sum)
+        .sum();
+
+    let data_point = RadixJoinPoint {
+        prefix_sum_ns: Some(prefix_sum_time),
+        partition_ns: Some(partition_time.as_nanos() as f64),
+        join_ns: Some(join_time.as_nanos() as f64),
+        partitions_malloc_ns: Some(partitions_malloc_time.as_nanos() as f64),
+        state_malloc_ns: Some(state_malloc_time.as_nanos() as f64),
+        cached_build_tuples: None,
+        cached_probe_tuples: None,
+        skewed_partitions: None,
+    };
+
+    Ok((sum, data_point))
+}
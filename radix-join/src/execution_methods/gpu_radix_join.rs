@@ -12,9 +12,9 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::error::{ErrorKind, Result};
+use crate::error::{Error, ErrorKind, Result};
 use crate::measurement::harness::RadixJoinPoint;
-use cstr::cstr;
+use crate::types::SkewHandling;
 use data_store::join_data::JoinData;
 use datagen::relation::KeyAttribute;
 use numa_gpu::error::Result as NumaGpuResult;
@@ -23,13 +23,14 @@ use numa_gpu::runtime::cpu_affinity::CpuAffinity;
 use numa_gpu::runtime::cuda_wrapper;
 use numa_gpu::runtime::memory::*;
 use numa_gpu::runtime::numa::PageType;
-use numa_gpu::runtime::nvtx::Range;
+use numa_gpu::runtime::trace::Span;
 use numa_gpu::utils::DeviceType;
 use rustacuda::context::{CacheConfig, CurrentContext, SharedMemoryConfig};
 use rustacuda::event::{Event, EventFlags};
 use rustacuda::function::{BlockSize, GridSize};
 use rustacuda::memory::{CopyDestination, DeviceBuffer, DeviceCopy};
 use rustacuda::stream::{Stream, StreamFlags, StreamWaitEventFlags};
+use sql_ops::hash_function::HashFunction;
 use sql_ops::join::{cuda_radix_join, no_partitioning_join, HashingScheme};
 use sql_ops::partition::cpu_radix_partition::{
     CpuHistogramAlgorithm, CpuRadixPartitionAlgorithm, CpuRadixPartitionable, CpuRadixPartitioner,
@@ -90,6 +91,8 @@ impl<T: DeviceCopy> MemLock for StreamState<T> {
 pub fn gpu_radix_join<T>(
     data: &mut JoinData<T>,
     hashing_scheme: HashingScheme,
+    hash_function: HashFunction,
+    skew_handling: SkewHandling,
     histogram_algorithm_fst: DeviceType<CpuHistogramAlgorithm, GpuHistogramAlgorithm>,
     histogram_algorithm_snd: DeviceType<CpuHistogramAlgorithm, GpuHistogramAlgorithm>,
     partition_algorithm_fst: DeviceType<CpuRadixPartitionAlgorithm, GpuRadixPartitionAlgorithm>,
@@ -130,6 +133,11 @@ where
     let partition_algorithm_snd = partition_algorithm_snd.gpu().ok_or_else(|| {
         ErrorKind::InvalidArgument("Only GPU partitioning is supported in 2nd pass".to_string())
     })?;
+    if skew_handling != SkewHandling::Disabled {
+        Err(ErrorKind::InvalidArgument(
+            "Skew handling is not supported by this execution method".to_string(),
+        ))?;
+    }
 
     CurrentContext::set_cache_config(CacheConfig::PreferShared)?;
     CurrentContext::set_shared_memory_config(SharedMemoryConfig::FourByteBankSize)?;
@@ -163,40 +171,41 @@ where
         grid_size,
         block_size,
         dmem_buffer_bytes,
-    )?;
+    )?
+    .with_hash_function(hash_function);
     radix_prnr.preallocate_partition_state::<T>(RadixPass::First)?;
 
-    let mut inner_rel_partitions = PartitionedRelation::new(
+    let mut inner_rel_partitions = PartitionedRelation::try_new(
         data.build_relation_key.len(),
         histogram_algorithm_fst.either(|cpu| cpu.into(), |gpu| gpu.into()),
         radix_bits.pass_radix_bits(RadixPass::First).unwrap(),
         max_chunks_1st,
-        Allocator::mem_alloc_fn(partitions_mem_type.clone()),
-        Allocator::mem_alloc_fn(partitions_mem_type.clone()),
-    );
+        Allocator::try_mem_alloc_fn(partitions_mem_type.clone()),
+        Allocator::try_mem_alloc_fn(partitions_mem_type.clone()),
+    )?;
 
-    let mut outer_rel_partitions = PartitionedRelation::new(
+    let mut outer_rel_partitions = PartitionedRelation::try_new(
         data.probe_relation_key.len(),
         histogram_algorithm_fst.either(|cpu| cpu.into(), |gpu| gpu.into()),
         radix_bits.pass_radix_bits(RadixPass::First).unwrap(),
         max_chunks_1st,
-        Allocator::mem_alloc_fn(partitions_mem_type.clone()),
-        Allocator::mem_alloc_fn(partitions_mem_type.clone()),
-    );
+        Allocator::try_mem_alloc_fn(partitions_mem_type.clone()),
+        Allocator::try_mem_alloc_fn(partitions_mem_type.clone()),
+    )?;
 
-    let mut inner_rel_partition_offsets = PartitionOffsets::new(
+    let mut inner_rel_partition_offsets = PartitionOffsets::try_new(
         histogram_algorithm_fst.either(|cpu| cpu.into(), |gpu| gpu.into()),
         max_chunks_1st,
         radix_bits.pass_radix_bits(RadixPass::First).unwrap(),
-        Allocator::mem_alloc_fn(partitions_mem_type.clone()),
-    );
+        Allocator::try_mem_alloc_fn(partitions_mem_type.clone()),
+    )?;
 
-    let mut outer_rel_partition_offsets = PartitionOffsets::new(
+    let mut outer_rel_partition_offsets = PartitionOffsets::try_new(
         histogram_algorithm_fst.either(|cpu| cpu.into(), |gpu| gpu.into()),
         max_chunks_1st,
         radix_bits.pass_radix_bits(RadixPass::First).unwrap(),
-        Allocator::mem_alloc_fn(partitions_mem_type.clone()),
-    );
+        Allocator::try_mem_alloc_fn(partitions_mem_type.clone()),
+    )?;
 
     inner_rel_partitions.mlock()?;
     outer_rel_partitions.mlock()?;
@@ -207,7 +216,7 @@ where
 
     let partitions_malloc_time = partitions_malloc_timer.elapsed();
 
-    let prefix_sum_range = Range::new(cstr!("phase_prefix_sum"));
+    let prefix_sum_span = Span::new("phase_prefix_sum");
     let prefix_sum_time = match histogram_algorithm_fst {
         DeviceType::Cpu(histogram_algorithm) => {
             let prefix_sum_timer = Instant::now();
@@ -224,30 +233,39 @@ where
             let outer_key_chunks = outer_key_slice.input_chunks::<T>(max_chunks_1st)?;
             let outer_offsets_chunks = outer_rel_partition_offsets.chunks_mut();
 
+            // First outer and then inner, because inner is smaller. Both have equal amount
+            // of chunks, thus inner chunks are smaller. Scheduling the smaller chunks last
+            // potentially mitigates stragglers.
+            let chunks: Vec<_> = outer_key_chunks
+                .into_iter()
+                .zip(outer_offsets_chunks)
+                .chain(inner_key_chunks.into_iter().zip(inner_offsets_chunks))
+                .collect();
+
+            // Each thread writes its result into its slot, because errors cannot be
+            // returned from within a thread pool scope.
+            let mut results: Vec<Result<()>> = chunks.iter().map(|_| Ok(())).collect();
+
             thread_pool.scope(|s| {
-                // First outer and then inner, because inner is smaller. Both have equal amount
-                // of chunks, thus inner chunks are smaller. Scheduling the smaller chunks last
-                // potentially mitigates stragglers.
-                for (input, output) in outer_key_chunks
-                    .into_iter()
-                    .zip(outer_offsets_chunks)
-                    .chain(inner_key_chunks.into_iter().zip(inner_offsets_chunks))
-                {
+                for ((input, output), result) in chunks.into_iter().zip(results.iter_mut()) {
                     s.spawn(move |_| {
-                        let mut radix_prnr = CpuRadixPartitioner::new(
+                        *result = CpuRadixPartitioner::new(
                             histogram_algorithm,
                             CpuRadixPartitionAlgorithm::NC,
                             radix_bits.pass_radix_bits(RadixPass::First).unwrap(),
                             DerefMemType::AlignedSysMem {
                                 align_bytes: sql_ops::CPU_CACHE_LINE_SIZE as usize,
                             },
-                        );
-                        radix_prnr
-                            .prefix_sum(input, output)
-                            .expect("Failed to run CPU prefix sum");
+                        )
+                        .and_then(|radix_prnr| {
+                            let mut radix_prnr = radix_prnr.with_hash_function(hash_function);
+                            radix_prnr.prefix_sum(input, output)
+                        })
+                        .map_err(Error::from);
                     })
                 }
             });
+            results.into_iter().collect::<Result<()>>()?;
 
             prefix_sum_timer.elapsed().as_nanos() as f64
         }
@@ -275,11 +293,11 @@ where
                 * 10_f64.powf(6.0)
         }
     };
-    prefix_sum_range.end();
+    prefix_sum_span.end();
 
     let partition_start_event = Event::new(EventFlags::DEFAULT)?;
     let partition_stop_event = Event::new(EventFlags::DEFAULT)?;
-    let partition_range = Range::new(cstr!("phase_partition"));
+    let partition_span = Span::new("phase_partition");
     partition_start_event.record(&stream)?;
 
     // Partition inner relation
@@ -333,62 +351,64 @@ where
                 stream_grid_size,
                 stream_block_size,
                 dmem_buffer_bytes,
-            )?,
+            )?
+            .with_hash_function(hash_function),
             radix_join: cuda_radix_join::CudaRadixJoin::new(
                 RadixPass::Second,
                 radix_bits.clone(),
                 hashing_scheme,
                 stream_grid_size,
                 stream_block_size,
-            )?,
-            cached_inner_key: Allocator::alloc_mem(
+            )?
+            .with_hash_function(hash_function),
+            cached_inner_key: Allocator::try_alloc_mem(
                 stream_state_mem_type.clone(),
                 max_inner_partition_len,
-            ),
-            cached_inner_pay: Allocator::alloc_mem(
+            )?,
+            cached_inner_pay: Allocator::try_alloc_mem(
                 stream_state_mem_type.clone(),
                 max_inner_partition_len,
-            ),
-            cached_outer_key: Allocator::alloc_mem(
+            )?,
+            cached_outer_key: Allocator::try_alloc_mem(
                 stream_state_mem_type.clone(),
                 max_outer_partition_len,
-            ),
-            cached_outer_pay: Allocator::alloc_mem(
+            )?,
+            cached_outer_pay: Allocator::try_alloc_mem(
                 stream_state_mem_type.clone(),
                 max_outer_partition_len,
-            ),
-            inner_rel_partition_offsets_2nd: PartitionOffsets::new(
+            )?,
+            inner_rel_partition_offsets_2nd: PartitionOffsets::try_new(
                 histogram_algorithm_snd.into(),
                 max_chunks_2nd,
                 radix_bits.pass_radix_bits(RadixPass::Second).unwrap(),
-                Allocator::mem_alloc_fn(stream_state_mem_type.clone()),
-            ),
-            outer_rel_partition_offsets_2nd: PartitionOffsets::new(
+                Allocator::try_mem_alloc_fn(stream_state_mem_type.clone()),
+            )?,
+            outer_rel_partition_offsets_2nd: PartitionOffsets::try_new(
                 histogram_algorithm_snd.into(),
                 max_chunks_2nd,
                 radix_bits.pass_radix_bits(RadixPass::Second).unwrap(),
-                Allocator::mem_alloc_fn(stream_state_mem_type.clone()),
-            ),
-            inner_rel_partitions_2nd: PartitionedRelation::new(
+                Allocator::try_mem_alloc_fn(stream_state_mem_type.clone()),
+            )?,
+            inner_rel_partitions_2nd: PartitionedRelation::try_new(
                 max_inner_partition_len,
                 histogram_algorithm_snd.into(),
                 radix_bits.pass_radix_bits(RadixPass::Second).unwrap(),
                 max_chunks_2nd,
-                Allocator::mem_alloc_fn(stream_state_mem_type.clone()),
-                Allocator::mem_alloc_fn(stream_state_mem_type.clone()),
-            ),
-            outer_rel_partitions_2nd: PartitionedRelation::new(
+                Allocator::try_mem_alloc_fn(stream_state_mem_type.clone()),
+                Allocator::try_mem_alloc_fn(stream_state_mem_type.clone()),
+            )?,
+            outer_rel_partitions_2nd: PartitionedRelation::try_new(
                 max_outer_partition_len,
                 histogram_algorithm_snd.into(),
                 radix_bits.pass_radix_bits(RadixPass::Second).unwrap(),
                 max_chunks_2nd,
-                Allocator::mem_alloc_fn(stream_state_mem_type.clone()),
-                Allocator::mem_alloc_fn(stream_state_mem_type.clone()),
-            ),
-            join_task_assignments: Allocator::alloc_mem(
+                Allocator::try_mem_alloc_fn(stream_state_mem_type.clone()),
+                Allocator::try_mem_alloc_fn(stream_state_mem_type.clone()),
+            )?,
+            join_task_assignments: Allocator::try_alloc_mem(
                 stream_state_mem_type.clone(),
                 join_dim.0.x as usize + 1,
-            ),
+            )?,
             join_result_sums,
         })
     })
@@ -398,7 +418,7 @@ where
     stream.synchronize()?;
     let partition_time =
         partition_stop_event.elapsed_time_f32(&partition_start_event)? as f64 * 10_f64.powf(6.0);
-    partition_range.end();
+    partition_span.end();
 
     Stream::drop(stream).map_err(|(e, _)| e)?;
 
@@ -412,7 +432,7 @@ where
 
     let state_malloc_time = state_malloc_timer.elapsed();
 
-    let join_range = Range::new(cstr!("phase_join"));
+    let join_span = Span::new("phase_join");
     let join_start_event = Event::new(EventFlags::DEFAULT)?;
     stream_states
         .iter()
@@ -530,7 +550,7 @@ where
                 stop_event.elapsed_time_f32(&join_start_event)? as f64 * 10_f64.powf(6.0);
             Ok(time.max(new_time))
         })?;
-    join_range.end();
+    join_span.end();
 
     let mut result_sums_host = vec![0; join_result_sums_len * NUM_STREAMS];
     stream_states
@@ -559,6 +579,7 @@ where
         state_malloc_ns: Some(state_malloc_time.as_nanos() as f64),
         cached_build_tuples: None,
         cached_probe_tuples: None,
+        skewed_partitions: None,
     };
 
     Ok((sum, data_point))
@@ -12,11 +12,13 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::error::{ErrorKind, Result};
+use super::skew_join;
+use crate::error::{Error, ErrorKind, Result};
 use crate::measurement::harness::RadixJoinPoint;
-use cstr::cstr;
+use crate::types::SkewHandling;
 use data_store::join_data::JoinData;
 use datagen::relation::KeyAttribute;
+use num_traits::AsPrimitive;
 use numa_gpu::error::Result as NumaGpuResult;
 use numa_gpu::runtime::allocator::{Allocator, CacheSpillType, DerefMemType, MemType};
 use numa_gpu::runtime::cpu_affinity::CpuAffinity;
@@ -24,13 +26,14 @@ use numa_gpu::runtime::cuda_wrapper;
 use numa_gpu::runtime::linux_wrapper;
 use numa_gpu::runtime::memory::*;
 use numa_gpu::runtime::numa::PageType;
-use numa_gpu::runtime::nvtx::Range;
+use numa_gpu::runtime::trace::Span;
 use numa_gpu::utils::DeviceType;
 use rustacuda::context::{CacheConfig, CurrentContext, SharedMemoryConfig};
 use rustacuda::event::{Event, EventFlags};
 use rustacuda::function::{BlockSize, GridSize};
 use rustacuda::memory::{CopyDestination, DeviceBuffer, DeviceCopy};
 use rustacuda::stream::{Stream, StreamFlags, StreamWaitEventFlags};
+use sql_ops::hash_function::HashFunction;
 use sql_ops::join::{cuda_radix_join, no_partitioning_join, HashingScheme};
 use sql_ops::partition::cpu_radix_partition::{
     CpuHistogramAlgorithm, CpuRadixPartitionAlgorithm, CpuRadixPartitionable, CpuRadixPartitioner,
@@ -40,15 +43,16 @@ use sql_ops::partition::gpu_radix_partition::{
 };
 use sql_ops::partition::{
     PartitionOffsets, PartitionedRelation, RadixBits, RadixPartitionInputChunkable, RadixPass,
-    Tuple, fanout, HistogramAlgorithmType,
+    Tuple, fanout, skew, HistogramAlgorithmType,
 };
 use sql_ops::partition::partitioned_relation::padding_len;
 use cust::memory::mem_get_info;
 
 use std::cmp;
-use std::convert::TryInto;
+use std::convert::{TryFrom, TryInto};
 use std::iter;
 use std::mem;
+use std::os::raw::c_uint;
 use std::sync::Arc;
 use std::time::Instant;
 
@@ -102,6 +106,8 @@ impl<T: DeviceCopy> MemLock for StreamState<T> {
 pub fn gpu_triton_join<T>(
     data: &mut JoinData<T>,
     hashing_scheme: HashingScheme,
+    hash_function: HashFunction,
+    skew_handling: SkewHandling,
     histogram_algorithm_fst: DeviceType<CpuHistogramAlgorithm, GpuHistogramAlgorithm>,
     histogram_algorithm_snd: DeviceType<CpuHistogramAlgorithm, GpuHistogramAlgorithm>,
     partition_algorithm_fst: DeviceType<CpuRadixPartitionAlgorithm, GpuRadixPartitionAlgorithm>,
@@ -126,6 +132,7 @@ where
         + CpuRadixPartitionable
         + GpuRadixPartitionable
         + KeyAttribute
+        + AsPrimitive<c_uint>
         + no_partitioning_join::CudaHashJoinable
         + no_partitioning_join::CpuHashJoinable
         + cuda_radix_join::CudaRadixJoinable,
@@ -142,6 +149,13 @@ where
     let partition_algorithm_snd = partition_algorithm_snd.gpu().ok_or_else(|| {
         ErrorKind::InvalidArgument("Only GPU partitioning is supported in 2nd pass".to_string())
     })?;
+    if skew_handling != SkewHandling::Disabled
+        && DerefMemType::try_from(partitions_mem_type.clone()).is_err()
+    {
+        Err(ErrorKind::InvalidArgument(
+            "Skew handling requires partitions that are accessible by the CPU".to_string(),
+        ))?;
+    }
 
     CurrentContext::set_cache_config(CacheConfig::PreferShared)?;
     CurrentContext::set_shared_memory_config(SharedMemoryConfig::FourByteBankSize)?;
@@ -197,22 +211,23 @@ where
         grid_size,
         block_size,
         dmem_buffer_bytes,
-    )?;
+    )?
+    .with_hash_function(hash_function);
     radix_prnr.preallocate_partition_state::<T>(RadixPass::First)?;
 
-    let mut inner_rel_partition_offsets = PartitionOffsets::new(
+    let mut inner_rel_partition_offsets = PartitionOffsets::try_new(
         histogram_algorithm_fst.either(|cpu| cpu.into(), |gpu| gpu.into()),
         max_chunks_1st,
         radix_bits.pass_radix_bits(RadixPass::First).unwrap(),
-        Allocator::mem_alloc_fn(offsets_mem_type.clone()),
-    );
+        Allocator::try_mem_alloc_fn(offsets_mem_type.clone()),
+    )?;
 
-    let mut outer_rel_partition_offsets = PartitionOffsets::new(
+    let mut outer_rel_partition_offsets = PartitionOffsets::try_new(
         histogram_algorithm_fst.either(|cpu| cpu.into(), |gpu| gpu.into()),
         max_chunks_1st,
         radix_bits.pass_radix_bits(RadixPass::First).unwrap(),
-        Allocator::mem_alloc_fn(offsets_mem_type.clone()),
-    );
+        Allocator::try_mem_alloc_fn(offsets_mem_type.clone()),
+    )?;
 
     inner_rel_partition_offsets.mlock()?;
     outer_rel_partition_offsets.mlock()?;
@@ -221,7 +236,7 @@ where
 
     let partitions_malloc_time = partitions_malloc_timer.elapsed();
 
-    let prefix_sum_range = Range::new(cstr!("phase_prefix_sum"));
+    let prefix_sum_span = Span::new("phase_prefix_sum");
     let prefix_sum_time = match histogram_algorithm_fst {
         DeviceType::Cpu(histogram_algorithm) => {
             let prefix_sum_timer = Instant::now();
@@ -238,30 +253,39 @@ where
             let outer_key_chunks = outer_key_slice.input_chunks::<T>(max_chunks_1st)?;
             let outer_offsets_chunks = outer_rel_partition_offsets.chunks_mut();
 
+            // First outer and then inner, because inner is smaller. Both have equal amount
+            // of chunks, thus inner chunks are smaller. Scheduling the smaller chunks last
+            // potentially mitigates stragglers.
+            let chunks: Vec<_> = outer_key_chunks
+                .into_iter()
+                .zip(outer_offsets_chunks)
+                .chain(inner_key_chunks.into_iter().zip(inner_offsets_chunks))
+                .collect();
+
+            // Each thread writes its result into its slot, because errors cannot be
+            // returned from within a thread pool scope.
+            let mut results: Vec<Result<()>> = chunks.iter().map(|_| Ok(())).collect();
+
             thread_pool.scope(|s| {
-                // First outer and then inner, because inner is smaller. Both have equal amount
-                // of chunks, thus inner chunks are smaller. Scheduling the smaller chunks last
-                // potentially mitigates stragglers.
-                for (input, output) in outer_key_chunks
-                    .into_iter()
-                    .zip(outer_offsets_chunks)
-                    .chain(inner_key_chunks.into_iter().zip(inner_offsets_chunks))
-                {
+                for ((input, output), result) in chunks.into_iter().zip(results.iter_mut()) {
                     s.spawn(move |_| {
-                        let mut radix_prnr = CpuRadixPartitioner::new(
+                        *result = CpuRadixPartitioner::new(
                             histogram_algorithm,
                             CpuRadixPartitionAlgorithm::NC,
                             radix_bits.pass_radix_bits(RadixPass::First).unwrap(),
                             DerefMemType::AlignedSysMem {
                                 align_bytes: sql_ops::CPU_CACHE_LINE_SIZE as usize,
                             },
-                        );
-                        radix_prnr
-                            .prefix_sum(input, output)
-                            .expect("Failed to run CPU prefix sum");
+                        )
+                        .and_then(|radix_prnr| {
+                            let mut radix_prnr = radix_prnr.with_hash_function(hash_function);
+                            radix_prnr.prefix_sum(input, output)
+                        })
+                        .map_err(Error::from);
                     })
                 }
             });
+            results.into_iter().collect::<Result<()>>()?;
 
             prefix_sum_timer.elapsed().as_nanos() as f64
         }
@@ -289,22 +313,39 @@ where
                 * 10_f64.powf(6.0)
         }
     };
-    prefix_sum_range.end();
+    prefix_sum_span.end();
+
+    // Detect skewed partitions using the histograms of the prefix sum. Skewed
+    // partitions are joined on the CPU instead of the GPU.
+    let skewed_partition_ids = match skew_handling {
+        SkewHandling::Disabled => Vec::new(),
+        SkewHandling::Broadcast(skew_factor) => {
+            let mut ids = skew::skewed_partitions(&inner_rel_partition_offsets, skew_factor)?;
+            ids.extend(skew::skewed_partitions(
+                &outer_rel_partition_offsets,
+                skew_factor,
+            )?);
+            ids.sort_unstable();
+            ids.dedup();
+            ids
+        }
+    };
+    let gpu_partition_ids: Vec<u32> = (0..radix_bits.pass_fanout(RadixPass::First).unwrap())
+        .filter(|partition_id| skewed_partition_ids.binary_search(partition_id).is_err())
+        .collect();
 
     let state_malloc_timer = Instant::now();
 
-    let max_inner_partition_len =
-        (0..inner_rel_partition_offsets.fanout()).try_fold(0, |max, partition_id| {
-            inner_rel_partition_offsets
-                .partition_len(partition_id)
-                .map(|len| cmp::max(max, len))
-        })?;
-    let max_outer_partition_len =
-        (0..outer_rel_partition_offsets.fanout()).try_fold(0, |max, partition_id| {
-            outer_rel_partition_offsets
-                .partition_len(partition_id)
-                .map(|len| cmp::max(max, len))
-        })?;
+    let max_inner_partition_len = gpu_partition_ids.iter().try_fold(0, |max, &partition_id| {
+        inner_rel_partition_offsets
+            .partition_len(partition_id)
+            .map(|len| cmp::max(max, len))
+    })?;
+    let max_outer_partition_len = gpu_partition_ids.iter().try_fold(0, |max, &partition_id| {
+        outer_rel_partition_offsets
+            .partition_len(partition_id)
+            .map(|len| cmp::max(max, len))
+    })?;
 
     // Memory allocations occur asynchronously in parallel to partitioning
     let mut stream_states = iter::repeat_with(|| {
@@ -322,62 +363,64 @@ where
                 stream_grid_size,
                 stream_block_size,
                 dmem_buffer_bytes,
-            )?,
+            )?
+            .with_hash_function(hash_function),
             radix_join: cuda_radix_join::CudaRadixJoin::new(
                 RadixPass::Second,
                 radix_bits.clone(),
                 hashing_scheme,
                 stream_grid_size,
                 stream_block_size,
-            )?,
-            cached_inner_key: Allocator::alloc_mem(
+            )?
+            .with_hash_function(hash_function),
+            cached_inner_key: Allocator::try_alloc_mem(
                 stream_state_mem_type.clone(),
                 max_inner_partition_len,
-            ),
-            cached_inner_pay: Allocator::alloc_mem(
+            )?,
+            cached_inner_pay: Allocator::try_alloc_mem(
                 stream_state_mem_type.clone(),
                 max_inner_partition_len,
-            ),
-            cached_outer_key: Allocator::alloc_mem(
+            )?,
+            cached_outer_key: Allocator::try_alloc_mem(
                 stream_state_mem_type.clone(),
                 max_outer_partition_len,
-            ),
-            cached_outer_pay: Allocator::alloc_mem(
+            )?,
+            cached_outer_pay: Allocator::try_alloc_mem(
                 stream_state_mem_type.clone(),
                 max_outer_partition_len,
-            ),
-            inner_rel_partition_offsets_2nd: PartitionOffsets::new(
+            )?,
+            inner_rel_partition_offsets_2nd: PartitionOffsets::try_new(
                 histogram_algorithm_snd.into(),
                 max_chunks_2nd,
                 radix_bits.pass_radix_bits(RadixPass::Second).unwrap(),
-                Allocator::mem_alloc_fn(stream_state_mem_type.clone()),
-            ),
-            outer_rel_partition_offsets_2nd: PartitionOffsets::new(
+                Allocator::try_mem_alloc_fn(stream_state_mem_type.clone()),
+            )?,
+            outer_rel_partition_offsets_2nd: PartitionOffsets::try_new(
                 histogram_algorithm_snd.into(),
                 max_chunks_2nd,
                 radix_bits.pass_radix_bits(RadixPass::Second).unwrap(),
-                Allocator::mem_alloc_fn(stream_state_mem_type.clone()),
-            ),
-            inner_rel_partitions_2nd: PartitionedRelation::new(
+                Allocator::try_mem_alloc_fn(stream_state_mem_type.clone()),
+            )?,
+            inner_rel_partitions_2nd: PartitionedRelation::try_new(
                 max_inner_partition_len,
                 histogram_algorithm_snd.into(),
                 radix_bits.pass_radix_bits(RadixPass::Second).unwrap(),
                 max_chunks_2nd,
-                Allocator::mem_alloc_fn(stream_state_mem_type.clone()),
-                Allocator::mem_alloc_fn(stream_state_mem_type.clone()),
-            ),
-            outer_rel_partitions_2nd: PartitionedRelation::new(
+                Allocator::try_mem_alloc_fn(stream_state_mem_type.clone()),
+                Allocator::try_mem_alloc_fn(stream_state_mem_type.clone()),
+            )?,
+            outer_rel_partitions_2nd: PartitionedRelation::try_new(
                 max_outer_partition_len,
                 histogram_algorithm_snd.into(),
                 radix_bits.pass_radix_bits(RadixPass::Second).unwrap(),
                 max_chunks_2nd,
-                Allocator::mem_alloc_fn(stream_state_mem_type.clone()),
-                Allocator::mem_alloc_fn(stream_state_mem_type.clone()),
-            ),
-            join_task_assignments: Allocator::alloc_mem(
+                Allocator::try_mem_alloc_fn(stream_state_mem_type.clone()),
+                Allocator::try_mem_alloc_fn(stream_state_mem_type.clone()),
+            )?,
+            join_task_assignments: Allocator::try_alloc_mem(
                 stream_state_mem_type.clone(),
                 join_dim.0.x as usize + 1,
-            ),
+            )?,
             join_result_sums,
         })
     })
@@ -448,7 +491,9 @@ where
     let partitions_mem_type_revised = match partitions_mem_type {
         MemType::DistributedNumaMem {..} => partitions_mem_type.clone(),
         _ => {
-            if free_mem > total_relation_size {
+            // The CPU joins the skewed partitions, thus the partitions must
+            // stay accessible by the CPU
+            if free_mem > total_relation_size && skew_handling == SkewHandling::Disabled {
                 println!("@@@ can use Devmem");
                 MemType::CudaDevMem
             } else {
@@ -525,7 +570,7 @@ where
 
     let partition_start_event = Event::new(EventFlags::DEFAULT)?;
     let partition_stop_event = Event::new(EventFlags::DEFAULT)?;
-    let partition_range = Range::new(cstr!("phase_partition"));
+    let partition_span = Span::new("phase_partition");
     partition_start_event.record(&stream)?;
 
     // Partition inner relation
@@ -553,19 +598,20 @@ where
     stream.synchronize()?;
     let partition_time =
         partition_stop_event.elapsed_time_f32(&partition_start_event)? as f64 * 10_f64.powf(6.0);
-    partition_range.end();
+    partition_span.end();
 
     Stream::drop(stream).map_err(|(e, _)| e)?;
 
-    let join_range = Range::new(cstr!("phase_join"));
+    let join_span = Span::new("phase_join");
     let join_start_event = Event::new(EventFlags::DEFAULT)?;
     stream_states
         .iter()
         .take(1)
         .try_for_each(|StreamState { stream, .. }| join_start_event.record(stream))?;
 
-    for (partition_id, stream_id) in
-        (0..radix_bits.pass_fanout(RadixPass::First).unwrap()).zip((0..stream_states.len()).cycle())
+    for (&partition_id, stream_id) in gpu_partition_ids
+        .iter()
+        .zip((0..stream_states.len()).cycle())
     {
         let StreamState {
             stream,
@@ -657,6 +703,15 @@ where
         event.record(stream)?;
     }
 
+    // Join the skewed partitions on the CPU in parallel to the GPU. The stop
+    // events are recorded afterwards, and thus include the CPU join time.
+    let skewed_sum = skew_join::cpu_broadcast_join(
+        &thread_pool,
+        &inner_rel_partitions,
+        &outer_rel_partitions,
+        &skewed_partition_ids,
+    )?;
+
     let join_stop_events = stream_states
         .iter()
         .map(|StreamState { stream, .. }| {
@@ -675,7 +730,7 @@ where
                 stop_event.elapsed_time_f32(&join_start_event)? as f64 * 10_f64.powf(6.0);
             Ok(time.max(new_time))
         })?;
-    join_range.end();
+    join_span.end();
 
     let mut result_sums_host = vec![0; join_result_sums_len * NUM_STREAMS];
     stream_states
@@ -694,7 +749,7 @@ where
         )
         .collect::<Result<()>>()?;
 
-    let sum = result_sums_host.iter().sum();
+    let sum = result_sums_host.iter().sum::<i64>() + skewed_sum;
 
     let data_point = RadixJoinPoint {
         prefix_sum_ns: Some(prefix_sum_time),
@@ -704,6 +759,10 @@ where
         state_malloc_ns: Some(state_malloc_time.as_nanos() as f64),
         cached_build_tuples: *cached_build_tuples.borrow(),
         cached_probe_tuples: *cached_probe_tuples.borrow(),
+        skewed_partitions: match skew_handling {
+            SkewHandling::Disabled => None,
+            SkewHandling::Broadcast(_) => Some(skewed_partition_ids.len()),
+        },
     };
 
     Ok((sum, data_point))
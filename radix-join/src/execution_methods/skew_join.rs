@@ -0,0 +1,128 @@
+// Copyright 2022 Clemens Lutz
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Broadcast join for skewed partitions.
+//!
+//! Heavy-hitter keys blow up single partitions. The GPU's 2nd pass and join
+//! allocate their buffers for the largest partition, and a skewed partition
+//! takes much longer to join than the others. Instead, the skewed partitions
+//! are joined on the CPU with a no-partitioning hash join.
+//!
+//! The hash table is shared by all CPU threads, i.e., it is broadcast to all
+//! threads. Each thread unzips one chunk of the 1st pass partition into keys
+//! and payloads, and then builds or probes the hash table with them.
+
+use crate::error::{Error, ErrorKind, Result};
+use datagen::relation::KeyAttribute;
+use itertools::izip;
+use num_traits::AsPrimitive;
+use numa_gpu::runtime::allocator::{Allocator, DerefMemType};
+use numa_gpu::runtime::memory::{DeviceCopy, MemLock};
+use rayon::ThreadPool;
+use sql_ops::join::no_partitioning_join::{CpuHashJoinBuilder, CpuHashJoinable, HashTable};
+use sql_ops::join::HashingScheme;
+use sql_ops::partition::{PartitionedRelation, Tuple};
+use std::os::raw::c_uint;
+use std::sync::Arc;
+
+/// Hash table entries per build-side tuple, rounded up to a power of two.
+const HASH_TABLE_LOAD_FACTOR: usize = 2;
+
+/// Returns a result slot for each chunk.
+///
+/// Each thread writes its result into its slot, because errors cannot be
+/// returned from within a thread pool scope.
+fn thread_results(chunks: u32) -> Vec<Result<()>> {
+    (0..chunks).map(|_| Ok(())).collect()
+}
+
+/// Joins the specified partitions on the CPU and returns the sum of the probe
+/// payloads.
+///
+/// The partitioned relations must be accessible by the CPU.
+pub(super) fn cpu_broadcast_join<T>(
+    thread_pool: &ThreadPool,
+    inner_rel_partitions: &PartitionedRelation<Tuple<T, T>>,
+    outer_rel_partitions: &PartitionedRelation<Tuple<T, T>>,
+    partition_ids: &[u32],
+) -> Result<i64>
+where
+    T: AsPrimitive<c_uint>
+        + Clone
+        + Default
+        + DeviceCopy
+        + Send
+        + Sync
+        + KeyAttribute
+        + CpuHashJoinable,
+{
+    partition_ids.iter().try_fold(0_i64, |sum, &partition_id| {
+        let hash_table_len = inner_rel_partitions
+            .partition_len(partition_id)?
+            .checked_next_power_of_two()
+            .and_then(|len| len.checked_mul(HASH_TABLE_LOAD_FACTOR))
+            .ok_or_else(|| {
+                ErrorKind::IntegerOverflow("Failed to compute hash table length".to_string())
+            })?;
+
+        let hash_table_mem = Allocator::try_alloc_deref_mem(DerefMemType::SysMem, hash_table_len)?;
+        let mut hash_table = HashTable::new_on_cpu(hash_table_mem, hash_table_len)?;
+        hash_table.mlock()?;
+
+        let hj_builder = CpuHashJoinBuilder::default()
+            .hashing_scheme(HashingScheme::LinearProbing)
+            .hash_table(Arc::new(hash_table));
+
+        let mut results = thread_results(inner_rel_partitions.num_chunks());
+        thread_pool.scope(|s| {
+            for (chunk_id, result) in (0..inner_rel_partitions.num_chunks()).zip(&mut results) {
+                let mut hj_op = hj_builder.build();
+                let tuples = &inner_rel_partitions[(chunk_id, partition_id)];
+                s.spawn(move |_| {
+                    let (keys, pays): (Vec<T>, Vec<T>) = tuples
+                        .iter()
+                        .map(|t| (t.key.clone(), t.value.clone()))
+                        .unzip();
+                    *result = hj_op.build(&keys, &pays).map_err(Error::from);
+                });
+            }
+        });
+        results.into_iter().collect::<Result<()>>()?;
+
+        let mut result_sums = vec![0_u64; outer_rel_partitions.num_chunks() as usize];
+        let mut results = thread_results(outer_rel_partitions.num_chunks());
+        thread_pool.scope(|s| {
+            for (chunk_id, result_sum, result) in izip!(
+                0..outer_rel_partitions.num_chunks(),
+                &mut result_sums,
+                &mut results
+            ) {
+                let mut hj_op = hj_builder.build();
+                let tuples = &outer_rel_partitions[(chunk_id, partition_id)];
+                s.spawn(move |_| {
+                    let (keys, pays): (Vec<T>, Vec<T>) = tuples
+                        .iter()
+                        .map(|t| (t.key.clone(), t.value.clone()))
+                        .unzip();
+                    *result = hj_op
+                        .probe_sum(&keys, &pays, result_sum)
+                        .map_err(Error::from);
+                });
+            }
+        });
+        results.into_iter().collect::<Result<()>>()?;
+
+        Ok(sum + result_sums.iter().sum::<u64>() as i64)
+    })
+}
@@ -13,6 +13,8 @@
 // limitations under the License.
 
 pub mod error;
+#[cfg(feature = "cuda")]
 pub mod execution_methods;
+#[cfg(feature = "cuda")]
 pub mod measurement;
 pub mod types;
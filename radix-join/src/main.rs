@@ -13,18 +13,21 @@
 // limitations under the License.
 
 use data_store::join_data::{JoinDataBuilder, JoinDataGenFn};
-use datagen::relation::KeyAttribute;
+use datagen::relation::{derive_seed, KeyAttribute};
 use num_rational::Ratio;
+use num_traits::AsPrimitive;
 use numa_gpu::runtime::allocator::MemType;
 use numa_gpu::runtime::cpu_affinity::CpuAffinity;
+use numa_gpu::runtime::huge_pages;
 use numa_gpu::runtime::hw_info::{cpu_codename, NvidiaDriverInfo};
 use numa_gpu::runtime::linux_wrapper;
 use numa_gpu::runtime::numa::NodeRatio;
+use numa_gpu::runtime::trace;
 use numa_gpu::utils::DeviceType;
 use radix_join::error::{ErrorKind, Result};
 use radix_join::execution_methods::{
-    cpu_partitioned_radix_join::cpu_partitioned_radix_join, gpu_radix_join::gpu_radix_join,
-    gpu_triton_join::gpu_triton_join,
+    cpu_partitioned_radix_join::cpu_partitioned_radix_join, cpu_radix_join::cpu_radix_join,
+    gpu_radix_join::gpu_radix_join, gpu_triton_join::gpu_triton_join,
 };
 use radix_join::measurement::data_point::DataPoint;
 use radix_join::measurement::harness::{self, RadixJoinPoint};
@@ -36,18 +39,27 @@ use rustacuda::function::{BlockSize, GridSize};
 use rustacuda::memory::DeviceCopy;
 use rustacuda::prelude::*;
 use serde::de::DeserializeOwned;
-use sql_ops::join::{cuda_radix_join, no_partitioning_join, HashingScheme};
+use sql_ops::hash_function::HashFunction;
+use sql_ops::join::{cpu_radix_join, cuda_radix_join, no_partitioning_join, HashingScheme};
 use sql_ops::partition::cpu_radix_partition::{CpuHistogramAlgorithm, CpuRadixPartitionable};
 use sql_ops::partition::gpu_radix_partition::{GpuHistogramAlgorithm, GpuRadixPartitionable};
 use sql_ops::partition::{RadixBits, RadixPass};
 use std::convert::TryInto;
 use std::mem::size_of;
+use std::os::raw::c_uint;
 use std::path::PathBuf;
 use structopt::StructOpt;
 
 fn main() -> Result<()> {
     // Parse commandline arguments
     let mut cmd = CmdOpt::from_args();
+    cmd.check_partition_algorithms()?;
+
+    // Fall back to another page type if huge pages are unavailable
+    huge_pages::set_fallback(cmd.huge_page_fallback.into());
+
+    // Trace execution phases with the selected backend
+    trace::set_backend(cmd.trace_backend.into());
 
     // Initialize CUDA
     rustacuda::init(CudaFlags::empty())?;
@@ -73,17 +85,24 @@ fn main() -> Result<()> {
     cmd.set_state_mem(cache_node);
     cmd.set_partitions_mem(cache_node, overflow_node)?;
 
-    match cmd.tuple_bytes {
-        ArgTupleBytes::Bytes8 => {
+    match (cmd.key_bytes, cmd.payload_bytes) {
+        (ArgAttributeBytes::Bytes4, ArgAttributeBytes::Bytes4) => {
             let (hjc, dp) = args_to_bench::<i32>(&cmd, device)?;
             harness::measure("radix_join", cmd.repeat, cmd.csv, dp, hjc)?;
         }
-        ArgTupleBytes::Bytes16 => {
+        (ArgAttributeBytes::Bytes8, ArgAttributeBytes::Bytes8) => {
             let (hjc, dp) = args_to_bench::<i64>(&cmd, device)?;
             harness::measure("radix_join", cmd.repeat, cmd.csv, dp, hjc)?;
         }
+        _ => Err(ErrorKind::InvalidArgument(
+            "Radix joins require keys and payloads of the same size".to_string(),
+        ))?,
     };
 
+    if let Some(trace_file) = &cmd.trace_file {
+        trace::write_chrome_trace(trace_file)?;
+    }
+
     Ok(())
 }
 
@@ -121,6 +140,37 @@ struct CmdOpt {
     )]
     hashing_scheme: ArgHashingScheme,
 
+    /// Hash function applied to the keys before partitioning and joining
+    ///
+    /// Defaults to the identity, because radix partitioning already spreads
+    /// dense keys evenly, and perfect hashing requires the identity hash
+    /// function.
+    #[structopt(
+        long = "hash-function",
+        default_value = "Identity",
+        possible_values = &ArgHashFunction::variants(),
+        case_insensitive = true
+    )]
+    hash_function: ArgHashFunction,
+
+    /// Handling of skewed partitions
+    ///
+    /// `Broadcast` joins partitions that are larger than the skew factor times
+    /// the average partition length on the CPU. Only the
+    /// `CpuPartitionedRadixJoinTwoPass` and `GpuTritonJoinTwoPass` execution
+    /// methods support skew handling.
+    #[structopt(
+        long = "skew-handling",
+        default_value = "Disabled",
+        possible_values = &ArgSkewHandling::variants(),
+        case_insensitive = true
+    )]
+    skew_handling: ArgSkewHandling,
+
+    /// Minimum ratio of a skewed partition's length to the average partition length
+    #[structopt(long = "skew-factor", default_value = "4.0")]
+    skew_factor: f64,
+
     /// Memory type with which to allocate the partitioned data
     ///
     /// If the `GpuTritonJoinTwoPass` execution method is specified, the default
@@ -204,6 +254,38 @@ struct CmdOpt {
     )]
     page_type: ArgPageType,
 
+    /// Page type to allocate if the requested huge pages are unavailable
+    //   fail: abort with an error
+    //   transparenthuge: fall back to transparent huge pages
+    //   small: fall back to small pages
+    #[structopt(
+        long = "huge-page-fallback",
+        default_value = "Fail",
+        possible_values = &ArgHugePageFallback::variants(),
+        case_insensitive = true
+    )]
+    huge_page_fallback: ArgHugePageFallback,
+
+    /// Backend with which to trace the execution phases
+    //   noop: disable tracing
+    //   nvtx: annotate NVTX ranges for the NVIDIA profilers
+    //   chrome: write Chrome trace-event JSON to the trace file
+    #[structopt(
+        long = "trace-backend",
+        default_value = "Nvtx",
+        possible_values = &ArgTraceBackend::variants(),
+        case_insensitive = true
+    )]
+    trace_backend: ArgTraceBackend,
+
+    /// Output filename for Chrome trace events; required for `--trace-backend Chrome`
+    #[structopt(
+        long = "trace-file",
+        parse(from_os_str),
+        required_if("trace_backend", "Chrome")
+    )]
+    trace_file: Option<PathBuf>,
+
     /// Use a pre-defined or custom data set.
     //   blanas: Blanas et al. "Main memory hash join algorithms for multi-core CPUs"
     //   blanas4mb: Blanas, but with a 4 MiB inner relation
@@ -239,7 +321,11 @@ struct CmdOpt {
     )]
     selectivity: u32,
 
-    /// Load data set from a TSV file with "key value" pairs and automatic gzip decompression
+    /// Seed of the data generator's random number generator (default: random)
+    #[structopt(long = "seed")]
+    seed: Option<u64>,
+
+    /// Load data set from a columnar file, or a TSV file with "key value" pairs and automatic gzip decompression
     #[structopt(
         long = "inner-rel-file",
         parse(from_os_str),
@@ -248,7 +334,7 @@ struct CmdOpt {
     )]
     inner_rel_file: Option<PathBuf>,
 
-    /// Load data set from a TSV file with "key value" pairs and automatic gzip decompression
+    /// Load data set from a columnar file, or a TSV file with "key value" pairs and automatic gzip decompression
     #[structopt(
         long = "outer-rel-file",
         parse(from_os_str),
@@ -257,14 +343,23 @@ struct CmdOpt {
     )]
     outer_rel_file: Option<PathBuf>,
 
-    /// Set the tuple size (bytes)
+    /// Set the key size (bytes)
+    #[structopt(
+        long = "key-bytes",
+        default_value = "Bytes4",
+        possible_values = &ArgAttributeBytes::variants(),
+        case_insensitive = true
+    )]
+    key_bytes: ArgAttributeBytes,
+
+    /// Set the payload size (bytes); must equal the key size
     #[structopt(
-        long = "tuple-bytes",
-        default_value = "Bytes8",
-        possible_values = &ArgTupleBytes::variants(),
+        long = "payload-bytes",
+        default_value = "Bytes4",
+        possible_values = &ArgAttributeBytes::variants(),
         case_insensitive = true
     )]
-    tuple_bytes: ArgTupleBytes,
+    payload_bytes: ArgAttributeBytes,
 
     /// Set the inner relation size (tuples); required for `-data-set Custom`
     #[structopt(long = "inner-rel-tuples", required_if("data_set", "Custom"))]
@@ -274,7 +369,7 @@ struct CmdOpt {
     #[structopt(long = "outer-rel-tuples", required_if("data_set", "Custom"))]
     outer_rel_tuples: Option<usize>,
 
-    /// Select the histogram algorithm for 1st pass
+    /// Select the histogram algorithm for 1st pass; CpuContiguous requires CpuNC
     #[structopt(
         long,
         default_value = "GpuChunked",
@@ -283,7 +378,7 @@ struct CmdOpt {
     )]
     histogram_algorithm: ArgHistogramAlgorithm,
 
-    /// Select the histogram algorithm for 2nd pass
+    /// Select the histogram algorithm for 2nd pass; CpuContiguous requires CpuNC
     #[structopt(
         long,
         default_value = "GpuContiguous",
@@ -346,12 +441,78 @@ struct CmdOpt {
     #[structopt(long = "cpu-affinity", parse(from_os_str))]
     cpu_affinity: Option<PathBuf>,
 
+    /// Generate the CPU affinities from the NUMA topology (in place of a map file)
+    //   compact: fill all hardware threads of a core before the next core
+    //   scatter: round-robin over NUMA nodes, physical cores first
+    //   nodelocal: only CPUs of the node specified with cpu-affinity-node
+    //   physicalcores: one hardware thread per physical core
+    #[structopt(
+        long = "cpu-affinity-policy",
+        possible_values = &ArgCpuAffinityPolicy::variants(),
+        case_insensitive = true,
+        conflicts_with = "cpu-affinity"
+    )]
+    cpu_affinity_policy: Option<ArgCpuAffinityPolicy>,
+
+    /// NUMA node of the node-local CPU affinity policy
+    #[structopt(long = "cpu-affinity-node", default_value = "0")]
+    cpu_affinity_node: u16,
+
     #[structopt(long = "grid-size", require_delimiter = true)]
     /// The CUDA grid size [Default: all SMs]
     grid_size: Option<u32>,
 }
 
 impl CmdOpt {
+    fn load_cpu_affinity(&self) -> Result<CpuAffinity> {
+        let cpu_affinity = if let Some(ref cpu_affinity_file) = self.cpu_affinity {
+            CpuAffinity::from_file(cpu_affinity_file.as_path())?
+        } else if let Some(policy) = self.cpu_affinity_policy {
+            CpuAffinity::from_policy(
+                ArgCpuAffinityPolicyHelper {
+                    policy,
+                    node: self.cpu_affinity_node,
+                }
+                .into(),
+            )?
+        } else {
+            CpuAffinity::default()
+        };
+
+        Ok(cpu_affinity)
+    }
+
+    /// Rejects histogram algorithms that the partition algorithm of the same
+    /// pass doesn't support.
+    ///
+    /// Contiguous CPU partitions are only supported by the CPU NC algorithm,
+    /// because SWWC buffer flushes would overwrite the tuples of neighboring
+    /// threads.
+    fn check_partition_algorithms(&self) -> Result<()> {
+        let passes = [
+            (self.histogram_algorithm, self.partition_algorithm, ""),
+            (
+                self.histogram_algorithm_2nd,
+                self.partition_algorithm_2nd,
+                "-2nd",
+            ),
+        ];
+
+        for &(histogram_algorithm, partition_algorithm, suffix) in passes.iter() {
+            if histogram_algorithm == ArgHistogramAlgorithm::CpuContiguous
+                && partition_algorithm != ArgRadixPartitionAlgorithm::CpuNC
+            {
+                Err(ErrorKind::InvalidArgument(format!(
+                    "--histogram-algorithm{} CpuContiguous requires \
+                    --partition-algorithm{} CpuNC",
+                    suffix, suffix
+                )))?;
+            }
+        }
+
+        Ok(())
+    }
+
     fn set_state_mem(&mut self, state_location: Option<u16>) {
         self.state_mem_type = if let Some(true) = self.use_numa_mem_state {
             ArgMemType::Numa
@@ -450,7 +611,9 @@ where
         + no_partitioning_join::CudaHashJoinable
         + no_partitioning_join::CpuHashJoinable
         + cuda_radix_join::CudaRadixJoinable
+        + cpu_radix_join::CpuRadixJoinable
         + KeyAttribute
+        + AsPrimitive<c_uint>
         + num_traits::FromPrimitive
         + DeserializeOwned,
 {
@@ -474,6 +637,7 @@ where
                 );
             }
         }
+        ArgExecutionMethod::CpuRadixJoinTwoPass => {}
     };
 
     // Device tuning
@@ -537,6 +701,11 @@ where
 
     // Convert ArgHashingScheme to HashingScheme
     let hashing_scheme = HashingScheme::from(cmd.hashing_scheme);
+    let hash_function = HashFunction::from(cmd.hash_function);
+    let skew_handling = match cmd.skew_handling {
+        ArgSkewHandling::Disabled => SkewHandling::Disabled,
+        ArgSkewHandling::Broadcast => SkewHandling::Broadcast(cmd.skew_factor),
+    };
 
     let node_ratios: Box<[NodeRatio]> = cmd
         .partitions_location
@@ -561,7 +730,7 @@ where
             cmd.inner_rel_file.as_ref().and_then(|p| p.to_str()),
             cmd.outer_rel_file.as_ref().and_then(|p| p.to_str()),
         ) {
-            data_builder.build_with_files::<T>(inner_rel_path, outer_rel_path)?
+            data_builder.build_with_files::<T, T>(inner_rel_path, outer_rel_path)?
         } else {
             let data_distribution = match cmd.data_distribution {
                 ArgDataDistribution::Uniform => DataDistribution::Uniform,
@@ -574,6 +743,7 @@ where
                 cmd.outer_rel_tuples,
                 data_distribution,
                 Some(cmd.selectivity),
+                cmd.seed,
             );
             data_builder
                 .inner_len(inner_relation_len)
@@ -584,15 +754,11 @@ where
     // Construct data point template for CSV
     let dp = DataPoint::new()?
         .fill_from_cmd_options(cmd)?
-        .fill_from_join_data(&join_data)
+        .fill_from_join_data(&join_data)?
         .set_init_time(malloc_time, data_gen_time)
         .set_gpu_threads(&grid_size, &block_size);
 
-    let cpu_affinity = if let Some(ref cpu_affinity_file) = cmd.cpu_affinity {
-        CpuAffinity::from_file(cpu_affinity_file.as_path())?
-    } else {
-        CpuAffinity::default()
-    };
+    let cpu_affinity = cmd.load_cpu_affinity()?;
 
     // Create closure that wraps a hash join benchmark function
     let hjc: Box<dyn FnMut() -> Result<RadixJoinPoint>> = match exec_method {
@@ -600,6 +766,8 @@ where
             let (_result, data_point) = cpu_partitioned_radix_join(
                 &mut join_data,
                 hashing_scheme,
+                hash_function,
+                skew_handling,
                 histogram_algorithms[0],
                 histogram_algorithms[1],
                 partition_algorithm,
@@ -618,10 +786,31 @@ where
 
             Ok(data_point)
         }),
+        ArgExecutionMethod::CpuRadixJoinTwoPass => Box::new(move || {
+            let (_result, data_point) = cpu_radix_join(
+                &mut join_data,
+                hashing_scheme,
+                hash_function,
+                skew_handling,
+                histogram_algorithms[0],
+                histogram_algorithms[1],
+                partition_algorithm,
+                partition_algorithm_2nd,
+                &radix_bits,
+                threads,
+                cpu_affinity.clone(),
+                partitions_mem_type.clone(),
+                state_mem_type.clone(),
+            )?;
+
+            Ok(data_point)
+        }),
         ArgExecutionMethod::GpuRadixJoinTwoPass => Box::new(move || {
             let (_result, data_point) = gpu_radix_join(
                 &mut join_data,
                 hashing_scheme,
+                hash_function,
+                skew_handling,
                 histogram_algorithms[0],
                 histogram_algorithms[1],
                 partition_algorithm,
@@ -644,6 +833,8 @@ where
             let (_result, data_point) = gpu_triton_join(
                 &mut join_data,
                 hashing_scheme,
+                hash_function,
+                skew_handling,
                 histogram_algorithms[0],
                 histogram_algorithms[1],
                 partition_algorithm,
@@ -673,6 +864,7 @@ fn data_gen_fn<T>(
     outer_rel_tuples: Option<usize>,
     data_distribution: DataDistribution,
     selectivity: Option<u32>,
+    seed: Option<u64>,
 ) -> (usize, usize, JoinDataGenFn<T>)
 where
     T: Copy + Send + KeyAttribute + num_traits::FromPrimitive,
@@ -682,20 +874,29 @@ where
             datagen::popular::Blanas::primary_key_len(),
             datagen::popular::Blanas::foreign_key_len(),
             Box::new(move |pk_rel, _, fk_rel, _| {
-                datagen::popular::Blanas::gen(pk_rel, fk_rel, selectivity).map_err(|e| e.into())
+                datagen::popular::Blanas::gen(pk_rel, fk_rel, selectivity, seed)
+                    .map_err(|e| e.into())
             }),
         ),
         ArgDataSet::Kim => (
             datagen::popular::Kim::primary_key_len(),
             datagen::popular::Kim::foreign_key_len(),
             Box::new(move |pk_rel, _, fk_rel, _| {
-                datagen::popular::Kim::gen(pk_rel, fk_rel, selectivity).map_err(|e| e.into())
+                datagen::popular::Kim::gen(pk_rel, fk_rel, selectivity, seed).map_err(|e| e.into())
             }),
         ),
         ArgDataSet::Blanas4MB => {
             let gen = move |pk_rel: &mut [_], _: &mut [_], fk_rel: &mut [_], _: &mut [_]| {
-                datagen::relation::UniformRelation::gen_primary_key_par(pk_rel, selectivity)?;
-                datagen::relation::UniformRelation::gen_attr_par(fk_rel, 0..pk_rel.len())?;
+                datagen::relation::UniformRelation::gen_primary_key_par(
+                    pk_rel,
+                    selectivity,
+                    derive_seed(seed, 0),
+                )?;
+                datagen::relation::UniformRelation::gen_attr_par(
+                    fk_rel,
+                    0..pk_rel.len(),
+                    derive_seed(seed, 1),
+                )?;
                 Ok(())
             };
 
@@ -703,9 +904,15 @@ where
         }
         ArgDataSet::Test => {
             let gen = move |pk_rel: &mut [_], _: &mut [_], fk_rel: &mut [_], _: &mut [_]| {
-                datagen::relation::UniformRelation::gen_primary_key(pk_rel, selectivity)?;
+                datagen::relation::UniformRelation::gen_primary_key(
+                    pk_rel,
+                    selectivity,
+                    derive_seed(seed, 0),
+                )?;
                 datagen::relation::UniformRelation::gen_foreign_key_from_primary_key(
-                    fk_rel, pk_rel,
+                    fk_rel,
+                    pk_rel,
+                    derive_seed(seed, 1),
                 );
                 Ok(())
             };
@@ -714,8 +921,16 @@ where
         }
         ArgDataSet::Lutz2Gv32G => {
             let gen = move |pk_rel: &mut [_], _: &mut [_], fk_rel: &mut [_], _: &mut [_]| {
-                datagen::relation::UniformRelation::gen_primary_key_par(pk_rel, selectivity)?;
-                datagen::relation::UniformRelation::gen_attr_par(fk_rel, 0..pk_rel.len())?;
+                datagen::relation::UniformRelation::gen_primary_key_par(
+                    pk_rel,
+                    selectivity,
+                    derive_seed(seed, 0),
+                )?;
+                datagen::relation::UniformRelation::gen_attr_par(
+                    fk_rel,
+                    0..pk_rel.len(),
+                    derive_seed(seed, 1),
+                )?;
                 Ok(())
             };
 
@@ -727,8 +942,16 @@ where
         }
         ArgDataSet::Lutz32Gv32G => {
             let gen = move |pk_rel: &mut [_], _: &mut [_], fk_rel: &mut [_], _: &mut [_]| {
-                datagen::relation::UniformRelation::gen_primary_key_par(pk_rel, selectivity)?;
-                datagen::relation::UniformRelation::gen_attr_par(fk_rel, 0..pk_rel.len())?;
+                datagen::relation::UniformRelation::gen_primary_key_par(
+                    pk_rel,
+                    selectivity,
+                    derive_seed(seed, 0),
+                )?;
+                datagen::relation::UniformRelation::gen_attr_par(
+                    fk_rel,
+                    0..pk_rel.len(),
+                    derive_seed(seed, 1),
+                )?;
                 Ok(())
             };
 
@@ -741,8 +964,16 @@ where
         ArgDataSet::Custom => {
             let uniform_gen = Box::new(
                 move |pk_rel: &mut [_], _: &mut [_], fk_rel: &mut [_], _: &mut [_]| {
-                    datagen::relation::UniformRelation::gen_primary_key_par(pk_rel, selectivity)?;
-                    datagen::relation::UniformRelation::gen_attr_par(fk_rel, 0..pk_rel.len())?;
+                    datagen::relation::UniformRelation::gen_primary_key_par(
+                        pk_rel,
+                        selectivity,
+                        derive_seed(seed, 0),
+                    )?;
+                    datagen::relation::UniformRelation::gen_attr_par(
+                        fk_rel,
+                        0..pk_rel.len(),
+                        derive_seed(seed, 1),
+                    )?;
                     Ok(())
                 },
             );
@@ -755,8 +986,14 @@ where
                         datagen::relation::UniformRelation::gen_primary_key_par(
                             pk_rel,
                             selectivity,
+                            derive_seed(seed, 0),
+                        )?;
+                        datagen::relation::ZipfRelation::gen_attr_par(
+                            fk_rel,
+                            pk_rel.len(),
+                            exp,
+                            derive_seed(seed, 1),
                         )?;
-                        datagen::relation::ZipfRelation::gen_attr_par(fk_rel, pk_rel.len(), exp)?;
                         Ok(())
                     },
                 ),
@@ -787,6 +1024,7 @@ impl CmdOptToDataPoint for DataPoint {
                 let device = Device::get_device(cmd.device_id.into())?;
                 vec![device.name()?]
             } // CPU execution methods should use: vec![numa_gpu::runtime::hw_info::cpu_codename()?]
+            ArgExecutionMethod::CpuRadixJoinTwoPass => vec![cpu_codename()?],
             ArgExecutionMethod::CpuPartitionedRadixJoinTwoPass => {
                 let device = Device::get_device(cmd.device_id.into())?;
                 vec![cpu_codename()?, device.name()?]
@@ -806,6 +1044,13 @@ impl CmdOptToDataPoint for DataPoint {
             radix_bits_snd: cmd.radix_bits.pass_radix_bits(RadixPass::Second),
             radix_bits_trd: cmd.radix_bits.pass_radix_bits(RadixPass::Third),
             hashing_scheme: Some(cmd.hashing_scheme),
+            hash_function: Some(cmd.hash_function),
+            skew_handling: Some(cmd.skew_handling),
+            skew_factor: if cmd.skew_handling == ArgSkewHandling::Broadcast {
+                Some(cmd.skew_factor)
+            } else {
+                None
+            },
             partitions_memory_type: Some(cmd.partitions_mem_type),
             partitions_memory_location: Some(cmd.partitions_location.clone()),
             partitions_proportions: Some(cmd.partitions_proportions.clone()),
@@ -814,9 +1059,11 @@ impl CmdOptToDataPoint for DataPoint {
                 ArgMemType::Numa | ArgMemType::NumaPinned => Some(cmd.state_location),
                 _ => None,
             },
-            tuple_bytes: Some(cmd.tuple_bytes),
+            key_bytes: Some(cmd.key_bytes),
+            payload_bytes: Some(cmd.payload_bytes),
             relation_memory_type: Some(cmd.mem_type),
             page_type: Some(cmd.page_type),
+            huge_page_fallback: Some(cmd.huge_page_fallback),
             inner_relation_memory_location: Some(cmd.inner_rel_location),
             outer_relation_memory_location: Some(cmd.outer_rel_location),
             data_distribution: Some(cmd.data_distribution),
@@ -826,6 +1073,7 @@ impl CmdOptToDataPoint for DataPoint {
                 None
             },
             join_selectivity: Some(cmd.selectivity as f64 / 100.0),
+            data_seed: cmd.seed,
             ..self.clone()
         };
 
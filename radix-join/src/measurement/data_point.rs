@@ -15,7 +15,7 @@
 use crate::types::*;
 use data_store::join_data::JoinData;
 use numa_gpu::error::Result;
-use numa_gpu::runtime::nvtx::RangeId;
+use numa_gpu::runtime::trace::SpanId;
 use rustacuda::function::{BlockSize, GridSize};
 use rustacuda::memory::DeviceCopy;
 use serde::Serializer;
@@ -42,6 +42,9 @@ pub struct DataPoint {
     pub radix_bits_snd: Option<u32>,
     pub radix_bits_trd: Option<u32>,
     pub hashing_scheme: Option<ArgHashingScheme>,
+    pub hash_function: Option<ArgHashFunction>,
+    pub skew_handling: Option<ArgSkewHandling>,
+    pub skew_factor: Option<f64>,
     pub partitions_memory_type: Option<ArgMemType>,
     #[serde(serialize_with = "serialize_vec")]
     pub partitions_memory_location: Option<Vec<u16>>,
@@ -49,22 +52,36 @@ pub struct DataPoint {
     pub partitions_proportions: Option<Vec<usize>>,
     pub state_memory_type: Option<ArgMemType>,
     pub state_memory_location: Option<u16>,
-    pub tuple_bytes: Option<ArgTupleBytes>,
+    pub key_bytes: Option<ArgAttributeBytes>,
+    pub payload_bytes: Option<ArgAttributeBytes>,
     pub relation_memory_type: Option<ArgMemType>,
     pub page_type: Option<ArgPageType>,
+    pub huge_page_fallback: Option<ArgHugePageFallback>,
     pub inner_relation_memory_location: Option<u16>,
     pub outer_relation_memory_location: Option<u16>,
+    #[serde(serialize_with = "serialize_vec")]
+    pub inner_relation_resident_nodes: Option<Vec<u16>>,
+    #[serde(serialize_with = "serialize_vec")]
+    pub inner_relation_resident_bytes: Option<Vec<usize>>,
+    #[serde(serialize_with = "serialize_vec")]
+    pub outer_relation_resident_nodes: Option<Vec<u16>>,
+    #[serde(serialize_with = "serialize_vec")]
+    pub outer_relation_resident_bytes: Option<Vec<usize>>,
+    pub inner_relation_huge_page_bytes: Option<usize>,
+    pub outer_relation_huge_page_bytes: Option<usize>,
     pub build_tuples: Option<usize>,
     pub build_bytes: Option<usize>,
     pub probe_tuples: Option<usize>,
     pub probe_bytes: Option<usize>,
     pub cached_build_tuples: Option<usize>,
     pub cached_probe_tuples: Option<usize>,
+    pub skewed_partitions: Option<usize>,
     pub data_distribution: Option<ArgDataDistribution>,
     pub zipf_exponent: Option<f64>,
     pub join_selectivity: Option<f64>,
+    pub data_seed: Option<u64>,
     pub warm_up: Option<bool>,
-    pub nvtx_run_id: Option<RangeId>,
+    pub trace_run_id: Option<SpanId>,
     pub prefix_sum_ns: Option<f64>,
     pub partition_ns: Option<f64>,
     pub join_ns: Option<f64>,
@@ -86,20 +103,36 @@ impl DataPoint {
         Ok(dp)
     }
 
-    pub fn fill_from_join_data<T: DeviceCopy>(&self, join_data: &JoinData<T>) -> DataPoint {
-        DataPoint {
+    pub fn fill_from_join_data<K: DeviceCopy, V: DeviceCopy>(
+        &self,
+        join_data: &JoinData<K, V>,
+    ) -> data_store::error::Result<DataPoint> {
+        // The residency and huge pages are unknown for memory that can reside on
+        // the GPU
+        let build_residency = join_data.build_relation_residency()?;
+        let probe_residency = join_data.probe_relation_residency()?;
+        let build_huge_page_bytes = join_data.build_relation_huge_page_bytes()?;
+        let probe_huge_page_bytes = join_data.probe_relation_huge_page_bytes()?;
+
+        Ok(DataPoint {
+            inner_relation_resident_nodes: build_residency.as_ref().map(|r| r.nodes()),
+            inner_relation_resident_bytes: build_residency.as_ref().map(|r| r.bytes()),
+            outer_relation_resident_nodes: probe_residency.as_ref().map(|r| r.nodes()),
+            outer_relation_resident_bytes: probe_residency.as_ref().map(|r| r.bytes()),
+            inner_relation_huge_page_bytes: build_huge_page_bytes,
+            outer_relation_huge_page_bytes: probe_huge_page_bytes,
             build_tuples: Some(join_data.build_relation_key.len()),
             build_bytes: Some(
-                (join_data.build_relation_key.len() + join_data.build_relation_payload.len())
-                    * size_of::<T>(),
+                join_data.build_relation_key.len() * size_of::<K>()
+                    + join_data.build_relation_payload.len() * size_of::<V>(),
             ),
             probe_tuples: Some(join_data.probe_relation_key.len()),
             probe_bytes: Some(
-                (join_data.probe_relation_key.len() + join_data.probe_relation_payload.len())
-                    * size_of::<T>(),
+                join_data.probe_relation_key.len() * size_of::<K>()
+                    + join_data.probe_relation_payload.len() * size_of::<V>(),
             ),
             ..self.clone()
-        }
+        })
     }
 
     pub fn set_init_time(&self, malloc: Duration, data_gen: Duration) -> DataPoint {
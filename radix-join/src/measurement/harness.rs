@@ -14,8 +14,7 @@
 
 use super::data_point::DataPoint;
 use crate::error::Result;
-use numa_gpu::runtime::nvtx::Range;
-use std::ffi::CString;
+use numa_gpu::runtime::trace::Span;
 use std::path::PathBuf;
 
 #[derive(Debug, Default)]
@@ -27,6 +26,7 @@ pub struct RadixJoinPoint {
     pub join_ns: Option<f64>,
     pub cached_build_tuples: Option<usize>,
     pub cached_probe_tuples: Option<usize>,
+    pub skewed_partitions: Option<usize>,
 }
 
 pub fn measure(
@@ -39,18 +39,16 @@ pub fn measure(
     let measurements = (0..repeat)
         .zip(std::iter::once(true).chain(std::iter::repeat(false)))
         .map(|(run, warm_up)| {
-            let range_message =
-                CString::new(format!("Measurement run {}", run)).expect("Failed to format string");
-
-            let range = Range::new(&range_message);
+            let span = Span::new(&format!("Measurement run {}", run));
             let result = func();
-            let run_id = range.end();
+            let run_id = span.end();
 
             result.map(|p| DataPoint {
                 warm_up: Some(warm_up),
-                nvtx_run_id: Some(run_id),
+                trace_run_id: Some(run_id),
                 cached_build_tuples: p.cached_build_tuples,
                 cached_probe_tuples: p.cached_probe_tuples,
+                skewed_partitions: p.skewed_partitions,
                 relation_malloc_ns: if warm_up {
                     template.relation_malloc_ns
                 } else {
@@ -13,12 +13,20 @@
 // limitations under the License.
 
 use numa_gpu::runtime::allocator;
+use numa_gpu::runtime::huge_pages::HugePageFallback;
 use numa_gpu::runtime::numa::{NodeRatio, PageType};
+pub use numa_gpu::runtime::topology::{ArgCpuAffinityPolicy, ArgCpuAffinityPolicyHelper};
+#[cfg(feature = "cuda")]
+use numa_gpu::runtime::trace::TraceBackend;
+#[cfg(feature = "cuda")]
 use numa_gpu::utils::DeviceType;
 use serde_derive::Serialize;
 use serde_repr::Serialize_repr;
+pub use sql_ops::hash_function::ArgHashFunction;
 use sql_ops::join::HashingScheme;
+#[cfg(feature = "cuda")]
 use sql_ops::partition::cpu_radix_partition::{CpuHistogramAlgorithm, CpuRadixPartitionAlgorithm};
+#[cfg(feature = "cuda")]
 use sql_ops::partition::gpu_radix_partition::{GpuHistogramAlgorithm, GpuRadixPartitionAlgorithm};
 use structopt::clap::arg_enum;
 
@@ -75,11 +83,30 @@ arg_enum! {
     }
 }
 
+arg_enum! {
+    #[derive(Copy, Clone, Debug, PartialEq, Serialize)]
+    pub enum ArgHugePageFallback {
+        Fail,
+        TransparentHuge,
+        Small,
+    }
+}
+
+arg_enum! {
+    #[derive(Copy, Clone, Debug, PartialEq, Serialize)]
+    pub enum ArgTraceBackend {
+        Noop,
+        Nvtx,
+        Chrome,
+    }
+}
+
 arg_enum! {
     #[derive(Copy, Clone, Debug, PartialEq, Serialize)]
     pub enum ArgHistogramAlgorithm {
         CpuChunked,
         CpuChunkedSimd,
+        CpuContiguous,
         GpuChunked,
         GpuContiguous,
     }
@@ -102,6 +129,7 @@ arg_enum! {
     #[derive(Copy, Clone, Debug, PartialEq, Serialize)]
     pub enum ArgExecutionMethod {
         CpuPartitionedRadixJoinTwoPass,
+        CpuRadixJoinTwoPass,
         GpuRadixJoinTwoPass,
         GpuTritonJoinTwoPass,
     }
@@ -116,12 +144,32 @@ arg_enum! {
     }
 }
 
+arg_enum! {
+    #[derive(Copy, Clone, Debug, PartialEq, Serialize)]
+    pub enum ArgSkewHandling {
+        Disabled,
+        Broadcast,
+    }
+}
+
+/// Specifies how the radix join handles skewed partitions.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum SkewHandling {
+    /// Joins all partitions with the radix join.
+    Disabled,
+
+    /// Joins partitions that are larger than the skew factor times the average
+    /// partition length with a hash join on the CPU. All CPU threads share the
+    /// hash table of a skewed partition.
+    Broadcast(f64),
+}
+
 arg_enum! {
     #[derive(Copy, Clone, Debug, PartialEq, Serialize_repr)]
     #[repr(usize)]
-    pub enum ArgTupleBytes {
+    pub enum ArgAttributeBytes {
+        Bytes4 = 4,
         Bytes8 = 8,
-        Bytes16 = 16,
     }
 }
 
@@ -146,6 +194,7 @@ impl From<ArgMemTypeHelper> for allocator::MemType {
                 node: node_ratios[0].node,
                 page_type: page_type.into(),
             },
+            #[cfg(feature = "cuda")]
             ArgMemType::NumaPinned => allocator::MemType::NumaPinnedMem {
                 node: node_ratios[0].node,
                 page_type: page_type.into(),
@@ -154,9 +203,19 @@ impl From<ArgMemTypeHelper> for allocator::MemType {
                 nodes: node_ratios,
                 page_type: page_type.into(),
             },
+            #[cfg(feature = "cuda")]
             ArgMemType::Pinned => allocator::MemType::CudaPinnedMem,
+            #[cfg(feature = "cuda")]
             ArgMemType::Unified => allocator::MemType::CudaUniMem,
+            #[cfg(feature = "cuda")]
             ArgMemType::Device => allocator::MemType::CudaDevMem,
+            #[cfg(not(feature = "cuda"))]
+            ArgMemType::NumaPinned
+            | ArgMemType::Pinned
+            | ArgMemType::Unified
+            | ArgMemType::Device => {
+                panic!("Error: {:?} memory requires the `cuda` feature!", mem_type)
+            }
         }
     }
 }
@@ -175,6 +234,7 @@ impl From<ArgMemTypeHelper> for allocator::DerefMemType {
                 node: node_ratios[0].node,
                 page_type: page_type.into(),
             },
+            #[cfg(feature = "cuda")]
             ArgMemType::NumaPinned => allocator::DerefMemType::NumaPinnedMem {
                 node: node_ratios[0].node,
                 page_type: page_type.into(),
@@ -183,9 +243,15 @@ impl From<ArgMemTypeHelper> for allocator::DerefMemType {
                 nodes: node_ratios,
                 page_type: page_type.into(),
             },
+            #[cfg(feature = "cuda")]
             ArgMemType::Pinned => allocator::DerefMemType::CudaPinnedMem,
+            #[cfg(feature = "cuda")]
             ArgMemType::Unified => allocator::DerefMemType::CudaUniMem,
             ArgMemType::Device => panic!("Error: Device memory not supported in this context!"),
+            #[cfg(not(feature = "cuda"))]
+            ArgMemType::NumaPinned | ArgMemType::Pinned | ArgMemType::Unified => {
+                panic!("Error: {:?} memory requires the `cuda` feature!", mem_type)
+            }
         }
     }
 }
@@ -204,6 +270,28 @@ impl From<ArgPageType> for PageType {
     }
 }
 
+impl From<ArgHugePageFallback> for HugePageFallback {
+    fn from(fallback: ArgHugePageFallback) -> Self {
+        match fallback {
+            ArgHugePageFallback::Fail => HugePageFallback::Fail,
+            ArgHugePageFallback::TransparentHuge => HugePageFallback::TransparentHuge,
+            ArgHugePageFallback::Small => HugePageFallback::Small,
+        }
+    }
+}
+
+#[cfg(feature = "cuda")]
+impl From<ArgTraceBackend> for TraceBackend {
+    fn from(backend: ArgTraceBackend) -> Self {
+        match backend {
+            ArgTraceBackend::Noop => TraceBackend::Noop,
+            ArgTraceBackend::Nvtx => TraceBackend::Nvtx,
+            ArgTraceBackend::Chrome => TraceBackend::Chrome,
+        }
+    }
+}
+
+#[cfg(feature = "cuda")]
 impl Into<DeviceType<CpuRadixPartitionAlgorithm, GpuRadixPartitionAlgorithm>>
     for ArgRadixPartitionAlgorithm
 {
@@ -220,11 +308,13 @@ impl Into<DeviceType<CpuRadixPartitionAlgorithm, GpuRadixPartitionAlgorithm>>
     }
 }
 
+#[cfg(feature = "cuda")]
 impl Into<DeviceType<CpuHistogramAlgorithm, GpuHistogramAlgorithm>> for ArgHistogramAlgorithm {
     fn into(self) -> DeviceType<CpuHistogramAlgorithm, GpuHistogramAlgorithm> {
         match self {
             Self::CpuChunked => DeviceType::Cpu(CpuHistogramAlgorithm::Chunked),
             Self::CpuChunkedSimd => DeviceType::Cpu(CpuHistogramAlgorithm::ChunkedSimd),
+            Self::CpuContiguous => DeviceType::Cpu(CpuHistogramAlgorithm::Contiguous),
             Self::GpuChunked => DeviceType::Gpu(GpuHistogramAlgorithm::Chunked),
             Self::GpuContiguous => DeviceType::Gpu(GpuHistogramAlgorithm::Contiguous),
         }
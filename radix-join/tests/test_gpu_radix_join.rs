@@ -24,9 +24,11 @@ use once_cell::sync::Lazy;
 use radix_join::error::Result as RJResult;
 use radix_join::execution_methods::gpu_radix_join::gpu_radix_join;
 use radix_join::measurement::harness::RadixJoinPoint;
+use radix_join::types::SkewHandling;
 use rustacuda::context::{Context, CurrentContext, UnownedContext};
 use rustacuda::device::Device;
 use rustacuda::function::{BlockSize, GridSize};
+use sql_ops::hash_function::HashFunction;
 use sql_ops::join::HashingScheme;
 use sql_ops::partition::cpu_radix_partition::{CpuHistogramAlgorithm, CpuRadixPartitionAlgorithm};
 use sql_ops::partition::gpu_radix_partition::{GpuHistogramAlgorithm, GpuRadixPartitionAlgorithm};
@@ -64,11 +66,14 @@ fn run_gpu_radix_join_validate_sum<JoinFn, PartitionsFn>(
     histogram_algorithm: DeviceType<CpuHistogramAlgorithm, GpuHistogramAlgorithm>,
     partition_algorithm: DeviceType<CpuRadixPartitionAlgorithm, GpuRadixPartitionAlgorithm>,
     hashing_scheme: HashingScheme,
+    skew_handling: SkewHandling,
 ) -> Result<(), Box<dyn Error>>
 where
     JoinFn: FnOnce(
         &mut JoinData<i32>,
         HashingScheme,
+        HashFunction,
+        SkewHandling,
         DeviceType<CpuHistogramAlgorithm, GpuHistogramAlgorithm>,
         DeviceType<CpuHistogramAlgorithm, GpuHistogramAlgorithm>,
         DeviceType<CpuRadixPartitionAlgorithm, GpuRadixPartitionAlgorithm>,
@@ -98,8 +103,8 @@ where
 
     let data_gen_fn = Box::new(
         |pk_rel_key: &mut [_], pk_rel_pay: &mut [_], fk_rel_key: &mut [_], fk_rel_pay: &mut [_]| {
-            UniformRelation::gen_primary_key(pk_rel_key, None)?;
-            UniformRelation::gen_foreign_key_from_primary_key(fk_rel_key, pk_rel_key);
+            UniformRelation::gen_primary_key(pk_rel_key, None, None)?;
+            UniformRelation::gen_foreign_key_from_primary_key(fk_rel_key, pk_rel_key, None);
 
             pk_rel_pay
                 .iter_mut()
@@ -127,6 +132,8 @@ where
     let (result_sum, _) = join_fn(
         &mut join_data,
         hashing_scheme,
+        HashFunction::Identity,
+        skew_handling,
         prefix_sum_algorithm_fst,
         prefix_sum_algorithm_snd,
         partition_algorithm_fst,
@@ -191,6 +198,7 @@ fn test_gpu_radix_partition_validate_sum_perfect_small_i32() -> Result<(), Box<d
         DeviceType::Gpu(GpuHistogramAlgorithm::Chunked),
         DeviceType::Gpu(GpuRadixPartitionAlgorithm::SSWWCv2),
         HashingScheme::Perfect,
+        SkewHandling::Disabled,
     )
 }
 
@@ -208,6 +216,7 @@ fn test_gpu_radix_partition_validate_sum_cpu_histogram_small_i32() -> Result<(),
         DeviceType::Cpu(CpuHistogramAlgorithm::Chunked),
         DeviceType::Gpu(GpuRadixPartitionAlgorithm::SSWWCv2),
         HashingScheme::Perfect,
+        SkewHandling::Disabled,
     )
 }
 
@@ -225,6 +234,7 @@ fn test_gpu_radix_partition_validate_sum_bucketchaining_small_i32() -> Result<()
         DeviceType::Gpu(GpuHistogramAlgorithm::Chunked),
         DeviceType::Gpu(GpuRadixPartitionAlgorithm::SSWWCv2),
         HashingScheme::BucketChaining,
+        SkewHandling::Disabled,
     )
 }
 
@@ -242,6 +252,7 @@ fn test_gpu_radix_partition_validate_sum_bucketchaining_large_i32() -> Result<()
         DeviceType::Gpu(GpuHistogramAlgorithm::Chunked),
         DeviceType::Gpu(GpuRadixPartitionAlgorithm::SSWWCv2),
         HashingScheme::BucketChaining,
+        SkewHandling::Disabled,
     )
 }
 
@@ -260,6 +271,7 @@ fn test_cpu_partitioned_validate_sum_perfect_small_i32() -> Result<(), Box<dyn E
         DeviceType::Cpu(CpuHistogramAlgorithm::Chunked),
         DeviceType::Cpu(CpuRadixPartitionAlgorithm::Swwc),
         HashingScheme::Perfect,
+        SkewHandling::Disabled,
     )
 }
 
@@ -278,6 +290,7 @@ fn test_cpu_partitioned_validate_sum_bucketchaining_small_i32() -> Result<(), Bo
         DeviceType::Cpu(CpuHistogramAlgorithm::Chunked),
         DeviceType::Cpu(CpuRadixPartitionAlgorithm::Swwc),
         HashingScheme::BucketChaining,
+        SkewHandling::Disabled,
     )
 }
 
@@ -296,6 +309,7 @@ fn test_cpu_partitioned_validate_sum_bucketchaining_large_i32() -> Result<(), Bo
         DeviceType::Cpu(CpuHistogramAlgorithm::Chunked),
         DeviceType::Cpu(CpuRadixPartitionAlgorithm::Swwc),
         HashingScheme::BucketChaining,
+        SkewHandling::Disabled,
     )
 }
 
@@ -314,6 +328,7 @@ fn test_gpu_triton_partition_validate_sum_perfect_small_i32() -> Result<(), Box<
         DeviceType::Gpu(GpuHistogramAlgorithm::Chunked),
         DeviceType::Gpu(GpuRadixPartitionAlgorithm::SSWWCv2),
         HashingScheme::Perfect,
+        SkewHandling::Disabled,
     )
 }
 
@@ -332,6 +347,7 @@ fn test_gpu_triton_partition_validate_sum_bucketchaining_small_i32() -> Result<(
         DeviceType::Gpu(GpuHistogramAlgorithm::Chunked),
         DeviceType::Gpu(GpuRadixPartitionAlgorithm::SSWWCv2),
         HashingScheme::BucketChaining,
+        SkewHandling::Disabled,
     )
 }
 
@@ -350,5 +366,44 @@ fn test_gpu_triton_partition_validate_sum_bucketchaining_large_i32() -> Result<(
         DeviceType::Gpu(GpuHistogramAlgorithm::Chunked),
         DeviceType::Gpu(GpuRadixPartitionAlgorithm::SSWWCv2),
         HashingScheme::BucketChaining,
+        SkewHandling::Disabled,
+    )
+}
+
+#[cfg(target_arch = "powerpc64")]
+#[test]
+fn test_cpu_partitioned_validate_sum_skew_broadcast_small_i32() -> Result<(), Box<dyn Error>> {
+    run_gpu_radix_join_validate_sum(
+        &cpu_partitioned_radix_join::<i32>,
+        &partitions_type_normal,
+        100_000,
+        100_000,
+        RadixBits::new(Some(3), Some(3), None),
+        GridSize::from(8),
+        BlockSize::from(128),
+        1,
+        DeviceType::Cpu(CpuHistogramAlgorithm::Chunked),
+        DeviceType::Cpu(CpuRadixPartitionAlgorithm::Swwc),
+        HashingScheme::BucketChaining,
+        SkewHandling::Broadcast(1.0),
+    )
+}
+
+#[cfg(target_arch = "powerpc64")]
+#[test]
+fn test_gpu_triton_partition_validate_sum_skew_broadcast_small_i32() -> Result<(), Box<dyn Error>> {
+    run_gpu_radix_join_validate_sum(
+        &gpu_triton_join::<i32>,
+        &partitions_type_cached,
+        100_000,
+        100_000,
+        RadixBits::new(Some(3), Some(3), None),
+        GridSize::from(8),
+        BlockSize::from(128),
+        1,
+        DeviceType::Gpu(GpuHistogramAlgorithm::Chunked),
+        DeviceType::Gpu(GpuRadixPartitionAlgorithm::SSWWCv2),
+        HashingScheme::BucketChaining,
+        SkewHandling::Broadcast(1.0),
     )
 }
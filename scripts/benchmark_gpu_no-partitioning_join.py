@@ -100,7 +100,8 @@ def main():
               --inner-rel-tuples {ts * 10**6 !s}           \
               --outer-rel-tuples {ts * 10**6 !s}           \
               --hashing-scheme {hs}                        \
-              --tuple-bytes Bytes{tb !s}                   \
+              --key-bytes Bytes{tb // 2 !s}                \
+              --payload-bytes Bytes{tb // 2 !s}            \
               --repeat 2                                   \
               --csv {tmp_csv}
             '''
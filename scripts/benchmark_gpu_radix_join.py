@@ -123,7 +123,8 @@ def main():
               --data-set Custom                                \
               --inner-rel-tuples {ts * 10**6 !s}               \
               --outer-rel-tuples {ts * 10**6 !s}               \
-              --tuple-bytes Bytes{tb !s}                       \
+              --key-bytes Bytes{tb // 2 !s}                    \
+              --payload-bytes Bytes{tb // 2 !s}                \
               --repeat 2                                       \
               --csv {tmp_csv}
             '''
@@ -125,6 +125,7 @@ arg_enum! {
     pub enum ArgHistogramAlgorithm {
         Chunked,
         ChunkedSimd,
+        Contiguous,
     }
 }
 
@@ -133,6 +134,7 @@ impl Into<CpuHistogramAlgorithm> for ArgHistogramAlgorithm {
         match self {
             Self::Chunked => CpuHistogramAlgorithm::Chunked,
             Self::ChunkedSimd => CpuHistogramAlgorithm::ChunkedSimd,
+            Self::Contiguous => CpuHistogramAlgorithm::Contiguous,
         }
     }
 }
@@ -426,7 +428,7 @@ where
                         DerefMemType::AlignedSysMem { align_bytes },
                     )
                 })
-                .collect();
+                .collect::<Result<_, _>>()?;
 
             let mut partition_offsets = PartitionOffsets::new(
                 prefix_sum_algorithm.into(),
@@ -568,7 +570,8 @@ where
                         partition_algorithm,
                         radix_bits,
                         DerefMemType::AlignedSysMem { align_bytes },
-                    );
+                    )
+                    .expect("Failed to allocate the CPU radix partitioner");
 
                     let streams: [_; PIPELINE_STAGES] = [Stream::new(StreamFlags::NON_BLOCKING, None)?, Stream::new(StreamFlags::NON_BLOCKING, None)?];
 
@@ -718,20 +721,25 @@ where
 
     match data_distribution {
         ArgDataDistribution::Unique => {
-            UniformRelation::gen_primary_key_par(data_key.as_mut_slice(), None)?;
+            UniformRelation::gen_primary_key_par(data_key.as_mut_slice(), None, None)?;
         }
         ArgDataDistribution::Uniform => {
-            UniformRelation::gen_attr_par(data_key.as_mut_slice(), 0..key_range)?;
+            UniformRelation::gen_attr_par(data_key.as_mut_slice(), 0..key_range, None)?;
         }
         ArgDataDistribution::Zipf if !(zipf_exponent.unwrap() > 0.0) => {
-            UniformRelation::gen_attr_par(data_key.as_mut_slice(), 0..key_range)?;
+            UniformRelation::gen_attr_par(data_key.as_mut_slice(), 0..key_range, None)?;
         }
         ArgDataDistribution::Zipf => {
-            ZipfRelation::gen_attr_par(data_key.as_mut_slice(), key_range, zipf_exponent.unwrap())?;
+            ZipfRelation::gen_attr_par(
+                data_key.as_mut_slice(),
+                key_range,
+                zipf_exponent.unwrap(),
+                None,
+            )?;
         }
     }
 
-    UniformRelation::gen_attr_par(data_pay.as_mut_slice(), PAYLOAD_RANGE)?;
+    UniformRelation::gen_attr_par(data_pay.as_mut_slice(), PAYLOAD_RANGE, None)?;
 
     Ok((data_key, data_pay))
 }
@@ -129,6 +129,7 @@ arg_enum! {
     pub enum ArgHistogramAlgorithm {
         CpuChunked,
         CpuChunkedSimd,
+        CpuContiguous,
         GpuChunked,
         GpuContiguous,
     }
@@ -139,6 +140,7 @@ impl Into<DeviceType<CpuHistogramAlgorithm, GpuHistogramAlgorithm>> for ArgHisto
         match self {
             Self::CpuChunked => DeviceType::Cpu(CpuHistogramAlgorithm::Chunked),
             Self::CpuChunkedSimd => DeviceType::Cpu(CpuHistogramAlgorithm::ChunkedSimd),
+            Self::CpuContiguous => DeviceType::Cpu(CpuHistogramAlgorithm::Contiguous),
             Self::GpuChunked => DeviceType::Gpu(GpuHistogramAlgorithm::Chunked),
             Self::GpuContiguous => DeviceType::Gpu(GpuHistogramAlgorithm::Contiguous),
         }
@@ -466,7 +468,8 @@ where
                                             CpuRadixPartitionAlgorithm::NC,
                                             radix_bits,
                                             DerefMemType::AlignedSysMem { align_bytes },
-                                        );
+                                        )
+                                        .expect("Failed to allocate the CPU radix partitioner");
                                         radix_prnr
                                             .prefix_sum(input, output)
                                             .expect("Failed to run CPU prefix sum");
@@ -546,24 +549,25 @@ where
 
     match data_distribution {
         ArgDataDistribution::Unique => {
-            UniformRelation::gen_primary_key_par(host_data_key.as_mut_slice(), None)?;
+            UniformRelation::gen_primary_key_par(host_data_key.as_mut_slice(), None, None)?;
         }
         ArgDataDistribution::Uniform => {
-            UniformRelation::gen_attr_par(host_data_key.as_mut_slice(), 0..key_range)?;
+            UniformRelation::gen_attr_par(host_data_key.as_mut_slice(), 0..key_range, None)?;
         }
         ArgDataDistribution::Zipf if !(zipf_exponent.unwrap() > 0.0) => {
-            UniformRelation::gen_attr_par(host_data_key.as_mut_slice(), 0..key_range)?;
+            UniformRelation::gen_attr_par(host_data_key.as_mut_slice(), 0..key_range, None)?;
         }
         ArgDataDistribution::Zipf => {
             ZipfRelation::gen_attr_par(
                 host_data_key.as_mut_slice(),
                 key_range,
                 zipf_exponent.unwrap(),
+                None,
             )?;
         }
     }
 
-    UniformRelation::gen_attr_par(host_data_pay.as_mut_slice(), PAYLOAD_RANGE).unwrap();
+    UniformRelation::gen_attr_par(host_data_pay.as_mut_slice(), PAYLOAD_RANGE, None).unwrap();
 
     let dev_data = if let MemType::CudaDevMem = mem_type {
         (
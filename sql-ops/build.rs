@@ -130,105 +130,111 @@ fn main() {
     ];
 
     // Add CUDA utils
-    let cuda_lib_file = format!("{}/cudautils.fatbin", out_dir);
     let cuda_files = vec![
         "cudautils/gpu_common.cu",
         "cudautils/no_partitioning_join.cu",
         "cudautils/radix_join.cu",
         "cudautils/radix_partition.cu",
     ];
-    let nvcc_build_args = vec![
-        "-rdc=true",
-        "--device-c",
-        "-std=c++14",
-        "--output-directory",
-        &out_dir,
-    ];
-    let nvcc_link_args = vec!["--device-link", "-fatbin", "--output-file", &cuda_lib_file];
-    let nvcc_host_compiler_args: Vec<_> = cpp_compiler
-        .as_ref()
-        .map_or_else(|_| Vec::new(), |cxx| ["-ccbin", cxx.as_str()].into());
 
-    // For gencodes, see: http://arnon.dk/matching-sm-architectures-arch-and-gencode-for-various-nvidia-cards/
-    let gpu_archs = vec![
-        "-gencode",
-        "arch=compute_50,code=sm_50", // GTX 940M
-        "-gencode",
-        "arch=compute_52,code=sm_52", // GTX 980
-        "-gencode",
-        "arch=compute_53,code=sm_53", // Jetson Nano
-        "-gencode",
-        "arch=compute_61,code=sm_61", // GTX 1080
-        "-gencode",
-        "arch=compute_70,code=sm_70", // Tesla V100
-    ];
-    let nvcc_include = {
-        let mut s = OsString::from("-I ");
-        s.push(include_path.as_os_str());
-        s
-    };
-    let constants_include = {
-        let mut s = OsString::from("-I ");
-        s.push(&out_dir);
-        s
-    };
+    // The CUDA module is only required by the GPU operators. Skip nvcc for
+    // CPU-only builds, i.e., if the `cuda` feature is disabled.
+    if env::var("CARGO_FEATURE_CUDA").is_ok() {
+        let cuda_lib_file = format!("{}/cudautils.fatbin", out_dir);
+        let nvcc_build_args = vec![
+            "-rdc=true",
+            "--device-c",
+            "-std=c++14",
+            "--output-directory",
+            &out_dir,
+        ];
+        let nvcc_link_args = vec!["--device-link", "-fatbin", "--output-file", &cuda_lib_file];
+        let nvcc_host_compiler_args: Vec<_> = cpp_compiler
+            .as_ref()
+            .map_or_else(|_| Vec::new(), |cxx| ["-ccbin", cxx.as_str()].into());
 
-    let output = Command::new("nvcc")
-        .args(cuda_files.as_slice())
-        .args(nvcc_host_compiler_args.as_slice())
-        .args(nvcc_build_args.as_slice())
-        .args(gpu_archs.as_slice())
-        .arg(nvcc_include)
-        .arg(constants_include)
-        .output()
-        .expect("Couldn't execute nvcc");
+        // For gencodes, see: http://arnon.dk/matching-sm-architectures-arch-and-gencode-for-various-nvidia-cards/
+        let gpu_archs = vec![
+            "-gencode",
+            "arch=compute_50,code=sm_50", // GTX 940M
+            "-gencode",
+            "arch=compute_52,code=sm_52", // GTX 980
+            "-gencode",
+            "arch=compute_53,code=sm_53", // Jetson Nano
+            "-gencode",
+            "arch=compute_61,code=sm_61", // GTX 1080
+            "-gencode",
+            "arch=compute_70,code=sm_70", // Tesla V100
+        ];
+        let nvcc_include = {
+            let mut s = OsString::from("-I ");
+            s.push(include_path.as_os_str());
+            s
+        };
+        let constants_include = {
+            let mut s = OsString::from("-I ");
+            s.push(&out_dir);
+            s
+        };
 
-    if !output.status.success() {
-        eprintln!("status: {}", output.status);
-        eprintln!("stdout: {}", String::from_utf8_lossy(&output.stdout));
-        eprintln!("stderr: {}", String::from_utf8_lossy(&output.stderr));
-        panic!();
-    }
+        let output = Command::new("nvcc")
+            .args(cuda_files.as_slice())
+            .args(nvcc_host_compiler_args.as_slice())
+            .args(nvcc_build_args.as_slice())
+            .args(gpu_archs.as_slice())
+            .arg(nvcc_include)
+            .arg(constants_include)
+            .output()
+            .expect("Couldn't execute nvcc");
 
-    let cuda_object_files: Vec<_> = cuda_files
-        .as_slice()
-        .iter()
-        .map(|f| {
-            let p = Path::new(f);
-            let mut obj = PathBuf::new();
-            obj.push(&out_dir);
-            obj.push(p.file_stem().unwrap());
-            obj.set_extension("o");
-            obj
-        })
-        .collect();
+        if !output.status.success() {
+            eprintln!("status: {}", output.status);
+            eprintln!("stdout: {}", String::from_utf8_lossy(&output.stdout));
+            eprintln!("stderr: {}", String::from_utf8_lossy(&output.stderr));
+            panic!();
+        }
 
-    let output = Command::new("nvcc")
-        .args(cuda_object_files.as_slice())
-        .args(nvcc_link_args.as_slice())
-        .args(gpu_archs.as_slice())
-        .output()
-        .expect("Couldn't execute nvcc");
+        let cuda_object_files: Vec<_> = cuda_files
+            .as_slice()
+            .iter()
+            .map(|f| {
+                let p = Path::new(f);
+                let mut obj = PathBuf::new();
+                obj.push(&out_dir);
+                obj.push(p.file_stem().unwrap());
+                obj.set_extension("o");
+                obj
+            })
+            .collect();
 
-    if !output.status.success() {
-        eprintln!("status: {}", output.status);
-        eprintln!("stdout: {}", String::from_utf8_lossy(&output.stdout));
-        eprintln!("stderr: {}", String::from_utf8_lossy(&output.stderr));
-        panic!();
-    }
+        let output = Command::new("nvcc")
+            .args(cuda_object_files.as_slice())
+            .args(nvcc_link_args.as_slice())
+            .args(gpu_archs.as_slice())
+            .output()
+            .expect("Couldn't execute nvcc");
+
+        if !output.status.success() {
+            eprintln!("status: {}", output.status);
+            eprintln!("stdout: {}", String::from_utf8_lossy(&output.stdout));
+            eprintln!("stderr: {}", String::from_utf8_lossy(&output.stderr));
+            panic!();
+        }
 
-    println!(
-        "cargo:rustc-env=CUDAUTILS_PATH={}/cudautils.fatbin",
-        out_dir
-    );
-    println!("cargo:rustc-link-search=native=/opt/cuda/lib64");
-    println!("cargo:rustc-link-search=native=/usr/local/cuda/lib64");
-    println!("cargo:rustc-link-lib=cudart");
+        println!(
+            "cargo:rustc-env=CUDAUTILS_PATH={}/cudautils.fatbin",
+            out_dir
+        );
+        println!("cargo:rustc-link-search=native=/opt/cuda/lib64");
+        println!("cargo:rustc-link-search=native=/usr/local/cuda/lib64");
+        println!("cargo:rustc-link-lib=cudart");
+    }
 
     // CPP files
     let cpp_files = vec![
         "cpputils/gpu_common.cpp",
         "cpputils/no_partitioning_join.cpp",
+        "cpputils/radix_join.cpp",
         "cpputils/radix_partition.cpp",
     ];
 
@@ -252,6 +258,10 @@ fn main() {
         .files(&cpp_files)
         .compile("libcpputils.a");
 
+    // Composite keys require a 16-byte compare-and-swap, which GCC implements
+    // in libatomic
+    println!("cargo:rustc-link-lib=atomic");
+
     vec!["include/", "cpputils/", "cudautils/"]
         .iter()
         .chain(include_files.iter())
@@ -24,22 +24,34 @@
 #include <atomic>
 #include <cstdint>
 
+// Returns the hash table index of a key with perfect hashing.
+//
+// Integer keys are used as their own index. Composite keys are indexed by their
+// second attribute. The first attribute is only compared on probe.
 template <typename T>
-void cpu_ht_insert_linearprobing(HtEntry<T, T> *const __restrict__ hash_table,
-                                 unsigned int log2_hash_table_entries, T key,
-                                 T payload) {
-  uint64_t index = hash<T>(key, log2_hash_table_entries);
+T perfect_index(T key) {
+  return key;
+}
+
+unsigned long long perfect_index(CompositeKey key) { return key.second; }
+
+template <typename K, typename V>
+void cpu_ht_insert_linearprobing(HtEntry<K, V> *const __restrict__ hash_table,
+                                 unsigned int log2_hash_table_entries,
+                                 HashFunction hash_function, K key, V payload) {
+  uint64_t index =
+      hash_to_bucket(hash_function, key, log2_hash_table_entries);
 
   uint64_t hash_table_entries = 1ULL << log2_hash_table_entries;
   uint64_t hash_table_mask = hash_table_entries - 1ULL;
 
   for (uint64_t i = 0; i < hash_table_entries;
        ++i, index = (index + 1ULL) & hash_table_mask) {
-    T old = hash_table[index].key;
-    if (old == null_key<T>()) {
-      T expected = null_key<T>();
+    K old = hash_table[index].key;
+    if (old == null_key<K>()) {
+      K expected = null_key<K>();
       bool is_inserted = std::atomic_compare_exchange_strong(
-          (std::atomic<T> *)&hash_table[index].key, &expected, key);
+          (std::atomic<K> *)&hash_table[index].key, &expected, key);
       if (is_inserted) {
         hash_table[index].value = payload;
         return;
@@ -49,47 +61,99 @@ void cpu_ht_insert_linearprobing(HtEntry<T, T> *const __restrict__ hash_table,
 }
 
 // extern "C"
-template <typename T>
-void cpu_ht_build_linearprobing(HtEntry<T, T> *const __restrict__ hash_table,
-                                uint64_t const hash_table_entries,
-                                const T *const __restrict__ join_attr_data,
-                                const T *const __restrict__ payload_attr_data,
-                                uint64_t const data_length) {
+template <typename K, typename V>
+void cpu_ht_build_linearprobing(
+    HtEntry<K, V> *const __restrict__ hash_table,
+    uint64_t const hash_table_entries, HashFunction const hash_function,
+    const K *const __restrict__ join_attr_data,
+    const V *const __restrict__ payload_attr_data, uint64_t const data_length) {
   const unsigned int log2_hash_table_entries =
       log2_floor_power_of_two(hash_table_entries);
 
   for (uint64_t tuple_id = 0; tuple_id < data_length; ++tuple_id) {
     cpu_ht_insert_linearprobing(hash_table, log2_hash_table_entries,
-                                join_attr_data[tuple_id],
+                                hash_function, join_attr_data[tuple_id],
                                 payload_attr_data[tuple_id]);
   }
 }
 
 extern "C" void cpu_ht_build_linearprobing_int32(
     HtEntry<int, int> *const __restrict__ hash_table,
-    uint64_t const hash_table_entries,
+    uint64_t const hash_table_entries, HashFunction const hash_function,
     const int *const __restrict__ join_attr_data,
     const int *const __restrict__ payload_attr_data,
     uint64_t const data_length) {
-  cpu_ht_build_linearprobing(hash_table, hash_table_entries, join_attr_data,
-                             payload_attr_data, data_length);
+  cpu_ht_build_linearprobing(hash_table, hash_table_entries, hash_function,
+                             join_attr_data, payload_attr_data, data_length);
 }
 
 extern "C" void cpu_ht_build_linearprobing_int64(
     HtEntry<long long, long long> *const __restrict__ hash_table,
-    uint64_t const hash_table_entries,
+    uint64_t const hash_table_entries, HashFunction const hash_function,
     const long long *const __restrict__ join_attr_data,
     const long long *const __restrict__ payload_attr_data,
     uint64_t const data_length) {
-  cpu_ht_build_linearprobing(hash_table, hash_table_entries, join_attr_data,
-                             payload_attr_data, data_length);
+  cpu_ht_build_linearprobing(hash_table, hash_table_entries, hash_function,
+                             join_attr_data, payload_attr_data, data_length);
 }
 
-template <typename T>
+extern "C" void cpu_ht_build_linearprobing_uint32(
+    HtEntry<unsigned, unsigned> *const __restrict__ hash_table,
+    uint64_t const hash_table_entries, HashFunction const hash_function,
+    const unsigned *const __restrict__ join_attr_data,
+    const unsigned *const __restrict__ payload_attr_data,
+    uint64_t const data_length) {
+  cpu_ht_build_linearprobing(hash_table, hash_table_entries, hash_function,
+                             join_attr_data, payload_attr_data, data_length);
+}
+
+extern "C" void cpu_ht_build_linearprobing_uint64(
+    HtEntry<unsigned long long, unsigned long long> *const __restrict__
+        hash_table,
+    uint64_t const hash_table_entries, HashFunction const hash_function,
+    const unsigned long long *const __restrict__ join_attr_data,
+    const unsigned long long *const __restrict__ payload_attr_data,
+    uint64_t const data_length) {
+  cpu_ht_build_linearprobing(hash_table, hash_table_entries, hash_function,
+                             join_attr_data, payload_attr_data, data_length);
+}
+
+extern "C" void cpu_ht_build_linearprobing_composite(
+    HtEntry<CompositeKey, CompositeKey> *const __restrict__ hash_table,
+    uint64_t const hash_table_entries, HashFunction const hash_function,
+    const CompositeKey *const __restrict__ join_attr_data,
+    const CompositeKey *const __restrict__ payload_attr_data,
+    uint64_t const data_length) {
+  cpu_ht_build_linearprobing(hash_table, hash_table_entries, hash_function,
+                             join_attr_data, payload_attr_data, data_length);
+}
+
+extern "C" void cpu_ht_build_linearprobing_int32_int64(
+    HtEntry<int, long long> *const __restrict__ hash_table,
+    uint64_t const hash_table_entries, HashFunction const hash_function,
+    const int *const __restrict__ join_attr_data,
+    const long long *const __restrict__ payload_attr_data,
+    uint64_t const data_length) {
+  cpu_ht_build_linearprobing(hash_table, hash_table_entries, hash_function,
+                             join_attr_data, payload_attr_data, data_length);
+}
+
+extern "C" void cpu_ht_build_linearprobing_int64_int32(
+    HtEntry<long long, int> *const __restrict__ hash_table,
+    uint64_t const hash_table_entries, HashFunction const hash_function,
+    const long long *const __restrict__ join_attr_data,
+    const int *const __restrict__ payload_attr_data,
+    uint64_t const data_length) {
+  cpu_ht_build_linearprobing(hash_table, hash_table_entries, hash_function,
+                             join_attr_data, payload_attr_data, data_length);
+}
+
+template <typename K, typename V>
 bool cpu_ht_findkey_linearprobing(
-    HtEntry<T, T> const *const __restrict__ hash_table,
-    unsigned int log2_hash_table_entries, T key, T const **found_payload,
-    uint64_t *__restrict__ last_index, bool use_last_index) {
+    HtEntry<K, V> const *const __restrict__ hash_table,
+    unsigned int log2_hash_table_entries, HashFunction hash_function, K key,
+    V const **found_payload, uint64_t *__restrict__ last_index,
+    bool use_last_index) {
   uint64_t hash_table_entries = 1ULL << log2_hash_table_entries;
   uint64_t hash_table_mask = hash_table_entries - 1ULL;
 
@@ -98,7 +162,7 @@ bool cpu_ht_findkey_linearprobing(
     index = *last_index;
     index = (index + 1ULL) & hash_table_mask;
   } else {
-    index = hash<T>(key, log2_hash_table_entries);
+    index = hash_to_bucket(hash_function, key, log2_hash_table_entries);
   }
 
   for (uint64_t i = 0; i < hash_table_mask + 1ULL;
@@ -107,7 +171,7 @@ bool cpu_ht_findkey_linearprobing(
       *found_payload = &hash_table[index].value;
       *last_index = index;
       return true;
-    } else if (hash_table[index].key == null_key<T>()) {
+    } else if (hash_table[index].key == null_key<K>()) {
       return false;
     }
   }
@@ -115,80 +179,141 @@ bool cpu_ht_findkey_linearprobing(
   return false;
 }
 
-template <typename T>
+template <typename K, typename V>
 void cpu_ht_probe_aggregate_linearprobing(
-    HtEntry<T, T> const *const __restrict__ hash_table,
-    uint64_t const hash_table_entries,
-    const T *const __restrict__ join_attr_data,
-    const T *const __restrict__ payload_attr_data, uint64_t const data_length,
+    HtEntry<K, V> const *const __restrict__ hash_table,
+    uint64_t const hash_table_entries, HashFunction const hash_function,
+    const K *const __restrict__ join_attr_data,
+    const V *const __restrict__ payload_attr_data, uint64_t const data_length,
     uint64_t *const __restrict__ aggregation_result) {
   const unsigned int log2_hash_table_entries =
       log2_floor_power_of_two(hash_table_entries);
 
   for (uint64_t tuple_id = 0; tuple_id < data_length; ++tuple_id) {
-    T const *hash_table_payload = nullptr;
+    V const *hash_table_payload = nullptr;
     uint64_t hash_table_last_index = 0;
     bool hash_table_use_last_index = false;
     while (cpu_ht_findkey_linearprobing(
-        hash_table, log2_hash_table_entries, join_attr_data[tuple_id],
-        &hash_table_payload, &hash_table_last_index,
+        hash_table, log2_hash_table_entries, hash_function,
+        join_attr_data[tuple_id], &hash_table_payload, &hash_table_last_index,
         hash_table_use_last_index)) {
       hash_table_use_last_index = true;
-      *aggregation_result += payload_attr_data[tuple_id];
+      *aggregation_result +=
+          static_cast<unsigned long long>(payload_attr_data[tuple_id]);
     }
   }
 }
 
 extern "C" void cpu_ht_probe_aggregate_linearprobing_int32(
     HtEntry<int, int> const *const __restrict__ hash_table,
-    uint64_t const hash_table_entries,
+    uint64_t const hash_table_entries, HashFunction const hash_function,
     const int *const __restrict__ join_attr_data,
     const int *const __restrict__ payload_attr_data, uint64_t const data_length,
     uint64_t *const __restrict__ aggregation_result) {
-  cpu_ht_probe_aggregate_linearprobing(hash_table, hash_table_entries,
-                                       join_attr_data, payload_attr_data,
-                                       data_length, aggregation_result);
+  cpu_ht_probe_aggregate_linearprobing(
+      hash_table, hash_table_entries, hash_function, join_attr_data,
+      payload_attr_data, data_length, aggregation_result);
 }
 
 extern "C" void cpu_ht_probe_aggregate_linearprobing_int64(
     HtEntry<long long, long long> const *const __restrict__ hash_table,
-    uint64_t const hash_table_entries,
+    uint64_t const hash_table_entries, HashFunction const hash_function,
     const long long *const __restrict__ join_attr_data,
     const long long *const __restrict__ payload_attr_data,
     uint64_t const data_length,
     uint64_t *const __restrict__ aggregation_result) {
-  cpu_ht_probe_aggregate_linearprobing(hash_table, hash_table_entries,
-                                       join_attr_data, payload_attr_data,
-                                       data_length, aggregation_result);
+  cpu_ht_probe_aggregate_linearprobing(
+      hash_table, hash_table_entries, hash_function, join_attr_data,
+      payload_attr_data, data_length, aggregation_result);
 }
 
-template <typename T>
-void cpu_ht_build_perfect(HtEntry<T, T> *const __restrict__ hash_table,
+extern "C" void cpu_ht_probe_aggregate_linearprobing_uint32(
+    HtEntry<unsigned, unsigned> const *const __restrict__ hash_table,
+    uint64_t const hash_table_entries, HashFunction const hash_function,
+    const unsigned *const __restrict__ join_attr_data,
+    const unsigned *const __restrict__ payload_attr_data,
+    uint64_t const data_length,
+    uint64_t *const __restrict__ aggregation_result) {
+  cpu_ht_probe_aggregate_linearprobing(
+      hash_table, hash_table_entries, hash_function, join_attr_data,
+      payload_attr_data, data_length, aggregation_result);
+}
+
+extern "C" void cpu_ht_probe_aggregate_linearprobing_uint64(
+    HtEntry<unsigned long long, unsigned long long> const *const __restrict__
+        hash_table,
+    uint64_t const hash_table_entries, HashFunction const hash_function,
+    const unsigned long long *const __restrict__ join_attr_data,
+    const unsigned long long *const __restrict__ payload_attr_data,
+    uint64_t const data_length,
+    uint64_t *const __restrict__ aggregation_result) {
+  cpu_ht_probe_aggregate_linearprobing(
+      hash_table, hash_table_entries, hash_function, join_attr_data,
+      payload_attr_data, data_length, aggregation_result);
+}
+
+extern "C" void cpu_ht_probe_aggregate_linearprobing_composite(
+    HtEntry<CompositeKey, CompositeKey> const *const __restrict__ hash_table,
+    uint64_t const hash_table_entries, HashFunction const hash_function,
+    const CompositeKey *const __restrict__ join_attr_data,
+    const CompositeKey *const __restrict__ payload_attr_data,
+    uint64_t const data_length,
+    uint64_t *const __restrict__ aggregation_result) {
+  cpu_ht_probe_aggregate_linearprobing(
+      hash_table, hash_table_entries, hash_function, join_attr_data,
+      payload_attr_data, data_length, aggregation_result);
+}
+
+extern "C" void cpu_ht_probe_aggregate_linearprobing_int32_int64(
+    HtEntry<int, long long> const *const __restrict__ hash_table,
+    uint64_t const hash_table_entries, HashFunction const hash_function,
+    const int *const __restrict__ join_attr_data,
+    const long long *const __restrict__ payload_attr_data,
+    uint64_t const data_length,
+    uint64_t *const __restrict__ aggregation_result) {
+  cpu_ht_probe_aggregate_linearprobing(
+      hash_table, hash_table_entries, hash_function, join_attr_data,
+      payload_attr_data, data_length, aggregation_result);
+}
+
+extern "C" void cpu_ht_probe_aggregate_linearprobing_int64_int32(
+    HtEntry<long long, int> const *const __restrict__ hash_table,
+    uint64_t const hash_table_entries, HashFunction const hash_function,
+    const long long *const __restrict__ join_attr_data,
+    const int *const __restrict__ payload_attr_data, uint64_t const data_length,
+    uint64_t *const __restrict__ aggregation_result) {
+  cpu_ht_probe_aggregate_linearprobing(
+      hash_table, hash_table_entries, hash_function, join_attr_data,
+      payload_attr_data, data_length, aggregation_result);
+}
+
+template <typename K, typename V>
+void cpu_ht_build_perfect(HtEntry<K, V> *const __restrict__ hash_table,
                           uint64_t const /* hash_table_entries */,
-                          const T *const __restrict__ join_attribute_data,
-                          const T *const __restrict__ payload_attributed_data,
+                          const K *const __restrict__ join_attribute_data,
+                          const V *const __restrict__ payload_attributed_data,
                           uint64_t const data_length) {
   for (uint64_t tuple_id = 0; tuple_id < data_length; ++tuple_id) {
-    T key = join_attribute_data[tuple_id];
-    T val = payload_attributed_data[tuple_id];
-    hash_table[key].key = key;
-    hash_table[key].value = val;
+    K key = join_attribute_data[tuple_id];
+    V val = payload_attributed_data[tuple_id];
+    hash_table[perfect_index(key)].key = key;
+    hash_table[perfect_index(key)].value = val;
   }
 }
 
-template <typename T>
+template <typename K, typename V>
 void cpu_ht_build_selective_perfect(
-    HtEntry<T, T> *const __restrict__ hash_table,
+    HtEntry<K, V> *const __restrict__ hash_table,
     uint64_t const /* hash_table_entries */,
-    const T *const __restrict__ join_attribute_data,
-    const T *const __restrict__ payload_attributed_data,
+    const K *const __restrict__ join_attribute_data,
+    const V *const __restrict__ payload_attributed_data,
     uint64_t const data_length) {
   for (uint64_t tuple_id = 0; tuple_id < data_length; ++tuple_id) {
-    T key = join_attribute_data[tuple_id];
-    if (key != null_key<T>()) {
-      T val = payload_attributed_data[tuple_id];
-      hash_table[key].key = key;
-      hash_table[key].value = val;
+    K key = join_attribute_data[tuple_id];
+    if (key != null_key<K>()) {
+      V val = payload_attributed_data[tuple_id];
+      hash_table[perfect_index(key)].key = key;
+      hash_table[perfect_index(key)].value = val;
     }
   }
 }
@@ -224,6 +349,57 @@ extern "C" void cpu_ht_build_perfect_int64(
                        payload_attributed_data, data_length);
 }
 
+extern "C" void cpu_ht_build_perfect_uint32(
+    HtEntry<unsigned, unsigned> *const __restrict__ hash_table,
+    uint64_t const hash_table_entries,
+    const unsigned *const __restrict__ join_attribute_data,
+    const unsigned *const __restrict__ payload_attributed_data,
+    uint64_t const data_length) {
+  cpu_ht_build_perfect(hash_table, hash_table_entries, join_attribute_data,
+                       payload_attributed_data, data_length);
+}
+
+extern "C" void cpu_ht_build_perfect_uint64(
+    HtEntry<unsigned long long, unsigned long long> *const __restrict__
+        hash_table,
+    uint64_t const hash_table_entries,
+    const unsigned long long *const __restrict__ join_attribute_data,
+    const unsigned long long *const __restrict__ payload_attributed_data,
+    uint64_t const data_length) {
+  cpu_ht_build_perfect(hash_table, hash_table_entries, join_attribute_data,
+                       payload_attributed_data, data_length);
+}
+
+extern "C" void cpu_ht_build_perfect_composite(
+    HtEntry<CompositeKey, CompositeKey> *const __restrict__ hash_table,
+    uint64_t const hash_table_entries,
+    const CompositeKey *const __restrict__ join_attribute_data,
+    const CompositeKey *const __restrict__ payload_attributed_data,
+    uint64_t const data_length) {
+  cpu_ht_build_perfect(hash_table, hash_table_entries, join_attribute_data,
+                       payload_attributed_data, data_length);
+}
+
+extern "C" void cpu_ht_build_perfect_int32_int64(
+    HtEntry<int, long long> *const __restrict__ hash_table,
+    uint64_t const hash_table_entries,
+    const int *const __restrict__ join_attribute_data,
+    const long long *const __restrict__ payload_attributed_data,
+    uint64_t const data_length) {
+  cpu_ht_build_perfect(hash_table, hash_table_entries, join_attribute_data,
+                       payload_attributed_data, data_length);
+}
+
+extern "C" void cpu_ht_build_perfect_int64_int32(
+    HtEntry<long long, int> *const __restrict__ hash_table,
+    uint64_t const hash_table_entries,
+    const long long *const __restrict__ join_attribute_data,
+    const int *const __restrict__ payload_attributed_data,
+    uint64_t const data_length) {
+  cpu_ht_build_perfect(hash_table, hash_table_entries, join_attribute_data,
+                       payload_attributed_data, data_length);
+}
+
 extern "C" void cpu_ht_build_selective_perfect_int64(
     HtEntry<long long, long long> *const __restrict__ hash_table,
     uint64_t const hash_table_entries,
@@ -235,17 +411,74 @@ extern "C" void cpu_ht_build_selective_perfect_int64(
                                  data_length);
 }
 
-template <typename T>
+extern "C" void cpu_ht_build_selective_perfect_uint32(
+    HtEntry<unsigned, unsigned> *const __restrict__ hash_table,
+    uint64_t const hash_table_entries,
+    const unsigned *const __restrict__ join_attribute_data,
+    const unsigned *const __restrict__ payload_attributed_data,
+    uint64_t const data_length) {
+  cpu_ht_build_selective_perfect(hash_table, hash_table_entries,
+                                 join_attribute_data, payload_attributed_data,
+                                 data_length);
+}
+
+extern "C" void cpu_ht_build_selective_perfect_uint64(
+    HtEntry<unsigned long long, unsigned long long> *const __restrict__
+        hash_table,
+    uint64_t const hash_table_entries,
+    const unsigned long long *const __restrict__ join_attribute_data,
+    const unsigned long long *const __restrict__ payload_attributed_data,
+    uint64_t const data_length) {
+  cpu_ht_build_selective_perfect(hash_table, hash_table_entries,
+                                 join_attribute_data, payload_attributed_data,
+                                 data_length);
+}
+
+extern "C" void cpu_ht_build_selective_perfect_composite(
+    HtEntry<CompositeKey, CompositeKey> *const __restrict__ hash_table,
+    uint64_t const hash_table_entries,
+    const CompositeKey *const __restrict__ join_attribute_data,
+    const CompositeKey *const __restrict__ payload_attributed_data,
+    uint64_t const data_length) {
+  cpu_ht_build_selective_perfect(hash_table, hash_table_entries,
+                                 join_attribute_data, payload_attributed_data,
+                                 data_length);
+}
+
+extern "C" void cpu_ht_build_selective_perfect_int32_int64(
+    HtEntry<int, long long> *const __restrict__ hash_table,
+    uint64_t const hash_table_entries,
+    const int *const __restrict__ join_attribute_data,
+    const long long *const __restrict__ payload_attributed_data,
+    uint64_t const data_length) {
+  cpu_ht_build_selective_perfect(hash_table, hash_table_entries,
+                                 join_attribute_data, payload_attributed_data,
+                                 data_length);
+}
+
+extern "C" void cpu_ht_build_selective_perfect_int64_int32(
+    HtEntry<long long, int> *const __restrict__ hash_table,
+    uint64_t const hash_table_entries,
+    const long long *const __restrict__ join_attribute_data,
+    const int *const __restrict__ payload_attributed_data,
+    uint64_t const data_length) {
+  cpu_ht_build_selective_perfect(hash_table, hash_table_entries,
+                                 join_attribute_data, payload_attributed_data,
+                                 data_length);
+}
+
+template <typename K, typename V>
 void cpu_ht_probe_aggregate_perfect(
-    const HtEntry<T, T> *const __restrict__ hash_table,
+    const HtEntry<K, V> *const __restrict__ hash_table,
     uint64_t const /* hash_table_entries */,
-    const T *const __restrict__ join_attribute_data,
-    const T *const __restrict__ payload_attribute_data,
+    const K *const __restrict__ join_attribute_data,
+    const V *const __restrict__ payload_attribute_data,
     uint64_t const data_length, uint64_t *__restrict__ aggregation_result) {
   for (uint64_t tuple_id = 0; tuple_id < data_length; ++tuple_id) {
-    T key = join_attribute_data[tuple_id];
-    if (hash_table[key].key == key) {
-      *aggregation_result += payload_attribute_data[tuple_id];
+    K key = join_attribute_data[tuple_id];
+    if (hash_table[perfect_index(key)].key == key) {
+      *aggregation_result +=
+          static_cast<unsigned long long>(payload_attribute_data[tuple_id]);
     }
   }
 }
@@ -271,3 +504,856 @@ extern "C" void cpu_ht_probe_aggregate_perfect_int64(
                                  join_attribute_data, payload_attribute_data,
                                  data_length, aggregation_result);
 }
+
+extern "C" void cpu_ht_probe_aggregate_perfect_uint32(
+    const HtEntry<unsigned, unsigned> *const __restrict__ hash_table,
+    uint64_t const hash_table_entries,
+    const unsigned *const __restrict__ join_attribute_data,
+    const unsigned *const __restrict__ payload_attribute_data,
+    uint64_t const data_length, uint64_t *__restrict__ aggregation_result) {
+  cpu_ht_probe_aggregate_perfect(hash_table, hash_table_entries,
+                                 join_attribute_data, payload_attribute_data,
+                                 data_length, aggregation_result);
+}
+
+extern "C" void cpu_ht_probe_aggregate_perfect_uint64(
+    const HtEntry<unsigned long long, unsigned long long> *const __restrict__
+        hash_table,
+    uint64_t const hash_table_entries,
+    const unsigned long long *const __restrict__ join_attribute_data,
+    const unsigned long long *const __restrict__ payload_attribute_data,
+    uint64_t const data_length, uint64_t *__restrict__ aggregation_result) {
+  cpu_ht_probe_aggregate_perfect(hash_table, hash_table_entries,
+                                 join_attribute_data, payload_attribute_data,
+                                 data_length, aggregation_result);
+}
+
+extern "C" void cpu_ht_probe_aggregate_perfect_composite(
+    const HtEntry<CompositeKey, CompositeKey> *const __restrict__ hash_table,
+    uint64_t const hash_table_entries,
+    const CompositeKey *const __restrict__ join_attribute_data,
+    const CompositeKey *const __restrict__ payload_attribute_data,
+    uint64_t const data_length, uint64_t *__restrict__ aggregation_result) {
+  cpu_ht_probe_aggregate_perfect(hash_table, hash_table_entries,
+                                 join_attribute_data, payload_attribute_data,
+                                 data_length, aggregation_result);
+}
+
+extern "C" void cpu_ht_probe_aggregate_perfect_int32_int64(
+    const HtEntry<int, long long> *const __restrict__ hash_table,
+    uint64_t const hash_table_entries,
+    const int *const __restrict__ join_attribute_data,
+    const long long *const __restrict__ payload_attribute_data,
+    uint64_t const data_length, uint64_t *__restrict__ aggregation_result) {
+  cpu_ht_probe_aggregate_perfect(hash_table, hash_table_entries,
+                                 join_attribute_data, payload_attribute_data,
+                                 data_length, aggregation_result);
+}
+
+extern "C" void cpu_ht_probe_aggregate_perfect_int64_int32(
+    const HtEntry<long long, int> *const __restrict__ hash_table,
+    uint64_t const hash_table_entries,
+    const long long *const __restrict__ join_attribute_data,
+    const int *const __restrict__ payload_attribute_data,
+    uint64_t const data_length, uint64_t *__restrict__ aggregation_result) {
+  cpu_ht_probe_aggregate_perfect(hash_table, hash_table_entries,
+                                 join_attribute_data, payload_attribute_data,
+                                 data_length, aggregation_result);
+}
+
+// Probes the hash table and writes the matching payload pairs to the output.
+//
+// Returns the number of probe tuples that were processed. Processing stops
+// before the first probe tuple for which not all matches fit into the output.
+// The number of written pairs is returned in `output_written`.
+template <typename K, typename V>
+uint64_t cpu_ht_probe_materialize_linearprobing(
+    HtEntry<K, V> const *const __restrict__ hash_table,
+    uint64_t const hash_table_entries, HashFunction const hash_function,
+    const K *const __restrict__ join_attr_data,
+    const V *const __restrict__ payload_attr_data, uint64_t const data_length,
+    JoinPair<V, V> *const __restrict__ output, uint64_t const output_length,
+    uint64_t *const __restrict__ output_written) {
+  const unsigned int log2_hash_table_entries =
+      log2_floor_power_of_two(hash_table_entries);
+
+  uint64_t written = 0;
+  uint64_t tuple_id = 0;
+  for (; tuple_id < data_length; ++tuple_id) {
+    V const *hash_table_payload = nullptr;
+    uint64_t hash_table_last_index = 0;
+    bool hash_table_use_last_index = false;
+    uint64_t matches = 0;
+    while (cpu_ht_findkey_linearprobing(
+        hash_table, log2_hash_table_entries, hash_function,
+        join_attr_data[tuple_id], &hash_table_payload, &hash_table_last_index,
+        hash_table_use_last_index)) {
+      hash_table_use_last_index = true;
+      ++matches;
+    }
+
+    if (written + matches > output_length) {
+      break;
+    }
+
+    hash_table_use_last_index = false;
+    while (cpu_ht_findkey_linearprobing(
+        hash_table, log2_hash_table_entries, hash_function,
+        join_attr_data[tuple_id], &hash_table_payload, &hash_table_last_index,
+        hash_table_use_last_index)) {
+      hash_table_use_last_index = true;
+      output[written] = {*hash_table_payload, payload_attr_data[tuple_id]};
+      ++written;
+    }
+  }
+
+  *output_written = written;
+  return tuple_id;
+}
+
+extern "C" uint64_t cpu_ht_probe_materialize_linearprobing_int32(
+    HtEntry<int, int> const *const __restrict__ hash_table,
+    uint64_t const hash_table_entries, HashFunction const hash_function,
+    const int *const __restrict__ join_attr_data,
+    const int *const __restrict__ payload_attr_data, uint64_t const data_length,
+    JoinPair<int, int> *const __restrict__ output, uint64_t const output_length,
+    uint64_t *const __restrict__ output_written) {
+  return cpu_ht_probe_materialize_linearprobing(
+      hash_table, hash_table_entries, hash_function, join_attr_data,
+      payload_attr_data, data_length, output, output_length, output_written);
+}
+
+extern "C" uint64_t cpu_ht_probe_materialize_linearprobing_int64(
+    HtEntry<long long, long long> const *const __restrict__ hash_table,
+    uint64_t const hash_table_entries, HashFunction const hash_function,
+    const long long *const __restrict__ join_attr_data,
+    const long long *const __restrict__ payload_attr_data,
+    uint64_t const data_length,
+    JoinPair<long long, long long> *const __restrict__ output,
+    uint64_t const output_length, uint64_t *const __restrict__ output_written) {
+  return cpu_ht_probe_materialize_linearprobing(
+      hash_table, hash_table_entries, hash_function, join_attr_data,
+      payload_attr_data, data_length, output, output_length, output_written);
+}
+
+extern "C" uint64_t cpu_ht_probe_materialize_linearprobing_uint32(
+    HtEntry<unsigned, unsigned> const *const __restrict__ hash_table,
+    uint64_t const hash_table_entries, HashFunction const hash_function,
+    const unsigned *const __restrict__ join_attr_data,
+    const unsigned *const __restrict__ payload_attr_data,
+    uint64_t const data_length,
+    JoinPair<unsigned, unsigned> *const __restrict__ output,
+    uint64_t const output_length, uint64_t *const __restrict__ output_written) {
+  return cpu_ht_probe_materialize_linearprobing(
+      hash_table, hash_table_entries, hash_function, join_attr_data,
+      payload_attr_data, data_length, output, output_length, output_written);
+}
+
+extern "C" uint64_t cpu_ht_probe_materialize_linearprobing_uint64(
+    HtEntry<unsigned long long, unsigned long long> const *const __restrict__
+        hash_table,
+    uint64_t const hash_table_entries, HashFunction const hash_function,
+    const unsigned long long *const __restrict__ join_attr_data,
+    const unsigned long long *const __restrict__ payload_attr_data,
+    uint64_t const data_length,
+    JoinPair<unsigned long long, unsigned long long> *const __restrict__ output,
+    uint64_t const output_length, uint64_t *const __restrict__ output_written) {
+  return cpu_ht_probe_materialize_linearprobing(
+      hash_table, hash_table_entries, hash_function, join_attr_data,
+      payload_attr_data, data_length, output, output_length, output_written);
+}
+
+extern "C" uint64_t cpu_ht_probe_materialize_linearprobing_composite(
+    HtEntry<CompositeKey, CompositeKey> const *const __restrict__ hash_table,
+    uint64_t const hash_table_entries, HashFunction const hash_function,
+    const CompositeKey *const __restrict__ join_attr_data,
+    const CompositeKey *const __restrict__ payload_attr_data,
+    uint64_t const data_length,
+    JoinPair<CompositeKey, CompositeKey> *const __restrict__ output,
+    uint64_t const output_length, uint64_t *const __restrict__ output_written) {
+  return cpu_ht_probe_materialize_linearprobing(
+      hash_table, hash_table_entries, hash_function, join_attr_data,
+      payload_attr_data, data_length, output, output_length, output_written);
+}
+
+extern "C" uint64_t cpu_ht_probe_materialize_linearprobing_int32_int64(
+    HtEntry<int, long long> const *const __restrict__ hash_table,
+    uint64_t const hash_table_entries, HashFunction const hash_function,
+    const int *const __restrict__ join_attr_data,
+    const long long *const __restrict__ payload_attr_data,
+    uint64_t const data_length,
+    JoinPair<long long, long long> *const __restrict__ output,
+    uint64_t const output_length, uint64_t *const __restrict__ output_written) {
+  return cpu_ht_probe_materialize_linearprobing(
+      hash_table, hash_table_entries, hash_function, join_attr_data,
+      payload_attr_data, data_length, output, output_length, output_written);
+}
+
+extern "C" uint64_t cpu_ht_probe_materialize_linearprobing_int64_int32(
+    HtEntry<long long, int> const *const __restrict__ hash_table,
+    uint64_t const hash_table_entries, HashFunction const hash_function,
+    const long long *const __restrict__ join_attr_data,
+    const int *const __restrict__ payload_attr_data, uint64_t const data_length,
+    JoinPair<int, int> *const __restrict__ output, uint64_t const output_length,
+    uint64_t *const __restrict__ output_written) {
+  return cpu_ht_probe_materialize_linearprobing(
+      hash_table, hash_table_entries, hash_function, join_attr_data,
+      payload_attr_data, data_length, output, output_length, output_written);
+}
+
+// Probes the hash table and writes the matching payload pairs to the output.
+//
+// See `cpu_ht_probe_materialize_linearprobing` for the return values.
+template <typename K, typename V>
+uint64_t cpu_ht_probe_materialize_perfect(
+    const HtEntry<K, V> *const __restrict__ hash_table,
+    uint64_t const /* hash_table_entries */,
+    const K *const __restrict__ join_attribute_data,
+    const V *const __restrict__ payload_attribute_data,
+    uint64_t const data_length, JoinPair<V, V> *const __restrict__ output,
+    uint64_t const output_length, uint64_t *const __restrict__ output_written) {
+  uint64_t written = 0;
+  uint64_t tuple_id = 0;
+  for (; tuple_id < data_length; ++tuple_id) {
+    K key = join_attribute_data[tuple_id];
+    if (hash_table[perfect_index(key)].key == key) {
+      if (written == output_length) {
+        break;
+      }
+
+      output[written] = {hash_table[perfect_index(key)].value,
+                         payload_attribute_data[tuple_id]};
+      ++written;
+    }
+  }
+
+  *output_written = written;
+  return tuple_id;
+}
+
+extern "C" uint64_t cpu_ht_probe_materialize_perfect_int32(
+    const HtEntry<int, int> *const __restrict__ hash_table,
+    uint64_t const hash_table_entries,
+    const int *const __restrict__ join_attribute_data,
+    const int *const __restrict__ payload_attribute_data,
+    uint64_t const data_length, JoinPair<int, int> *const __restrict__ output,
+    uint64_t const output_length, uint64_t *const __restrict__ output_written) {
+  return cpu_ht_probe_materialize_perfect(
+      hash_table, hash_table_entries, join_attribute_data,
+      payload_attribute_data, data_length, output, output_length,
+      output_written);
+}
+
+extern "C" uint64_t cpu_ht_probe_materialize_perfect_int64(
+    const HtEntry<long long, long long> *const __restrict__ hash_table,
+    uint64_t const hash_table_entries,
+    const long long *const __restrict__ join_attribute_data,
+    const long long *const __restrict__ payload_attribute_data,
+    uint64_t const data_length,
+    JoinPair<long long, long long> *const __restrict__ output,
+    uint64_t const output_length, uint64_t *const __restrict__ output_written) {
+  return cpu_ht_probe_materialize_perfect(
+      hash_table, hash_table_entries, join_attribute_data,
+      payload_attribute_data, data_length, output, output_length,
+      output_written);
+}
+
+extern "C" uint64_t cpu_ht_probe_materialize_perfect_uint32(
+    const HtEntry<unsigned, unsigned> *const __restrict__ hash_table,
+    uint64_t const hash_table_entries,
+    const unsigned *const __restrict__ join_attribute_data,
+    const unsigned *const __restrict__ payload_attribute_data,
+    uint64_t const data_length,
+    JoinPair<unsigned, unsigned> *const __restrict__ output,
+    uint64_t const output_length, uint64_t *const __restrict__ output_written) {
+  return cpu_ht_probe_materialize_perfect(
+      hash_table, hash_table_entries, join_attribute_data,
+      payload_attribute_data, data_length, output, output_length,
+      output_written);
+}
+
+extern "C" uint64_t cpu_ht_probe_materialize_perfect_uint64(
+    const HtEntry<unsigned long long, unsigned long long> *const __restrict__
+        hash_table,
+    uint64_t const hash_table_entries,
+    const unsigned long long *const __restrict__ join_attribute_data,
+    const unsigned long long *const __restrict__ payload_attribute_data,
+    uint64_t const data_length,
+    JoinPair<unsigned long long, unsigned long long> *const __restrict__ output,
+    uint64_t const output_length, uint64_t *const __restrict__ output_written) {
+  return cpu_ht_probe_materialize_perfect(
+      hash_table, hash_table_entries, join_attribute_data,
+      payload_attribute_data, data_length, output, output_length,
+      output_written);
+}
+
+extern "C" uint64_t cpu_ht_probe_materialize_perfect_composite(
+    const HtEntry<CompositeKey, CompositeKey> *const __restrict__ hash_table,
+    uint64_t const hash_table_entries,
+    const CompositeKey *const __restrict__ join_attribute_data,
+    const CompositeKey *const __restrict__ payload_attribute_data,
+    uint64_t const data_length,
+    JoinPair<CompositeKey, CompositeKey> *const __restrict__ output,
+    uint64_t const output_length, uint64_t *const __restrict__ output_written) {
+  return cpu_ht_probe_materialize_perfect(
+      hash_table, hash_table_entries, join_attribute_data,
+      payload_attribute_data, data_length, output, output_length,
+      output_written);
+}
+
+extern "C" uint64_t cpu_ht_probe_materialize_perfect_int32_int64(
+    const HtEntry<int, long long> *const __restrict__ hash_table,
+    uint64_t const hash_table_entries,
+    const int *const __restrict__ join_attribute_data,
+    const long long *const __restrict__ payload_attribute_data,
+    uint64_t const data_length,
+    JoinPair<long long, long long> *const __restrict__ output,
+    uint64_t const output_length, uint64_t *const __restrict__ output_written) {
+  return cpu_ht_probe_materialize_perfect(
+      hash_table, hash_table_entries, join_attribute_data,
+      payload_attribute_data, data_length, output, output_length,
+      output_written);
+}
+
+extern "C" uint64_t cpu_ht_probe_materialize_perfect_int64_int32(
+    const HtEntry<long long, int> *const __restrict__ hash_table,
+    uint64_t const hash_table_entries,
+    const long long *const __restrict__ join_attribute_data,
+    const int *const __restrict__ payload_attribute_data,
+    uint64_t const data_length, JoinPair<int, int> *const __restrict__ output,
+    uint64_t const output_length, uint64_t *const __restrict__ output_written) {
+  return cpu_ht_probe_materialize_perfect(
+      hash_table, hash_table_entries, join_attribute_data,
+      payload_attribute_data, data_length, output, output_length,
+      output_written);
+}
+
+// The integer type that indexes the entries of a bucket chaining hash table.
+//
+// Integer keys use their own type as index. Composite keys use an 8-byte
+// index, because atomic operations on composite keys are restricted to
+// compare-and-swap.
+template <typename T>
+struct ChainingIndex {
+  using type = T;
+};
+
+template <>
+struct ChainingIndex<CompositeKey> {
+  using type = unsigned long long;
+};
+
+// Bucket chaining hash table.
+//
+// The hash table reuses the memory of `hash_table_entries` entries. The memory
+// begins with an allocation counter, followed by the bucket heads, and the
+// keys, values, and links of the chained entries. The counter, heads, and links
+// are indices of type `I`. The keys, values, and links each begin at the next
+// offset that is aligned to their type. The number of buckets is the largest
+// power of two that is at most a quarter of `hash_table_entries`.
+//
+// Empty chains and chain ends are marked with `null_key<I>()`. The counter
+// holds the index of the last allocated entry. Thus, the hash table is empty if
+// all bits of its memory are set, including any padding between the keys and
+// values of `HtEntry`.
+//
+// Inserts are thread-safe. Each insert allocates an entry by incrementing the
+// counter, and then pushes the entry onto the head of its bucket's chain.
+template <typename K, typename V, typename I = typename ChainingIndex<K>::type>
+class CpuChainingHashTable {
+ public:
+  CpuChainingHashTable(HtEntry<K, V> const *const hash_table,
+                       uint64_t const hash_table_entries,
+                       HashFunction const hash_function)
+      : hash_function(hash_function),
+        log2_buckets(log2_floor_power_of_two(
+            hash_table_entries / 4ULL > 0ULL ? hash_table_entries / 4ULL
+                                             : 1ULL)),
+        capacity(chained_entries(hash_table_entries, log2_buckets)),
+        counter(reinterpret_cast<std::atomic<I> *>(
+            const_cast<HtEntry<K, V> *>(hash_table))),
+        heads(&counter[1]),
+        keys(align_up<K>(&heads[1ULL << log2_buckets])),
+        values(align_up<V>(&keys[capacity])),
+        links(align_up<I>(&values[capacity])) {}
+
+  // Inserts a tuple. Returns false if the hash table is full.
+  bool insert(K key, V value) {
+    I index = std::atomic_fetch_add(counter, static_cast<I>(1)) + 1;
+    if (static_cast<uint64_t>(index) >= capacity) {
+      return false;
+    }
+
+    keys[index] = key;
+    values[index] = value;
+
+    std::atomic<I> *head =
+        &heads[hash_to_bucket(hash_function, key, log2_buckets)];
+    I expected = std::atomic_load(head);
+    do {
+      links[index] = expected;
+    } while (!std::atomic_compare_exchange_weak(head, &expected, index));
+
+    return true;
+  }
+
+  // Calls `f` with the value of each entry that matches `key`.
+  template <typename F>
+  void for_each_match(K key, F f) const {
+    I index = std::atomic_load_explicit(
+        &heads[hash_to_bucket(hash_function, key, log2_buckets)],
+        std::memory_order_relaxed);
+    for (; index != null_key<I>(); index = links[index]) {
+      if (keys[index] == key) {
+        f(values[index]);
+      }
+    }
+  }
+
+ private:
+  // Computes the number of chained entries that fit into the memory after the
+  // counter and the heads. Reserves the worst-case alignment padding in front
+  // of the keys, values, and links.
+  static uint64_t chained_entries(uint64_t hash_table_entries,
+                                  unsigned int log2_buckets) {
+    uint64_t bytes = hash_table_entries * sizeof(HtEntry<K, V>);
+    uint64_t reserved = (1ULL + (1ULL << log2_buckets)) * sizeof(I) +
+                        alignof(K) + alignof(V) + alignof(I);
+
+    return bytes > reserved
+               ? (bytes - reserved) / (sizeof(K) + sizeof(V) + sizeof(I))
+               : 0ULL;
+  }
+
+  // Rounds up the address to the alignment of `T`.
+  template <typename T, typename P>
+  static T *align_up(P *ptr) {
+    uintptr_t address = reinterpret_cast<uintptr_t>(ptr);
+    return reinterpret_cast<T *>((address + alignof(T) - 1ULL) / alignof(T) *
+                                 alignof(T));
+  }
+
+  HashFunction const hash_function;
+  unsigned int const log2_buckets;
+  uint64_t const capacity;
+  std::atomic<I> *const counter;
+  std::atomic<I> *const heads;
+  K *const keys;
+  V *const values;
+  I *const links;
+};
+
+// Inserts the tuples into the hash table.
+//
+// If `is_selective` is set, tuples with a `null_key<K>()` are skipped. Returns
+// the number of tuples that didn't fit into the hash table.
+template <bool is_selective, typename K, typename V>
+uint64_t cpu_ht_build_chaining(
+    HtEntry<K, V> *const __restrict__ hash_table,
+    uint64_t const hash_table_entries, HashFunction const hash_function,
+    const K *const __restrict__ join_attr_data,
+    const V *const __restrict__ payload_attr_data, uint64_t const data_length) {
+  CpuChainingHashTable<K, V> table(hash_table, hash_table_entries,
+                                   hash_function);
+
+  uint64_t overflow = 0;
+  for (uint64_t tuple_id = 0; tuple_id < data_length; ++tuple_id) {
+    K key = join_attr_data[tuple_id];
+    if (is_selective && key == null_key<K>()) {
+      continue;
+    }
+
+    if (!table.insert(key, payload_attr_data[tuple_id])) {
+      ++overflow;
+    }
+  }
+
+  return overflow;
+}
+
+extern "C" uint64_t cpu_ht_build_chaining_int32(
+    HtEntry<int, int> *const __restrict__ hash_table,
+    uint64_t const hash_table_entries, HashFunction const hash_function,
+    const int *const __restrict__ join_attr_data,
+    const int *const __restrict__ payload_attr_data,
+    uint64_t const data_length) {
+  return cpu_ht_build_chaining<false>(hash_table, hash_table_entries,
+                                      hash_function, join_attr_data,
+                                      payload_attr_data, data_length);
+}
+
+extern "C" uint64_t cpu_ht_build_selective_chaining_int32(
+    HtEntry<int, int> *const __restrict__ hash_table,
+    uint64_t const hash_table_entries, HashFunction const hash_function,
+    const int *const __restrict__ join_attr_data,
+    const int *const __restrict__ payload_attr_data,
+    uint64_t const data_length) {
+  return cpu_ht_build_chaining<true>(hash_table, hash_table_entries,
+                                     hash_function, join_attr_data,
+                                     payload_attr_data, data_length);
+}
+
+extern "C" uint64_t cpu_ht_build_chaining_int64(
+    HtEntry<long long, long long> *const __restrict__ hash_table,
+    uint64_t const hash_table_entries, HashFunction const hash_function,
+    const long long *const __restrict__ join_attr_data,
+    const long long *const __restrict__ payload_attr_data,
+    uint64_t const data_length) {
+  return cpu_ht_build_chaining<false>(hash_table, hash_table_entries,
+                                      hash_function, join_attr_data,
+                                      payload_attr_data, data_length);
+}
+
+extern "C" uint64_t cpu_ht_build_chaining_uint32(
+    HtEntry<unsigned, unsigned> *const __restrict__ hash_table,
+    uint64_t const hash_table_entries, HashFunction const hash_function,
+    const unsigned *const __restrict__ join_attr_data,
+    const unsigned *const __restrict__ payload_attr_data,
+    uint64_t const data_length) {
+  return cpu_ht_build_chaining<false>(hash_table, hash_table_entries,
+                                      hash_function, join_attr_data,
+                                      payload_attr_data, data_length);
+}
+
+extern "C" uint64_t cpu_ht_build_chaining_uint64(
+    HtEntry<unsigned long long, unsigned long long> *const __restrict__
+        hash_table,
+    uint64_t const hash_table_entries, HashFunction const hash_function,
+    const unsigned long long *const __restrict__ join_attr_data,
+    const unsigned long long *const __restrict__ payload_attr_data,
+    uint64_t const data_length) {
+  return cpu_ht_build_chaining<false>(hash_table, hash_table_entries,
+                                      hash_function, join_attr_data,
+                                      payload_attr_data, data_length);
+}
+
+extern "C" uint64_t cpu_ht_build_chaining_composite(
+    HtEntry<CompositeKey, CompositeKey> *const __restrict__ hash_table,
+    uint64_t const hash_table_entries, HashFunction const hash_function,
+    const CompositeKey *const __restrict__ join_attr_data,
+    const CompositeKey *const __restrict__ payload_attr_data,
+    uint64_t const data_length) {
+  return cpu_ht_build_chaining<false>(hash_table, hash_table_entries,
+                                      hash_function, join_attr_data,
+                                      payload_attr_data, data_length);
+}
+
+extern "C" uint64_t cpu_ht_build_chaining_int32_int64(
+    HtEntry<int, long long> *const __restrict__ hash_table,
+    uint64_t const hash_table_entries, HashFunction const hash_function,
+    const int *const __restrict__ join_attr_data,
+    const long long *const __restrict__ payload_attr_data,
+    uint64_t const data_length) {
+  return cpu_ht_build_chaining<false>(hash_table, hash_table_entries,
+                                      hash_function, join_attr_data,
+                                      payload_attr_data, data_length);
+}
+
+extern "C" uint64_t cpu_ht_build_chaining_int64_int32(
+    HtEntry<long long, int> *const __restrict__ hash_table,
+    uint64_t const hash_table_entries, HashFunction const hash_function,
+    const long long *const __restrict__ join_attr_data,
+    const int *const __restrict__ payload_attr_data,
+    uint64_t const data_length) {
+  return cpu_ht_build_chaining<false>(hash_table, hash_table_entries,
+                                      hash_function, join_attr_data,
+                                      payload_attr_data, data_length);
+}
+
+extern "C" uint64_t cpu_ht_build_selective_chaining_int64(
+    HtEntry<long long, long long> *const __restrict__ hash_table,
+    uint64_t const hash_table_entries, HashFunction const hash_function,
+    const long long *const __restrict__ join_attr_data,
+    const long long *const __restrict__ payload_attr_data,
+    uint64_t const data_length) {
+  return cpu_ht_build_chaining<true>(hash_table, hash_table_entries,
+                                     hash_function, join_attr_data,
+                                     payload_attr_data, data_length);
+}
+
+extern "C" uint64_t cpu_ht_build_selective_chaining_uint32(
+    HtEntry<unsigned, unsigned> *const __restrict__ hash_table,
+    uint64_t const hash_table_entries, HashFunction const hash_function,
+    const unsigned *const __restrict__ join_attr_data,
+    const unsigned *const __restrict__ payload_attr_data,
+    uint64_t const data_length) {
+  return cpu_ht_build_chaining<true>(hash_table, hash_table_entries,
+                                     hash_function, join_attr_data,
+                                     payload_attr_data, data_length);
+}
+
+extern "C" uint64_t cpu_ht_build_selective_chaining_uint64(
+    HtEntry<unsigned long long, unsigned long long> *const __restrict__
+        hash_table,
+    uint64_t const hash_table_entries, HashFunction const hash_function,
+    const unsigned long long *const __restrict__ join_attr_data,
+    const unsigned long long *const __restrict__ payload_attr_data,
+    uint64_t const data_length) {
+  return cpu_ht_build_chaining<true>(hash_table, hash_table_entries,
+                                     hash_function, join_attr_data,
+                                     payload_attr_data, data_length);
+}
+
+extern "C" uint64_t cpu_ht_build_selective_chaining_composite(
+    HtEntry<CompositeKey, CompositeKey> *const __restrict__ hash_table,
+    uint64_t const hash_table_entries, HashFunction const hash_function,
+    const CompositeKey *const __restrict__ join_attr_data,
+    const CompositeKey *const __restrict__ payload_attr_data,
+    uint64_t const data_length) {
+  return cpu_ht_build_chaining<true>(hash_table, hash_table_entries,
+                                     hash_function, join_attr_data,
+                                     payload_attr_data, data_length);
+}
+
+extern "C" uint64_t cpu_ht_build_selective_chaining_int32_int64(
+    HtEntry<int, long long> *const __restrict__ hash_table,
+    uint64_t const hash_table_entries, HashFunction const hash_function,
+    const int *const __restrict__ join_attr_data,
+    const long long *const __restrict__ payload_attr_data,
+    uint64_t const data_length) {
+  return cpu_ht_build_chaining<true>(hash_table, hash_table_entries,
+                                     hash_function, join_attr_data,
+                                     payload_attr_data, data_length);
+}
+
+extern "C" uint64_t cpu_ht_build_selective_chaining_int64_int32(
+    HtEntry<long long, int> *const __restrict__ hash_table,
+    uint64_t const hash_table_entries, HashFunction const hash_function,
+    const long long *const __restrict__ join_attr_data,
+    const int *const __restrict__ payload_attr_data,
+    uint64_t const data_length) {
+  return cpu_ht_build_chaining<true>(hash_table, hash_table_entries,
+                                     hash_function, join_attr_data,
+                                     payload_attr_data, data_length);
+}
+
+template <typename K, typename V>
+void cpu_ht_probe_aggregate_chaining(
+    HtEntry<K, V> const *const __restrict__ hash_table,
+    uint64_t const hash_table_entries, HashFunction const hash_function,
+    const K *const __restrict__ join_attr_data,
+    const V *const __restrict__ payload_attr_data, uint64_t const data_length,
+    uint64_t *const __restrict__ aggregation_result) {
+  CpuChainingHashTable<K, V> const table(hash_table, hash_table_entries,
+                                         hash_function);
+
+  uint64_t sum = 0;
+  for (uint64_t tuple_id = 0; tuple_id < data_length; ++tuple_id) {
+    V payload = payload_attr_data[tuple_id];
+    table.for_each_match(join_attr_data[tuple_id], [&](V) {
+      sum += static_cast<unsigned long long>(payload);
+    });
+  }
+
+  *aggregation_result += sum;
+}
+
+extern "C" void cpu_ht_probe_aggregate_chaining_int32(
+    HtEntry<int, int> const *const __restrict__ hash_table,
+    uint64_t const hash_table_entries, HashFunction const hash_function,
+    const int *const __restrict__ join_attr_data,
+    const int *const __restrict__ payload_attr_data, uint64_t const data_length,
+    uint64_t *const __restrict__ aggregation_result) {
+  cpu_ht_probe_aggregate_chaining(hash_table, hash_table_entries, hash_function,
+                                  join_attr_data, payload_attr_data,
+                                  data_length, aggregation_result);
+}
+
+extern "C" void cpu_ht_probe_aggregate_chaining_int64(
+    HtEntry<long long, long long> const *const __restrict__ hash_table,
+    uint64_t const hash_table_entries, HashFunction const hash_function,
+    const long long *const __restrict__ join_attr_data,
+    const long long *const __restrict__ payload_attr_data,
+    uint64_t const data_length,
+    uint64_t *const __restrict__ aggregation_result) {
+  cpu_ht_probe_aggregate_chaining(hash_table, hash_table_entries, hash_function,
+                                  join_attr_data, payload_attr_data,
+                                  data_length, aggregation_result);
+}
+
+extern "C" void cpu_ht_probe_aggregate_chaining_uint32(
+    HtEntry<unsigned, unsigned> const *const __restrict__ hash_table,
+    uint64_t const hash_table_entries, HashFunction const hash_function,
+    const unsigned *const __restrict__ join_attr_data,
+    const unsigned *const __restrict__ payload_attr_data,
+    uint64_t const data_length,
+    uint64_t *const __restrict__ aggregation_result) {
+  cpu_ht_probe_aggregate_chaining(hash_table, hash_table_entries, hash_function,
+                                  join_attr_data, payload_attr_data,
+                                  data_length, aggregation_result);
+}
+
+extern "C" void cpu_ht_probe_aggregate_chaining_uint64(
+    HtEntry<unsigned long long, unsigned long long> const *const __restrict__
+        hash_table,
+    uint64_t const hash_table_entries, HashFunction const hash_function,
+    const unsigned long long *const __restrict__ join_attr_data,
+    const unsigned long long *const __restrict__ payload_attr_data,
+    uint64_t const data_length,
+    uint64_t *const __restrict__ aggregation_result) {
+  cpu_ht_probe_aggregate_chaining(hash_table, hash_table_entries, hash_function,
+                                  join_attr_data, payload_attr_data,
+                                  data_length, aggregation_result);
+}
+
+extern "C" void cpu_ht_probe_aggregate_chaining_composite(
+    HtEntry<CompositeKey, CompositeKey> const *const __restrict__ hash_table,
+    uint64_t const hash_table_entries, HashFunction const hash_function,
+    const CompositeKey *const __restrict__ join_attr_data,
+    const CompositeKey *const __restrict__ payload_attr_data,
+    uint64_t const data_length,
+    uint64_t *const __restrict__ aggregation_result) {
+  cpu_ht_probe_aggregate_chaining(hash_table, hash_table_entries, hash_function,
+                                  join_attr_data, payload_attr_data,
+                                  data_length, aggregation_result);
+}
+
+extern "C" void cpu_ht_probe_aggregate_chaining_int32_int64(
+    HtEntry<int, long long> const *const __restrict__ hash_table,
+    uint64_t const hash_table_entries, HashFunction const hash_function,
+    const int *const __restrict__ join_attr_data,
+    const long long *const __restrict__ payload_attr_data,
+    uint64_t const data_length,
+    uint64_t *const __restrict__ aggregation_result) {
+  cpu_ht_probe_aggregate_chaining(hash_table, hash_table_entries, hash_function,
+                                  join_attr_data, payload_attr_data,
+                                  data_length, aggregation_result);
+}
+
+extern "C" void cpu_ht_probe_aggregate_chaining_int64_int32(
+    HtEntry<long long, int> const *const __restrict__ hash_table,
+    uint64_t const hash_table_entries, HashFunction const hash_function,
+    const long long *const __restrict__ join_attr_data,
+    const int *const __restrict__ payload_attr_data, uint64_t const data_length,
+    uint64_t *const __restrict__ aggregation_result) {
+  cpu_ht_probe_aggregate_chaining(hash_table, hash_table_entries, hash_function,
+                                  join_attr_data, payload_attr_data,
+                                  data_length, aggregation_result);
+}
+
+// Probes the hash table and writes the matching payload pairs to the output.
+//
+// See `cpu_ht_probe_materialize_linearprobing` for the return values.
+template <typename K, typename V>
+uint64_t cpu_ht_probe_materialize_chaining(
+    HtEntry<K, V> const *const __restrict__ hash_table,
+    uint64_t const hash_table_entries, HashFunction const hash_function,
+    const K *const __restrict__ join_attr_data,
+    const V *const __restrict__ payload_attr_data, uint64_t const data_length,
+    JoinPair<V, V> *const __restrict__ output, uint64_t const output_length,
+    uint64_t *const __restrict__ output_written) {
+  CpuChainingHashTable<K, V> const table(hash_table, hash_table_entries,
+                                         hash_function);
+
+  uint64_t written = 0;
+  uint64_t tuple_id = 0;
+  for (; tuple_id < data_length; ++tuple_id) {
+    K key = join_attr_data[tuple_id];
+    V payload = payload_attr_data[tuple_id];
+
+    uint64_t matches = 0;
+    table.for_each_match(key, [&](V) { ++matches; });
+
+    if (written + matches > output_length) {
+      break;
+    }
+
+    table.for_each_match(key, [&](V value) {
+      output[written] = {value, payload};
+      ++written;
+    });
+  }
+
+  *output_written = written;
+  return tuple_id;
+}
+
+extern "C" uint64_t cpu_ht_probe_materialize_chaining_int32(
+    HtEntry<int, int> const *const __restrict__ hash_table,
+    uint64_t const hash_table_entries, HashFunction const hash_function,
+    const int *const __restrict__ join_attr_data,
+    const int *const __restrict__ payload_attr_data, uint64_t const data_length,
+    JoinPair<int, int> *const __restrict__ output, uint64_t const output_length,
+    uint64_t *const __restrict__ output_written) {
+  return cpu_ht_probe_materialize_chaining(
+      hash_table, hash_table_entries, hash_function, join_attr_data,
+      payload_attr_data, data_length, output, output_length, output_written);
+}
+
+extern "C" uint64_t cpu_ht_probe_materialize_chaining_int64(
+    HtEntry<long long, long long> const *const __restrict__ hash_table,
+    uint64_t const hash_table_entries, HashFunction const hash_function,
+    const long long *const __restrict__ join_attr_data,
+    const long long *const __restrict__ payload_attr_data,
+    uint64_t const data_length,
+    JoinPair<long long, long long> *const __restrict__ output,
+    uint64_t const output_length, uint64_t *const __restrict__ output_written) {
+  return cpu_ht_probe_materialize_chaining(
+      hash_table, hash_table_entries, hash_function, join_attr_data,
+      payload_attr_data, data_length, output, output_length, output_written);
+}
+
+extern "C" uint64_t cpu_ht_probe_materialize_chaining_uint32(
+    HtEntry<unsigned, unsigned> const *const __restrict__ hash_table,
+    uint64_t const hash_table_entries, HashFunction const hash_function,
+    const unsigned *const __restrict__ join_attr_data,
+    const unsigned *const __restrict__ payload_attr_data,
+    uint64_t const data_length,
+    JoinPair<unsigned, unsigned> *const __restrict__ output,
+    uint64_t const output_length, uint64_t *const __restrict__ output_written) {
+  return cpu_ht_probe_materialize_chaining(
+      hash_table, hash_table_entries, hash_function, join_attr_data,
+      payload_attr_data, data_length, output, output_length, output_written);
+}
+
+extern "C" uint64_t cpu_ht_probe_materialize_chaining_uint64(
+    HtEntry<unsigned long long, unsigned long long> const *const __restrict__
+        hash_table,
+    uint64_t const hash_table_entries, HashFunction const hash_function,
+    const unsigned long long *const __restrict__ join_attr_data,
+    const unsigned long long *const __restrict__ payload_attr_data,
+    uint64_t const data_length,
+    JoinPair<unsigned long long, unsigned long long> *const __restrict__ output,
+    uint64_t const output_length, uint64_t *const __restrict__ output_written) {
+  return cpu_ht_probe_materialize_chaining(
+      hash_table, hash_table_entries, hash_function, join_attr_data,
+      payload_attr_data, data_length, output, output_length, output_written);
+}
+
+extern "C" uint64_t cpu_ht_probe_materialize_chaining_composite(
+    HtEntry<CompositeKey, CompositeKey> const *const __restrict__ hash_table,
+    uint64_t const hash_table_entries, HashFunction const hash_function,
+    const CompositeKey *const __restrict__ join_attr_data,
+    const CompositeKey *const __restrict__ payload_attr_data,
+    uint64_t const data_length,
+    JoinPair<CompositeKey, CompositeKey> *const __restrict__ output,
+    uint64_t const output_length, uint64_t *const __restrict__ output_written) {
+  return cpu_ht_probe_materialize_chaining(
+      hash_table, hash_table_entries, hash_function, join_attr_data,
+      payload_attr_data, data_length, output, output_length, output_written);
+}
+
+extern "C" uint64_t cpu_ht_probe_materialize_chaining_int32_int64(
+    HtEntry<int, long long> const *const __restrict__ hash_table,
+    uint64_t const hash_table_entries, HashFunction const hash_function,
+    const int *const __restrict__ join_attr_data,
+    const long long *const __restrict__ payload_attr_data,
+    uint64_t const data_length,
+    JoinPair<long long, long long> *const __restrict__ output,
+    uint64_t const output_length, uint64_t *const __restrict__ output_written) {
+  return cpu_ht_probe_materialize_chaining(
+      hash_table, hash_table_entries, hash_function, join_attr_data,
+      payload_attr_data, data_length, output, output_length, output_written);
+}
+
+extern "C" uint64_t cpu_ht_probe_materialize_chaining_int64_int32(
+    HtEntry<long long, int> const *const __restrict__ hash_table,
+    uint64_t const hash_table_entries, HashFunction const hash_function,
+    const long long *const __restrict__ join_attr_data,
+    const int *const __restrict__ payload_attr_data, uint64_t const data_length,
+    JoinPair<int, int> *const __restrict__ output, uint64_t const output_length,
+    uint64_t *const __restrict__ output_written) {
+  return cpu_ht_probe_materialize_chaining(
+      hash_table, hash_table_entries, hash_function, join_attr_data,
+      payload_attr_data, data_length, output, output_length, output_written);
+}
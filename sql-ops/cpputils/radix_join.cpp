@@ -0,0 +1,378 @@
+// Copyright 2022 Clemens Lutz
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#define CUDA_MODIFIER
+#include <gpu_common.h>
+
+#include <cstdint>
+
+// Arguments to the join functions.
+//
+// The aggregate functions use `aggregation_result`. The materialize functions
+// use the output and cursor fields instead. Both count the build-side keys
+// that don't fit into the hash table in `rejected_keys`.
+//
+// Note that the struct's layout must be kept in sync with its counterpart in
+// Rust.
+struct CpuRadixJoinArgs {
+  void const *const build_rel;
+  uint64_t const *const build_rel_partition_offsets;
+  void const *const probe_rel;
+  uint64_t const *const probe_rel_partition_offsets;
+  void *const hash_table;
+  uint64_t const build_rel_padded_length;
+  uint64_t const probe_rel_padded_length;
+  uint32_t const build_rel_padding_length;
+  uint32_t const probe_rel_padding_length;
+  uint32_t const build_rel_chunks;
+  uint32_t const probe_rel_chunks;
+  uint32_t const radix_bits;
+  uint32_t const ignore_bits;
+  HashFunction const hash_function;
+  uint32_t const ht_entries;
+  uint32_t const ht_buckets;
+  int64_t *const aggregation_result;
+  void *const output;
+  uint64_t const output_length;
+  uint64_t *const output_written;
+  uint32_t const start_partition;
+  uint64_t const start_offset;
+  uint32_t *const next_partition;
+  uint64_t *const next_offset;
+  uint64_t *const rejected_keys;
+};
+
+// Computes the hash table index of a given key.
+//
+// The index is formed by the bits of the key's hash value remaining after
+// radix partitioning, i.e., the bits above `ignore_bits`.
+template <typename K>
+uint64_t key_to_ht_index(K key, uint64_t mask, uint32_t ignore_bits,
+                         HashFunction hash_function) {
+  return (static_cast<uint64_t>(hash_key(hash_function, key)) & mask) >>
+         ignore_bits;
+}
+
+// Returns the begin and end offsets of a partition within a chunk.
+//
+// The end offset excludes the padding of the next partition. The last
+// partition of the last chunk ends at the end of the padded relation.
+static void partition_bounds(uint64_t const *const __restrict__ offsets,
+                             uint64_t const padded_length,
+                             uint32_t const padding_length,
+                             uint32_t const chunks, uint32_t const fanout,
+                             uint32_t const chunk_id,
+                             uint32_t const partition_id, uint64_t &begin,
+                             uint64_t &end) {
+  uint64_t const ofi =
+      static_cast<uint64_t>(chunk_id) * fanout + partition_id;
+  begin = offsets[ofi];
+  end = (ofi + 1U < static_cast<uint64_t>(chunks) * fanout)
+            ? offsets[ofi + 1U] - padding_length
+            : padded_length;
+}
+
+// Perfect hashing in a cache-resident hash table.
+//
+// Assumes that the build-side keys are unique and dense.
+template <typename K, typename V>
+class PerfectHashTable {
+ public:
+  explicit PerfectHashTable(CpuRadixJoinArgs const &args)
+      : table(static_cast<HtEntry<K, V> *>(args.hash_table)),
+        entries(args.ht_entries),
+        mask(~static_cast<uint64_t>((1ULL << args.ignore_bits) - 1U)),
+        ignore_bits(args.ignore_bits),
+        hash_function(args.hash_function) {}
+
+  void clear() {
+    for (uint32_t i = 0; i < entries; ++i) {
+      table[i] = {null_key<K>(), 0};
+    }
+  }
+
+  // Returns false if the key's index lies outside of the hash table.
+  bool insert(K key, V value) {
+    auto index = key_to_ht_index(key, mask, ignore_bits, hash_function);
+
+    if (index >= entries) {
+      return false;
+    }
+
+    table[index] = {key, value};
+    return true;
+  }
+
+  // Calls `f` with the value of each entry that matches `key`.
+  template <typename F>
+  void for_each_match(K key, F f) const {
+    auto index = key_to_ht_index(key, mask, ignore_bits, hash_function);
+
+    if (index < entries && table[index].key == key) {
+      f(table[index].value);
+    }
+  }
+
+ private:
+  HtEntry<K, V> *const __restrict__ table;
+  uint32_t const entries;
+  uint64_t const mask;
+  uint32_t const ignore_bits;
+  HashFunction const hash_function;
+};
+
+// Bucket chaining in a cache-resident hash table.
+//
+// The hash table is laid out as separate arrays of keys, values, bucket heads,
+// and links.
+template <typename K, typename V>
+class ChainingHashTable {
+ public:
+  explicit ChainingHashTable(CpuRadixJoinArgs const &args)
+      : keys(static_cast<K *>(args.hash_table)),
+        values(reinterpret_cast<V *>(&keys[args.ht_entries])),
+        heads(reinterpret_cast<uint32_t *>(&values[args.ht_entries])),
+        links(&heads[args.ht_buckets]),
+        entries(args.ht_entries),
+        buckets(args.ht_buckets),
+        log2_buckets(log2_floor_power_of_two(args.ht_buckets)),
+        mask(~static_cast<uint64_t>((1ULL << args.ignore_bits) - 1U)),
+        ignore_bits(args.ignore_bits),
+        hash_function(args.hash_function),
+        next_entry(0) {}
+
+  void clear() {
+    for (uint32_t i = 0; i < buckets; ++i) {
+      heads[i] = tail;
+    }
+    next_entry = 0;
+  }
+
+  // Returns false if the hash table is full.
+  bool insert(K key, V value) {
+    if (next_entry >= entries) {
+      return false;
+    }
+
+    keys[next_entry] = key;
+    values[next_entry] = value;
+
+    auto bucket = bucket_of(key);
+    links[next_entry] = heads[bucket];
+    heads[bucket] = next_entry;
+
+    ++next_entry;
+    return true;
+  }
+
+  // Calls `f` with the value of each entry that matches `key`.
+  template <typename F>
+  void for_each_match(K key, F f) const {
+    for (uint32_t e = heads[bucket_of(key)]; e != tail; e = links[e]) {
+      if (keys[e] == key) {
+        f(values[e]);
+      }
+    }
+  }
+
+ private:
+  static constexpr uint32_t tail = UINT32_MAX;
+
+  K bucket_of(K key) const {
+    auto index = key_to_ht_index(key, mask, ignore_bits, hash_function);
+    return hash<K>(static_cast<K>(index), log2_buckets);
+  }
+
+  K *const __restrict__ keys;
+  V *const __restrict__ values;
+  uint32_t *const __restrict__ heads;
+  uint32_t *const __restrict__ links;
+  uint32_t const entries;
+  uint32_t const buckets;
+  unsigned int const log2_buckets;
+  uint64_t const mask;
+  uint32_t const ignore_bits;
+  HashFunction const hash_function;
+  uint32_t next_entry;
+};
+
+template <typename K, typename V>
+constexpr uint32_t ChainingHashTable<K, V>::tail;
+
+// Inserts all build-side tuples of a partition into the hash table.
+//
+// Returns the number of tuples that the hash table rejected.
+template <typename K, typename PI, typename HT>
+uint64_t build_partition(CpuRadixJoinArgs const &args, HT &hash_table,
+                         uint32_t const fanout, uint32_t const partition_id) {
+  auto build_rel = static_cast<Tuple<K, PI> const *>(args.build_rel);
+  uint64_t rejected = 0;
+
+  hash_table.clear();
+
+  for (uint32_t c = 0; c < args.build_rel_chunks; ++c) {
+    uint64_t begin = 0;
+    uint64_t end = 0;
+    partition_bounds(args.build_rel_partition_offsets,
+                     args.build_rel_padded_length,
+                     args.build_rel_padding_length, args.build_rel_chunks,
+                     fanout, c, partition_id, begin, end);
+
+    for (uint64_t i = begin; i < end; ++i) {
+      Tuple<K, PI> tuple = build_rel[i];
+      if (!hash_table.insert(tuple.key, tuple.value)) {
+        ++rejected;
+      }
+    }
+  }
+
+  return rejected;
+}
+
+// Joins all partitions and sums up the probe-side payloads.
+//
+// See the Rust module for details.
+template <typename K, typename PI, typename PO, typename HT>
+void cpu_radix_join_aggregate(CpuRadixJoinArgs &args) {
+  const uint32_t fanout = 1U << args.radix_bits;
+  auto probe_rel = static_cast<Tuple<K, PO> const *>(args.probe_rel);
+
+  HT hash_table(args);
+  int64_t sum = 0;
+  uint64_t rejected = 0;
+
+  for (uint32_t p = 0; p < fanout; ++p) {
+    rejected += build_partition<K, PI>(args, hash_table, fanout, p);
+
+    for (uint32_t c = 0; c < args.probe_rel_chunks; ++c) {
+      uint64_t begin = 0;
+      uint64_t end = 0;
+      partition_bounds(args.probe_rel_partition_offsets,
+                       args.probe_rel_padded_length,
+                       args.probe_rel_padding_length, args.probe_rel_chunks,
+                       fanout, c, p, begin, end);
+
+      for (uint64_t i = begin; i < end; ++i) {
+        Tuple<K, PO> tuple = probe_rel[i];
+        hash_table.for_each_match(tuple.key,
+                                  [&](PI) { sum += tuple.value; });
+      }
+    }
+  }
+
+  *args.aggregation_result += sum;
+  *args.rejected_keys = rejected;
+}
+
+// Joins the partitions and writes the matching payload pairs to the output.
+//
+// Resumes at `start_partition` and `start_offset`, which is an offset into the
+// padded probe relation. Stops before the first probe tuple for which not all
+// matches fit into the output, and returns the position in `next_partition`
+// and `next_offset`. After the last partition, `next_partition` is set to the
+// fanout.
+template <typename K, typename PI, typename PO, typename HT>
+void cpu_radix_join_materialize(CpuRadixJoinArgs &args) {
+  const uint32_t fanout = 1U << args.radix_bits;
+  auto probe_rel = static_cast<Tuple<K, PO> const *>(args.probe_rel);
+  auto output = static_cast<JoinPair<PI, PO> *>(args.output);
+
+  HT hash_table(args);
+  uint64_t written = 0;
+  uint64_t offset = args.start_offset;
+  uint64_t rejected = 0;
+
+  for (uint32_t p = args.start_partition; p < fanout; ++p, offset = 0) {
+    rejected += build_partition<K, PI>(args, hash_table, fanout, p);
+
+    for (uint32_t c = 0; c < args.probe_rel_chunks; ++c) {
+      uint64_t begin = 0;
+      uint64_t end = 0;
+      partition_bounds(args.probe_rel_partition_offsets,
+                       args.probe_rel_padded_length,
+                       args.probe_rel_padding_length, args.probe_rel_chunks,
+                       fanout, c, p, begin, end);
+
+      for (uint64_t i = (begin < offset) ? offset : begin; i < end; ++i) {
+        Tuple<K, PO> tuple = probe_rel[i];
+
+        uint64_t matches = 0;
+        hash_table.for_each_match(tuple.key, [&](PI) { ++matches; });
+
+        if (written + matches > args.output_length) {
+          *args.next_partition = p;
+          *args.next_offset = i;
+          *args.output_written = written;
+          *args.rejected_keys = rejected;
+          return;
+        }
+
+        hash_table.for_each_match(tuple.key, [&](PI value) {
+          output[written] = {value, tuple.value};
+          ++written;
+        });
+      }
+    }
+  }
+
+  *args.next_partition = fanout;
+  *args.next_offset = 0;
+  *args.output_written = written;
+  *args.rejected_keys = rejected;
+}
+
+extern "C" void cpu_join_aggregate_perfect_int32_int32_int32(
+    CpuRadixJoinArgs *args) {
+  cpu_radix_join_aggregate<int, int, int, PerfectHashTable<int, int>>(*args);
+}
+
+extern "C" void cpu_join_aggregate_perfect_int64_int64_int64(
+    CpuRadixJoinArgs *args) {
+  cpu_radix_join_aggregate<long long, long long, long long,
+                           PerfectHashTable<long long, long long>>(*args);
+}
+
+extern "C" void cpu_join_aggregate_chaining_int32_int32_int32(
+    CpuRadixJoinArgs *args) {
+  cpu_radix_join_aggregate<int, int, int, ChainingHashTable<int, int>>(*args);
+}
+
+extern "C" void cpu_join_aggregate_chaining_int64_int64_int64(
+    CpuRadixJoinArgs *args) {
+  cpu_radix_join_aggregate<long long, long long, long long,
+                           ChainingHashTable<long long, long long>>(*args);
+}
+
+extern "C" void cpu_join_materialize_perfect_int32_int32_int32(
+    CpuRadixJoinArgs *args) {
+  cpu_radix_join_materialize<int, int, int, PerfectHashTable<int, int>>(*args);
+}
+
+extern "C" void cpu_join_materialize_perfect_int64_int64_int64(
+    CpuRadixJoinArgs *args) {
+  cpu_radix_join_materialize<long long, long long, long long,
+                             PerfectHashTable<long long, long long>>(*args);
+}
+
+extern "C" void cpu_join_materialize_chaining_int32_int32_int32(
+    CpuRadixJoinArgs *args) {
+  cpu_radix_join_materialize<int, int, int, ChainingHashTable<int, int>>(
+      *args);
+}
+
+extern "C" void cpu_join_materialize_chaining_int64_int64_int64(
+    CpuRadixJoinArgs *args) {
+  cpu_radix_join_materialize<long long, long long, long long,
+                             ChainingHashTable<long long, long long>>(*args);
+}
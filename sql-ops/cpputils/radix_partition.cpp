@@ -85,6 +85,18 @@
 
 using namespace std;
 
+// The widest x86-64 SIMD instruction set that the SIMD kernels may use.
+//
+// The kernels detect the CPU features at run-time, and never use an
+// instruction set that the CPU does not support.
+//
+// Note that the enum's values must be kept in sync with its counterpart in
+// Rust.
+enum class X86SimdIsa : unsigned int {
+  Avx2 = 0,
+  Avx512 = 1,
+};
+
 // Arguments to the prefix sum function.
 //
 // Note that the struct's layout must be kept in sync with its counterpart in
@@ -97,6 +109,8 @@ struct PrefixSumArgs {
   uint32_t const padding_length;
   uint32_t const radix_bits;
   uint32_t const ignore_bits;
+  HashFunction const hash_function;
+  X86SimdIsa const simd_isa;
 
   // State
   unsigned int *const __restrict__ tmp_partition_offsets;
@@ -117,6 +131,8 @@ struct RadixPartitionArgs {
   size_t const padding_length;
   uint32_t const radix_bits;
   uint32_t const ignore_bits;
+  HashFunction const hash_function;
+  X86SimdIsa const simd_isa;
   const unsigned long long *const __restrict__ partition_offsets;
 
   // State
@@ -151,9 +167,12 @@ union WriteCombineBuffer {
 } __attribute__((packed));
 
 // Computes the partition ID of a given key.
+//
+// The partition ID is formed by the bits of the key's hash value that are
+// selected by `mask`.
 template <typename T, typename M, typename B>
-M key_to_partition(T key, M mask, B bits) {
-  return (static_cast<M>(key) & mask) >> bits;
+M key_to_partition(T key, M mask, B bits, HashFunction hash_function) {
+  return (static_cast<M>(hash_key(hash_function, key)) & mask) >> bits;
 }
 
 #if defined(__ALTIVEC__)
@@ -163,6 +182,117 @@ vector M key_to_partition_simd(vector T key, M mask, B bits) {
 }
 #endif
 
+#if defined(__x86_64__)
+// Defines the number of keys per batch in the x86-64 SIMD code paths.
+//
+// The partition IDs of a batch are computed with SIMD instructions and stored
+// in a small buffer, because AVX2 lacks scatter instructions and the
+// histogram and SWWC buffer updates are not vectorizable. The buffer size is
+// chosen such that it fits into the L1 cache.
+constexpr size_t X86_SIMD_BATCH_LEN = 256U;
+
+// Computes the partition IDs of a batch of keys with AVX2.
+//
+// The batch length must be a multiple of the vector length. Loads are
+// unaligned, whereas `p_index` must be aligned to 32 bytes.
+template <typename K, typename M>
+__attribute__((target("avx2"))) void key_to_partition_avx2(
+    const K *const __restrict__ keys, size_t const len, M const mask,
+    uint32_t const bits, M *const __restrict__ p_index) {
+  static_assert(sizeof(K) == sizeof(M),
+                "SIMD code path requires equal key and mask sizes");
+  static_assert(sizeof(K) == 4 || sizeof(K) == 8,
+                "SIMD code path requires 4- or 8-byte keys");
+
+  constexpr size_t vec_len = sizeof(__m256i) / sizeof(K);
+
+  const __m256i mask_avx =
+      sizeof(M) == 4 ? _mm256_set1_epi32(static_cast<int>(mask))
+                     : _mm256_set1_epi64x(static_cast<long long>(mask));
+  const __m128i bits_avx = _mm_cvtsi32_si128(static_cast<int>(bits));
+
+  for (size_t i = 0; i < len; i += vec_len) {
+    __m256i key =
+        _mm256_loadu_si256(reinterpret_cast<const __m256i *>(&keys[i]));
+    __m256i masked = _mm256_and_si256(key, mask_avx);
+    __m256i p = sizeof(M) == 4 ? _mm256_srl_epi32(masked, bits_avx)
+                               : _mm256_srl_epi64(masked, bits_avx);
+    _mm256_store_si256(reinterpret_cast<__m256i *>(&p_index[i]), p);
+  }
+}
+
+// Computes the partition IDs of a batch of keys with AVX-512.
+//
+// The batch length must be a multiple of the vector length. Loads are
+// unaligned, whereas `p_index` must be aligned to 64 bytes.
+template <typename K, typename M>
+__attribute__((target("avx512f"))) void key_to_partition_avx512(
+    const K *const __restrict__ keys, size_t const len, M const mask,
+    uint32_t const bits, M *const __restrict__ p_index) {
+  static_assert(sizeof(K) == sizeof(M),
+                "SIMD code path requires equal key and mask sizes");
+  static_assert(sizeof(K) == 4 || sizeof(K) == 8,
+                "SIMD code path requires 4- or 8-byte keys");
+
+  constexpr size_t vec_len = sizeof(__m512i) / sizeof(K);
+
+  const __m512i mask_avx =
+      sizeof(M) == 4 ? _mm512_set1_epi32(static_cast<int>(mask))
+                     : _mm512_set1_epi64(static_cast<long long>(mask));
+  const __m128i bits_avx = _mm_cvtsi32_si128(static_cast<int>(bits));
+
+  for (size_t i = 0; i < len; i += vec_len) {
+    __m512i key = _mm512_loadu_si512(static_cast<const void *>(&keys[i]));
+    __m512i masked = _mm512_and_si512(key, mask_avx);
+    // Note: the zero-masking variants avoid a spurious uninitialized warning
+    // in GCC's `_mm512_srl_*` intrinsics
+    __m512i p =
+        sizeof(M) == 4
+            ? _mm512_maskz_srl_epi32(static_cast<__mmask16>(~0U), masked,
+                                     bits_avx)
+            : _mm512_maskz_srl_epi64(static_cast<__mmask8>(~0U), masked,
+                                     bits_avx);
+    _mm512_store_si512(static_cast<void *>(&p_index[i]), p);
+  }
+}
+
+// Computes the partition IDs of a batch of keys without SIMD instructions.
+//
+// Serves as a fallback for CPUs that support neither AVX2 nor AVX-512.
+template <typename K, typename M>
+void key_to_partition_batch(const K *const __restrict__ keys, size_t const len,
+                            M const mask, uint32_t const bits,
+                            M *const __restrict__ p_index) {
+  for (size_t i = 0; i < len; ++i) {
+    p_index[i] = key_to_partition(keys[i], mask, bits, HashFunction::Identity);
+  }
+}
+
+template <typename K, typename M>
+using KeyToPartitionBatchFn = void (*)(const K *const __restrict__,
+                                       size_t const, M const, uint32_t const,
+                                       M *const __restrict__);
+
+// Selects the widest SIMD variant that the CPU supports at run-time, up to
+// `max_isa`.
+//
+// Detecting the CPU features at run-time instead of at compile-time allows us
+// to ship a single binary to CPUs with and without AVX-512. Limiting the
+// instruction set allows us to test the AVX2 variant on AVX-512 CPUs.
+template <typename K, typename M>
+KeyToPartitionBatchFn<K, M> select_key_to_partition_batch(X86SimdIsa max_isa) {
+  __builtin_cpu_init();
+
+  if (max_isa == X86SimdIsa::Avx512 && __builtin_cpu_supports("avx512f")) {
+    return key_to_partition_avx512<K, M>;
+  } else if (__builtin_cpu_supports("avx2")) {
+    return key_to_partition_avx2<K, M>;
+  } else {
+    return key_to_partition_batch<K, M>;
+  }
+}
+#endif
+
 // Flushes a SWWC buffer from cache to memory.
 //
 // If possible, uses non-temporal SIMD writes, that require vector-length
@@ -222,6 +352,33 @@ void flush_buffer(void *const __restrict__ dst,
 #endif
 }
 
+// Computes the SWWC buffer slot of a tuple in the partitioned relation.
+//
+// The buffer slot is derived from the tuple's byte address, so that a full
+// buffer maps to an `SWWC_BUFFER_SIZE`-aligned block of the relation. The
+// relation is only guaranteed to be aligned to the tuple's alignment, which
+// can be smaller than the tuple's size (e.g., for composite keys).
+template <typename T>
+size_t swwc_buffer_slot(const T *const partitioned_relation, size_t slot) {
+  return (reinterpret_cast<uintptr_t>(partitioned_relation + slot) %
+          SWWC_BUFFER_SIZE) /
+         sizeof(T);
+}
+
+// Flushes a full SWWC buffer to the partitioned relation.
+//
+// Non-temporal writes require the destination to be aligned to
+// `SWWC_BUFFER_SIZE`. This is not the case if tuples straddle cache lines, in
+// which case the buffer is copied with regular writes.
+void flush_full_buffer(void *const __restrict__ dst,
+                       const void *const __restrict__ src) {
+  if (reinterpret_cast<uintptr_t>(dst) % SWWC_BUFFER_SIZE == 0) {
+    flush_buffer(dst, src);
+  } else {
+    memcpy(dst, src, SWWC_BUFFER_SIZE);
+  }
+}
+
 // Chunked histogram and offset computation.
 //
 // See the Rust module for details.
@@ -233,7 +390,7 @@ void cpu_chunked_prefix_sum(PrefixSumArgs &args, uint32_t const chunk_id,
 #endif
 
   const size_t fanout = 1UL << args.radix_bits;
-  const M mask = static_cast<M>(fanout - 1UL);
+  const M mask = static_cast<M>(fanout - 1UL) << args.ignore_bits;
 
   auto partition_attr =
       static_cast<const K *const __restrict__>(args.partition_attr);
@@ -247,7 +404,8 @@ void cpu_chunked_prefix_sum(PrefixSumArgs &args, uint32_t const chunk_id,
 #pragma GCC unroll 16
   for (size_t i = 0; i < args.data_length; ++i) {
     auto key = partition_attr[i];
-    M p_index = key_to_partition(key, mask, 0);
+    M p_index =
+        key_to_partition(key, mask, args.ignore_bits, args.hash_function);
     args.tmp_partition_offsets[p_index] += 1;
   }
 
@@ -272,7 +430,13 @@ void cpu_chunked_prefix_sum(PrefixSumArgs &args, uint32_t const chunk_id,
 // See the Rust module for details.
 template <typename K, typename M>
 void cpu_chunked_prefix_sum_simd(PrefixSumArgs &args, uint32_t const chunk_id,
-                                 uint32_t const /* num_chunks */) {
+                                 uint32_t const num_chunks) {
+  // The SIMD code path only implements the identity hash function
+  if (args.hash_function != HashFunction::Identity) {
+    cpu_chunked_prefix_sum<K, M>(args, chunk_id, num_chunks);
+    return;
+  }
+
   // Disable strided prefetch and set maximum prefetch depth
   __mtspr(PPC_DSCR, PPC_TUNE_DSCR);
 
@@ -280,7 +444,7 @@ void cpu_chunked_prefix_sum_simd(PrefixSumArgs &args, uint32_t const chunk_id,
   constexpr size_t vec_len = sizeof(vector int) / sizeof(K);
   constexpr size_t unroll_len = 4U;
   const size_t fanout = 1UL << args.radix_bits;
-  const M mask = static_cast<M>(fanout - 1UL);
+  const M mask = static_cast<M>(fanout - 1UL) << args.ignore_bits;
 
   // Performance drops when array is larger than 2 KiB. Might be that L1 cache
   // set prediction (SETP) is most effective when only one cache slot per set
@@ -295,7 +459,8 @@ void cpu_chunked_prefix_sum_simd(PrefixSumArgs &args, uint32_t const chunk_id,
          "128-bit intrinsics require 16-byte alignment");
 
   const vector M mask_vsx = vec_splats(mask);
-  const vector M ignore_bits_vsx = vec_splats(static_cast<M>(0U));
+  const vector M ignore_bits_vsx =
+      vec_splats(static_cast<M>(args.ignore_bits));
   size_t i;
 
   // Ensure counters are all zeroed
@@ -383,7 +548,104 @@ void cpu_chunked_prefix_sum_simd(PrefixSumArgs &args, uint32_t const chunk_id,
   }
   for (; i < args.data_length; ++i) {
     auto key = partition_attr[i];
-    auto p_index = key_to_partition(key, mask, 0);
+    auto p_index =
+        key_to_partition(key, mask, args.ignore_bits, args.hash_function);
+    args.tmp_partition_offsets[p_index] += 1;
+  }
+
+  // Compute offsets with exclusive prefix sum
+  size_t partitioned_data_offset =
+      (args.canonical_chunk_length + args.padding_length * fanout) * chunk_id;
+  uint64_t offset = partitioned_data_offset + args.padding_length;
+  for (uint32_t i = 0; i < fanout; ++i) {
+    // Add data offset onto partitions offsets and write out the final offsets
+    // to device memory.
+    args.partition_offsets[i] = offset;
+
+    // Update offset
+    offset += static_cast<uint64_t>(args.tmp_partition_offsets[i]);
+    offset += args.padding_length;
+  }
+}
+#elif defined(__x86_64__)
+// Chunked histogram and offset computation with SIMD optimizations.
+//
+// Computes the partition IDs with AVX2 or AVX-512, depending on the CPU
+// features detected at run-time.
+//
+// See the Rust module for details.
+template <typename K, typename M>
+void cpu_chunked_prefix_sum_simd(PrefixSumArgs &args, uint32_t const chunk_id,
+                                 uint32_t const num_chunks) {
+  // The SIMD code path only implements the identity hash function
+  if (args.hash_function != HashFunction::Identity) {
+    cpu_chunked_prefix_sum<K, M>(args, chunk_id, num_chunks);
+    return;
+  }
+
+  constexpr size_t batch_len = X86_SIMD_BATCH_LEN;
+  constexpr size_t unroll_len = 4U;
+  const size_t fanout = 1UL << args.radix_bits;
+  const M mask = static_cast<M>(fanout - 1UL) << args.ignore_bits;
+
+  // Replicate the histogram to avoid read-after-write hazards if the
+  // replicas fit into the L1 cache.
+  constexpr size_t target_bytes = 4096;
+
+  auto partition_attr =
+      static_cast<const K *const __restrict__>(args.partition_attr);
+  auto key_to_partition_fn = select_key_to_partition_batch<K, M>(args.simd_isa);
+
+  alignas(64) M p_index[batch_len];
+  size_t i;
+
+  // Ensure counters are all zeroed
+  for (size_t i = 0; i < fanout * unroll_len; ++i) {
+    args.tmp_partition_offsets[i] = 0;
+  }
+
+  // Compute local histograms per partition
+  i = 0;
+  if (sizeof(args.tmp_partition_offsets[0]) * fanout * unroll_len <=
+      target_bytes) {
+    for (; i + batch_len <= args.data_length; i += batch_len) {
+      key_to_partition_fn(&partition_attr[i], batch_len, mask,
+                          args.ignore_bits, p_index);
+
+#pragma GCC unroll 4
+      for (size_t j = 0; j < batch_len; ++j) {
+        args.tmp_partition_offsets[p_index[j] * unroll_len + j % unroll_len] +=
+            1;
+      }
+    }
+
+    // Combine sub-histograms
+    for (uint32_t i = 0; i < fanout; ++i) {
+      for (uint32_t u = 1; u < unroll_len; ++u) {
+        args.tmp_partition_offsets[i * unroll_len] +=
+            args.tmp_partition_offsets[i * unroll_len + u];
+      }
+    }
+    // Transpose into the non-replicated layout
+    for (uint32_t i = 1; i < fanout; ++i) {
+      args.tmp_partition_offsets[i] =
+          args.tmp_partition_offsets[i * unroll_len];
+    }
+  } else {
+    for (; i + batch_len <= args.data_length; i += batch_len) {
+      key_to_partition_fn(&partition_attr[i], batch_len, mask,
+                          args.ignore_bits, p_index);
+
+#pragma GCC unroll 16
+      for (size_t j = 0; j < batch_len; ++j) {
+        args.tmp_partition_offsets[p_index[j]] += 1;
+      }
+    }
+  }
+  for (; i < args.data_length; ++i) {
+    auto key = partition_attr[i];
+    auto p_index =
+        key_to_partition(key, mask, args.ignore_bits, args.hash_function);
     args.tmp_partition_offsets[p_index] += 1;
   }
 
@@ -401,7 +663,7 @@ void cpu_chunked_prefix_sum_simd(PrefixSumArgs &args, uint32_t const chunk_id,
     offset += args.padding_length;
   }
 }
-#endif /* defined(__ALTIVEC__) */
+#endif /* defined(__ALTIVEC__) || defined(__x86_64__) */
 
 // Chunked radix partitioning.
 //
@@ -421,7 +683,7 @@ void cpu_chunked_radix_partition(RadixPartitionArgs &args) {
   auto tmp_partition_offsets = args.tmp_partition_offsets;
 
   const size_t fanout = 1UL << args.radix_bits;
-  const M mask = static_cast<M>(fanout - 1UL);
+  const M mask = static_cast<M>(fanout - 1UL) << args.ignore_bits;
   const size_t partitioned_data_offset =
       args.partition_offsets[0] - args.padding_length;
 
@@ -438,7 +700,8 @@ void cpu_chunked_radix_partition(RadixPartitionArgs &args) {
     tuple.key = join_attr_data[i];
     tuple.value = payload_attr_data[i];
 
-    M p_index = key_to_partition(tuple.key, mask, 0);
+    M p_index =
+        key_to_partition(tuple.key, mask, args.ignore_bits, args.hash_function);
     auto &offset = tmp_partition_offsets[p_index];
     partitioned_relation[offset] = tuple;
     offset += 1;
@@ -463,10 +726,7 @@ void buffer_tuple(Tuple<K, V> *const __restrict__ partitioned_relation,
 
   // align the flush destination to tuples_per_buffer (i.e., align to a cache
   // line)
-  size_t buffer_slot =
-      (reinterpret_cast<size_t>(partitioned_relation) / sizeof(Tuple<K, V>) +
-       slot) %
-      tuples_per_buffer;
+  size_t buffer_slot = swwc_buffer_slot(partitioned_relation, slot);
 
   // `buffer.meta.slot` is overwritten on buffer_slot == (tuples_per_buffer -
   // 1), and restored after the buffer flush.
@@ -476,8 +736,8 @@ void buffer_tuple(Tuple<K, V> *const __restrict__ partitioned_relation,
   // Can occur on partially filled buffer due to cache-line alignment,
   // because first output slot might not be at offset % tuples_per_buffer == 0
   if (buffer_slot + 1 == tuples_per_buffer) {
-    flush_buffer(partitioned_relation + (slot + 1) - tuples_per_buffer,
-                 buffer.tuples.data);
+    flush_full_buffer(partitioned_relation + (slot + 1) - tuples_per_buffer,
+                      buffer.tuples.data);
   }
 
   // Restore `buffer.meta.slot` after overwriting it above, and increment its
@@ -513,7 +773,7 @@ void cpu_chunked_radix_partition_swwc(RadixPartitionArgs &args) {
       args.write_combine_buffer);
 
   const size_t fanout = 1UL << args.radix_bits;
-  const M mask = static_cast<M>(fanout - 1UL);
+  const M mask = static_cast<M>(fanout - 1UL) << args.ignore_bits;
   const size_t partitioned_data_offset =
       args.partition_offsets[0] - args.padding_length;
 
@@ -537,7 +797,8 @@ void cpu_chunked_radix_partition_swwc(RadixPartitionArgs &args) {
     K key = join_attr_data[i];
     V pay = payload_attr_data[i];
 
-    M p_index = key_to_partition(key, mask, 0);
+    M p_index =
+        key_to_partition(key, mask, args.ignore_bits, args.hash_function);
 
     buffer_tuple<K, V, M>(partitioned_relation, buffers, p_index, key, pay);
   }
@@ -545,10 +806,7 @@ void cpu_chunked_radix_partition_swwc(RadixPartitionArgs &args) {
   // Flush remainders of all buffers.
   for (size_t i = 0; i < fanout; ++i) {
     size_t slot = buffers[i].meta.slot;
-    size_t remaining =
-        (reinterpret_cast<size_t>(partitioned_relation) / sizeof(Tuple<K, V>) +
-         slot) %
-        tuples_per_buffer;
+    size_t remaining = swwc_buffer_slot(partitioned_relation, slot);
 
     for (size_t j = slot - remaining, k = 0; k < remaining; ++j, ++k) {
       partitioned_relation[j] = buffers[i].tuples.data[k];
@@ -563,6 +821,12 @@ void cpu_chunked_radix_partition_swwc(RadixPartitionArgs &args) {
 // See the Rust module for details.
 template <typename K, typename V, typename M>
 void cpu_chunked_radix_partition_swwc_simd(RadixPartitionArgs &args) {
+  // The SIMD code path only implements the identity hash function
+  if (args.hash_function != HashFunction::Identity) {
+    cpu_chunked_radix_partition_swwc<K, V, M>(args);
+    return;
+  }
+
   __mtspr(PPC_DSCR, PPC_TUNE_DSCR);
 
   constexpr size_t tuples_per_buffer =
@@ -593,12 +857,13 @@ void cpu_chunked_radix_partition_swwc_simd(RadixPartitionArgs &args) {
       "Payload column should be aligned to ALIGN_BYTES for best performance");
 
   const size_t fanout = 1UL << args.radix_bits;
-  const M mask = static_cast<M>(fanout - 1UL);
+  const M mask = static_cast<M>(fanout - 1UL) << args.ignore_bits;
   const size_t partitioned_data_offset =
       args.partition_offsets[0] - args.padding_length;
 
   const vector M mask_vsx = vec_splats(mask);
-  const vector M ignore_bits_vsx = vec_splats(static_cast<M>(0U));
+  const vector M ignore_bits_vsx =
+      vec_splats(static_cast<M>(args.ignore_bits));
   size_t i;
 
   // Initialize the buffers with NULL so that we don't write out uninitialized
@@ -658,24 +923,111 @@ void cpu_chunked_radix_partition_swwc_simd(RadixPartitionArgs &args) {
     K key = join_attr_data[i];
     V payload = payload_attr_data[i];
 
-    M p_index = key_to_partition(key, mask, 0);
+    M p_index =
+        key_to_partition(key, mask, args.ignore_bits, args.hash_function);
+    buffer_tuple<K, V, M>(partitioned_relation, buffers, p_index, key, payload);
+  }
+
+  // Flush remainders of all buffers.
+  for (size_t i = 0; i < fanout; ++i) {
+    size_t slot = buffers[i].meta.slot;
+    size_t remaining = swwc_buffer_slot(partitioned_relation, slot);
+
+    for (size_t j = slot - remaining, k = 0; k < remaining; ++j, ++k) {
+      partitioned_relation[j] = buffers[i].tuples.data[k];
+    }
+  }
+}
+#elif defined(__x86_64__)
+// Chunked radix partitioning with software write-combining and SIMD
+// optimizations.
+//
+// Computes the partition IDs with AVX2 or AVX-512, depending on the CPU
+// features detected at run-time.
+//
+// See the Rust module for details.
+template <typename K, typename V, typename M>
+void cpu_chunked_radix_partition_swwc_simd(RadixPartitionArgs &args) {
+  // The SIMD code path only implements the identity hash function
+  if (args.hash_function != HashFunction::Identity) {
+    cpu_chunked_radix_partition_swwc<K, V, M>(args);
+    return;
+  }
+
+  constexpr size_t tuples_per_buffer =
+      WriteCombineBuffer<Tuple<K, V>, SWWC_BUFFER_SIZE>::tuples_per_buffer();
+  constexpr size_t batch_len = X86_SIMD_BATCH_LEN;
+
+  assert(reinterpret_cast<size_t>(args.write_combine_buffer) % 64UL == 0 &&
+         "512-bit intrinsics require 64-byte alignment");
+  assert(args.padding_length % tuples_per_buffer == 0 &&
+         "Padding must be a multiple of the buffer length");
+
+  auto join_attr_data =
+      static_cast<const K *const __restrict__>(args.join_attr_data);
+  auto payload_attr_data =
+      static_cast<const V *const __restrict__>(args.payload_attr_data);
+  auto partitioned_relation =
+      static_cast<Tuple<K, V> *const __restrict__>(args.partitioned_relation);
+  auto buffers = static_cast<
+      WriteCombineBuffer<Tuple<K, V>, SWWC_BUFFER_SIZE> *const __restrict__>(
+      args.write_combine_buffer);
+  auto key_to_partition_fn = select_key_to_partition_batch<K, M>(args.simd_isa);
+
+  const size_t fanout = 1UL << args.radix_bits;
+  const M mask = static_cast<M>(fanout - 1UL) << args.ignore_bits;
+  const size_t partitioned_data_offset =
+      args.partition_offsets[0] - args.padding_length;
+
+  alignas(64) M p_index[batch_len];
+  size_t i;
+
+  // Initialize the buffers with NULL so that we don't write out uninitialized
+  // data
+  for (size_t p = 0; p < fanout; ++p) {
+    for (size_t i = 0; i < tuples_per_buffer; ++i) {
+      buffers[p].tuples.data[i].key = null_key<K>();
+      buffers[p].tuples.data[i].value = {};
+    }
+  }
+
+  // Load partition offsets
+  for (size_t i = 0; i < fanout; ++i) {
+    buffers[i].meta.slot = args.partition_offsets[i] - partitioned_data_offset;
+  }
+
+  // Partition into software write combine buffers
+  i = 0;
+  for (; i + batch_len <= args.data_length; i += batch_len) {
+    key_to_partition_fn(&join_attr_data[i], batch_len, mask, args.ignore_bits,
+                        p_index);
+
+#pragma GCC unroll 4
+    for (size_t j = 0; j < batch_len; ++j) {
+      buffer_tuple<K, V, M>(partitioned_relation, buffers, p_index[j],
+                            join_attr_data[i + j], payload_attr_data[i + j]);
+    }
+  }
+  for (; i < args.data_length; ++i) {
+    K key = join_attr_data[i];
+    V payload = payload_attr_data[i];
+
+    M p_index =
+        key_to_partition(key, mask, args.ignore_bits, args.hash_function);
     buffer_tuple<K, V, M>(partitioned_relation, buffers, p_index, key, payload);
   }
 
   // Flush remainders of all buffers.
   for (size_t i = 0; i < fanout; ++i) {
     size_t slot = buffers[i].meta.slot;
-    size_t remaining =
-        (reinterpret_cast<size_t>(partitioned_relation) / sizeof(Tuple<K, V>) +
-         slot) %
-        tuples_per_buffer;
+    size_t remaining = swwc_buffer_slot(partitioned_relation, slot);
 
     for (size_t j = slot - remaining, k = 0; k < remaining; ++j, ++k) {
       partitioned_relation[j] = buffers[i].tuples.data[k];
     }
   }
 }
-#endif /* defined(__ALTIVEC__) */
+#endif /* defined(__ALTIVEC__) || defined(__x86_64__) */
 
 // Exports the the size of all SWWC buffers.
 extern "C" size_t cpu_swwc_buffer_bytes() { return SWWC_BUFFER_SIZE; }
@@ -695,7 +1047,30 @@ extern "C" void cpu_chunked_prefix_sum_int64(PrefixSumArgs *const args,
                                                         num_chunks);
 }
 
-#if defined(__ALTIVEC__)
+// Exports the prefix sum function for 4-byte unsigned keys.
+extern "C" void cpu_chunked_prefix_sum_uint32(PrefixSumArgs *const args,
+                                              uint32_t const chunk_id,
+                                              uint32_t const num_chunks) {
+  cpu_chunked_prefix_sum<unsigned, unsigned>(*args, chunk_id, num_chunks);
+}
+
+// Exports the prefix sum function for 8-byte unsigned keys.
+extern "C" void cpu_chunked_prefix_sum_uint64(PrefixSumArgs *const args,
+                                              uint32_t const chunk_id,
+                                              uint32_t const num_chunks) {
+  cpu_chunked_prefix_sum<unsigned long long, unsigned long long>(
+      *args, chunk_id, num_chunks);
+}
+
+// Exports the prefix sum function for 16-byte composite keys.
+extern "C" void cpu_chunked_prefix_sum_composite(PrefixSumArgs *const args,
+                                                 uint32_t const chunk_id,
+                                                 uint32_t const num_chunks) {
+  cpu_chunked_prefix_sum<CompositeKey, unsigned long long>(*args, chunk_id,
+                                                           num_chunks);
+}
+
+#if defined(__ALTIVEC__) || defined(__x86_64__)
 // Exports the SIMD prefix sum function for 4-byte keys.
 extern "C" void cpu_chunked_prefix_sum_simd_int32(PrefixSumArgs *const args,
                                                   uint32_t const chunk_id,
@@ -710,6 +1085,32 @@ extern "C" void cpu_chunked_prefix_sum_simd_int64(PrefixSumArgs *const args,
   cpu_chunked_prefix_sum_simd<long long, unsigned long long>(*args, chunk_id,
                                                              num_chunks);
 }
+
+// Exports the SIMD prefix sum function for 4-byte unsigned keys.
+extern "C" void cpu_chunked_prefix_sum_simd_uint32(PrefixSumArgs *const args,
+                                                   uint32_t const chunk_id,
+                                                   uint32_t const num_chunks) {
+  cpu_chunked_prefix_sum_simd<unsigned, unsigned>(*args, chunk_id, num_chunks);
+}
+
+// Exports the SIMD prefix sum function for 8-byte unsigned keys.
+extern "C" void cpu_chunked_prefix_sum_simd_uint64(PrefixSumArgs *const args,
+                                                   uint32_t const chunk_id,
+                                                   uint32_t const num_chunks) {
+  cpu_chunked_prefix_sum_simd<unsigned long long, unsigned long long>(
+      *args, chunk_id, num_chunks);
+}
+
+// Exports the SIMD prefix sum function for 16-byte composite keys.
+//
+// Composite keys don't fit the SIMD code path, and fall back to the scalar
+// prefix sum.
+extern "C" void cpu_chunked_prefix_sum_simd_composite(
+    PrefixSumArgs *const args, uint32_t const chunk_id,
+    uint32_t const num_chunks) {
+  cpu_chunked_prefix_sum<CompositeKey, unsigned long long>(*args, chunk_id,
+                                                           num_chunks);
+}
 #else  // define dummy function symbols
 // Exports the SIMD prefix sum function for 4-byte keys.
 extern "C" void cpu_chunked_prefix_sum_simd_int32(PrefixSumArgs *const,
@@ -720,7 +1121,22 @@ extern "C" void cpu_chunked_prefix_sum_simd_int32(PrefixSumArgs *const,
 extern "C" void cpu_chunked_prefix_sum_simd_int64(PrefixSumArgs *const,
                                                   uint32_t const,
                                                   uint32_t const) {}
-#endif /* defined(__ALTIVEC__) */
+
+// Exports the SIMD prefix sum function for 4-byte unsigned keys.
+extern "C" void cpu_chunked_prefix_sum_simd_uint32(PrefixSumArgs *const,
+                                                   uint32_t const,
+                                                   uint32_t const) {}
+
+// Exports the SIMD prefix sum function for 8-byte unsigned keys.
+extern "C" void cpu_chunked_prefix_sum_simd_uint64(PrefixSumArgs *const,
+                                                   uint32_t const,
+                                                   uint32_t const) {}
+
+// Exports the SIMD prefix sum function for 16-byte composite keys.
+extern "C" void cpu_chunked_prefix_sum_simd_composite(PrefixSumArgs *const,
+                                                      uint32_t const,
+                                                      uint32_t const) {}
+#endif /* defined(__ALTIVEC__) || defined(__x86_64__) */
 
 // Exports the partitioning function for 8-byte key/value tuples.
 extern "C" void cpu_chunked_radix_partition_int32_int32(
@@ -734,6 +1150,38 @@ extern "C" void cpu_chunked_radix_partition_int64_int64(
   cpu_chunked_radix_partition<long long, long long, unsigned long long>(*args);
 }
 
+// Exports the partitioning function for 8-byte unsigned key/value tuples.
+extern "C" void cpu_chunked_radix_partition_uint32_uint32(
+    RadixPartitionArgs *args) {
+  cpu_chunked_radix_partition<unsigned, unsigned, unsigned>(*args);
+}
+
+// Exports the partitioning function for 16-byte unsigned key/value tuples.
+extern "C" void cpu_chunked_radix_partition_uint64_uint64(
+    RadixPartitionArgs *args) {
+  cpu_chunked_radix_partition<unsigned long long, unsigned long long,
+                              unsigned long long>(*args);
+}
+
+// Exports the partitioning function for 32-byte composite key/value tuples.
+extern "C" void cpu_chunked_radix_partition_composite_composite(
+    RadixPartitionArgs *args) {
+  cpu_chunked_radix_partition<CompositeKey, CompositeKey, unsigned long long>(
+      *args);
+}
+
+// Exports the partitioning function for 4-byte keys with 8-byte payloads.
+extern "C" void cpu_chunked_radix_partition_int32_int64(
+    RadixPartitionArgs *args) {
+  cpu_chunked_radix_partition<int, long long, unsigned>(*args);
+}
+
+// Exports the partitioning function for 8-byte keys with 4-byte payloads.
+extern "C" void cpu_chunked_radix_partition_int64_int32(
+    RadixPartitionArgs *args) {
+  cpu_chunked_radix_partition<long long, int, unsigned long long>(*args);
+}
+
 // Exports the partitioning function for 8-byte key/value tuples.
 extern "C" void cpu_chunked_radix_partition_swwc_int32_int32(
     RadixPartitionArgs *args) {
@@ -747,7 +1195,39 @@ extern "C" void cpu_chunked_radix_partition_swwc_int64_int64(
       *args);
 }
 
-#if defined(__ALTIVEC__)
+// Exports the partitioning function for 8-byte unsigned key/value tuples.
+extern "C" void cpu_chunked_radix_partition_swwc_uint32_uint32(
+    RadixPartitionArgs *args) {
+  cpu_chunked_radix_partition_swwc<unsigned, unsigned, unsigned>(*args);
+}
+
+// Exports the partitioning function for 16-byte unsigned key/value tuples.
+extern "C" void cpu_chunked_radix_partition_swwc_uint64_uint64(
+    RadixPartitionArgs *args) {
+  cpu_chunked_radix_partition_swwc<unsigned long long, unsigned long long,
+                                   unsigned long long>(*args);
+}
+
+// Exports the partitioning function for 32-byte composite key/value tuples.
+extern "C" void cpu_chunked_radix_partition_swwc_composite_composite(
+    RadixPartitionArgs *args) {
+  cpu_chunked_radix_partition_swwc<CompositeKey, CompositeKey,
+                                   unsigned long long>(*args);
+}
+
+// Exports the partitioning function for 4-byte keys with 8-byte payloads.
+extern "C" void cpu_chunked_radix_partition_swwc_int32_int64(
+    RadixPartitionArgs *args) {
+  cpu_chunked_radix_partition_swwc<int, long long, unsigned>(*args);
+}
+
+// Exports the partitioning function for 8-byte keys with 4-byte payloads.
+extern "C" void cpu_chunked_radix_partition_swwc_int64_int32(
+    RadixPartitionArgs *args) {
+  cpu_chunked_radix_partition_swwc<long long, int, unsigned long long>(*args);
+}
+
+#if defined(__ALTIVEC__) || defined(__x86_64__)
 // Exports the partitioning function for 8-byte key/value tuples.
 extern "C" void cpu_chunked_radix_partition_swwc_simd_int32_int32(
     RadixPartitionArgs *args) {
@@ -760,9 +1240,59 @@ extern "C" void cpu_chunked_radix_partition_swwc_simd_int64_int64(
   cpu_chunked_radix_partition_swwc_simd<long long, long long,
                                         unsigned long long>(*args);
 }
+
+// Exports the partitioning function for 8-byte unsigned key/value tuples.
+extern "C" void cpu_chunked_radix_partition_swwc_simd_uint32_uint32(
+    RadixPartitionArgs *args) {
+  cpu_chunked_radix_partition_swwc_simd<unsigned, unsigned, unsigned>(*args);
+}
+
+// Exports the partitioning function for 16-byte unsigned key/value tuples.
+extern "C" void cpu_chunked_radix_partition_swwc_simd_uint64_uint64(
+    RadixPartitionArgs *args) {
+  cpu_chunked_radix_partition_swwc_simd<unsigned long long, unsigned long long,
+                                        unsigned long long>(*args);
+}
+
+// Exports the partitioning function for 32-byte composite key/value tuples.
+//
+// Composite keys don't fit the SIMD code path, and fall back to the scalar
+// partitioning function.
+extern "C" void cpu_chunked_radix_partition_swwc_simd_composite_composite(
+    RadixPartitionArgs *args) {
+  cpu_chunked_radix_partition_swwc<CompositeKey, CompositeKey,
+                                   unsigned long long>(*args);
+}
+
+// Exports the partitioning function for 4-byte keys with 8-byte payloads.
+//
+// The SIMD code path requires keys and payloads of the same size. Thus, mixed
+// sizes fall back to the scalar partitioning function.
+extern "C" void cpu_chunked_radix_partition_swwc_simd_int32_int64(
+    RadixPartitionArgs *args) {
+  cpu_chunked_radix_partition_swwc<int, long long, unsigned>(*args);
+}
+
+// Exports the partitioning function for 8-byte keys with 4-byte payloads.
+//
+// See above for why the scalar partitioning function is called.
+extern "C" void cpu_chunked_radix_partition_swwc_simd_int64_int32(
+    RadixPartitionArgs *args) {
+  cpu_chunked_radix_partition_swwc<long long, int, unsigned long long>(*args);
+}
 #else  // define dummy function symbols
 extern "C" void cpu_chunked_radix_partition_swwc_simd_int32_int32(
     RadixPartitionArgs * /* args */) {}
 extern "C" void cpu_chunked_radix_partition_swwc_simd_int64_int64(
     RadixPartitionArgs * /* args */) {}
-#endif /* defined(__ALTIVEC__) */
+extern "C" void cpu_chunked_radix_partition_swwc_simd_uint32_uint32(
+    RadixPartitionArgs * /* args */) {}
+extern "C" void cpu_chunked_radix_partition_swwc_simd_uint64_uint64(
+    RadixPartitionArgs * /* args */) {}
+extern "C" void cpu_chunked_radix_partition_swwc_simd_composite_composite(
+    RadixPartitionArgs * /* args */) {}
+extern "C" void cpu_chunked_radix_partition_swwc_simd_int32_int64(
+    RadixPartitionArgs * /* args */) {}
+extern "C" void cpu_chunked_radix_partition_swwc_simd_int64_int32(
+    RadixPartitionArgs * /* args */) {}
+#endif /* defined(__ALTIVEC__) || defined(__x86_64__) */
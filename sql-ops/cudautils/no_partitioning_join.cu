@@ -38,8 +38,9 @@ typedef unsigned long long int uint64_t;
 
 __device__ void gpu_ht_insert_linearprobing_int32(
     HtEntry<int, int> *const __restrict__ hash_table,
-    unsigned int log2_hash_table_entries, int key, int payload) {
-  uint64_t index = hash<int>(key, log2_hash_table_entries);
+    unsigned int log2_hash_table_entries, HashFunction hash_function, int key,
+    int payload) {
+  uint64_t index = hash_to_bucket(hash_function, key, log2_hash_table_entries);
 
   uint64_t hash_table_entries = 1ULL << log2_hash_table_entries;
   uint64_t hash_table_mask = hash_table_entries - 1ULL;
@@ -72,9 +73,9 @@ __device__ void gpu_ht_insert_linearprobing_int32(
 
 __device__ void gpu_ht_insert_linearprobing_int64(
     HtEntry<long long, long long> *const __restrict__ hash_table,
-    unsigned int log2_hash_table_entries, long long key, long long payload) {
-  uint64_t index =
-      static_cast<uint64_t>(hash<long long>(key, log2_hash_table_entries));
+    unsigned int log2_hash_table_entries, HashFunction hash_function,
+    long long key, long long payload) {
+  uint64_t index = hash_to_bucket(hash_function, key, log2_hash_table_entries);
 
   uint64_t hash_table_entries = 1ULL << log2_hash_table_entries;
   uint64_t hash_table_mask = hash_table_entries - 1ULL;
@@ -95,7 +96,7 @@ __device__ void gpu_ht_insert_linearprobing_int64(
 
 extern "C" __global__ void gpu_ht_build_linearprobing_int32(
     HtEntry<int, int> *const __restrict__ hash_table,
-    uint64_t const hash_table_entries,
+    uint64_t const hash_table_entries, HashFunction const hash_function,
     const int *const __restrict__ join_attr_data,
     const int *const __restrict__ payload_attr_data,
     uint64_t const data_length) {
@@ -107,14 +108,14 @@ extern "C" __global__ void gpu_ht_build_linearprobing_int32(
   for (uint64_t tuple_id = global_idx; tuple_id < data_length;
        tuple_id += global_threads) {
     gpu_ht_insert_linearprobing_int32(hash_table, log2_hash_table_entries,
-                                      join_attr_data[tuple_id],
+                                      hash_function, join_attr_data[tuple_id],
                                       payload_attr_data[tuple_id]);
   }
 }
 
 extern "C" __global__ void gpu_ht_build_linearprobing_int64(
     HtEntry<long long, long long> *const __restrict__ hash_table,
-    uint64_t const hash_table_entries,
+    uint64_t const hash_table_entries, HashFunction const hash_function,
     const long long *const __restrict__ join_attr_data,
     const long long *const __restrict__ payload_attr_data,
     uint64_t const data_length) {
@@ -126,15 +127,16 @@ extern "C" __global__ void gpu_ht_build_linearprobing_int64(
   for (uint64_t tuple_id = global_idx; tuple_id < data_length;
        tuple_id += global_threads) {
     gpu_ht_insert_linearprobing_int64(hash_table, log2_hash_table_entries,
-                                      join_attr_data[tuple_id],
+                                      hash_function, join_attr_data[tuple_id],
                                       payload_attr_data[tuple_id]);
   }
 }
 
 __device__ bool gpu_ht_findkey_linearprobing_int32(
     const HtEntry<int, int> *const __restrict__ hash_table,
-    unsigned int log2_hash_table_entries, int key, int *found_payload,
-    uint64_t *__restrict__ last_index, bool use_last_index) {
+    unsigned int log2_hash_table_entries, HashFunction hash_function, int key,
+    int *found_payload, uint64_t *__restrict__ last_index,
+    bool use_last_index) {
   uint64_t hash_table_entries = 1ULL << log2_hash_table_entries;
   uint64_t hash_table_mask = hash_table_entries - 1ULL;
 
@@ -143,7 +145,7 @@ __device__ bool gpu_ht_findkey_linearprobing_int32(
     index = *last_index;
     index = (index + 1ULL) & hash_table_mask;
   } else {
-    index = hash<int>(key, log2_hash_table_entries);
+    index = hash_to_bucket(hash_function, key, log2_hash_table_entries);
   }
 
   for (uint64_t i = 0; i < hash_table_entries;
@@ -165,8 +167,8 @@ __device__ bool gpu_ht_findkey_linearprobing_int32(
 
 __device__ bool gpu_ht_findkey_linearprobing_int64(
     const HtEntry<long long, long long> *const __restrict__ hash_table,
-    unsigned int log2_hash_table_entries, long long key,
-    long long *found_payload, uint64_t *__restrict__ last_index,
+    unsigned int log2_hash_table_entries, HashFunction hash_function,
+    long long key, long long *found_payload, uint64_t *__restrict__ last_index,
     bool use_last_index) {
   uint64_t hash_table_entries = 1ULL << log2_hash_table_entries;
   uint64_t hash_table_mask = hash_table_entries - 1ULL;
@@ -176,7 +178,7 @@ __device__ bool gpu_ht_findkey_linearprobing_int64(
     index = *last_index;
     index = (index + 1ULL) & hash_table_mask;
   } else {
-    index = hash<long long>(key, log2_hash_table_entries);
+    index = hash_to_bucket(hash_function, key, log2_hash_table_entries);
   }
 
   for (uint64_t i = 0; i < hash_table_entries;
@@ -198,7 +200,7 @@ __device__ bool gpu_ht_findkey_linearprobing_int64(
 
 extern "C" __global__ void gpu_ht_probe_aggregate_linearprobing_int32(
     const HtEntry<int, int> *const __restrict__ hash_table,
-    uint64_t const hash_table_entries,
+    uint64_t const hash_table_entries, HashFunction const hash_function,
     const int *const __restrict__ join_attr_data,
     const int *const __restrict__ payload_attr_data, uint64_t const data_length,
     uint64_t *__restrict__ aggregation_result) {
@@ -213,8 +215,8 @@ extern "C" __global__ void gpu_ht_probe_aggregate_linearprobing_int32(
     uint64_t hash_table_last_index = 0;
     bool hash_table_use_last_index = false;
     while (gpu_ht_findkey_linearprobing_int32(
-        hash_table, log2_hash_table_entries, join_attr_data[tuple_id],
-        &hash_table_payload, &hash_table_last_index,
+        hash_table, log2_hash_table_entries, hash_function,
+        join_attr_data[tuple_id], &hash_table_payload, &hash_table_last_index,
         hash_table_use_last_index)) {
       hash_table_use_last_index = true;
       aggregation_result[global_idx] += payload_attr_data[tuple_id];
@@ -224,7 +226,7 @@ extern "C" __global__ void gpu_ht_probe_aggregate_linearprobing_int32(
 
 extern "C" __global__ void gpu_ht_probe_aggregate_linearprobing_int64(
     const HtEntry<long long, long long> *const __restrict__ hash_table,
-    uint64_t const hash_table_entries,
+    uint64_t const hash_table_entries, HashFunction const hash_function,
     const long long *const __restrict__ join_attr_data,
     const long long *const __restrict__ payload_attr_data,
     uint64_t const data_length, uint64_t *__restrict__ aggregation_result) {
@@ -239,8 +241,8 @@ extern "C" __global__ void gpu_ht_probe_aggregate_linearprobing_int64(
     uint64_t hash_table_last_index = 0;
     bool hash_table_use_last_index = false;
     while (gpu_ht_findkey_linearprobing_int64(
-        hash_table, log2_hash_table_entries, join_attr_data[tuple_id],
-        &hash_table_payload, &hash_table_last_index,
+        hash_table, log2_hash_table_entries, hash_function,
+        join_attr_data[tuple_id], &hash_table_payload, &hash_table_last_index,
         hash_table_use_last_index)) {
       hash_table_use_last_index = true;
       aggregation_result[global_idx] += payload_attr_data[tuple_id];
@@ -45,6 +45,7 @@ struct JoinAggregateArgs {
   uint32_t const probe_rel_padding_length;
   uint32_t const radix_bits;
   uint32_t const ignore_bits;
+  HashFunction const hash_function;
   uint32_t const ht_entries;
 };
 
@@ -155,7 +156,8 @@ __device__ void gpu_radix_join_aggregate_smem_perfect(JoinAggregateArgs &args) {
     // Build
     for (uint32_t i = threadIdx.x; i < build_size; i += blockDim.x) {
       Tuple<K, PI> tuple = build_rel[i];
-      auto ht_index = key_to_partition(tuple.key, mask, args.ignore_bits);
+      auto ht_index = key_to_partition(tuple.key, mask, args.ignore_bits,
+                                       args.hash_function);
 
 #ifdef DEBUG
       assert(ht_index < args.ht_entries && "Invalid hash table index");
@@ -169,7 +171,8 @@ __device__ void gpu_radix_join_aggregate_smem_perfect(JoinAggregateArgs &args) {
     // Probe
     for (uint32_t i = threadIdx.x; i < probe_size; i += blockDim.x) {
       Tuple<K, PO> tuple = probe_rel[i];
-      auto ht_index = key_to_partition(tuple.key, mask, args.ignore_bits);
+      auto ht_index = key_to_partition(tuple.key, mask, args.ignore_bits,
+                                       args.hash_function);
 
 #ifdef DEBUG
       assert(ht_index < args.ht_entries && "Invalid hash table index");
@@ -280,7 +283,8 @@ __device__ void gpu_radix_join_aggregate_smem_chaining(
       keys[i] = tuple.key;
       values[i] = tuple.value;
 
-      auto ht_index = key_to_partition(tuple.key, mask, args.ignore_bits);
+      auto ht_index = key_to_partition(tuple.key, mask, args.ignore_bits,
+                                       args.hash_function);
       auto bucket = hash<K>(ht_index, log2_buckets);
       unsigned int next = atomicExch(&heads[bucket], i);
       links[i] = static_cast<unsigned short>(next);
@@ -293,7 +297,8 @@ __device__ void gpu_radix_join_aggregate_smem_chaining(
       Tuple<K, PO> tuple;
       tuple.load(probe_rel[i]);
 
-      auto ht_index = key_to_partition(tuple.key, mask, args.ignore_bits);
+      auto ht_index = key_to_partition(tuple.key, mask, args.ignore_bits,
+                                       args.hash_function);
       auto bucket = hash<K>(ht_index, log2_buckets);
 
       for (unsigned short i = static_cast<unsigned short>(heads[bucket]);
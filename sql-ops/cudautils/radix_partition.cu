@@ -73,7 +73,8 @@ __device__ void gpu_chunked_prefix_sum(PrefixSumArgs &args) {
   // 1. Compute local histograms per partition for thread block.
   for (size_t i = threadIdx.x; i < data_length; i += blockDim.x) {
     auto key = partition_attr[i];
-    auto p_index = key_to_partition(key, mask, args.ignore_bits);
+    auto p_index =
+        key_to_partition(key, mask, args.ignore_bits, args.hash_function);
     atomicAdd(&tmp_partition_offsets[p_index], 1U);
   }
 
@@ -137,7 +138,8 @@ __device__ void gpu_contiguous_prefix_sum(PrefixSumArgs &args) {
   // Compute local histograms per partition for thread block.
   for (size_t i = threadIdx.x; i < data_length; i += blockDim.x) {
     auto key = partition_attr[i];
-    auto p_index = key_to_partition(key, mask, args.ignore_bits);
+    auto p_index =
+        key_to_partition(key, mask, args.ignore_bits, args.hash_function);
     atomicAdd(&tmp_partition_offsets[p_index], 1U);
   }
 
@@ -253,7 +255,8 @@ __device__ void gpu_contiguous_prefix_sum_and_copy_with_payload(
     dst_partition_attr[i] = tuple.key;
     dst_payload_attr[i] = tuple.value;
 
-    auto p_index = key_to_partition(tuple.key, mask, args.ignore_bits);
+    auto p_index =
+        key_to_partition(tuple.key, mask, args.ignore_bits, args.hash_function);
     atomicAdd(&tmp_partition_offsets[p_index], 1U);
   }
 
@@ -405,7 +408,8 @@ __device__ void gpu_contiguous_prefix_sum_and_transform(
     dst_partition_attr[i] = tuple.key;
     dst_payload_attr[i] = tuple.value;
 
-    auto p_index = key_to_partition(tuple.key, mask, args.ignore_bits);
+    auto p_index =
+        key_to_partition(tuple.key, mask, args.ignore_bits, args.hash_function);
     atomicAdd(&tmp_partition_offsets[p_index], 1U);
   }
 
@@ -518,7 +522,8 @@ __device__ void gpu_chunked_radix_partition(RadixPartitionArgs &args) {
     tuple.key = join_attr_data[i];
     tuple.value = payload_attr_data[i];
 
-    auto p_index = key_to_partition(tuple.key, mask, args.ignore_bits);
+    auto p_index =
+        key_to_partition(tuple.key, mask, args.ignore_bits, args.hash_function);
     auto offset = atomicAdd(&tmp_partition_offsets[p_index], 1U);
     tuple.store(partitioned_relation[offset]);
   }
@@ -608,7 +613,8 @@ __device__ void gpu_chunked_laswwc_radix_partition(RadixPartitionArgs &args,
 #pragma unroll
     for (uint32_t k = 0; k < LASWWC_TUPLES_PER_THREAD; ++k) {
       // Hash keys to partition IDs
-      auto p_index = key_to_partition(tuple[k].key, mask, args.ignore_bits);
+      auto p_index = key_to_partition(tuple[k].key, mask, args.ignore_bits,
+                                      args.hash_function);
 
       // Build histogram of cached tuples
       atomicAdd(&cache_offsets[p_index], 1U);
@@ -633,7 +639,8 @@ __device__ void gpu_chunked_laswwc_radix_partition(RadixPartitionArgs &args,
     // Allocate space per tuple for tuple reordering and then do reordering
 #pragma unroll
     for (uint32_t k = 0; k < LASWWC_TUPLES_PER_THREAD; ++k) {
-      auto p_index = key_to_partition(tuple[k].key, mask, args.ignore_bits);
+      auto p_index = key_to_partition(tuple[k].key, mask, args.ignore_bits,
+                                      args.hash_function);
       auto pos = atomicAdd(&cache_offsets[p_index], 1U);
       cached_keys[pos] = tuple[k].key;
       cached_vals[pos] = tuple[k].value;
@@ -648,7 +655,8 @@ __device__ void gpu_chunked_laswwc_radix_partition(RadixPartitionArgs &args,
       Tuple<K, V> tuple;
       tuple.key = cached_keys[k];
       tuple.value = cached_vals[k];
-      auto p_index = key_to_partition(tuple.key, mask, args.ignore_bits);
+      auto p_index = key_to_partition(tuple.key, mask, args.ignore_bits,
+                                      args.hash_function);
 
       unsigned int offset = cache_offsets[p_index] - (k + 1);
       offset += tmp_partition_offsets[p_index];
@@ -663,7 +671,8 @@ __device__ void gpu_chunked_laswwc_radix_partition(RadixPartitionArgs &args,
     for (uint32_t k = threadIdx.x; k < blockDim.x * LASWWC_TUPLES_PER_THREAD;
          k += blockDim.x) {
       auto key = cached_keys[k];
-      auto p_index = key_to_partition(key, mask, args.ignore_bits);
+      auto p_index =
+          key_to_partition(key, mask, args.ignore_bits, args.hash_function);
       atomicAdd(&tmp_partition_offsets[p_index], 1);
     }
   }
@@ -676,7 +685,8 @@ __device__ void gpu_chunked_laswwc_radix_partition(RadixPartitionArgs &args,
     tuple.key = join_attr_data[i];
     tuple.value = payload_attr_data[i];
 
-    auto p_index = key_to_partition(tuple.key, mask, args.ignore_bits);
+    auto p_index =
+        key_to_partition(tuple.key, mask, args.ignore_bits, args.hash_function);
     auto offset = atomicAdd(&tmp_partition_offsets[p_index], 1U);
     partitioned_relation[offset] = tuple;
   }
@@ -791,7 +801,8 @@ __device__ void gpu_chunked_sswwc_radix_partition_v2(
     tuple.key = join_attr_data[i];
     tuple.value = payload_attr_data[i];
 
-    uint32_t p_index = key_to_partition(tuple.key, mask, args.ignore_bits);
+    uint32_t p_index =
+        key_to_partition(tuple.key, mask, args.ignore_bits, args.hash_function);
     uint32_t pos = 0;
     bool done = false;
     do {
@@ -913,7 +924,8 @@ __device__ void gpu_chunked_sswwc_radix_partition_v2(
     tuple.key = join_attr_data[i];
     tuple.value = payload_attr_data[i];
 
-    auto p_index = key_to_partition(tuple.key, mask, args.ignore_bits);
+    auto p_index =
+        key_to_partition(tuple.key, mask, args.ignore_bits, args.hash_function);
     auto offset = atomicAdd(&tmp_partition_offsets[p_index], 1U);
     partitioned_relation[offset] = tuple;
   }
@@ -1012,7 +1024,8 @@ __device__ void gpu_chunked_sswwc_radix_partition_v2g(
     tuple.key = ptx_load_cache_streaming(&join_attr_data[i]);
     tuple.value = ptx_load_cache_streaming(&payload_attr_data[i]);
 
-    uint32_t p_index = key_to_partition(tuple.key, mask, args.ignore_bits);
+    uint32_t p_index =
+        key_to_partition(tuple.key, mask, args.ignore_bits, args.hash_function);
     uint32_t pos = 0;
     bool done = false;
     do {
@@ -1126,7 +1139,8 @@ __device__ void gpu_chunked_sswwc_radix_partition_v2g(
     tuple.key = ptx_load_cache_streaming(&join_attr_data[i]);
     tuple.value = ptx_load_cache_streaming(&payload_attr_data[i]);
 
-    auto p_index = key_to_partition(tuple.key, mask, args.ignore_bits);
+    auto p_index =
+        key_to_partition(tuple.key, mask, args.ignore_bits, args.hash_function);
     auto offset = atomicAdd(&tmp_partition_offsets[p_index], 1U);
     tuple.store_streaming(partitioned_relation[offset]);
   }
@@ -1266,7 +1280,8 @@ __device__ void gpu_chunked_hsswwc_radix_partition_v4(
     tuple.key = join_attr_data[i];
     tuple.value = payload_attr_data[i];
 
-    uint32_t p_index = key_to_partition(tuple.key, mask, args.ignore_bits);
+    uint32_t p_index =
+        key_to_partition(tuple.key, mask, args.ignore_bits, args.hash_function);
     uint32_t pos = 0;
     bool done = false;
     do {
@@ -1470,7 +1485,8 @@ __device__ void gpu_chunked_hsswwc_radix_partition_v4(
     tuple.key = join_attr_data[i];
     tuple.value = payload_attr_data[i];
 
-    auto p_index = key_to_partition(tuple.key, mask, args.ignore_bits);
+    auto p_index =
+        key_to_partition(tuple.key, mask, args.ignore_bits, args.hash_function);
     auto offset = atomicAdd(&tmp_partition_offsets[p_index], 1U);
     partitioned_relation[offset] = tuple;
   }
@@ -23,6 +23,10 @@
 #define __forceinline__ __attribute__((always_inline)) inline
 #endif /* not __CUDACC__ */
 
+#if !defined(__CUDACC__) && defined(__SSE4_2__)
+#include <nmmintrin.h>
+#endif
+
 #ifndef CUDA_MODIFIER
 #define CUDA_MODIFIER __device__
 #endif
@@ -109,6 +113,46 @@ struct Tuple {
 template <typename K, typename V>
 using HtEntry = Tuple<K, V>;
 
+// A join result consisting of a matched build-side and probe-side payload.
+//
+// Note that the struct's layout must be kept in sync with its counterpart in
+// Rust.
+template <typename B, typename P>
+struct JoinPair {
+  B build;
+  P probe;
+};
+
+// A composite key of two 8-byte attributes.
+//
+// Composite keys compare equal if both attributes are equal. The explicit
+// conversion to an integer returns the second attribute. For example, radix
+// partitioning and hash tables take the low-order bits of a hashed composite
+// key from its second attribute.
+//
+// The 16-byte alignment enables atomic compare-and-swap operations on the key.
+//
+// Note that the struct's layout must be kept in sync with its counterpart in
+// Rust.
+struct alignas(16) CompositeKey {
+  unsigned long long first;
+  unsigned long long second;
+
+  CUDA_MODIFIER __forceinline__ explicit operator unsigned long long() const {
+    return second;
+  }
+};
+
+CUDA_MODIFIER __forceinline__ bool operator==(CompositeKey const &lhs,
+                                              CompositeKey const &rhs) {
+  return lhs.first == rhs.first && lhs.second == rhs.second;
+}
+
+CUDA_MODIFIER __forceinline__ bool operator!=(CompositeKey const &lhs,
+                                              CompositeKey const &rhs) {
+  return !(lhs == rhs);
+}
+
 template <typename K>
 CUDA_MODIFIER constexpr K null_key();
 
@@ -122,6 +166,21 @@ CUDA_MODIFIER constexpr long long null_key<long long>() {
   return 0xFFFFFFFFFFFFFFFFll;
 }
 
+template <>
+CUDA_MODIFIER constexpr unsigned int null_key<unsigned int>() {
+  return 0xFFFFFFFFu;
+}
+
+template <>
+CUDA_MODIFIER constexpr unsigned long long null_key<unsigned long long>() {
+  return 0xFFFFFFFFFFFFFFFFull;
+}
+
+template <>
+CUDA_MODIFIER constexpr CompositeKey null_key<CompositeKey>() {
+  return {0xFFFFFFFFFFFFFFFFull, 0xFFFFFFFFFFFFFFFFull};
+}
+
 // Multiply-shift hash function
 //
 // Takes a value and returns the hash of the value, modulo the number of
@@ -181,32 +240,42 @@ CUDA_MODIFIER __forceinline__ long long mult_shift_hash(
 template <typename T>
 CUDA_MODIFIER T murmur3_hash(T value, unsigned int log2_buckets);
 
-template <>
-CUDA_MODIFIER __forceinline__ int murmur3_hash(int value,
-                                               unsigned int log2_buckets) {
-  unsigned int h = static_cast<unsigned int>(value);
-
+CUDA_MODIFIER __forceinline__ unsigned int murmur3_fmix(unsigned int h) {
   h ^= h >> 16;
   h *= 0x85ebca6b;
   h ^= h >> 13;
   h *= 0xc2b2ae35;
   h ^= h >> 16;
 
-  unsigned int buckets_mask = (1u << log2_buckets) - 1u;
-  return static_cast<int>(h & buckets_mask);
+  return h;
 }
 
-template <>
-CUDA_MODIFIER __forceinline__ long long murmur3_hash(
-    long long value, unsigned int log2_buckets) {
-  unsigned long long k = static_cast<unsigned long long>(value);
-
+CUDA_MODIFIER __forceinline__ unsigned long long murmur3_fmix(
+    unsigned long long k) {
   k ^= k >> 33;
   k *= 0xff51afd7ed558ccdull;
   k ^= k >> 33;
   k *= 0xc4ceb9fe1a85ec53ull;
   k ^= k >> 33;
 
+  return k;
+}
+
+template <>
+CUDA_MODIFIER __forceinline__ int murmur3_hash(int value,
+                                               unsigned int log2_buckets) {
+  unsigned int h = murmur3_fmix(static_cast<unsigned int>(value));
+
+  unsigned int buckets_mask = (1u << log2_buckets) - 1u;
+  return static_cast<int>(h & buckets_mask);
+}
+
+template <>
+CUDA_MODIFIER __forceinline__ long long murmur3_hash(
+    long long value, unsigned int log2_buckets) {
+  unsigned long long k =
+      murmur3_fmix(static_cast<unsigned long long>(value));
+
   unsigned long long buckets_mask = (1llu << log2_buckets) - 1llu;
   return static_cast<long long>(k & buckets_mask);
 }
@@ -216,4 +285,171 @@ template <typename T>
 constexpr auto hash = &mult_shift_hash<T>;
 // constexpr auto hash = &murmur3_hash<T>;
 
+// Reverses the order of the bits in a value.
+CUDA_MODIFIER __forceinline__ unsigned int reverse_bits(unsigned int x) {
+#ifdef __CUDA_ARCH__
+  return __brev(x);
+#else
+  x = ((x >> 1) & 0x55555555u) | ((x & 0x55555555u) << 1);
+  x = ((x >> 2) & 0x33333333u) | ((x & 0x33333333u) << 2);
+  x = ((x >> 4) & 0x0F0F0F0Fu) | ((x & 0x0F0F0F0Fu) << 4);
+  return __builtin_bswap32(x);
+#endif
+}
+
+CUDA_MODIFIER __forceinline__ unsigned long long reverse_bits(
+    unsigned long long x) {
+#ifdef __CUDA_ARCH__
+  return __brevll(x);
+#else
+  x = ((x >> 1) & 0x5555555555555555ull) | ((x & 0x5555555555555555ull) << 1);
+  x = ((x >> 2) & 0x3333333333333333ull) | ((x & 0x3333333333333333ull) << 2);
+  x = ((x >> 4) & 0x0F0F0F0F0F0F0F0Full) | ((x & 0x0F0F0F0F0F0F0F0Full) << 4);
+  return __builtin_bswap64(x);
+#endif
+}
+
+// CRC32-C (Castagnoli) checksum of a 4-byte value
+//
+// Uses the SSE 4.2 CRC32 instruction if available. The software fallback
+// computes the same checksum bit-by-bit.
+CUDA_MODIFIER __forceinline__ unsigned int crc32c(unsigned int crc,
+                                                  unsigned int value) {
+#if !defined(__CUDA_ARCH__) && defined(__SSE4_2__)
+  return _mm_crc32_u32(crc, value);
+#else
+  constexpr unsigned int POLYNOMIAL = 0x82F63B78u;
+
+  crc ^= value;
+  for (int i = 0; i < 32; ++i) {
+    crc = (crc >> 1) ^ (POLYNOMIAL & (0u - (crc & 1u)));
+  }
+  return crc;
+#endif
+}
+
+// Hash functions that can be selected at runtime
+//
+// Note that the enum's values must be kept in sync with its counterpart in
+// Rust.
+enum class HashFunction : unsigned int {
+  Identity = 0,
+  Multiplicative = 1,
+  Murmur3 = 2,
+  Crc32 = 3,
+};
+
+// Hashes a key with the given hash function
+//
+// In contrast to the hash functions above, returns a full-width hash value.
+// Radix partitioning and hash tables use the value's low-order bits. Thus, the
+// multiplicative hash reverses the product's bits, because the high-order bits
+// of a product are the best mixed. All hash functions are bijective, i.e.,
+// distinct keys have distinct hash values.
+//
+// See also Richter et al., Seven-Dimensional Analysis of Hashing Methods
+template <typename T>
+CUDA_MODIFIER T hash_key(HashFunction hash_function, T key);
+
+template <>
+CUDA_MODIFIER __forceinline__ int hash_key(HashFunction hash_function,
+                                           int key) {
+  constexpr unsigned int HASH_FACTOR = 2654435769u;
+
+  unsigned int k = static_cast<unsigned int>(key);
+
+  switch (hash_function) {
+    case HashFunction::Multiplicative:
+      k = reverse_bits(k * HASH_FACTOR);
+      break;
+    case HashFunction::Murmur3:
+      k = murmur3_fmix(k);
+      break;
+    case HashFunction::Crc32:
+      k = crc32c(0u, k);
+      break;
+    case HashFunction::Identity:
+    default:
+      break;
+  }
+
+  return static_cast<int>(k);
+}
+
+template <>
+CUDA_MODIFIER __forceinline__ long long hash_key(HashFunction hash_function,
+                                                 long long key) {
+  constexpr unsigned long long HASH_FACTOR = 11400714819323198485llu;
+
+  unsigned long long k = static_cast<unsigned long long>(key);
+
+  switch (hash_function) {
+    case HashFunction::Multiplicative:
+      k = reverse_bits(k * HASH_FACTOR);
+      break;
+    case HashFunction::Murmur3:
+      k = murmur3_fmix(k);
+      break;
+    case HashFunction::Crc32: {
+      // Checksum the low and high halves, so that all key bits affect the low
+      // 32 bits of the hash value
+      unsigned int lo = static_cast<unsigned int>(k);
+      unsigned int hi = static_cast<unsigned int>(k >> 32);
+      unsigned int crc_lo = crc32c(crc32c(0u, lo), hi);
+      unsigned int crc_hi = crc32c(0u, hi);
+      k = (static_cast<unsigned long long>(crc_hi) << 32) | crc_lo;
+      break;
+    }
+    case HashFunction::Identity:
+    default:
+      break;
+  }
+
+  return static_cast<long long>(k);
+}
+
+template <>
+CUDA_MODIFIER __forceinline__ unsigned int hash_key(HashFunction hash_function,
+                                                    unsigned int key) {
+  return static_cast<unsigned int>(
+      hash_key(hash_function, static_cast<int>(key)));
+}
+
+template <>
+CUDA_MODIFIER __forceinline__ unsigned long long hash_key(
+    HashFunction hash_function, unsigned long long key) {
+  return static_cast<unsigned long long>(
+      hash_key(hash_function, static_cast<long long>(key)));
+}
+
+// Hashes a composite key
+//
+// Hashes the first attribute, and then hashes the second attribute combined
+// with the hash value of the first attribute. Thus, all key bits affect the
+// second attribute of the hash value, and the hash function remains
+// bijective. The identity hash function returns the key unchanged.
+template <>
+CUDA_MODIFIER __forceinline__ CompositeKey hash_key(HashFunction hash_function,
+                                                    CompositeKey key) {
+  if (hash_function == HashFunction::Identity) {
+    return key;
+  }
+
+  unsigned long long first = hash_key(hash_function, key.first);
+  unsigned long long second = hash_key(hash_function, key.second ^ first);
+  return {first, second};
+}
+
+// Returns the hash table bucket of a key
+//
+// The bucket is formed by the low-order bits of the key's hash value. Takes the
+// number of buckets as log2(buckets).
+template <typename T>
+CUDA_MODIFIER __forceinline__ unsigned long long hash_to_bucket(
+    HashFunction hash_function, T key, unsigned int log2_buckets) {
+  unsigned long long buckets_mask = (1llu << log2_buckets) - 1llu;
+  return static_cast<unsigned long long>(hash_key(hash_function, key)) &
+         buckets_mask;
+}
+
 #endif /* GPU_COMMON_H */
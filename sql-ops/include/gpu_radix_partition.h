@@ -16,6 +16,7 @@
 #define GPU_RADIX_PARTITION_H
 
 #include <constants.h>
+#include "gpu_common.h"
 #include "prefix_scan_state.h"
 
 #include <cstdint>
@@ -42,6 +43,7 @@ struct PrefixSumArgs {
   uint32_t const padding_length;
   uint32_t const radix_bits;
   uint32_t const ignore_bits;
+  HashFunction const hash_function;
 
   // State
   ScanState<unsigned long long> *const prefix_scan_state;
@@ -61,6 +63,7 @@ struct PrefixSumAndCopyWithPayloadArgs {
   uint32_t const padding_length;
   uint32_t const radix_bits;
   uint32_t const ignore_bits;
+  HashFunction const hash_function;
 
   // State
   ScanState<unsigned long long> *const prefix_scan_state;
@@ -83,6 +86,7 @@ struct PrefixSumAndTransformArgs {
   uint32_t const padding_length;
   uint32_t const radix_bits;
   uint32_t const ignore_bits;
+  HashFunction const hash_function;
 
   // State
   ScanState<unsigned long long> *const prefix_scan_state;
@@ -106,6 +110,7 @@ struct RadixPartitionArgs {
   uint32_t const padding_length;
   uint32_t const radix_bits;
   uint32_t const ignore_bits;
+  HashFunction const hash_function;
   const unsigned long long *const __restrict__ partition_offsets;
 
   // State
@@ -119,11 +124,15 @@ struct RadixPartitionArgs {
 };
 
 // Computes the partition ID of a given key.
+//
+// The key is hashed with `hash_function` before extracting the radix bits.
 template <typename T, typename B>
 CUDA_MODIFIER unsigned int key_to_partition(T key, unsigned long long mask,
-                                            B bits) {
+                                            B bits,
+                                            HashFunction hash_function) {
   return static_cast<unsigned int>(
-      (static_cast<unsigned long long>(key) & mask) >> bits);
+      (static_cast<unsigned long long>(hash_key(hash_function, key)) & mask) >>
+      bits);
 }
 
 #endif /* GPU_RADIX_PARTITION_H */
@@ -0,0 +1,87 @@
+// Copyright 2022 Clemens Lutz
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A composite key type for joins on two attributes.
+//!
+//! A `CompositeKey` combines two 8-byte attributes into a single 16-byte key.
+//! Thus, a join on two columns can be executed as a join on a single key
+//! column. Composite keys are supported by the CPU radix partitioner and the
+//! CPU hash join.
+//!
+//! Hash functions take the low-order bits of a hashed composite key from its
+//! second attribute. The hash value's second attribute depends on both key
+//! attributes, except for the identity hash function. Perfect hashing indexes
+//! the hash table with the second attribute, which therefore must be unique
+//! among the build-side keys. Probes that aggregate the payloads sum up the
+//! second attribute of composite payloads.
+//!
+//! Building a linear probing hash table requires a 16-byte compare-and-swap
+//! instruction. The C++ code relies on `libatomic` for this operation.
+
+use datagen::error::Result as DatagenResult;
+use datagen::relation::KeyAttribute;
+use num_traits::cast::AsPrimitive;
+use numa_gpu::runtime::memory::DeviceCopy;
+use std::fmt;
+
+/// A key that consists of two 8-byte attributes.
+///
+/// Keys are compared by both attributes. The `NULL` key has all bits set in
+/// both attributes.
+///
+/// Note that the struct's layout must be kept in sync with its counterpart in
+/// C/C++.
+#[derive(Copy, Clone, Default, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[repr(C, align(16))]
+pub struct CompositeKey(pub u64, pub u64);
+
+unsafe impl DeviceCopy for CompositeKey {}
+
+impl KeyAttribute for CompositeKey {
+    fn null_key() -> Self {
+        Self(u64::MAX, u64::MAX)
+    }
+
+    fn try_from_usize(x: usize) -> DatagenResult<Self> {
+        Ok(Self(0, x as u64))
+    }
+}
+
+/// Truncates the key to the second attribute's low-order bits.
+///
+/// The truncation matches the C++ conversion of a composite key to an integer.
+/// For example, the `NULL` key converts to an integer with all bits set.
+impl AsPrimitive<u32> for CompositeKey {
+    fn as_(self) -> u32 {
+        self.1 as u32
+    }
+}
+
+impl From<(u64, u64)> for CompositeKey {
+    fn from((first, second): (u64, u64)) -> Self {
+        Self(first, second)
+    }
+}
+
+impl From<CompositeKey> for (u64, u64) {
+    fn from(key: CompositeKey) -> Self {
+        (key.0, key.1)
+    }
+}
+
+impl fmt::Display for CompositeKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "({}, {})", self.0, self.1)
+    }
+}
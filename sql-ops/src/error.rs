@@ -18,6 +18,7 @@ pub type Result<T> = std::result::Result<T, Error>;
 
 #[derive(Debug)]
 pub enum ErrorKind {
+    #[cfg(feature = "cuda")]
     CudaError(rustacuda::error::CudaError),
     IntegerOverflow(String),
     InvalidArgument(String),
@@ -47,6 +48,7 @@ impl std::fmt::Display for Error {
     }
 }
 
+#[cfg(feature = "cuda")]
 impl From<rustacuda::error::CudaError> for Error {
     fn from(error: rustacuda::error::CudaError) -> Self {
         Self {
@@ -80,6 +82,7 @@ impl From<ErrorKind> for Error {
 impl std::fmt::Display for ErrorKind {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
+            #[cfg(feature = "cuda")]
             ErrorKind::CudaError(ref e) => e.fmt(f),
             ErrorKind::IntegerOverflow(ref s) => write!(f, "IntegerOverflow: {}", s),
             ErrorKind::InvalidArgument(ref s) => write!(f, "InvalidArgument: {}", s),
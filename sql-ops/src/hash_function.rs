@@ -0,0 +1,89 @@
+// Copyright 2022 Clemens Lutz
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Definitions of hash functions for radix partitioning and hash tables.
+//!
+//! The hash function is applied to a key before extracting its radix bits or
+//! its hash table bucket. Thus, prefix sum, partitioning, and join must all use
+//! the same hash function. Otherwise, matching keys end up in different
+//! partitions or buckets.
+//!
+//! All hash functions are bijective, i.e., distinct keys have distinct hash
+//! values. The radix join relies on this property, because it partitions and
+//! joins on the hash value instead of the key.
+
+use numa_gpu::runtime::memory::DeviceCopy;
+use serde_derive::Serialize;
+use structopt::clap::arg_enum;
+
+/// Specifies the hash function applied to keys.
+///
+/// Note that the enum's values must be kept in sync with its counterpart in
+/// C/C++.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[repr(u32)]
+pub enum HashFunction {
+    /// Uses the key as its hash value.
+    ///
+    /// The identity is the fastest hash function, but skewed bit patterns in
+    /// the keys lead to skewed partitions and hash table collisions.
+    Identity = 0,
+
+    /// Multiplicative (Fibonacci) hashing.
+    ///
+    /// Multiplies the key by the golden ratio scaled to the key width.
+    Multiplicative = 1,
+
+    /// The finalizer of the MurmurHash3 hash function.
+    Murmur3 = 2,
+
+    /// CRC32-C checksum of the key.
+    ///
+    /// Uses the SSE 4.2 CRC32 instruction on x86_64 CPUs, if the build target
+    /// supports it.
+    Crc32 = 3,
+}
+
+impl Default for HashFunction {
+    fn default() -> Self {
+        HashFunction::Identity
+    }
+}
+
+unsafe impl DeviceCopy for HashFunction {}
+
+arg_enum! {
+    /// Command-line argument that selects a `HashFunction`.
+    ///
+    /// Shared by all benchmark binaries. Each binary chooses its own default
+    /// value, and documents it at its command-line option.
+    #[derive(Copy, Clone, Debug, PartialEq, Serialize)]
+    pub enum ArgHashFunction {
+        Identity,
+        Multiplicative,
+        Murmur3,
+        Crc32,
+    }
+}
+
+impl From<ArgHashFunction> for HashFunction {
+    fn from(ahf: ArgHashFunction) -> Self {
+        match ahf {
+            ArgHashFunction::Identity => HashFunction::Identity,
+            ArgHashFunction::Multiplicative => HashFunction::Multiplicative,
+            ArgHashFunction::Murmur3 => HashFunction::Murmur3,
+            ArgHashFunction::Crc32 => HashFunction::Crc32,
+        }
+    }
+}
@@ -14,10 +14,14 @@
 
 //! A collection of relational join operators.
 
+pub mod cpu_radix_join;
+#[cfg(feature = "cuda")]
 pub mod cuda_radix_join;
 mod hashing_scheme;
 pub mod no_partitioning_join;
 
+use numa_gpu::runtime::memory::DeviceCopy;
+
 pub use hashing_scheme::HashingScheme;
 
 /// A hash table entry in the C/C++ implementation.
@@ -25,3 +29,39 @@ pub use hashing_scheme::HashingScheme;
 /// Note that the struct's layout must be kept in sync with its counterpart in
 /// C/C++.
 pub type HtEntry<K, V> = crate::partition::Tuple<K, V>;
+
+/// A join result consisting of a matched build-side and probe-side payload.
+///
+/// Join operators that materialize their result output a collection of
+/// `JoinPair<B, P>`. To obtain row-ID pairs instead of payload pairs, pass the
+/// row IDs as payload attributes.
+///
+/// Note that the struct's layout must be kept in sync with its counterpart in
+/// C/C++.
+#[derive(Copy, Clone, Default, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[repr(C)]
+pub struct JoinPair<B: Sized, P: Sized> {
+    pub build: B,
+    pub probe: P,
+}
+
+unsafe impl<B, P> DeviceCopy for JoinPair<B, P>
+where
+    B: DeviceCopy,
+    P: DeviceCopy,
+{
+}
+
+/// The progress of a materializing probe.
+///
+/// A materializing probe stops early if the output buffer is full. The caller
+/// then consumes the output and resumes the probe after the last processed
+/// probe tuple.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub struct ProbeProgress {
+    /// Number of probe tuples that were processed.
+    pub probe_tuples: usize,
+
+    /// Number of join results written to the output.
+    pub output_tuples: usize,
+}
@@ -0,0 +1,503 @@
+// Copyright 2022 Clemens Lutz
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Radix join operators for CPUs.
+//!
+//! The CPU radix join is the counterpart of `CudaRadixJoin`. It joins two
+//! relations that have been radix partitioned with the same radix bits.
+//!
+//! Specifically, `CpuRadixJoin::join` performs:
+//!
+//! ```sql
+//! SELECT SUM(s.value)
+//! FROM r
+//! JOIN s ON r.key = s.key
+//! ```
+//!
+//! Alternatively, `CpuRadixJoin::join_materialize` writes the matching
+//! (build payload, probe payload) pairs into an output buffer. If the buffer is
+//! full, the join stops and records its position in a `RadixJoinCursor`. The
+//! caller then consumes the buffer and resumes the join with the cursor.
+//!
+//! ## Hashing schemes
+//!
+//! Perfect hashing and bucket chaining are implemented. Linear probing is
+//! currently not implemented. The hash table is sized to the largest build-side
+//! partition, and reused for all partitions. Thus, the radix bits should be
+//! chosen such that the hash table fits into the CPU's L1 or L2 cache.
+//!
+//! ## Thread-safety
+//!
+//! One call to `CpuRadixJoin::join` processes all partitions sequentially.
+//! The operator is meant to be externally parallelized by the caller, e.g., by
+//! assigning a disjunct set of partitioned relations to each thread. Each thread
+//! must have exclusive ownership of its `CpuRadixJoin` instance, because the
+//! instance holds the hash table.
+//!
+//! ## Chunked relations
+//!
+//! In contrast to `CudaRadixJoin`, the build and probe relations may consist of
+//! multiple chunks. The chunks of a partition are joined as if they were one
+//! contiguous partition.
+
+use super::{HashingScheme, HtEntry, JoinPair};
+use crate::error::{ErrorKind, Result};
+use crate::hash_function::HashFunction;
+use crate::partition::PartitionedRelation;
+use crate::partition::Tuple;
+use crate::partition::{RadixBits, RadixPass};
+use datagen::relation::KeyAttribute;
+use numa_gpu::runtime::memory::DeviceCopy;
+use std::convert::TryInto;
+use std::ffi::c_void;
+use std::mem;
+use std::ptr;
+
+/// Arguments to the C/C++ join functions.
+///
+/// The aggregate functions use `aggregation_result`. The materialize functions
+/// use the output and cursor fields instead. Both count the build-side keys
+/// that don't fit into the hash table in `rejected_keys`.
+///
+/// Note that the struct's layout must be kept in sync with its counterpart in
+/// C/C++.
+#[repr(C)]
+#[derive(Clone, Debug)]
+struct CpuRadixJoinArgs {
+    build_rel: *const c_void,
+    build_rel_partition_offsets: *const u64,
+    probe_rel: *const c_void,
+    probe_rel_partition_offsets: *const u64,
+    hash_table: *mut c_void,
+    build_rel_padded_len: u64,
+    probe_rel_padded_len: u64,
+    build_rel_padding_len: u32,
+    probe_rel_padding_len: u32,
+    build_rel_chunks: u32,
+    probe_rel_chunks: u32,
+    radix_bits: u32,
+    ignore_bits: u32,
+    hash_function: HashFunction,
+    ht_entries: u32,
+    ht_buckets: u32,
+    aggregation_result: *mut i64,
+    output: *mut c_void,
+    output_len: u64,
+    output_written: *mut u64,
+    start_partition: u32,
+    start_offset: u64,
+    next_partition: *mut u32,
+    next_offset: *mut u64,
+    rejected_keys: *mut u64,
+}
+
+extern "C" {
+    fn cpu_join_aggregate_perfect_int32_int32_int32(args: *mut CpuRadixJoinArgs);
+    fn cpu_join_aggregate_perfect_int64_int64_int64(args: *mut CpuRadixJoinArgs);
+    fn cpu_join_aggregate_chaining_int32_int32_int32(args: *mut CpuRadixJoinArgs);
+    fn cpu_join_aggregate_chaining_int64_int64_int64(args: *mut CpuRadixJoinArgs);
+    fn cpu_join_materialize_perfect_int32_int32_int32(args: *mut CpuRadixJoinArgs);
+    fn cpu_join_materialize_perfect_int64_int64_int64(args: *mut CpuRadixJoinArgs);
+    fn cpu_join_materialize_chaining_int32_int32_int32(args: *mut CpuRadixJoinArgs);
+    fn cpu_join_materialize_chaining_int64_int64_int64(args: *mut CpuRadixJoinArgs);
+}
+
+/// Specifies that the implementing type can be used as a join key in `CpuRadixJoin`.
+///
+/// CpuRadixJoinable is a trait for which specialized implementations exist for each implementing
+/// type (currently i32 and i64). Specialization is necessary because each type requires a
+/// different C/C++ function to be called.
+pub trait CpuRadixJoinable: DeviceCopy + KeyAttribute {
+    fn join_impl(
+        rj: &mut CpuRadixJoin,
+        build_rel: &PartitionedRelation<Tuple<Self, Self>>,
+        probe_rel: &PartitionedRelation<Tuple<Self, Self>>,
+        result: &mut i64,
+    ) -> Result<()>;
+
+    fn join_materialize_impl(
+        rj: &mut CpuRadixJoin,
+        build_rel: &PartitionedRelation<Tuple<Self, Self>>,
+        probe_rel: &PartitionedRelation<Tuple<Self, Self>>,
+        cursor: &mut RadixJoinCursor,
+        output: &mut [JoinPair<Self, Self>],
+    ) -> Result<usize>;
+}
+
+/// The position at which a materializing radix join resumes.
+///
+/// A materializing join stops early if the output buffer is full. The cursor
+/// records the partition and the probe tuple at which the join stopped. Passing
+/// the same cursor to the next call resumes the join at that position.
+///
+/// A new cursor starts at the first partition.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub struct RadixJoinCursor {
+    partition_id: u32,
+    probe_offset: u64,
+    is_finished: bool,
+}
+
+impl RadixJoinCursor {
+    /// Returns `true` if all partitions have been joined.
+    pub fn is_finished(&self) -> bool {
+        self.is_finished
+    }
+}
+
+/// CPU radix join implementation in C++.
+///
+/// See the module documentation for details.
+#[derive(Debug)]
+pub struct CpuRadixJoin {
+    radix_pass: RadixPass,
+    radix_bits: RadixBits,
+    hashing_scheme: HashingScheme,
+    hash_function: HashFunction,
+    hash_table: Vec<u64>,
+}
+
+impl CpuRadixJoin {
+    /// Create a new radix join instance.
+    ///
+    /// The `radix_pass` specifies the last partitioning pass that was applied
+    /// to the input relations.
+    pub fn new(
+        radix_pass: RadixPass,
+        radix_bits: RadixBits,
+        hashing_scheme: HashingScheme,
+    ) -> Result<Self> {
+        if radix_bits.pass_radix_bits(radix_pass).is_none() {
+            Err(ErrorKind::InvalidArgument(
+                "Radix pass has no radix bits".to_string(),
+            ))?;
+        }
+
+        if let HashingScheme::LinearProbing = hashing_scheme {
+            Err(ErrorKind::InvalidArgument(
+                "Linear probing is not supported by the CPU radix join".to_string(),
+            ))?;
+        }
+
+        Ok(Self {
+            radix_pass,
+            radix_bits,
+            hashing_scheme,
+            hash_function: HashFunction::default(),
+            hash_table: Vec::new(),
+        })
+    }
+
+    /// Sets the hash function with which the relations were partitioned.
+    ///
+    /// The join computes the hash table index from the bits of the key's hash
+    /// value that remain after partitioning. Thus, the hash function must be
+    /// the same as the partitioner's. Perfect hashing requires the identity
+    /// hash function.
+    pub fn with_hash_function(mut self, hash_function: HashFunction) -> Self {
+        self.hash_function = hash_function;
+        self
+    }
+
+    /// Join two relations and add the aggregate value to `result`.
+    ///
+    /// Returns an error if a build-side key doesn't fit into the hash table.
+    /// In that case, `result` is left unchanged.
+    pub fn join<T>(
+        &mut self,
+        build_rel: &PartitionedRelation<Tuple<T, T>>,
+        probe_rel: &PartitionedRelation<Tuple<T, T>>,
+        result: &mut i64,
+    ) -> Result<()>
+    where
+        T: DeviceCopy + KeyAttribute + CpuRadixJoinable,
+    {
+        T::join_impl(self, build_rel, probe_rel, result)
+    }
+
+    /// Join two relations and write the matching payload pairs to `output`.
+    ///
+    /// Specifically, the join performs:
+    ///
+    /// ```sql
+    /// SELECT r.value, s.value
+    /// FROM r
+    /// JOIN s ON r.key = s.key
+    /// ```
+    ///
+    /// The join starts at the position of `cursor` and stops when `output` is
+    /// full, or all partitions are joined. The matches of a probe tuple are
+    /// never split across two calls. On return, `cursor` points to the first
+    /// probe tuple that has not been joined yet.
+    ///
+    /// Returns the number of pairs written to `output`. The caller should
+    /// repeat the call with the same relations and cursor until
+    /// `cursor.is_finished()` is `true`. The order of the pairs is unspecified.
+    ///
+    /// Returns an error if `output` is too small to hold all matches of a
+    /// single probe tuple, or if a build-side key doesn't fit into the hash
+    /// table.
+    pub fn join_materialize<T>(
+        &mut self,
+        build_rel: &PartitionedRelation<Tuple<T, T>>,
+        probe_rel: &PartitionedRelation<Tuple<T, T>>,
+        cursor: &mut RadixJoinCursor,
+        output: &mut [JoinPair<T, T>],
+    ) -> Result<usize>
+    where
+        T: DeviceCopy + KeyAttribute + CpuRadixJoinable,
+    {
+        T::join_materialize_impl(self, build_rel, probe_rel, cursor, output)
+    }
+
+    /// Ensures that the hash table scratch space is at least `bytes` large.
+    fn reserve_hash_table(&mut self, bytes: usize) -> *mut c_void {
+        let len = (bytes + mem::size_of::<u64>() - 1) / mem::size_of::<u64>();
+        if self.hash_table.len() < len {
+            self.hash_table.resize(len, 0);
+        }
+
+        self.hash_table.as_mut_ptr() as *mut c_void
+    }
+
+    /// Validates the relations, sizes the hash table, and fills in the
+    /// arguments that are common to all join functions.
+    ///
+    /// The aggregation, output, and cursor fields are left empty.
+    fn join_args<T: DeviceCopy>(
+        &mut self,
+        build_rel: &PartitionedRelation<Tuple<T, T>>,
+        probe_rel: &PartitionedRelation<Tuple<T, T>>,
+    ) -> Result<CpuRadixJoinArgs> {
+        let radix_bits = self.radix_bits.pass_radix_bits(self.radix_pass).unwrap();
+        let ignore_bits = self.radix_bits.pass_ignore_bits(self.radix_pass) + radix_bits;
+
+        if let HashingScheme::Perfect = self.hashing_scheme {
+            if self.hash_function != HashFunction::Identity {
+                Err(ErrorKind::InvalidArgument(
+                    "Perfect hashing requires the identity hash function".to_string(),
+                ))?;
+            }
+        }
+
+        if build_rel.radix_bits() != radix_bits {
+            Err(ErrorKind::InvalidArgument(
+                "Build relation has mismatching radix bits".to_string(),
+            ))?;
+        }
+        if probe_rel.radix_bits() != radix_bits {
+            Err(ErrorKind::InvalidArgument(
+                "Probe relation has mismatching radix bits".to_string(),
+            ))?;
+        }
+
+        let (build_data, build_offsets): (&[Tuple<T, T>], &[u64]) = match (
+            (&build_rel.relation).try_into(),
+            (&build_rel.offsets).try_into(),
+        ) {
+            (Ok(data), Ok(offsets)) => (data, offsets),
+            _ => Err(ErrorKind::RuntimeError(
+                "Trying to dereference device memory!".to_string(),
+            ))?,
+        };
+        let (probe_data, probe_offsets): (&[Tuple<T, T>], &[u64]) = match (
+            (&probe_rel.relation).try_into(),
+            (&probe_rel.offsets).try_into(),
+        ) {
+            (Ok(data), Ok(offsets)) => (data, offsets),
+            _ => Err(ErrorKind::RuntimeError(
+                "Trying to dereference device memory!".to_string(),
+            ))?,
+        };
+
+        let max_build_len = max_partition_len(build_rel)?;
+
+        let (ht_entries, ht_buckets, ht_bytes) = match self.hashing_scheme {
+            HashingScheme::Perfect => {
+                let entries = max_build_len + 1;
+                (entries, 0, entries * mem::size_of::<HtEntry<T, T>>())
+            }
+            HashingScheme::BucketChaining => {
+                let entries = max_build_len.max(1);
+                let buckets = entries.next_power_of_two().max(2);
+                (
+                    entries,
+                    buckets,
+                    entries * (2 * mem::size_of::<T>() + mem::size_of::<u32>())
+                        + buckets * mem::size_of::<u32>(),
+                )
+            }
+            HashingScheme::LinearProbing => unreachable!(),
+        };
+
+        if ht_entries >= u32::MAX as usize {
+            Err(ErrorKind::InvalidArgument(
+                "Build-side partition is too large for the hash table".to_string(),
+            ))?;
+        }
+
+        let hash_table = self.reserve_hash_table(ht_bytes);
+
+        Ok(CpuRadixJoinArgs {
+            build_rel: build_data.as_ptr() as *const c_void,
+            build_rel_partition_offsets: build_offsets.as_ptr(),
+            probe_rel: probe_data.as_ptr() as *const c_void,
+            probe_rel_partition_offsets: probe_offsets.as_ptr(),
+            hash_table,
+            build_rel_padded_len: build_rel.padded_len() as u64,
+            probe_rel_padded_len: probe_rel.padded_len() as u64,
+            build_rel_padding_len: build_rel.padding_len(),
+            probe_rel_padding_len: probe_rel.padding_len(),
+            build_rel_chunks: build_rel.num_chunks(),
+            probe_rel_chunks: probe_rel.num_chunks(),
+            radix_bits,
+            ignore_bits,
+            hash_function: self.hash_function,
+            ht_entries: ht_entries as u32,
+            ht_buckets: ht_buckets as u32,
+            aggregation_result: ptr::null_mut(),
+            output: ptr::null_mut(),
+            output_len: 0,
+            output_written: ptr::null_mut(),
+            start_partition: 0,
+            start_offset: 0,
+            next_partition: ptr::null_mut(),
+            next_offset: ptr::null_mut(),
+            rejected_keys: ptr::null_mut(),
+        })
+    }
+}
+
+/// Returns an error if the hash table rejected build-side keys.
+///
+/// Perfect hashing rejects keys that map to an index outside of the hash
+/// table, i.e., keys that are not dense.
+fn check_rejected_keys(rejected_keys: u64) -> Result<()> {
+    if rejected_keys != 0 {
+        Err(ErrorKind::InvalidArgument(format!(
+            "Hash table rejected {} build-side keys that are out of range",
+            rejected_keys
+        )))?;
+    }
+
+    Ok(())
+}
+
+/// Returns the largest partition length of the relation.
+fn max_partition_len<T: DeviceCopy>(rel: &PartitionedRelation<T>) -> Result<usize> {
+    (0..rel.fanout()).try_fold(0, |max, p| Ok(max.max(rel.partition_len(p)?)))
+}
+
+macro_rules! impl_cpu_radix_join_for_type {
+    ($Type:ty, $Suffix:expr) => {
+        impl CpuRadixJoinable for $Type {
+            paste::item! {
+                fn join_impl(
+                    rj: &mut CpuRadixJoin,
+                    build_rel: &PartitionedRelation<Tuple<Self, Self>>,
+                    probe_rel: &PartitionedRelation<Tuple<Self, Self>>,
+                    result: &mut i64,
+                    ) -> Result<()> {
+                    let join_fn: unsafe extern "C" fn(*mut CpuRadixJoinArgs) =
+                        match rj.hashing_scheme {
+                            HashingScheme::Perfect =>
+                                [<cpu_join_aggregate_perfect_ $Suffix _ $Suffix _ $Suffix>],
+                            HashingScheme::BucketChaining =>
+                                [<cpu_join_aggregate_chaining_ $Suffix _ $Suffix _ $Suffix>],
+                            HashingScheme::LinearProbing => unreachable!(),
+                        };
+
+                    let mut sum = 0_i64;
+                    let mut rejected_keys = 0_u64;
+
+                    let mut args = rj.join_args(build_rel, probe_rel)?;
+                    args.aggregation_result = &mut sum as *mut i64;
+                    args.rejected_keys = &mut rejected_keys as *mut u64;
+
+                    unsafe {
+                        join_fn(&mut args as *mut CpuRadixJoinArgs);
+                    }
+
+                    check_rejected_keys(rejected_keys)?;
+                    *result += sum;
+
+                    Ok(())
+                }
+            }
+
+            paste::item! {
+                fn join_materialize_impl(
+                    rj: &mut CpuRadixJoin,
+                    build_rel: &PartitionedRelation<Tuple<Self, Self>>,
+                    probe_rel: &PartitionedRelation<Tuple<Self, Self>>,
+                    cursor: &mut RadixJoinCursor,
+                    output: &mut [JoinPair<Self, Self>],
+                    ) -> Result<usize> {
+                    if cursor.is_finished {
+                        return Ok(0);
+                    }
+
+                    let join_fn: unsafe extern "C" fn(*mut CpuRadixJoinArgs) =
+                        match rj.hashing_scheme {
+                            HashingScheme::Perfect =>
+                                [<cpu_join_materialize_perfect_ $Suffix _ $Suffix _ $Suffix>],
+                            HashingScheme::BucketChaining =>
+                                [<cpu_join_materialize_chaining_ $Suffix _ $Suffix _ $Suffix>],
+                            HashingScheme::LinearProbing => unreachable!(),
+                        };
+
+                    let mut output_written = 0_u64;
+                    let mut next_partition = 0_u32;
+                    let mut next_offset = 0_u64;
+                    let mut rejected_keys = 0_u64;
+
+                    let mut args = rj.join_args(build_rel, probe_rel)?;
+                    args.output = output.as_mut_ptr() as *mut c_void;
+                    args.output_len = output.len() as u64;
+                    args.output_written = &mut output_written as *mut u64;
+                    args.start_partition = cursor.partition_id;
+                    args.start_offset = cursor.probe_offset;
+                    args.next_partition = &mut next_partition as *mut u32;
+                    args.next_offset = &mut next_offset as *mut u64;
+                    args.rejected_keys = &mut rejected_keys as *mut u64;
+
+                    unsafe {
+                        join_fn(&mut args as *mut CpuRadixJoinArgs);
+                    }
+
+                    check_rejected_keys(rejected_keys)?;
+
+                    let is_finished = next_partition >= build_rel.fanout();
+                    // The join only stops early with an empty output if the
+                    // next probe tuple has more matches than the output holds.
+                    if output_written == 0 && !is_finished {
+                        Err(ErrorKind::InvalidArgument(
+                                "Output buffer is too small to hold the matches of a probe tuple"
+                                .to_string(),
+                                ))?;
+                    }
+
+                    cursor.partition_id = next_partition;
+                    cursor.probe_offset = next_offset;
+                    cursor.is_finished = is_finished;
+
+                    Ok(output_written as usize)
+                }
+            }
+        }
+    };
+}
+
+impl_cpu_radix_join_for_type!(i32, int32);
+impl_cpu_radix_join_for_type!(i64, int64);
@@ -56,6 +56,7 @@
 
 use super::{HashingScheme, HtEntry};
 use crate::error::{ErrorKind, Result};
+use crate::hash_function::HashFunction;
 use crate::partition::PartitionedRelation;
 use crate::partition::Tuple;
 use crate::partition::{RadixBits, RadixPass};
@@ -89,6 +90,7 @@ struct JoinAggregateArgs {
     probe_rel_padding_len: u32,
     radix_bits: u32,
     ignore_bits: u32,
+    hash_function: HashFunction,
     ht_entries: u32,
 }
 
@@ -118,6 +120,7 @@ pub struct CudaRadixJoin {
     radix_pass: RadixPass,
     radix_bits: RadixBits,
     hashing_scheme: HashingScheme,
+    hash_function: HashFunction,
     grid_size: GridSize,
     block_size: BlockSize,
 }
@@ -135,11 +138,21 @@ impl CudaRadixJoin {
             radix_pass,
             radix_bits,
             hashing_scheme,
+            hash_function: HashFunction::default(),
             grid_size: grid_size.clone(),
             block_size: block_size.clone(),
         })
     }
 
+    /// Sets the hash function with which the relations were partitioned.
+    ///
+    /// The hash function must be the same as the partitioner's. Perfect hashing
+    /// requires the identity hash function.
+    pub fn with_hash_function(mut self, hash_function: HashFunction) -> Self {
+        self.hash_function = hash_function;
+        self
+    }
+
     /// Join two relations and output a set of aggregate values.
     pub fn join<T>(
         &self,
@@ -192,6 +205,14 @@ macro_rules! impl_cuda_radix_join_for_type {
                                 .to_string(),
                                 ))?;
                     }
+                    if let HashingScheme::Perfect = rj.hashing_scheme {
+                        if rj.hash_function != HashFunction::Identity {
+                            Err(ErrorKind::InvalidArgument(
+                                    "Perfect hashing requires the identity hash function"
+                                    .to_string(),
+                                    ))?;
+                        }
+                    }
                     if grid.x + 1 != task_assignments.len() as u32 {
                         Err(ErrorKind::InvalidArgument(
                                 "Task assignement array must have length: grid size + 1".to_string(),
@@ -220,6 +241,7 @@ macro_rules! impl_cuda_radix_join_for_type {
                         probe_rel_padding_len: probe_rel.padding_len(),
                         radix_bits,
                         ignore_bits,
+                        hash_function: rj.hash_function,
                         ht_entries: 0,
                     };
 
@@ -24,36 +24,73 @@
 //! `build` must be completed on all threads before calling `probe_sum`.
 //! This design was chosen to maximize flexibility on which cores to execute on.
 //!
+//! Instead of `probe_sum`, the CPU join can also materialize its result with
+//! `probe_materialize`. The probe writes the matching (build payload, probe
+//! payload) pairs into an output buffer. If the buffer is full, the probe stops
+//! and returns how far it got. The caller then consumes the buffer and resumes
+//! the probe with the remaining probe tuples.
+//!
+//! The CPU join supports perfect hashing, linear probing, and bucket chaining.
+//! Bucket chaining stores its buckets and chained entries within the hash
+//! table's memory. The hash table should thus have at least twice as many
+//! entries as there are build-side tuples. The build returns an error if the
+//! hash table is too small. Bucket chaining is currently only implemented on
+//! the CPU.
+//!
+//! Linear probing and bucket chaining hash the join keys with a `HashFunction`,
+//! which defaults to multiplicative hashing. CPU and GPU joins that share a
+//! hash table must be built with the same hash function. Perfect hashing
+//! ignores the hash function.
+//!
+//! Besides signed and unsigned integer keys, the CPU join supports keys that
+//! consist of two attributes. See the `composite_key` module for details.
+//! Payloads usually have the same type as the keys. The CPU join also supports
+//! i32 keys with i64 payloads, and i64 keys with i32 payloads.
+//!
 //! To execute in parallel on a GPU, it is sufficient to call `build` and
 //! `probe_sum` once. Both methods require grid and block sizes as input,
 //! that specify the parallelism with which to execute on the GPU. The join
 //! can also be parallelized over multiple GPUs by calling the methods multiple
 //! times using different CUDA devices.
 
-use super::{HashingScheme, HtEntry};
+use super::{HashingScheme, HtEntry, JoinPair, ProbeProgress};
+use crate::composite_key::CompositeKey;
 use crate::error::{ErrorKind, Result};
+use crate::hash_function::HashFunction;
 use cstr::cstr;
+#[cfg(feature = "cuda")]
 use cuda_driver_sys::cuMemsetD32_v2;
 use datagen::relation::KeyAttribute;
 use likwid;
+#[cfg(feature = "cuda")]
 use num_traits::cast::AsPrimitive;
 use numa_gpu::error::Result as NumaGpuResult;
+#[cfg(feature = "cuda")]
 use numa_gpu::error::ToResult;
 use numa_gpu::runtime::allocator;
 use numa_gpu::runtime::memory::*;
+use numa_gpu::runtime::trace::Span;
+#[cfg(feature = "cuda")]
 use rustacuda::context::CurrentContext;
+#[cfg(feature = "cuda")]
 use rustacuda::function::{BlockSize, GridSize};
+#[cfg(feature = "cuda")]
 use rustacuda::launch;
-use rustacuda::memory::DeviceCopy;
+#[cfg(feature = "cuda")]
 use rustacuda::prelude::*;
 use std::mem::size_of;
-use std::os::raw::{c_uint, c_void};
+#[cfg(feature = "cuda")]
+use std::os::raw::c_uint;
+#[cfg(feature = "cuda")]
+use std::os::raw::c_void;
+use std::ptr;
 use std::sync::Arc;
 
 extern "C" {
     fn cpu_ht_build_linearprobing_int32(
         hash_table: *mut HtEntry<i32, i32>,
         hash_table_entries: u64,
+        hash_function: HashFunction,
         join_attr_data: *const i32,
         payload_attr_data: *const i32,
         data_length: u64,
@@ -62,14 +99,61 @@ extern "C" {
     fn cpu_ht_build_linearprobing_int64(
         hash_table: *mut HtEntry<i64, i64>,
         hash_table_entries: u64,
+        hash_function: HashFunction,
         join_attr_data: *const i64,
         payload_attr_data: *const i64,
         data_length: u64,
     );
 
+    fn cpu_ht_build_linearprobing_uint32(
+        hash_table: *mut HtEntry<u32, u32>,
+        hash_table_entries: u64,
+        hash_function: HashFunction,
+        join_attr_data: *const u32,
+        payload_attr_data: *const u32,
+        data_length: u64,
+    );
+
+    fn cpu_ht_build_linearprobing_uint64(
+        hash_table: *mut HtEntry<u64, u64>,
+        hash_table_entries: u64,
+        hash_function: HashFunction,
+        join_attr_data: *const u64,
+        payload_attr_data: *const u64,
+        data_length: u64,
+    );
+
+    fn cpu_ht_build_linearprobing_composite(
+        hash_table: *mut HtEntry<CompositeKey, CompositeKey>,
+        hash_table_entries: u64,
+        hash_function: HashFunction,
+        join_attr_data: *const CompositeKey,
+        payload_attr_data: *const CompositeKey,
+        data_length: u64,
+    );
+
+    fn cpu_ht_build_linearprobing_int32_int64(
+        hash_table: *mut HtEntry<i32, i64>,
+        hash_table_entries: u64,
+        hash_function: HashFunction,
+        join_attr_data: *const i32,
+        payload_attr_data: *const i64,
+        data_length: u64,
+    );
+
+    fn cpu_ht_build_linearprobing_int64_int32(
+        hash_table: *mut HtEntry<i64, i32>,
+        hash_table_entries: u64,
+        hash_function: HashFunction,
+        join_attr_data: *const i64,
+        payload_attr_data: *const i32,
+        data_length: u64,
+    );
+
     fn cpu_ht_probe_aggregate_linearprobing_int32(
         hash_table: *const HtEntry<i32, i32>,
         hash_table_entries: u64,
+        hash_function: HashFunction,
         join_attr_data: *const i32,
         payload_attr_data: *const i32,
         data_length: u64,
@@ -79,12 +163,63 @@ extern "C" {
     fn cpu_ht_probe_aggregate_linearprobing_int64(
         hash_table: *const HtEntry<i64, i64>,
         hash_table_entries: u64,
+        hash_function: HashFunction,
         join_attr_data: *const i64,
         payload_attr_data: *const i64,
         data_length: u64,
         aggregation_result: *mut u64,
     );
 
+    fn cpu_ht_probe_aggregate_linearprobing_uint32(
+        hash_table: *const HtEntry<u32, u32>,
+        hash_table_entries: u64,
+        hash_function: HashFunction,
+        join_attr_data: *const u32,
+        payload_attr_data: *const u32,
+        data_length: u64,
+        aggregation_result: *mut u64,
+    );
+
+    fn cpu_ht_probe_aggregate_linearprobing_uint64(
+        hash_table: *const HtEntry<u64, u64>,
+        hash_table_entries: u64,
+        hash_function: HashFunction,
+        join_attr_data: *const u64,
+        payload_attr_data: *const u64,
+        data_length: u64,
+        aggregation_result: *mut u64,
+    );
+
+    fn cpu_ht_probe_aggregate_linearprobing_composite(
+        hash_table: *const HtEntry<CompositeKey, CompositeKey>,
+        hash_table_entries: u64,
+        hash_function: HashFunction,
+        join_attr_data: *const CompositeKey,
+        payload_attr_data: *const CompositeKey,
+        data_length: u64,
+        aggregation_result: *mut u64,
+    );
+
+    fn cpu_ht_probe_aggregate_linearprobing_int32_int64(
+        hash_table: *const HtEntry<i32, i64>,
+        hash_table_entries: u64,
+        hash_function: HashFunction,
+        join_attr_data: *const i32,
+        payload_attr_data: *const i64,
+        data_length: u64,
+        aggregation_result: *mut u64,
+    );
+
+    fn cpu_ht_probe_aggregate_linearprobing_int64_int32(
+        hash_table: *const HtEntry<i64, i32>,
+        hash_table_entries: u64,
+        hash_function: HashFunction,
+        join_attr_data: *const i64,
+        payload_attr_data: *const i32,
+        data_length: u64,
+        aggregation_result: *mut u64,
+    );
+
     fn cpu_ht_build_perfect_int32(
         hash_table: *mut HtEntry<i32, i32>,
         hash_table_entries: u64,
@@ -109,6 +244,46 @@ extern "C" {
         data_length: u64,
     );
 
+    fn cpu_ht_build_perfect_uint32(
+        hash_table: *mut HtEntry<u32, u32>,
+        hash_table_entries: u64,
+        join_attr_data: *const u32,
+        payload_attr_data: *const u32,
+        data_length: u64,
+    );
+
+    fn cpu_ht_build_perfect_uint64(
+        hash_table: *mut HtEntry<u64, u64>,
+        hash_table_entries: u64,
+        join_attr_data: *const u64,
+        payload_attr_data: *const u64,
+        data_length: u64,
+    );
+
+    fn cpu_ht_build_perfect_composite(
+        hash_table: *mut HtEntry<CompositeKey, CompositeKey>,
+        hash_table_entries: u64,
+        join_attr_data: *const CompositeKey,
+        payload_attr_data: *const CompositeKey,
+        data_length: u64,
+    );
+
+    fn cpu_ht_build_perfect_int32_int64(
+        hash_table: *mut HtEntry<i32, i64>,
+        hash_table_entries: u64,
+        join_attr_data: *const i32,
+        payload_attr_data: *const i64,
+        data_length: u64,
+    );
+
+    fn cpu_ht_build_perfect_int64_int32(
+        hash_table: *mut HtEntry<i64, i32>,
+        hash_table_entries: u64,
+        join_attr_data: *const i64,
+        payload_attr_data: *const i32,
+        data_length: u64,
+    );
+
     fn cpu_ht_build_selective_perfect_int64(
         hash_table: *mut HtEntry<i64, i64>,
         hash_table_entries: u64,
@@ -117,6 +292,46 @@ extern "C" {
         data_length: u64,
     );
 
+    fn cpu_ht_build_selective_perfect_uint32(
+        hash_table: *mut HtEntry<u32, u32>,
+        hash_table_entries: u64,
+        join_attr_data: *const u32,
+        payload_attr_data: *const u32,
+        data_length: u64,
+    );
+
+    fn cpu_ht_build_selective_perfect_uint64(
+        hash_table: *mut HtEntry<u64, u64>,
+        hash_table_entries: u64,
+        join_attr_data: *const u64,
+        payload_attr_data: *const u64,
+        data_length: u64,
+    );
+
+    fn cpu_ht_build_selective_perfect_composite(
+        hash_table: *mut HtEntry<CompositeKey, CompositeKey>,
+        hash_table_entries: u64,
+        join_attr_data: *const CompositeKey,
+        payload_attr_data: *const CompositeKey,
+        data_length: u64,
+    );
+
+    fn cpu_ht_build_selective_perfect_int32_int64(
+        hash_table: *mut HtEntry<i32, i64>,
+        hash_table_entries: u64,
+        join_attr_data: *const i32,
+        payload_attr_data: *const i64,
+        data_length: u64,
+    );
+
+    fn cpu_ht_build_selective_perfect_int64_int32(
+        hash_table: *mut HtEntry<i64, i32>,
+        hash_table_entries: u64,
+        join_attr_data: *const i64,
+        payload_attr_data: *const i32,
+        data_length: u64,
+    );
+
     fn cpu_ht_probe_aggregate_perfect_int32(
         hash_table: *const HtEntry<i32, i32>,
         hash_table_entries: u64,
@@ -134,6 +349,492 @@ extern "C" {
         data_length: u64,
         aggregation_result: *mut u64,
     );
+
+    fn cpu_ht_probe_aggregate_perfect_uint32(
+        hash_table: *const HtEntry<u32, u32>,
+        hash_table_entries: u64,
+        join_attr_data: *const u32,
+        payload_attr_data: *const u32,
+        data_length: u64,
+        aggregation_result: *mut u64,
+    );
+
+    fn cpu_ht_probe_aggregate_perfect_uint64(
+        hash_table: *const HtEntry<u64, u64>,
+        hash_table_entries: u64,
+        join_attr_data: *const u64,
+        payload_attr_data: *const u64,
+        data_length: u64,
+        aggregation_result: *mut u64,
+    );
+
+    fn cpu_ht_probe_aggregate_perfect_composite(
+        hash_table: *const HtEntry<CompositeKey, CompositeKey>,
+        hash_table_entries: u64,
+        join_attr_data: *const CompositeKey,
+        payload_attr_data: *const CompositeKey,
+        data_length: u64,
+        aggregation_result: *mut u64,
+    );
+
+    fn cpu_ht_probe_aggregate_perfect_int32_int64(
+        hash_table: *const HtEntry<i32, i64>,
+        hash_table_entries: u64,
+        join_attr_data: *const i32,
+        payload_attr_data: *const i64,
+        data_length: u64,
+        aggregation_result: *mut u64,
+    );
+
+    fn cpu_ht_probe_aggregate_perfect_int64_int32(
+        hash_table: *const HtEntry<i64, i32>,
+        hash_table_entries: u64,
+        join_attr_data: *const i64,
+        payload_attr_data: *const i32,
+        data_length: u64,
+        aggregation_result: *mut u64,
+    );
+
+    fn cpu_ht_probe_materialize_linearprobing_int32(
+        hash_table: *const HtEntry<i32, i32>,
+        hash_table_entries: u64,
+        hash_function: HashFunction,
+        join_attr_data: *const i32,
+        payload_attr_data: *const i32,
+        data_length: u64,
+        output: *mut JoinPair<i32, i32>,
+        output_length: u64,
+        output_written: *mut u64,
+    ) -> u64;
+
+    fn cpu_ht_probe_materialize_linearprobing_int64(
+        hash_table: *const HtEntry<i64, i64>,
+        hash_table_entries: u64,
+        hash_function: HashFunction,
+        join_attr_data: *const i64,
+        payload_attr_data: *const i64,
+        data_length: u64,
+        output: *mut JoinPair<i64, i64>,
+        output_length: u64,
+        output_written: *mut u64,
+    ) -> u64;
+
+    fn cpu_ht_probe_materialize_linearprobing_uint32(
+        hash_table: *const HtEntry<u32, u32>,
+        hash_table_entries: u64,
+        hash_function: HashFunction,
+        join_attr_data: *const u32,
+        payload_attr_data: *const u32,
+        data_length: u64,
+        output: *mut JoinPair<u32, u32>,
+        output_length: u64,
+        output_written: *mut u64,
+    ) -> u64;
+
+    fn cpu_ht_probe_materialize_linearprobing_uint64(
+        hash_table: *const HtEntry<u64, u64>,
+        hash_table_entries: u64,
+        hash_function: HashFunction,
+        join_attr_data: *const u64,
+        payload_attr_data: *const u64,
+        data_length: u64,
+        output: *mut JoinPair<u64, u64>,
+        output_length: u64,
+        output_written: *mut u64,
+    ) -> u64;
+
+    fn cpu_ht_probe_materialize_linearprobing_composite(
+        hash_table: *const HtEntry<CompositeKey, CompositeKey>,
+        hash_table_entries: u64,
+        hash_function: HashFunction,
+        join_attr_data: *const CompositeKey,
+        payload_attr_data: *const CompositeKey,
+        data_length: u64,
+        output: *mut JoinPair<CompositeKey, CompositeKey>,
+        output_length: u64,
+        output_written: *mut u64,
+    ) -> u64;
+
+    fn cpu_ht_probe_materialize_linearprobing_int32_int64(
+        hash_table: *const HtEntry<i32, i64>,
+        hash_table_entries: u64,
+        hash_function: HashFunction,
+        join_attr_data: *const i32,
+        payload_attr_data: *const i64,
+        data_length: u64,
+        output: *mut JoinPair<i64, i64>,
+        output_length: u64,
+        output_written: *mut u64,
+    ) -> u64;
+
+    fn cpu_ht_probe_materialize_linearprobing_int64_int32(
+        hash_table: *const HtEntry<i64, i32>,
+        hash_table_entries: u64,
+        hash_function: HashFunction,
+        join_attr_data: *const i64,
+        payload_attr_data: *const i32,
+        data_length: u64,
+        output: *mut JoinPair<i32, i32>,
+        output_length: u64,
+        output_written: *mut u64,
+    ) -> u64;
+
+    fn cpu_ht_probe_materialize_perfect_int32(
+        hash_table: *const HtEntry<i32, i32>,
+        hash_table_entries: u64,
+        join_attr_data: *const i32,
+        payload_attr_data: *const i32,
+        data_length: u64,
+        output: *mut JoinPair<i32, i32>,
+        output_length: u64,
+        output_written: *mut u64,
+    ) -> u64;
+
+    fn cpu_ht_probe_materialize_perfect_int64(
+        hash_table: *const HtEntry<i64, i64>,
+        hash_table_entries: u64,
+        join_attr_data: *const i64,
+        payload_attr_data: *const i64,
+        data_length: u64,
+        output: *mut JoinPair<i64, i64>,
+        output_length: u64,
+        output_written: *mut u64,
+    ) -> u64;
+
+    fn cpu_ht_probe_materialize_perfect_uint32(
+        hash_table: *const HtEntry<u32, u32>,
+        hash_table_entries: u64,
+        join_attr_data: *const u32,
+        payload_attr_data: *const u32,
+        data_length: u64,
+        output: *mut JoinPair<u32, u32>,
+        output_length: u64,
+        output_written: *mut u64,
+    ) -> u64;
+
+    fn cpu_ht_probe_materialize_perfect_uint64(
+        hash_table: *const HtEntry<u64, u64>,
+        hash_table_entries: u64,
+        join_attr_data: *const u64,
+        payload_attr_data: *const u64,
+        data_length: u64,
+        output: *mut JoinPair<u64, u64>,
+        output_length: u64,
+        output_written: *mut u64,
+    ) -> u64;
+
+    fn cpu_ht_probe_materialize_perfect_composite(
+        hash_table: *const HtEntry<CompositeKey, CompositeKey>,
+        hash_table_entries: u64,
+        join_attr_data: *const CompositeKey,
+        payload_attr_data: *const CompositeKey,
+        data_length: u64,
+        output: *mut JoinPair<CompositeKey, CompositeKey>,
+        output_length: u64,
+        output_written: *mut u64,
+    ) -> u64;
+
+    fn cpu_ht_probe_materialize_perfect_int32_int64(
+        hash_table: *const HtEntry<i32, i64>,
+        hash_table_entries: u64,
+        join_attr_data: *const i32,
+        payload_attr_data: *const i64,
+        data_length: u64,
+        output: *mut JoinPair<i64, i64>,
+        output_length: u64,
+        output_written: *mut u64,
+    ) -> u64;
+
+    fn cpu_ht_probe_materialize_perfect_int64_int32(
+        hash_table: *const HtEntry<i64, i32>,
+        hash_table_entries: u64,
+        join_attr_data: *const i64,
+        payload_attr_data: *const i32,
+        data_length: u64,
+        output: *mut JoinPair<i32, i32>,
+        output_length: u64,
+        output_written: *mut u64,
+    ) -> u64;
+
+    fn cpu_ht_build_chaining_int32(
+        hash_table: *mut HtEntry<i32, i32>,
+        hash_table_entries: u64,
+        hash_function: HashFunction,
+        join_attr_data: *const i32,
+        payload_attr_data: *const i32,
+        data_length: u64,
+    ) -> u64;
+
+    fn cpu_ht_build_selective_chaining_int32(
+        hash_table: *mut HtEntry<i32, i32>,
+        hash_table_entries: u64,
+        hash_function: HashFunction,
+        join_attr_data: *const i32,
+        payload_attr_data: *const i32,
+        data_length: u64,
+    ) -> u64;
+
+    fn cpu_ht_build_chaining_int64(
+        hash_table: *mut HtEntry<i64, i64>,
+        hash_table_entries: u64,
+        hash_function: HashFunction,
+        join_attr_data: *const i64,
+        payload_attr_data: *const i64,
+        data_length: u64,
+    ) -> u64;
+
+    fn cpu_ht_build_chaining_uint32(
+        hash_table: *mut HtEntry<u32, u32>,
+        hash_table_entries: u64,
+        hash_function: HashFunction,
+        join_attr_data: *const u32,
+        payload_attr_data: *const u32,
+        data_length: u64,
+    ) -> u64;
+
+    fn cpu_ht_build_chaining_uint64(
+        hash_table: *mut HtEntry<u64, u64>,
+        hash_table_entries: u64,
+        hash_function: HashFunction,
+        join_attr_data: *const u64,
+        payload_attr_data: *const u64,
+        data_length: u64,
+    ) -> u64;
+
+    fn cpu_ht_build_chaining_composite(
+        hash_table: *mut HtEntry<CompositeKey, CompositeKey>,
+        hash_table_entries: u64,
+        hash_function: HashFunction,
+        join_attr_data: *const CompositeKey,
+        payload_attr_data: *const CompositeKey,
+        data_length: u64,
+    ) -> u64;
+
+    fn cpu_ht_build_chaining_int32_int64(
+        hash_table: *mut HtEntry<i32, i64>,
+        hash_table_entries: u64,
+        hash_function: HashFunction,
+        join_attr_data: *const i32,
+        payload_attr_data: *const i64,
+        data_length: u64,
+    ) -> u64;
+
+    fn cpu_ht_build_chaining_int64_int32(
+        hash_table: *mut HtEntry<i64, i32>,
+        hash_table_entries: u64,
+        hash_function: HashFunction,
+        join_attr_data: *const i64,
+        payload_attr_data: *const i32,
+        data_length: u64,
+    ) -> u64;
+
+    fn cpu_ht_build_selective_chaining_int64(
+        hash_table: *mut HtEntry<i64, i64>,
+        hash_table_entries: u64,
+        hash_function: HashFunction,
+        join_attr_data: *const i64,
+        payload_attr_data: *const i64,
+        data_length: u64,
+    ) -> u64;
+
+    fn cpu_ht_build_selective_chaining_uint32(
+        hash_table: *mut HtEntry<u32, u32>,
+        hash_table_entries: u64,
+        hash_function: HashFunction,
+        join_attr_data: *const u32,
+        payload_attr_data: *const u32,
+        data_length: u64,
+    ) -> u64;
+
+    fn cpu_ht_build_selective_chaining_uint64(
+        hash_table: *mut HtEntry<u64, u64>,
+        hash_table_entries: u64,
+        hash_function: HashFunction,
+        join_attr_data: *const u64,
+        payload_attr_data: *const u64,
+        data_length: u64,
+    ) -> u64;
+
+    fn cpu_ht_build_selective_chaining_composite(
+        hash_table: *mut HtEntry<CompositeKey, CompositeKey>,
+        hash_table_entries: u64,
+        hash_function: HashFunction,
+        join_attr_data: *const CompositeKey,
+        payload_attr_data: *const CompositeKey,
+        data_length: u64,
+    ) -> u64;
+
+    fn cpu_ht_build_selective_chaining_int32_int64(
+        hash_table: *mut HtEntry<i32, i64>,
+        hash_table_entries: u64,
+        hash_function: HashFunction,
+        join_attr_data: *const i32,
+        payload_attr_data: *const i64,
+        data_length: u64,
+    ) -> u64;
+
+    fn cpu_ht_build_selective_chaining_int64_int32(
+        hash_table: *mut HtEntry<i64, i32>,
+        hash_table_entries: u64,
+        hash_function: HashFunction,
+        join_attr_data: *const i64,
+        payload_attr_data: *const i32,
+        data_length: u64,
+    ) -> u64;
+
+    fn cpu_ht_probe_aggregate_chaining_int32(
+        hash_table: *const HtEntry<i32, i32>,
+        hash_table_entries: u64,
+        hash_function: HashFunction,
+        join_attr_data: *const i32,
+        payload_attr_data: *const i32,
+        data_length: u64,
+        aggregation_result: *mut u64,
+    );
+
+    fn cpu_ht_probe_aggregate_chaining_int64(
+        hash_table: *const HtEntry<i64, i64>,
+        hash_table_entries: u64,
+        hash_function: HashFunction,
+        join_attr_data: *const i64,
+        payload_attr_data: *const i64,
+        data_length: u64,
+        aggregation_result: *mut u64,
+    );
+
+    fn cpu_ht_probe_aggregate_chaining_uint32(
+        hash_table: *const HtEntry<u32, u32>,
+        hash_table_entries: u64,
+        hash_function: HashFunction,
+        join_attr_data: *const u32,
+        payload_attr_data: *const u32,
+        data_length: u64,
+        aggregation_result: *mut u64,
+    );
+
+    fn cpu_ht_probe_aggregate_chaining_uint64(
+        hash_table: *const HtEntry<u64, u64>,
+        hash_table_entries: u64,
+        hash_function: HashFunction,
+        join_attr_data: *const u64,
+        payload_attr_data: *const u64,
+        data_length: u64,
+        aggregation_result: *mut u64,
+    );
+
+    fn cpu_ht_probe_aggregate_chaining_composite(
+        hash_table: *const HtEntry<CompositeKey, CompositeKey>,
+        hash_table_entries: u64,
+        hash_function: HashFunction,
+        join_attr_data: *const CompositeKey,
+        payload_attr_data: *const CompositeKey,
+        data_length: u64,
+        aggregation_result: *mut u64,
+    );
+
+    fn cpu_ht_probe_aggregate_chaining_int32_int64(
+        hash_table: *const HtEntry<i32, i64>,
+        hash_table_entries: u64,
+        hash_function: HashFunction,
+        join_attr_data: *const i32,
+        payload_attr_data: *const i64,
+        data_length: u64,
+        aggregation_result: *mut u64,
+    );
+
+    fn cpu_ht_probe_aggregate_chaining_int64_int32(
+        hash_table: *const HtEntry<i64, i32>,
+        hash_table_entries: u64,
+        hash_function: HashFunction,
+        join_attr_data: *const i64,
+        payload_attr_data: *const i32,
+        data_length: u64,
+        aggregation_result: *mut u64,
+    );
+
+    fn cpu_ht_probe_materialize_chaining_int32(
+        hash_table: *const HtEntry<i32, i32>,
+        hash_table_entries: u64,
+        hash_function: HashFunction,
+        join_attr_data: *const i32,
+        payload_attr_data: *const i32,
+        data_length: u64,
+        output: *mut JoinPair<i32, i32>,
+        output_length: u64,
+        output_written: *mut u64,
+    ) -> u64;
+
+    fn cpu_ht_probe_materialize_chaining_int64(
+        hash_table: *const HtEntry<i64, i64>,
+        hash_table_entries: u64,
+        hash_function: HashFunction,
+        join_attr_data: *const i64,
+        payload_attr_data: *const i64,
+        data_length: u64,
+        output: *mut JoinPair<i64, i64>,
+        output_length: u64,
+        output_written: *mut u64,
+    ) -> u64;
+
+    fn cpu_ht_probe_materialize_chaining_uint32(
+        hash_table: *const HtEntry<u32, u32>,
+        hash_table_entries: u64,
+        hash_function: HashFunction,
+        join_attr_data: *const u32,
+        payload_attr_data: *const u32,
+        data_length: u64,
+        output: *mut JoinPair<u32, u32>,
+        output_length: u64,
+        output_written: *mut u64,
+    ) -> u64;
+
+    fn cpu_ht_probe_materialize_chaining_uint64(
+        hash_table: *const HtEntry<u64, u64>,
+        hash_table_entries: u64,
+        hash_function: HashFunction,
+        join_attr_data: *const u64,
+        payload_attr_data: *const u64,
+        data_length: u64,
+        output: *mut JoinPair<u64, u64>,
+        output_length: u64,
+        output_written: *mut u64,
+    ) -> u64;
+
+    fn cpu_ht_probe_materialize_chaining_composite(
+        hash_table: *const HtEntry<CompositeKey, CompositeKey>,
+        hash_table_entries: u64,
+        hash_function: HashFunction,
+        join_attr_data: *const CompositeKey,
+        payload_attr_data: *const CompositeKey,
+        data_length: u64,
+        output: *mut JoinPair<CompositeKey, CompositeKey>,
+        output_length: u64,
+        output_written: *mut u64,
+    ) -> u64;
+
+    fn cpu_ht_probe_materialize_chaining_int32_int64(
+        hash_table: *const HtEntry<i32, i64>,
+        hash_table_entries: u64,
+        hash_function: HashFunction,
+        join_attr_data: *const i32,
+        payload_attr_data: *const i64,
+        data_length: u64,
+        output: *mut JoinPair<i64, i64>,
+        output_length: u64,
+        output_written: *mut u64,
+    ) -> u64;
+
+    fn cpu_ht_probe_materialize_chaining_int64_int32(
+        hash_table: *const HtEntry<i64, i32>,
+        hash_table_entries: u64,
+        hash_function: HashFunction,
+        join_attr_data: *const i64,
+        payload_attr_data: *const i32,
+        data_length: u64,
+        output: *mut JoinPair<i32, i32>,
+        output_length: u64,
+        output_written: *mut u64,
+    ) -> u64;
 }
 
 /// Specifies that the implementing type can be used as a join key in
@@ -150,6 +851,7 @@ extern "C" {
 /// support [impl specializations with default implementations](https://github.com/rust-lang/rfcs/blob/master/text/1210-impl-specialization.md).
 /// [Rust issue #31844](https://github.com/rust-lang/rust/issues/31844) tracks
 /// the RFC.
+#[cfg(feature = "cuda")]
 pub trait CudaHashJoinable: DeviceCopy + KeyAttribute {
     /// Implements `CudaHashJoin::build` for the implementing type.
     fn build_impl(
@@ -170,24 +872,35 @@ pub trait CudaHashJoinable: DeviceCopy + KeyAttribute {
 }
 
 /// Specifies that the implementing type can be used as a join key in
-/// `CpuHashJoin`.
+/// `CpuHashJoin` with payloads of type `V`.
 ///
-/// See `CudaHashJoinable` for more details on the design decision.
-pub trait CpuHashJoinable: DeviceCopy + KeyAttribute {
+/// Specialized implementations exist for i32, i64, u32, u64, and
+/// `CompositeKey` keys with payloads of the same type. Additionally, i32 keys
+/// can be joined with i64 payloads, and i64 keys with i32 payloads. See
+/// `CudaHashJoinable` for more details on the design decision.
+pub trait CpuHashJoinable<V: DeviceCopy = Self>: DeviceCopy + KeyAttribute {
     /// Implements `CpuHashJoin::build` for the implementing type.
     fn build_impl(
-        hj: &mut CpuHashJoin<Self>,
+        hj: &mut CpuHashJoin<Self, V>,
         join_attr: &[Self],
-        payload_attr: &[Self],
+        payload_attr: &[V],
     ) -> Result<()>;
 
     /// Implements `CpuHashJoin::probe_sum` for the implementing type.
     fn probe_sum_impl(
-        hj: &mut CpuHashJoin<Self>,
+        hj: &mut CpuHashJoin<Self, V>,
         join_attr: &[Self],
-        payload_attr: &[Self],
+        payload_attr: &[V],
         join_result: &mut u64,
     ) -> Result<()>;
+
+    /// Implements `CpuHashJoin::probe_materialize` for the implementing type.
+    fn probe_materialize_impl(
+        hj: &mut CpuHashJoin<Self, V>,
+        join_attr: &[Self],
+        payload_attr: &[V],
+        output: &mut [JoinPair<V, V>],
+    ) -> Result<ProbeProgress>;
 }
 
 /// GPU hash join implemented in CUDA.
@@ -198,9 +911,11 @@ pub trait CpuHashJoinable: DeviceCopy + KeyAttribute {
 /// corresponding implementations in `CudaHashJoinable`. The wrapping is
 /// necessary due to the specialization for each type `T`. See the documentation
 /// of `CudaHashJoinable` for details.
+#[cfg(feature = "cuda")]
 #[derive(Debug)]
 pub struct CudaHashJoin<T: DeviceCopy + KeyAttribute> {
     hashing_scheme: HashingScheme,
+    hash_function: HashFunction,
     is_selective: bool,
     hash_table: Arc<HashTable<T>>,
     build_dim: (GridSize, BlockSize),
@@ -213,26 +928,32 @@ pub struct CudaHashJoin<T: DeviceCopy + KeyAttribute> {
 ///
 /// The `build` and `probe_sum` methods are simply wrappers for the
 /// corresponding implementations in `CpuHashJoinable`. The wrapping is
-/// necessary due to the specialization for each type `T`. See the documentation
-/// of `CpuHashJoinable` for details.
+/// necessary due to the specialization for each key type `K` and payload type
+/// `V`. See the documentation of `CpuHashJoinable` for details.
 #[derive(Debug)]
-pub struct CpuHashJoin<T: DeviceCopy + KeyAttribute> {
+pub struct CpuHashJoin<K: DeviceCopy + KeyAttribute, V: DeviceCopy = K> {
     hashing_scheme: HashingScheme,
+    hash_function: HashFunction,
     is_selective: bool,
-    hash_table: Arc<HashTable<T>>,
+    hash_table: Arc<HashTable<K, V>>,
 }
 
 /// Hash table for `CpuHashJoin` and `CudaHashJoin`.
+///
+/// The payload type `V` defaults to the key type `K`. `CudaHashJoin` only
+/// supports hash tables with equal key and payload types.
 #[derive(Debug)]
-pub struct HashTable<T: DeviceCopy + KeyAttribute> {
-    mem: Mem<HtEntry<T, T>>,
+pub struct HashTable<K: DeviceCopy + KeyAttribute, V: DeviceCopy = K> {
+    mem: Mem<HtEntry<K, V>>,
     size: usize,
 }
 
 /// Build a `CudaHashJoin`.
+#[cfg(feature = "cuda")]
 #[derive(Clone, Debug)]
 pub struct CudaHashJoinBuilder<T: DeviceCopy + KeyAttribute> {
     hashing_scheme: HashingScheme,
+    hash_function: HashFunction,
     is_selective: bool,
     hash_table_i: Option<Arc<HashTable<T>>>,
     build_dim_i: (GridSize, BlockSize),
@@ -241,12 +962,14 @@ pub struct CudaHashJoinBuilder<T: DeviceCopy + KeyAttribute> {
 
 /// Build a `CpuHashJoin`.
 #[derive(Clone, Debug)]
-pub struct CpuHashJoinBuilder<T: DeviceCopy + KeyAttribute> {
+pub struct CpuHashJoinBuilder<K: DeviceCopy + KeyAttribute, V: DeviceCopy = K> {
     hashing_scheme: HashingScheme,
+    hash_function: HashFunction,
     is_selective: bool,
-    hash_table_i: Option<Arc<HashTable<T>>>,
+    hash_table_i: Option<Arc<HashTable<K, V>>>,
 }
 
+#[cfg(feature = "cuda")]
 impl<T> CudaHashJoin<T>
 where
     T: DeviceCopy + KeyAttribute + CudaHashJoinable,
@@ -278,13 +1001,14 @@ where
     }
 }
 
-impl<T> CpuHashJoin<T>
+impl<K, V> CpuHashJoin<K, V>
 where
-    T: DeviceCopy + KeyAttribute + CpuHashJoinable,
+    K: DeviceCopy + KeyAttribute + CpuHashJoinable<V>,
+    V: DeviceCopy,
 {
     /// Build a hash table on the CPU.
-    pub fn build(&mut self, join_attr: &[T], payload_attr: &[T]) -> Result<()> {
-        T::build_impl(self, join_attr, payload_attr)
+    pub fn build(&mut self, join_attr: &[K], payload_attr: &[V]) -> Result<()> {
+        K::build_impl(self, join_attr, payload_attr)
     }
 
     /// Probe the hash table on the CPU and sum the payload attribute rows.
@@ -295,17 +1019,42 @@ where
     /// ```
     pub fn probe_sum(
         &mut self,
-        join_attr: &[T],
-        payload_attr: &[T],
+        join_attr: &[K],
+        payload_attr: &[V],
         join_result: &mut u64,
     ) -> Result<()> {
-        T::probe_sum_impl(self, join_attr, payload_attr, join_result)
+        K::probe_sum_impl(self, join_attr, payload_attr, join_result)
+    }
+
+    /// Probe the hash table on the CPU and materialize the join result.
+    ///
+    /// This effectively implements the SQL code:
+    /// ```SQL
+    /// SELECT r.payload_attr, s.payload_attr FROM r JOIN s ON r.join_attr = s.join_attr
+    /// ```
+    ///
+    /// Writes the matching (build payload, probe payload) pairs into `output`.
+    /// The probe stops early if `output` is too small to hold all matches of
+    /// the next probe tuple. Thus, if `ProbeProgress::probe_tuples` is less
+    /// than the length of `join_attr`, the caller should consume the output and
+    /// resume the probe from that offset.
+    ///
+    /// Returns an error if `output` cannot hold the matches of even a single
+    /// probe tuple.
+    pub fn probe_materialize(
+        &mut self,
+        join_attr: &[K],
+        payload_attr: &[V],
+        output: &mut [JoinPair<V, V>],
+    ) -> Result<ProbeProgress> {
+        K::probe_materialize_impl(self, join_attr, payload_attr, output)
     }
 }
 
 /// A Rust macro for specializing the implementation of a join key type. Each
 /// type calls a different CUDA function. The function to be called is specified
 /// by the `Suffix` parameter.
+#[cfg(feature = "cuda")]
 macro_rules! impl_cuda_hash_join_for_type {
     ($Type:ty, $Suffix:expr) => {
         impl CudaHashJoinable for $Type {
@@ -359,6 +1108,7 @@ macro_rules! impl_cuda_hash_join_for_type {
                                 module.[<gpu_ht_build_linearprobing_ $Suffix>]<<<grid, block, 0, stream>>>(
                                     hj.hash_table.mem.as_launchable_ptr(),
                                     hash_table_size,
+                                    hj.hash_function,
                                     join_attr.as_launchable_ptr(),
                                     payload_attr.as_launchable_ptr(),
                                     join_attr_len
@@ -417,6 +1167,7 @@ macro_rules! impl_cuda_hash_join_for_type {
                                 module.[<gpu_ht_probe_aggregate_linearprobing_ $Suffix>]<<<grid, block, 0, stream>>>(
                                     hj.hash_table.mem.as_launchable_ptr(),
                                     hash_table_size,
+                                    hj.hash_function,
                                     join_attr.as_launchable_ptr(),
                                     payload_attr.as_launchable_ptr(),
                                     join_attr_len,
@@ -433,17 +1184,19 @@ macro_rules! impl_cuda_hash_join_for_type {
     };
 }
 
+#[cfg(feature = "cuda")]
 impl_cuda_hash_join_for_type!(i32, int32);
+#[cfg(feature = "cuda")]
 impl_cuda_hash_join_for_type!(i64, int64);
 
-/// A Rust macro for specializing the implementation of a join key type. Each
-/// type calls a different C++ function. The function to be called is specified
-/// by the `Suffix` parameter.
+/// A Rust macro for specializing the implementation of a join key and payload
+/// type pair. Each pair calls a different C++ function. The function to be
+/// called is specified by the `Suffix` parameter.
 macro_rules! impl_cpu_hash_join_for_type {
-    ($Type:ty, $Suffix:expr) => {
-        impl CpuHashJoinable for $Type {
+    ($KeyType:ty, $PayloadType:ty, $Suffix:expr) => {
+        impl CpuHashJoinable<$PayloadType> for $KeyType {
             paste::item!{
-                fn build_impl(hj: &mut CpuHashJoin<$Type>, join_attr: &[$Type], payload_attr: &[$Type]) -> Result<()> {
+                fn build_impl(hj: &mut CpuHashJoin<$KeyType, $PayloadType>, join_attr: &[$KeyType], payload_attr: &[$PayloadType]) -> Result<()> {
                     if join_attr.len() != payload_attr.len() {
                         Err(ErrorKind::InvalidArgument(
                                 "Join and payload attributes have different sizes"
@@ -461,8 +1214,11 @@ macro_rules! impl_cpu_hash_join_for_type {
                     let join_attr_len = join_attr.len() as u64;
                     let hash_table_size = hj.hash_table.size as u64;
 
+                    let mut overflow: u64 = 0;
+
                     let region_name = cstr!("cpu_hash_join_build");
                     likwid::marker_start_region(region_name)?;
+                    let span = Span::new("cpu_hash_join_build");
 
                     match (&hj.hashing_scheme, &hj.is_selective) {
                         (HashingScheme::Perfect, false) => unsafe {
@@ -487,27 +1243,54 @@ macro_rules! impl_cpu_hash_join_for_type {
                             [<cpu_ht_build_linearprobing_ $Suffix>](
                                 hj.hash_table.mem.as_ptr() as *mut _,
                                 hash_table_size,
+                                hj.hash_function,
                                 join_attr.as_ptr(),
                                 payload_attr.as_ptr(),
                                 join_attr_len,
                                 )
                         },
                         (HashingScheme::LinearProbing, true) => unimplemented!(),
-			(HashingScheme::BucketChaining, false) => unimplemented!(),
-			(HashingScheme::BucketChaining, true) => unimplemented!(),
+                        (HashingScheme::BucketChaining, false) => unsafe {
+                            overflow = [<cpu_ht_build_chaining_ $Suffix>](
+                                hj.hash_table.mem.as_ptr() as *mut _,
+                                hash_table_size,
+                                hj.hash_function,
+                                join_attr.as_ptr(),
+                                payload_attr.as_ptr(),
+                                join_attr_len,
+                                )
+                        },
+                        (HashingScheme::BucketChaining, true) => unsafe {
+                            overflow = [<cpu_ht_build_selective_chaining_ $Suffix>](
+                                hj.hash_table.mem.as_ptr() as *mut _,
+                                hash_table_size,
+                                hj.hash_function,
+                                join_attr.as_ptr(),
+                                payload_attr.as_ptr(),
+                                join_attr_len,
+                                )
+                        },
                     };
 
+                    span.end();
                     likwid::marker_stop_region(region_name)?;
 
+                    if overflow != 0 {
+                        Err(ErrorKind::InvalidArgument(
+                                "Hash table is too small for the build data"
+                                .to_string()
+                                ))?;
+                    }
+
                     Ok(())
                 }
             }
 
             paste::item!{
                 fn probe_sum_impl(
-                    hj: &mut CpuHashJoin<$Type>,
-                    join_attr: &[$Type],
-                    payload_attr: &[$Type],
+                    hj: &mut CpuHashJoin<$KeyType, $PayloadType>,
+                    join_attr: &[$KeyType],
+                    payload_attr: &[$PayloadType],
                     join_result: &mut u64,
                     ) -> Result<()> {
 
@@ -523,6 +1306,7 @@ macro_rules! impl_cpu_hash_join_for_type {
 
                     let region_name = cstr!("cpu_hash_join_probe");
                     likwid::marker_start_region(region_name)?;
+                    let span = Span::new("cpu_hash_join_probe");
 
                     match &hj.hashing_scheme {
                         HashingScheme::Perfect => unsafe {
@@ -532,47 +1316,161 @@ macro_rules! impl_cpu_hash_join_for_type {
                                 join_attr.as_ptr(),
                                 payload_attr.as_ptr(),
                                 join_attr_len,
-                                join_result,
+                                join_result,
+                                )
+                        },
+                        HashingScheme::LinearProbing => unsafe {
+                            [<cpu_ht_probe_aggregate_linearprobing_ $Suffix>](
+                                hj.hash_table.mem.as_ptr(),
+                                hash_table_size,
+                                hj.hash_function,
+                                join_attr.as_ptr(),
+                                payload_attr.as_ptr(),
+                                join_attr_len,
+                                join_result,
+                                )
+                        },
+                        HashingScheme::BucketChaining => unsafe {
+                            [<cpu_ht_probe_aggregate_chaining_ $Suffix>](
+                                hj.hash_table.mem.as_ptr(),
+                                hash_table_size,
+                                hj.hash_function,
+                                join_attr.as_ptr(),
+                                payload_attr.as_ptr(),
+                                join_attr_len,
+                                join_result,
+                                )
+                        },
+                    };
+
+                    span.end();
+                    likwid::marker_stop_region(region_name)?;
+
+                    Ok(())
+                }
+            }
+
+            paste::item!{
+                fn probe_materialize_impl(
+                    hj: &mut CpuHashJoin<$KeyType, $PayloadType>,
+                    join_attr: &[$KeyType],
+                    payload_attr: &[$PayloadType],
+                    output: &mut [JoinPair<$PayloadType, $PayloadType>],
+                    ) -> Result<ProbeProgress> {
+
+                    if join_attr.len() != payload_attr.len() {
+                        Err(ErrorKind::InvalidArgument(
+                                "Join and payload attributes have different sizes"
+                                .to_string()
+                                ))?;
+                    }
+
+                    let join_attr_len = join_attr.len() as u64;
+                    let hash_table_size = hj.hash_table.size as u64;
+                    let mut output_written: u64 = 0;
+
+                    let region_name = cstr!("cpu_hash_join_probe_materialize");
+                    likwid::marker_start_region(region_name)?;
+                    let span = Span::new("cpu_hash_join_probe_materialize");
+
+                    let probed = match &hj.hashing_scheme {
+                        HashingScheme::Perfect => unsafe {
+                            [<cpu_ht_probe_materialize_perfect_ $Suffix>](
+                                hj.hash_table.mem.as_ptr(),
+                                hash_table_size,
+                                join_attr.as_ptr(),
+                                payload_attr.as_ptr(),
+                                join_attr_len,
+                                output.as_mut_ptr(),
+                                output.len() as u64,
+                                &mut output_written,
+                                )
+                        },
+                        HashingScheme::LinearProbing => unsafe {
+                            [<cpu_ht_probe_materialize_linearprobing_ $Suffix>](
+                                hj.hash_table.mem.as_ptr(),
+                                hash_table_size,
+                                hj.hash_function,
+                                join_attr.as_ptr(),
+                                payload_attr.as_ptr(),
+                                join_attr_len,
+                                output.as_mut_ptr(),
+                                output.len() as u64,
+                                &mut output_written,
                                 )
                         },
-                        HashingScheme::LinearProbing => unsafe {
-                            [<cpu_ht_probe_aggregate_linearprobing_ $Suffix>](
+                        HashingScheme::BucketChaining => unsafe {
+                            [<cpu_ht_probe_materialize_chaining_ $Suffix>](
                                 hj.hash_table.mem.as_ptr(),
                                 hash_table_size,
+                                hj.hash_function,
                                 join_attr.as_ptr(),
                                 payload_attr.as_ptr(),
                                 join_attr_len,
-                                join_result,
+                                output.as_mut_ptr(),
+                                output.len() as u64,
+                                &mut output_written,
                                 )
                         },
-                        HashingScheme::BucketChaining => unimplemented!(),
                     };
 
+                    span.end();
                     likwid::marker_stop_region(region_name)?;
 
-                    Ok(())
+                    if probed == 0 && join_attr_len != 0 {
+                        Err(ErrorKind::InvalidArgument(
+                                "Output buffer is too small to hold the matches of a probe tuple"
+                                .to_string()
+                                ))?;
+                    }
+
+                    Ok(ProbeProgress {
+                        probe_tuples: probed as usize,
+                        output_tuples: output_written as usize,
+                    })
                 }
             }
         }
     };
 }
 
-impl_cpu_hash_join_for_type!(i32, int32);
-impl_cpu_hash_join_for_type!(i64, int64);
+impl_cpu_hash_join_for_type!(i32, i32, int32);
+impl_cpu_hash_join_for_type!(i64, i64, int64);
+impl_cpu_hash_join_for_type!(u32, u32, uint32);
+impl_cpu_hash_join_for_type!(u64, u64, uint64);
+impl_cpu_hash_join_for_type!(CompositeKey, CompositeKey, composite);
+impl_cpu_hash_join_for_type!(i32, i64, int32_int64);
+impl_cpu_hash_join_for_type!(i64, i32, int64_int32);
 
-impl<T: AsPrimitive<c_uint> + DeviceCopy + KeyAttribute> HashTable<T> {
+impl<K, V> HashTable<K, V>
+where
+    K: DeviceCopy + KeyAttribute,
+    V: DeviceCopy + KeyAttribute,
+{
     /// Create a new CPU hash table.
     ///
     /// The hash table can be used on CPUs. In the case of NVLink 2.0 on POWER9,
     /// it can also be used on GPUs.
-    pub fn new_on_cpu(mut mem: DerefMem<HtEntry<T, T>>, size: usize) -> Result<Self> {
+    pub fn new_on_cpu(mut mem: DerefMem<HtEntry<K, V>>, size: usize) -> Result<Self> {
         if mem.len() < size {
             Err(ErrorKind::InvalidArgument(
                 "Provided memory must be larger than hash table size".to_string(),
             ))?;
         }
 
-        mem.iter_mut().by_ref().for_each(|x| x.key = T::null_key());
+        // Bucket chaining lays out its chains over the padding between keys
+        // and values. The null keys of all types have all bits set, thus set
+        // the padding bytes in the same way.
+        if size_of::<HtEntry<K, V>>() != size_of::<K>() + size_of::<V>() {
+            unsafe { ptr::write_bytes(mem.as_mut_ptr(), 0xFF, mem.len()) };
+        }
+
+        // Bucket chaining also stores its chains in the values, and thus
+        // requires the values to be null as well
+        mem.iter_mut().by_ref().for_each(|x| {
+            x.key = K::null_key();
+            x.value = V::null_key();
+        });
 
         Ok(Self {
             mem: mem.into(),
@@ -580,6 +1478,25 @@ impl<T: AsPrimitive<c_uint> + DeviceCopy + KeyAttribute> HashTable<T> {
         })
     }
 
+    /// Create a new hash table from another hash table.
+    ///
+    /// Copies the contents of the source hash table into the new hash table.
+    pub fn new_from_hash_table(mut mem: Mem<HtEntry<K, V>>, src: &Self) -> Result<Self>
+    where
+        K: Copy,
+        V: Copy,
+    {
+        mem.copy_from_mem(&src.mem)?;
+
+        Ok(Self {
+            mem,
+            size: src.size,
+        })
+    }
+}
+
+#[cfg(feature = "cuda")]
+impl<T: AsPrimitive<c_uint> + DeviceCopy + KeyAttribute> HashTable<T> {
     /// Create a new GPU hash table.
     ///
     /// The hash table can be used on GPUs. It cannot always be used on CPUs,
@@ -632,21 +1549,9 @@ impl<T: AsPrimitive<c_uint> + DeviceCopy + KeyAttribute> HashTable<T> {
 
         Ok(Self { mem, size })
     }
-
-    /// Create a new hash table from another hash table.
-    ///
-    /// Copies the contents of the source hash table into the new hash table.
-    pub fn new_from_hash_table(mut mem: Mem<HtEntry<T, T>>, src: &Self) -> Result<Self> {
-        mem.copy_from_mem(&src.mem)?;
-
-        Ok(Self {
-            mem,
-            size: src.size,
-        })
-    }
 }
 
-impl<T: DeviceCopy + KeyAttribute> MemLock for HashTable<T> {
+impl<K: DeviceCopy + KeyAttribute, V: DeviceCopy> MemLock for HashTable<K, V> {
     fn mlock(&mut self) -> NumaGpuResult<()> {
         self.mem.mlock()?;
 
@@ -660,6 +1565,7 @@ impl<T: DeviceCopy + KeyAttribute> MemLock for HashTable<T> {
     }
 }
 
+#[cfg(feature = "cuda")]
 impl<T: DeviceCopy + KeyAttribute> ::std::default::Default for CudaHashJoinBuilder<T> {
     fn default() -> Self {
         // Pre-load the CUDA module to enable callers to compute the amount of
@@ -668,6 +1574,7 @@ impl<T: DeviceCopy + KeyAttribute> ::std::default::Default for CudaHashJoinBuild
 
         Self {
             hashing_scheme: HashingScheme::default(),
+            hash_function: HashFunction::Multiplicative,
             is_selective: false,
             hash_table_i: None,
             build_dim_i: (1.into(), 1.into()),
@@ -676,6 +1583,7 @@ impl<T: DeviceCopy + KeyAttribute> ::std::default::Default for CudaHashJoinBuild
     }
 }
 
+#[cfg(feature = "cuda")]
 impl<T> CudaHashJoinBuilder<T>
 where
     T: Clone + Default + DeviceCopy + KeyAttribute,
@@ -687,6 +1595,11 @@ where
         self
     }
 
+    pub fn hash_function(mut self, hash_function: HashFunction) -> Self {
+        self.hash_function = hash_function;
+        self
+    }
+
     pub fn is_selective(mut self, is_selective: bool) -> Self {
         self.is_selective = is_selective;
         self
@@ -726,6 +1639,7 @@ where
 
         Ok(CudaHashJoin {
             hashing_scheme: self.hashing_scheme,
+            hash_function: self.hash_function,
             is_selective: self.is_selective,
             hash_table,
             build_dim: self.build_dim_i.clone(),
@@ -734,17 +1648,24 @@ where
     }
 }
 
-impl<T: DeviceCopy + KeyAttribute> ::std::default::Default for CpuHashJoinBuilder<T> {
+impl<K: DeviceCopy + KeyAttribute, V: DeviceCopy> ::std::default::Default
+    for CpuHashJoinBuilder<K, V>
+{
     fn default() -> Self {
         Self {
             hashing_scheme: HashingScheme::default(),
+            hash_function: HashFunction::Multiplicative,
             is_selective: false,
             hash_table_i: None,
         }
     }
 }
 
-impl<T: Clone + Default + DeviceCopy + KeyAttribute> CpuHashJoinBuilder<T> {
+impl<K, V> CpuHashJoinBuilder<K, V>
+where
+    K: Clone + Default + DeviceCopy + KeyAttribute,
+    V: Clone + Default + DeviceCopy,
+{
     const DEFAULT_HT_SIZE: usize = 1024;
 
     pub fn hashing_scheme(mut self, hashing_scheme: HashingScheme) -> Self {
@@ -752,21 +1673,26 @@ impl<T: Clone + Default + DeviceCopy + KeyAttribute> CpuHashJoinBuilder<T> {
         self
     }
 
+    pub fn hash_function(mut self, hash_function: HashFunction) -> Self {
+        self.hash_function = hash_function;
+        self
+    }
+
     pub fn is_selective(mut self, is_selective: bool) -> Self {
         self.is_selective = is_selective;
         self
     }
 
-    pub fn hash_table(mut self, hash_table: Arc<HashTable<T>>) -> Self {
+    pub fn hash_table(mut self, hash_table: Arc<HashTable<K, V>>) -> Self {
         self.hash_table_i = Some(hash_table);
         self
     }
 
-    pub fn build(&self) -> CpuHashJoin<T> {
+    pub fn build(&self) -> CpuHashJoin<K, V> {
         let hash_table = match &self.hash_table_i {
             Some(ht) => ht.clone(),
             None => Arc::new(HashTable {
-                mem: allocator::Allocator::alloc_mem::<HtEntry<T, T>>(
+                mem: allocator::Allocator::alloc_mem::<HtEntry<K, V>>(
                     allocator::MemType::SysMem,
                     Self::DEFAULT_HT_SIZE,
                 ),
@@ -776,34 +1702,38 @@ impl<T: Clone + Default + DeviceCopy + KeyAttribute> CpuHashJoinBuilder<T> {
 
         CpuHashJoin {
             hashing_scheme: self.hashing_scheme,
+            hash_function: self.hash_function,
             is_selective: self.is_selective,
             hash_table,
         }
     }
 }
 
-impl<T> ::std::fmt::Display for HashTable<T>
+impl<K, V> ::std::fmt::Display for HashTable<K, V>
 where
-    T: DeviceCopy + ::std::fmt::Display + KeyAttribute,
+    K: DeviceCopy + ::std::fmt::Display + KeyAttribute,
+    V: DeviceCopy + ::std::fmt::Display,
 {
     fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
-        if let CudaDevMem(_) = self.mem {
-            write!(f, "[Cannot print device memory]")?;
-        } else {
-            write!(f, "[")?;
-            match self.mem {
-                SysMem(ref m) => m.as_slice(),
-                CudaUniMem(ref m) => m.as_slice(),
-                _ => &[],
+        #[cfg(feature = "cuda")]
+        {
+            if let CudaDevMem(_) = self.mem {
+                return write!(f, "[Cannot print device memory]");
             }
-            .iter()
-            .take(self.size)
-            .map(|entry| write!(f, "{}:{},", entry.key, entry.value))
-            .collect::<::std::fmt::Result>()?;
-            write!(f, "]")?;
         }
 
-        Ok(())
+        write!(f, "[")?;
+        match self.mem {
+            SysMem(ref m) => m.as_slice(),
+            #[cfg(feature = "cuda")]
+            CudaUniMem(ref m) => m.as_slice(),
+            _ => &[],
+        }
+        .iter()
+        .take(self.size)
+        .map(|entry| write!(f, "{}:{},", entry.key, entry.value))
+        .collect::<::std::fmt::Result>()?;
+        write!(f, "]")
     }
 }
 
@@ -815,19 +1745,31 @@ impl ::std::default::Default for HashingScheme {
 
 #[cfg(test)]
 mod tests {
-    use super::{CpuHashJoinBuilder, CudaHashJoinBuilder, HashTable, HashingScheme};
+    #[cfg(feature = "cuda")]
+    use super::CudaHashJoinBuilder;
+    use super::{CpuHashJoinBuilder, HashFunction, HashTable, HashingScheme, JoinPair};
+    use crate::composite_key::CompositeKey;
     use datagen::relation::{KeyAttribute, UniformRelation};
-    use numa_gpu::runtime::allocator::{Allocator, DerefMemType, MemType};
+    #[cfg(feature = "cuda")]
+    use numa_gpu::runtime::allocator::MemType;
+    use numa_gpu::runtime::allocator::{Allocator, DerefMemType};
+    #[cfg(feature = "cuda")]
     use numa_gpu::runtime::memory::Mem;
+    #[cfg(feature = "cuda")]
     use once_cell::sync::Lazy;
+    #[cfg(feature = "cuda")]
     use rustacuda::context::{Context, CurrentContext, UnownedContext};
+    #[cfg(feature = "cuda")]
     use rustacuda::stream::{Stream, StreamFlags};
+    #[cfg(feature = "cuda")]
     use std::convert::TryInto;
     use std::error::Error;
     use std::result::Result;
     use std::sync::Arc;
 
+    #[cfg(feature = "cuda")]
     static mut CUDA_CONTEXT_OWNER: Option<Context> = None;
+    #[cfg(feature = "cuda")]
     static CUDA_CONTEXT: Lazy<UnownedContext> = Lazy::new(|| {
         let context = rustacuda::quick_init().expect("Failed to initialize CUDA context");
         let unowned = context.get_unowned();
@@ -854,10 +1796,11 @@ mod tests {
                 let mut outer_rel_key = alloc_fn(ROWS);
                 let mut outer_rel_pay = alloc_fn(ROWS);
 
-                UniformRelation::gen_primary_key(&mut inner_rel_key, None)?;
+                UniformRelation::gen_primary_key(&mut inner_rel_key, None, None)?;
                 UniformRelation::gen_foreign_key_from_primary_key(
                     &mut outer_rel_key,
                     &inner_rel_key,
+                    None,
                 );
 
                 inner_rel_pay
@@ -956,8 +1899,392 @@ mod tests {
         i64
     );
 
+    test_cpu_seq!(
+        cpu_seq_sysmem_chaining_i32,
+        DerefMemType::SysMem,
+        HashingScheme::BucketChaining,
+        false,
+        i32
+    );
+    test_cpu_seq!(
+        cpu_seq_sysmem_chaining_selective_i32,
+        DerefMemType::SysMem,
+        HashingScheme::BucketChaining,
+        true,
+        i32
+    );
+    test_cpu_seq!(
+        cpu_seq_sysmem_chaining_i64,
+        DerefMemType::SysMem,
+        HashingScheme::BucketChaining,
+        false,
+        i64
+    );
+    test_cpu_seq!(
+        cpu_seq_sysmem_chaining_selective_i64,
+        DerefMemType::SysMem,
+        HashingScheme::BucketChaining,
+        true,
+        i64
+    );
+    test_cpu_seq!(
+        cpu_seq_sysmem_perfect_selective_u32,
+        DerefMemType::SysMem,
+        HashingScheme::Perfect,
+        true,
+        u32
+    );
+    test_cpu_seq!(
+        cpu_seq_sysmem_linearprobing_u32,
+        DerefMemType::SysMem,
+        HashingScheme::LinearProbing,
+        false,
+        u32
+    );
+    test_cpu_seq!(
+        cpu_seq_sysmem_linearprobing_u64,
+        DerefMemType::SysMem,
+        HashingScheme::LinearProbing,
+        false,
+        u64
+    );
+    test_cpu_seq!(
+        cpu_seq_sysmem_chaining_selective_u64,
+        DerefMemType::SysMem,
+        HashingScheme::BucketChaining,
+        true,
+        u64
+    );
+
+    macro_rules! test_cpu_hash_function {
+        ($name:ident, $scheme:expr, $hash_function:expr, $type:ty) => {
+            #[test]
+            fn $name() -> Result<(), Box<dyn Error>> {
+                const ROWS: usize = 1 << 16;
+                const HT_LEN: usize = 2 * ROWS;
+
+                // Keys with zeroed low-order bits collide without hashing
+                let inner_rel_key: Vec<$type> = (0..ROWS as $type).map(|k| k << 8).collect();
+                let inner_rel_pay: Vec<$type> = (1..=ROWS as $type).collect();
+                let outer_rel_key: Vec<$type> = (0..2 * ROWS as $type).map(|k| k << 7).collect();
+                let outer_rel_pay: Vec<$type> = vec![1; 2 * ROWS];
+
+                let ht_mem = Allocator::alloc_deref_mem(DerefMemType::SysMem, HT_LEN);
+                let hash_table = HashTable::new_on_cpu(ht_mem, HT_LEN)?;
+
+                let mut hj_op = CpuHashJoinBuilder::default()
+                    .hashing_scheme($scheme)
+                    .hash_function($hash_function)
+                    .hash_table(Arc::new(hash_table))
+                    .build();
+
+                hj_op.build(&inner_rel_key, &inner_rel_pay)?;
+                let mut result_sum: u64 = 0;
+                hj_op.probe_sum(&outer_rel_key, &outer_rel_pay, &mut result_sum)?;
+
+                assert_eq!(ROWS as u64, result_sum);
+
+                Ok(())
+            }
+        };
+    }
+
+    test_cpu_hash_function!(
+        cpu_linearprobing_identity_i32,
+        HashingScheme::LinearProbing,
+        HashFunction::Identity,
+        i32
+    );
+    test_cpu_hash_function!(
+        cpu_linearprobing_murmur3_i32,
+        HashingScheme::LinearProbing,
+        HashFunction::Murmur3,
+        i32
+    );
+    test_cpu_hash_function!(
+        cpu_linearprobing_crc32_i64,
+        HashingScheme::LinearProbing,
+        HashFunction::Crc32,
+        i64
+    );
+    test_cpu_hash_function!(
+        cpu_chaining_identity_i64,
+        HashingScheme::BucketChaining,
+        HashFunction::Identity,
+        i64
+    );
+    test_cpu_hash_function!(
+        cpu_chaining_murmur3_i64,
+        HashingScheme::BucketChaining,
+        HashFunction::Murmur3,
+        i64
+    );
+    test_cpu_hash_function!(
+        cpu_chaining_crc32_i32,
+        HashingScheme::BucketChaining,
+        HashFunction::Crc32,
+        i32
+    );
+
+    macro_rules! test_cpu_composite {
+        ($name:ident, $scheme:expr, $hash_function:expr) => {
+            #[test]
+            fn $name() -> Result<(), Box<dyn Error>> {
+                const ROWS: usize = 1 << 16;
+                const HT_LEN: usize = 2 * ROWS;
+
+                // All probe keys match the second attribute of a build key,
+                // but only a third of them also match its first attribute
+                let inner_rel_key: Vec<CompositeKey> =
+                    (0..ROWS as u64).map(|k| CompositeKey(k % 3, k)).collect();
+                let inner_rel_pay: Vec<CompositeKey> =
+                    (0..ROWS as u64).map(|p| CompositeKey(p, 0)).collect();
+                let outer_rel_key: Vec<CompositeKey> = (0..2 * ROWS as u64)
+                    .map(|k| CompositeKey(k % 2, k / 2))
+                    .collect();
+                let outer_rel_pay: Vec<CompositeKey> = vec![CompositeKey(u64::MAX, 1); 2 * ROWS];
+
+                let ht_mem = Allocator::alloc_deref_mem(DerefMemType::SysMem, HT_LEN);
+                let hash_table = HashTable::new_on_cpu(ht_mem, HT_LEN)?;
+
+                let mut hj_op = CpuHashJoinBuilder::default()
+                    .hashing_scheme($scheme)
+                    .hash_function($hash_function)
+                    .hash_table(Arc::new(hash_table))
+                    .build();
+
+                hj_op.build(&inner_rel_key, &inner_rel_pay)?;
+                let mut result_sum: u64 = 0;
+                hj_op.probe_sum(&outer_rel_key, &outer_rel_pay, &mut result_sum)?;
+
+                let expected_sum = outer_rel_key
+                    .iter()
+                    .filter(|&&key| inner_rel_key[key.1 as usize] == key)
+                    .count();
+
+                assert_eq!(expected_sum as u64, result_sum);
+
+                Ok(())
+            }
+        };
+    }
+
+    test_cpu_composite!(
+        cpu_composite_perfect,
+        HashingScheme::Perfect,
+        HashFunction::Identity
+    );
+    test_cpu_composite!(
+        cpu_composite_linearprobing_identity,
+        HashingScheme::LinearProbing,
+        HashFunction::Identity
+    );
+    test_cpu_composite!(
+        cpu_composite_linearprobing_murmur3,
+        HashingScheme::LinearProbing,
+        HashFunction::Murmur3
+    );
+    test_cpu_composite!(
+        cpu_composite_chaining_crc32,
+        HashingScheme::BucketChaining,
+        HashFunction::Crc32
+    );
+
+    macro_rules! test_cpu_mixed {
+        ($name:ident, $scheme:expr, $key_type:ty, $payload_type:ty) => {
+            #[test]
+            fn $name() -> Result<(), Box<dyn Error>> {
+                const ROWS: usize = 1 << 16;
+                const HT_LEN: usize = 2 * ROWS;
+
+                let inner_rel_key: Vec<$key_type> = (0..ROWS as $key_type).collect();
+                let inner_rel_pay: Vec<$payload_type> = (0..ROWS as $payload_type).collect();
+                let outer_rel_key: Vec<$key_type> =
+                    (0..2 * ROWS as $key_type).map(|k| k / 2).collect();
+                let outer_rel_pay: Vec<$payload_type> =
+                    (0..2 * ROWS as $payload_type).map(|p| p + 1).collect();
+
+                let ht_mem = Allocator::alloc_deref_mem(DerefMemType::SysMem, HT_LEN);
+                let hash_table = HashTable::new_on_cpu(ht_mem, HT_LEN)?;
+
+                let mut hj_op = CpuHashJoinBuilder::default()
+                    .hashing_scheme($scheme)
+                    .hash_table(Arc::new(hash_table))
+                    .build();
+
+                hj_op.build(&inner_rel_key, &inner_rel_pay)?;
+                let mut result_sum: u64 = 0;
+                hj_op.probe_sum(&outer_rel_key, &outer_rel_pay, &mut result_sum)?;
+
+                let expected_sum = (2 * ROWS as u64 * (2 * ROWS as u64 + 1)) / 2;
+                assert_eq!(expected_sum, result_sum);
+
+                let mut output = vec![JoinPair::default(); 2 * ROWS];
+                let progress =
+                    hj_op.probe_materialize(&outer_rel_key, &outer_rel_pay, &mut output)?;
+
+                assert_eq!(2 * ROWS, progress.probe_tuples);
+                assert_eq!(2 * ROWS, progress.output_tuples);
+                assert!(output.iter().all(|pair| pair.build == (pair.probe - 1) / 2));
+
+                Ok(())
+            }
+        };
+    }
+
+    test_cpu_mixed!(cpu_mixed_perfect_i32_i64, HashingScheme::Perfect, i32, i64);
+    test_cpu_mixed!(
+        cpu_mixed_linearprobing_i32_i64,
+        HashingScheme::LinearProbing,
+        i32,
+        i64
+    );
+    test_cpu_mixed!(
+        cpu_mixed_chaining_i32_i64,
+        HashingScheme::BucketChaining,
+        i32,
+        i64
+    );
+    test_cpu_mixed!(
+        cpu_mixed_linearprobing_i64_i32,
+        HashingScheme::LinearProbing,
+        i64,
+        i32
+    );
+    test_cpu_mixed!(
+        cpu_mixed_chaining_i64_i32,
+        HashingScheme::BucketChaining,
+        i64,
+        i32
+    );
+
+    #[test]
+    fn cpu_chaining_hash_table_too_small() -> Result<(), Box<dyn Error>> {
+        const ROWS: usize = 1024;
+
+        let inner_rel_key: Vec<i32> = (0..ROWS as i32).collect();
+        let inner_rel_pay: Vec<i32> = (0..ROWS as i32).collect();
+
+        let ht_mem = Allocator::alloc_deref_mem(DerefMemType::SysMem, ROWS);
+        let hash_table = HashTable::new_on_cpu(ht_mem, ROWS)?;
+
+        let mut hj_op = CpuHashJoinBuilder::default()
+            .hashing_scheme(HashingScheme::BucketChaining)
+            .hash_table(Arc::new(hash_table))
+            .build();
+
+        assert!(hj_op.build(&inner_rel_key, &inner_rel_pay).is_err());
+
+        Ok(())
+    }
+
+    macro_rules! test_cpu_materialize {
+        ($name:ident, $scheme:expr, $output_len:expr, $type:ty) => {
+            #[test]
+            fn $name() -> Result<(), Box<dyn Error>> {
+                const ROWS: usize = 1024;
+                const HT_LEN: usize = 2 * ROWS;
+
+                let mut inner_rel_key: Vec<$type> = vec![0; ROWS];
+                let mut outer_rel_key: Vec<$type> = vec![0; ROWS];
+
+                UniformRelation::gen_primary_key(&mut inner_rel_key, None, None)?;
+                UniformRelation::gen_foreign_key_from_primary_key(
+                    &mut outer_rel_key,
+                    &inner_rel_key,
+                    None,
+                );
+
+                // Use row IDs as payloads
+                let inner_rel_pay: Vec<$type> = (0..ROWS as $type).collect();
+                let outer_rel_pay: Vec<$type> = (0..ROWS as $type).collect();
+
+                let ht_mem = Allocator::alloc_deref_mem(DerefMemType::SysMem, HT_LEN);
+                let hash_table = HashTable::new_on_cpu(ht_mem, HT_LEN)?;
+
+                let mut hj_op = CpuHashJoinBuilder::default()
+                    .hashing_scheme($scheme)
+                    .hash_table(Arc::new(hash_table))
+                    .build();
+
+                hj_op.build(&inner_rel_key, &inner_rel_pay)?;
+
+                let mut output = vec![JoinPair::default(); $output_len];
+                let mut result = Vec::new();
+                let mut offset = 0;
+                while offset < outer_rel_key.len() {
+                    let progress = hj_op.probe_materialize(
+                        &outer_rel_key[offset..],
+                        &outer_rel_pay[offset..],
+                        &mut output,
+                    )?;
+                    result.extend_from_slice(&output[0..progress.output_tuples]);
+                    offset += progress.probe_tuples;
+                }
+
+                // Nested-loop join as reference
+                let mut expected: Vec<JoinPair<$type, $type>> = outer_rel_key
+                    .iter()
+                    .zip(outer_rel_pay.iter())
+                    .flat_map(|(&probe_key, &probe)| {
+                        inner_rel_key
+                            .iter()
+                            .zip(inner_rel_pay.iter())
+                            .filter(move |(&build_key, _)| build_key == probe_key)
+                            .map(move |(_, &build)| JoinPair { build, probe })
+                    })
+                    .collect();
+
+                result.sort();
+                expected.sort();
+                assert_eq!(expected, result);
+
+                Ok(())
+            }
+        };
+    }
+
+    test_cpu_materialize!(
+        cpu_materialize_perfect_i32,
+        HashingScheme::Perfect,
+        4096,
+        i32
+    );
+    test_cpu_materialize!(
+        cpu_materialize_perfect_overflow_i64,
+        HashingScheme::Perfect,
+        100,
+        i64
+    );
+    test_cpu_materialize!(
+        cpu_materialize_linearprobing_i32,
+        HashingScheme::LinearProbing,
+        4096,
+        i32
+    );
+    test_cpu_materialize!(
+        cpu_materialize_linearprobing_overflow_i64,
+        HashingScheme::LinearProbing,
+        100,
+        i64
+    );
+
+    test_cpu_materialize!(
+        cpu_materialize_chaining_i32,
+        HashingScheme::BucketChaining,
+        4096,
+        i32
+    );
+    test_cpu_materialize!(
+        cpu_materialize_chaining_overflow_i64,
+        HashingScheme::BucketChaining,
+        100,
+        i64
+    );
+
     macro_rules! test_cuda {
         ($name:ident, $mem_type:expr, $scheme:expr, $is_selective:expr, $type:ty) => {
+            #[cfg(feature = "cuda")]
             #[test]
             fn $name() -> Result<(), Box<dyn Error>> {
                 const GRID_SIZE: u32 = 16;
@@ -974,10 +2301,11 @@ mod tests {
                 let mut outer_rel_key = alloc_fn(ROWS);
                 let mut outer_rel_pay = alloc_fn(ROWS);
 
-                UniformRelation::gen_primary_key(&mut inner_rel_key, None)?;
+                UniformRelation::gen_primary_key(&mut inner_rel_key, None, None)?;
                 UniformRelation::gen_foreign_key_from_primary_key(
                     &mut outer_rel_key,
                     &inner_rel_key,
+                    None,
                 );
 
                 inner_rel_pay
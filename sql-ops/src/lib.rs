@@ -111,16 +111,28 @@
 //! a singleton instance of the context that is only initialized once. See
 //! `sql-ops/tests/test_gpu_radix_partition.rs` as an example.
 //!
+//! # CPU-only builds
+//!
+//! The GPU operators are enabled by the default `cuda` feature. Building with
+//! `--no-default-features` skips compiling the CUDA module, and only the CPU
+//! operators are available.
+//!
 //! [fatbin]: https://docs.nvidia.com/cuda/cuda-compiler-driver-nvcc/index.html#fatbinaries
 //! [cuModuleLoad]: https://docs.nvidia.com/cuda/archive/10.2/cuda-driver-api/group__CUDA__MODULE.html#group__CUDA__MODULE_1g366093bd269dafd0af21f1c7d18115d3
 
+pub mod composite_key;
 pub mod error;
+pub mod hash_function;
 pub mod join;
 pub mod partition;
 pub mod prefix_scan;
+pub mod select;
 
+#[cfg(feature = "cuda")]
 use once_cell::sync::Lazy;
+#[cfg(feature = "cuda")]
 use rustacuda::module::Module;
+#[cfg(feature = "cuda")]
 use std::ffi::CString;
 
 #[allow(dead_code)]
@@ -132,7 +144,9 @@ pub(crate) mod constants {
 pub use constants::CACHE_LINE_SIZE as CPU_CACHE_LINE_SIZE;
 pub use constants::GPU_CACHE_LINE_SIZE;
 
+#[cfg(feature = "cuda")]
 static mut MODULE_OWNER: Option<Module> = None;
+#[cfg(feature = "cuda")]
 static MODULE: Lazy<&'static Module> = Lazy::new(|| {
     let module_path = CString::new(env!("CUDAUTILS_PATH"))
         .expect("Failed to load CUDA module, check your CUDAUTILS_PATH");
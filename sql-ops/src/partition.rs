@@ -15,19 +15,22 @@
 //! A collection of partitioning operators.
 
 use crate::error::{Error, ErrorKind};
-use rustacuda::memory::DeviceCopy;
+use numa_gpu::runtime::memory::DeviceCopy;
 use std::convert::TryFrom;
 
+pub mod cpu_multi_pass_radix_partition;
 pub mod cpu_radix_partition;
+#[cfg(feature = "cuda")]
 pub mod gpu_radix_partition;
 mod partition_input_chunk;
 pub mod partitioned_relation;
+pub mod skew;
 
 // Export structs
 pub use partition_input_chunk::{RadixPartitionInputChunk, RadixPartitionInputChunkable};
 pub use partitioned_relation::{
     PartitionOffsets, PartitionOffsetsChunksMut, PartitionOffsetsMutSlice, PartitionedRelation,
-    PartitionedRelationChunksMut, PartitionedRelationMutSlice,
+    PartitionedRelationChunksMut, PartitionedRelationMutSlice, SharedRelationMutPtr,
 };
 
 /// Histogram algorithm type
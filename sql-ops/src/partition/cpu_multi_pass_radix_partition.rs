@@ -0,0 +1,538 @@
+// Copyright 2022 Clemens Lutz
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Multi-pass radix partitioning on the CPU.
+//!
+//! `CpuRadixPartitioner` runs a single radix pass over a chunk of the input.
+//! Partitioning with more than one pass requires gathering each partition of
+//! the previous pass from all chunks, and partitioning it again with the next
+//! pass's radix bits. `CpuMultiPassRadixPartitioner` drives all passes
+//! configured in a `RadixBits` and returns the final partitioned relation.
+//!
+//! # Algorithm
+//!
+//! The first pass partitions the input in parallel, with one chunk per
+//! thread. Afterwards, each thread processes a contiguous range of first pass
+//! partitions. The thread gathers each partition from all chunks, and
+//! recursively partitions it with the remaining passes. The leaf partitions
+//! are copied into the output relation.
+//!
+//! The output location of each first pass partition is known after the first
+//! pass's prefix sum. Thus, the threads write to disjunct ranges of the output
+//! relation and don't need to synchronize.
+//!
+//! # Partition IDs
+//!
+//! The output relation has a single chunk and `RadixBits::fanout()`
+//! partitions. Partitions are ordered by their pass, i.e., the partition
+//! `(p1, p2, p3)` of the passes `First`, `Second`, and `Third` has the ID
+//! `p1 * f2 * f3 + p2 * f3 + p3`, where `f2` and `f3` are the fanouts of the
+//! second and third pass.
+//!
+//! Note that the first pass uses the lowest radix bits (see
+//! `RadixBits::pass_ignore_bits`). Thus, the partition ID does not equal the
+//! radix bits of the key.
+
+use super::cpu_radix_partition::{
+    CpuHistogramAlgorithm, CpuRadixPartitionAlgorithm, CpuRadixPartitionable, CpuRadixPartitioner,
+};
+use super::{
+    fanout, HistogramAlgorithmType, PartitionOffsets, PartitionedRelation, RadixBits,
+    RadixPartitionInputChunk, RadixPartitionInputChunkable, RadixPass, Tuple,
+};
+use crate::error::{ErrorKind, Result};
+use crate::hash_function::HashFunction;
+use numa_gpu::runtime::allocator::{Allocator, DerefMemType};
+use numa_gpu::runtime::memory::{DerefMem, DeviceCopy};
+use rayon::ThreadPool;
+use std::convert::TryInto;
+
+/// A CPU radix partitioner that runs all passes specified by `RadixBits`.
+#[derive(Debug)]
+pub struct CpuMultiPassRadixPartitioner {
+    prefix_sum_algorithm: CpuHistogramAlgorithm,
+    partition_algorithm: CpuRadixPartitionAlgorithm,
+    radix_bits: RadixBits,
+    hash_function: HashFunction,
+    state_mem_type: DerefMemType,
+}
+
+impl CpuMultiPassRadixPartitioner {
+    /// Creates a new multi-pass CPU radix partitioner.
+    ///
+    /// All passes use the same histogram and partitioning algorithms. The
+    /// per-thread state is allocated in `state_mem_type`.
+    ///
+    /// Returns an error if the first pass has no radix bits, or if the third
+    /// pass is specified without the second pass.
+    pub fn new(
+        prefix_sum_algorithm: CpuHistogramAlgorithm,
+        partition_algorithm: CpuRadixPartitionAlgorithm,
+        radix_bits: &RadixBits,
+        state_mem_type: DerefMemType,
+    ) -> Result<Self> {
+        if radix_bits.pass_radix_bits(RadixPass::First).is_none() {
+            Err(ErrorKind::InvalidArgument(
+                "No radix bits specified for First pass".to_string(),
+            ))?;
+        }
+        if radix_bits.pass_radix_bits(RadixPass::Second).is_none()
+            && radix_bits.pass_radix_bits(RadixPass::Third).is_some()
+        {
+            Err(ErrorKind::InvalidArgument(
+                "Third pass requires radix bits for the Second pass".to_string(),
+            ))?;
+        }
+
+        Ok(Self {
+            prefix_sum_algorithm,
+            partition_algorithm,
+            radix_bits: *radix_bits,
+            hash_function: HashFunction::default(),
+            state_mem_type,
+        })
+    }
+
+    /// Sets the hash function applied to the keys before partitioning.
+    ///
+    /// All passes use the same hash function. By default, the keys are
+    /// partitioned by their identity.
+    pub fn with_hash_function(mut self, hash_function: HashFunction) -> Self {
+        self.hash_function = hash_function;
+        self
+    }
+
+    /// Radix-partitions a relation by its key attribute in all passes.
+    ///
+    /// Runs on the threads of `thread_pool`. The first pass splits the input
+    /// into one chunk per thread. The intermediate and final partitioned
+    /// relations are allocated in `partitions_mem_type`.
+    ///
+    /// Returns a `PartitionedRelation` with a single chunk. See the
+    /// module-level documentation for the order of the partitions.
+    pub fn partition<K, V>(
+        &self,
+        thread_pool: &ThreadPool,
+        partition_attr: &[K],
+        payload_attr: &[V],
+        partitions_mem_type: DerefMemType,
+    ) -> Result<PartitionedRelation<Tuple<K, V>>>
+    where
+        K: Copy + Default + DeviceCopy + CpuRadixPartitionable<V> + Send + Sync,
+        V: Copy + Default + DeviceCopy + Send + Sync,
+    {
+        if partition_attr.len() != payload_attr.len() {
+            Err(ErrorKind::InvalidArgument(
+                "Key and payload attributes have different lengths".to_string(),
+            ))?;
+        }
+
+        let threads = thread_pool.current_num_threads();
+        let num_chunks = threads as u32;
+        let radix_bits_fst = self.radix_bits.pass_radix_bits(RadixPass::First).unwrap();
+        let fanout_fst = fanout(radix_bits_fst);
+        let later_passes: Vec<RadixPass> = [RadixPass::Second, RadixPass::Third]
+            .iter()
+            .copied()
+            .filter(|&pass| self.radix_bits.pass_radix_bits(pass).is_some())
+            .collect();
+
+        // First pass
+        let mut radix_prnrs = (0..threads)
+            .map(|_| {
+                Ok(CpuRadixPartitioner::new_with_pass(
+                    self.prefix_sum_algorithm,
+                    self.partition_algorithm,
+                    &self.radix_bits,
+                    RadixPass::First,
+                    self.state_mem_type.clone(),
+                )?
+                .with_hash_function(self.hash_function))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let mut partition_offsets_fst = PartitionOffsets::try_new(
+            self.prefix_sum_algorithm.into(),
+            num_chunks,
+            radix_bits_fst,
+            Allocator::try_mem_alloc_fn(partitions_mem_type.clone().into()),
+        )?;
+
+        let mut partitioned_relation_fst = PartitionedRelation::try_new(
+            partition_attr.len(),
+            self.prefix_sum_algorithm.into(),
+            radix_bits_fst,
+            num_chunks,
+            Allocator::try_mem_alloc_fn(partitions_mem_type.clone().into()),
+            Allocator::try_mem_alloc_fn(partitions_mem_type.clone().into()),
+        )?;
+
+        let mut results: Vec<Result<()>> = (0..threads).map(|_| Ok(())).collect();
+        let key_chunks = partition_attr.input_chunks::<K>(num_chunks)?;
+        thread_pool.scope(|s| {
+            for ((radix_prnr, key_chunk), (offsets_chunk, result)) in radix_prnrs
+                .iter_mut()
+                .zip(key_chunks.into_iter())
+                .zip(partition_offsets_fst.chunks_mut().zip(results.iter_mut()))
+            {
+                s.spawn(move |_| {
+                    *result = radix_prnr.prefix_sum(key_chunk, offsets_chunk);
+                })
+            }
+        });
+        results
+            .iter_mut()
+            .try_for_each(|result| result_take(result))?;
+
+        let key_chunks = partition_attr.input_chunks::<K>(num_chunks)?;
+        let pay_chunks = payload_attr.input_chunks::<K>(num_chunks)?;
+        thread_pool.scope(|s| {
+            for (
+                ((radix_prnr, key_chunk), (pay_chunk, offsets_chunk)),
+                (partitioned_chunk, result),
+            ) in radix_prnrs
+                .iter_mut()
+                .zip(key_chunks.into_iter())
+                .zip(
+                    pay_chunks
+                        .into_iter()
+                        .zip(partition_offsets_fst.chunks_mut()),
+                )
+                .zip(
+                    partitioned_relation_fst
+                        .chunks_mut()
+                        .zip(results.iter_mut()),
+                )
+            {
+                s.spawn(move |_| {
+                    *result = radix_prnr.partition(
+                        key_chunk,
+                        pay_chunk,
+                        offsets_chunk,
+                        partitioned_chunk,
+                    );
+                })
+            }
+        });
+        results
+            .iter_mut()
+            .try_for_each(|result| result_take(result))?;
+
+        // Later passes
+        let partition_lens_fst = (0..fanout_fst)
+            .map(|partition_id| partition_offsets_fst.partition_len(partition_id))
+            .collect::<Result<Vec<_>>>()?;
+        let max_partition_len = partition_lens_fst.iter().copied().max().unwrap_or(0);
+
+        let mut thread_states = (0..threads)
+            .map(|_| {
+                later_passes
+                    .iter()
+                    .map(|&pass| {
+                        PassState::new(
+                            self.prefix_sum_algorithm,
+                            self.partition_algorithm,
+                            &self.radix_bits,
+                            pass,
+                            self.hash_function,
+                            max_partition_len,
+                            self.state_mem_type.clone(),
+                        )
+                    })
+                    .collect::<Result<Vec<_>>>()
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let fanout_later = fanout(self.radix_bits.radix_bits() - radix_bits_fst) as usize;
+        let mut partitioned_relation = PartitionedRelation::try_new(
+            partition_attr.len(),
+            HistogramAlgorithmType::Contiguous,
+            self.radix_bits.radix_bits(),
+            1,
+            Allocator::try_mem_alloc_fn(partitions_mem_type.clone().into()),
+            Allocator::try_mem_alloc_fn(partitions_mem_type.into()),
+        )?;
+        let padding_len = partitioned_relation.padding_len() as usize;
+
+        let relation: &mut [Tuple<K, V>] = (&mut partitioned_relation.relation)
+            .try_into()
+            .map_err(|_| {
+                ErrorKind::RuntimeError("Partitions must be accessible by the CPU".to_string())
+            })?;
+        let offsets: &mut [u64] = (&mut partitioned_relation.offsets)
+            .try_into()
+            .map_err(|_| {
+                ErrorKind::RuntimeError("Partitions must be accessible by the CPU".to_string())
+            })?;
+
+        // Split the output into disjunct ranges, one per first pass partition
+        let mut writers = Vec::with_capacity(fanout_fst as usize);
+        let mut relation_tail = relation;
+        let mut offset = 0;
+        for (&partition_len, offsets_chunk) in partition_lens_fst
+            .iter()
+            .zip(offsets.chunks_mut(fanout_later))
+        {
+            let range_len = partition_len + fanout_later * padding_len;
+            let (range, tail) = relation_tail.split_at_mut(range_len);
+            writers.push(PartitionWriter {
+                relation: range,
+                offsets: offsets_chunk,
+                base: offset,
+                pos: 0,
+                partition_id: 0,
+                padding_len,
+            });
+            relation_tail = tail;
+            offset += range_len;
+        }
+
+        let partitions_per_thread = (fanout_fst as usize + threads - 1) / threads;
+        let num_chunks_fst = partitioned_relation_fst.num_chunks();
+        let partitioned_relation_fst = &partitioned_relation_fst;
+        thread_pool.scope(|s| {
+            for ((thread_id, passes), (writers_chunk, result)) in
+                (0..threads).zip(thread_states.iter_mut()).zip(
+                    writers
+                        .chunks_mut(partitions_per_thread)
+                        .zip(results.iter_mut()),
+                )
+            {
+                s.spawn(move |_| {
+                    *result = writers_chunk
+                        .iter_mut()
+                        .enumerate()
+                        .try_for_each(|(i, writer)| {
+                            let partition_id = (thread_id * partitions_per_thread + i) as u32;
+                            let chunks = (0..num_chunks_fst).map(|chunk_id| {
+                                &partitioned_relation_fst[(chunk_id, partition_id)]
+                            });
+
+                            if let Some(pass) = passes.first_mut() {
+                                let len = pass.gather(chunks);
+                                partition_recursive(passes, len, writer)
+                            } else {
+                                writer.begin_partition();
+                                chunks.for_each(|tuples| writer.extend(tuples));
+                                Ok(())
+                            }
+                        });
+                })
+            }
+        });
+        results
+            .iter_mut()
+            .try_for_each(|result| result_take(result))?;
+
+        Ok(partitioned_relation)
+    }
+}
+
+/// Returns the result of a thread, and resets it for reuse.
+fn result_take(result: &mut Result<()>) -> Result<()> {
+    std::mem::replace(result, Ok(()))
+}
+
+/// Partitions the gathered tuples of the first pass in `passes`, and
+/// recursively partitions each resulting partition in the remaining passes.
+fn partition_recursive<K, V>(
+    passes: &mut [PassState<K, V>],
+    len: usize,
+    writer: &mut PartitionWriter<'_, Tuple<K, V>>,
+) -> Result<()>
+where
+    K: Copy + Default + DeviceCopy + CpuRadixPartitionable<V>,
+    V: Copy + Default + DeviceCopy,
+{
+    let (pass, next_passes) = match passes.split_first_mut() {
+        Some(split) => split,
+        None => return Ok(()),
+    };
+
+    pass.partition(len)?;
+
+    for partition_id in 0..pass.partitioned_relation.fanout() {
+        let tuples = &pass.partitioned_relation[(0, partition_id)];
+
+        if let Some(next_pass) = next_passes.first_mut() {
+            let len = next_pass.gather(std::iter::once(tuples));
+            partition_recursive(next_passes, len, writer)?;
+        } else {
+            writer.begin_partition();
+            writer.extend(tuples);
+        }
+    }
+
+    Ok(())
+}
+
+/// Thread-local state of a radix pass after the first pass.
+///
+/// Each partition of the previous pass is gathered into the key and payload
+/// buffers, because the CPU partitioner takes columns as input.
+struct PassState<K: DeviceCopy, V: DeviceCopy> {
+    radix_prnr: CpuRadixPartitioner,
+    partition_key: DerefMem<K>,
+    partition_pay: DerefMem<V>,
+    partition_offsets: PartitionOffsets<Tuple<K, V>>,
+    partitioned_relation: PartitionedRelation<Tuple<K, V>>,
+}
+
+impl<K, V> PassState<K, V>
+where
+    K: Copy + Default + DeviceCopy + CpuRadixPartitionable<V>,
+    V: Copy + Default + DeviceCopy,
+{
+    fn new(
+        prefix_sum_algorithm: CpuHistogramAlgorithm,
+        partition_algorithm: CpuRadixPartitionAlgorithm,
+        radix_bits: &RadixBits,
+        radix_pass: RadixPass,
+        hash_function: HashFunction,
+        max_partition_len: usize,
+        state_mem_type: DerefMemType,
+    ) -> Result<Self> {
+        let pass_radix_bits = radix_bits.pass_radix_bits(radix_pass).ok_or_else(|| {
+            ErrorKind::InvalidArgument(format!("No radix bits specified for {:?} pass", radix_pass))
+        })?;
+
+        Ok(Self {
+            radix_prnr: CpuRadixPartitioner::new_with_pass(
+                prefix_sum_algorithm,
+                partition_algorithm,
+                radix_bits,
+                radix_pass,
+                state_mem_type.clone(),
+            )?
+            .with_hash_function(hash_function),
+            partition_key: Allocator::try_alloc_deref_mem(
+                state_mem_type.clone(),
+                max_partition_len,
+            )?,
+            partition_pay: Allocator::try_alloc_deref_mem(
+                state_mem_type.clone(),
+                max_partition_len,
+            )?,
+            partition_offsets: PartitionOffsets::try_new(
+                prefix_sum_algorithm.into(),
+                1,
+                pass_radix_bits,
+                Allocator::try_mem_alloc_fn(state_mem_type.clone().into()),
+            )?,
+            partitioned_relation: PartitionedRelation::try_new(
+                max_partition_len,
+                prefix_sum_algorithm.into(),
+                pass_radix_bits,
+                1,
+                Allocator::try_mem_alloc_fn(state_mem_type.clone().into()),
+                Allocator::try_mem_alloc_fn(state_mem_type.into()),
+            )?,
+        })
+    }
+
+    /// Gathers the tuples into the key and payload buffers, and returns the
+    /// number of gathered tuples.
+    fn gather<'t, I>(&mut self, partitions: I) -> usize
+    where
+        I: Iterator<Item = &'t [Tuple<K, V>]>,
+        K: 't,
+        V: 't,
+    {
+        let tuples = partitions.flat_map(|partition| partition.iter());
+        let mut len = 0;
+        for ((key, pay), tuple) in self
+            .partition_key
+            .iter_mut()
+            .zip(self.partition_pay.iter_mut())
+            .zip(tuples)
+        {
+            *key = tuple.key;
+            *pay = tuple.value;
+            len += 1;
+        }
+
+        len
+    }
+
+    /// Partitions the first `len` gathered tuples.
+    fn partition(&mut self, len: usize) -> Result<()> {
+        self.partitioned_relation.resize(len)?;
+
+        let key_slice: &[K] = &self.partition_key[0..len];
+        let pay_slice: &[V] = &self.partition_pay[0..len];
+
+        let offsets_chunk = self
+            .partition_offsets
+            .chunks_mut()
+            .next()
+            .ok_or_else(|| ErrorKind::RuntimeError("Failed to get offsets chunk".into()))?;
+        self.radix_prnr
+            .prefix_sum(single_chunk::<K, K>(key_slice)?, offsets_chunk)?;
+
+        let offsets_chunk = self
+            .partition_offsets
+            .chunks_mut()
+            .next()
+            .ok_or_else(|| ErrorKind::RuntimeError("Failed to get offsets chunk".into()))?;
+        let partitioned_chunk = self
+            .partitioned_relation
+            .chunks_mut()
+            .next()
+            .ok_or_else(|| ErrorKind::RuntimeError("Failed to get partitioned chunk".into()))?;
+        self.radix_prnr.partition(
+            single_chunk::<K, K>(key_slice)?,
+            single_chunk::<K, V>(pay_slice)?,
+            offsets_chunk,
+            partitioned_chunk,
+        )?;
+
+        Ok(())
+    }
+}
+
+/// Returns the whole slice as a single input chunk.
+fn single_chunk<Key, T>(data: &[T]) -> Result<RadixPartitionInputChunk<'_, T>> {
+    data.input_chunks::<Key>(1)?
+        .into_iter()
+        .next()
+        .ok_or_else(|| ErrorKind::RuntimeError("Failed to get input chunk".into()).into())
+}
+
+/// Writes the partitions of one first pass partition into its range of the
+/// output relation.
+///
+/// Each partition is padded in front, and its offset is set relative to the
+/// beginning of the output relation.
+struct PartitionWriter<'a, T> {
+    relation: &'a mut [T],
+    offsets: &'a mut [u64],
+    base: usize,
+    pos: usize,
+    partition_id: usize,
+    padding_len: usize,
+}
+
+impl<'a, T: Copy> PartitionWriter<'a, T> {
+    fn begin_partition(&mut self) {
+        self.pos += self.padding_len;
+        self.offsets[self.partition_id] = (self.base + self.pos) as u64;
+        self.partition_id += 1;
+    }
+
+    fn extend(&mut self, tuples: &[T]) {
+        let end = self.pos + tuples.len();
+        self.relation[self.pos..end].copy_from_slice(tuples);
+        self.pos = end;
+    }
+}
@@ -26,6 +26,10 @@
 //! et al. in Section 6 of "An Experimental Comparison of Thirteen Relational
 //! Equi-Joins in Main Memory".
 //!
+//! `CpuRadixPartitioner` runs a single radix pass. To partition a relation
+//! with all passes specified by a `RadixBits`, use
+//! `cpu_multi_pass_radix_partition::CpuMultiPassRadixPartitioner`.
+//!
 //! # Thread-safety
 //!
 //! The radix partitioning operators are designed to be thread-safe. Although the
@@ -61,6 +65,18 @@
 //! into the CPU's L1 cache. This upper bound for buckets is given by the
 //! GPU hardware and partitioning algorithm.
 //!
+//! # Hash functions
+//!
+//! By default, keys are partitioned by their low-order bits. If the keys have
+//! a skewed bit pattern, a `HashFunction` can be set with
+//! `CpuRadixPartitioner::with_hash_function`. The radix bits are then taken
+//! from the key's hash value. All devices that cooperate on a relation, as
+//! well as the join of the partitioned relations, must use the same hash
+//! function.
+//!
+//! Note that the SIMD variants only support the identity hash function, and
+//! fall back to the scalar variants for all other functions.
+//!
 //! # Optimizations and tuning
 //!
 //! ## Hardware prefetching
@@ -80,7 +96,14 @@
 //!
 //! ## SIMD vectorization
 //!
-//! Keys are hashed using VSX instructions on POWER9.
+//! Keys are hashed using VSX instructions on POWER9. On x86-64, the partition
+//! IDs of a batch of keys are computed with AVX-512 instructions if the CPU
+//! supports them, and with AVX2 instructions otherwise. The instruction set is
+//! detected at run-time, and CPUs without AVX2 take the scalar code path.
+//! `CpuRadixPartitioner::with_x86_simd_isa` limits the instruction set, e.g.,
+//! to run the AVX2 code path on AVX-512 CPUs. As AVX2 lacks scatter
+//! instructions, the histogram and buffer updates remain scalar. Composite
+//! keys always take the scalar code path.
 //!
 //! ## Data hazard avoidance
 //!
@@ -120,6 +143,9 @@
 //!
 //!  - Add SIMD partitioning variant for IBM POWER9.
 //!
+//!  - Add SIMD prefix sum and partitioning variants for x86-64 AVX2 and
+//!  AVX-512.
+//!
 //!  - Avoid data hazards in the prefix sum.
 //!
 //!  - Tune the hardware prefetcher for IBM POWER9.
@@ -128,14 +154,16 @@
 
 use super::{
     fanout, HistogramAlgorithmType, PartitionOffsetsMutSlice, PartitionedRelationMutSlice,
-    RadixPartitionInputChunk, Tuple,
+    RadixBits, RadixPartitionInputChunk, RadixPass, Tuple,
 };
+use crate::composite_key::CompositeKey;
 use crate::constants;
 use crate::error::{ErrorKind, Result};
-use numa_gpu::runtime::allocator::{Allocator, DerefMemAllocFn, DerefMemType};
-use numa_gpu::runtime::memory::DerefMem;
+use crate::hash_function::HashFunction;
+use numa_gpu::runtime::allocator::{Allocator, DerefMemType, TryDerefMemAllocFn};
+use numa_gpu::runtime::memory::{DerefMem, DeviceCopy};
+use numa_gpu::runtime::trace::Span;
 use numa_gpu::utils::CachePadded;
-use rustacuda::memory::DeviceCopy;
 use std::ffi::c_void;
 use std::{mem, ptr};
 
@@ -143,18 +171,51 @@ extern "C" {
     fn cpu_swwc_buffer_bytes() -> usize;
     fn cpu_chunked_prefix_sum_int32(args: *mut PrefixSumArgs, chunk_id: u32, num_chunks: u32);
     fn cpu_chunked_prefix_sum_int64(args: *mut PrefixSumArgs, chunk_id: u32, num_chunks: u32);
-    #[cfg(target_arch = "powerpc64")]
+    fn cpu_chunked_prefix_sum_uint32(args: *mut PrefixSumArgs, chunk_id: u32, num_chunks: u32);
+    fn cpu_chunked_prefix_sum_uint64(args: *mut PrefixSumArgs, chunk_id: u32, num_chunks: u32);
+    fn cpu_chunked_prefix_sum_composite(args: *mut PrefixSumArgs, chunk_id: u32, num_chunks: u32);
+    #[cfg(any(target_arch = "powerpc64", target_arch = "x86_64"))]
     fn cpu_chunked_prefix_sum_simd_int32(args: *mut PrefixSumArgs, chunk_id: u32, num_chunks: u32);
-    #[cfg(target_arch = "powerpc64")]
+    #[cfg(any(target_arch = "powerpc64", target_arch = "x86_64"))]
     fn cpu_chunked_prefix_sum_simd_int64(args: *mut PrefixSumArgs, chunk_id: u32, num_chunks: u32);
+    #[cfg(any(target_arch = "powerpc64", target_arch = "x86_64"))]
+    fn cpu_chunked_prefix_sum_simd_uint32(args: *mut PrefixSumArgs, chunk_id: u32, num_chunks: u32);
+    #[cfg(any(target_arch = "powerpc64", target_arch = "x86_64"))]
+    fn cpu_chunked_prefix_sum_simd_uint64(args: *mut PrefixSumArgs, chunk_id: u32, num_chunks: u32);
+    #[cfg(any(target_arch = "powerpc64", target_arch = "x86_64"))]
+    fn cpu_chunked_prefix_sum_simd_composite(
+        args: *mut PrefixSumArgs,
+        chunk_id: u32,
+        num_chunks: u32,
+    );
     fn cpu_chunked_radix_partition_int32_int32(args: *mut RadixPartitionArgs);
     fn cpu_chunked_radix_partition_int64_int64(args: *mut RadixPartitionArgs);
+    fn cpu_chunked_radix_partition_uint32_uint32(args: *mut RadixPartitionArgs);
+    fn cpu_chunked_radix_partition_uint64_uint64(args: *mut RadixPartitionArgs);
+    fn cpu_chunked_radix_partition_composite_composite(args: *mut RadixPartitionArgs);
+    fn cpu_chunked_radix_partition_int32_int64(args: *mut RadixPartitionArgs);
+    fn cpu_chunked_radix_partition_int64_int32(args: *mut RadixPartitionArgs);
     fn cpu_chunked_radix_partition_swwc_int32_int32(args: *mut RadixPartitionArgs);
     fn cpu_chunked_radix_partition_swwc_int64_int64(args: *mut RadixPartitionArgs);
-    #[cfg(target_arch = "powerpc64")]
+    fn cpu_chunked_radix_partition_swwc_uint32_uint32(args: *mut RadixPartitionArgs);
+    fn cpu_chunked_radix_partition_swwc_uint64_uint64(args: *mut RadixPartitionArgs);
+    fn cpu_chunked_radix_partition_swwc_composite_composite(args: *mut RadixPartitionArgs);
+    fn cpu_chunked_radix_partition_swwc_int32_int64(args: *mut RadixPartitionArgs);
+    fn cpu_chunked_radix_partition_swwc_int64_int32(args: *mut RadixPartitionArgs);
+    #[cfg(any(target_arch = "powerpc64", target_arch = "x86_64"))]
     fn cpu_chunked_radix_partition_swwc_simd_int32_int32(args: *mut RadixPartitionArgs);
-    #[cfg(target_arch = "powerpc64")]
+    #[cfg(any(target_arch = "powerpc64", target_arch = "x86_64"))]
     fn cpu_chunked_radix_partition_swwc_simd_int64_int64(args: *mut RadixPartitionArgs);
+    #[cfg(any(target_arch = "powerpc64", target_arch = "x86_64"))]
+    fn cpu_chunked_radix_partition_swwc_simd_uint32_uint32(args: *mut RadixPartitionArgs);
+    #[cfg(any(target_arch = "powerpc64", target_arch = "x86_64"))]
+    fn cpu_chunked_radix_partition_swwc_simd_uint64_uint64(args: *mut RadixPartitionArgs);
+    #[cfg(any(target_arch = "powerpc64", target_arch = "x86_64"))]
+    fn cpu_chunked_radix_partition_swwc_simd_composite_composite(args: *mut RadixPartitionArgs);
+    #[cfg(any(target_arch = "powerpc64", target_arch = "x86_64"))]
+    fn cpu_chunked_radix_partition_swwc_simd_int32_int64(args: *mut RadixPartitionArgs);
+    #[cfg(any(target_arch = "powerpc64", target_arch = "x86_64"))]
+    fn cpu_chunked_radix_partition_swwc_simd_int64_int32(args: *mut RadixPartitionArgs);
 }
 
 /// Arguments to the C/C++ prefix sum function.
@@ -171,6 +232,8 @@ struct PrefixSumArgs {
     padding_len: u32,
     radix_bits: u32,
     ignore_bits: u32,
+    hash_function: HashFunction,
+    simd_isa: X86SimdIsa,
 
     // State
     tmp_partition_offsets: *mut u32,
@@ -193,6 +256,8 @@ struct RadixPartitionArgs {
     padding_len: usize, // FIXME: u32
     radix_bits: u32,
     ignore_bits: u32,
+    hash_function: HashFunction,
+    simd_isa: X86SimdIsa,
     partition_offsets: *const u64,
 
     // State
@@ -225,15 +290,15 @@ struct WriteCombineBuffer {
 
 impl WriteCombineBuffer {
     /// Creates a new set of SWWC buffers.
-    fn new(radix_bits: u32, alloc_fn: DerefMemAllocFn<u64>) -> Self {
+    fn new(radix_bits: u32, alloc_fn: TryDerefMemAllocFn<u64>) -> Result<Self> {
         let buffer_bytes = unsafe { cpu_swwc_buffer_bytes() };
         assert!(buffer_bytes as u32 <= constants::PADDING_BYTES, "Partition padding is too small for the SWWC buffers; padding must be at least {} bytes", buffer_bytes);
 
         let align_bytes = mem::align_of::<CachePadded<u64>>();
         let bytes = buffer_bytes * fanout(radix_bits) as usize + align_bytes;
-        let raw_memory = alloc_fn(bytes / mem::size_of::<u64>());
+        let raw_memory = alloc_fn(bytes / mem::size_of::<u64>())?;
 
-        Self { raw_memory }
+        Ok(Self { raw_memory })
     }
 
     /// Returns a mutable slice to the aligned buffers
@@ -262,26 +327,30 @@ impl WriteCombineBuffer {
 }
 
 /// Specifies that the implementing type can be used as partitioning key in
-/// `CpuRadixPartitioner`.
+/// `CpuRadixPartitioner`, with payloads of type `V`.
 ///
 /// `CpuRadixPartitionable` is a trait for which specialized implementations
-/// exist for each implementing type (currently i32 and i64). Specialization is
+/// exist for each implementing type (currently i32, i64, u32, u64, and
+/// `CompositeKey`). Specialization is
 /// necessary because each type requires a different C++ function to be called.
 ///
+/// Keys and payloads usually have the same type. Additionally, i32 keys can be
+/// partitioned with i64 payloads, and i64 keys with i32 payloads.
+///
 /// See `CudaHashJoinable` for more details on the design decision.
-pub trait CpuRadixPartitionable: Sized + DeviceCopy {
+pub trait CpuRadixPartitionable<V: DeviceCopy = Self>: Sized + DeviceCopy {
     fn prefix_sum_impl(
         rp: &mut CpuRadixPartitioner,
         partition_attr: RadixPartitionInputChunk<'_, Self>,
-        partition_offsets: PartitionOffsetsMutSlice<'_, Tuple<Self, Self>>,
+        partition_offsets: PartitionOffsetsMutSlice<'_, Tuple<Self, V>>,
     ) -> Result<()>;
 
     fn partition_impl(
         rp: &mut CpuRadixPartitioner,
         partition_attr: RadixPartitionInputChunk<'_, Self>,
-        payload_attr: RadixPartitionInputChunk<'_, Self>,
-        partition_offsets: PartitionOffsetsMutSlice<Tuple<Self, Self>>,
-        partitioned_relation: PartitionedRelationMutSlice<Tuple<Self, Self>>,
+        payload_attr: RadixPartitionInputChunk<'_, V>,
+        partition_offsets: PartitionOffsetsMutSlice<Tuple<Self, V>>,
+        partitioned_relation: PartitionedRelationMutSlice<Tuple<Self, V>>,
     ) -> Result<()>;
 }
 
@@ -300,10 +369,27 @@ pub enum CpuHistogramAlgorithm {
     /// This is the same algorithm as `Chunked`, but uses SIMD load instructions. Also, loops are
     /// manually unrolled to 64 bytes.
     ///
+    /// On x86-64, the partition IDs are computed with AVX2, or with AVX-512 if the CPU supports
+    /// it at run-time.
+    ///
     /// # Limitations
     ///
-    /// Currently only implemented for PPC64le.
+    /// Currently only implemented for PPC64le and x86-64.
     ChunkedSimd,
+
+    /// `Contiguous` computes a single set of partitions for all threads. Thus, the resulting
+    /// partitions are one contiguous sequence, and joins need only one range per partition.
+    ///
+    /// Each thread computes the histogram of its chunk. The last thread to finish computes the
+    /// global prefix sum and the local offsets of all threads. The local offsets are compatible
+    /// with `GpuHistogramAlgorithm::Contiguous`.
+    ///
+    /// # Limitations
+    ///
+    /// Only `CpuRadixPartitionAlgorithm::NC` supports contiguous partitions, because SWWC
+    /// buffer flushes would overwrite the tuples of neighboring threads. `max_chunks` of the
+    /// `PartitionOffsets` and `PartitionedRelation` must equal the number of threads.
+    Contiguous,
 }
 
 impl From<CpuHistogramAlgorithm> for HistogramAlgorithmType {
@@ -311,6 +397,7 @@ impl From<CpuHistogramAlgorithm> for HistogramAlgorithmType {
         match algorithm {
             CpuHistogramAlgorithm::Chunked => Self::Chunked,
             CpuHistogramAlgorithm::ChunkedSimd => Self::Chunked,
+            CpuHistogramAlgorithm::Contiguous => Self::Contiguous,
         }
     }
 }
@@ -336,16 +423,45 @@ pub enum CpuRadixPartitionAlgorithm {
     /// This is the same algorithm as `Swwc`, but uses SIMD loads in addition to
     /// the SIMD buffer flush. Also, loops are manually unrolled to 64 bytes.
     ///
+    /// On x86-64, the partition IDs are computed with AVX2, or with AVX-512 if
+    /// the CPU supports it at run-time.
+    ///
     /// # Limitations
     ///
-    /// Currently only implemented for PPC64le.
+    /// Currently only implemented for PPC64le and x86-64.
     SwwcSimd,
 }
 
+/// Specifies the widest x86-64 SIMD instruction set used by the SIMD algorithms.
+///
+/// The SIMD algorithms detect the CPU features at run-time, and never use an
+/// instruction set that the CPU doesn't support. Limiting the instruction set
+/// is useful to test and benchmark the AVX2 code path on AVX-512 CPUs. Other
+/// architectures ignore this setting.
+///
+/// Note that the enum's values must be kept in sync with its counterpart in
+/// C/C++.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+#[repr(u32)]
+pub enum X86SimdIsa {
+    /// Use at most AVX2 instructions.
+    Avx2 = 0,
+
+    /// Use AVX-512 instructions if the CPU supports them.
+    Avx512 = 1,
+}
+
+impl Default for X86SimdIsa {
+    fn default() -> Self {
+        X86SimdIsa::Avx512
+    }
+}
+
 #[derive(Debug)]
 enum PrefixSumState {
     Chunked(DerefMem<u32>),
     ChunkedSimd(DerefMem<u32>),
+    Contiguous(DerefMem<u32>),
 }
 
 /// Mutable internal state of the partition functions.
@@ -363,57 +479,123 @@ enum RadixPartitionState {
 #[derive(Debug)]
 pub struct CpuRadixPartitioner {
     radix_bits: u32,
+    ignore_bits: u32,
+    hash_function: HashFunction,
+    simd_isa: X86SimdIsa,
     prefix_sum_state: PrefixSumState,
     radix_partition_state: RadixPartitionState,
 }
 
 impl CpuRadixPartitioner {
     /// Creates a new CPU radix partitioner.
+    ///
+    /// Returns an error if the partitioner state cannot be allocated.
     pub fn new(
         prefix_sum_algorithm: CpuHistogramAlgorithm,
         partition_algorithm: CpuRadixPartitionAlgorithm,
         radix_bits: u32,
         state_mem_type: DerefMemType,
-    ) -> Self {
+    ) -> Result<Self> {
+        Self::new_with_ignore_bits(
+            prefix_sum_algorithm,
+            partition_algorithm,
+            radix_bits,
+            0,
+            state_mem_type,
+        )
+    }
+
+    /// Creates a new CPU radix partitioner for the specified radix pass.
+    ///
+    /// The partitioner ignores the radix bits of all earlier passes. Thus, the
+    /// partitioner can subdivide the partitions of an earlier pass.
+    pub fn new_with_pass(
+        prefix_sum_algorithm: CpuHistogramAlgorithm,
+        partition_algorithm: CpuRadixPartitionAlgorithm,
+        radix_bits: &RadixBits,
+        radix_pass: RadixPass,
+        state_mem_type: DerefMemType,
+    ) -> Result<Self> {
+        let pass_radix_bits = radix_bits.pass_radix_bits(radix_pass).ok_or_else(|| {
+            ErrorKind::InvalidArgument(format!("No radix bits specified for {:?} pass", radix_pass))
+        })?;
+
+        Self::new_with_ignore_bits(
+            prefix_sum_algorithm,
+            partition_algorithm,
+            pass_radix_bits,
+            radix_bits.pass_ignore_bits(radix_pass),
+            state_mem_type,
+        )
+    }
+
+    fn new_with_ignore_bits(
+        prefix_sum_algorithm: CpuHistogramAlgorithm,
+        partition_algorithm: CpuRadixPartitionAlgorithm,
+        radix_bits: u32,
+        ignore_bits: u32,
+        state_mem_type: DerefMemType,
+    ) -> Result<Self> {
         let num_partitions = fanout(radix_bits) as usize;
         let vec_len = 4;
         let unroll_len = 4;
 
         let prefix_sum_state = match prefix_sum_algorithm {
-            CpuHistogramAlgorithm::Chunked => PrefixSumState::Chunked(Allocator::alloc_deref_mem(
-                state_mem_type.clone(),
-                num_partitions,
-            )),
+            CpuHistogramAlgorithm::Chunked => PrefixSumState::Chunked(
+                Allocator::try_alloc_deref_mem(state_mem_type.clone(), num_partitions)?,
+            ),
             CpuHistogramAlgorithm::ChunkedSimd => {
-                PrefixSumState::ChunkedSimd(Allocator::alloc_deref_mem(
+                PrefixSumState::ChunkedSimd(Allocator::try_alloc_deref_mem(
                     state_mem_type.clone(),
                     num_partitions * vec_len * unroll_len,
-                ))
+                )?)
             }
+            CpuHistogramAlgorithm::Contiguous => PrefixSumState::Contiguous(
+                Allocator::try_alloc_deref_mem(state_mem_type.clone(), num_partitions)?,
+            ),
         };
 
         let radix_partition_state = match partition_algorithm {
-            CpuRadixPartitionAlgorithm::NC => RadixPartitionState::NC(Allocator::alloc_deref_mem(
-                state_mem_type.clone(),
-                num_partitions,
-            )),
+            CpuRadixPartitionAlgorithm::NC => RadixPartitionState::NC(
+                Allocator::try_alloc_deref_mem(state_mem_type.clone(), num_partitions)?,
+            ),
             CpuRadixPartitionAlgorithm::Swwc => RadixPartitionState::Swwc(WriteCombineBuffer::new(
                 radix_bits,
-                Allocator::deref_mem_alloc_fn(state_mem_type.clone()),
-            )),
+                Allocator::try_deref_mem_alloc_fn(state_mem_type.clone()),
+            )?),
             CpuRadixPartitionAlgorithm::SwwcSimd => {
                 RadixPartitionState::SwwcSimd(WriteCombineBuffer::new(
                     radix_bits,
-                    Allocator::deref_mem_alloc_fn(state_mem_type.clone()),
-                ))
+                    Allocator::try_deref_mem_alloc_fn(state_mem_type.clone()),
+                )?)
             }
         };
 
-        Self {
+        Ok(Self {
             radix_bits,
+            ignore_bits,
+            hash_function: HashFunction::default(),
+            simd_isa: X86SimdIsa::default(),
             prefix_sum_state,
             radix_partition_state,
-        }
+        })
+    }
+
+    /// Sets the hash function applied to the keys before partitioning.
+    ///
+    /// The prefix sum and the partitioning function use the same hash
+    /// function. By default, the keys are partitioned by their identity.
+    pub fn with_hash_function(mut self, hash_function: HashFunction) -> Self {
+        self.hash_function = hash_function;
+        self
+    }
+
+    /// Limits the x86-64 SIMD instruction set of the SIMD algorithms.
+    ///
+    /// By default, the widest instruction set that the CPU supports is used.
+    pub fn with_x86_simd_isa(mut self, simd_isa: X86SimdIsa) -> Self {
+        self.simd_isa = simd_isa;
+        self
     }
 
     /// Computes the prefix sum.
@@ -431,25 +613,35 @@ impl CpuRadixPartitioner {
     ///
     /// The function is thread-safe, and meant to be externally parallelized by
     /// the caller.
-    pub fn prefix_sum<T: DeviceCopy + CpuRadixPartitionable>(
+    pub fn prefix_sum<K, V>(
         &mut self,
-        partition_attr: RadixPartitionInputChunk<'_, T>,
-        partition_offsets: PartitionOffsetsMutSlice<'_, Tuple<T, T>>,
-    ) -> Result<()> {
-        T::prefix_sum_impl(self, partition_attr, partition_offsets)
+        partition_attr: RadixPartitionInputChunk<'_, K>,
+        partition_offsets: PartitionOffsetsMutSlice<'_, Tuple<K, V>>,
+    ) -> Result<()>
+    where
+        K: DeviceCopy + CpuRadixPartitionable<V>,
+        V: DeviceCopy,
+    {
+        let _span = Span::new("cpu_radix_prefix_sum");
+        K::prefix_sum_impl(self, partition_attr, partition_offsets)
     }
 
     /// Radix-partitions a relation by its key attribute.
     ///
     /// See the module-level documentation for details on the algorithm.
-    pub fn partition<T: DeviceCopy + CpuRadixPartitionable>(
+    pub fn partition<K, V>(
         &mut self,
-        partition_attr: RadixPartitionInputChunk<'_, T>,
-        payload_attr: RadixPartitionInputChunk<'_, T>,
-        partition_offsets: PartitionOffsetsMutSlice<Tuple<T, T>>,
-        partitioned_relation: PartitionedRelationMutSlice<Tuple<T, T>>,
-    ) -> Result<()> {
-        T::partition_impl(
+        partition_attr: RadixPartitionInputChunk<'_, K>,
+        payload_attr: RadixPartitionInputChunk<'_, V>,
+        partition_offsets: PartitionOffsetsMutSlice<Tuple<K, V>>,
+        partitioned_relation: PartitionedRelationMutSlice<Tuple<K, V>>,
+    ) -> Result<()>
+    where
+        K: DeviceCopy + CpuRadixPartitionable<V>,
+        V: DeviceCopy,
+    {
+        let _span = Span::new("cpu_radix_partition");
+        K::partition_impl(
             self,
             partition_attr,
             payload_attr,
@@ -460,13 +652,13 @@ impl CpuRadixPartitioner {
 }
 
 macro_rules! impl_cpu_radix_partition_for_type {
-    ($Type:ty, $Suffix:expr) => {
-        impl CpuRadixPartitionable for $Type {
+    ($KeyType:ty, $PayloadType:ty, $KeySuffix:expr, $PayloadSuffix:expr) => {
+        impl CpuRadixPartitionable<$PayloadType> for $KeyType {
             paste::item! {
                 fn prefix_sum_impl(
                     rp: &mut CpuRadixPartitioner,
                     partition_attr: RadixPartitionInputChunk<'_, Self>,
-                    mut partition_offsets: PartitionOffsetsMutSlice<'_, Tuple<Self, Self>>,
+                    mut partition_offsets: PartitionOffsetsMutSlice<'_, Tuple<Self, $PayloadType>>,
                     ) -> Result<()> {
 
                     let radix_bits = rp.radix_bits;
@@ -485,11 +677,20 @@ macro_rules! impl_cpu_radix_partition_for_type {
                                 "PartitionOffsets has mismatching number of chunks".to_string(),
                                 ))?;
                     }
-                    if partition_offsets.padding_len() < WriteCombineBuffer::tuples_per_buffer::<Tuple<Self, Self>>() {
+                    if partition_offsets.padding_len() < WriteCombineBuffer::tuples_per_buffer::<Tuple<Self, $PayloadType>>() {
                         Err(ErrorKind::InvalidArgument(
                                 "Padding is too small; should be at least the SWWC buffer size".to_string(),
                                 ))?;
                     }
+                    let is_contiguous = match rp.prefix_sum_state {
+                        PrefixSumState::Contiguous(_) => true,
+                        _ => false,
+                    };
+                    if is_contiguous != partition_offsets.is_contiguous() {
+                        Err(ErrorKind::InvalidArgument(
+                                "PartitionOffsets has mismatching histogram algorithm".to_string(),
+                                ))?;
+                    }
 
                     partition_offsets.set_data_len(partition_attr.total_data_len);
 
@@ -501,18 +702,23 @@ macro_rules! impl_cpu_radix_partition_for_type {
                     {
                         PrefixSumState::Chunked(ref mut state) =>
                             (
-                                [<cpu_chunked_prefix_sum_ $Suffix>],
+                                [<cpu_chunked_prefix_sum_ $KeySuffix>],
                                 state.as_mut_ptr(),
                             ),
-                        #[cfg(target_arch = "powerpc64")]
+                        #[cfg(any(target_arch = "powerpc64", target_arch = "x86_64"))]
                         PrefixSumState::ChunkedSimd(ref mut state) =>
                             (
-                                [<cpu_chunked_prefix_sum_simd_ $Suffix>],
+                                [<cpu_chunked_prefix_sum_simd_ $KeySuffix>],
                                 state.as_mut_ptr(),
                             ),
-                        #[cfg(not(target_arch = "powerpc64"))]
+                        #[cfg(not(any(target_arch = "powerpc64", target_arch = "x86_64")))]
                         PrefixSumState::ChunkedSimd(_) =>
-                            unimplemented!()
+                            unimplemented!(),
+                        PrefixSumState::Contiguous(ref mut state) =>
+                            (
+                                [<cpu_chunked_prefix_sum_ $KeySuffix>],
+                                state.as_mut_ptr(),
+                            ),
                     };
 
                     let mut args = PrefixSumArgs {
@@ -521,7 +727,9 @@ macro_rules! impl_cpu_radix_partition_for_type {
                         canonical_chunk_len: partition_attr.canonical_chunk_len,
                         padding_len: partition_offsets.padding_len(),
                         radix_bits,
-                        ignore_bits: 0,
+                        ignore_bits: rp.ignore_bits,
+                        hash_function: rp.hash_function,
+                        simd_isa: rp.simd_isa,
                         tmp_partition_offsets,
                         partition_offsets: partition_offsets.offsets.as_mut_ptr(),
                     };
@@ -530,6 +738,29 @@ macro_rules! impl_cpu_radix_partition_for_type {
                         prefix_sum_fn(&mut args, partition_offsets.chunk_id, partition_offsets.chunks);
                     }
 
+                    if is_contiguous {
+                        // Convert the chunked offsets back into the chunk's
+                        // histogram, from which the last chunk computes the
+                        // contiguous offsets.
+                        let padding_len = partition_offsets.padding_len() as u64;
+                        let chunk_len = partition_attr.data.len() as u64;
+                        let num_partitions = partition_offsets.offsets.len();
+                        let offsets = unsafe {
+                            std::slice::from_raw_parts_mut(
+                                partition_offsets.offsets.as_mut_ptr(),
+                                num_partitions,
+                            )
+                        };
+                        let mut end = offsets[0] - padding_len + chunk_len + num_partitions as u64 * padding_len;
+                        for offset in offsets.iter_mut().rev() {
+                            let begin = *offset;
+                            *offset = end - begin;
+                            end = begin - padding_len;
+                        }
+
+                        partition_offsets.finish_contiguous_prefix_sum();
+                    }
+
                     Ok(())
 
                 }
@@ -537,9 +768,9 @@ macro_rules! impl_cpu_radix_partition_for_type {
                 fn partition_impl(
                     rp: &mut CpuRadixPartitioner,
                     partition_attr: RadixPartitionInputChunk<'_, Self>,
-                    payload_attr: RadixPartitionInputChunk<'_, Self>,
-                    partition_offsets: PartitionOffsetsMutSlice<Tuple<Self, Self>>,
-                    mut partitioned_relation: PartitionedRelationMutSlice<Tuple<Self, Self>>,
+                    payload_attr: RadixPartitionInputChunk<'_, $PayloadType>,
+                    partition_offsets: PartitionOffsetsMutSlice<Tuple<Self, $PayloadType>>,
+                    mut partitioned_relation: PartitionedRelationMutSlice<Tuple<Self, $PayloadType>>,
                     ) -> Result<()>
                 {
                     if partition_attr.data.len() != payload_attr.data.len() {
@@ -569,6 +800,28 @@ macro_rules! impl_cpu_radix_partition_for_type {
                                 "PartitionOffsets and PartitionedRelation have mismatching padding".to_string(),
                                 ))?;
                     }
+                    let is_contiguous = match partitioned_relation.histogram_algorithm_type {
+                        HistogramAlgorithmType::Contiguous => true,
+                        HistogramAlgorithmType::Chunked => false,
+                    };
+                    if (partition_offsets.is_contiguous() != is_contiguous) {
+                        Err(ErrorKind::InvalidArgument(
+                                "PartitionOffsets and PartitionedRelation have mismatching histogram algorithms".to_string(),
+                                ))?;
+                    }
+                    if is_contiguous && !partition_offsets.is_prefix_sum_complete() {
+                        Err(ErrorKind::InvalidArgument(
+                                "Contiguous prefix sum is incomplete, because not all chunks computed their prefix sum".to_string(),
+                                ))?;
+                    }
+                    if is_contiguous {
+                        match rp.radix_partition_state {
+                            RadixPartitionState::NC(_) => {},
+                            _ => Err(ErrorKind::InvalidArgument(
+                                    "Contiguous partitions are only supported by the NC partitioning algorithm".to_string(),
+                                    ))?,
+                        }
+                    }
 
                     let data_len = partition_attr.data.len();
                     let (partition_fn, tmp_partition_offsets, write_combine_buffer):
@@ -580,40 +833,54 @@ macro_rules! impl_cpu_radix_partition_for_type {
                     {
                         RadixPartitionState::NC(ref mut offsets) =>
                             (
-                                [<cpu_chunked_radix_partition_ $Suffix _ $Suffix>],
+                                [<cpu_chunked_radix_partition_ $KeySuffix _ $PayloadSuffix>],
                                 offsets.as_mut_ptr(),
                                 ptr::null_mut(),
                             ),
                         RadixPartitionState::Swwc(ref mut swwc) =>
                             (
-                                [<cpu_chunked_radix_partition_swwc_ $Suffix _ $Suffix>],
+                                [<cpu_chunked_radix_partition_swwc_ $KeySuffix _ $PayloadSuffix>],
                                 ptr::null_mut(),
                                 swwc.as_mut_slice().as_mut_ptr() as *mut c_void,
                             ),
-                        #[cfg(target_arch = "powerpc64")]
+                        #[cfg(any(target_arch = "powerpc64", target_arch = "x86_64"))]
                         RadixPartitionState::SwwcSimd(ref mut swwc) =>
                             (
-                                [<cpu_chunked_radix_partition_swwc_simd_ $Suffix _ $Suffix>],
+                                [<cpu_chunked_radix_partition_swwc_simd_ $KeySuffix _ $PayloadSuffix>],
                                 ptr::null_mut(),
                                 swwc.as_mut_slice().as_mut_ptr() as *mut c_void,
                             ),
-                        #[cfg(not(target_arch = "powerpc64"))]
+                        #[cfg(not(any(target_arch = "powerpc64", target_arch = "x86_64")))]
                         RadixPartitionState::SwwcSimd(ref mut _swwc) =>
                             unimplemented!(),
                     };
 
+                    // Contiguous chunks share the relation. The partitioning
+                    // function writes relative to the chunk's first partition.
+                    let relation_offset = if is_contiguous {
+                        unsafe {
+                            partition_offsets.offsets.as_slice()[0] as usize
+                                - partitioned_relation.padding_len() as usize
+                        }
+                    } else {
+                        0
+                    };
+
                     let mut args = RadixPartitionArgs {
                         partition_attr_data: partition_attr.data.as_ptr() as *const c_void,
                         payload_attr_data: payload_attr.data.as_ptr() as *const c_void,
                         data_len,
                         padding_len: partitioned_relation.padding_len() as usize,
                         radix_bits: rp.radix_bits,
-                        ignore_bits: 0,
+                        ignore_bits: rp.ignore_bits,
+                        hash_function: rp.hash_function,
+                        simd_isa: rp.simd_isa,
                         partition_offsets: partition_offsets.offsets.as_ptr(),
                         tmp_partition_offsets,
                         write_combine_buffer,
                         partitioned_relation: partitioned_relation.relation
-                            .as_mut_ptr() as *mut c_void,
+                            .as_mut_ptr()
+                            .wrapping_add(relation_offset) as *mut c_void,
                     };
 
                     unsafe {
@@ -622,11 +889,15 @@ macro_rules! impl_cpu_radix_partition_for_type {
                         );
                     }
 
-                    // Copy offsets to PartitionedRelation.
-                    unsafe {
-                        partitioned_relation.offsets
-                            .as_mut_slice()
-                            .copy_from_slice(partition_offsets.offsets.as_slice());
+                    // Copy offsets to PartitionedRelation. Contiguous chunks
+                    // share the offsets, which only the first chunk copies,
+                    // because its local offsets equal the global offsets.
+                    if partitioned_relation.offsets.len() != 0 {
+                        unsafe {
+                            partitioned_relation.offsets
+                                .as_mut_slice()
+                                .copy_from_slice(partition_offsets.offsets.as_slice());
+                        }
                     }
 
                     Ok(())
@@ -636,5 +907,10 @@ macro_rules! impl_cpu_radix_partition_for_type {
     };
 }
 
-impl_cpu_radix_partition_for_type!(i32, int32);
-impl_cpu_radix_partition_for_type!(i64, int64);
+impl_cpu_radix_partition_for_type!(i32, i32, int32, int32);
+impl_cpu_radix_partition_for_type!(i64, i64, int64, int64);
+impl_cpu_radix_partition_for_type!(u32, u32, uint32, uint32);
+impl_cpu_radix_partition_for_type!(u64, u64, uint64, uint64);
+impl_cpu_radix_partition_for_type!(CompositeKey, CompositeKey, composite, composite);
+impl_cpu_radix_partition_for_type!(i32, i64, int32, int64);
+impl_cpu_radix_partition_for_type!(i64, i32, int64, int32);
@@ -19,6 +19,7 @@ use super::{
 };
 use crate::constants;
 use crate::error::{ErrorKind, Result};
+use crate::hash_function::HashFunction;
 use crate::prefix_scan::{GpuPrefixScanState, GpuPrefixSum};
 use numa_gpu::runtime::allocator::{Allocator, MemType};
 use numa_gpu::runtime::memory::{
@@ -48,6 +49,7 @@ struct PrefixSumArgs {
     padding_len: u32,
     radix_bits: u32,
     ignore_bits: u32,
+    hash_function: HashFunction,
 
     // State
     prefix_scan_state: LaunchableMutPtr<GpuPrefixScanState<u64>>,
@@ -74,6 +76,7 @@ struct PrefixSumAndCopyWithPayloadArgs {
     padding_len: u32,
     radix_bits: u32,
     ignore_bits: u32,
+    hash_function: HashFunction,
 
     // State
     prefix_scan_state: LaunchableMutPtr<GpuPrefixScanState<u64>>,
@@ -104,6 +107,7 @@ struct PrefixSumAndTransformArgs {
     padding_len: u32,
     radix_bits: u32,
     ignore_bits: u32,
+    hash_function: HashFunction,
 
     // State
     prefix_scan_state: LaunchableMutPtr<GpuPrefixScanState<u64>>,
@@ -131,6 +135,7 @@ struct RadixPartitionArgs {
     padding_len: u32,
     radix_bits: u32,
     ignore_bits: u32,
+    hash_function: HashFunction,
     partition_offsets: LaunchablePtr<u64>,
 
     // State
@@ -232,6 +237,7 @@ impl From<CpuHistogramAlgorithm> for GpuHistogramAlgorithm {
         match algo {
             CpuHistogramAlgorithm::Chunked => Self::Chunked,
             CpuHistogramAlgorithm::ChunkedSimd => Self::Chunked,
+            CpuHistogramAlgorithm::Contiguous => Self::Contiguous,
         }
     }
 }
@@ -314,6 +320,7 @@ enum RadixPartitionState {
 #[derive(Debug)]
 pub struct GpuRadixPartitioner {
     radix_bits: RadixBits,
+    hash_function: HashFunction,
     prefix_sum_algorithm: GpuHistogramAlgorithm,
     partition_algorithm: GpuRadixPartitionAlgorithm,
     prefix_sum_state: PrefixSumState,
@@ -338,10 +345,9 @@ impl GpuRadixPartitioner {
 
         let prefix_sum_state = match prefix_sum_algorithm {
             GpuHistogramAlgorithm::Chunked => PrefixSumState::Chunked,
-            GpuHistogramAlgorithm::Contiguous => PrefixSumState::Contiguous(Allocator::alloc_mem(
-                MemType::CudaDevMem,
-                prefix_scan_state_len,
-            )),
+            GpuHistogramAlgorithm::Contiguous => PrefixSumState::Contiguous(
+                Allocator::try_alloc_mem(MemType::CudaDevMem, prefix_scan_state_len)?,
+            ),
         };
 
         let partition_state = match partition_algorithm {
@@ -371,6 +377,7 @@ impl GpuRadixPartitioner {
 
         Ok(Self {
             radix_bits,
+            hash_function: HashFunction::default(),
             prefix_sum_algorithm,
             partition_algorithm,
             prefix_sum_state,
@@ -382,6 +389,15 @@ impl GpuRadixPartitioner {
         })
     }
 
+    /// Sets the hash function applied to the keys before partitioning.
+    ///
+    /// The prefix sum and the partitioning function use the same hash
+    /// function. By default, the keys are partitioned by their identity.
+    pub fn with_hash_function(mut self, hash_function: HashFunction) -> Self {
+        self.hash_function = hash_function;
+        self
+    }
+
     /// Computes the prefix sum.
     ///
     /// The prefix sum performs a scan over all partitioning keys. It first
@@ -634,6 +650,7 @@ macro_rules! impl_gpu_radix_partition_for_type {
                         padding_len: partition_offsets.padding_len(),
                         radix_bits,
                         ignore_bits,
+                        hash_function: rp.hash_function,
                         prefix_scan_state: LaunchableMutPtr::null_mut(),
                         tmp_partition_offsets,
                         partition_offsets: partition_offsets.offsets.as_launchable_mut_ptr(),
@@ -780,6 +797,7 @@ macro_rules! impl_gpu_radix_partition_for_type {
                         padding_len: partition_offsets.padding_len(),
                         radix_bits,
                         ignore_bits,
+                        hash_function: rp.hash_function,
                         prefix_scan_state: LaunchableMutPtr::null_mut(),
                         tmp_partition_offsets,
                         dst_partition_attr: dst_partition_attr.as_launchable_mut_ptr().as_void(),
@@ -911,6 +929,7 @@ macro_rules! impl_gpu_radix_partition_for_type {
                         padding_len: partition_offsets.padding_len(),
                         radix_bits,
                         ignore_bits,
+                        hash_function: rp.hash_function,
                         prefix_scan_state: LaunchableMutPtr::null_mut(),
                         tmp_partition_offsets,
                         dst_partition_attr: dst_partition_attr.as_launchable_mut_ptr().as_void(),
@@ -1127,6 +1146,7 @@ macro_rules! impl_gpu_radix_partition_for_type {
                         padding_len: partitioned_relation.padding_len(),
                         radix_bits,
                         ignore_bits,
+                        hash_function: rp.hash_function,
                         partition_offsets: partition_offsets_ptr,
                         tmp_partition_offsets,
                         l2_cache_buffers,
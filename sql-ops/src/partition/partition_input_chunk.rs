@@ -14,8 +14,7 @@
 
 use crate::constants;
 use crate::error::{ErrorKind, Result};
-use numa_gpu::runtime::memory::DerefMem;
-use rustacuda::memory::DeviceCopy;
+use numa_gpu::runtime::memory::{DerefMem, DeviceCopy};
 use std::mem;
 
 /// Returns the reference chunk size with which input should be partitioned.
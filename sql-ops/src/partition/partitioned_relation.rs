@@ -15,13 +15,14 @@
 use super::{fanout, HistogramAlgorithmType, Tuple};
 use crate::error::{ErrorKind, Result};
 use numa_gpu::error::Result as NumaGpuResult;
-use numa_gpu::runtime::allocator::MemAllocFn;
-use numa_gpu::runtime::memory::{LaunchableMem, LaunchableMutSlice, Mem, MemLock};
-use rustacuda::memory::DeviceCopy;
+use numa_gpu::runtime::allocator::{MemAllocFn, TryMemAllocFn};
+use numa_gpu::runtime::memory::{DeviceCopy, LaunchableMem, LaunchableMutSlice, Mem, MemLock};
 use std::convert::TryInto;
+use std::marker::PhantomData;
 use std::mem;
 use std::ops::{Index, IndexMut};
 use std::slice::ChunksMut;
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
 
 /// Convert padding bytes into padding length for the type `T`
 pub fn padding_len<T: Sized>() -> u32 {
@@ -53,6 +54,11 @@ pub fn padding_len<T: Sized>() -> u32 {
 /// Thus, the contiguous prefix sum variants output `local_offsets` in addition
 /// to the normal `offsets`.
 ///
+/// On the CPU, the chunks compute the contiguous prefix sum cooperatively.
+/// Each chunk first computes its histogram in its `local_offsets`. The last
+/// chunk to finish then computes the `offsets` and `local_offsets` of all
+/// chunks.
+///
 /// # Invariants
 ///
 ///  - `len` must match in `PartitionedRelation`
@@ -66,6 +72,8 @@ pub struct PartitionOffsets<T: DeviceCopy> {
     data_len: Option<usize>,
     chunks: u32,
     radix_bits: u32,
+    finished_chunks: AtomicU32,
+    contiguous_prefix_sum_complete: AtomicBool,
     phantom_data: std::marker::PhantomData<T>,
 }
 
@@ -77,29 +85,48 @@ impl<T: DeviceCopy> PartitionOffsets<T> {
         radix_bits: u32,
         alloc_fn: MemAllocFn<u64>,
     ) -> Self {
+        Self::try_new(
+            histogram_algorithm_type,
+            max_chunks,
+            radix_bits,
+            Box::new(move |len| Ok(alloc_fn(len))),
+        )
+        .expect("Failed to allocate the partition offsets")
+    }
+
+    /// Creates a new partition offsets array, and returns an error if the
+    /// allocation fails.
+    pub fn try_new(
+        histogram_algorithm_type: HistogramAlgorithmType,
+        max_chunks: u32,
+        radix_bits: u32,
+        alloc_fn: TryMemAllocFn<u64>,
+    ) -> Result<Self> {
         let chunks: u32 = match histogram_algorithm_type {
             HistogramAlgorithmType::Chunked => max_chunks,
             HistogramAlgorithmType::Contiguous => 1,
         };
 
         let num_partitions = fanout(radix_bits) as usize;
-        let offsets = alloc_fn(num_partitions * chunks as usize);
+        let offsets = alloc_fn(num_partitions * chunks as usize)?;
 
         let local_offsets = match histogram_algorithm_type {
             HistogramAlgorithmType::Contiguous => {
-                Some(alloc_fn(num_partitions * max_chunks as usize))
+                Some(alloc_fn(num_partitions * max_chunks as usize)?)
             }
             _ => None,
         };
 
-        Self {
+        Ok(Self {
             offsets,
             local_offsets,
             data_len: None,
             chunks,
             radix_bits,
+            finished_chunks: AtomicU32::new(0),
+            contiguous_prefix_sum_complete: AtomicBool::new(false),
             phantom_data: std::marker::PhantomData,
-        }
+        })
     }
 
     /// Returns the total number of elements in the relation (excluding padding).
@@ -141,6 +168,9 @@ impl<T: DeviceCopy> PartitionOffsets<T> {
     ///
     /// Chunks are non-overlapping and can safely be used for parallel
     /// processing.
+    ///
+    /// For a contiguous histogram, the iterator returns one chunk per
+    /// `max_chunks`. Each chunk references its `local_offsets`.
     pub fn chunks_mut(&mut self) -> PartitionOffsetsChunksMut<'_, T> {
         PartitionOffsetsChunksMut::new(self)
     }
@@ -226,6 +256,7 @@ pub struct PartitionOffsetsChunksMut<'a, T: DeviceCopy> {
     // Note: unsafe slices, must convert back to LaunchableSlice
     offsets_chunks: ChunksMut<'a, u64>,
     data_len: Option<&'a mut Option<usize>>,
+    contiguous: Option<ContiguousPrefixSum<'a>>,
     chunk_id: u32,
     chunks: u32,
     radix_bits: u32,
@@ -236,17 +267,51 @@ impl<'a, T: DeviceCopy> PartitionOffsetsChunksMut<'a, T> {
     fn new(offsets: &'a mut PartitionOffsets<T>) -> Self {
         unsafe {
             let num_partitions = fanout(offsets.radix_bits) as usize;
-            let offsets_chunks = offsets
-                .offsets
-                .as_launchable_mut_slice()
-                .as_mut_slice()
-                .chunks_mut(num_partitions);
+            *offsets.finished_chunks.get_mut() = 0;
+
+            let (offsets_chunks, contiguous, chunks) = match offsets.local_offsets {
+                Some(ref mut local_offsets) => {
+                    let local_offsets = local_offsets.as_launchable_mut_slice().as_mut_slice();
+                    let local_offsets_ptr = local_offsets.as_mut_ptr();
+                    let local_offsets_len = local_offsets.len();
+                    let contiguous = ContiguousPrefixSum {
+                        offsets: offsets
+                            .offsets
+                            .as_launchable_mut_slice()
+                            .as_mut_slice()
+                            .as_mut_ptr(),
+                        local_offsets: local_offsets_ptr,
+                        finished_chunks: &offsets.finished_chunks,
+                        complete: &offsets.contiguous_prefix_sum_complete,
+                    };
+
+                    // Safety: Each chunk references a disjunct part of the
+                    // local offsets. Only the last chunk to finish accesses
+                    // all local offsets via `ContiguousPrefixSum`.
+                    let offsets_chunks =
+                        std::slice::from_raw_parts_mut(local_offsets_ptr, local_offsets_len)
+                            .chunks_mut(num_partitions);
+                    let chunks = (local_offsets_len / num_partitions) as u32;
+
+                    (offsets_chunks, Some(contiguous), chunks)
+                }
+                None => {
+                    let offsets_chunks = offsets
+                        .offsets
+                        .as_launchable_mut_slice()
+                        .as_mut_slice()
+                        .chunks_mut(num_partitions);
+
+                    (offsets_chunks, None, offsets.chunks)
+                }
+            };
 
             Self {
                 offsets_chunks,
                 data_len: Some(&mut offsets.data_len),
+                contiguous,
                 chunk_id: 0,
-                chunks: offsets.chunks,
+                chunks,
                 radix_bits: offsets.radix_bits,
                 phantom_data: std::marker::PhantomData,
             }
@@ -266,6 +331,7 @@ impl<'a, T: DeviceCopy> Iterator for PartitionOffsetsChunksMut<'a, T> {
             Some(PartitionOffsetsMutSlice {
                 offsets: o.as_launchable_mut_slice(),
                 data_len: self.data_len.take(),
+                contiguous: self.contiguous,
                 chunk_id,
                 chunks: self.chunks,
                 radix_bits: self.radix_bits,
@@ -287,6 +353,7 @@ pub struct PartitionOffsetsMutSlice<'a, T: DeviceCopy> {
     pub(super) chunks: u32,
     pub(super) radix_bits: u32,
     data_len: Option<&'a mut Option<usize>>,
+    contiguous: Option<ContiguousPrefixSum<'a>>,
     phantom_data: std::marker::PhantomData<T>,
 }
 
@@ -305,8 +372,107 @@ impl<'a, T: DeviceCopy> PartitionOffsetsMutSlice<'a, T> {
             **this_len = Some(len);
         }
     }
+
+    /// Returns `true` if the offsets belong to a contiguous histogram.
+    pub(super) fn is_contiguous(&self) -> bool {
+        self.contiguous.is_some()
+    }
+
+    /// Returns `true` if all chunks have finished the contiguous prefix sum.
+    ///
+    /// If a chunk skipped the prefix sum, the offsets of all chunks contain
+    /// only their histograms, and are thus invalid.
+    ///
+    /// Offsets of a chunked histogram are always complete.
+    pub(super) fn is_prefix_sum_complete(&self) -> bool {
+        self.contiguous.map_or(true, |contiguous| {
+            contiguous.complete.load(Ordering::Acquire)
+        })
+    }
+
+    /// Completes the contiguous prefix sum for this chunk.
+    ///
+    /// The chunk's offsets must contain the chunk's histogram. The last chunk
+    /// to finish computes the offsets of all chunks from their histograms.
+    /// Thus, the offsets are valid only after all chunks have finished.
+    ///
+    /// Offsets of a chunked histogram are left unmodified.
+    pub(super) fn finish_contiguous_prefix_sum(self) {
+        let Self {
+            offsets,
+            chunks,
+            contiguous,
+            ..
+        } = self;
+        let num_partitions = offsets.len();
+        let padding_len = padding_len::<T>() as u64;
+
+        let contiguous = match contiguous {
+            Some(contiguous) => contiguous,
+            None => return,
+        };
+
+        // Invalidate the offsets of a previous run, in case that this run
+        // doesn't complete. Publish this chunk's histogram, and acquire the
+        // histograms of all chunks that finished before.
+        contiguous.complete.store(false, Ordering::Relaxed);
+        let finished = contiguous.finished_chunks.fetch_add(1, Ordering::AcqRel) + 1;
+        if finished != chunks {
+            return;
+        }
+
+        // Safety: All other chunks have finished, thus this chunk has
+        // exclusive access to the offsets.
+        let (global_offsets, local_offsets) = unsafe {
+            (
+                std::slice::from_raw_parts_mut(contiguous.offsets, num_partitions),
+                std::slice::from_raw_parts_mut(
+                    contiguous.local_offsets,
+                    num_partitions * chunks as usize,
+                ),
+            )
+        };
+
+        let mut offset = 0;
+        for (partition_id, global_offset) in global_offsets.iter_mut().enumerate() {
+            offset += padding_len;
+            *global_offset = offset;
+
+            for local_offset in local_offsets
+                .iter_mut()
+                .skip(partition_id)
+                .step_by(num_partitions)
+            {
+                let count = *local_offset;
+                *local_offset = offset;
+                offset += count;
+            }
+        }
+
+        contiguous.complete.store(true, Ordering::Release);
+    }
 }
 
+/// Shared state of a contiguous prefix sum, that all chunks of a
+/// `PartitionOffsets` compute cooperatively.
+#[derive(Clone, Copy, Debug)]
+struct ContiguousPrefixSum<'a> {
+    offsets: *mut u64,
+    local_offsets: *mut u64,
+    finished_chunks: &'a AtomicU32,
+    complete: &'a AtomicBool,
+}
+
+// Safety: The raw pointers are derived from the `&'a mut PartitionOffsets`
+// borrowed by `PartitionOffsetsChunksMut`, and thus remain valid for `'a`.
+// Chunks only dereference the pointers in `finish_contiguous_prefix_sum`, and
+// only the last chunk to finish does so. The chunk's `AcqRel` increment of
+// `finished_chunks` synchronizes with the increments of all other chunks,
+// which have consumed their `PartitionOffsetsMutSlice` by then. The last chunk
+// thus has exclusive access to the offsets, regardless of the thread that it
+// runs on.
+unsafe impl<'a> Send for ContiguousPrefixSum<'a> {}
+
 /// A radix-partitioned relation, optionally with padding in front of each
 /// partition.
 ///
@@ -326,7 +492,9 @@ pub struct PartitionedRelation<T: DeviceCopy> {
     pub offsets: Mem<u64>,
     len: usize,
     chunks: u32,
+    max_chunks: u32,
     radix_bits: u32,
+    histogram_algorithm_type: HistogramAlgorithmType,
 }
 
 impl<T: DeviceCopy> PartitionedRelation<T> {
@@ -340,6 +508,33 @@ impl<T: DeviceCopy> PartitionedRelation<T> {
         partition_alloc_fn: MemAllocFn<T>,
         offsets_alloc_fn: MemAllocFn<u64>,
     ) -> Self {
+        Self::try_new(
+            len,
+            histogram_algorithm_type,
+            radix_bits,
+            max_chunks,
+            move |len| Ok(partition_alloc_fn(len)),
+            move |len| Ok(offsets_alloc_fn(len)),
+        )
+        .expect("Failed to allocate the partitioned relation")
+    }
+
+    /// Creates a new partitioned relation, and returns an error if the
+    /// allocation fails.
+    ///
+    /// Accepts a `TryMemAllocFn` as well as any other allocation closure.
+    pub fn try_new<P, O>(
+        len: usize,
+        histogram_algorithm_type: HistogramAlgorithmType,
+        radix_bits: u32,
+        max_chunks: u32,
+        partition_alloc_fn: P,
+        offsets_alloc_fn: O,
+    ) -> Result<Self>
+    where
+        P: FnOnce(usize) -> NumaGpuResult<Mem<T>>,
+        O: FnOnce(usize) -> NumaGpuResult<Mem<u64>>,
+    {
         let chunks: u32 = match histogram_algorithm_type {
             HistogramAlgorithmType::Chunked => max_chunks,
             HistogramAlgorithmType::Contiguous => 1,
@@ -349,16 +544,18 @@ impl<T: DeviceCopy> PartitionedRelation<T> {
         let num_partitions = fanout(radix_bits) as usize;
         let relation_len = len + (num_partitions * chunks as usize) * padding_len as usize;
 
-        let relation = partition_alloc_fn(relation_len);
-        let offsets = offsets_alloc_fn(num_partitions * chunks as usize);
+        let relation = partition_alloc_fn(relation_len)?;
+        let offsets = offsets_alloc_fn(num_partitions * chunks as usize)?;
 
-        Self {
+        Ok(Self {
             relation,
             offsets,
             chunks,
+            max_chunks,
             radix_bits,
+            histogram_algorithm_type,
             len,
-        }
+        })
     }
 
     /// Returns the total number of elements in the relation (excluding padding).
@@ -501,6 +698,12 @@ impl<K: DeviceCopy, V: DeviceCopy> PartitionedRelation<Tuple<K, V>> {
     ///
     /// Chunks are non-overlapping and can safely be used for parallel
     /// processing.
+    ///
+    /// For a contiguous histogram, the iterator returns one chunk per
+    /// `max_chunks`. Each chunk references the whole relation through a
+    /// `SharedRelationMutPtr`, because the chunks' tuples are interleaved
+    /// within each partition. Chunks nonetheless write disjunct tuples, as
+    /// determined by their local offsets.
     pub fn chunks_mut(&mut self) -> PartitionedRelationChunksMut<'_, Tuple<K, V>> {
         PartitionedRelationChunksMut::new(self)
     }
@@ -582,19 +785,98 @@ impl<T: DeviceCopy> MemLock for PartitionedRelation<T> {
     }
 }
 
+/// A mutable pointer to a relation that is shared by several chunks.
+///
+/// The chunks of a contiguous relation interleave their tuples within each
+/// partition. Thus, the relation cannot be split into one mutable slice per
+/// chunk, as the slices would alias. Instead, each chunk references the whole
+/// relation by pointer, and writes are unsafe.
+#[derive(Debug)]
+pub struct SharedRelationMutPtr<'a, T> {
+    ptr: *mut T,
+    len: usize,
+    phantom_data: PhantomData<&'a mut [T]>,
+}
+
+impl<'a, T> SharedRelationMutPtr<'a, T> {
+    /// Returns the length of the relation.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns a pointer to the beginning of the relation.
+    pub fn as_mut_ptr(&mut self) -> *mut T {
+        self.ptr
+    }
+
+    /// Writes a tuple to the relation.
+    ///
+    /// Panics if the index is out of bounds.
+    ///
+    /// # Safety
+    ///
+    /// No other chunk may concurrently access the same tuple. Chunks of a
+    /// contiguous relation satisfy this by writing only the tuples determined
+    /// by their local offsets.
+    pub unsafe fn write(&mut self, index: usize, value: T) {
+        assert!(index < self.len, "Index out of bounds");
+        self.ptr.add(index).write(value);
+    }
+}
+
+// Safety: Sending the pointer to another thread is equivalent to sending a
+// `&mut [T]`. Concurrent writes by the chunks are guarded by the safety
+// contract of `write`, and by the partitioning function for raw pointer writes.
+unsafe impl<'a, T: Send> Send for SharedRelationMutPtr<'a, T> {}
+
+/// The part of a `PartitionedRelation` that a chunk writes.
+#[derive(Debug)]
+pub(super) enum RelationChunkMut<'a, T> {
+    /// A chunked relation gives each chunk an exclusive slice.
+    Chunked(LaunchableMutSlice<'a, T>),
+
+    /// A contiguous relation is shared by all chunks.
+    Contiguous(SharedRelationMutPtr<'a, T>),
+}
+
+impl<'a, T> RelationChunkMut<'a, T> {
+    /// Returns a pointer to the beginning of the chunk.
+    pub(super) fn as_mut_ptr(&mut self) -> *mut T {
+        match self {
+            Self::Chunked(relation) => relation.as_mut_ptr(),
+            Self::Contiguous(relation) => relation.as_mut_ptr(),
+        }
+    }
+}
+
 /// An iterator that generates `PartitionedRelationMutSlice`.
 #[derive(Debug)]
 pub struct PartitionedRelationChunksMut<'a, T: DeviceCopy> {
     relation_remainder: Option<&'a mut [T]>,
     canonical_chunk_len: usize,
     offsets_chunks: ChunksMut<'a, u64>,
+    contiguous: Option<ContiguousRelation<'a, T>>,
     chunks: u32,
     radix_bits: u32,
 }
 
+/// The relation and offsets of a contiguous `PartitionedRelation`, that are
+/// shared by all chunks.
+#[derive(Debug)]
+struct ContiguousRelation<'a, T> {
+    relation: *mut T,
+    relation_len: usize,
+    offsets: Option<&'a mut [u64]>,
+    chunk_id: u32,
+}
+
 impl<'a, K: DeviceCopy, V: DeviceCopy> PartitionedRelationChunksMut<'a, Tuple<K, V>> {
     /// Creates a new chunk iterator for a `PartitionedRelation`.
     fn new(rel: &'a mut PartitionedRelation<Tuple<K, V>>) -> Self {
+        if let HistogramAlgorithmType::Contiguous = rel.histogram_algorithm_type {
+            return Self::new_contiguous(rel);
+        }
+
         let canonical_chunk_len =
             super::partition_input_chunk::input_chunk_size::<K>(rel.len(), rel.num_chunks())
                 .unwrap()
@@ -613,11 +895,37 @@ impl<'a, K: DeviceCopy, V: DeviceCopy> PartitionedRelationChunksMut<'a, Tuple<K,
                 relation_remainder,
                 canonical_chunk_len,
                 offsets_chunks,
+                contiguous: None,
                 chunks: rel.chunks,
                 radix_bits: rel.radix_bits,
             }
         }
     }
+
+    /// Creates a new chunk iterator for a contiguous `PartitionedRelation`.
+    ///
+    /// The first chunk references the offsets, and all other chunks reference
+    /// an empty slice.
+    fn new_contiguous(rel: &'a mut PartitionedRelation<Tuple<K, V>>) -> Self {
+        unsafe {
+            let relation = rel.relation.as_launchable_mut_slice().as_mut_slice();
+            let offsets = rel.offsets.as_launchable_mut_slice().as_mut_slice();
+
+            Self {
+                relation_remainder: None,
+                canonical_chunk_len: 0,
+                offsets_chunks: <&mut [u64]>::default().chunks_mut(1),
+                contiguous: Some(ContiguousRelation {
+                    relation: relation.as_mut_ptr(),
+                    relation_len: relation.len(),
+                    offsets: Some(offsets),
+                    chunk_id: 0,
+                }),
+                chunks: rel.max_chunks,
+                radix_bits: rel.radix_bits,
+            }
+        }
+    }
 }
 
 impl<'a, T: DeviceCopy> Iterator for PartitionedRelationChunksMut<'a, T> {
@@ -625,6 +933,30 @@ impl<'a, T: DeviceCopy> Iterator for PartitionedRelationChunksMut<'a, T> {
 
     #[inline]
     fn next(&mut self) -> Option<PartitionedRelationMutSlice<'a, T>> {
+        if let Some(ref mut contiguous) = self.contiguous {
+            if contiguous.chunk_id == self.chunks {
+                return None;
+            }
+            contiguous.chunk_id += 1;
+
+            // The chunks share the relation, and write disjunct tuples. See
+            // `PartitionedRelation::chunks_mut`.
+            let relation = SharedRelationMutPtr {
+                ptr: contiguous.relation,
+                len: contiguous.relation_len,
+                phantom_data: PhantomData,
+            };
+            let offsets = contiguous.offsets.take().unwrap_or_default();
+
+            return Some(PartitionedRelationMutSlice {
+                relation: RelationChunkMut::Contiguous(relation),
+                offsets: offsets.as_launchable_mut_slice(),
+                chunks: self.chunks,
+                radix_bits: self.radix_bits,
+                histogram_algorithm_type: HistogramAlgorithmType::Contiguous,
+            });
+        }
+
         let chunk = if let Some(remainder) = self.relation_remainder.take() {
             let mid = std::cmp::min(self.canonical_chunk_len, remainder.len());
             let (c, r) = remainder.split_at_mut(mid);
@@ -638,10 +970,11 @@ impl<'a, T: DeviceCopy> Iterator for PartitionedRelationChunksMut<'a, T> {
             chunk.and_then(|rel| self.offsets_chunks.next().and_then(|off| Some((rel, off))));
         zipped.and_then(|(r, o)| {
             Some(PartitionedRelationMutSlice {
-                relation: r.as_launchable_mut_slice(),
+                relation: RelationChunkMut::Chunked(r.as_launchable_mut_slice()),
                 offsets: o.as_launchable_mut_slice(),
                 chunks: self.chunks,
                 radix_bits: self.radix_bits,
+                histogram_algorithm_type: HistogramAlgorithmType::Chunked,
             })
         })
     }
@@ -654,10 +987,11 @@ impl<'a, T: DeviceCopy> Iterator for PartitionedRelationChunksMut<'a, T> {
 #[derive(Debug)]
 pub struct PartitionedRelationMutSlice<'a, T> {
     // FIXME: convert to normal slice, and check that not DevMem in chunks_mut()
-    pub(super) relation: LaunchableMutSlice<'a, T>,
+    pub(super) relation: RelationChunkMut<'a, T>,
     pub(super) offsets: LaunchableMutSlice<'a, u64>,
     pub(super) chunks: u32,
     pub(super) radix_bits: u32,
+    pub(super) histogram_algorithm_type: HistogramAlgorithmType,
 }
 
 impl<'a, T: DeviceCopy> PartitionedRelationMutSlice<'a, T> {
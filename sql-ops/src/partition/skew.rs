@@ -0,0 +1,60 @@
+// Copyright 2022 Clemens Lutz
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Detection of skewed partitions.
+//!
+//! Radix partitioning assumes that the keys are spread roughly uniformly over
+//! the partitions. Skewed key distributions (e.g., Zipf) violate this
+//! assumption. Heavy-hitter keys all fall into the same partition, which then
+//! becomes much larger than the average partition.
+//!
+//! Skew is detected using the histograms that the prefix sum already computed.
+//! Thus, detection is cheap and runs before the data are partitioned. A
+//! partition is considered skewed if its length exceeds the average partition
+//! length by more than a skew factor.
+
+use super::PartitionOffsets;
+use crate::error::{ErrorKind, Result};
+use numa_gpu::runtime::memory::DeviceCopy;
+
+/// Returns the IDs of all skewed partitions in ascending order.
+///
+/// A partition is skewed if its length is larger than `skew_factor` times the
+/// average partition length.
+///
+/// The prefix sum must be computed before calling this function, and the
+/// offsets must be accessible by the CPU.
+pub fn skewed_partitions<T: DeviceCopy>(
+    offsets: &PartitionOffsets<T>,
+    skew_factor: f64,
+) -> Result<Vec<u32>> {
+    if !(skew_factor >= 1.0) {
+        Err(ErrorKind::InvalidArgument(
+            "Skew factor must be at least 1.0".to_string(),
+        ))?;
+    }
+
+    let len = offsets
+        .len()
+        .ok_or_else(|| ErrorKind::RuntimeError("Data length not yet computed".to_string()))?;
+    let threshold = skew_factor * len as f64 / offsets.fanout() as f64;
+
+    (0..offsets.fanout())
+        .filter_map(|partition_id| match offsets.partition_len(partition_id) {
+            Ok(partition_len) if partition_len as f64 > threshold => Some(Ok(partition_id)),
+            Ok(_) => None,
+            Err(e) => Some(Err(e)),
+        })
+        .collect()
+}
@@ -14,6 +14,10 @@
 
 //! A collection of prefix scan operators.
 
+mod cpu_prefix_scan;
+#[cfg(feature = "cuda")]
 mod gpu_prefix_scan;
 
+pub use cpu_prefix_scan::{CpuPrefixScanState, CpuPrefixScannable, CpuPrefixSum};
+#[cfg(feature = "cuda")]
 pub use gpu_prefix_scan::{GpuPrefixScanState, GpuPrefixSum};
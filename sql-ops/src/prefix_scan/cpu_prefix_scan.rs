@@ -0,0 +1,260 @@
+// Copyright 2022 Clemens Lutz
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A parallel CPU prefix sum.
+//!
+//! The prefix sum is a single-pass scan with decoupled look-back, as described
+//! by Merrill and Garland in "Single-pass Parallel Prefix Scan with Decoupled
+//! Look-back". `GpuPrefixSum` implements the same algorithm on the GPU.
+//!
+//! The input is split into tiles. Each thread repeatedly fetches the next tile,
+//! reduces the tile, and publishes the tile's aggregate in its
+//! `CpuPrefixScanState`. The thread then looks back at the states of the
+//! preceding tiles, until it finds a tile that has published its inclusive
+//! prefix. Finally, the thread publishes its own inclusive prefix and scans
+//! the tile. As tiles fit into the CPU cache, the data is read from memory
+//! only once.
+//!
+//! Tiles are fetched in ascending order. Thus, a thread only waits for tiles
+//! that are being processed by other threads, which guarantees progress.
+
+use crate::error::{ErrorKind, Result};
+use rayon::ThreadPool;
+use std::cell::UnsafeCell;
+use std::cmp;
+use std::hint;
+use std::mem;
+use std::sync::atomic::{AtomicU32, AtomicUsize, Ordering};
+use std::sync::Mutex;
+
+/// Size of a tile in bytes.
+///
+/// The tile should fit into the L2 cache, so that the scan re-reads the tile
+/// from cache after reducing it.
+const TILE_BYTES: usize = 128 * 1024;
+
+const SCAN_STATUS_INVALID: u32 = 0;
+const SCAN_STATUS_AGGREGATE_AVAIL: u32 = 1;
+const SCAN_STATUS_PREFIX_AVAIL: u32 = 2;
+
+/// Specifies that the implementing type can be summed by `CpuPrefixSum`.
+///
+/// Sums wrap around on overflow, the same as in `GpuPrefixSum`.
+pub trait CpuPrefixScannable: Copy + Default + Send + Sync {
+    fn wrapping_add(self, rhs: Self) -> Self;
+}
+
+macro_rules! impl_cpu_prefix_scannable_for_type {
+    ($Type:ty) => {
+        impl CpuPrefixScannable for $Type {
+            fn wrapping_add(self, rhs: Self) -> Self {
+                <$Type>::wrapping_add(self, rhs)
+            }
+        }
+    };
+}
+
+impl_cpu_prefix_scannable_for_type!(u32);
+impl_cpu_prefix_scannable_for_type!(u64);
+impl_cpu_prefix_scannable_for_type!(i64);
+
+/// The scan state of a tile, which other threads read in their look-back.
+///
+/// The state is aligned to 128 bytes to avoid false sharing between threads.
+#[derive(Debug, Default)]
+#[repr(align(128))]
+pub struct CpuPrefixScanState<T> {
+    status: AtomicU32,
+    aggregate: UnsafeCell<T>,
+    prefix: UnsafeCell<T>,
+}
+
+// The aggregate and the prefix are each written once before their status is
+// published, and are read only after the status is observed.
+unsafe impl<T: Send> Sync for CpuPrefixScanState<T> {}
+
+impl<T: CpuPrefixScannable> CpuPrefixScanState<T> {
+    fn reset(&mut self) {
+        *self.status.get_mut() = SCAN_STATUS_INVALID;
+    }
+
+    fn publish_aggregate(&self, aggregate: T) {
+        unsafe {
+            *self.aggregate.get() = aggregate;
+        }
+        self.status
+            .store(SCAN_STATUS_AGGREGATE_AVAIL, Ordering::Release);
+    }
+
+    fn publish_prefix(&self, prefix: T) {
+        unsafe {
+            *self.prefix.get() = prefix;
+        }
+        self.status
+            .store(SCAN_STATUS_PREFIX_AVAIL, Ordering::Release);
+    }
+}
+
+pub struct CpuPrefixSum;
+
+impl CpuPrefixSum {
+    // Computes state length as number of 'CpuPrefixScanState<T>' elements
+    pub fn state_len<T>(data_len: usize) -> Result<usize> {
+        let tile_len = Self::tile_len::<T>();
+        let padded_len = data_len.checked_add(tile_len - 1).ok_or_else(|| {
+            ErrorKind::IntegerOverflow("Data is too long for the prefix scan state".to_string())
+        })?;
+
+        Ok(padded_len / tile_len)
+    }
+
+    /// Computes the exclusive prefix sum of `data` in-place, and returns the
+    /// total sum.
+    ///
+    /// Runs on the threads of `thread_pool`. `state` must have at least
+    /// `state_len` elements.
+    pub fn exclusive<T: CpuPrefixScannable>(
+        thread_pool: &ThreadPool,
+        data: &mut [T],
+        state: &mut [CpuPrefixScanState<T>],
+    ) -> Result<T> {
+        Self::scan(thread_pool, data, state, true)
+    }
+
+    /// Computes the inclusive prefix sum of `data` in-place, and returns the
+    /// total sum.
+    ///
+    /// Runs on the threads of `thread_pool`. `state` must have at least
+    /// `state_len` elements.
+    pub fn inclusive<T: CpuPrefixScannable>(
+        thread_pool: &ThreadPool,
+        data: &mut [T],
+        state: &mut [CpuPrefixScanState<T>],
+    ) -> Result<T> {
+        Self::scan(thread_pool, data, state, false)
+    }
+
+    fn tile_len<T>() -> usize {
+        cmp::max(TILE_BYTES / cmp::max(mem::size_of::<T>(), 1), 1)
+    }
+
+    fn scan<T: CpuPrefixScannable>(
+        thread_pool: &ThreadPool,
+        data: &mut [T],
+        state: &mut [CpuPrefixScanState<T>],
+        exclusive: bool,
+    ) -> Result<T> {
+        let num_tiles = Self::state_len::<T>(data.len())?;
+        if state.len() < num_tiles {
+            Err(ErrorKind::InvalidArgument(
+                "Prefix scan state is too short".to_string(),
+            ))?;
+        }
+
+        let state = &mut state[0..num_tiles];
+        state.iter_mut().for_each(|tile_state| tile_state.reset());
+
+        let tiles: Vec<Mutex<&mut [T]>> = data
+            .chunks_mut(Self::tile_len::<T>())
+            .map(Mutex::new)
+            .collect();
+        let next_tile = AtomicUsize::new(0);
+        let threads = cmp::min(thread_pool.current_num_threads(), num_tiles);
+
+        {
+            let state = &*state;
+            let tiles = &tiles;
+            let next_tile = &next_tile;
+
+            thread_pool.scope(|s| {
+                for _ in 0..threads {
+                    s.spawn(move |_| loop {
+                        let tile_id = next_tile.fetch_add(1, Ordering::Relaxed);
+                        let tile = match tiles.get(tile_id) {
+                            Some(tile) => tile,
+                            None => break,
+                        };
+
+                        let mut tile = tile.lock().unwrap();
+                        Self::scan_tile(state, tile_id, &mut tile, exclusive);
+                    });
+                }
+            });
+        }
+
+        let total = state
+            .last_mut()
+            .map_or_else(T::default, |tile_state| *tile_state.prefix.get_mut());
+
+        Ok(total)
+    }
+
+    /// Scans a tile with the prefix of all preceding tiles.
+    fn scan_tile<T: CpuPrefixScannable>(
+        state: &[CpuPrefixScanState<T>],
+        tile_id: usize,
+        tile: &mut [T],
+        exclusive: bool,
+    ) {
+        let aggregate = tile
+            .iter()
+            .fold(T::default(), |sum, &item| sum.wrapping_add(item));
+
+        let prefix = if tile_id == 0 {
+            T::default()
+        } else {
+            state[tile_id].publish_aggregate(aggregate);
+            Self::look_back(&state[0..tile_id])
+        };
+        state[tile_id].publish_prefix(prefix.wrapping_add(aggregate));
+
+        let mut sum = prefix;
+        if exclusive {
+            tile.iter_mut().for_each(|item| {
+                let old_sum = sum;
+                sum = sum.wrapping_add(*item);
+                *item = old_sum;
+            });
+        } else {
+            tile.iter_mut().for_each(|item| {
+                sum = sum.wrapping_add(*item);
+                *item = sum;
+            });
+        }
+    }
+
+    /// Returns the exclusive prefix of the tile that follows `predecessors`.
+    fn look_back<T: CpuPrefixScannable>(predecessors: &[CpuPrefixScanState<T>]) -> T {
+        let mut prefix = T::default();
+
+        for predecessor in predecessors.iter().rev() {
+            loop {
+                match predecessor.status.load(Ordering::Acquire) {
+                    SCAN_STATUS_PREFIX_AVAIL => {
+                        let predecessor_prefix = unsafe { *predecessor.prefix.get() };
+                        return prefix.wrapping_add(predecessor_prefix);
+                    }
+                    SCAN_STATUS_AGGREGATE_AVAIL => {
+                        let predecessor_aggregate = unsafe { *predecessor.aggregate.get() };
+                        prefix = prefix.wrapping_add(predecessor_aggregate);
+                        break;
+                    }
+                    _ => hint::spin_loop(),
+                }
+            }
+        }
+
+        prefix
+    }
+}
@@ -0,0 +1,616 @@
+// Copyright 2020-2022 Clemens Lutz
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//! A CPU selection operator.
+//!
+//! The selection evaluates a conjunction of predicates over one or more
+//! columns, and outputs the selected rows either as a selection vector of row
+//! IDs or as a bitmap. The selection is single-threaded, and is externally
+//! parallelized by the caller, e.g., by splitting the columns into chunks.
+//!
+//! The columns of a conjunction may have different types. Each predicate is
+//! wrapped together with its column into a `ColumnPredicate`, which erases the
+//! column's type.
+//!
+//! Range predicates are normalized into closed ranges before the selection.
+//! Thus, each predicate evaluates to two comparisons, regardless of its type.
+//!
+//! The SIMD algorithm compares the values with AVX2 instructions on x86-64, if
+//! the CPU supports AVX2 at run-time. Other CPUs take a scalar code path. As
+//! AVX2 only compares signed integers, unsigned values are compared after
+//! flipping their sign bit, which preserves their order.
+
+use crate::error::{ErrorKind, Result};
+use std::convert::TryFrom;
+use std::ops::Bound;
+
+#[cfg(target_arch = "x86_64")]
+use std::arch::x86_64::{
+    __m256i, _mm256_castsi256_pd, _mm256_castsi256_ps, _mm256_cmpgt_epi32, _mm256_cmpgt_epi64,
+    _mm256_loadu_si256, _mm256_movemask_pd, _mm256_movemask_ps, _mm256_or_si256, _mm256_set1_epi32,
+    _mm256_set1_epi64x, _mm256_xor_si256,
+};
+
+/// Number of rows in a block.
+///
+/// A block's selection fits into a single bitmap word.
+const BLOCK_LEN: usize = 64;
+
+/// Specifies the selection algorithm.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum CpuSelectionAlgorithm {
+    /// Evaluates the predicates with branches.
+    ///
+    /// The predicates are evaluated in order, and evaluation stops at the first
+    /// predicate that fails. Branching performs well if the branches are
+    /// predictable, i.e., at low or high selectivities.
+    Branching,
+
+    /// Evaluates the predicates without branches.
+    ///
+    /// All predicates are evaluated for all rows, and the output is written
+    /// unconditionally. The cost per row is independent of the selectivity.
+    Predication,
+
+    /// Evaluates the predicates block-wise into bitmasks with SIMD instructions.
+    ///
+    /// Each predicate is evaluated on a block of 64 rows at a time. On x86-64,
+    /// the values are compared with AVX2 instructions if the CPU supports them,
+    /// and with a branch-free scalar loop otherwise. The selection vector is
+    /// extracted from the combined bitmask.
+    Simd,
+}
+
+/// Specifies that the implementing type can be selected by `CpuSelection`.
+pub trait CpuSelectable: Copy + PartialOrd + Send + Sync {
+    const MIN: Self;
+    const MAX: Self;
+
+    fn checked_successor(self) -> Option<Self>;
+    fn checked_predecessor(self) -> Option<Self>;
+
+    /// Returns a bitmask of the values in `block` that lie within the closed
+    /// range `[low, high]`.
+    ///
+    /// Bit `i` of the mask is set if `block[i]` lies within the range. The
+    /// block must contain at most 64 values. Uses SIMD instructions if the CPU
+    /// supports them.
+    fn range_mask(block: &[Self], low: Self, high: Self) -> u64;
+
+    /// Wraps the column and its predicate into a `ColumnPredicate`.
+    fn column_predicate(column: &[Self], predicate: Predicate<Self>) -> ColumnPredicate<'_>;
+}
+
+macro_rules! impl_cpu_selectable_for_type {
+    ($Type:ty, $Variant:ident, $SignedType:ty, $sign_flip:expr, $range_mask_avx2:ident) => {
+        impl CpuSelectable for $Type {
+            const MIN: Self = <$Type>::min_value();
+            const MAX: Self = <$Type>::max_value();
+
+            fn checked_successor(self) -> Option<Self> {
+                self.checked_add(1)
+            }
+
+            fn checked_predecessor(self) -> Option<Self> {
+                self.checked_sub(1)
+            }
+
+            // The casts are no-ops for signed types
+            #[allow(clippy::unnecessary_cast)]
+            fn range_mask(block: &[Self], low: Self, high: Self) -> u64 {
+                debug_assert!(block.len() <= BLOCK_LEN);
+
+                #[cfg(target_arch = "x86_64")]
+                {
+                    if is_x86_feature_detected!("avx2") {
+                        let flip: $SignedType = $sign_flip;
+                        let block = unsafe {
+                            std::slice::from_raw_parts(
+                                block.as_ptr() as *const $SignedType,
+                                block.len(),
+                            )
+                        };
+
+                        return unsafe {
+                            $range_mask_avx2(
+                                block,
+                                low as $SignedType ^ flip,
+                                high as $SignedType ^ flip,
+                                flip,
+                            )
+                        };
+                    }
+                }
+
+                range_mask_scalar(block, low, high)
+            }
+
+            fn column_predicate(
+                column: &[Self],
+                predicate: Predicate<Self>,
+            ) -> ColumnPredicate<'_> {
+                ColumnPredicate::$Variant(column, predicate)
+            }
+        }
+    };
+}
+
+// Unsigned types flip their sign bit for the signed SIMD comparison
+impl_cpu_selectable_for_type!(i32, I32, i32, 0, range_mask_avx2_epi32);
+impl_cpu_selectable_for_type!(i64, I64, i64, 0, range_mask_avx2_epi64);
+impl_cpu_selectable_for_type!(u32, U32, i32, i32::MIN, range_mask_avx2_epi32);
+impl_cpu_selectable_for_type!(u64, U64, i64, i64::MIN, range_mask_avx2_epi64);
+
+/// Evaluates a closed range on a block of values without SIMD instructions.
+///
+/// Serves as a fallback for CPUs that don't support AVX2.
+#[inline(always)]
+fn range_mask_scalar<T: Copy + PartialOrd>(block: &[T], low: T, high: T) -> u64 {
+    block.iter().enumerate().fold(0_u64, |acc, (bit, &value)| {
+        acc | ((((value >= low) & (value <= high)) as u64) << bit)
+    })
+}
+
+macro_rules! impl_range_mask_avx2 {
+    ($name:ident, $Type:ty, $lanes:expr, $set1:ident, $cmpgt:ident, $cast:ident, $movemask:ident) => {
+        /// Evaluates a closed range on a block of signed values with AVX2
+        /// instructions.
+        ///
+        /// The values are XORed with `flip` before the comparison. `low` and
+        /// `high` must already be flipped by the caller.
+        #[cfg(target_arch = "x86_64")]
+        #[target_feature(enable = "avx2")]
+        unsafe fn $name(block: &[$Type], low: $Type, high: $Type, flip: $Type) -> u64 {
+            let low_vec = $set1(low);
+            let high_vec = $set1(high);
+            let flip_vec = $set1(flip);
+
+            let vectors = block.chunks_exact($lanes);
+            let remainder = vectors.remainder();
+            let mut mask = 0_u64;
+
+            for (i, vector) in vectors.enumerate() {
+                let values = _mm256_xor_si256(
+                    _mm256_loadu_si256(vector.as_ptr() as *const __m256i),
+                    flip_vec,
+                );
+                let outside = _mm256_or_si256($cmpgt(low_vec, values), $cmpgt(values, high_vec));
+                let outside_bits = $movemask($cast(outside)) as u64;
+                mask |= (!outside_bits & ((1 << $lanes) - 1)) << (i * $lanes);
+            }
+
+            let offset = block.len() - remainder.len();
+            for (bit, &value) in remainder.iter().enumerate() {
+                let value = value ^ flip;
+                mask |= (((value >= low) & (value <= high)) as u64) << (offset + bit);
+            }
+
+            mask
+        }
+    };
+}
+
+impl_range_mask_avx2!(
+    range_mask_avx2_epi32,
+    i32,
+    8,
+    _mm256_set1_epi32,
+    _mm256_cmpgt_epi32,
+    _mm256_castsi256_ps,
+    _mm256_movemask_ps
+);
+impl_range_mask_avx2!(
+    range_mask_avx2_epi64,
+    i64,
+    4,
+    _mm256_set1_epi64x,
+    _mm256_cmpgt_epi64,
+    _mm256_castsi256_pd,
+    _mm256_movemask_pd
+);
+
+/// A predicate on the values of a column.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Predicate<T> {
+    /// Selects values that are equal to the constant.
+    Equal(T),
+
+    /// Selects values that lie within the lower and upper bounds.
+    Range(Bound<T>, Bound<T>),
+}
+
+impl<T: CpuSelectable> Predicate<T> {
+    /// Returns the predicate as a closed range, or `None` if the range is
+    /// empty.
+    fn closed_range(&self) -> Option<ClosedRange<T>> {
+        let (low, high) = match *self {
+            Predicate::Equal(value) => (value, value),
+            Predicate::Range(low, high) => {
+                let low = match low {
+                    Bound::Included(low) => low,
+                    Bound::Excluded(low) => low.checked_successor()?,
+                    Bound::Unbounded => T::MIN,
+                };
+                let high = match high {
+                    Bound::Included(high) => high,
+                    Bound::Excluded(high) => high.checked_predecessor()?,
+                    Bound::Unbounded => T::MAX,
+                };
+                (low, high)
+            }
+        };
+
+        if low <= high {
+            Some(ClosedRange { low, high })
+        } else {
+            None
+        }
+    }
+}
+
+/// A closed range `[low, high]`.
+#[derive(Copy, Clone, Debug)]
+struct ClosedRange<T> {
+    low: T,
+    high: T,
+}
+
+impl<T: CpuSelectable> ClosedRange<T> {
+    /// Evaluates both comparisons without short-circuiting.
+    #[inline(always)]
+    fn contains(&self, value: T) -> bool {
+        (value >= self.low) & (value <= self.high)
+    }
+}
+
+/// A predicate on a column of any selectable type.
+///
+/// Erasing the column type allows a conjunction to combine columns of
+/// different types.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum ColumnPredicate<'a> {
+    I32(&'a [i32], Predicate<i32>),
+    I64(&'a [i64], Predicate<i64>),
+    U32(&'a [u32], Predicate<u32>),
+    U64(&'a [u64], Predicate<u64>),
+}
+
+impl<'a> ColumnPredicate<'a> {
+    /// Creates a predicate on a column.
+    pub fn new<T: CpuSelectable>(column: &'a [T], predicate: Predicate<T>) -> Self {
+        T::column_predicate(column, predicate)
+    }
+
+    /// Returns the length of the column.
+    pub fn len(&self) -> usize {
+        match *self {
+            ColumnPredicate::I32(column, _) => column.len(),
+            ColumnPredicate::I64(column, _) => column.len(),
+            ColumnPredicate::U32(column, _) => column.len(),
+            ColumnPredicate::U64(column, _) => column.len(),
+        }
+    }
+
+    /// Returns `true` if the column is empty.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Normalizes the predicate, or returns `None` if the predicate is never
+    /// true.
+    fn column_range(&self) -> Option<ColumnRange<'a>> {
+        let range = match *self {
+            ColumnPredicate::I32(column, predicate) => {
+                ColumnRange::I32(TypedColumnRange::new(column, predicate)?)
+            }
+            ColumnPredicate::I64(column, predicate) => {
+                ColumnRange::I64(TypedColumnRange::new(column, predicate)?)
+            }
+            ColumnPredicate::U32(column, predicate) => {
+                ColumnRange::U32(TypedColumnRange::new(column, predicate)?)
+            }
+            ColumnPredicate::U64(column, predicate) => {
+                ColumnRange::U64(TypedColumnRange::new(column, predicate)?)
+            }
+        };
+
+        Some(range)
+    }
+}
+
+/// A predicate normalized into a closed range over a column.
+struct TypedColumnRange<'a, T> {
+    column: &'a [T],
+    range: ClosedRange<T>,
+}
+
+impl<'a, T: CpuSelectable> TypedColumnRange<'a, T> {
+    fn new(column: &'a [T], predicate: Predicate<T>) -> Option<Self> {
+        predicate.closed_range().map(|range| Self { column, range })
+    }
+
+    #[inline(always)]
+    fn contains(&self, row: usize) -> bool {
+        self.range.contains(self.column[row])
+    }
+
+    #[inline(always)]
+    fn range_mask(&self, begin: usize, end: usize) -> u64 {
+        T::range_mask(&self.column[begin..end], self.range.low, self.range.high)
+    }
+}
+
+/// A normalized predicate on a column of any selectable type.
+enum ColumnRange<'a> {
+    I32(TypedColumnRange<'a, i32>),
+    I64(TypedColumnRange<'a, i64>),
+    U32(TypedColumnRange<'a, u32>),
+    U64(TypedColumnRange<'a, u64>),
+}
+
+impl ColumnRange<'_> {
+    /// Evaluates the predicate on a row.
+    #[inline(always)]
+    fn contains(&self, row: usize) -> bool {
+        match self {
+            ColumnRange::I32(r) => r.contains(row),
+            ColumnRange::I64(r) => r.contains(row),
+            ColumnRange::U32(r) => r.contains(row),
+            ColumnRange::U64(r) => r.contains(row),
+        }
+    }
+
+    /// Evaluates the predicate on the rows `begin..end` with SIMD instructions.
+    #[inline(always)]
+    fn range_mask(&self, begin: usize, end: usize) -> u64 {
+        match self {
+            ColumnRange::I32(r) => r.range_mask(begin, end),
+            ColumnRange::I64(r) => r.range_mask(begin, end),
+            ColumnRange::U32(r) => r.range_mask(begin, end),
+            ColumnRange::U64(r) => r.range_mask(begin, end),
+        }
+    }
+}
+
+/// A selection operator that evaluates a conjunction of predicates.
+///
+/// The operator is thread-safe, and can be shared between threads that each
+/// select a different chunk of the columns.
+#[derive(Debug)]
+pub struct CpuSelection {
+    algorithm: CpuSelectionAlgorithm,
+}
+
+impl CpuSelection {
+    /// Creates a new selection operator.
+    pub fn new(algorithm: CpuSelectionAlgorithm) -> Self {
+        Self { algorithm }
+    }
+
+    /// Selects the rows that satisfy all predicates, and writes their row IDs
+    /// into `selection_vector`.
+    ///
+    /// Each predicate is paired with the column it is evaluated on. All
+    /// columns must have the same length, and `selection_vector` must be at
+    /// least as long as the columns. Returns the number of selected rows.
+    pub fn select(
+        &self,
+        predicates: &[ColumnPredicate<'_>],
+        selection_vector: &mut [u32],
+    ) -> Result<usize> {
+        let len = Self::column_len(predicates)?;
+
+        if selection_vector.len() < len {
+            Err(ErrorKind::InvalidArgument(
+                "Selection vector must be at least as long as the columns".to_string(),
+            ))?;
+        }
+        if u32::try_from(len).is_err() {
+            Err(ErrorKind::IntegerOverflow(
+                "Column length doesn't fit into a row ID".to_string(),
+            ))?;
+        }
+
+        let ranges = match Self::closed_ranges(predicates) {
+            Some(ranges) => ranges,
+            None => return Ok(0),
+        };
+        let selection_vector = &mut selection_vector[0..len];
+
+        let selected = match self.algorithm {
+            CpuSelectionAlgorithm::Branching => {
+                Self::select_branching(&ranges, len, selection_vector)
+            }
+            CpuSelectionAlgorithm::Predication => {
+                Self::select_predication(&ranges, len, selection_vector)
+            }
+            CpuSelectionAlgorithm::Simd => Self::select_simd(&ranges, len, selection_vector),
+        };
+
+        Ok(selected)
+    }
+
+    /// Selects the rows that satisfy all predicates, and sets their bits in
+    /// `bitmap`.
+    ///
+    /// Row `i` is stored in bit `i % 64` of word `i / 64`. `bitmap` must have
+    /// at least `bitmap_len` words. Bits of unselected rows, and bits beyond
+    /// the column length, are cleared. Returns the number of selected rows.
+    pub fn select_bitmap(
+        &self,
+        predicates: &[ColumnPredicate<'_>],
+        bitmap: &mut [u64],
+    ) -> Result<usize> {
+        let len = Self::column_len(predicates)?;
+        let bitmap_len = Self::bitmap_len(len);
+
+        if bitmap.len() < bitmap_len {
+            Err(ErrorKind::InvalidArgument(
+                "Bitmap is too short for the columns".to_string(),
+            ))?;
+        }
+
+        let bitmap = &mut bitmap[0..bitmap_len];
+        let ranges = match Self::closed_ranges(predicates) {
+            Some(ranges) => ranges,
+            None => {
+                bitmap.iter_mut().for_each(|word| *word = 0);
+                return Ok(0);
+            }
+        };
+
+        let block_mask: fn(&[ColumnRange<'_>], usize, usize) -> u64 = match self.algorithm {
+            CpuSelectionAlgorithm::Branching => Self::block_mask_branching,
+            CpuSelectionAlgorithm::Predication => Self::block_mask_predication,
+            CpuSelectionAlgorithm::Simd => Self::block_mask_simd,
+        };
+
+        let selected = bitmap
+            .iter_mut()
+            .enumerate()
+            .map(|(block_id, word)| {
+                let begin = block_id * BLOCK_LEN;
+                let end = std::cmp::min(begin + BLOCK_LEN, len);
+                *word = block_mask(&ranges, begin, end);
+                word.count_ones() as usize
+            })
+            .sum();
+
+        Ok(selected)
+    }
+
+    /// Returns the number of bitmap words required to store `len` rows.
+    pub fn bitmap_len(len: usize) -> usize {
+        (len + BLOCK_LEN - 1) / BLOCK_LEN
+    }
+
+    /// Returns the common length of the columns.
+    fn column_len(predicates: &[ColumnPredicate<'_>]) -> Result<usize> {
+        let len = match predicates.first() {
+            Some(predicate) => predicate.len(),
+            None => Err(ErrorKind::InvalidArgument(
+                "Selection requires at least one predicate".to_string(),
+            ))?,
+        };
+
+        if predicates.iter().any(|predicate| predicate.len() != len) {
+            Err(ErrorKind::InvalidArgument(
+                "Columns must have the same length".to_string(),
+            ))?;
+        }
+
+        Ok(len)
+    }
+
+    /// Normalizes the predicates, or returns `None` if any predicate is never
+    /// true.
+    fn closed_ranges<'a>(predicates: &[ColumnPredicate<'a>]) -> Option<Vec<ColumnRange<'a>>> {
+        predicates
+            .iter()
+            .map(|predicate| predicate.column_range())
+            .collect()
+    }
+
+    fn select_branching(
+        ranges: &[ColumnRange<'_>],
+        len: usize,
+        selection_vector: &mut [u32],
+    ) -> usize {
+        let mut selected = 0;
+
+        for i in 0..len {
+            if ranges.iter().all(|r| r.contains(i)) {
+                selection_vector[selected] = i as u32;
+                selected += 1;
+            }
+        }
+
+        selected
+    }
+
+    fn select_predication(
+        ranges: &[ColumnRange<'_>],
+        len: usize,
+        selection_vector: &mut [u32],
+    ) -> usize {
+        let mut selected = 0;
+
+        // The write position never overtakes the row ID, thus the write is
+        // always in bounds.
+        for i in 0..len {
+            let is_selected = ranges.iter().fold(true, |acc, r| acc & r.contains(i));
+            selection_vector[selected] = i as u32;
+            selected += is_selected as usize;
+        }
+
+        selected
+    }
+
+    fn select_simd(ranges: &[ColumnRange<'_>], len: usize, selection_vector: &mut [u32]) -> usize {
+        let mut selected = 0;
+
+        for begin in (0..len).step_by(BLOCK_LEN) {
+            let end = std::cmp::min(begin + BLOCK_LEN, len);
+            let mut mask = Self::block_mask_simd(ranges, begin, end);
+
+            while mask != 0 {
+                let bit = mask.trailing_zeros() as usize;
+                selection_vector[selected] = (begin + bit) as u32;
+                selected += 1;
+                mask &= mask - 1;
+            }
+        }
+
+        selected
+    }
+
+    fn block_mask_branching(ranges: &[ColumnRange<'_>], begin: usize, end: usize) -> u64 {
+        let mut mask = 0;
+
+        for (bit, i) in (begin..end).enumerate() {
+            if ranges.iter().all(|r| r.contains(i)) {
+                mask |= 1 << bit;
+            }
+        }
+
+        mask
+    }
+
+    fn block_mask_predication(ranges: &[ColumnRange<'_>], begin: usize, end: usize) -> u64 {
+        let mut mask = 0;
+
+        for (bit, i) in (begin..end).enumerate() {
+            let is_selected = ranges.iter().fold(true, |acc, r| acc & r.contains(i));
+            mask |= (is_selected as u64) << bit;
+        }
+
+        mask
+    }
+
+    /// Evaluates the predicates column-wise on a block, and combines their
+    /// bitmasks.
+    fn block_mask_simd(ranges: &[ColumnRange<'_>], begin: usize, end: usize) -> u64 {
+        let mut mask = !0_u64 >> (BLOCK_LEN - (end - begin));
+
+        for r in ranges {
+            // Skip the remaining predicates if no row is left in the block.
+            if mask == 0 {
+                break;
+            }
+
+            mask &= r.range_mask(begin, end);
+        }
+
+        mask
+    }
+}
@@ -13,7 +13,7 @@
 // limitations under the License.
 
 use datagen::relation::KeyAttribute;
-use rustacuda::memory::DeviceCopy;
+use numa_gpu::runtime::memory::DeviceCopy;
 use sql_ops::partition::{PartitionedRelation, RadixBits, RadixPass, Tuple};
 use std::collections::hash_map::{Entry, HashMap};
 use std::error::Error;
@@ -30,16 +30,17 @@ pub fn key_to_partition(key: i32, radix_bits: &RadixBits, radix_pass: RadixPass)
     partition
 }
 
-pub fn tuple_loss_or_duplicates<T>(
+pub fn tuple_loss_or_duplicates<K, V>(
     _radix_pass: RadixPass,
     _radix_bits: &RadixBits,
-    data_key: &[T],
-    data_pay: &[T],
-    partitioned_relation: &PartitionedRelation<Tuple<T, T>>,
+    data_key: &[K],
+    data_pay: &[V],
+    partitioned_relation: &PartitionedRelation<Tuple<K, V>>,
     partition_id: Option<u32>,
 ) -> Result<(), Box<dyn Error>>
 where
-    T: Clone + Debug + Default + Display + DeviceCopy + Eq + Hash + KeyAttribute,
+    K: Clone + Debug + Default + Display + DeviceCopy + Eq + Hash + KeyAttribute,
+    V: Clone + Debug + Default + Display + DeviceCopy + PartialEq,
 {
     let mut original_tuples: HashMap<_, _> = data_key
         .iter()
@@ -68,7 +69,7 @@ where
             }
             entry @ Entry::Vacant(_) => {
                 // skip padding entries
-                if *entry.key() != T::null_key() {
+                if *entry.key() != K::null_key() {
                     assert!(false, "Invalid key: {}", entry.key());
                 }
             }
@@ -0,0 +1,144 @@
+// Copyright 2019-2022 Clemens Lutz
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use rand::distributions::{Distribution, Standard};
+use rand::{thread_rng, Rng};
+use rayon::ThreadPoolBuilder;
+use sql_ops::prefix_scan::{CpuPrefixScanState, CpuPrefixScannable, CpuPrefixSum};
+use std::error::Error;
+use std::fmt::Debug;
+
+fn cpu_prefix_sum<T>(data_len: usize, threads: usize, exclusive: bool) -> Result<(), Box<dyn Error>>
+where
+    T: CpuPrefixScannable + Debug + PartialEq,
+    Standard: Distribution<T>,
+{
+    let thread_pool = ThreadPoolBuilder::new().num_threads(threads).build()?;
+
+    let data: Vec<T> = (0..data_len)
+        .into_iter()
+        .scan(thread_rng(), |rng, _| Some(rng.gen()))
+        .collect();
+    let mut result = data.clone();
+
+    let state_len = CpuPrefixSum::state_len::<T>(data_len)?;
+    let mut state: Vec<CpuPrefixScanState<T>> = (0..state_len)
+        .map(|_| CpuPrefixScanState::default())
+        .collect();
+
+    let total = if exclusive {
+        CpuPrefixSum::exclusive(&thread_pool, &mut result, &mut state)?
+    } else {
+        CpuPrefixSum::inclusive(&thread_pool, &mut result, &mut state)?
+    };
+
+    let prefix_sum: Vec<_> = data
+        .iter()
+        .scan(T::default(), |sum, &item| {
+            let old_sum = *sum;
+            *sum = sum.wrapping_add(item);
+            Some(if exclusive { old_sum } else { *sum })
+        })
+        .collect();
+    let check_total = data
+        .iter()
+        .fold(T::default(), |sum, &item| sum.wrapping_add(item));
+
+    prefix_sum
+        .iter()
+        .cloned()
+        .zip(result.iter().cloned())
+        .for_each(|(check, res)| {
+            assert_eq!(check, res);
+        });
+    assert_eq!(check_total, total);
+
+    Ok(())
+}
+
+#[test]
+fn cpu_exclusive_prefix_sum_empty() -> Result<(), Box<dyn Error>> {
+    cpu_prefix_sum::<u64>(0, 4, true)
+}
+
+#[test]
+fn cpu_exclusive_prefix_sum_single_item() -> Result<(), Box<dyn Error>> {
+    cpu_prefix_sum::<u64>(1, 4, true)
+}
+
+#[test]
+fn cpu_exclusive_prefix_sum_single_thread() -> Result<(), Box<dyn Error>> {
+    cpu_prefix_sum::<u64>(1_000_000, 1, true)
+}
+
+#[test]
+fn cpu_exclusive_prefix_sum_u32() -> Result<(), Box<dyn Error>> {
+    cpu_prefix_sum::<u32>(1_000_000, 4, true)
+}
+
+#[test]
+fn cpu_exclusive_prefix_sum_u64() -> Result<(), Box<dyn Error>> {
+    cpu_prefix_sum::<u64>(1_000_000, 4, true)
+}
+
+#[test]
+fn cpu_exclusive_prefix_sum_i64() -> Result<(), Box<dyn Error>> {
+    cpu_prefix_sum::<i64>(1_000_000, 4, true)
+}
+
+#[test]
+fn cpu_exclusive_prefix_sum_more_threads_than_tiles() -> Result<(), Box<dyn Error>> {
+    cpu_prefix_sum::<u64>(100, 16, true)
+}
+
+#[test]
+fn cpu_exclusive_prefix_sum_non_power_2_data_len() -> Result<(), Box<dyn Error>> {
+    cpu_prefix_sum::<u64>(1_000_003, 7, true)
+}
+
+#[test]
+fn cpu_inclusive_prefix_sum_u32() -> Result<(), Box<dyn Error>> {
+    cpu_prefix_sum::<u32>(1_000_000, 4, false)
+}
+
+#[test]
+fn cpu_inclusive_prefix_sum_u64() -> Result<(), Box<dyn Error>> {
+    cpu_prefix_sum::<u64>(1_000_000, 4, false)
+}
+
+#[test]
+fn cpu_inclusive_prefix_sum_i64() -> Result<(), Box<dyn Error>> {
+    cpu_prefix_sum::<i64>(1_000_000, 4, false)
+}
+
+#[test]
+fn cpu_inclusive_prefix_sum_non_power_2_data_len() -> Result<(), Box<dyn Error>> {
+    cpu_prefix_sum::<i64>(1_000_003, 7, false)
+}
+
+#[test]
+fn cpu_prefix_sum_rejects_short_state() -> Result<(), Box<dyn Error>> {
+    let thread_pool = ThreadPoolBuilder::new().num_threads(4).build()?;
+    let mut data = vec![1_u64; 1_000_000];
+    let mut state: Vec<CpuPrefixScanState<u64>> = Vec::new();
+
+    assert!(CpuPrefixSum::exclusive(&thread_pool, &mut data, &mut state).is_err());
+
+    Ok(())
+}
+
+#[test]
+fn cpu_prefix_sum_state_len_rejects_overflow() {
+    assert!(CpuPrefixSum::state_len::<u64>(usize::MAX).is_err());
+}
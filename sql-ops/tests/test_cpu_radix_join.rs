@@ -0,0 +1,497 @@
+// Copyright 2022 Clemens Lutz
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use datagen::relation::UniformRelation;
+use itertools::izip;
+use numa_gpu::runtime::allocator::{Allocator, DerefMemType, MemType};
+use sql_ops::hash_function::HashFunction;
+use sql_ops::join::cpu_radix_join::{CpuRadixJoin, RadixJoinCursor};
+use sql_ops::join::{HashingScheme, JoinPair};
+use sql_ops::partition::cpu_radix_partition::{
+    CpuHistogramAlgorithm, CpuRadixPartitionAlgorithm, CpuRadixPartitioner,
+};
+use sql_ops::partition::{
+    PartitionOffsets, PartitionedRelation, RadixBits, RadixPartitionInputChunkable, RadixPass,
+    Tuple,
+};
+use std::error::Error;
+use std::result::Result;
+
+fn partition(
+    key: &[i32],
+    pay: &[i32],
+    radix_bits: &RadixBits,
+    radix_pass: RadixPass,
+    chunks: u32,
+    hash_function: HashFunction,
+) -> Result<PartitionedRelation<Tuple<i32, i32>>, Box<dyn Error>> {
+    let histogram_algorithm = CpuHistogramAlgorithm::Chunked;
+    let partition_algorithm = CpuRadixPartitionAlgorithm::Swwc;
+    let pass_bits = radix_bits.pass_radix_bits(radix_pass).unwrap();
+
+    let mut partition_offsets = PartitionOffsets::new(
+        histogram_algorithm.into(),
+        chunks,
+        pass_bits,
+        Allocator::mem_alloc_fn(MemType::SysMem),
+    );
+
+    let mut partitioned_relation = PartitionedRelation::new(
+        key.len(),
+        histogram_algorithm.into(),
+        pass_bits,
+        chunks,
+        Allocator::mem_alloc_fn(MemType::SysMem),
+        Allocator::mem_alloc_fn(MemType::SysMem),
+    );
+
+    let mut partitioner = CpuRadixPartitioner::new_with_pass(
+        histogram_algorithm,
+        partition_algorithm,
+        radix_bits,
+        radix_pass,
+        DerefMemType::SysMem,
+    )?
+    .with_hash_function(hash_function);
+
+    for (key_chunk, offsets_chunk) in izip!(
+        key.input_chunks::<i32>(chunks)?.into_iter(),
+        partition_offsets.chunks_mut()
+    ) {
+        partitioner.prefix_sum(key_chunk, offsets_chunk)?;
+    }
+
+    for (key_chunk, pay_chunk, offsets_chunk, partitioned_chunk) in izip!(
+        key.input_chunks::<i32>(chunks)?.into_iter(),
+        pay.input_chunks::<i32>(chunks)?.into_iter(),
+        partition_offsets.chunks_mut(),
+        partitioned_relation.chunks_mut()
+    ) {
+        partitioner.partition(key_chunk, pay_chunk, offsets_chunk, partitioned_chunk)?;
+    }
+
+    Ok(partitioned_relation)
+}
+
+fn cpu_verify_join_aggregate(
+    build_tuples: usize,
+    probe_tuples: usize,
+    hashing_scheme: HashingScheme,
+    hash_function: HashFunction,
+    radix_bits: RadixBits,
+    radix_pass: RadixPass,
+    chunks: u32,
+) -> Result<(), Box<dyn Error>> {
+    let mut inner_rel_key = vec![0_i32; build_tuples];
+    let mut inner_rel_pay = vec![0_i32; build_tuples];
+    let mut outer_rel_key = vec![0_i32; probe_tuples];
+    let mut outer_rel_pay = vec![0_i32; probe_tuples];
+
+    UniformRelation::gen_primary_key(&mut inner_rel_key, None, None)?;
+    UniformRelation::gen_foreign_key_from_primary_key(&mut outer_rel_key, &inner_rel_key, None);
+
+    inner_rel_pay
+        .iter_mut()
+        .enumerate()
+        .for_each(|(i, x)| *x = (i + 1) as i32);
+    outer_rel_pay
+        .iter_mut()
+        .enumerate()
+        .for_each(|(i, x)| *x = (i + 1) as i32);
+
+    let inner_rel_partitions = partition(
+        &inner_rel_key,
+        &inner_rel_pay,
+        &radix_bits,
+        radix_pass,
+        chunks,
+        hash_function,
+    )?;
+    let outer_rel_partitions = partition(
+        &outer_rel_key,
+        &outer_rel_pay,
+        &radix_bits,
+        radix_pass,
+        chunks,
+        hash_function,
+    )?;
+
+    let mut radix_join = CpuRadixJoin::new(radix_pass, radix_bits, hashing_scheme)?
+        .with_hash_function(hash_function);
+
+    let mut result_sum = 0;
+    radix_join.join(
+        &inner_rel_partitions,
+        &outer_rel_partitions,
+        &mut result_sum,
+    )?;
+
+    assert_eq!(
+        (probe_tuples as i64 * (probe_tuples as i64 + 1)) / 2,
+        result_sum
+    );
+
+    Ok(())
+}
+
+fn cpu_verify_join_materialize(
+    build_tuples: usize,
+    probe_tuples: usize,
+    hashing_scheme: HashingScheme,
+    radix_bits: RadixBits,
+    chunks: u32,
+    output_len: usize,
+) -> Result<(), Box<dyn Error>> {
+    let radix_pass = RadixPass::First;
+
+    let mut inner_rel_key = vec![0_i32; build_tuples];
+    let mut outer_rel_key = vec![0_i32; probe_tuples];
+
+    UniformRelation::gen_primary_key(&mut inner_rel_key, None, None)?;
+    UniformRelation::gen_foreign_key_from_primary_key(&mut outer_rel_key, &inner_rel_key, None);
+
+    // Use the row IDs as payloads to check the result pairs
+    let inner_rel_pay: Vec<i32> = (0..build_tuples as i32).collect();
+    let outer_rel_pay: Vec<i32> = (0..probe_tuples as i32).collect();
+
+    let inner_rel_partitions = partition(
+        &inner_rel_key,
+        &inner_rel_pay,
+        &radix_bits,
+        radix_pass,
+        chunks,
+        HashFunction::Identity,
+    )?;
+    let outer_rel_partitions = partition(
+        &outer_rel_key,
+        &outer_rel_pay,
+        &radix_bits,
+        radix_pass,
+        chunks,
+        HashFunction::Identity,
+    )?;
+
+    let mut radix_join = CpuRadixJoin::new(radix_pass, radix_bits, hashing_scheme)?;
+
+    let mut cursor = RadixJoinCursor::default();
+    let mut output = vec![JoinPair::default(); output_len];
+    let mut result: Vec<JoinPair<i32, i32>> = Vec::new();
+
+    while !cursor.is_finished() {
+        let written = radix_join.join_materialize(
+            &inner_rel_partitions,
+            &outer_rel_partitions,
+            &mut cursor,
+            &mut output,
+        )?;
+        result.extend_from_slice(&output[0..written]);
+    }
+
+    let mut expected: Vec<JoinPair<i32, i32>> = outer_rel_key
+        .iter()
+        .enumerate()
+        .map(|(probe, key)| JoinPair {
+            build: inner_rel_key.iter().position(|k| k == key).unwrap() as i32,
+            probe: probe as i32,
+        })
+        .collect();
+
+    result.sort();
+    expected.sort();
+    assert_eq!(expected, result);
+
+    Ok(())
+}
+
+#[test]
+fn cpu_verify_join_aggregate_perfect_i32_0_bits() -> Result<(), Box<dyn Error>> {
+    cpu_verify_join_aggregate(
+        6100,
+        6100,
+        HashingScheme::Perfect,
+        HashFunction::Identity,
+        RadixBits::from(0),
+        RadixPass::First,
+        1,
+    )
+}
+
+#[test]
+fn cpu_verify_join_aggregate_perfect_i32_2_bits() -> Result<(), Box<dyn Error>> {
+    cpu_verify_join_aggregate(
+        6100,
+        6100,
+        HashingScheme::Perfect,
+        HashFunction::Identity,
+        RadixBits::from(2),
+        RadixPass::First,
+        1,
+    )
+}
+
+#[test]
+fn cpu_verify_join_aggregate_perfect_i32_8_bits() -> Result<(), Box<dyn Error>> {
+    cpu_verify_join_aggregate(
+        6100,
+        6100,
+        HashingScheme::Perfect,
+        HashFunction::Identity,
+        RadixBits::from(8),
+        RadixPass::First,
+        1,
+    )
+}
+
+#[test]
+fn cpu_verify_join_aggregate_perfect_i32_8_bits_4_chunks() -> Result<(), Box<dyn Error>> {
+    cpu_verify_join_aggregate(
+        6100,
+        12200,
+        HashingScheme::Perfect,
+        HashFunction::Identity,
+        RadixBits::from(8),
+        RadixPass::First,
+        4,
+    )
+}
+
+#[test]
+fn cpu_verify_join_aggregate_bucketchaining_i32_0_bits() -> Result<(), Box<dyn Error>> {
+    cpu_verify_join_aggregate(
+        6100,
+        6100,
+        HashingScheme::BucketChaining,
+        HashFunction::Identity,
+        RadixBits::from(0),
+        RadixPass::First,
+        1,
+    )
+}
+
+#[test]
+fn cpu_verify_join_aggregate_bucketchaining_i32_8_bits() -> Result<(), Box<dyn Error>> {
+    cpu_verify_join_aggregate(
+        6100,
+        6100,
+        HashingScheme::BucketChaining,
+        HashFunction::Identity,
+        RadixBits::from(8),
+        RadixPass::First,
+        1,
+    )
+}
+
+#[test]
+fn cpu_verify_join_aggregate_bucketchaining_i32_8_bits_4_chunks() -> Result<(), Box<dyn Error>> {
+    cpu_verify_join_aggregate(
+        6100,
+        12200,
+        HashingScheme::BucketChaining,
+        HashFunction::Identity,
+        RadixBits::from(8),
+        RadixPass::First,
+        4,
+    )
+}
+
+#[test]
+fn cpu_verify_join_aggregate_bucketchaining_i32_2nd_pass() -> Result<(), Box<dyn Error>> {
+    cpu_verify_join_aggregate(
+        6100,
+        6100,
+        HashingScheme::BucketChaining,
+        HashFunction::Identity,
+        RadixBits::new(Some(4), Some(4), None),
+        RadixPass::Second,
+        1,
+    )
+}
+
+#[test]
+fn cpu_verify_join_aggregate_bucketchaining_i32_multiplicative() -> Result<(), Box<dyn Error>> {
+    cpu_verify_join_aggregate(
+        6100,
+        12200,
+        HashingScheme::BucketChaining,
+        HashFunction::Multiplicative,
+        RadixBits::from(8),
+        RadixPass::First,
+        4,
+    )
+}
+
+#[test]
+fn cpu_verify_join_aggregate_bucketchaining_i32_murmur3() -> Result<(), Box<dyn Error>> {
+    cpu_verify_join_aggregate(
+        6100,
+        6100,
+        HashingScheme::BucketChaining,
+        HashFunction::Murmur3,
+        RadixBits::new(Some(4), Some(4), None),
+        RadixPass::Second,
+        1,
+    )
+}
+
+#[test]
+fn cpu_verify_join_aggregate_bucketchaining_i32_crc32() -> Result<(), Box<dyn Error>> {
+    cpu_verify_join_aggregate(
+        6100,
+        6100,
+        HashingScheme::BucketChaining,
+        HashFunction::Crc32,
+        RadixBits::from(8),
+        RadixPass::First,
+        1,
+    )
+}
+
+#[test]
+fn cpu_verify_join_materialize_perfect_i32_4_bits() -> Result<(), Box<dyn Error>> {
+    cpu_verify_join_materialize(
+        1000,
+        2000,
+        HashingScheme::Perfect,
+        RadixBits::from(4),
+        1,
+        4096,
+    )
+}
+
+#[test]
+fn cpu_verify_join_materialize_perfect_i32_small_output() -> Result<(), Box<dyn Error>> {
+    cpu_verify_join_materialize(
+        1000,
+        2000,
+        HashingScheme::Perfect,
+        RadixBits::from(4),
+        3,
+        37,
+    )
+}
+
+#[test]
+fn cpu_verify_join_materialize_bucketchaining_i32_4_bits() -> Result<(), Box<dyn Error>> {
+    cpu_verify_join_materialize(
+        1000,
+        2000,
+        HashingScheme::BucketChaining,
+        RadixBits::from(4),
+        1,
+        4096,
+    )
+}
+
+#[test]
+fn cpu_verify_join_materialize_bucketchaining_i32_small_output() -> Result<(), Box<dyn Error>> {
+    cpu_verify_join_materialize(
+        1000,
+        2000,
+        HashingScheme::BucketChaining,
+        RadixBits::from(4),
+        3,
+        37,
+    )
+}
+
+#[test]
+fn cpu_radix_join_materialize_rejects_empty_output() -> Result<(), Box<dyn Error>> {
+    let key = vec![1_i32; 16];
+    let pay = vec![0_i32; 16];
+    let radix_bits = RadixBits::from(2);
+
+    let rel = partition(
+        &key,
+        &pay,
+        &radix_bits,
+        RadixPass::First,
+        1,
+        HashFunction::Identity,
+    )?;
+    let mut radix_join =
+        CpuRadixJoin::new(RadixPass::First, radix_bits, HashingScheme::BucketChaining)?;
+
+    let mut cursor = RadixJoinCursor::default();
+    let mut output: Vec<JoinPair<i32, i32>> = Vec::new();
+    assert!(radix_join
+        .join_materialize(&rel, &rel, &mut cursor, &mut output)
+        .is_err());
+
+    Ok(())
+}
+
+#[test]
+fn cpu_radix_join_rejects_linear_probing() {
+    assert!(CpuRadixJoin::new(
+        RadixPass::First,
+        RadixBits::from(4),
+        HashingScheme::LinearProbing
+    )
+    .is_err());
+}
+
+#[test]
+fn cpu_radix_join_rejects_perfect_hashing_with_hash_function() -> Result<(), Box<dyn Error>> {
+    let key: Vec<i32> = (0..16).collect();
+    let pay = vec![0_i32; 16];
+    let radix_bits = RadixBits::from(2);
+
+    let rel = partition(
+        &key,
+        &pay,
+        &radix_bits,
+        RadixPass::First,
+        1,
+        HashFunction::Murmur3,
+    )?;
+    let mut radix_join = CpuRadixJoin::new(RadixPass::First, radix_bits, HashingScheme::Perfect)?
+        .with_hash_function(HashFunction::Murmur3);
+
+    let mut result_sum = 0;
+    assert!(radix_join.join(&rel, &rel, &mut result_sum).is_err());
+
+    Ok(())
+}
+
+#[test]
+fn cpu_radix_join_perfect_rejects_out_of_range_key() -> Result<(), Box<dyn Error>> {
+    let mut key: Vec<i32> = (0..16).collect();
+    let pay = vec![0_i32; 16];
+    let radix_bits = RadixBits::from(2);
+
+    // The key hashes to an index far beyond the end of the hash table
+    key[15] = 1 << 20;
+
+    let rel = partition(
+        &key,
+        &pay,
+        &radix_bits,
+        RadixPass::First,
+        1,
+        HashFunction::Identity,
+    )?;
+    let mut radix_join = CpuRadixJoin::new(RadixPass::First, radix_bits, HashingScheme::Perfect)?;
+
+    let mut result_sum = 0;
+    assert!(radix_join.join(&rel, &rel, &mut result_sum).is_err());
+    assert_eq!(0, result_sum);
+
+    let mut cursor = RadixJoinCursor::default();
+    let mut output = vec![JoinPair::default(); 16];
+    assert!(radix_join
+        .join_materialize(&rel, &rel, &mut cursor, &mut output)
+        .is_err());
+
+    Ok(())
+}
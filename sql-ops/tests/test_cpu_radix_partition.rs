@@ -14,23 +14,29 @@
 
 pub mod radix_partition;
 
-use datagen::relation::{KeyAttribute, UniformRelation};
+use datagen::relation::{KeyAttribute, UniformRelation, ZipfRelation};
 use itertools::izip;
 use numa_gpu::runtime::allocator::{Allocator, DerefMemType, MemType};
-use radix_partition::{tuple_loss_or_duplicates, verify_partitions};
-use rustacuda::memory::DeviceCopy;
+use numa_gpu::runtime::memory::DeviceCopy;
+use radix_partition::{key_to_partition, tuple_loss_or_duplicates, verify_partitions};
+use sql_ops::composite_key::CompositeKey;
+use sql_ops::partition::cpu_multi_pass_radix_partition::CpuMultiPassRadixPartitioner;
 use sql_ops::partition::cpu_radix_partition::{
     CpuHistogramAlgorithm, CpuRadixPartitionAlgorithm, CpuRadixPartitionable, CpuRadixPartitioner,
+    X86SimdIsa,
 };
 use sql_ops::partition::{
-    PartitionOffsets, PartitionedRelation, RadixBits, RadixPartitionInputChunkable, RadixPass,
-    Tuple,
+    skew, PartitionOffsets, PartitionedRelation, RadixBits, RadixPartitionInputChunkable,
+    RadixPass, Tuple,
 };
+use std::collections::HashMap;
 use std::error::Error;
+use std::fmt::Display;
+use std::hash::Hash;
 use std::mem::size_of;
 use std::result::Result;
 
-fn run_cpu_partitioning<T, KeyGenFn, PayGenFn, ValidatorFn>(
+fn run_cpu_partitioning<K, V, KeyGenFn, PayGenFn, ValidatorFn>(
     tuples: usize,
     key_gen: Box<KeyGenFn>,
     pay_gen: Box<PayGenFn>,
@@ -38,23 +44,62 @@ fn run_cpu_partitioning<T, KeyGenFn, PayGenFn, ValidatorFn>(
     partition_algorithm: CpuRadixPartitionAlgorithm,
     radix_bits: RadixBits,
     threads: u32,
+    validator: Box<ValidatorFn>,
+) -> Result<(), Box<dyn Error>>
+where
+    K: Clone + Default + KeyAttribute + DeviceCopy + CpuRadixPartitionable<V>,
+    V: Clone + Default + DeviceCopy,
+    KeyGenFn: FnOnce(&mut [K]) -> Result<(), Box<dyn Error>>,
+    PayGenFn: FnOnce(&mut [V]) -> Result<(), Box<dyn Error>>,
+    ValidatorFn: FnMut(
+        RadixPass,
+        &RadixBits,
+        &[K],
+        &[V],
+        &PartitionedRelation<Tuple<K, V>>,
+        Option<u32>,
+    ) -> Result<(), Box<dyn Error>>,
+{
+    run_cpu_partitioning_with_simd_isa(
+        tuples,
+        key_gen,
+        pay_gen,
+        prefix_sum_algorithm,
+        partition_algorithm,
+        X86SimdIsa::default(),
+        radix_bits,
+        threads,
+        validator,
+    )
+}
+
+fn run_cpu_partitioning_with_simd_isa<K, V, KeyGenFn, PayGenFn, ValidatorFn>(
+    tuples: usize,
+    key_gen: Box<KeyGenFn>,
+    pay_gen: Box<PayGenFn>,
+    prefix_sum_algorithm: CpuHistogramAlgorithm,
+    partition_algorithm: CpuRadixPartitionAlgorithm,
+    simd_isa: X86SimdIsa,
+    radix_bits: RadixBits,
+    threads: u32,
     mut validator: Box<ValidatorFn>,
 ) -> Result<(), Box<dyn Error>>
 where
-    T: Clone + Default + KeyAttribute + DeviceCopy + CpuRadixPartitionable,
-    KeyGenFn: FnOnce(&mut [T]) -> Result<(), Box<dyn Error>>,
-    PayGenFn: FnOnce(&mut [T]) -> Result<(), Box<dyn Error>>,
+    K: Clone + Default + KeyAttribute + DeviceCopy + CpuRadixPartitionable<V>,
+    V: Clone + Default + DeviceCopy,
+    KeyGenFn: FnOnce(&mut [K]) -> Result<(), Box<dyn Error>>,
+    PayGenFn: FnOnce(&mut [V]) -> Result<(), Box<dyn Error>>,
     ValidatorFn: FnMut(
         RadixPass,
         &RadixBits,
-        &[T],
-        &[T],
-        &PartitionedRelation<Tuple<T, T>>,
+        &[K],
+        &[V],
+        &PartitionedRelation<Tuple<K, V>>,
         Option<u32>,
     ) -> Result<(), Box<dyn Error>>,
 {
-    let mut data_key: Vec<T> = vec![T::default(); tuples];
-    let mut data_pay: Vec<T> = vec![T::default(); tuples];
+    let mut data_key: Vec<K> = vec![K::default(); tuples];
+    let mut data_pay: Vec<V> = vec![V::default(); tuples];
 
     key_gen(data_key.as_mut_slice())?;
     pay_gen(data_pay.as_mut_slice())?;
@@ -81,8 +126,8 @@ where
             .iter_mut()
             .for_each(|x| {
                 *x = Tuple {
-                    key: T::null_key(),
-                    value: T::default(),
+                    key: K::null_key(),
+                    value: V::default(),
                 }
             });
     }
@@ -92,9 +137,10 @@ where
         partition_algorithm,
         radix_bits.pass_radix_bits(RadixPass::First).unwrap(),
         DerefMemType::SysMem,
-    );
+    )?
+    .with_x86_simd_isa(simd_isa);
 
-    let data_key_chunks = data_key.as_slice().input_chunks::<T>(threads)?;
+    let data_key_chunks = data_key.as_slice().input_chunks::<K>(threads)?;
 
     for (key_chunk, offsets_chunk) in
         izip!(data_key_chunks.into_iter(), partition_offsets.chunks_mut(),)
@@ -102,8 +148,8 @@ where
         partitioner.prefix_sum(key_chunk, offsets_chunk)?;
     }
 
-    let data_key_chunks = data_key.as_slice().input_chunks::<T>(threads)?;
-    let data_pay_chunks = data_pay.as_slice().input_chunks::<T>(threads)?;
+    let data_key_chunks = data_key.as_slice().input_chunks::<K>(threads)?;
+    let data_pay_chunks = data_pay.as_slice().input_chunks::<K>(threads)?;
 
     for (key_chunk, pay_chunk, offsets_chunk, partitioned_chunk) in izip!(
         data_key_chunks.into_iter(),
@@ -132,8 +178,8 @@ where
 fn cpu_tuple_loss_or_duplicates_chunked_i32_small_data() -> Result<(), Box<dyn Error>> {
     run_cpu_partitioning(
         15,
-        Box::new(|keys: &mut _| Ok(UniformRelation::gen_primary_key::<i32>(keys, None)?)),
-        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000)?)),
+        Box::new(|keys: &mut _| Ok(UniformRelation::gen_primary_key::<i32>(keys, None, None)?)),
+        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000, None)?)),
         CpuHistogramAlgorithm::Chunked,
         CpuRadixPartitionAlgorithm::NC,
         RadixBits::from(4),
@@ -146,8 +192,8 @@ fn cpu_tuple_loss_or_duplicates_chunked_i32_small_data() -> Result<(), Box<dyn E
 fn cpu_verify_partitions_chunked_i32_small_data() -> Result<(), Box<dyn Error>> {
     run_cpu_partitioning(
         15,
-        Box::new(|keys: &mut _| Ok(UniformRelation::gen_attr::<i32>(keys, 0..(32 << 20))?)),
-        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000)?)),
+        Box::new(|keys: &mut _| Ok(UniformRelation::gen_attr::<i32>(keys, 0..(32 << 20), None)?)),
+        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000, None)?)),
         CpuHistogramAlgorithm::Chunked,
         CpuRadixPartitionAlgorithm::NC,
         RadixBits::from(4),
@@ -160,8 +206,8 @@ fn cpu_verify_partitions_chunked_i32_small_data() -> Result<(), Box<dyn Error>>
 fn cpu_tuple_loss_or_duplicates_chunked_i32_0_bits() -> Result<(), Box<dyn Error>> {
     run_cpu_partitioning(
         (32 << 20) / size_of::<i32>(),
-        Box::new(|keys: &mut _| Ok(UniformRelation::gen_primary_key::<i32>(keys, None)?)),
-        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000)?)),
+        Box::new(|keys: &mut _| Ok(UniformRelation::gen_primary_key::<i32>(keys, None, None)?)),
+        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000, None)?)),
         CpuHistogramAlgorithm::Chunked,
         CpuRadixPartitionAlgorithm::NC,
         RadixBits::from(0),
@@ -174,8 +220,8 @@ fn cpu_tuple_loss_or_duplicates_chunked_i32_0_bits() -> Result<(), Box<dyn Error
 fn cpu_verify_partitions_chunked_i32_0_bits() -> Result<(), Box<dyn Error>> {
     run_cpu_partitioning(
         (32 << 20) / size_of::<i32>(),
-        Box::new(|keys: &mut _| Ok(UniformRelation::gen_attr::<i32>(keys, 0..(32 << 20))?)),
-        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000)?)),
+        Box::new(|keys: &mut _| Ok(UniformRelation::gen_attr::<i32>(keys, 0..(32 << 20), None)?)),
+        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000, None)?)),
         CpuHistogramAlgorithm::Chunked,
         CpuRadixPartitionAlgorithm::NC,
         RadixBits::from(0),
@@ -188,8 +234,8 @@ fn cpu_verify_partitions_chunked_i32_0_bits() -> Result<(), Box<dyn Error>> {
 fn cpu_tuple_loss_or_duplicates_chunked_i32_1_bit() -> Result<(), Box<dyn Error>> {
     run_cpu_partitioning(
         (32 << 20) / size_of::<i32>(),
-        Box::new(|keys: &mut _| Ok(UniformRelation::gen_primary_key::<i32>(keys, None)?)),
-        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000)?)),
+        Box::new(|keys: &mut _| Ok(UniformRelation::gen_primary_key::<i32>(keys, None, None)?)),
+        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000, None)?)),
         CpuHistogramAlgorithm::Chunked,
         CpuRadixPartitionAlgorithm::NC,
         RadixBits::from(1),
@@ -202,8 +248,8 @@ fn cpu_tuple_loss_or_duplicates_chunked_i32_1_bit() -> Result<(), Box<dyn Error>
 fn cpu_verify_partitions_chunked_i32_1_bit() -> Result<(), Box<dyn Error>> {
     run_cpu_partitioning(
         (32 << 20) / size_of::<i32>(),
-        Box::new(|keys: &mut _| Ok(UniformRelation::gen_attr::<i32>(keys, 0..(32 << 20))?)),
-        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000)?)),
+        Box::new(|keys: &mut _| Ok(UniformRelation::gen_attr::<i32>(keys, 0..(32 << 20), None)?)),
+        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000, None)?)),
         CpuHistogramAlgorithm::Chunked,
         CpuRadixPartitionAlgorithm::NC,
         RadixBits::from(1),
@@ -216,8 +262,8 @@ fn cpu_verify_partitions_chunked_i32_1_bit() -> Result<(), Box<dyn Error>> {
 fn cpu_tuple_loss_or_duplicates_chunked_i32_2_bits() -> Result<(), Box<dyn Error>> {
     run_cpu_partitioning(
         (32 << 20) / size_of::<i32>(),
-        Box::new(|keys: &mut _| Ok(UniformRelation::gen_primary_key::<i32>(keys, None)?)),
-        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000)?)),
+        Box::new(|keys: &mut _| Ok(UniformRelation::gen_primary_key::<i32>(keys, None, None)?)),
+        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000, None)?)),
         CpuHistogramAlgorithm::Chunked,
         CpuRadixPartitionAlgorithm::NC,
         RadixBits::from(2),
@@ -230,8 +276,8 @@ fn cpu_tuple_loss_or_duplicates_chunked_i32_2_bits() -> Result<(), Box<dyn Error
 fn cpu_verify_partitions_chunked_i32_2_bits() -> Result<(), Box<dyn Error>> {
     run_cpu_partitioning(
         (32 << 20) / size_of::<i32>(),
-        Box::new(|keys: &mut _| Ok(UniformRelation::gen_attr::<i32>(keys, 0..(32 << 20))?)),
-        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000)?)),
+        Box::new(|keys: &mut _| Ok(UniformRelation::gen_attr::<i32>(keys, 0..(32 << 20), None)?)),
+        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000, None)?)),
         CpuHistogramAlgorithm::Chunked,
         CpuRadixPartitionAlgorithm::NC,
         RadixBits::from(2),
@@ -244,8 +290,8 @@ fn cpu_verify_partitions_chunked_i32_2_bits() -> Result<(), Box<dyn Error>> {
 fn cpu_tuple_loss_or_duplicates_chunked_i32_12_bits() -> Result<(), Box<dyn Error>> {
     run_cpu_partitioning(
         (32 << 20) / size_of::<i32>(),
-        Box::new(|keys: &mut _| Ok(UniformRelation::gen_primary_key::<i32>(keys, None)?)),
-        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000)?)),
+        Box::new(|keys: &mut _| Ok(UniformRelation::gen_primary_key::<i32>(keys, None, None)?)),
+        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000, None)?)),
         CpuHistogramAlgorithm::Chunked,
         CpuRadixPartitionAlgorithm::NC,
         RadixBits::from(12),
@@ -258,8 +304,8 @@ fn cpu_tuple_loss_or_duplicates_chunked_i32_12_bits() -> Result<(), Box<dyn Erro
 fn cpu_verify_partitions_chunked_i32_12_bits() -> Result<(), Box<dyn Error>> {
     run_cpu_partitioning(
         (32 << 20) / size_of::<i32>(),
-        Box::new(|keys: &mut _| Ok(UniformRelation::gen_attr::<i32>(keys, 0..(32 << 20))?)),
-        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000)?)),
+        Box::new(|keys: &mut _| Ok(UniformRelation::gen_attr::<i32>(keys, 0..(32 << 20), None)?)),
+        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000, None)?)),
         CpuHistogramAlgorithm::Chunked,
         CpuRadixPartitionAlgorithm::NC,
         RadixBits::from(12),
@@ -272,8 +318,8 @@ fn cpu_verify_partitions_chunked_i32_12_bits() -> Result<(), Box<dyn Error>> {
 fn cpu_tuple_loss_or_duplicates_chunked_i32_13_bits() -> Result<(), Box<dyn Error>> {
     run_cpu_partitioning(
         (32 << 20) / size_of::<i32>(),
-        Box::new(|keys: &mut _| Ok(UniformRelation::gen_primary_key::<i32>(keys, None)?)),
-        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000)?)),
+        Box::new(|keys: &mut _| Ok(UniformRelation::gen_primary_key::<i32>(keys, None, None)?)),
+        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000, None)?)),
         CpuHistogramAlgorithm::Chunked,
         CpuRadixPartitionAlgorithm::NC,
         RadixBits::from(13),
@@ -286,8 +332,8 @@ fn cpu_tuple_loss_or_duplicates_chunked_i32_13_bits() -> Result<(), Box<dyn Erro
 fn cpu_verify_partitions_chunked_i32_13_bits() -> Result<(), Box<dyn Error>> {
     run_cpu_partitioning(
         (32 << 20) / size_of::<i32>(),
-        Box::new(|keys: &mut _| Ok(UniformRelation::gen_attr::<i32>(keys, 0..(32 << 20))?)),
-        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000)?)),
+        Box::new(|keys: &mut _| Ok(UniformRelation::gen_attr::<i32>(keys, 0..(32 << 20), None)?)),
+        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000, None)?)),
         CpuHistogramAlgorithm::Chunked,
         CpuRadixPartitionAlgorithm::NC,
         RadixBits::from(13),
@@ -300,8 +346,8 @@ fn cpu_verify_partitions_chunked_i32_13_bits() -> Result<(), Box<dyn Error>> {
 fn cpu_tuple_loss_or_duplicates_chunked_i32_14_bits() -> Result<(), Box<dyn Error>> {
     run_cpu_partitioning(
         (32 << 20) / size_of::<i32>(),
-        Box::new(|keys: &mut _| Ok(UniformRelation::gen_primary_key::<i32>(keys, None)?)),
-        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000)?)),
+        Box::new(|keys: &mut _| Ok(UniformRelation::gen_primary_key::<i32>(keys, None, None)?)),
+        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000, None)?)),
         CpuHistogramAlgorithm::Chunked,
         CpuRadixPartitionAlgorithm::NC,
         RadixBits::from(14),
@@ -314,8 +360,8 @@ fn cpu_tuple_loss_or_duplicates_chunked_i32_14_bits() -> Result<(), Box<dyn Erro
 fn cpu_verify_partitions_chunked_i32_14_bits() -> Result<(), Box<dyn Error>> {
     run_cpu_partitioning(
         (32 << 20) / size_of::<i32>(),
-        Box::new(|keys: &mut _| Ok(UniformRelation::gen_attr::<i32>(keys, 0..(32 << 20))?)),
-        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000)?)),
+        Box::new(|keys: &mut _| Ok(UniformRelation::gen_attr::<i32>(keys, 0..(32 << 20), None)?)),
+        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000, None)?)),
         CpuHistogramAlgorithm::Chunked,
         CpuRadixPartitionAlgorithm::NC,
         RadixBits::from(14),
@@ -328,8 +374,8 @@ fn cpu_verify_partitions_chunked_i32_14_bits() -> Result<(), Box<dyn Error>> {
 fn cpu_tuple_loss_or_duplicates_chunked_i32_15_bits() -> Result<(), Box<dyn Error>> {
     run_cpu_partitioning(
         (32 << 20) / size_of::<i32>(),
-        Box::new(|keys: &mut _| Ok(UniformRelation::gen_primary_key::<i32>(keys, None)?)),
-        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000)?)),
+        Box::new(|keys: &mut _| Ok(UniformRelation::gen_primary_key::<i32>(keys, None, None)?)),
+        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000, None)?)),
         CpuHistogramAlgorithm::Chunked,
         CpuRadixPartitionAlgorithm::NC,
         RadixBits::from(15),
@@ -342,8 +388,8 @@ fn cpu_tuple_loss_or_duplicates_chunked_i32_15_bits() -> Result<(), Box<dyn Erro
 fn cpu_verify_partitions_chunked_i32_15_bits() -> Result<(), Box<dyn Error>> {
     run_cpu_partitioning(
         (32 << 20) / size_of::<i32>(),
-        Box::new(|keys: &mut _| Ok(UniformRelation::gen_attr::<i32>(keys, 0..(32 << 20))?)),
-        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000)?)),
+        Box::new(|keys: &mut _| Ok(UniformRelation::gen_attr::<i32>(keys, 0..(32 << 20), None)?)),
+        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000, None)?)),
         CpuHistogramAlgorithm::Chunked,
         CpuRadixPartitionAlgorithm::NC,
         RadixBits::from(15),
@@ -356,8 +402,8 @@ fn cpu_verify_partitions_chunked_i32_15_bits() -> Result<(), Box<dyn Error>> {
 fn cpu_tuple_loss_or_duplicates_chunked_i32_16_bits() -> Result<(), Box<dyn Error>> {
     run_cpu_partitioning(
         (32 << 20) / size_of::<i32>(),
-        Box::new(|keys: &mut _| Ok(UniformRelation::gen_primary_key::<i32>(keys, None)?)),
-        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000)?)),
+        Box::new(|keys: &mut _| Ok(UniformRelation::gen_primary_key::<i32>(keys, None, None)?)),
+        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000, None)?)),
         CpuHistogramAlgorithm::Chunked,
         CpuRadixPartitionAlgorithm::NC,
         RadixBits::from(16),
@@ -370,8 +416,8 @@ fn cpu_tuple_loss_or_duplicates_chunked_i32_16_bits() -> Result<(), Box<dyn Erro
 fn cpu_verify_partitions_chunked_i32_16_bits() -> Result<(), Box<dyn Error>> {
     run_cpu_partitioning(
         (32 << 20) / size_of::<i32>(),
-        Box::new(|keys: &mut _| Ok(UniformRelation::gen_attr::<i32>(keys, 0..(32 << 20))?)),
-        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000)?)),
+        Box::new(|keys: &mut _| Ok(UniformRelation::gen_attr::<i32>(keys, 0..(32 << 20), None)?)),
+        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000, None)?)),
         CpuHistogramAlgorithm::Chunked,
         CpuRadixPartitionAlgorithm::NC,
         RadixBits::from(16),
@@ -384,8 +430,8 @@ fn cpu_verify_partitions_chunked_i32_16_bits() -> Result<(), Box<dyn Error>> {
 fn cpu_tuple_loss_or_duplicates_chunked_i32_17_bits() -> Result<(), Box<dyn Error>> {
     run_cpu_partitioning(
         (32 << 20) / size_of::<i32>(),
-        Box::new(|keys: &mut _| Ok(UniformRelation::gen_primary_key::<i32>(keys, None)?)),
-        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000)?)),
+        Box::new(|keys: &mut _| Ok(UniformRelation::gen_primary_key::<i32>(keys, None, None)?)),
+        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000, None)?)),
         CpuHistogramAlgorithm::Chunked,
         CpuRadixPartitionAlgorithm::NC,
         RadixBits::from(17),
@@ -398,8 +444,8 @@ fn cpu_tuple_loss_or_duplicates_chunked_i32_17_bits() -> Result<(), Box<dyn Erro
 fn cpu_verify_partitions_chunked_i32_17_bits() -> Result<(), Box<dyn Error>> {
     run_cpu_partitioning(
         (32 << 20) / size_of::<i32>(),
-        Box::new(|keys: &mut _| Ok(UniformRelation::gen_attr::<i32>(keys, 0..(32 << 20))?)),
-        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000)?)),
+        Box::new(|keys: &mut _| Ok(UniformRelation::gen_attr::<i32>(keys, 0..(32 << 20), None)?)),
+        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000, None)?)),
         CpuHistogramAlgorithm::Chunked,
         CpuRadixPartitionAlgorithm::NC,
         RadixBits::from(17),
@@ -413,8 +459,8 @@ fn cpu_tuple_loss_or_duplicates_chunked_i32_less_tuples_than_partitions(
 ) -> Result<(), Box<dyn Error>> {
     run_cpu_partitioning(
         (32 << 5) / size_of::<i32>(),
-        Box::new(|keys: &mut _| Ok(UniformRelation::gen_primary_key::<i32>(keys, None)?)),
-        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000)?)),
+        Box::new(|keys: &mut _| Ok(UniformRelation::gen_primary_key::<i32>(keys, None, None)?)),
+        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000, None)?)),
         CpuHistogramAlgorithm::Chunked,
         CpuRadixPartitionAlgorithm::NC,
         RadixBits::from(17),
@@ -427,8 +473,8 @@ fn cpu_tuple_loss_or_duplicates_chunked_i32_less_tuples_than_partitions(
 fn cpu_verify_partitions_chunked_i32_less_tuples_than_partitions() -> Result<(), Box<dyn Error>> {
     run_cpu_partitioning(
         (32 << 5) / size_of::<i32>(),
-        Box::new(|keys: &mut _| Ok(UniformRelation::gen_attr::<i32>(keys, 0..(32 << 20))?)),
-        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000)?)),
+        Box::new(|keys: &mut _| Ok(UniformRelation::gen_attr::<i32>(keys, 0..(32 << 20), None)?)),
+        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000, None)?)),
         CpuHistogramAlgorithm::Chunked,
         CpuRadixPartitionAlgorithm::NC,
         RadixBits::from(17),
@@ -441,8 +487,8 @@ fn cpu_verify_partitions_chunked_i32_less_tuples_than_partitions() -> Result<(),
 fn cpu_tuple_loss_or_duplicates_chunked_i32_non_power_2_data_len() -> Result<(), Box<dyn Error>> {
     run_cpu_partitioning(
         (32 << 10) / size_of::<i32>() - 7,
-        Box::new(|keys: &mut _| Ok(UniformRelation::gen_primary_key::<i32>(keys, None)?)),
-        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000)?)),
+        Box::new(|keys: &mut _| Ok(UniformRelation::gen_primary_key::<i32>(keys, None, None)?)),
+        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000, None)?)),
         CpuHistogramAlgorithm::Chunked,
         CpuRadixPartitionAlgorithm::NC,
         RadixBits::from(10),
@@ -455,8 +501,8 @@ fn cpu_tuple_loss_or_duplicates_chunked_i32_non_power_2_data_len() -> Result<(),
 fn cpu_verify_partitions_chunked_i32_non_power_2_data_len() -> Result<(), Box<dyn Error>> {
     run_cpu_partitioning(
         (32 << 10) / size_of::<i32>() - 7,
-        Box::new(|keys: &mut _| Ok(UniformRelation::gen_attr::<i32>(keys, 0..(32 << 20))?)),
-        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000)?)),
+        Box::new(|keys: &mut _| Ok(UniformRelation::gen_attr::<i32>(keys, 0..(32 << 20), None)?)),
+        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000, None)?)),
         CpuHistogramAlgorithm::Chunked,
         CpuRadixPartitionAlgorithm::NC,
         RadixBits::from(10),
@@ -469,8 +515,8 @@ fn cpu_verify_partitions_chunked_i32_non_power_2_data_len() -> Result<(), Box<dy
 fn cpu_tuple_loss_or_duplicates_chunked_i64_17_bits() -> Result<(), Box<dyn Error>> {
     run_cpu_partitioning(
         (64 << 20) / size_of::<i64>(),
-        Box::new(|keys: &mut _| Ok(UniformRelation::gen_primary_key::<i64>(keys, None)?)),
-        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i64>(pays, 0..10000)?)),
+        Box::new(|keys: &mut _| Ok(UniformRelation::gen_primary_key::<i64>(keys, None, None)?)),
+        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i64>(pays, 0..10000, None)?)),
         CpuHistogramAlgorithm::Chunked,
         CpuRadixPartitionAlgorithm::NC,
         RadixBits::from(17),
@@ -483,8 +529,8 @@ fn cpu_tuple_loss_or_duplicates_chunked_i64_17_bits() -> Result<(), Box<dyn Erro
 // fn cpu_verify_partitions_chunked_i64_17_bits() -> Result<(), Box<dyn Error>> {
 //     run_cpu_partitioning(
 //         (64 << 20) / size_of::<i64>(),
-//         Box::new(|keys: &mut _| Ok(UniformRelation::gen_attr::<i64>(keys, 0..(32 << 20))?)),
-//         Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i64>(pays, 0..10000)?)),
+//         Box::new(|keys: &mut _| Ok(UniformRelation::gen_attr::<i64>(keys, 0..(32 << 20), None)?)),
+//         Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i64>(pays, 0..10000, None)?)),
 //         CpuHistogramAlgorithm::Chunked,
 //         CpuRadixPartitionAlgorithm::NC,
 //         RadixBits::from(17),
@@ -493,13 +539,13 @@ fn cpu_tuple_loss_or_duplicates_chunked_i64_17_bits() -> Result<(), Box<dyn Erro
 //     )
 // }
 
-#[cfg(target_arch = "powerpc64")]
+#[cfg(any(target_arch = "powerpc64", target_arch = "x86_64"))]
 #[test]
 fn cpu_tuple_loss_or_duplicates_chunked_simd_i32_12_bits() -> Result<(), Box<dyn Error>> {
     run_cpu_partitioning(
         (32 << 20) / size_of::<i32>(),
-        Box::new(|keys: &mut _| Ok(UniformRelation::gen_primary_key::<i32>(keys, None)?)),
-        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000)?)),
+        Box::new(|keys: &mut _| Ok(UniformRelation::gen_primary_key::<i32>(keys, None, None)?)),
+        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000, None)?)),
         CpuHistogramAlgorithm::ChunkedSimd,
         CpuRadixPartitionAlgorithm::NC,
         RadixBits::from(12),
@@ -508,13 +554,13 @@ fn cpu_tuple_loss_or_duplicates_chunked_simd_i32_12_bits() -> Result<(), Box<dyn
     )
 }
 
-#[cfg(target_arch = "powerpc64")]
+#[cfg(any(target_arch = "powerpc64", target_arch = "x86_64"))]
 #[test]
 fn cpu_verify_partitions_chunked_simd_i32_12_bits() -> Result<(), Box<dyn Error>> {
     run_cpu_partitioning(
         (32 << 20) / size_of::<i32>(),
-        Box::new(|keys: &mut _| Ok(UniformRelation::gen_attr::<i32>(keys, 0..(32 << 20))?)),
-        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000)?)),
+        Box::new(|keys: &mut _| Ok(UniformRelation::gen_attr::<i32>(keys, 0..(32 << 20), None)?)),
+        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000, None)?)),
         CpuHistogramAlgorithm::ChunkedSimd,
         CpuRadixPartitionAlgorithm::NC,
         RadixBits::from(12),
@@ -529,8 +575,8 @@ fn cpu_verify_partitions_chunked_simd_i32_12_bits() -> Result<(), Box<dyn Error>
 fn cpu_tuple_loss_or_duplicates_chunked_swwc_i32_small_data() -> Result<(), Box<dyn Error>> {
     run_cpu_partitioning(
         15,
-        Box::new(|keys: &mut _| Ok(UniformRelation::gen_primary_key::<i32>(keys, None)?)),
-        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000)?)),
+        Box::new(|keys: &mut _| Ok(UniformRelation::gen_primary_key::<i32>(keys, None, None)?)),
+        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000, None)?)),
         CpuHistogramAlgorithm::Chunked,
         CpuRadixPartitionAlgorithm::Swwc,
         RadixBits::from(4),
@@ -543,8 +589,8 @@ fn cpu_tuple_loss_or_duplicates_chunked_swwc_i32_small_data() -> Result<(), Box<
 fn cpu_verify_partitions_chunked_swwc_i32_small_data() -> Result<(), Box<dyn Error>> {
     run_cpu_partitioning(
         15,
-        Box::new(|keys: &mut _| Ok(UniformRelation::gen_attr::<i32>(keys, 0..(32 << 20))?)),
-        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000)?)),
+        Box::new(|keys: &mut _| Ok(UniformRelation::gen_attr::<i32>(keys, 0..(32 << 20), None)?)),
+        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000, None)?)),
         CpuHistogramAlgorithm::Chunked,
         CpuRadixPartitionAlgorithm::Swwc,
         RadixBits::from(4),
@@ -557,8 +603,8 @@ fn cpu_verify_partitions_chunked_swwc_i32_small_data() -> Result<(), Box<dyn Err
 fn cpu_tuple_loss_or_duplicates_chunked_swwc_i32_0_bits() -> Result<(), Box<dyn Error>> {
     run_cpu_partitioning(
         (32 << 20) / size_of::<i32>(),
-        Box::new(|keys: &mut _| Ok(UniformRelation::gen_primary_key::<i32>(keys, None)?)),
-        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000)?)),
+        Box::new(|keys: &mut _| Ok(UniformRelation::gen_primary_key::<i32>(keys, None, None)?)),
+        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000, None)?)),
         CpuHistogramAlgorithm::Chunked,
         CpuRadixPartitionAlgorithm::Swwc,
         RadixBits::from(0),
@@ -571,8 +617,8 @@ fn cpu_tuple_loss_or_duplicates_chunked_swwc_i32_0_bits() -> Result<(), Box<dyn
 fn cpu_verify_partitions_chunked_swwc_i32_0_bits() -> Result<(), Box<dyn Error>> {
     run_cpu_partitioning(
         (32 << 20) / size_of::<i32>(),
-        Box::new(|keys: &mut _| Ok(UniformRelation::gen_attr::<i32>(keys, 0..(32 << 20))?)),
-        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000)?)),
+        Box::new(|keys: &mut _| Ok(UniformRelation::gen_attr::<i32>(keys, 0..(32 << 20), None)?)),
+        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000, None)?)),
         CpuHistogramAlgorithm::Chunked,
         CpuRadixPartitionAlgorithm::Swwc,
         RadixBits::from(0),
@@ -585,8 +631,8 @@ fn cpu_verify_partitions_chunked_swwc_i32_0_bits() -> Result<(), Box<dyn Error>>
 fn cpu_tuple_loss_or_duplicates_chunked_swwc_i32_1_bit() -> Result<(), Box<dyn Error>> {
     run_cpu_partitioning(
         (32 << 20) / size_of::<i32>(),
-        Box::new(|keys: &mut _| Ok(UniformRelation::gen_primary_key::<i32>(keys, None)?)),
-        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000)?)),
+        Box::new(|keys: &mut _| Ok(UniformRelation::gen_primary_key::<i32>(keys, None, None)?)),
+        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000, None)?)),
         CpuHistogramAlgorithm::Chunked,
         CpuRadixPartitionAlgorithm::Swwc,
         RadixBits::from(1),
@@ -599,8 +645,8 @@ fn cpu_tuple_loss_or_duplicates_chunked_swwc_i32_1_bit() -> Result<(), Box<dyn E
 fn cpu_verify_partitions_chunked_swwc_i32_1_bit() -> Result<(), Box<dyn Error>> {
     run_cpu_partitioning(
         (32 << 20) / size_of::<i32>(),
-        Box::new(|keys: &mut _| Ok(UniformRelation::gen_attr::<i32>(keys, 0..(32 << 20))?)),
-        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000)?)),
+        Box::new(|keys: &mut _| Ok(UniformRelation::gen_attr::<i32>(keys, 0..(32 << 20), None)?)),
+        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000, None)?)),
         CpuHistogramAlgorithm::Chunked,
         CpuRadixPartitionAlgorithm::Swwc,
         RadixBits::from(1),
@@ -613,8 +659,8 @@ fn cpu_verify_partitions_chunked_swwc_i32_1_bit() -> Result<(), Box<dyn Error>>
 fn cpu_tuple_loss_or_duplicates_chunked_swwc_i32_2_bits() -> Result<(), Box<dyn Error>> {
     run_cpu_partitioning(
         (32 << 20) / size_of::<i32>(),
-        Box::new(|keys: &mut _| Ok(UniformRelation::gen_primary_key::<i32>(keys, None)?)),
-        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000)?)),
+        Box::new(|keys: &mut _| Ok(UniformRelation::gen_primary_key::<i32>(keys, None, None)?)),
+        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000, None)?)),
         CpuHistogramAlgorithm::Chunked,
         CpuRadixPartitionAlgorithm::Swwc,
         RadixBits::from(2),
@@ -627,8 +673,8 @@ fn cpu_tuple_loss_or_duplicates_chunked_swwc_i32_2_bits() -> Result<(), Box<dyn
 fn cpu_verify_partitions_chunked_swwc_i32_2_bits() -> Result<(), Box<dyn Error>> {
     run_cpu_partitioning(
         (32 << 20) / size_of::<i32>(),
-        Box::new(|keys: &mut _| Ok(UniformRelation::gen_attr::<i32>(keys, 0..(32 << 20))?)),
-        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000)?)),
+        Box::new(|keys: &mut _| Ok(UniformRelation::gen_attr::<i32>(keys, 0..(32 << 20), None)?)),
+        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000, None)?)),
         CpuHistogramAlgorithm::Chunked,
         CpuRadixPartitionAlgorithm::Swwc,
         RadixBits::from(2),
@@ -641,8 +687,8 @@ fn cpu_verify_partitions_chunked_swwc_i32_2_bits() -> Result<(), Box<dyn Error>>
 fn cpu_tuple_loss_or_duplicates_chunked_swwc_i32_12_bits() -> Result<(), Box<dyn Error>> {
     run_cpu_partitioning(
         (32 << 20) / size_of::<i32>(),
-        Box::new(|keys: &mut _| Ok(UniformRelation::gen_primary_key::<i32>(keys, None)?)),
-        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000)?)),
+        Box::new(|keys: &mut _| Ok(UniformRelation::gen_primary_key::<i32>(keys, None, None)?)),
+        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000, None)?)),
         CpuHistogramAlgorithm::Chunked,
         CpuRadixPartitionAlgorithm::Swwc,
         RadixBits::from(12),
@@ -655,8 +701,8 @@ fn cpu_tuple_loss_or_duplicates_chunked_swwc_i32_12_bits() -> Result<(), Box<dyn
 fn cpu_verify_partitions_chunked_swwc_i32_12_bits() -> Result<(), Box<dyn Error>> {
     run_cpu_partitioning(
         (32 << 20) / size_of::<i32>(),
-        Box::new(|keys: &mut _| Ok(UniformRelation::gen_attr::<i32>(keys, 0..(32 << 20))?)),
-        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000)?)),
+        Box::new(|keys: &mut _| Ok(UniformRelation::gen_attr::<i32>(keys, 0..(32 << 20), None)?)),
+        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000, None)?)),
         CpuHistogramAlgorithm::Chunked,
         CpuRadixPartitionAlgorithm::Swwc,
         RadixBits::from(12),
@@ -669,8 +715,8 @@ fn cpu_verify_partitions_chunked_swwc_i32_12_bits() -> Result<(), Box<dyn Error>
 fn cpu_tuple_loss_or_duplicates_chunked_swwc_i32_13_bits() -> Result<(), Box<dyn Error>> {
     run_cpu_partitioning(
         (32 << 20) / size_of::<i32>(),
-        Box::new(|keys: &mut _| Ok(UniformRelation::gen_primary_key::<i32>(keys, None)?)),
-        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000)?)),
+        Box::new(|keys: &mut _| Ok(UniformRelation::gen_primary_key::<i32>(keys, None, None)?)),
+        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000, None)?)),
         CpuHistogramAlgorithm::Chunked,
         CpuRadixPartitionAlgorithm::Swwc,
         RadixBits::from(13),
@@ -683,8 +729,8 @@ fn cpu_tuple_loss_or_duplicates_chunked_swwc_i32_13_bits() -> Result<(), Box<dyn
 fn cpu_verify_partitions_chunked_swwc_i32_13_bits() -> Result<(), Box<dyn Error>> {
     run_cpu_partitioning(
         (32 << 20) / size_of::<i32>(),
-        Box::new(|keys: &mut _| Ok(UniformRelation::gen_attr::<i32>(keys, 0..(32 << 20))?)),
-        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000)?)),
+        Box::new(|keys: &mut _| Ok(UniformRelation::gen_attr::<i32>(keys, 0..(32 << 20), None)?)),
+        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000, None)?)),
         CpuHistogramAlgorithm::Chunked,
         CpuRadixPartitionAlgorithm::Swwc,
         RadixBits::from(13),
@@ -697,8 +743,8 @@ fn cpu_verify_partitions_chunked_swwc_i32_13_bits() -> Result<(), Box<dyn Error>
 fn cpu_tuple_loss_or_duplicates_chunked_swwc_i32_14_bits() -> Result<(), Box<dyn Error>> {
     run_cpu_partitioning(
         (32 << 20) / size_of::<i32>(),
-        Box::new(|keys: &mut _| Ok(UniformRelation::gen_primary_key::<i32>(keys, None)?)),
-        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000)?)),
+        Box::new(|keys: &mut _| Ok(UniformRelation::gen_primary_key::<i32>(keys, None, None)?)),
+        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000, None)?)),
         CpuHistogramAlgorithm::Chunked,
         CpuRadixPartitionAlgorithm::Swwc,
         RadixBits::from(14),
@@ -711,8 +757,8 @@ fn cpu_tuple_loss_or_duplicates_chunked_swwc_i32_14_bits() -> Result<(), Box<dyn
 fn cpu_verify_partitions_chunked_swwc_i32_14_bits() -> Result<(), Box<dyn Error>> {
     run_cpu_partitioning(
         (32 << 20) / size_of::<i32>(),
-        Box::new(|keys: &mut _| Ok(UniformRelation::gen_attr::<i32>(keys, 0..(32 << 20))?)),
-        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000)?)),
+        Box::new(|keys: &mut _| Ok(UniformRelation::gen_attr::<i32>(keys, 0..(32 << 20), None)?)),
+        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000, None)?)),
         CpuHistogramAlgorithm::Chunked,
         CpuRadixPartitionAlgorithm::Swwc,
         RadixBits::from(14),
@@ -725,8 +771,8 @@ fn cpu_verify_partitions_chunked_swwc_i32_14_bits() -> Result<(), Box<dyn Error>
 fn cpu_tuple_loss_or_duplicates_chunked_swwc_i32_15_bits() -> Result<(), Box<dyn Error>> {
     run_cpu_partitioning(
         (32 << 20) / size_of::<i32>(),
-        Box::new(|keys: &mut _| Ok(UniformRelation::gen_primary_key::<i32>(keys, None)?)),
-        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000)?)),
+        Box::new(|keys: &mut _| Ok(UniformRelation::gen_primary_key::<i32>(keys, None, None)?)),
+        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000, None)?)),
         CpuHistogramAlgorithm::Chunked,
         CpuRadixPartitionAlgorithm::Swwc,
         RadixBits::from(15),
@@ -739,8 +785,8 @@ fn cpu_tuple_loss_or_duplicates_chunked_swwc_i32_15_bits() -> Result<(), Box<dyn
 fn cpu_verify_partitions_chunked_swwc_i32_15_bits() -> Result<(), Box<dyn Error>> {
     run_cpu_partitioning(
         (32 << 20) / size_of::<i32>(),
-        Box::new(|keys: &mut _| Ok(UniformRelation::gen_attr::<i32>(keys, 0..(32 << 20))?)),
-        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000)?)),
+        Box::new(|keys: &mut _| Ok(UniformRelation::gen_attr::<i32>(keys, 0..(32 << 20), None)?)),
+        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000, None)?)),
         CpuHistogramAlgorithm::Chunked,
         CpuRadixPartitionAlgorithm::Swwc,
         RadixBits::from(15),
@@ -753,8 +799,8 @@ fn cpu_verify_partitions_chunked_swwc_i32_15_bits() -> Result<(), Box<dyn Error>
 fn cpu_tuple_loss_or_duplicates_chunked_swwc_i32_16_bits() -> Result<(), Box<dyn Error>> {
     run_cpu_partitioning(
         (32 << 20) / size_of::<i32>(),
-        Box::new(|keys: &mut _| Ok(UniformRelation::gen_primary_key::<i32>(keys, None)?)),
-        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000)?)),
+        Box::new(|keys: &mut _| Ok(UniformRelation::gen_primary_key::<i32>(keys, None, None)?)),
+        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000, None)?)),
         CpuHistogramAlgorithm::Chunked,
         CpuRadixPartitionAlgorithm::Swwc,
         RadixBits::from(16),
@@ -767,8 +813,8 @@ fn cpu_tuple_loss_or_duplicates_chunked_swwc_i32_16_bits() -> Result<(), Box<dyn
 fn cpu_verify_partitions_chunked_swwc_i32_16_bits() -> Result<(), Box<dyn Error>> {
     run_cpu_partitioning(
         (32 << 20) / size_of::<i32>(),
-        Box::new(|keys: &mut _| Ok(UniformRelation::gen_attr::<i32>(keys, 0..(32 << 20))?)),
-        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000)?)),
+        Box::new(|keys: &mut _| Ok(UniformRelation::gen_attr::<i32>(keys, 0..(32 << 20), None)?)),
+        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000, None)?)),
         CpuHistogramAlgorithm::Chunked,
         CpuRadixPartitionAlgorithm::Swwc,
         RadixBits::from(16),
@@ -781,8 +827,8 @@ fn cpu_verify_partitions_chunked_swwc_i32_16_bits() -> Result<(), Box<dyn Error>
 fn cpu_tuple_loss_or_duplicates_chunked_swwc_i32_17_bits() -> Result<(), Box<dyn Error>> {
     run_cpu_partitioning(
         (32 << 20) / size_of::<i32>(),
-        Box::new(|keys: &mut _| Ok(UniformRelation::gen_primary_key::<i32>(keys, None)?)),
-        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000)?)),
+        Box::new(|keys: &mut _| Ok(UniformRelation::gen_primary_key::<i32>(keys, None, None)?)),
+        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000, None)?)),
         CpuHistogramAlgorithm::Chunked,
         CpuRadixPartitionAlgorithm::Swwc,
         RadixBits::from(17),
@@ -795,8 +841,8 @@ fn cpu_tuple_loss_or_duplicates_chunked_swwc_i32_17_bits() -> Result<(), Box<dyn
 fn cpu_verify_partitions_chunked_swwc_i32_17_bits() -> Result<(), Box<dyn Error>> {
     run_cpu_partitioning(
         (32 << 20) / size_of::<i32>(),
-        Box::new(|keys: &mut _| Ok(UniformRelation::gen_attr::<i32>(keys, 0..(32 << 20))?)),
-        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000)?)),
+        Box::new(|keys: &mut _| Ok(UniformRelation::gen_attr::<i32>(keys, 0..(32 << 20), None)?)),
+        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000, None)?)),
         CpuHistogramAlgorithm::Chunked,
         CpuRadixPartitionAlgorithm::Swwc,
         RadixBits::from(17),
@@ -810,8 +856,8 @@ fn cpu_tuple_loss_or_duplicates_chunked_swwc_i32_less_tuples_than_partitions(
 ) -> Result<(), Box<dyn Error>> {
     run_cpu_partitioning(
         (32 << 5) / size_of::<i32>(),
-        Box::new(|keys: &mut _| Ok(UniformRelation::gen_primary_key::<i32>(keys, None)?)),
-        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000)?)),
+        Box::new(|keys: &mut _| Ok(UniformRelation::gen_primary_key::<i32>(keys, None, None)?)),
+        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000, None)?)),
         CpuHistogramAlgorithm::Chunked,
         CpuRadixPartitionAlgorithm::Swwc,
         RadixBits::from(17),
@@ -825,8 +871,8 @@ fn cpu_verify_partitions_chunked_swwc_i32_less_tuples_than_partitions() -> Resul
 {
     run_cpu_partitioning(
         (32 << 5) / size_of::<i32>(),
-        Box::new(|keys: &mut _| Ok(UniformRelation::gen_attr::<i32>(keys, 0..(32 << 20))?)),
-        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000)?)),
+        Box::new(|keys: &mut _| Ok(UniformRelation::gen_attr::<i32>(keys, 0..(32 << 20), None)?)),
+        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000, None)?)),
         CpuHistogramAlgorithm::Chunked,
         CpuRadixPartitionAlgorithm::Swwc,
         RadixBits::from(17),
@@ -840,8 +886,8 @@ fn cpu_tuple_loss_or_duplicates_chunked_swwc_i32_non_power_2_data_len() -> Resul
 {
     run_cpu_partitioning(
         (32 << 10) / size_of::<i32>() - 7,
-        Box::new(|keys: &mut _| Ok(UniformRelation::gen_primary_key::<i32>(keys, None)?)),
-        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000)?)),
+        Box::new(|keys: &mut _| Ok(UniformRelation::gen_primary_key::<i32>(keys, None, None)?)),
+        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000, None)?)),
         CpuHistogramAlgorithm::Chunked,
         CpuRadixPartitionAlgorithm::Swwc,
         RadixBits::from(10),
@@ -854,8 +900,8 @@ fn cpu_tuple_loss_or_duplicates_chunked_swwc_i32_non_power_2_data_len() -> Resul
 fn cpu_verify_partitions_chunked_swwc_i32_non_power_2_data_len() -> Result<(), Box<dyn Error>> {
     run_cpu_partitioning(
         (32 << 10) / size_of::<i32>() - 7,
-        Box::new(|keys: &mut _| Ok(UniformRelation::gen_attr::<i32>(keys, 0..(32 << 20))?)),
-        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000)?)),
+        Box::new(|keys: &mut _| Ok(UniformRelation::gen_attr::<i32>(keys, 0..(32 << 20), None)?)),
+        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000, None)?)),
         CpuHistogramAlgorithm::Chunked,
         CpuRadixPartitionAlgorithm::Swwc,
         RadixBits::from(10),
@@ -868,8 +914,8 @@ fn cpu_verify_partitions_chunked_swwc_i32_non_power_2_data_len() -> Result<(), B
 fn cpu_tuple_loss_or_duplicates_chunked_swwc_i64_17_bits() -> Result<(), Box<dyn Error>> {
     run_cpu_partitioning(
         (64 << 20) / size_of::<i64>(),
-        Box::new(|keys: &mut _| Ok(UniformRelation::gen_primary_key::<i64>(keys, None)?)),
-        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i64>(pays, 0..10000)?)),
+        Box::new(|keys: &mut _| Ok(UniformRelation::gen_primary_key::<i64>(keys, None, None)?)),
+        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i64>(pays, 0..10000, None)?)),
         CpuHistogramAlgorithm::Chunked,
         CpuRadixPartitionAlgorithm::Swwc,
         RadixBits::from(17),
@@ -880,13 +926,13 @@ fn cpu_tuple_loss_or_duplicates_chunked_swwc_i64_17_bits() -> Result<(), Box<dyn
 
 // ======================== Chunked SWWC SIMD ========================
 
-#[cfg(target_arch = "powerpc64")]
+#[cfg(any(target_arch = "powerpc64", target_arch = "x86_64"))]
 #[test]
 fn cpu_tuple_loss_or_duplicates_chunked_swwc_simd_i32_small_data() -> Result<(), Box<dyn Error>> {
     run_cpu_partitioning(
         15,
-        Box::new(|keys: &mut _| Ok(UniformRelation::gen_primary_key::<i32>(keys, None)?)),
-        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000)?)),
+        Box::new(|keys: &mut _| Ok(UniformRelation::gen_primary_key::<i32>(keys, None, None)?)),
+        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000, None)?)),
         CpuHistogramAlgorithm::Chunked,
         CpuRadixPartitionAlgorithm::SwwcSimd,
         RadixBits::from(4),
@@ -895,13 +941,13 @@ fn cpu_tuple_loss_or_duplicates_chunked_swwc_simd_i32_small_data() -> Result<(),
     )
 }
 
-#[cfg(target_arch = "powerpc64")]
+#[cfg(any(target_arch = "powerpc64", target_arch = "x86_64"))]
 #[test]
 fn cpu_verify_partitions_chunked_swwc_simd_i32_small_data() -> Result<(), Box<dyn Error>> {
     run_cpu_partitioning(
         15,
-        Box::new(|keys: &mut _| Ok(UniformRelation::gen_attr::<i32>(keys, 0..(32 << 20))?)),
-        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000)?)),
+        Box::new(|keys: &mut _| Ok(UniformRelation::gen_attr::<i32>(keys, 0..(32 << 20), None)?)),
+        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000, None)?)),
         CpuHistogramAlgorithm::Chunked,
         CpuRadixPartitionAlgorithm::SwwcSimd,
         RadixBits::from(4),
@@ -910,13 +956,13 @@ fn cpu_verify_partitions_chunked_swwc_simd_i32_small_data() -> Result<(), Box<dy
     )
 }
 
-#[cfg(target_arch = "powerpc64")]
+#[cfg(any(target_arch = "powerpc64", target_arch = "x86_64"))]
 #[test]
 fn cpu_tuple_loss_or_duplicates_chunked_swwc_simd_i32_0_bits() -> Result<(), Box<dyn Error>> {
     run_cpu_partitioning(
         (32 << 20) / size_of::<i32>(),
-        Box::new(|keys: &mut _| Ok(UniformRelation::gen_primary_key::<i32>(keys, None)?)),
-        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000)?)),
+        Box::new(|keys: &mut _| Ok(UniformRelation::gen_primary_key::<i32>(keys, None, None)?)),
+        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000, None)?)),
         CpuHistogramAlgorithm::Chunked,
         CpuRadixPartitionAlgorithm::SwwcSimd,
         RadixBits::from(0),
@@ -925,13 +971,13 @@ fn cpu_tuple_loss_or_duplicates_chunked_swwc_simd_i32_0_bits() -> Result<(), Box
     )
 }
 
-#[cfg(target_arch = "powerpc64")]
+#[cfg(any(target_arch = "powerpc64", target_arch = "x86_64"))]
 #[test]
 fn cpu_verify_partitions_chunked_swwc_simd_i32_0_bits() -> Result<(), Box<dyn Error>> {
     run_cpu_partitioning(
         (32 << 20) / size_of::<i32>(),
-        Box::new(|keys: &mut _| Ok(UniformRelation::gen_attr::<i32>(keys, 0..(32 << 20))?)),
-        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000)?)),
+        Box::new(|keys: &mut _| Ok(UniformRelation::gen_attr::<i32>(keys, 0..(32 << 20), None)?)),
+        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000, None)?)),
         CpuHistogramAlgorithm::Chunked,
         CpuRadixPartitionAlgorithm::SwwcSimd,
         RadixBits::from(0),
@@ -940,13 +986,13 @@ fn cpu_verify_partitions_chunked_swwc_simd_i32_0_bits() -> Result<(), Box<dyn Er
     )
 }
 
-#[cfg(target_arch = "powerpc64")]
+#[cfg(any(target_arch = "powerpc64", target_arch = "x86_64"))]
 #[test]
 fn cpu_tuple_loss_or_duplicates_chunked_swwc_simd_i32_1_bit() -> Result<(), Box<dyn Error>> {
     run_cpu_partitioning(
         (32 << 20) / size_of::<i32>(),
-        Box::new(|keys: &mut _| Ok(UniformRelation::gen_primary_key::<i32>(keys, None)?)),
-        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000)?)),
+        Box::new(|keys: &mut _| Ok(UniformRelation::gen_primary_key::<i32>(keys, None, None)?)),
+        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000, None)?)),
         CpuHistogramAlgorithm::Chunked,
         CpuRadixPartitionAlgorithm::SwwcSimd,
         RadixBits::from(1),
@@ -955,13 +1001,13 @@ fn cpu_tuple_loss_or_duplicates_chunked_swwc_simd_i32_1_bit() -> Result<(), Box<
     )
 }
 
-#[cfg(target_arch = "powerpc64")]
+#[cfg(any(target_arch = "powerpc64", target_arch = "x86_64"))]
 #[test]
 fn cpu_verify_partitions_chunked_swwc_simd_i32_1_bit() -> Result<(), Box<dyn Error>> {
     run_cpu_partitioning(
         (32 << 20) / size_of::<i32>(),
-        Box::new(|keys: &mut _| Ok(UniformRelation::gen_attr::<i32>(keys, 0..(32 << 20))?)),
-        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000)?)),
+        Box::new(|keys: &mut _| Ok(UniformRelation::gen_attr::<i32>(keys, 0..(32 << 20), None)?)),
+        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000, None)?)),
         CpuHistogramAlgorithm::Chunked,
         CpuRadixPartitionAlgorithm::SwwcSimd,
         RadixBits::from(1),
@@ -970,13 +1016,13 @@ fn cpu_verify_partitions_chunked_swwc_simd_i32_1_bit() -> Result<(), Box<dyn Err
     )
 }
 
-#[cfg(target_arch = "powerpc64")]
+#[cfg(any(target_arch = "powerpc64", target_arch = "x86_64"))]
 #[test]
 fn cpu_tuple_loss_or_duplicates_chunked_swwc_simd_i32_2_bits() -> Result<(), Box<dyn Error>> {
     run_cpu_partitioning(
         (32 << 20) / size_of::<i32>(),
-        Box::new(|keys: &mut _| Ok(UniformRelation::gen_primary_key::<i32>(keys, None)?)),
-        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000)?)),
+        Box::new(|keys: &mut _| Ok(UniformRelation::gen_primary_key::<i32>(keys, None, None)?)),
+        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000, None)?)),
         CpuHistogramAlgorithm::Chunked,
         CpuRadixPartitionAlgorithm::SwwcSimd,
         RadixBits::from(2),
@@ -985,13 +1031,13 @@ fn cpu_tuple_loss_or_duplicates_chunked_swwc_simd_i32_2_bits() -> Result<(), Box
     )
 }
 
-#[cfg(target_arch = "powerpc64")]
+#[cfg(any(target_arch = "powerpc64", target_arch = "x86_64"))]
 #[test]
 fn cpu_verify_partitions_chunked_swwc_simd_i32_2_bits() -> Result<(), Box<dyn Error>> {
     run_cpu_partitioning(
         (32 << 20) / size_of::<i32>(),
-        Box::new(|keys: &mut _| Ok(UniformRelation::gen_attr::<i32>(keys, 0..(32 << 20))?)),
-        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000)?)),
+        Box::new(|keys: &mut _| Ok(UniformRelation::gen_attr::<i32>(keys, 0..(32 << 20), None)?)),
+        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000, None)?)),
         CpuHistogramAlgorithm::Chunked,
         CpuRadixPartitionAlgorithm::SwwcSimd,
         RadixBits::from(2),
@@ -1000,13 +1046,13 @@ fn cpu_verify_partitions_chunked_swwc_simd_i32_2_bits() -> Result<(), Box<dyn Er
     )
 }
 
-#[cfg(target_arch = "powerpc64")]
+#[cfg(any(target_arch = "powerpc64", target_arch = "x86_64"))]
 #[test]
 fn cpu_tuple_loss_or_duplicates_chunked_swwc_simd_i32_12_bits() -> Result<(), Box<dyn Error>> {
     run_cpu_partitioning(
         (32 << 20) / size_of::<i32>(),
-        Box::new(|keys: &mut _| Ok(UniformRelation::gen_primary_key::<i32>(keys, None)?)),
-        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000)?)),
+        Box::new(|keys: &mut _| Ok(UniformRelation::gen_primary_key::<i32>(keys, None, None)?)),
+        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000, None)?)),
         CpuHistogramAlgorithm::Chunked,
         CpuRadixPartitionAlgorithm::SwwcSimd,
         RadixBits::from(12),
@@ -1015,13 +1061,13 @@ fn cpu_tuple_loss_or_duplicates_chunked_swwc_simd_i32_12_bits() -> Result<(), Bo
     )
 }
 
-#[cfg(target_arch = "powerpc64")]
+#[cfg(any(target_arch = "powerpc64", target_arch = "x86_64"))]
 #[test]
 fn cpu_verify_partitions_chunked_swwc_simd_i32_12_bits() -> Result<(), Box<dyn Error>> {
     run_cpu_partitioning(
         (32 << 20) / size_of::<i32>(),
-        Box::new(|keys: &mut _| Ok(UniformRelation::gen_attr::<i32>(keys, 0..(32 << 20))?)),
-        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000)?)),
+        Box::new(|keys: &mut _| Ok(UniformRelation::gen_attr::<i32>(keys, 0..(32 << 20), None)?)),
+        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000, None)?)),
         CpuHistogramAlgorithm::Chunked,
         CpuRadixPartitionAlgorithm::SwwcSimd,
         RadixBits::from(12),
@@ -1030,13 +1076,13 @@ fn cpu_verify_partitions_chunked_swwc_simd_i32_12_bits() -> Result<(), Box<dyn E
     )
 }
 
-#[cfg(target_arch = "powerpc64")]
+#[cfg(any(target_arch = "powerpc64", target_arch = "x86_64"))]
 #[test]
 fn cpu_tuple_loss_or_duplicates_chunked_swwc_simd_i32_13_bits() -> Result<(), Box<dyn Error>> {
     run_cpu_partitioning(
         (32 << 20) / size_of::<i32>(),
-        Box::new(|keys: &mut _| Ok(UniformRelation::gen_primary_key::<i32>(keys, None)?)),
-        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000)?)),
+        Box::new(|keys: &mut _| Ok(UniformRelation::gen_primary_key::<i32>(keys, None, None)?)),
+        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000, None)?)),
         CpuHistogramAlgorithm::Chunked,
         CpuRadixPartitionAlgorithm::SwwcSimd,
         RadixBits::from(13),
@@ -1045,13 +1091,13 @@ fn cpu_tuple_loss_or_duplicates_chunked_swwc_simd_i32_13_bits() -> Result<(), Bo
     )
 }
 
-#[cfg(target_arch = "powerpc64")]
+#[cfg(any(target_arch = "powerpc64", target_arch = "x86_64"))]
 #[test]
 fn cpu_verify_partitions_chunked_swwc_simd_i32_13_bits() -> Result<(), Box<dyn Error>> {
     run_cpu_partitioning(
         (32 << 20) / size_of::<i32>(),
-        Box::new(|keys: &mut _| Ok(UniformRelation::gen_attr::<i32>(keys, 0..(32 << 20))?)),
-        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000)?)),
+        Box::new(|keys: &mut _| Ok(UniformRelation::gen_attr::<i32>(keys, 0..(32 << 20), None)?)),
+        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000, None)?)),
         CpuHistogramAlgorithm::Chunked,
         CpuRadixPartitionAlgorithm::SwwcSimd,
         RadixBits::from(13),
@@ -1060,13 +1106,13 @@ fn cpu_verify_partitions_chunked_swwc_simd_i32_13_bits() -> Result<(), Box<dyn E
     )
 }
 
-#[cfg(target_arch = "powerpc64")]
+#[cfg(any(target_arch = "powerpc64", target_arch = "x86_64"))]
 #[test]
 fn cpu_tuple_loss_or_duplicates_chunked_swwc_simd_i32_14_bits() -> Result<(), Box<dyn Error>> {
     run_cpu_partitioning(
         (32 << 20) / size_of::<i32>(),
-        Box::new(|keys: &mut _| Ok(UniformRelation::gen_primary_key::<i32>(keys, None)?)),
-        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000)?)),
+        Box::new(|keys: &mut _| Ok(UniformRelation::gen_primary_key::<i32>(keys, None, None)?)),
+        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000, None)?)),
         CpuHistogramAlgorithm::Chunked,
         CpuRadixPartitionAlgorithm::SwwcSimd,
         RadixBits::from(14),
@@ -1075,13 +1121,13 @@ fn cpu_tuple_loss_or_duplicates_chunked_swwc_simd_i32_14_bits() -> Result<(), Bo
     )
 }
 
-#[cfg(target_arch = "powerpc64")]
+#[cfg(any(target_arch = "powerpc64", target_arch = "x86_64"))]
 #[test]
 fn cpu_verify_partitions_chunked_swwc_simd_i32_14_bits() -> Result<(), Box<dyn Error>> {
     run_cpu_partitioning(
         (32 << 20) / size_of::<i32>(),
-        Box::new(|keys: &mut _| Ok(UniformRelation::gen_attr::<i32>(keys, 0..(32 << 20))?)),
-        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000)?)),
+        Box::new(|keys: &mut _| Ok(UniformRelation::gen_attr::<i32>(keys, 0..(32 << 20), None)?)),
+        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000, None)?)),
         CpuHistogramAlgorithm::Chunked,
         CpuRadixPartitionAlgorithm::SwwcSimd,
         RadixBits::from(14),
@@ -1090,13 +1136,13 @@ fn cpu_verify_partitions_chunked_swwc_simd_i32_14_bits() -> Result<(), Box<dyn E
     )
 }
 
-#[cfg(target_arch = "powerpc64")]
+#[cfg(any(target_arch = "powerpc64", target_arch = "x86_64"))]
 #[test]
 fn cpu_tuple_loss_or_duplicates_chunked_swwc_simd_i32_15_bits() -> Result<(), Box<dyn Error>> {
     run_cpu_partitioning(
         (32 << 20) / size_of::<i32>(),
-        Box::new(|keys: &mut _| Ok(UniformRelation::gen_primary_key::<i32>(keys, None)?)),
-        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000)?)),
+        Box::new(|keys: &mut _| Ok(UniformRelation::gen_primary_key::<i32>(keys, None, None)?)),
+        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000, None)?)),
         CpuHistogramAlgorithm::Chunked,
         CpuRadixPartitionAlgorithm::SwwcSimd,
         RadixBits::from(15),
@@ -1105,13 +1151,13 @@ fn cpu_tuple_loss_or_duplicates_chunked_swwc_simd_i32_15_bits() -> Result<(), Bo
     )
 }
 
-#[cfg(target_arch = "powerpc64")]
+#[cfg(any(target_arch = "powerpc64", target_arch = "x86_64"))]
 #[test]
 fn cpu_verify_partitions_chunked_swwc_simd_i32_15_bits() -> Result<(), Box<dyn Error>> {
     run_cpu_partitioning(
         (32 << 20) / size_of::<i32>(),
-        Box::new(|keys: &mut _| Ok(UniformRelation::gen_attr::<i32>(keys, 0..(32 << 20))?)),
-        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000)?)),
+        Box::new(|keys: &mut _| Ok(UniformRelation::gen_attr::<i32>(keys, 0..(32 << 20), None)?)),
+        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000, None)?)),
         CpuHistogramAlgorithm::Chunked,
         CpuRadixPartitionAlgorithm::SwwcSimd,
         RadixBits::from(15),
@@ -1120,13 +1166,13 @@ fn cpu_verify_partitions_chunked_swwc_simd_i32_15_bits() -> Result<(), Box<dyn E
     )
 }
 
-#[cfg(target_arch = "powerpc64")]
+#[cfg(any(target_arch = "powerpc64", target_arch = "x86_64"))]
 #[test]
 fn cpu_tuple_loss_or_duplicates_chunked_swwc_simd_i32_16_bits() -> Result<(), Box<dyn Error>> {
     run_cpu_partitioning(
         (32 << 20) / size_of::<i32>(),
-        Box::new(|keys: &mut _| Ok(UniformRelation::gen_primary_key::<i32>(keys, None)?)),
-        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000)?)),
+        Box::new(|keys: &mut _| Ok(UniformRelation::gen_primary_key::<i32>(keys, None, None)?)),
+        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000, None)?)),
         CpuHistogramAlgorithm::Chunked,
         CpuRadixPartitionAlgorithm::SwwcSimd,
         RadixBits::from(16),
@@ -1135,13 +1181,13 @@ fn cpu_tuple_loss_or_duplicates_chunked_swwc_simd_i32_16_bits() -> Result<(), Bo
     )
 }
 
-#[cfg(target_arch = "powerpc64")]
+#[cfg(any(target_arch = "powerpc64", target_arch = "x86_64"))]
 #[test]
 fn cpu_verify_partitions_chunked_swwc_simd_i32_16_bits() -> Result<(), Box<dyn Error>> {
     run_cpu_partitioning(
         (32 << 20) / size_of::<i32>(),
-        Box::new(|keys: &mut _| Ok(UniformRelation::gen_attr::<i32>(keys, 0..(32 << 20))?)),
-        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000)?)),
+        Box::new(|keys: &mut _| Ok(UniformRelation::gen_attr::<i32>(keys, 0..(32 << 20), None)?)),
+        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000, None)?)),
         CpuHistogramAlgorithm::Chunked,
         CpuRadixPartitionAlgorithm::SwwcSimd,
         RadixBits::from(16),
@@ -1150,13 +1196,13 @@ fn cpu_verify_partitions_chunked_swwc_simd_i32_16_bits() -> Result<(), Box<dyn E
     )
 }
 
-#[cfg(target_arch = "powerpc64")]
+#[cfg(any(target_arch = "powerpc64", target_arch = "x86_64"))]
 #[test]
 fn cpu_tuple_loss_or_duplicates_chunked_swwc_simd_i32_17_bits() -> Result<(), Box<dyn Error>> {
     run_cpu_partitioning(
         (32 << 20) / size_of::<i32>(),
-        Box::new(|keys: &mut _| Ok(UniformRelation::gen_primary_key::<i32>(keys, None)?)),
-        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000)?)),
+        Box::new(|keys: &mut _| Ok(UniformRelation::gen_primary_key::<i32>(keys, None, None)?)),
+        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000, None)?)),
         CpuHistogramAlgorithm::Chunked,
         CpuRadixPartitionAlgorithm::SwwcSimd,
         RadixBits::from(17),
@@ -1165,13 +1211,13 @@ fn cpu_tuple_loss_or_duplicates_chunked_swwc_simd_i32_17_bits() -> Result<(), Bo
     )
 }
 
-#[cfg(target_arch = "powerpc64")]
+#[cfg(any(target_arch = "powerpc64", target_arch = "x86_64"))]
 #[test]
 fn cpu_verify_partitions_chunked_swwc_simd_i32_17_bits() -> Result<(), Box<dyn Error>> {
     run_cpu_partitioning(
         (32 << 20) / size_of::<i32>(),
-        Box::new(|keys: &mut _| Ok(UniformRelation::gen_attr::<i32>(keys, 0..(32 << 20))?)),
-        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000)?)),
+        Box::new(|keys: &mut _| Ok(UniformRelation::gen_attr::<i32>(keys, 0..(32 << 20), None)?)),
+        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000, None)?)),
         CpuHistogramAlgorithm::Chunked,
         CpuRadixPartitionAlgorithm::SwwcSimd,
         RadixBits::from(17),
@@ -1180,14 +1226,14 @@ fn cpu_verify_partitions_chunked_swwc_simd_i32_17_bits() -> Result<(), Box<dyn E
     )
 }
 
-#[cfg(target_arch = "powerpc64")]
+#[cfg(any(target_arch = "powerpc64", target_arch = "x86_64"))]
 #[test]
 fn cpu_tuple_loss_or_duplicates_chunked_swwc_simd_i32_less_tuples_than_partitions(
 ) -> Result<(), Box<dyn Error>> {
     run_cpu_partitioning(
         (32 << 5) / size_of::<i32>(),
-        Box::new(|keys: &mut _| Ok(UniformRelation::gen_primary_key::<i32>(keys, None)?)),
-        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000)?)),
+        Box::new(|keys: &mut _| Ok(UniformRelation::gen_primary_key::<i32>(keys, None, None)?)),
+        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000, None)?)),
         CpuHistogramAlgorithm::Chunked,
         CpuRadixPartitionAlgorithm::SwwcSimd,
         RadixBits::from(17),
@@ -1196,14 +1242,14 @@ fn cpu_tuple_loss_or_duplicates_chunked_swwc_simd_i32_less_tuples_than_partition
     )
 }
 
-#[cfg(target_arch = "powerpc64")]
+#[cfg(any(target_arch = "powerpc64", target_arch = "x86_64"))]
 #[test]
 fn cpu_verify_partitions_chunked_swwc_simd_i32_less_tuples_than_partitions(
 ) -> Result<(), Box<dyn Error>> {
     run_cpu_partitioning(
         (32 << 5) / size_of::<i32>(),
-        Box::new(|keys: &mut _| Ok(UniformRelation::gen_attr::<i32>(keys, 0..(32 << 20))?)),
-        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000)?)),
+        Box::new(|keys: &mut _| Ok(UniformRelation::gen_attr::<i32>(keys, 0..(32 << 20), None)?)),
+        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000, None)?)),
         CpuHistogramAlgorithm::Chunked,
         CpuRadixPartitionAlgorithm::SwwcSimd,
         RadixBits::from(17),
@@ -1212,14 +1258,14 @@ fn cpu_verify_partitions_chunked_swwc_simd_i32_less_tuples_than_partitions(
     )
 }
 
-#[cfg(target_arch = "powerpc64")]
+#[cfg(any(target_arch = "powerpc64", target_arch = "x86_64"))]
 #[test]
 fn cpu_tuple_loss_or_duplicates_chunked_swwc_simd_i32_non_power_2_data_len(
 ) -> Result<(), Box<dyn Error>> {
     run_cpu_partitioning(
         (32 << 10) / size_of::<i32>() - 7,
-        Box::new(|keys: &mut _| Ok(UniformRelation::gen_primary_key::<i32>(keys, None)?)),
-        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000)?)),
+        Box::new(|keys: &mut _| Ok(UniformRelation::gen_primary_key::<i32>(keys, None, None)?)),
+        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000, None)?)),
         CpuHistogramAlgorithm::Chunked,
         CpuRadixPartitionAlgorithm::SwwcSimd,
         RadixBits::from(10),
@@ -1228,14 +1274,14 @@ fn cpu_tuple_loss_or_duplicates_chunked_swwc_simd_i32_non_power_2_data_len(
     )
 }
 
-#[cfg(target_arch = "powerpc64")]
+#[cfg(any(target_arch = "powerpc64", target_arch = "x86_64"))]
 #[test]
 fn cpu_verify_partitions_chunked_swwc_simd_i32_non_power_2_data_len() -> Result<(), Box<dyn Error>>
 {
     run_cpu_partitioning(
         (32 << 10) / size_of::<i32>() - 7,
-        Box::new(|keys: &mut _| Ok(UniformRelation::gen_attr::<i32>(keys, 0..(32 << 20))?)),
-        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000)?)),
+        Box::new(|keys: &mut _| Ok(UniformRelation::gen_attr::<i32>(keys, 0..(32 << 20), None)?)),
+        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000, None)?)),
         CpuHistogramAlgorithm::Chunked,
         CpuRadixPartitionAlgorithm::SwwcSimd,
         RadixBits::from(10),
@@ -1244,13 +1290,13 @@ fn cpu_verify_partitions_chunked_swwc_simd_i32_non_power_2_data_len() -> Result<
     )
 }
 
-#[cfg(target_arch = "powerpc64")]
+#[cfg(any(target_arch = "powerpc64", target_arch = "x86_64"))]
 #[test]
 fn cpu_tuple_loss_or_duplicates_chunked_swwc_simd_i64_17_bits() -> Result<(), Box<dyn Error>> {
     run_cpu_partitioning(
         (64 << 20) / size_of::<i64>(),
-        Box::new(|keys: &mut _| Ok(UniformRelation::gen_primary_key::<i64>(keys, None)?)),
-        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i64>(pays, 0..10000)?)),
+        Box::new(|keys: &mut _| Ok(UniformRelation::gen_primary_key::<i64>(keys, None, None)?)),
+        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i64>(pays, 0..10000, None)?)),
         CpuHistogramAlgorithm::Chunked,
         CpuRadixPartitionAlgorithm::SwwcSimd,
         RadixBits::from(17),
@@ -1261,14 +1307,14 @@ fn cpu_tuple_loss_or_duplicates_chunked_swwc_simd_i64_17_bits() -> Result<(), Bo
 
 // ======================== Chunked SIMD SWWC SIMD ========================
 
-#[cfg(target_arch = "powerpc64")]
+#[cfg(any(target_arch = "powerpc64", target_arch = "x86_64"))]
 #[test]
 fn cpu_tuple_loss_or_duplicates_chunked_simd_swwc_simd_i32_small_data() -> Result<(), Box<dyn Error>>
 {
     run_cpu_partitioning(
         15,
-        Box::new(|keys: &mut _| Ok(UniformRelation::gen_primary_key::<i32>(keys, None)?)),
-        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000)?)),
+        Box::new(|keys: &mut _| Ok(UniformRelation::gen_primary_key::<i32>(keys, None, None)?)),
+        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000, None)?)),
         CpuHistogramAlgorithm::ChunkedSimd,
         CpuRadixPartitionAlgorithm::SwwcSimd,
         RadixBits::from(4),
@@ -1277,13 +1323,13 @@ fn cpu_tuple_loss_or_duplicates_chunked_simd_swwc_simd_i32_small_data() -> Resul
     )
 }
 
-#[cfg(target_arch = "powerpc64")]
+#[cfg(any(target_arch = "powerpc64", target_arch = "x86_64"))]
 #[test]
 fn cpu_verify_partitions_chunked_simd_swwc_simd_i32_small_data() -> Result<(), Box<dyn Error>> {
     run_cpu_partitioning(
         15,
-        Box::new(|keys: &mut _| Ok(UniformRelation::gen_attr::<i32>(keys, 0..(32 << 20))?)),
-        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000)?)),
+        Box::new(|keys: &mut _| Ok(UniformRelation::gen_attr::<i32>(keys, 0..(32 << 20), None)?)),
+        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000, None)?)),
         CpuHistogramAlgorithm::ChunkedSimd,
         CpuRadixPartitionAlgorithm::SwwcSimd,
         RadixBits::from(4),
@@ -1292,13 +1338,13 @@ fn cpu_verify_partitions_chunked_simd_swwc_simd_i32_small_data() -> Result<(), B
     )
 }
 
-#[cfg(target_arch = "powerpc64")]
+#[cfg(any(target_arch = "powerpc64", target_arch = "x86_64"))]
 #[test]
 fn cpu_tuple_loss_or_duplicates_chunked_simd_swwc_simd_i32_0_bits() -> Result<(), Box<dyn Error>> {
     run_cpu_partitioning(
         (32 << 20) / size_of::<i32>(),
-        Box::new(|keys: &mut _| Ok(UniformRelation::gen_primary_key::<i32>(keys, None)?)),
-        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000)?)),
+        Box::new(|keys: &mut _| Ok(UniformRelation::gen_primary_key::<i32>(keys, None, None)?)),
+        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000, None)?)),
         CpuHistogramAlgorithm::ChunkedSimd,
         CpuRadixPartitionAlgorithm::SwwcSimd,
         RadixBits::from(0),
@@ -1307,13 +1353,13 @@ fn cpu_tuple_loss_or_duplicates_chunked_simd_swwc_simd_i32_0_bits() -> Result<()
     )
 }
 
-#[cfg(target_arch = "powerpc64")]
+#[cfg(any(target_arch = "powerpc64", target_arch = "x86_64"))]
 #[test]
 fn cpu_verify_partitions_chunked_simd_swwc_simd_i32_0_bits() -> Result<(), Box<dyn Error>> {
     run_cpu_partitioning(
         (32 << 20) / size_of::<i32>(),
-        Box::new(|keys: &mut _| Ok(UniformRelation::gen_attr::<i32>(keys, 0..(32 << 20))?)),
-        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000)?)),
+        Box::new(|keys: &mut _| Ok(UniformRelation::gen_attr::<i32>(keys, 0..(32 << 20), None)?)),
+        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000, None)?)),
         CpuHistogramAlgorithm::ChunkedSimd,
         CpuRadixPartitionAlgorithm::SwwcSimd,
         RadixBits::from(0),
@@ -1322,13 +1368,13 @@ fn cpu_verify_partitions_chunked_simd_swwc_simd_i32_0_bits() -> Result<(), Box<d
     )
 }
 
-#[cfg(target_arch = "powerpc64")]
+#[cfg(any(target_arch = "powerpc64", target_arch = "x86_64"))]
 #[test]
 fn cpu_tuple_loss_or_duplicates_chunked_simd_swwc_simd_i32_1_bit() -> Result<(), Box<dyn Error>> {
     run_cpu_partitioning(
         (32 << 20) / size_of::<i32>(),
-        Box::new(|keys: &mut _| Ok(UniformRelation::gen_primary_key::<i32>(keys, None)?)),
-        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000)?)),
+        Box::new(|keys: &mut _| Ok(UniformRelation::gen_primary_key::<i32>(keys, None, None)?)),
+        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000, None)?)),
         CpuHistogramAlgorithm::ChunkedSimd,
         CpuRadixPartitionAlgorithm::SwwcSimd,
         RadixBits::from(1),
@@ -1337,13 +1383,13 @@ fn cpu_tuple_loss_or_duplicates_chunked_simd_swwc_simd_i32_1_bit() -> Result<(),
     )
 }
 
-#[cfg(target_arch = "powerpc64")]
+#[cfg(any(target_arch = "powerpc64", target_arch = "x86_64"))]
 #[test]
 fn cpu_verify_partitions_chunked_simd_swwc_simd_i32_1_bit() -> Result<(), Box<dyn Error>> {
     run_cpu_partitioning(
         (32 << 20) / size_of::<i32>(),
-        Box::new(|keys: &mut _| Ok(UniformRelation::gen_attr::<i32>(keys, 0..(32 << 20))?)),
-        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000)?)),
+        Box::new(|keys: &mut _| Ok(UniformRelation::gen_attr::<i32>(keys, 0..(32 << 20), None)?)),
+        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000, None)?)),
         CpuHistogramAlgorithm::ChunkedSimd,
         CpuRadixPartitionAlgorithm::SwwcSimd,
         RadixBits::from(1),
@@ -1352,13 +1398,13 @@ fn cpu_verify_partitions_chunked_simd_swwc_simd_i32_1_bit() -> Result<(), Box<dy
     )
 }
 
-#[cfg(target_arch = "powerpc64")]
+#[cfg(any(target_arch = "powerpc64", target_arch = "x86_64"))]
 #[test]
 fn cpu_tuple_loss_or_duplicates_chunked_simd_swwc_simd_i32_2_bits() -> Result<(), Box<dyn Error>> {
     run_cpu_partitioning(
         (32 << 20) / size_of::<i32>(),
-        Box::new(|keys: &mut _| Ok(UniformRelation::gen_primary_key::<i32>(keys, None)?)),
-        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000)?)),
+        Box::new(|keys: &mut _| Ok(UniformRelation::gen_primary_key::<i32>(keys, None, None)?)),
+        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000, None)?)),
         CpuHistogramAlgorithm::ChunkedSimd,
         CpuRadixPartitionAlgorithm::SwwcSimd,
         RadixBits::from(2),
@@ -1367,13 +1413,13 @@ fn cpu_tuple_loss_or_duplicates_chunked_simd_swwc_simd_i32_2_bits() -> Result<()
     )
 }
 
-#[cfg(target_arch = "powerpc64")]
+#[cfg(any(target_arch = "powerpc64", target_arch = "x86_64"))]
 #[test]
 fn cpu_verify_partitions_chunked_simd_swwc_simd_i32_2_bits() -> Result<(), Box<dyn Error>> {
     run_cpu_partitioning(
         (32 << 20) / size_of::<i32>(),
-        Box::new(|keys: &mut _| Ok(UniformRelation::gen_attr::<i32>(keys, 0..(32 << 20))?)),
-        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000)?)),
+        Box::new(|keys: &mut _| Ok(UniformRelation::gen_attr::<i32>(keys, 0..(32 << 20), None)?)),
+        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000, None)?)),
         CpuHistogramAlgorithm::ChunkedSimd,
         CpuRadixPartitionAlgorithm::SwwcSimd,
         RadixBits::from(2),
@@ -1382,13 +1428,13 @@ fn cpu_verify_partitions_chunked_simd_swwc_simd_i32_2_bits() -> Result<(), Box<d
     )
 }
 
-#[cfg(target_arch = "powerpc64")]
+#[cfg(any(target_arch = "powerpc64", target_arch = "x86_64"))]
 #[test]
 fn cpu_tuple_loss_or_duplicates_chunked_simd_swwc_simd_i32_12_bits() -> Result<(), Box<dyn Error>> {
     run_cpu_partitioning(
         (32 << 20) / size_of::<i32>(),
-        Box::new(|keys: &mut _| Ok(UniformRelation::gen_primary_key::<i32>(keys, None)?)),
-        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000)?)),
+        Box::new(|keys: &mut _| Ok(UniformRelation::gen_primary_key::<i32>(keys, None, None)?)),
+        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000, None)?)),
         CpuHistogramAlgorithm::ChunkedSimd,
         CpuRadixPartitionAlgorithm::SwwcSimd,
         RadixBits::from(12),
@@ -1397,13 +1443,13 @@ fn cpu_tuple_loss_or_duplicates_chunked_simd_swwc_simd_i32_12_bits() -> Result<(
     )
 }
 
-#[cfg(target_arch = "powerpc64")]
+#[cfg(any(target_arch = "powerpc64", target_arch = "x86_64"))]
 #[test]
 fn cpu_verify_partitions_chunked_simd_swwc_simd_i32_12_bits() -> Result<(), Box<dyn Error>> {
     run_cpu_partitioning(
         (32 << 20) / size_of::<i32>(),
-        Box::new(|keys: &mut _| Ok(UniformRelation::gen_attr::<i32>(keys, 0..(32 << 20))?)),
-        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000)?)),
+        Box::new(|keys: &mut _| Ok(UniformRelation::gen_attr::<i32>(keys, 0..(32 << 20), None)?)),
+        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000, None)?)),
         CpuHistogramAlgorithm::ChunkedSimd,
         CpuRadixPartitionAlgorithm::SwwcSimd,
         RadixBits::from(12),
@@ -1412,13 +1458,13 @@ fn cpu_verify_partitions_chunked_simd_swwc_simd_i32_12_bits() -> Result<(), Box<
     )
 }
 
-#[cfg(target_arch = "powerpc64")]
+#[cfg(any(target_arch = "powerpc64", target_arch = "x86_64"))]
 #[test]
 fn cpu_tuple_loss_or_duplicates_chunked_simd_swwc_simd_i32_13_bits() -> Result<(), Box<dyn Error>> {
     run_cpu_partitioning(
         (32 << 20) / size_of::<i32>(),
-        Box::new(|keys: &mut _| Ok(UniformRelation::gen_primary_key::<i32>(keys, None)?)),
-        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000)?)),
+        Box::new(|keys: &mut _| Ok(UniformRelation::gen_primary_key::<i32>(keys, None, None)?)),
+        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000, None)?)),
         CpuHistogramAlgorithm::ChunkedSimd,
         CpuRadixPartitionAlgorithm::SwwcSimd,
         RadixBits::from(13),
@@ -1427,13 +1473,13 @@ fn cpu_tuple_loss_or_duplicates_chunked_simd_swwc_simd_i32_13_bits() -> Result<(
     )
 }
 
-#[cfg(target_arch = "powerpc64")]
+#[cfg(any(target_arch = "powerpc64", target_arch = "x86_64"))]
 #[test]
 fn cpu_verify_partitions_chunked_simd_swwc_simd_i32_13_bits() -> Result<(), Box<dyn Error>> {
     run_cpu_partitioning(
         (32 << 20) / size_of::<i32>(),
-        Box::new(|keys: &mut _| Ok(UniformRelation::gen_attr::<i32>(keys, 0..(32 << 20))?)),
-        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000)?)),
+        Box::new(|keys: &mut _| Ok(UniformRelation::gen_attr::<i32>(keys, 0..(32 << 20), None)?)),
+        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000, None)?)),
         CpuHistogramAlgorithm::ChunkedSimd,
         CpuRadixPartitionAlgorithm::SwwcSimd,
         RadixBits::from(13),
@@ -1442,13 +1488,13 @@ fn cpu_verify_partitions_chunked_simd_swwc_simd_i32_13_bits() -> Result<(), Box<
     )
 }
 
-#[cfg(target_arch = "powerpc64")]
+#[cfg(any(target_arch = "powerpc64", target_arch = "x86_64"))]
 #[test]
 fn cpu_tuple_loss_or_duplicates_chunked_simd_swwc_simd_i32_14_bits() -> Result<(), Box<dyn Error>> {
     run_cpu_partitioning(
         (32 << 20) / size_of::<i32>(),
-        Box::new(|keys: &mut _| Ok(UniformRelation::gen_primary_key::<i32>(keys, None)?)),
-        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000)?)),
+        Box::new(|keys: &mut _| Ok(UniformRelation::gen_primary_key::<i32>(keys, None, None)?)),
+        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000, None)?)),
         CpuHistogramAlgorithm::ChunkedSimd,
         CpuRadixPartitionAlgorithm::SwwcSimd,
         RadixBits::from(14),
@@ -1457,13 +1503,13 @@ fn cpu_tuple_loss_or_duplicates_chunked_simd_swwc_simd_i32_14_bits() -> Result<(
     )
 }
 
-#[cfg(target_arch = "powerpc64")]
+#[cfg(any(target_arch = "powerpc64", target_arch = "x86_64"))]
 #[test]
 fn cpu_verify_partitions_chunked_simd_swwc_simd_i32_14_bits() -> Result<(), Box<dyn Error>> {
     run_cpu_partitioning(
         (32 << 20) / size_of::<i32>(),
-        Box::new(|keys: &mut _| Ok(UniformRelation::gen_attr::<i32>(keys, 0..(32 << 20))?)),
-        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000)?)),
+        Box::new(|keys: &mut _| Ok(UniformRelation::gen_attr::<i32>(keys, 0..(32 << 20), None)?)),
+        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000, None)?)),
         CpuHistogramAlgorithm::ChunkedSimd,
         CpuRadixPartitionAlgorithm::SwwcSimd,
         RadixBits::from(14),
@@ -1472,13 +1518,13 @@ fn cpu_verify_partitions_chunked_simd_swwc_simd_i32_14_bits() -> Result<(), Box<
     )
 }
 
-#[cfg(target_arch = "powerpc64")]
+#[cfg(any(target_arch = "powerpc64", target_arch = "x86_64"))]
 #[test]
 fn cpu_tuple_loss_or_duplicates_chunked_simd_swwc_simd_i32_15_bits() -> Result<(), Box<dyn Error>> {
     run_cpu_partitioning(
         (32 << 20) / size_of::<i32>(),
-        Box::new(|keys: &mut _| Ok(UniformRelation::gen_primary_key::<i32>(keys, None)?)),
-        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000)?)),
+        Box::new(|keys: &mut _| Ok(UniformRelation::gen_primary_key::<i32>(keys, None, None)?)),
+        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000, None)?)),
         CpuHistogramAlgorithm::ChunkedSimd,
         CpuRadixPartitionAlgorithm::SwwcSimd,
         RadixBits::from(15),
@@ -1487,13 +1533,13 @@ fn cpu_tuple_loss_or_duplicates_chunked_simd_swwc_simd_i32_15_bits() -> Result<(
     )
 }
 
-#[cfg(target_arch = "powerpc64")]
+#[cfg(any(target_arch = "powerpc64", target_arch = "x86_64"))]
 #[test]
 fn cpu_verify_partitions_chunked_simd_swwc_simd_i32_15_bits() -> Result<(), Box<dyn Error>> {
     run_cpu_partitioning(
         (32 << 20) / size_of::<i32>(),
-        Box::new(|keys: &mut _| Ok(UniformRelation::gen_attr::<i32>(keys, 0..(32 << 20))?)),
-        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000)?)),
+        Box::new(|keys: &mut _| Ok(UniformRelation::gen_attr::<i32>(keys, 0..(32 << 20), None)?)),
+        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000, None)?)),
         CpuHistogramAlgorithm::ChunkedSimd,
         CpuRadixPartitionAlgorithm::SwwcSimd,
         RadixBits::from(15),
@@ -1502,13 +1548,13 @@ fn cpu_verify_partitions_chunked_simd_swwc_simd_i32_15_bits() -> Result<(), Box<
     )
 }
 
-#[cfg(target_arch = "powerpc64")]
+#[cfg(any(target_arch = "powerpc64", target_arch = "x86_64"))]
 #[test]
 fn cpu_tuple_loss_or_duplicates_chunked_simd_swwc_simd_i32_16_bits() -> Result<(), Box<dyn Error>> {
     run_cpu_partitioning(
         (32 << 20) / size_of::<i32>(),
-        Box::new(|keys: &mut _| Ok(UniformRelation::gen_primary_key::<i32>(keys, None)?)),
-        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000)?)),
+        Box::new(|keys: &mut _| Ok(UniformRelation::gen_primary_key::<i32>(keys, None, None)?)),
+        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000, None)?)),
         CpuHistogramAlgorithm::ChunkedSimd,
         CpuRadixPartitionAlgorithm::SwwcSimd,
         RadixBits::from(16),
@@ -1517,13 +1563,13 @@ fn cpu_tuple_loss_or_duplicates_chunked_simd_swwc_simd_i32_16_bits() -> Result<(
     )
 }
 
-#[cfg(target_arch = "powerpc64")]
+#[cfg(any(target_arch = "powerpc64", target_arch = "x86_64"))]
 #[test]
 fn cpu_verify_partitions_chunked_simd_swwc_simd_i32_16_bits() -> Result<(), Box<dyn Error>> {
     run_cpu_partitioning(
         (32 << 20) / size_of::<i32>(),
-        Box::new(|keys: &mut _| Ok(UniformRelation::gen_attr::<i32>(keys, 0..(32 << 20))?)),
-        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000)?)),
+        Box::new(|keys: &mut _| Ok(UniformRelation::gen_attr::<i32>(keys, 0..(32 << 20), None)?)),
+        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000, None)?)),
         CpuHistogramAlgorithm::ChunkedSimd,
         CpuRadixPartitionAlgorithm::SwwcSimd,
         RadixBits::from(16),
@@ -1532,13 +1578,13 @@ fn cpu_verify_partitions_chunked_simd_swwc_simd_i32_16_bits() -> Result<(), Box<
     )
 }
 
-#[cfg(target_arch = "powerpc64")]
+#[cfg(any(target_arch = "powerpc64", target_arch = "x86_64"))]
 #[test]
 fn cpu_tuple_loss_or_duplicates_chunked_simd_swwc_simd_i32_17_bits() -> Result<(), Box<dyn Error>> {
     run_cpu_partitioning(
         (32 << 20) / size_of::<i32>(),
-        Box::new(|keys: &mut _| Ok(UniformRelation::gen_primary_key::<i32>(keys, None)?)),
-        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000)?)),
+        Box::new(|keys: &mut _| Ok(UniformRelation::gen_primary_key::<i32>(keys, None, None)?)),
+        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000, None)?)),
         CpuHistogramAlgorithm::ChunkedSimd,
         CpuRadixPartitionAlgorithm::SwwcSimd,
         RadixBits::from(17),
@@ -1547,13 +1593,13 @@ fn cpu_tuple_loss_or_duplicates_chunked_simd_swwc_simd_i32_17_bits() -> Result<(
     )
 }
 
-#[cfg(target_arch = "powerpc64")]
+#[cfg(any(target_arch = "powerpc64", target_arch = "x86_64"))]
 #[test]
 fn cpu_verify_partitions_chunked_simd_swwc_simd_i32_17_bits() -> Result<(), Box<dyn Error>> {
     run_cpu_partitioning(
         (32 << 20) / size_of::<i32>(),
-        Box::new(|keys: &mut _| Ok(UniformRelation::gen_attr::<i32>(keys, 0..(32 << 20))?)),
-        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000)?)),
+        Box::new(|keys: &mut _| Ok(UniformRelation::gen_attr::<i32>(keys, 0..(32 << 20), None)?)),
+        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000, None)?)),
         CpuHistogramAlgorithm::ChunkedSimd,
         CpuRadixPartitionAlgorithm::SwwcSimd,
         RadixBits::from(17),
@@ -1562,14 +1608,14 @@ fn cpu_verify_partitions_chunked_simd_swwc_simd_i32_17_bits() -> Result<(), Box<
     )
 }
 
-#[cfg(target_arch = "powerpc64")]
+#[cfg(any(target_arch = "powerpc64", target_arch = "x86_64"))]
 #[test]
 fn cpu_tuple_loss_or_duplicates_chunked_simd_swwc_simd_i32_less_tuples_than_partitions(
 ) -> Result<(), Box<dyn Error>> {
     run_cpu_partitioning(
         (32 << 5) / size_of::<i32>(),
-        Box::new(|keys: &mut _| Ok(UniformRelation::gen_primary_key::<i32>(keys, None)?)),
-        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000)?)),
+        Box::new(|keys: &mut _| Ok(UniformRelation::gen_primary_key::<i32>(keys, None, None)?)),
+        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000, None)?)),
         CpuHistogramAlgorithm::ChunkedSimd,
         CpuRadixPartitionAlgorithm::SwwcSimd,
         RadixBits::from(17),
@@ -1578,14 +1624,14 @@ fn cpu_tuple_loss_or_duplicates_chunked_simd_swwc_simd_i32_less_tuples_than_part
     )
 }
 
-#[cfg(target_arch = "powerpc64")]
+#[cfg(any(target_arch = "powerpc64", target_arch = "x86_64"))]
 #[test]
 fn cpu_verify_partitions_chunked_simd_swwc_simd_i32_less_tuples_than_partitions(
 ) -> Result<(), Box<dyn Error>> {
     run_cpu_partitioning(
         (32 << 5) / size_of::<i32>(),
-        Box::new(|keys: &mut _| Ok(UniformRelation::gen_attr::<i32>(keys, 0..(32 << 20))?)),
-        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000)?)),
+        Box::new(|keys: &mut _| Ok(UniformRelation::gen_attr::<i32>(keys, 0..(32 << 20), None)?)),
+        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000, None)?)),
         CpuHistogramAlgorithm::ChunkedSimd,
         CpuRadixPartitionAlgorithm::SwwcSimd,
         RadixBits::from(17),
@@ -1594,14 +1640,14 @@ fn cpu_verify_partitions_chunked_simd_swwc_simd_i32_less_tuples_than_partitions(
     )
 }
 
-#[cfg(target_arch = "powerpc64")]
+#[cfg(any(target_arch = "powerpc64", target_arch = "x86_64"))]
 #[test]
 fn cpu_tuple_loss_or_duplicates_chunked_simd_swwc_simd_i32_non_power_2_data_len(
 ) -> Result<(), Box<dyn Error>> {
     run_cpu_partitioning(
         (32 << 10) / size_of::<i32>() - 7,
-        Box::new(|keys: &mut _| Ok(UniformRelation::gen_primary_key::<i32>(keys, None)?)),
-        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000)?)),
+        Box::new(|keys: &mut _| Ok(UniformRelation::gen_primary_key::<i32>(keys, None, None)?)),
+        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000, None)?)),
         CpuHistogramAlgorithm::ChunkedSimd,
         CpuRadixPartitionAlgorithm::SwwcSimd,
         RadixBits::from(10),
@@ -1610,14 +1656,14 @@ fn cpu_tuple_loss_or_duplicates_chunked_simd_swwc_simd_i32_non_power_2_data_len(
     )
 }
 
-#[cfg(target_arch = "powerpc64")]
+#[cfg(any(target_arch = "powerpc64", target_arch = "x86_64"))]
 #[test]
 fn cpu_verify_partitions_chunked_simd_swwc_simd_i32_non_power_2_data_len(
 ) -> Result<(), Box<dyn Error>> {
     run_cpu_partitioning(
         (32 << 10) / size_of::<i32>() - 7,
-        Box::new(|keys: &mut _| Ok(UniformRelation::gen_attr::<i32>(keys, 0..(32 << 20))?)),
-        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000)?)),
+        Box::new(|keys: &mut _| Ok(UniformRelation::gen_attr::<i32>(keys, 0..(32 << 20), None)?)),
+        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000, None)?)),
         CpuHistogramAlgorithm::ChunkedSimd,
         CpuRadixPartitionAlgorithm::SwwcSimd,
         RadixBits::from(10),
@@ -1626,17 +1672,926 @@ fn cpu_verify_partitions_chunked_simd_swwc_simd_i32_non_power_2_data_len(
     )
 }
 
-#[cfg(target_arch = "powerpc64")]
+#[cfg(any(target_arch = "powerpc64", target_arch = "x86_64"))]
 #[test]
 fn cpu_tuple_loss_or_duplicates_chunked_simd_swwc_simd_i64_17_bits() -> Result<(), Box<dyn Error>> {
     run_cpu_partitioning(
         (64 << 20) / size_of::<i64>(),
-        Box::new(|keys: &mut _| Ok(UniformRelation::gen_primary_key::<i64>(keys, None)?)),
-        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i64>(pays, 0..10000)?)),
+        Box::new(|keys: &mut _| Ok(UniformRelation::gen_primary_key::<i64>(keys, None, None)?)),
+        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i64>(pays, 0..10000, None)?)),
+        CpuHistogramAlgorithm::ChunkedSimd,
+        CpuRadixPartitionAlgorithm::SwwcSimd,
+        RadixBits::from(17),
+        4,
+        Box::new(&tuple_loss_or_duplicates),
+    )
+}
+
+// ======================== Chunked SIMD SWWC SIMD with AVX2 ========================
+//
+// Limits the instruction set to AVX2, because AVX-512 CPUs otherwise never run
+// the AVX2 code path.
+
+#[cfg(target_arch = "x86_64")]
+#[test]
+fn cpu_tuple_loss_or_duplicates_chunked_simd_swwc_simd_avx2_i32_12_bits(
+) -> Result<(), Box<dyn Error>> {
+    run_cpu_partitioning_with_simd_isa(
+        (32 << 20) / size_of::<i32>(),
+        Box::new(|keys: &mut _| Ok(UniformRelation::gen_primary_key::<i32>(keys, None, None)?)),
+        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000, None)?)),
+        CpuHistogramAlgorithm::ChunkedSimd,
+        CpuRadixPartitionAlgorithm::SwwcSimd,
+        X86SimdIsa::Avx2,
+        RadixBits::from(12),
+        4,
+        Box::new(&tuple_loss_or_duplicates),
+    )
+}
+
+#[cfg(target_arch = "x86_64")]
+#[test]
+fn cpu_verify_partitions_chunked_simd_swwc_simd_avx2_i32_12_bits() -> Result<(), Box<dyn Error>> {
+    run_cpu_partitioning_with_simd_isa(
+        (32 << 20) / size_of::<i32>(),
+        Box::new(|keys: &mut _| Ok(UniformRelation::gen_attr::<i32>(keys, 0..(32 << 20), None)?)),
+        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000, None)?)),
+        CpuHistogramAlgorithm::ChunkedSimd,
+        CpuRadixPartitionAlgorithm::SwwcSimd,
+        X86SimdIsa::Avx2,
+        RadixBits::from(12),
+        4,
+        Box::new(&verify_partitions),
+    )
+}
+
+#[cfg(target_arch = "x86_64")]
+#[test]
+fn cpu_verify_partitions_chunked_simd_swwc_simd_avx2_i32_non_power_2_data_len(
+) -> Result<(), Box<dyn Error>> {
+    run_cpu_partitioning_with_simd_isa(
+        (32 << 10) / size_of::<i32>() - 7,
+        Box::new(|keys: &mut _| Ok(UniformRelation::gen_attr::<i32>(keys, 0..(32 << 20), None)?)),
+        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000, None)?)),
+        CpuHistogramAlgorithm::ChunkedSimd,
+        CpuRadixPartitionAlgorithm::SwwcSimd,
+        X86SimdIsa::Avx2,
+        RadixBits::from(10),
+        4,
+        Box::new(&verify_partitions),
+    )
+}
+
+#[cfg(target_arch = "x86_64")]
+#[test]
+fn cpu_tuple_loss_or_duplicates_chunked_simd_swwc_simd_avx2_i64_17_bits(
+) -> Result<(), Box<dyn Error>> {
+    run_cpu_partitioning_with_simd_isa(
+        (64 << 20) / size_of::<i64>(),
+        Box::new(|keys: &mut _| Ok(UniformRelation::gen_primary_key::<i64>(keys, None, None)?)),
+        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i64>(pays, 0..10000, None)?)),
         CpuHistogramAlgorithm::ChunkedSimd,
         CpuRadixPartitionAlgorithm::SwwcSimd,
+        X86SimdIsa::Avx2,
         RadixBits::from(17),
         4,
         Box::new(&tuple_loss_or_duplicates),
     )
 }
+
+// ======================== Contiguous NC ========================
+
+#[test]
+fn cpu_tuple_loss_or_duplicates_contiguous_i32_small_data() -> Result<(), Box<dyn Error>> {
+    run_cpu_partitioning(
+        15,
+        Box::new(|keys: &mut _| Ok(UniformRelation::gen_primary_key::<i32>(keys, None, None)?)),
+        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000, None)?)),
+        CpuHistogramAlgorithm::Contiguous,
+        CpuRadixPartitionAlgorithm::NC,
+        RadixBits::from(4),
+        4,
+        Box::new(&tuple_loss_or_duplicates),
+    )
+}
+
+#[test]
+fn cpu_verify_partitions_contiguous_i32_small_data() -> Result<(), Box<dyn Error>> {
+    run_cpu_partitioning(
+        15,
+        Box::new(|keys: &mut _| Ok(UniformRelation::gen_attr::<i32>(keys, 0..(32 << 20), None)?)),
+        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000, None)?)),
+        CpuHistogramAlgorithm::Contiguous,
+        CpuRadixPartitionAlgorithm::NC,
+        RadixBits::from(4),
+        4,
+        Box::new(&verify_partitions),
+    )
+}
+
+#[test]
+fn cpu_tuple_loss_or_duplicates_contiguous_i32_0_bits() -> Result<(), Box<dyn Error>> {
+    run_cpu_partitioning(
+        (32 << 20) / size_of::<i32>(),
+        Box::new(|keys: &mut _| Ok(UniformRelation::gen_primary_key::<i32>(keys, None, None)?)),
+        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000, None)?)),
+        CpuHistogramAlgorithm::Contiguous,
+        CpuRadixPartitionAlgorithm::NC,
+        RadixBits::from(0),
+        4,
+        Box::new(&tuple_loss_or_duplicates),
+    )
+}
+
+#[test]
+fn cpu_verify_partitions_contiguous_i32_0_bits() -> Result<(), Box<dyn Error>> {
+    run_cpu_partitioning(
+        (32 << 20) / size_of::<i32>(),
+        Box::new(|keys: &mut _| Ok(UniformRelation::gen_attr::<i32>(keys, 0..(32 << 20), None)?)),
+        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000, None)?)),
+        CpuHistogramAlgorithm::Contiguous,
+        CpuRadixPartitionAlgorithm::NC,
+        RadixBits::from(0),
+        4,
+        Box::new(&verify_partitions),
+    )
+}
+
+#[test]
+fn cpu_tuple_loss_or_duplicates_contiguous_i32_2_bits() -> Result<(), Box<dyn Error>> {
+    run_cpu_partitioning(
+        (32 << 20) / size_of::<i32>(),
+        Box::new(|keys: &mut _| Ok(UniformRelation::gen_primary_key::<i32>(keys, None, None)?)),
+        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000, None)?)),
+        CpuHistogramAlgorithm::Contiguous,
+        CpuRadixPartitionAlgorithm::NC,
+        RadixBits::from(2),
+        4,
+        Box::new(&tuple_loss_or_duplicates),
+    )
+}
+
+#[test]
+fn cpu_verify_partitions_contiguous_i32_2_bits() -> Result<(), Box<dyn Error>> {
+    run_cpu_partitioning(
+        (32 << 20) / size_of::<i32>(),
+        Box::new(|keys: &mut _| Ok(UniformRelation::gen_attr::<i32>(keys, 0..(32 << 20), None)?)),
+        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000, None)?)),
+        CpuHistogramAlgorithm::Contiguous,
+        CpuRadixPartitionAlgorithm::NC,
+        RadixBits::from(2),
+        4,
+        Box::new(&verify_partitions),
+    )
+}
+
+#[test]
+fn cpu_tuple_loss_or_duplicates_contiguous_i32_12_bits() -> Result<(), Box<dyn Error>> {
+    run_cpu_partitioning(
+        (32 << 20) / size_of::<i32>(),
+        Box::new(|keys: &mut _| Ok(UniformRelation::gen_primary_key::<i32>(keys, None, None)?)),
+        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000, None)?)),
+        CpuHistogramAlgorithm::Contiguous,
+        CpuRadixPartitionAlgorithm::NC,
+        RadixBits::from(12),
+        4,
+        Box::new(&tuple_loss_or_duplicates),
+    )
+}
+
+#[test]
+fn cpu_verify_partitions_contiguous_i32_12_bits() -> Result<(), Box<dyn Error>> {
+    run_cpu_partitioning(
+        (32 << 20) / size_of::<i32>(),
+        Box::new(|keys: &mut _| Ok(UniformRelation::gen_attr::<i32>(keys, 0..(32 << 20), None)?)),
+        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000, None)?)),
+        CpuHistogramAlgorithm::Contiguous,
+        CpuRadixPartitionAlgorithm::NC,
+        RadixBits::from(12),
+        4,
+        Box::new(&verify_partitions),
+    )
+}
+
+#[test]
+fn cpu_tuple_loss_or_duplicates_contiguous_i32_less_tuples_than_partitions(
+) -> Result<(), Box<dyn Error>> {
+    run_cpu_partitioning(
+        1000,
+        Box::new(|keys: &mut _| Ok(UniformRelation::gen_primary_key::<i32>(keys, None, None)?)),
+        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000, None)?)),
+        CpuHistogramAlgorithm::Contiguous,
+        CpuRadixPartitionAlgorithm::NC,
+        RadixBits::from(12),
+        4,
+        Box::new(&tuple_loss_or_duplicates),
+    )
+}
+
+#[test]
+fn cpu_verify_partitions_contiguous_i32_less_tuples_than_partitions() -> Result<(), Box<dyn Error>>
+{
+    run_cpu_partitioning(
+        1000,
+        Box::new(|keys: &mut _| Ok(UniformRelation::gen_attr::<i32>(keys, 0..(32 << 20), None)?)),
+        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000, None)?)),
+        CpuHistogramAlgorithm::Contiguous,
+        CpuRadixPartitionAlgorithm::NC,
+        RadixBits::from(12),
+        4,
+        Box::new(&verify_partitions),
+    )
+}
+
+#[test]
+fn cpu_tuple_loss_or_duplicates_contiguous_i32_non_power_2_data_len() -> Result<(), Box<dyn Error>>
+{
+    run_cpu_partitioning(
+        (32 << 10) / size_of::<i32>() - 7,
+        Box::new(|keys: &mut _| Ok(UniformRelation::gen_primary_key::<i32>(keys, None, None)?)),
+        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000, None)?)),
+        CpuHistogramAlgorithm::Contiguous,
+        CpuRadixPartitionAlgorithm::NC,
+        RadixBits::from(10),
+        4,
+        Box::new(&tuple_loss_or_duplicates),
+    )
+}
+
+#[test]
+fn cpu_verify_partitions_contiguous_i32_non_power_2_data_len() -> Result<(), Box<dyn Error>> {
+    run_cpu_partitioning(
+        (32 << 10) / size_of::<i32>() - 7,
+        Box::new(|keys: &mut _| Ok(UniformRelation::gen_attr::<i32>(keys, 0..(32 << 20), None)?)),
+        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000, None)?)),
+        CpuHistogramAlgorithm::Contiguous,
+        CpuRadixPartitionAlgorithm::NC,
+        RadixBits::from(10),
+        4,
+        Box::new(&verify_partitions),
+    )
+}
+
+fn verify_single_chunk(
+    _radix_pass: RadixPass,
+    _radix_bits: &RadixBits,
+    data_key: &[i32],
+    _data_pay: &[i32],
+    partitioned_relation: &PartitionedRelation<Tuple<i32, i32>>,
+    _partition_id: Option<u32>,
+) -> Result<(), Box<dyn Error>> {
+    assert_eq!(partitioned_relation.num_chunks(), 1);
+
+    let len = (0..partitioned_relation.fanout())
+        .map(|partition_id| partitioned_relation.partition_len(partition_id))
+        .sum::<Result<usize, _>>()?;
+    assert_eq!(len, data_key.len());
+
+    Ok(())
+}
+
+#[test]
+fn cpu_contiguous_has_single_chunk() -> Result<(), Box<dyn Error>> {
+    run_cpu_partitioning(
+        (32 << 10) / size_of::<i32>() - 7,
+        Box::new(|keys: &mut _| Ok(UniformRelation::gen_primary_key::<i32>(keys, None, None)?)),
+        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000, None)?)),
+        CpuHistogramAlgorithm::Contiguous,
+        CpuRadixPartitionAlgorithm::NC,
+        RadixBits::from(6),
+        4,
+        Box::new(&verify_single_chunk),
+    )
+}
+
+#[test]
+fn cpu_contiguous_rejects_swwc() {
+    let result = run_cpu_partitioning(
+        (32 << 10) / size_of::<i32>(),
+        Box::new(|keys: &mut _| Ok(UniformRelation::gen_primary_key::<i32>(keys, None, None)?)),
+        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000, None)?)),
+        CpuHistogramAlgorithm::Contiguous,
+        CpuRadixPartitionAlgorithm::Swwc,
+        RadixBits::from(6),
+        4,
+        Box::new(&tuple_loss_or_duplicates),
+    );
+
+    assert!(result.is_err());
+}
+
+#[test]
+fn cpu_contiguous_rejects_incomplete_prefix_sum() -> Result<(), Box<dyn Error>> {
+    let tuples = (32 << 10) / size_of::<i32>();
+    let threads = 4;
+    let radix_bits = 6;
+
+    let mut data_key = vec![0_i32; tuples];
+    let mut data_pay = vec![0_i32; tuples];
+    UniformRelation::gen_primary_key(data_key.as_mut_slice(), None, None)?;
+    UniformRelation::gen_attr(data_pay.as_mut_slice(), 0..10000, None)?;
+
+    let mut partition_offsets = PartitionOffsets::new(
+        CpuHistogramAlgorithm::Contiguous.into(),
+        threads,
+        radix_bits,
+        Allocator::mem_alloc_fn(MemType::SysMem),
+    );
+    let mut partitioned_relation = PartitionedRelation::new(
+        tuples,
+        CpuHistogramAlgorithm::Contiguous.into(),
+        radix_bits,
+        threads,
+        Allocator::mem_alloc_fn(MemType::SysMem),
+        Allocator::mem_alloc_fn(MemType::SysMem),
+    );
+    let mut partitioner = CpuRadixPartitioner::new(
+        CpuHistogramAlgorithm::Contiguous,
+        CpuRadixPartitionAlgorithm::NC,
+        radix_bits,
+        DerefMemType::SysMem,
+    )?;
+
+    // Skip the prefix sum of the last chunk
+    let data_key_chunks = data_key.as_slice().input_chunks::<i32>(threads)?;
+    for (key_chunk, offsets_chunk) in
+        izip!(data_key_chunks.into_iter(), partition_offsets.chunks_mut())
+            .take(threads as usize - 1)
+    {
+        partitioner.prefix_sum(key_chunk, offsets_chunk)?;
+    }
+
+    let data_key_chunks = data_key.as_slice().input_chunks::<i32>(threads)?;
+    let data_pay_chunks = data_pay.as_slice().input_chunks::<i32>(threads)?;
+    let (key_chunk, pay_chunk, offsets_chunk, partitioned_chunk) = izip!(
+        data_key_chunks.into_iter(),
+        data_pay_chunks.into_iter(),
+        partition_offsets.chunks_mut(),
+        partitioned_relation.chunks_mut()
+    )
+    .next()
+    .unwrap();
+    let result = partitioner.partition(key_chunk, pay_chunk, offsets_chunk, partitioned_chunk);
+
+    assert!(result.is_err());
+
+    Ok(())
+}
+
+// ======================== Unsigned and composite keys ========================
+
+fn gen_composite_key(keys: &mut [CompositeKey]) -> Result<(), Box<dyn Error>> {
+    UniformRelation::gen_primary_key(keys, None, None)?;
+    keys.iter_mut().for_each(|key| key.0 = key.1 % 7);
+    Ok(())
+}
+
+fn gen_composite_pay(pays: &mut [CompositeKey]) -> Result<(), Box<dyn Error>> {
+    pays.iter_mut()
+        .enumerate()
+        .for_each(|(i, pay)| *pay = CompositeKey(i as u64, !(i as u64)));
+    Ok(())
+}
+
+fn verify_composite_partitions(
+    radix_pass: RadixPass,
+    radix_bits: &RadixBits,
+    _data_key: &[CompositeKey],
+    _data_pay: &[CompositeKey],
+    partitioned_relation: &PartitionedRelation<Tuple<CompositeKey, CompositeKey>>,
+    _partition_id: Option<u32>,
+) -> Result<(), Box<dyn Error>> {
+    for c in 0..partitioned_relation.num_chunks() {
+        for p in 0..partitioned_relation.fanout() {
+            for tuple in partitioned_relation[(c, p)].iter() {
+                let dst_partition = key_to_partition(tuple.key.1 as i32, radix_bits, radix_pass);
+                assert_eq!(
+                    dst_partition, p,
+                    "Wrong partitioning detected in chunk {}: key {} in partition {}; \
+                    expected partition {}",
+                    c, tuple.key, p, dst_partition
+                );
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn cpu_tuple_loss_or_duplicates_chunked_u32_12_bits() -> Result<(), Box<dyn Error>> {
+    run_cpu_partitioning(
+        (32 << 20) / size_of::<u32>(),
+        Box::new(|keys: &mut _| Ok(UniformRelation::gen_primary_key::<u32>(keys, None, None)?)),
+        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<u32>(pays, 0..10000, None)?)),
+        CpuHistogramAlgorithm::Chunked,
+        CpuRadixPartitionAlgorithm::NC,
+        RadixBits::from(12),
+        4,
+        Box::new(&tuple_loss_or_duplicates),
+    )
+}
+
+#[test]
+fn cpu_tuple_loss_or_duplicates_chunked_swwc_u64_12_bits() -> Result<(), Box<dyn Error>> {
+    run_cpu_partitioning(
+        (32 << 20) / size_of::<u64>(),
+        Box::new(|keys: &mut _| Ok(UniformRelation::gen_primary_key::<u64>(keys, None, None)?)),
+        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<u64>(pays, 0..10000, None)?)),
+        CpuHistogramAlgorithm::Chunked,
+        CpuRadixPartitionAlgorithm::Swwc,
+        RadixBits::from(12),
+        4,
+        Box::new(&tuple_loss_or_duplicates),
+    )
+}
+
+#[test]
+fn cpu_tuple_loss_or_duplicates_chunked_composite_12_bits() -> Result<(), Box<dyn Error>> {
+    run_cpu_partitioning(
+        (32 << 20) / size_of::<CompositeKey>(),
+        Box::new(|keys: &mut _| gen_composite_key(keys)),
+        Box::new(|pays: &mut _| gen_composite_pay(pays)),
+        CpuHistogramAlgorithm::Chunked,
+        CpuRadixPartitionAlgorithm::NC,
+        RadixBits::from(12),
+        4,
+        Box::new(&tuple_loss_or_duplicates),
+    )
+}
+
+#[test]
+fn cpu_tuple_loss_or_duplicates_chunked_swwc_composite_12_bits() -> Result<(), Box<dyn Error>> {
+    run_cpu_partitioning(
+        (32 << 20) / size_of::<CompositeKey>(),
+        Box::new(|keys: &mut _| gen_composite_key(keys)),
+        Box::new(|pays: &mut _| gen_composite_pay(pays)),
+        CpuHistogramAlgorithm::Chunked,
+        CpuRadixPartitionAlgorithm::Swwc,
+        RadixBits::from(12),
+        4,
+        Box::new(&tuple_loss_or_duplicates),
+    )
+}
+
+#[test]
+fn cpu_verify_partitions_chunked_swwc_composite_12_bits() -> Result<(), Box<dyn Error>> {
+    run_cpu_partitioning(
+        (32 << 20) / size_of::<CompositeKey>(),
+        Box::new(|keys: &mut _| gen_composite_key(keys)),
+        Box::new(|pays: &mut _| gen_composite_pay(pays)),
+        CpuHistogramAlgorithm::Chunked,
+        CpuRadixPartitionAlgorithm::Swwc,
+        RadixBits::from(12),
+        4,
+        Box::new(&verify_composite_partitions),
+    )
+}
+
+// ======================== Mixed key and payload types ========================
+
+fn verify_mixed_partitions<K, V>(
+    radix_pass: RadixPass,
+    radix_bits: &RadixBits,
+    _data_key: &[K],
+    _data_pay: &[V],
+    partitioned_relation: &PartitionedRelation<Tuple<K, V>>,
+    _partition_id: Option<u32>,
+) -> Result<(), Box<dyn Error>>
+where
+    K: Copy + DeviceCopy + Display + Into<i64>,
+    V: Copy + DeviceCopy + Into<i64>,
+{
+    for c in 0..partitioned_relation.num_chunks() {
+        for p in 0..partitioned_relation.fanout() {
+            for tuple in partitioned_relation[(c, p)].iter() {
+                let key: i64 = tuple.key.into();
+                let value: i64 = tuple.value.into();
+                let dst_partition = key_to_partition(key as i32, radix_bits, radix_pass);
+                assert_eq!(
+                    dst_partition, p,
+                    "Wrong partitioning detected in chunk {}: key {} in partition {}; \
+                    expected partition {}",
+                    c, tuple.key, p, dst_partition
+                );
+                assert_eq!(key + 1, value, "Wrong payload {} of key {}", value, key);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn cpu_tuple_loss_or_duplicates_chunked_i32_i64_12_bits() -> Result<(), Box<dyn Error>> {
+    run_cpu_partitioning(
+        (32 << 20) / size_of::<i64>(),
+        Box::new(|keys: &mut _| Ok(UniformRelation::gen_primary_key::<i32>(keys, None, None)?)),
+        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i64>(pays, 0..10000, None)?)),
+        CpuHistogramAlgorithm::Chunked,
+        CpuRadixPartitionAlgorithm::NC,
+        RadixBits::from(12),
+        4,
+        Box::new(&tuple_loss_or_duplicates),
+    )
+}
+
+#[test]
+fn cpu_verify_partitions_chunked_swwc_i32_i64_12_bits() -> Result<(), Box<dyn Error>> {
+    run_cpu_partitioning(
+        (32 << 20) / size_of::<i64>(),
+        Box::new(|keys: &mut [i32]| {
+            keys.iter_mut().enumerate().for_each(|(i, k)| *k = i as i32);
+            Ok(())
+        }),
+        Box::new(|pays: &mut [i64]| {
+            pays.iter_mut()
+                .enumerate()
+                .for_each(|(i, p)| *p = i as i64 + 1);
+            Ok(())
+        }),
+        CpuHistogramAlgorithm::Chunked,
+        CpuRadixPartitionAlgorithm::Swwc,
+        RadixBits::from(12),
+        4,
+        Box::new(&verify_mixed_partitions),
+    )
+}
+
+#[test]
+fn cpu_verify_partitions_chunked_i64_i32_12_bits() -> Result<(), Box<dyn Error>> {
+    run_cpu_partitioning(
+        (32 << 20) / size_of::<i64>(),
+        Box::new(|keys: &mut [i64]| {
+            keys.iter_mut().enumerate().for_each(|(i, k)| *k = i as i64);
+            Ok(())
+        }),
+        Box::new(|pays: &mut [i32]| {
+            pays.iter_mut()
+                .enumerate()
+                .for_each(|(i, p)| *p = i as i32 + 1);
+            Ok(())
+        }),
+        CpuHistogramAlgorithm::Chunked,
+        CpuRadixPartitionAlgorithm::NC,
+        RadixBits::from(12),
+        4,
+        Box::new(&verify_mixed_partitions),
+    )
+}
+
+// ======================== Multi-pass ========================
+
+fn run_cpu_multi_pass_partitioning<K, V, KeyGenFn, PayGenFn, ValidatorFn>(
+    tuples: usize,
+    key_gen: Box<KeyGenFn>,
+    pay_gen: Box<PayGenFn>,
+    prefix_sum_algorithm: CpuHistogramAlgorithm,
+    partition_algorithm: CpuRadixPartitionAlgorithm,
+    radix_bits: RadixBits,
+    threads: usize,
+    mut validator: Box<ValidatorFn>,
+) -> Result<(), Box<dyn Error>>
+where
+    K: Copy + Default + KeyAttribute + DeviceCopy + CpuRadixPartitionable<V> + Send + Sync,
+    V: Copy + Default + DeviceCopy + Send + Sync,
+    KeyGenFn: FnOnce(&mut [K]) -> Result<(), Box<dyn Error>>,
+    PayGenFn: FnOnce(&mut [V]) -> Result<(), Box<dyn Error>>,
+    ValidatorFn: FnMut(
+        &RadixBits,
+        &[K],
+        &[V],
+        &PartitionedRelation<Tuple<K, V>>,
+    ) -> Result<(), Box<dyn Error>>,
+{
+    let mut data_key: Vec<K> = vec![K::default(); tuples];
+    let mut data_pay: Vec<V> = vec![V::default(); tuples];
+
+    key_gen(data_key.as_mut_slice())?;
+    pay_gen(data_pay.as_mut_slice())?;
+
+    let thread_pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(threads)
+        .build()?;
+
+    let partitioner = CpuMultiPassRadixPartitioner::new(
+        prefix_sum_algorithm,
+        partition_algorithm,
+        &radix_bits,
+        DerefMemType::SysMem,
+    )?;
+
+    let partitioned_relation = partitioner.partition(
+        &thread_pool,
+        data_key.as_slice(),
+        data_pay.as_slice(),
+        DerefMemType::SysMem,
+    )?;
+
+    assert_eq!(partitioned_relation.num_chunks(), 1);
+    assert_eq!(partitioned_relation.fanout(), radix_bits.fanout());
+
+    validator(
+        &radix_bits,
+        data_key.as_slice(),
+        data_pay.as_slice(),
+        &partitioned_relation,
+    )
+}
+
+// Computes the partition ID of the multi-pass output, i.e., the per-pass
+// partitions nested in pass order.
+fn multi_pass_key_to_partition(key: i32, radix_bits: &RadixBits) -> u32 {
+    [RadixPass::First, RadixPass::Second, RadixPass::Third]
+        .iter()
+        .filter_map(|&pass| radix_bits.pass_fanout(pass).map(|fanout| (pass, fanout)))
+        .fold(0, |id, (pass, fanout)| {
+            id * fanout + key_to_partition(key, radix_bits, pass)
+        })
+}
+
+fn multi_pass_tuple_loss_or_duplicates<K, V>(
+    _radix_bits: &RadixBits,
+    data_key: &[K],
+    data_pay: &[V],
+    partitioned_relation: &PartitionedRelation<Tuple<K, V>>,
+) -> Result<(), Box<dyn Error>>
+where
+    K: Copy + DeviceCopy + Display + Eq + Hash,
+    V: Copy + DeviceCopy + Display + PartialEq,
+{
+    let mut original_tuples: HashMap<K, (V, u32)> = data_key
+        .iter()
+        .copied()
+        .zip(data_pay.iter().copied().zip(std::iter::repeat(0)))
+        .collect();
+
+    for p in 0..partitioned_relation.fanout() {
+        assert_eq!(
+            partitioned_relation[(0, p)].len(),
+            partitioned_relation.partition_len(p)?
+        );
+
+        for tuple in partitioned_relation[(0, p)].iter() {
+            let (value, counter) = original_tuples
+                .get_mut(&tuple.key)
+                .unwrap_or_else(|| panic!("Invalid key: {}", tuple.key));
+            assert!(
+                tuple.value == *value,
+                "Invalid payload in partition {}: {}; expected: {}",
+                p,
+                tuple.value,
+                value
+            );
+            assert_eq!(*counter, 0, "Duplicate key: {}", tuple.key);
+            *counter += 1;
+        }
+    }
+
+    original_tuples.iter().for_each(|(key, &(_, counter))| {
+        assert_eq!(
+            counter, 1,
+            "Key {} occurs {} times; expected exactly once",
+            key, counter
+        );
+    });
+
+    Ok(())
+}
+
+fn multi_pass_verify_partitions(
+    radix_bits: &RadixBits,
+    _data_key: &[i32],
+    _data_pay: &[i32],
+    partitioned_relation: &PartitionedRelation<Tuple<i32, i32>>,
+) -> Result<(), Box<dyn Error>> {
+    for p in 0..partitioned_relation.fanout() {
+        for tuple in partitioned_relation[(0, p)].iter() {
+            let dst_partition = multi_pass_key_to_partition(tuple.key, radix_bits);
+            assert_eq!(
+                dst_partition, p,
+                "Wrong partitioning detected: key {} in partition {}; expected partition {}",
+                tuple.key, p, dst_partition
+            );
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn cpu_tuple_loss_or_duplicates_multi_pass_chunked_i32_1_pass() -> Result<(), Box<dyn Error>> {
+    run_cpu_multi_pass_partitioning(
+        (32 << 20) / size_of::<i32>(),
+        Box::new(|keys: &mut _| Ok(UniformRelation::gen_primary_key::<i32>(keys, None, None)?)),
+        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000, None)?)),
+        CpuHistogramAlgorithm::Chunked,
+        CpuRadixPartitionAlgorithm::NC,
+        RadixBits::from(8),
+        4,
+        Box::new(&multi_pass_tuple_loss_or_duplicates),
+    )
+}
+
+#[test]
+fn cpu_tuple_loss_or_duplicates_multi_pass_chunked_i32_2_passes() -> Result<(), Box<dyn Error>> {
+    run_cpu_multi_pass_partitioning(
+        (32 << 20) / size_of::<i32>(),
+        Box::new(|keys: &mut _| Ok(UniformRelation::gen_primary_key::<i32>(keys, None, None)?)),
+        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000, None)?)),
+        CpuHistogramAlgorithm::Chunked,
+        CpuRadixPartitionAlgorithm::NC,
+        RadixBits::new(Some(6), Some(6), None),
+        4,
+        Box::new(&multi_pass_tuple_loss_or_duplicates),
+    )
+}
+
+#[test]
+fn cpu_verify_partitions_multi_pass_chunked_i32_2_passes() -> Result<(), Box<dyn Error>> {
+    run_cpu_multi_pass_partitioning(
+        (32 << 20) / size_of::<i32>(),
+        Box::new(|keys: &mut _| Ok(UniformRelation::gen_attr::<i32>(keys, 0..(32 << 20), None)?)),
+        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000, None)?)),
+        CpuHistogramAlgorithm::Chunked,
+        CpuRadixPartitionAlgorithm::NC,
+        RadixBits::new(Some(6), Some(6), None),
+        4,
+        Box::new(&multi_pass_verify_partitions),
+    )
+}
+
+#[test]
+fn cpu_tuple_loss_or_duplicates_multi_pass_chunked_swwc_i32_3_passes() -> Result<(), Box<dyn Error>>
+{
+    run_cpu_multi_pass_partitioning(
+        (32 << 20) / size_of::<i32>(),
+        Box::new(|keys: &mut _| Ok(UniformRelation::gen_primary_key::<i32>(keys, None, None)?)),
+        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000, None)?)),
+        CpuHistogramAlgorithm::Chunked,
+        CpuRadixPartitionAlgorithm::Swwc,
+        RadixBits::new(Some(4), Some(4), Some(4)),
+        4,
+        Box::new(&multi_pass_tuple_loss_or_duplicates),
+    )
+}
+
+#[test]
+fn cpu_verify_partitions_multi_pass_chunked_swwc_i32_3_passes() -> Result<(), Box<dyn Error>> {
+    run_cpu_multi_pass_partitioning(
+        (32 << 20) / size_of::<i32>(),
+        Box::new(|keys: &mut _| Ok(UniformRelation::gen_attr::<i32>(keys, 0..(32 << 20), None)?)),
+        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000, None)?)),
+        CpuHistogramAlgorithm::Chunked,
+        CpuRadixPartitionAlgorithm::Swwc,
+        RadixBits::new(Some(4), Some(4), Some(4)),
+        4,
+        Box::new(&multi_pass_verify_partitions),
+    )
+}
+
+#[test]
+fn cpu_verify_partitions_multi_pass_chunked_i32_less_tuples_than_partitions(
+) -> Result<(), Box<dyn Error>> {
+    run_cpu_multi_pass_partitioning(
+        1000,
+        Box::new(|keys: &mut _| Ok(UniformRelation::gen_attr::<i32>(keys, 0..(32 << 20), None)?)),
+        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000, None)?)),
+        CpuHistogramAlgorithm::Chunked,
+        CpuRadixPartitionAlgorithm::NC,
+        RadixBits::new(Some(6), Some(6), None),
+        4,
+        Box::new(&multi_pass_verify_partitions),
+    )
+}
+
+#[test]
+fn cpu_tuple_loss_or_duplicates_multi_pass_chunked_i64_3_passes() -> Result<(), Box<dyn Error>> {
+    run_cpu_multi_pass_partitioning(
+        (32 << 20) / size_of::<i64>(),
+        Box::new(|keys: &mut _| Ok(UniformRelation::gen_primary_key::<i64>(keys, None, None)?)),
+        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i64>(pays, 0..10000, None)?)),
+        CpuHistogramAlgorithm::Chunked,
+        CpuRadixPartitionAlgorithm::NC,
+        RadixBits::new(Some(4), Some(4), Some(4)),
+        4,
+        Box::new(&multi_pass_tuple_loss_or_duplicates),
+    )
+}
+
+#[test]
+fn cpu_tuple_loss_or_duplicates_multi_pass_contiguous_i32_2_passes() -> Result<(), Box<dyn Error>> {
+    run_cpu_multi_pass_partitioning(
+        (32 << 20) / size_of::<i32>(),
+        Box::new(|keys: &mut _| Ok(UniformRelation::gen_primary_key::<i32>(keys, None, None)?)),
+        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000, None)?)),
+        CpuHistogramAlgorithm::Contiguous,
+        CpuRadixPartitionAlgorithm::NC,
+        RadixBits::new(Some(6), Some(6), None),
+        4,
+        Box::new(&multi_pass_tuple_loss_or_duplicates),
+    )
+}
+
+#[test]
+fn cpu_verify_partitions_multi_pass_contiguous_i32_2_passes() -> Result<(), Box<dyn Error>> {
+    run_cpu_multi_pass_partitioning(
+        (32 << 20) / size_of::<i32>(),
+        Box::new(|keys: &mut _| Ok(UniformRelation::gen_attr::<i32>(keys, 0..(32 << 20), None)?)),
+        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr::<i32>(pays, 0..10000, None)?)),
+        CpuHistogramAlgorithm::Contiguous,
+        CpuRadixPartitionAlgorithm::NC,
+        RadixBits::new(Some(6), Some(6), None),
+        4,
+        Box::new(&multi_pass_verify_partitions),
+    )
+}
+
+#[test]
+fn cpu_multi_pass_rejects_third_pass_without_second() {
+    let partitioner = CpuMultiPassRadixPartitioner::new(
+        CpuHistogramAlgorithm::Chunked,
+        CpuRadixPartitionAlgorithm::NC,
+        &RadixBits::new(Some(4), None, Some(4)),
+        DerefMemType::SysMem,
+    );
+
+    assert!(partitioner.is_err());
+}
+
+// ======================== Skew detection ========================
+
+fn run_skew_detection<T>(
+    data_key: &[T],
+    radix_bits: u32,
+    threads: u32,
+    skew_factor: f64,
+) -> Result<Vec<u32>, Box<dyn Error>>
+where
+    T: Clone + Default + KeyAttribute + DeviceCopy + CpuRadixPartitionable,
+{
+    let mut partition_offsets = PartitionOffsets::<Tuple<T, T>>::new(
+        CpuHistogramAlgorithm::Chunked.into(),
+        threads,
+        radix_bits,
+        Allocator::mem_alloc_fn(MemType::SysMem),
+    );
+
+    let mut partitioner = CpuRadixPartitioner::new(
+        CpuHistogramAlgorithm::Chunked,
+        CpuRadixPartitionAlgorithm::NC,
+        radix_bits,
+        DerefMemType::SysMem,
+    )?;
+
+    let data_key_chunks = data_key.input_chunks::<T>(threads)?;
+    for (key_chunk, offsets_chunk) in
+        izip!(data_key_chunks.into_iter(), partition_offsets.chunks_mut())
+    {
+        partitioner.prefix_sum(key_chunk, offsets_chunk)?;
+    }
+
+    Ok(skew::skewed_partitions(&partition_offsets, skew_factor)?)
+}
+
+#[test]
+fn cpu_skewed_partitions_uniform_i32() -> Result<(), Box<dyn Error>> {
+    let mut data_key = vec![0_i32; 64 << 10];
+    UniformRelation::gen_primary_key(data_key.as_mut_slice(), None, None)?;
+
+    let skewed = run_skew_detection(&data_key, 4, 4, 4.0)?;
+    assert!(skewed.is_empty());
+
+    Ok(())
+}
+
+#[test]
+fn cpu_skewed_partitions_single_key_i32() -> Result<(), Box<dyn Error>> {
+    let data_key = vec![5_i32; 64 << 10];
+
+    let skewed = run_skew_detection(&data_key, 4, 4, 4.0)?;
+    assert_eq!(skewed, vec![5]);
+
+    Ok(())
+}
+
+#[test]
+fn cpu_skewed_partitions_zipf_i64() -> Result<(), Box<dyn Error>> {
+    let mut data_key = vec![0_i64; 64 << 10];
+    ZipfRelation::gen_attr(&mut data_key, 10000, 1.5, None)?;
+
+    // Key 1 is the most frequent key, and is assigned to partition 1
+    let skewed = run_skew_detection(&data_key, 4, 4, 4.0)?;
+    assert!(skewed.contains(&1));
+
+    Ok(())
+}
+
+#[test]
+fn cpu_skewed_partitions_rejects_small_skew_factor() -> Result<(), Box<dyn Error>> {
+    let data_key = vec![5_i32; 1024];
+
+    assert!(run_skew_detection(&data_key, 4, 4, 0.5).is_err());
+
+    Ok(())
+}
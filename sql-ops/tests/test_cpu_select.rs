@@ -0,0 +1,285 @@
+// Copyright 2020-2022 Clemens Lutz
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use rand::distributions::{Distribution, Uniform};
+use sql_ops::select::{
+    ColumnPredicate, CpuSelectable, CpuSelection, CpuSelectionAlgorithm, Predicate,
+};
+use std::error::Error;
+use std::ops::Bound;
+use std::result::Result;
+
+const ALGORITHMS: [CpuSelectionAlgorithm; 3] = [
+    CpuSelectionAlgorithm::Branching,
+    CpuSelectionAlgorithm::Predication,
+    CpuSelectionAlgorithm::Simd,
+];
+
+fn contains<T: CpuSelectable>(predicate: &Predicate<T>, value: T) -> bool {
+    match *predicate {
+        Predicate::Equal(constant) => value == constant,
+        Predicate::Range(low, high) => {
+            let above_low = match low {
+                Bound::Included(low) => value >= low,
+                Bound::Excluded(low) => value > low,
+                Bound::Unbounded => true,
+            };
+            let below_high = match high {
+                Bound::Included(high) => value <= high,
+                Bound::Excluded(high) => value < high,
+                Bound::Unbounded => true,
+            };
+            above_low && below_high
+        }
+    }
+}
+
+fn run_cpu_selection<T: CpuSelectable>(
+    predicates: &[(&[T], Predicate<T>)],
+) -> Result<(), Box<dyn Error>> {
+    let len = predicates[0].0.len();
+    let expected: Vec<bool> = (0..len)
+        .map(|i| {
+            predicates
+                .iter()
+                .all(|(column, predicate)| contains(predicate, column[i]))
+        })
+        .collect();
+    let column_predicates: Vec<ColumnPredicate<'_>> = predicates
+        .iter()
+        .map(|&(column, predicate)| ColumnPredicate::new(column, predicate))
+        .collect();
+
+    check_cpu_selection(&column_predicates, &expected)
+}
+
+fn check_cpu_selection(
+    predicates: &[ColumnPredicate<'_>],
+    expected_rows: &[bool],
+) -> Result<(), Box<dyn Error>> {
+    let len = expected_rows.len();
+    let expected: Vec<u32> = (0..len)
+        .filter(|&i| expected_rows[i])
+        .map(|i| i as u32)
+        .collect();
+
+    for &algorithm in ALGORITHMS.iter() {
+        let selection = CpuSelection::new(algorithm);
+
+        let mut selection_vector = vec![0; len];
+        let selected = selection.select(predicates, &mut selection_vector)?;
+        assert_eq!(
+            &selection_vector[0..selected],
+            expected.as_slice(),
+            "Selection vector of {:?} is wrong",
+            algorithm
+        );
+
+        let mut bitmap = vec![!0; CpuSelection::bitmap_len(len)];
+        let selected = selection.select_bitmap(predicates, &mut bitmap)?;
+        let bitmap_rows: Vec<u32> = (0..bitmap.len() * 64)
+            .filter(|&i| (bitmap[i / 64] >> (i % 64)) & 1 == 1)
+            .map(|i| i as u32)
+            .collect();
+        assert_eq!(selected, expected.len());
+        assert_eq!(bitmap_rows, expected, "Bitmap of {:?} is wrong", algorithm);
+    }
+
+    Ok(())
+}
+
+fn uniform_column(len: usize, high: i32) -> Vec<i32> {
+    let mut rng = rand::thread_rng();
+    let dist = Uniform::new(0, high);
+    dist.sample_iter(&mut rng).take(len).collect()
+}
+
+#[test]
+fn cpu_select_equal_i32() -> Result<(), Box<dyn Error>> {
+    let column = uniform_column(10_000, 10);
+    run_cpu_selection(&[(&column[..], Predicate::Equal(3))])
+}
+
+#[test]
+fn cpu_select_range_i32() -> Result<(), Box<dyn Error>> {
+    let column = uniform_column(10_000, 100);
+    run_cpu_selection(&[(
+        &column[..],
+        Predicate::Range(Bound::Excluded(20), Bound::Included(60)),
+    )])
+}
+
+#[test]
+fn cpu_select_unbounded_range_i32() -> Result<(), Box<dyn Error>> {
+    let column = uniform_column(10_000, 100);
+    run_cpu_selection(&[(
+        &column[..],
+        Predicate::Range(Bound::Unbounded, Bound::Excluded(50)),
+    )])
+}
+
+#[test]
+fn cpu_select_conjunction_i32() -> Result<(), Box<dyn Error>> {
+    let shipdate = uniform_column(10_000, 2_000);
+    let discount = uniform_column(10_000, 11);
+    let quantity = uniform_column(10_000, 51);
+    run_cpu_selection(&[
+        (
+            &shipdate[..],
+            Predicate::Range(Bound::Included(732), Bound::Excluded(1097)),
+        ),
+        (
+            &discount[..],
+            Predicate::Range(Bound::Included(5), Bound::Included(7)),
+        ),
+        (
+            &quantity[..],
+            Predicate::Range(Bound::Unbounded, Bound::Excluded(24)),
+        ),
+    ])
+}
+
+#[test]
+fn cpu_select_conjunction_mixed_types() -> Result<(), Box<dyn Error>> {
+    let shipdate = uniform_column(10_000, 2_000);
+    let discount: Vec<i64> = (0..10_000).map(|i| i % 11).collect();
+    let quantity: Vec<u32> = (0..10_000).map(|i| i % 51).collect();
+    let extendedprice: Vec<u64> = (0..10_000).map(|i| (1 << 63) - 5_000 + i).collect();
+
+    let shipdate_predicate = Predicate::Range(Bound::Included(732), Bound::Excluded(1097));
+    let discount_predicate = Predicate::Range(Bound::Included(5), Bound::Included(7));
+    let quantity_predicate = Predicate::Range(Bound::Unbounded, Bound::Excluded(24));
+    let extendedprice_predicate = Predicate::Range(Bound::Excluded(1 << 63), Bound::Unbounded);
+
+    let expected: Vec<bool> = (0..10_000)
+        .map(|i| {
+            contains(&shipdate_predicate, shipdate[i])
+                && contains(&discount_predicate, discount[i])
+                && contains(&quantity_predicate, quantity[i])
+                && contains(&extendedprice_predicate, extendedprice[i])
+        })
+        .collect();
+
+    check_cpu_selection(
+        &[
+            ColumnPredicate::new(&shipdate, shipdate_predicate),
+            ColumnPredicate::new(&discount, discount_predicate),
+            ColumnPredicate::new(&quantity, quantity_predicate),
+            ColumnPredicate::new(&extendedprice, extendedprice_predicate),
+        ],
+        &expected,
+    )
+}
+
+#[test]
+fn cpu_select_non_multiple_of_block_len_i64() -> Result<(), Box<dyn Error>> {
+    let column: Vec<i64> = (0..1_001).collect();
+    run_cpu_selection(&[(
+        &column[..],
+        Predicate::Range(Bound::Included(63), Bound::Included(998)),
+    )])
+}
+
+#[test]
+fn cpu_select_empty_range_u64() -> Result<(), Box<dyn Error>> {
+    let column: Vec<u64> = (0..1_000).collect();
+    run_cpu_selection(&[(
+        &column[..],
+        Predicate::Range(Bound::Excluded(u64::MAX), Bound::Unbounded),
+    )])
+}
+
+#[test]
+fn cpu_select_all_u32() -> Result<(), Box<dyn Error>> {
+    let column: Vec<u32> = (0..1_000).collect();
+    run_cpu_selection(&[(
+        &column[..],
+        Predicate::Range(Bound::Unbounded, Bound::Unbounded),
+    )])
+}
+
+#[test]
+fn cpu_select_negative_range_i64() -> Result<(), Box<dyn Error>> {
+    let column: Vec<i64> = (-500..500).collect();
+    run_cpu_selection(&[(
+        &column[..],
+        Predicate::Range(Bound::Included(-300), Bound::Excluded(-7)),
+    )])
+}
+
+#[test]
+fn cpu_select_range_across_sign_bit_u32() -> Result<(), Box<dyn Error>> {
+    let column: Vec<u32> = (0..1_000).map(|i| (1 << 31) - 500 + i).collect();
+    run_cpu_selection(&[(
+        &column[..],
+        Predicate::Range(
+            Bound::Included((1 << 31) - 100),
+            Bound::Excluded((1 << 31) + 100),
+        ),
+    )])
+}
+
+#[test]
+fn cpu_select_range_across_sign_bit_u64() -> Result<(), Box<dyn Error>> {
+    let column: Vec<u64> = (0..1_000).map(|i| (1 << 63) - 500 + i).collect();
+    run_cpu_selection(&[(
+        &column[..],
+        Predicate::Range(Bound::Excluded((1 << 63) - 100), Bound::Unbounded),
+    )])
+}
+
+#[test]
+fn cpu_select_empty_columns_i32() -> Result<(), Box<dyn Error>> {
+    let column: Vec<i32> = Vec::new();
+    run_cpu_selection(&[(&column[..], Predicate::Equal(0))])
+}
+
+#[test]
+fn cpu_select_rejects_mismatched_columns() {
+    let short = vec![0_i32; 10];
+    let long = vec![0_i32; 11];
+    let mut selection_vector = vec![0; 11];
+
+    let result = CpuSelection::new(CpuSelectionAlgorithm::Branching).select(
+        &[
+            ColumnPredicate::new(&short, Predicate::Equal(0)),
+            ColumnPredicate::new(&long, Predicate::Equal(0)),
+        ],
+        &mut selection_vector,
+    );
+    assert!(result.is_err());
+}
+
+#[test]
+fn cpu_select_rejects_short_selection_vector() {
+    let column = vec![0_i32; 10];
+    let mut selection_vector = vec![0; 9];
+
+    let result = CpuSelection::new(CpuSelectionAlgorithm::Predication).select(
+        &[ColumnPredicate::new(&column, Predicate::Equal(0))],
+        &mut selection_vector,
+    );
+    assert!(result.is_err());
+}
+
+#[test]
+fn cpu_select_rejects_short_bitmap() {
+    let column = vec![0_i32; 65];
+    let mut bitmap = vec![0; 1];
+
+    let result = CpuSelection::new(CpuSelectionAlgorithm::Simd).select_bitmap(
+        &[ColumnPredicate::new(&column, Predicate::Equal(0))],
+        &mut bitmap,
+    );
+    assert!(result.is_err());
+}
@@ -62,8 +62,8 @@ fn gpu_verify_join_aggregate(
     let mut outer_rel_key = alloc_fn(probe_tuples);
     let mut outer_rel_pay = alloc_fn(probe_tuples);
 
-    UniformRelation::gen_primary_key(&mut inner_rel_key, None)?;
-    UniformRelation::gen_foreign_key_from_primary_key(&mut outer_rel_key, &inner_rel_key);
+    UniformRelation::gen_primary_key(&mut inner_rel_key, None, None)?;
+    UniformRelation::gen_foreign_key_from_primary_key(&mut outer_rel_key, &inner_rel_key, None);
 
     inner_rel_pay
         .iter_mut()
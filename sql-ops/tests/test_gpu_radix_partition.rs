@@ -134,7 +134,7 @@ where
                 CpuRadixPartitionAlgorithm::NC,
                 radix_bits.pass_radix_bits(RadixPass::First).unwrap(),
                 DerefMemType::SysMem,
-            );
+            )?;
 
             for (key_chunk, offset_chunk) in key_chunks.into_iter().zip(offset_chunks) {
                 radix_prnr.prefix_sum(key_chunk, offset_chunk)?;
@@ -459,8 +459,8 @@ where
 fn gpu_tuple_loss_or_duplicates_cpu_chunked_i32_2_bits() -> Result<(), Box<dyn Error>> {
     run_gpu_partitioning(
         (32 << 20) / mem::size_of::<i32>(),
-        Box::new(|keys: &mut _| Ok(UniformRelation::gen_primary_key(keys, None)?)),
-        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr(pays, 0..10000)?)),
+        Box::new(|keys: &mut _| Ok(UniformRelation::gen_primary_key(keys, None, None)?)),
+        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr(pays, 0..10000, None)?)),
         DeviceType::Cpu(CpuHistogramAlgorithm::Chunked),
         GpuRadixPartitionAlgorithm::NC,
         RadixBits::from(2),
@@ -474,8 +474,8 @@ fn gpu_tuple_loss_or_duplicates_cpu_chunked_i32_2_bits() -> Result<(), Box<dyn E
 fn gpu_verify_partitions_cpu_chunked_i32_2_bits() -> Result<(), Box<dyn Error>> {
     run_gpu_partitioning(
         (32 << 20) / mem::size_of::<i32>(),
-        Box::new(|keys: &mut _| Ok(UniformRelation::gen_attr(keys, 0..(32 << 20))?)),
-        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr(pays, 0..10000)?)),
+        Box::new(|keys: &mut _| Ok(UniformRelation::gen_attr(keys, 0..(32 << 20), None)?)),
+        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr(pays, 0..10000, None)?)),
         DeviceType::Cpu(CpuHistogramAlgorithm::Chunked),
         GpuRadixPartitionAlgorithm::NC,
         RadixBits::from(2),
@@ -489,8 +489,8 @@ fn gpu_verify_partitions_cpu_chunked_i32_2_bits() -> Result<(), Box<dyn Error>>
 fn gpu_tuple_loss_or_duplicates_small_chunked_i32_2_bits() -> Result<(), Box<dyn Error>> {
     run_gpu_partitioning(
         100,
-        Box::new(|keys: &mut _| Ok(UniformRelation::gen_primary_key(keys, None)?)),
-        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr(pays, 0..10000)?)),
+        Box::new(|keys: &mut _| Ok(UniformRelation::gen_primary_key(keys, None, None)?)),
+        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr(pays, 0..10000, None)?)),
         DeviceType::Gpu(GpuHistogramAlgorithm::Chunked),
         GpuRadixPartitionAlgorithm::NC,
         RadixBits::from(2),
@@ -504,8 +504,8 @@ fn gpu_tuple_loss_or_duplicates_small_chunked_i32_2_bits() -> Result<(), Box<dyn
 fn gpu_verify_partitions_small_chunked_i32_2_bits() -> Result<(), Box<dyn Error>> {
     run_gpu_partitioning(
         100,
-        Box::new(|keys: &mut _| Ok(UniformRelation::gen_attr(keys, 0..(32 << 20))?)),
-        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr(pays, 0..10000)?)),
+        Box::new(|keys: &mut _| Ok(UniformRelation::gen_attr(keys, 0..(32 << 20), None)?)),
+        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr(pays, 0..10000, None)?)),
         DeviceType::Gpu(GpuHistogramAlgorithm::Chunked),
         GpuRadixPartitionAlgorithm::NC,
         RadixBits::from(2),
@@ -519,8 +519,8 @@ fn gpu_verify_partitions_small_chunked_i32_2_bits() -> Result<(), Box<dyn Error>
 fn gpu_tuple_loss_or_duplicates_chunked_i32_2_bits() -> Result<(), Box<dyn Error>> {
     run_gpu_partitioning(
         (32 << 20) / mem::size_of::<i32>(),
-        Box::new(|keys: &mut _| Ok(UniformRelation::gen_primary_key(keys, None)?)),
-        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr(pays, 0..10000)?)),
+        Box::new(|keys: &mut _| Ok(UniformRelation::gen_primary_key(keys, None, None)?)),
+        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr(pays, 0..10000, None)?)),
         DeviceType::Gpu(GpuHistogramAlgorithm::Chunked),
         GpuRadixPartitionAlgorithm::NC,
         RadixBits::from(2),
@@ -534,8 +534,8 @@ fn gpu_tuple_loss_or_duplicates_chunked_i32_2_bits() -> Result<(), Box<dyn Error
 fn gpu_tuple_loss_or_duplicates_chunked_i32_10_bits() -> Result<(), Box<dyn Error>> {
     run_gpu_partitioning(
         (32 << 20) / mem::size_of::<i32>(),
-        Box::new(|keys: &mut _| Ok(UniformRelation::gen_primary_key(keys, None)?)),
-        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr(pays, 0..10000)?)),
+        Box::new(|keys: &mut _| Ok(UniformRelation::gen_primary_key(keys, None, None)?)),
+        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr(pays, 0..10000, None)?)),
         DeviceType::Gpu(GpuHistogramAlgorithm::Chunked),
         GpuRadixPartitionAlgorithm::NC,
         RadixBits::from(10),
@@ -549,8 +549,8 @@ fn gpu_tuple_loss_or_duplicates_chunked_i32_10_bits() -> Result<(), Box<dyn Erro
 fn gpu_verify_partitions_chunked_i32_2_bits() -> Result<(), Box<dyn Error>> {
     run_gpu_partitioning(
         (32 << 20) / mem::size_of::<i32>(),
-        Box::new(|keys: &mut _| Ok(UniformRelation::gen_attr(keys, 0..(32 << 20))?)),
-        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr(pays, 0..10000)?)),
+        Box::new(|keys: &mut _| Ok(UniformRelation::gen_attr(keys, 0..(32 << 20), None)?)),
+        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr(pays, 0..10000, None)?)),
         DeviceType::Gpu(GpuHistogramAlgorithm::Chunked),
         GpuRadixPartitionAlgorithm::NC,
         RadixBits::from(2),
@@ -564,8 +564,8 @@ fn gpu_verify_partitions_chunked_i32_2_bits() -> Result<(), Box<dyn Error>> {
 fn gpu_verify_partitions_chunked_i32_10_bits() -> Result<(), Box<dyn Error>> {
     run_gpu_partitioning(
         (32 << 20) / mem::size_of::<i32>(),
-        Box::new(|keys: &mut _| Ok(UniformRelation::gen_attr(keys, 0..(32 << 20))?)),
-        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr(pays, 0..10000)?)),
+        Box::new(|keys: &mut _| Ok(UniformRelation::gen_attr(keys, 0..(32 << 20), None)?)),
+        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr(pays, 0..10000, None)?)),
         DeviceType::Gpu(GpuHistogramAlgorithm::Chunked),
         GpuRadixPartitionAlgorithm::NC,
         RadixBits::from(10),
@@ -579,8 +579,8 @@ fn gpu_verify_partitions_chunked_i32_10_bits() -> Result<(), Box<dyn Error>> {
 fn gpu_tuple_loss_or_duplicates_chunked_i32_12_bits() -> Result<(), Box<dyn Error>> {
     run_gpu_partitioning(
         (32 << 20) / mem::size_of::<i32>(),
-        Box::new(|keys: &mut _| Ok(UniformRelation::gen_primary_key(keys, None)?)),
-        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr(pays, 0..10000)?)),
+        Box::new(|keys: &mut _| Ok(UniformRelation::gen_primary_key(keys, None, None)?)),
+        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr(pays, 0..10000, None)?)),
         DeviceType::Gpu(GpuHistogramAlgorithm::Chunked),
         GpuRadixPartitionAlgorithm::NC,
         RadixBits::from(12),
@@ -594,8 +594,8 @@ fn gpu_tuple_loss_or_duplicates_chunked_i32_12_bits() -> Result<(), Box<dyn Erro
 fn gpu_verify_partitions_chunked_i32_12_bits() -> Result<(), Box<dyn Error>> {
     run_gpu_partitioning(
         (32 << 20) / mem::size_of::<i32>(),
-        Box::new(|keys: &mut _| Ok(UniformRelation::gen_attr(keys, 0..(32 << 20))?)),
-        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr(pays, 0..10000)?)),
+        Box::new(|keys: &mut _| Ok(UniformRelation::gen_attr(keys, 0..(32 << 20), None)?)),
+        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr(pays, 0..10000, None)?)),
         DeviceType::Gpu(GpuHistogramAlgorithm::Chunked),
         GpuRadixPartitionAlgorithm::NC,
         RadixBits::from(12),
@@ -609,8 +609,8 @@ fn gpu_verify_partitions_chunked_i32_12_bits() -> Result<(), Box<dyn Error>> {
 fn gpu_tuple_loss_or_duplicates_chunked_non_power_two() -> Result<(), Box<dyn Error>> {
     run_gpu_partitioning(
         10_usize.pow(6),
-        Box::new(|keys: &mut _| Ok(UniformRelation::gen_primary_key(keys, None)?)),
-        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr(pays, 0..10000)?)),
+        Box::new(|keys: &mut _| Ok(UniformRelation::gen_primary_key(keys, None, None)?)),
+        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr(pays, 0..10000, None)?)),
         DeviceType::Gpu(GpuHistogramAlgorithm::Chunked),
         GpuRadixPartitionAlgorithm::NC,
         RadixBits::from(10),
@@ -624,8 +624,8 @@ fn gpu_tuple_loss_or_duplicates_chunked_non_power_two() -> Result<(), Box<dyn Er
 fn gpu_verify_partitions_chunked_non_power_two() -> Result<(), Box<dyn Error>> {
     run_gpu_partitioning(
         10_usize.pow(6),
-        Box::new(|keys: &mut _| Ok(UniformRelation::gen_attr(keys, 0..(32 << 20))?)),
-        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr(pays, 0..10000)?)),
+        Box::new(|keys: &mut _| Ok(UniformRelation::gen_attr(keys, 0..(32 << 20), None)?)),
+        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr(pays, 0..10000, None)?)),
         DeviceType::Gpu(GpuHistogramAlgorithm::Chunked),
         GpuRadixPartitionAlgorithm::NC,
         RadixBits::from(10),
@@ -639,8 +639,8 @@ fn gpu_verify_partitions_chunked_non_power_two() -> Result<(), Box<dyn Error>> {
 fn gpu_tuple_loss_or_duplicates_contiguous_i32_2_bits() -> Result<(), Box<dyn Error>> {
     run_gpu_partitioning(
         (32 << 20) / mem::size_of::<i32>(),
-        Box::new(|keys: &mut _| Ok(UniformRelation::gen_primary_key(keys, None)?)),
-        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr(pays, 0..10000)?)),
+        Box::new(|keys: &mut _| Ok(UniformRelation::gen_primary_key(keys, None, None)?)),
+        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr(pays, 0..10000, None)?)),
         DeviceType::Gpu(GpuHistogramAlgorithm::Contiguous),
         GpuRadixPartitionAlgorithm::NC,
         RadixBits::from(2),
@@ -654,8 +654,8 @@ fn gpu_tuple_loss_or_duplicates_contiguous_i32_2_bits() -> Result<(), Box<dyn Er
 fn gpu_tuple_loss_or_duplicates_contiguous_i32_10_bits() -> Result<(), Box<dyn Error>> {
     run_gpu_partitioning(
         (32 << 20) / mem::size_of::<i32>(),
-        Box::new(|keys: &mut _| Ok(UniformRelation::gen_primary_key(keys, None)?)),
-        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr(pays, 0..10000)?)),
+        Box::new(|keys: &mut _| Ok(UniformRelation::gen_primary_key(keys, None, None)?)),
+        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr(pays, 0..10000, None)?)),
         DeviceType::Gpu(GpuHistogramAlgorithm::Contiguous),
         GpuRadixPartitionAlgorithm::NC,
         RadixBits::from(10),
@@ -669,8 +669,8 @@ fn gpu_tuple_loss_or_duplicates_contiguous_i32_10_bits() -> Result<(), Box<dyn E
 fn gpu_verify_partitions_contiguous_i32_2_bits() -> Result<(), Box<dyn Error>> {
     run_gpu_partitioning(
         (32 << 20) / mem::size_of::<i32>(),
-        Box::new(|keys: &mut _| Ok(UniformRelation::gen_attr(keys, 0..(32 << 20))?)),
-        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr(pays, 0..10000)?)),
+        Box::new(|keys: &mut _| Ok(UniformRelation::gen_attr(keys, 0..(32 << 20), None)?)),
+        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr(pays, 0..10000, None)?)),
         DeviceType::Gpu(GpuHistogramAlgorithm::Contiguous),
         GpuRadixPartitionAlgorithm::NC,
         RadixBits::from(2),
@@ -684,8 +684,8 @@ fn gpu_verify_partitions_contiguous_i32_2_bits() -> Result<(), Box<dyn Error>> {
 fn gpu_verify_partitions_contiguous_i32_10_bits() -> Result<(), Box<dyn Error>> {
     run_gpu_partitioning(
         (32 << 20) / mem::size_of::<i32>(),
-        Box::new(|keys: &mut _| Ok(UniformRelation::gen_attr(keys, 0..(32 << 20))?)),
-        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr(pays, 0..10000)?)),
+        Box::new(|keys: &mut _| Ok(UniformRelation::gen_attr(keys, 0..(32 << 20), None)?)),
+        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr(pays, 0..10000, None)?)),
         DeviceType::Gpu(GpuHistogramAlgorithm::Contiguous),
         GpuRadixPartitionAlgorithm::NC,
         RadixBits::from(10),
@@ -699,8 +699,8 @@ fn gpu_verify_partitions_contiguous_i32_10_bits() -> Result<(), Box<dyn Error>>
 fn gpu_tuple_loss_or_duplicates_contiguous_i32_12_bits() -> Result<(), Box<dyn Error>> {
     run_gpu_partitioning(
         (32 << 20) / mem::size_of::<i32>(),
-        Box::new(|keys: &mut _| Ok(UniformRelation::gen_primary_key(keys, None)?)),
-        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr(pays, 0..10000)?)),
+        Box::new(|keys: &mut _| Ok(UniformRelation::gen_primary_key(keys, None, None)?)),
+        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr(pays, 0..10000, None)?)),
         DeviceType::Gpu(GpuHistogramAlgorithm::Contiguous),
         GpuRadixPartitionAlgorithm::NC,
         RadixBits::from(12),
@@ -714,8 +714,8 @@ fn gpu_tuple_loss_or_duplicates_contiguous_i32_12_bits() -> Result<(), Box<dyn E
 fn gpu_verify_partitions_contiguous_i32_12_bits() -> Result<(), Box<dyn Error>> {
     run_gpu_partitioning(
         (32 << 20) / mem::size_of::<i32>(),
-        Box::new(|keys: &mut _| Ok(UniformRelation::gen_attr(keys, 0..(32 << 20))?)),
-        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr(pays, 0..10000)?)),
+        Box::new(|keys: &mut _| Ok(UniformRelation::gen_attr(keys, 0..(32 << 20), None)?)),
+        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr(pays, 0..10000, None)?)),
         DeviceType::Gpu(GpuHistogramAlgorithm::Contiguous),
         GpuRadixPartitionAlgorithm::NC,
         RadixBits::from(12),
@@ -729,8 +729,8 @@ fn gpu_verify_partitions_contiguous_i32_12_bits() -> Result<(), Box<dyn Error>>
 fn gpu_tuple_loss_or_duplicates_contiguous_non_power_two() -> Result<(), Box<dyn Error>> {
     run_gpu_partitioning(
         10_usize.pow(6),
-        Box::new(|keys: &mut _| Ok(UniformRelation::gen_primary_key(keys, None)?)),
-        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr(pays, 0..10000)?)),
+        Box::new(|keys: &mut _| Ok(UniformRelation::gen_primary_key(keys, None, None)?)),
+        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr(pays, 0..10000, None)?)),
         DeviceType::Gpu(GpuHistogramAlgorithm::Contiguous),
         GpuRadixPartitionAlgorithm::NC,
         RadixBits::from(10),
@@ -744,8 +744,8 @@ fn gpu_tuple_loss_or_duplicates_contiguous_non_power_two() -> Result<(), Box<dyn
 fn gpu_verify_partitions_contiguous_non_power_two() -> Result<(), Box<dyn Error>> {
     run_gpu_partitioning(
         10_usize.pow(6),
-        Box::new(|keys: &mut _| Ok(UniformRelation::gen_attr(keys, 0..(32 << 20))?)),
-        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr(pays, 0..10000)?)),
+        Box::new(|keys: &mut _| Ok(UniformRelation::gen_attr(keys, 0..(32 << 20), None)?)),
+        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr(pays, 0..10000, None)?)),
         DeviceType::Gpu(GpuHistogramAlgorithm::Contiguous),
         GpuRadixPartitionAlgorithm::NC,
         RadixBits::from(10),
@@ -759,8 +759,8 @@ fn gpu_verify_partitions_contiguous_non_power_two() -> Result<(), Box<dyn Error>
 fn gpu_tuple_loss_or_duplicates_chunked_laswwc_i32_2_bits() -> Result<(), Box<dyn Error>> {
     run_gpu_partitioning(
         (32 << 20) / mem::size_of::<i32>(),
-        Box::new(|keys: &mut _| Ok(UniformRelation::gen_primary_key(keys, None)?)),
-        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr(pays, 0..10000)?)),
+        Box::new(|keys: &mut _| Ok(UniformRelation::gen_primary_key(keys, None, None)?)),
+        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr(pays, 0..10000, None)?)),
         DeviceType::Gpu(GpuHistogramAlgorithm::Chunked),
         GpuRadixPartitionAlgorithm::LASWWC,
         RadixBits::from(2),
@@ -774,8 +774,8 @@ fn gpu_tuple_loss_or_duplicates_chunked_laswwc_i32_2_bits() -> Result<(), Box<dy
 fn gpu_tuple_loss_or_duplicates_chunked_laswwc_i32_10_bits() -> Result<(), Box<dyn Error>> {
     run_gpu_partitioning(
         (32 << 20) / mem::size_of::<i32>(),
-        Box::new(|keys: &mut _| Ok(UniformRelation::gen_primary_key(keys, None)?)),
-        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr(pays, 0..10000)?)),
+        Box::new(|keys: &mut _| Ok(UniformRelation::gen_primary_key(keys, None, None)?)),
+        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr(pays, 0..10000, None)?)),
         DeviceType::Gpu(GpuHistogramAlgorithm::Chunked),
         GpuRadixPartitionAlgorithm::LASWWC,
         RadixBits::from(10),
@@ -789,8 +789,8 @@ fn gpu_tuple_loss_or_duplicates_chunked_laswwc_i32_10_bits() -> Result<(), Box<d
 fn gpu_verify_partitions_chunked_laswwc_i32_2_bits() -> Result<(), Box<dyn Error>> {
     run_gpu_partitioning(
         (32 << 20) / mem::size_of::<i32>(),
-        Box::new(|keys: &mut _| Ok(UniformRelation::gen_attr(keys, 0..(32 << 20))?)),
-        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr(pays, 0..10000)?)),
+        Box::new(|keys: &mut _| Ok(UniformRelation::gen_attr(keys, 0..(32 << 20), None)?)),
+        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr(pays, 0..10000, None)?)),
         DeviceType::Gpu(GpuHistogramAlgorithm::Chunked),
         GpuRadixPartitionAlgorithm::LASWWC,
         RadixBits::from(2),
@@ -804,8 +804,8 @@ fn gpu_verify_partitions_chunked_laswwc_i32_2_bits() -> Result<(), Box<dyn Error
 fn gpu_verify_partitions_chunked_laswwc_i32_10_bits() -> Result<(), Box<dyn Error>> {
     run_gpu_partitioning(
         (32 << 20) / mem::size_of::<i32>(),
-        Box::new(|keys: &mut _| Ok(UniformRelation::gen_attr(keys, 0..(32 << 20))?)),
-        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr(pays, 0..10000)?)),
+        Box::new(|keys: &mut _| Ok(UniformRelation::gen_attr(keys, 0..(32 << 20), None)?)),
+        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr(pays, 0..10000, None)?)),
         DeviceType::Gpu(GpuHistogramAlgorithm::Chunked),
         GpuRadixPartitionAlgorithm::LASWWC,
         RadixBits::from(10),
@@ -819,8 +819,8 @@ fn gpu_verify_partitions_chunked_laswwc_i32_10_bits() -> Result<(), Box<dyn Erro
 fn gpu_tuple_loss_or_duplicates_chunked_laswwc_non_power_two() -> Result<(), Box<dyn Error>> {
     run_gpu_partitioning(
         10_usize.pow(6),
-        Box::new(|keys: &mut _| Ok(UniformRelation::gen_primary_key(keys, None)?)),
-        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr(pays, 0..10000)?)),
+        Box::new(|keys: &mut _| Ok(UniformRelation::gen_primary_key(keys, None, None)?)),
+        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr(pays, 0..10000, None)?)),
         DeviceType::Gpu(GpuHistogramAlgorithm::Chunked),
         GpuRadixPartitionAlgorithm::LASWWC,
         RadixBits::from(10),
@@ -834,8 +834,8 @@ fn gpu_tuple_loss_or_duplicates_chunked_laswwc_non_power_two() -> Result<(), Box
 fn gpu_verify_partitions_chunked_laswwc_non_power_two() -> Result<(), Box<dyn Error>> {
     run_gpu_partitioning(
         10_usize.pow(6),
-        Box::new(|keys: &mut _| Ok(UniformRelation::gen_attr(keys, 0..(32 << 20))?)),
-        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr(pays, 0..10000)?)),
+        Box::new(|keys: &mut _| Ok(UniformRelation::gen_attr(keys, 0..(32 << 20), None)?)),
+        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr(pays, 0..10000, None)?)),
         DeviceType::Gpu(GpuHistogramAlgorithm::Chunked),
         GpuRadixPartitionAlgorithm::LASWWC,
         RadixBits::from(10),
@@ -849,8 +849,8 @@ fn gpu_verify_partitions_chunked_laswwc_non_power_two() -> Result<(), Box<dyn Er
 fn gpu_tuple_loss_or_duplicates_chunked_sswwc_v2_i32_2_bits() -> Result<(), Box<dyn Error>> {
     run_gpu_partitioning(
         (32 << 20) / mem::size_of::<i32>(),
-        Box::new(|keys: &mut _| Ok(UniformRelation::gen_primary_key(keys, None)?)),
-        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr(pays, 0..10000)?)),
+        Box::new(|keys: &mut _| Ok(UniformRelation::gen_primary_key(keys, None, None)?)),
+        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr(pays, 0..10000, None)?)),
         DeviceType::Gpu(GpuHistogramAlgorithm::Chunked),
         GpuRadixPartitionAlgorithm::SSWWCv2,
         RadixBits::from(2),
@@ -864,8 +864,8 @@ fn gpu_tuple_loss_or_duplicates_chunked_sswwc_v2_i32_2_bits() -> Result<(), Box<
 fn gpu_tuple_loss_or_duplicates_chunked_sswwc_v2_i32_10_bits() -> Result<(), Box<dyn Error>> {
     run_gpu_partitioning(
         (32 << 20) / mem::size_of::<i32>(),
-        Box::new(|keys: &mut _| Ok(UniformRelation::gen_primary_key(keys, None)?)),
-        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr(pays, 0..10000)?)),
+        Box::new(|keys: &mut _| Ok(UniformRelation::gen_primary_key(keys, None, None)?)),
+        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr(pays, 0..10000, None)?)),
         DeviceType::Gpu(GpuHistogramAlgorithm::Chunked),
         GpuRadixPartitionAlgorithm::SSWWCv2,
         RadixBits::from(10),
@@ -879,8 +879,8 @@ fn gpu_tuple_loss_or_duplicates_chunked_sswwc_v2_i32_10_bits() -> Result<(), Box
 fn gpu_tuple_loss_or_duplicates_contiguous_sswwc_v2_i32_10_bits() -> Result<(), Box<dyn Error>> {
     run_gpu_partitioning(
         (32 << 20) / mem::size_of::<i32>(),
-        Box::new(|keys: &mut _| Ok(UniformRelation::gen_primary_key(keys, None)?)),
-        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr(pays, 0..10000)?)),
+        Box::new(|keys: &mut _| Ok(UniformRelation::gen_primary_key(keys, None, None)?)),
+        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr(pays, 0..10000, None)?)),
         DeviceType::Gpu(GpuHistogramAlgorithm::Contiguous),
         GpuRadixPartitionAlgorithm::SSWWCv2,
         RadixBits::from(10),
@@ -894,8 +894,8 @@ fn gpu_tuple_loss_or_duplicates_contiguous_sswwc_v2_i32_10_bits() -> Result<(),
 fn gpu_verify_partitions_chunked_sswwc_v2_i32_2_bits() -> Result<(), Box<dyn Error>> {
     run_gpu_partitioning(
         (32 << 20) / mem::size_of::<i32>(),
-        Box::new(|keys: &mut _| Ok(UniformRelation::gen_attr(keys, 0..(32 << 20))?)),
-        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr(pays, 0..10000)?)),
+        Box::new(|keys: &mut _| Ok(UniformRelation::gen_attr(keys, 0..(32 << 20), None)?)),
+        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr(pays, 0..10000, None)?)),
         DeviceType::Gpu(GpuHistogramAlgorithm::Chunked),
         GpuRadixPartitionAlgorithm::SSWWCv2,
         RadixBits::from(2),
@@ -909,8 +909,8 @@ fn gpu_verify_partitions_chunked_sswwc_v2_i32_2_bits() -> Result<(), Box<dyn Err
 fn gpu_verify_partitions_chunked_sswwc_v2_i32_10_bits() -> Result<(), Box<dyn Error>> {
     run_gpu_partitioning(
         (32 << 20) / mem::size_of::<i32>(),
-        Box::new(|keys: &mut _| Ok(UniformRelation::gen_attr(keys, 0..(32 << 20))?)),
-        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr(pays, 0..10000)?)),
+        Box::new(|keys: &mut _| Ok(UniformRelation::gen_attr(keys, 0..(32 << 20), None)?)),
+        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr(pays, 0..10000, None)?)),
         DeviceType::Gpu(GpuHistogramAlgorithm::Chunked),
         GpuRadixPartitionAlgorithm::SSWWCv2,
         RadixBits::from(10),
@@ -924,8 +924,8 @@ fn gpu_verify_partitions_chunked_sswwc_v2_i32_10_bits() -> Result<(), Box<dyn Er
 fn gpu_verify_partitions_contiguous_sswwc_v2_i32_10_bits() -> Result<(), Box<dyn Error>> {
     run_gpu_partitioning(
         (32 << 20) / mem::size_of::<i32>(),
-        Box::new(|keys: &mut _| Ok(UniformRelation::gen_attr(keys, 0..(32 << 20))?)),
-        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr(pays, 0..10000)?)),
+        Box::new(|keys: &mut _| Ok(UniformRelation::gen_attr(keys, 0..(32 << 20), None)?)),
+        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr(pays, 0..10000, None)?)),
         DeviceType::Gpu(GpuHistogramAlgorithm::Contiguous),
         GpuRadixPartitionAlgorithm::SSWWCv2,
         RadixBits::from(10),
@@ -939,8 +939,8 @@ fn gpu_verify_partitions_contiguous_sswwc_v2_i32_10_bits() -> Result<(), Box<dyn
 fn gpu_tuple_loss_or_duplicates_chunked_sswwc_v2_non_power_two() -> Result<(), Box<dyn Error>> {
     run_gpu_partitioning(
         10_usize.pow(6),
-        Box::new(|keys: &mut _| Ok(UniformRelation::gen_primary_key(keys, None)?)),
-        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr(pays, 0..10000)?)),
+        Box::new(|keys: &mut _| Ok(UniformRelation::gen_primary_key(keys, None, None)?)),
+        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr(pays, 0..10000, None)?)),
         DeviceType::Gpu(GpuHistogramAlgorithm::Chunked),
         GpuRadixPartitionAlgorithm::SSWWCv2,
         RadixBits::from(10),
@@ -954,8 +954,8 @@ fn gpu_tuple_loss_or_duplicates_chunked_sswwc_v2_non_power_two() -> Result<(), B
 fn gpu_verify_partitions_chunked_sswwc_v2_non_power_two() -> Result<(), Box<dyn Error>> {
     run_gpu_partitioning(
         10_usize.pow(6),
-        Box::new(|keys: &mut _| Ok(UniformRelation::gen_attr(keys, 0..(32 << 20))?)),
-        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr(pays, 0..10000)?)),
+        Box::new(|keys: &mut _| Ok(UniformRelation::gen_attr(keys, 0..(32 << 20), None)?)),
+        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr(pays, 0..10000, None)?)),
         DeviceType::Gpu(GpuHistogramAlgorithm::Chunked),
         GpuRadixPartitionAlgorithm::SSWWCv2,
         RadixBits::from(10),
@@ -969,8 +969,8 @@ fn gpu_verify_partitions_chunked_sswwc_v2_non_power_two() -> Result<(), Box<dyn
 fn gpu_tuple_loss_or_duplicates_chunked_sswwc_v2g_i32_2_bits() -> Result<(), Box<dyn Error>> {
     run_gpu_partitioning(
         (32 << 20) / mem::size_of::<i32>(),
-        Box::new(|keys: &mut _| Ok(UniformRelation::gen_primary_key(keys, None)?)),
-        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr(pays, 0..10000)?)),
+        Box::new(|keys: &mut _| Ok(UniformRelation::gen_primary_key(keys, None, None)?)),
+        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr(pays, 0..10000, None)?)),
         DeviceType::Gpu(GpuHistogramAlgorithm::Chunked),
         GpuRadixPartitionAlgorithm::SSWWCv2G,
         RadixBits::from(2),
@@ -984,8 +984,8 @@ fn gpu_tuple_loss_or_duplicates_chunked_sswwc_v2g_i32_2_bits() -> Result<(), Box
 fn gpu_tuple_loss_or_duplicates_chunked_sswwc_v2g_i32_10_bits() -> Result<(), Box<dyn Error>> {
     run_gpu_partitioning(
         (32 << 20) / mem::size_of::<i32>(),
-        Box::new(|keys: &mut _| Ok(UniformRelation::gen_primary_key(keys, None)?)),
-        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr(pays, 0..10000)?)),
+        Box::new(|keys: &mut _| Ok(UniformRelation::gen_primary_key(keys, None, None)?)),
+        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr(pays, 0..10000, None)?)),
         DeviceType::Gpu(GpuHistogramAlgorithm::Chunked),
         GpuRadixPartitionAlgorithm::SSWWCv2G,
         RadixBits::from(10),
@@ -999,8 +999,8 @@ fn gpu_tuple_loss_or_duplicates_chunked_sswwc_v2g_i32_10_bits() -> Result<(), Bo
 fn gpu_verify_partitions_chunked_sswwc_v2g_i32_2_bits() -> Result<(), Box<dyn Error>> {
     run_gpu_partitioning(
         (32 << 20) / mem::size_of::<i32>(),
-        Box::new(|keys: &mut _| Ok(UniformRelation::gen_attr(keys, 0..(32 << 20))?)),
-        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr(pays, 0..10000)?)),
+        Box::new(|keys: &mut _| Ok(UniformRelation::gen_attr(keys, 0..(32 << 20), None)?)),
+        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr(pays, 0..10000, None)?)),
         DeviceType::Gpu(GpuHistogramAlgorithm::Chunked),
         GpuRadixPartitionAlgorithm::SSWWCv2G,
         RadixBits::from(2),
@@ -1014,8 +1014,8 @@ fn gpu_verify_partitions_chunked_sswwc_v2g_i32_2_bits() -> Result<(), Box<dyn Er
 fn gpu_verify_partitions_chunked_sswwc_v2g_i32_10_bits() -> Result<(), Box<dyn Error>> {
     run_gpu_partitioning(
         (32 << 20) / mem::size_of::<i32>(),
-        Box::new(|keys: &mut _| Ok(UniformRelation::gen_attr(keys, 0..(32 << 20))?)),
-        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr(pays, 0..10000)?)),
+        Box::new(|keys: &mut _| Ok(UniformRelation::gen_attr(keys, 0..(32 << 20), None)?)),
+        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr(pays, 0..10000, None)?)),
         DeviceType::Gpu(GpuHistogramAlgorithm::Chunked),
         GpuRadixPartitionAlgorithm::SSWWCv2G,
         RadixBits::from(10),
@@ -1029,8 +1029,8 @@ fn gpu_verify_partitions_chunked_sswwc_v2g_i32_10_bits() -> Result<(), Box<dyn E
 fn gpu_tuple_loss_or_duplicates_chunked_sswwc_v2g_non_power_two() -> Result<(), Box<dyn Error>> {
     run_gpu_partitioning(
         10_usize.pow(6),
-        Box::new(|keys: &mut _| Ok(UniformRelation::gen_primary_key(keys, None)?)),
-        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr(pays, 0..10000)?)),
+        Box::new(|keys: &mut _| Ok(UniformRelation::gen_primary_key(keys, None, None)?)),
+        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr(pays, 0..10000, None)?)),
         DeviceType::Gpu(GpuHistogramAlgorithm::Chunked),
         GpuRadixPartitionAlgorithm::SSWWCv2G,
         RadixBits::from(10),
@@ -1044,8 +1044,8 @@ fn gpu_tuple_loss_or_duplicates_chunked_sswwc_v2g_non_power_two() -> Result<(),
 fn gpu_verify_partitions_chunked_sswwc_v2g_non_power_two() -> Result<(), Box<dyn Error>> {
     run_gpu_partitioning(
         10_usize.pow(6),
-        Box::new(|keys: &mut _| Ok(UniformRelation::gen_attr(keys, 0..(32 << 20))?)),
-        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr(pays, 0..10000)?)),
+        Box::new(|keys: &mut _| Ok(UniformRelation::gen_attr(keys, 0..(32 << 20), None)?)),
+        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr(pays, 0..10000, None)?)),
         DeviceType::Gpu(GpuHistogramAlgorithm::Chunked),
         GpuRadixPartitionAlgorithm::SSWWCv2G,
         RadixBits::from(10),
@@ -1059,8 +1059,8 @@ fn gpu_verify_partitions_chunked_sswwc_v2g_non_power_two() -> Result<(), Box<dyn
 fn gpu_tuple_loss_or_duplicates_chunked_hsswwc_v4_i32_2_bits() -> Result<(), Box<dyn Error>> {
     run_gpu_partitioning(
         (32 << 20) / mem::size_of::<i32>(),
-        Box::new(|keys: &mut _| Ok(UniformRelation::gen_primary_key(keys, None)?)),
-        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr(pays, 0..10000)?)),
+        Box::new(|keys: &mut _| Ok(UniformRelation::gen_primary_key(keys, None, None)?)),
+        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr(pays, 0..10000, None)?)),
         DeviceType::Gpu(GpuHistogramAlgorithm::Chunked),
         GpuRadixPartitionAlgorithm::HSSWWCv4,
         RadixBits::from(2),
@@ -1074,8 +1074,8 @@ fn gpu_tuple_loss_or_duplicates_chunked_hsswwc_v4_i32_2_bits() -> Result<(), Box
 fn gpu_tuple_loss_or_duplicates_chunked_hsswwc_v4_i32_10_bits() -> Result<(), Box<dyn Error>> {
     run_gpu_partitioning(
         (32 << 20) / mem::size_of::<i32>(),
-        Box::new(|keys: &mut _| Ok(UniformRelation::gen_primary_key(keys, None)?)),
-        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr(pays, 0..10000)?)),
+        Box::new(|keys: &mut _| Ok(UniformRelation::gen_primary_key(keys, None, None)?)),
+        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr(pays, 0..10000, None)?)),
         DeviceType::Gpu(GpuHistogramAlgorithm::Chunked),
         GpuRadixPartitionAlgorithm::HSSWWCv4,
         RadixBits::from(10),
@@ -1089,8 +1089,8 @@ fn gpu_tuple_loss_or_duplicates_chunked_hsswwc_v4_i32_10_bits() -> Result<(), Bo
 fn gpu_tuple_loss_or_duplicates_contiguous_hsswwc_v4_i32_10_bits() -> Result<(), Box<dyn Error>> {
     run_gpu_partitioning(
         (32 << 20) / mem::size_of::<i32>(),
-        Box::new(|keys: &mut _| Ok(UniformRelation::gen_primary_key(keys, None)?)),
-        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr(pays, 0..10000)?)),
+        Box::new(|keys: &mut _| Ok(UniformRelation::gen_primary_key(keys, None, None)?)),
+        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr(pays, 0..10000, None)?)),
         DeviceType::Gpu(GpuHistogramAlgorithm::Contiguous),
         GpuRadixPartitionAlgorithm::HSSWWCv4,
         RadixBits::from(10),
@@ -1104,8 +1104,8 @@ fn gpu_tuple_loss_or_duplicates_contiguous_hsswwc_v4_i32_10_bits() -> Result<(),
 fn gpu_verify_partitions_chunked_hsswwc_v4_i32_2_bits() -> Result<(), Box<dyn Error>> {
     run_gpu_partitioning(
         (32 << 20) / mem::size_of::<i32>(),
-        Box::new(|keys: &mut _| Ok(UniformRelation::gen_attr(keys, 0..(32 << 20))?)),
-        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr(pays, 0..10000)?)),
+        Box::new(|keys: &mut _| Ok(UniformRelation::gen_attr(keys, 0..(32 << 20), None)?)),
+        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr(pays, 0..10000, None)?)),
         DeviceType::Gpu(GpuHistogramAlgorithm::Chunked),
         GpuRadixPartitionAlgorithm::HSSWWCv4,
         RadixBits::from(2),
@@ -1119,8 +1119,8 @@ fn gpu_verify_partitions_chunked_hsswwc_v4_i32_2_bits() -> Result<(), Box<dyn Er
 fn gpu_verify_partitions_chunked_hsswwc_v4_i32_10_bits() -> Result<(), Box<dyn Error>> {
     run_gpu_partitioning(
         (32 << 20) / mem::size_of::<i32>(),
-        Box::new(|keys: &mut _| Ok(UniformRelation::gen_attr(keys, 0..(32 << 20))?)),
-        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr(pays, 0..10000)?)),
+        Box::new(|keys: &mut _| Ok(UniformRelation::gen_attr(keys, 0..(32 << 20), None)?)),
+        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr(pays, 0..10000, None)?)),
         DeviceType::Gpu(GpuHistogramAlgorithm::Chunked),
         GpuRadixPartitionAlgorithm::HSSWWCv4,
         RadixBits::from(10),
@@ -1134,8 +1134,8 @@ fn gpu_verify_partitions_chunked_hsswwc_v4_i32_10_bits() -> Result<(), Box<dyn E
 fn gpu_verify_partitions_contiguous_hsswwc_v4_i32_10_bits() -> Result<(), Box<dyn Error>> {
     run_gpu_partitioning(
         (32 << 20) / mem::size_of::<i32>(),
-        Box::new(|keys: &mut _| Ok(UniformRelation::gen_attr(keys, 0..(32 << 20))?)),
-        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr(pays, 0..10000)?)),
+        Box::new(|keys: &mut _| Ok(UniformRelation::gen_attr(keys, 0..(32 << 20), None)?)),
+        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr(pays, 0..10000, None)?)),
         DeviceType::Gpu(GpuHistogramAlgorithm::Contiguous),
         GpuRadixPartitionAlgorithm::HSSWWCv4,
         RadixBits::from(10),
@@ -1149,8 +1149,8 @@ fn gpu_verify_partitions_contiguous_hsswwc_v4_i32_10_bits() -> Result<(), Box<dy
 fn gpu_tuple_loss_or_duplicates_chunked_hsswwc_v4_non_power_two() -> Result<(), Box<dyn Error>> {
     run_gpu_partitioning(
         10_usize.pow(6),
-        Box::new(|keys: &mut _| Ok(UniformRelation::gen_primary_key(keys, None)?)),
-        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr(pays, 0..10000)?)),
+        Box::new(|keys: &mut _| Ok(UniformRelation::gen_primary_key(keys, None, None)?)),
+        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr(pays, 0..10000, None)?)),
         DeviceType::Gpu(GpuHistogramAlgorithm::Chunked),
         GpuRadixPartitionAlgorithm::HSSWWCv4,
         RadixBits::from(10),
@@ -1164,8 +1164,8 @@ fn gpu_tuple_loss_or_duplicates_chunked_hsswwc_v4_non_power_two() -> Result<(),
 fn gpu_verify_partitions_chunked_hsswwc_v4_non_power_two() -> Result<(), Box<dyn Error>> {
     run_gpu_partitioning(
         10_usize.pow(6),
-        Box::new(|keys: &mut _| Ok(UniformRelation::gen_attr(keys, 0..(32 << 20))?)),
-        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr(pays, 0..10000)?)),
+        Box::new(|keys: &mut _| Ok(UniformRelation::gen_attr(keys, 0..(32 << 20), None)?)),
+        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr(pays, 0..10000, None)?)),
         DeviceType::Gpu(GpuHistogramAlgorithm::Chunked),
         GpuRadixPartitionAlgorithm::HSSWWCv4,
         RadixBits::from(10),
@@ -1180,8 +1180,8 @@ fn gpu_tuple_loss_or_duplicates_copy_with_payload_contiguous_i32_2_bits(
 ) -> Result<(), Box<dyn Error>> {
     run_gpu_partitioning_and_copy_with_payload(
         10_usize.pow(6),
-        Box::new(|keys: &mut _| Ok(UniformRelation::gen_primary_key(keys, None)?)),
-        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr(pays, 0..10000)?)),
+        Box::new(|keys: &mut _| Ok(UniformRelation::gen_primary_key(keys, None, None)?)),
+        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr(pays, 0..10000, None)?)),
         GpuHistogramAlgorithm::Contiguous,
         GpuRadixPartitionAlgorithm::NC,
         RadixBits::from(2),
@@ -1198,8 +1198,8 @@ fn gpu_tuple_loss_or_duplicates_copy_with_payload_contiguous_i32_10_bits(
 ) -> Result<(), Box<dyn Error>> {
     run_gpu_partitioning_and_copy_with_payload(
         10_usize.pow(6),
-        Box::new(|keys: &mut _| Ok(UniformRelation::gen_primary_key(keys, None)?)),
-        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr(pays, 0..10000)?)),
+        Box::new(|keys: &mut _| Ok(UniformRelation::gen_primary_key(keys, None, None)?)),
+        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr(pays, 0..10000, None)?)),
         GpuHistogramAlgorithm::Contiguous,
         GpuRadixPartitionAlgorithm::NC,
         RadixBits::from(10),
@@ -1215,8 +1215,8 @@ fn gpu_tuple_loss_or_duplicates_copy_with_payload_contiguous_i32_10_bits(
 fn gpu_verify_partitions_copy_with_payload_contiguous_i32_2_bits() -> Result<(), Box<dyn Error>> {
     run_gpu_partitioning_and_copy_with_payload(
         10_usize.pow(6),
-        Box::new(|keys: &mut _| Ok(UniformRelation::gen_attr(keys, 0..(32 << 20))?)),
-        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr(pays, 0..10000)?)),
+        Box::new(|keys: &mut _| Ok(UniformRelation::gen_attr(keys, 0..(32 << 20), None)?)),
+        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr(pays, 0..10000, None)?)),
         GpuHistogramAlgorithm::Contiguous,
         GpuRadixPartitionAlgorithm::NC,
         RadixBits::from(2),
@@ -1232,8 +1232,8 @@ fn gpu_verify_partitions_copy_with_payload_contiguous_i32_2_bits() -> Result<(),
 fn gpu_verify_partitions_copy_with_payload_contiguous_i32_10_bits() -> Result<(), Box<dyn Error>> {
     run_gpu_partitioning_and_copy_with_payload(
         10_usize.pow(6),
-        Box::new(|keys: &mut _| Ok(UniformRelation::gen_attr(keys, 0..(32 << 20))?)),
-        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr(pays, 0..10000)?)),
+        Box::new(|keys: &mut _| Ok(UniformRelation::gen_attr(keys, 0..(32 << 20), None)?)),
+        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr(pays, 0..10000, None)?)),
         GpuHistogramAlgorithm::Contiguous,
         GpuRadixPartitionAlgorithm::NC,
         RadixBits::from(10),
@@ -1249,8 +1249,8 @@ fn gpu_verify_partitions_copy_with_payload_contiguous_i32_10_bits() -> Result<()
 fn gpu_check_copy_with_payload_contiguous_i32_2_bits() -> Result<(), Box<dyn Error>> {
     run_gpu_partitioning_and_copy_with_payload(
         10_usize.pow(6),
-        Box::new(|keys: &mut _| Ok(UniformRelation::gen_attr(keys, 0..(32 << 20))?)),
-        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr(pays, 0..10000)?)),
+        Box::new(|keys: &mut _| Ok(UniformRelation::gen_attr(keys, 0..(32 << 20), None)?)),
+        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr(pays, 0..10000, None)?)),
         GpuHistogramAlgorithm::Contiguous,
         GpuRadixPartitionAlgorithm::NC,
         RadixBits::from(2),
@@ -1264,8 +1264,8 @@ fn gpu_check_copy_with_payload_contiguous_i32_2_bits() -> Result<(), Box<dyn Err
 fn gpu_check_copy_with_payload_contiguous_i32_10_bits() -> Result<(), Box<dyn Error>> {
     run_gpu_partitioning_and_copy_with_payload(
         10_usize.pow(6),
-        Box::new(|keys: &mut _| Ok(UniformRelation::gen_attr(keys, 0..(32 << 20))?)),
-        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr(pays, 0..10000)?)),
+        Box::new(|keys: &mut _| Ok(UniformRelation::gen_attr(keys, 0..(32 << 20), None)?)),
+        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr(pays, 0..10000, None)?)),
         GpuHistogramAlgorithm::Contiguous,
         GpuRadixPartitionAlgorithm::NC,
         RadixBits::from(10),
@@ -1279,8 +1279,8 @@ fn gpu_check_copy_with_payload_contiguous_i32_10_bits() -> Result<(), Box<dyn Er
 fn gpu_loss_or_duplicates_two_pass_chunked_contiguous_i32_0_0_bits() -> Result<(), Box<dyn Error>> {
     run_gpu_two_pass_partitioning(
         10_usize.pow(6),
-        Box::new(|keys: &mut _| Ok(UniformRelation::gen_primary_key(keys, None)?)),
-        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr(pays, 0..10000)?)),
+        Box::new(|keys: &mut _| Ok(UniformRelation::gen_primary_key(keys, None, None)?)),
+        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr(pays, 0..10000, None)?)),
         GpuHistogramAlgorithm::Chunked,
         GpuRadixPartitionAlgorithm::NC,
         GpuHistogramAlgorithm::Contiguous,
@@ -1296,8 +1296,8 @@ fn gpu_loss_or_duplicates_two_pass_chunked_contiguous_i32_0_0_bits() -> Result<(
 fn gpu_loss_or_duplicates_two_pass_chunked_contiguous_i32_2_2_bits() -> Result<(), Box<dyn Error>> {
     run_gpu_two_pass_partitioning(
         10_usize.pow(6),
-        Box::new(|keys: &mut _| Ok(UniformRelation::gen_primary_key(keys, None)?)),
-        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr(pays, 0..10000)?)),
+        Box::new(|keys: &mut _| Ok(UniformRelation::gen_primary_key(keys, None, None)?)),
+        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr(pays, 0..10000, None)?)),
         GpuHistogramAlgorithm::Chunked,
         GpuRadixPartitionAlgorithm::NC,
         GpuHistogramAlgorithm::Contiguous,
@@ -1314,8 +1314,8 @@ fn gpu_loss_or_duplicates_two_pass_chunked_contiguous_i32_10_10_bits() -> Result
 {
     run_gpu_two_pass_partitioning(
         10_usize.pow(6),
-        Box::new(|keys: &mut _| Ok(UniformRelation::gen_primary_key(keys, None)?)),
-        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr(pays, 0..10000)?)),
+        Box::new(|keys: &mut _| Ok(UniformRelation::gen_primary_key(keys, None, None)?)),
+        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr(pays, 0..10000, None)?)),
         GpuHistogramAlgorithm::Chunked,
         GpuRadixPartitionAlgorithm::NC,
         GpuHistogramAlgorithm::Contiguous,
@@ -1332,8 +1332,8 @@ fn gpu_loss_or_duplicates_two_pass_contiguous_contiguous_i32_2_2_bits() -> Resul
 {
     run_gpu_two_pass_partitioning(
         10_usize.pow(6),
-        Box::new(|keys: &mut _| Ok(UniformRelation::gen_primary_key(keys, None)?)),
-        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr(pays, 0..10000)?)),
+        Box::new(|keys: &mut _| Ok(UniformRelation::gen_primary_key(keys, None, None)?)),
+        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr(pays, 0..10000, None)?)),
         GpuHistogramAlgorithm::Contiguous,
         GpuRadixPartitionAlgorithm::NC,
         GpuHistogramAlgorithm::Contiguous,
@@ -1349,8 +1349,8 @@ fn gpu_loss_or_duplicates_two_pass_contiguous_contiguous_i32_2_2_bits() -> Resul
 fn gpu_verify_two_pass_chunked_contiguous_i32_0_0_bits() -> Result<(), Box<dyn Error>> {
     run_gpu_two_pass_partitioning(
         10_usize.pow(6),
-        Box::new(|keys: &mut _| Ok(UniformRelation::gen_attr(keys, 0..(32 << 20))?)),
-        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr(pays, 0..10000)?)),
+        Box::new(|keys: &mut _| Ok(UniformRelation::gen_attr(keys, 0..(32 << 20), None)?)),
+        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr(pays, 0..10000, None)?)),
         GpuHistogramAlgorithm::Chunked,
         GpuRadixPartitionAlgorithm::NC,
         GpuHistogramAlgorithm::Contiguous,
@@ -1366,8 +1366,8 @@ fn gpu_verify_two_pass_chunked_contiguous_i32_0_0_bits() -> Result<(), Box<dyn E
 fn gpu_verify_two_pass_chunked_contiguous_i32_2_2_bits() -> Result<(), Box<dyn Error>> {
     run_gpu_two_pass_partitioning(
         10_usize.pow(6),
-        Box::new(|keys: &mut _| Ok(UniformRelation::gen_attr(keys, 0..(32 << 20))?)),
-        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr(pays, 0..10000)?)),
+        Box::new(|keys: &mut _| Ok(UniformRelation::gen_attr(keys, 0..(32 << 20), None)?)),
+        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr(pays, 0..10000, None)?)),
         GpuHistogramAlgorithm::Chunked,
         GpuRadixPartitionAlgorithm::NC,
         GpuHistogramAlgorithm::Contiguous,
@@ -1383,8 +1383,8 @@ fn gpu_verify_two_pass_chunked_contiguous_i32_2_2_bits() -> Result<(), Box<dyn E
 fn gpu_verify_two_pass_chunked_contiguous_i32_10_10_bits() -> Result<(), Box<dyn Error>> {
     run_gpu_two_pass_partitioning(
         10_usize.pow(6),
-        Box::new(|keys: &mut _| Ok(UniformRelation::gen_attr(keys, 0..(32 << 20))?)),
-        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr(pays, 0..10000)?)),
+        Box::new(|keys: &mut _| Ok(UniformRelation::gen_attr(keys, 0..(32 << 20), None)?)),
+        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr(pays, 0..10000, None)?)),
         GpuHistogramAlgorithm::Chunked,
         GpuRadixPartitionAlgorithm::NC,
         GpuHistogramAlgorithm::Contiguous,
@@ -1400,8 +1400,8 @@ fn gpu_verify_two_pass_chunked_contiguous_i32_10_10_bits() -> Result<(), Box<dyn
 fn gpu_verify_two_pass_contiguous_contiguous_i32_2_2_bits() -> Result<(), Box<dyn Error>> {
     run_gpu_two_pass_partitioning(
         10_usize.pow(6),
-        Box::new(|keys: &mut _| Ok(UniformRelation::gen_attr(keys, 0..(32 << 20))?)),
-        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr(pays, 0..10000)?)),
+        Box::new(|keys: &mut _| Ok(UniformRelation::gen_attr(keys, 0..(32 << 20), None)?)),
+        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr(pays, 0..10000, None)?)),
         GpuHistogramAlgorithm::Contiguous,
         GpuRadixPartitionAlgorithm::NC,
         GpuHistogramAlgorithm::Contiguous,
@@ -1417,8 +1417,8 @@ fn gpu_verify_two_pass_contiguous_contiguous_i32_2_2_bits() -> Result<(), Box<dy
 fn gpu_transform_two_pass_chunked_contiguous_i32_0_0_bits() -> Result<(), Box<dyn Error>> {
     run_gpu_two_pass_partitioning(
         10_usize.pow(6),
-        Box::new(|keys: &mut _| Ok(UniformRelation::gen_attr(keys, 0..(32 << 20))?)),
-        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr(pays, 0..10000)?)),
+        Box::new(|keys: &mut _| Ok(UniformRelation::gen_attr(keys, 0..(32 << 20), None)?)),
+        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr(pays, 0..10000, None)?)),
         GpuHistogramAlgorithm::Chunked,
         GpuRadixPartitionAlgorithm::NC,
         GpuHistogramAlgorithm::Contiguous,
@@ -1434,8 +1434,8 @@ fn gpu_transform_two_pass_chunked_contiguous_i32_0_0_bits() -> Result<(), Box<dy
 fn gpu_transform_two_pass_chunked_contiguous_i32_2_2_bits() -> Result<(), Box<dyn Error>> {
     run_gpu_two_pass_partitioning(
         10_usize.pow(6),
-        Box::new(|keys: &mut _| Ok(UniformRelation::gen_attr(keys, 0..(32 << 20))?)),
-        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr(pays, 0..10000)?)),
+        Box::new(|keys: &mut _| Ok(UniformRelation::gen_attr(keys, 0..(32 << 20), None)?)),
+        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr(pays, 0..10000, None)?)),
         GpuHistogramAlgorithm::Chunked,
         GpuRadixPartitionAlgorithm::NC,
         GpuHistogramAlgorithm::Contiguous,
@@ -1451,8 +1451,8 @@ fn gpu_transform_two_pass_chunked_contiguous_i32_2_2_bits() -> Result<(), Box<dy
 fn gpu_transform_two_pass_chunked_contiguous_i32_10_10_bits() -> Result<(), Box<dyn Error>> {
     run_gpu_two_pass_partitioning(
         10_usize.pow(6),
-        Box::new(|keys: &mut _| Ok(UniformRelation::gen_attr(keys, 0..(32 << 20))?)),
-        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr(pays, 0..10000)?)),
+        Box::new(|keys: &mut _| Ok(UniformRelation::gen_attr(keys, 0..(32 << 20), None)?)),
+        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr(pays, 0..10000, None)?)),
         GpuHistogramAlgorithm::Chunked,
         GpuRadixPartitionAlgorithm::NC,
         GpuHistogramAlgorithm::Contiguous,
@@ -1468,8 +1468,8 @@ fn gpu_transform_two_pass_chunked_contiguous_i32_10_10_bits() -> Result<(), Box<
 fn gpu_transform_two_pass_contiguous_contiguous_i32_2_2_bits() -> Result<(), Box<dyn Error>> {
     run_gpu_two_pass_partitioning(
         10_usize.pow(6),
-        Box::new(|keys: &mut _| Ok(UniformRelation::gen_attr(keys, 0..(32 << 20))?)),
-        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr(pays, 0..10000)?)),
+        Box::new(|keys: &mut _| Ok(UniformRelation::gen_attr(keys, 0..(32 << 20), None)?)),
+        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr(pays, 0..10000, None)?)),
         GpuHistogramAlgorithm::Contiguous,
         GpuRadixPartitionAlgorithm::NC,
         GpuHistogramAlgorithm::Contiguous,
@@ -1485,8 +1485,8 @@ fn gpu_transform_two_pass_contiguous_contiguous_i32_2_2_bits() -> Result<(), Box
 fn gpu_loss_or_duplicates_two_pass_laswwc_i32_6_6_bits() -> Result<(), Box<dyn Error>> {
     run_gpu_two_pass_partitioning(
         10_usize.pow(6),
-        Box::new(|keys: &mut _| Ok(UniformRelation::gen_primary_key(keys, None)?)),
-        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr(pays, 0..10000)?)),
+        Box::new(|keys: &mut _| Ok(UniformRelation::gen_primary_key(keys, None, None)?)),
+        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr(pays, 0..10000, None)?)),
         GpuHistogramAlgorithm::Chunked,
         GpuRadixPartitionAlgorithm::NC,
         GpuHistogramAlgorithm::Contiguous,
@@ -1502,8 +1502,8 @@ fn gpu_loss_or_duplicates_two_pass_laswwc_i32_6_6_bits() -> Result<(), Box<dyn E
 fn gpu_verify_partitions_two_pass_laswwc_i32_6_6_bits() -> Result<(), Box<dyn Error>> {
     run_gpu_two_pass_partitioning(
         10_usize.pow(6),
-        Box::new(|keys: &mut _| Ok(UniformRelation::gen_attr(keys, 0..(32 << 20))?)),
-        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr(pays, 0..10000)?)),
+        Box::new(|keys: &mut _| Ok(UniformRelation::gen_attr(keys, 0..(32 << 20), None)?)),
+        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr(pays, 0..10000, None)?)),
         GpuHistogramAlgorithm::Chunked,
         GpuRadixPartitionAlgorithm::NC,
         GpuHistogramAlgorithm::Contiguous,
@@ -1519,8 +1519,8 @@ fn gpu_verify_partitions_two_pass_laswwc_i32_6_6_bits() -> Result<(), Box<dyn Er
 fn gpu_loss_or_duplicates_two_pass_sswwc_v2_i32_6_6_bits() -> Result<(), Box<dyn Error>> {
     run_gpu_two_pass_partitioning(
         10_usize.pow(6),
-        Box::new(|keys: &mut _| Ok(UniformRelation::gen_primary_key(keys, None)?)),
-        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr(pays, 0..10000)?)),
+        Box::new(|keys: &mut _| Ok(UniformRelation::gen_primary_key(keys, None, None)?)),
+        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr(pays, 0..10000, None)?)),
         GpuHistogramAlgorithm::Chunked,
         GpuRadixPartitionAlgorithm::NC,
         GpuHistogramAlgorithm::Contiguous,
@@ -1536,8 +1536,8 @@ fn gpu_loss_or_duplicates_two_pass_sswwc_v2_i32_6_6_bits() -> Result<(), Box<dyn
 fn gpu_verify_partitions_two_pass_sswwc_v2_i32_6_6_bits() -> Result<(), Box<dyn Error>> {
     run_gpu_two_pass_partitioning(
         10_usize.pow(6),
-        Box::new(|keys: &mut _| Ok(UniformRelation::gen_attr(keys, 0..(32 << 20))?)),
-        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr(pays, 0..10000)?)),
+        Box::new(|keys: &mut _| Ok(UniformRelation::gen_attr(keys, 0..(32 << 20), None)?)),
+        Box::new(|pays: &mut _| Ok(UniformRelation::gen_attr(pays, 0..10000, None)?)),
         GpuHistogramAlgorithm::Chunked,
         GpuRadixPartitionAlgorithm::NC,
         GpuHistogramAlgorithm::Contiguous,
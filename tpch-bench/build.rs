@@ -21,78 +21,81 @@ fn main() {
     let out_dir = env::var("OUT_DIR").unwrap();
     let cpp_compiler = env::var("CXX");
 
-    // Add CUDA utils
-    let cuda_lib_file = format!("{}/cudautils.fatbin", out_dir);
-    let cuda_files = vec!["cudautils/queries.cu"];
-    let nvcc_build_args = vec!["--device-c", "-std=c++11", "--output-directory", &out_dir];
-    let nvcc_link_args = vec!["--device-link", "-fatbin", "--output-file", &cuda_lib_file];
-    let nvcc_host_compiler_args: Vec<_> = cpp_compiler
-        .as_ref()
-        .map_or_else(|_| Vec::new(), |cxx| ["-ccbin", cxx.as_str()].into());
+    // Add CUDA utils, which are only required by the GPU queries. Skip nvcc
+    // for CPU-only builds, i.e., if the `cuda` feature is disabled.
+    if env::var("CARGO_FEATURE_CUDA").is_ok() {
+        let cuda_lib_file = format!("{}/cudautils.fatbin", out_dir);
+        let cuda_files = vec!["cudautils/queries.cu"];
+        let nvcc_build_args = vec!["--device-c", "-std=c++11", "--output-directory", &out_dir];
+        let nvcc_link_args = vec!["--device-link", "-fatbin", "--output-file", &cuda_lib_file];
+        let nvcc_host_compiler_args: Vec<_> = cpp_compiler
+            .as_ref()
+            .map_or_else(|_| Vec::new(), |cxx| ["-ccbin", cxx.as_str()].into());
 
-    // For gencodes, see: http://arnon.dk/matching-sm-architectures-arch-and-gencode-for-various-nvidia-cards/
-    let gpu_archs = vec![
-        "-gencode",
-        "arch=compute_50,code=sm_50", // GTX 940M
-        "-gencode",
-        "arch=compute_52,code=sm_52", // GTX 980
-        "-gencode",
-        "arch=compute_53,code=sm_53", // Jetson Nano
-        "-gencode",
-        "arch=compute_61,code=sm_61", // GTX 1080
-        "-gencode",
-        "arch=compute_70,code=sm_70", // Tesla V100
-    ];
+        // For gencodes, see: http://arnon.dk/matching-sm-architectures-arch-and-gencode-for-various-nvidia-cards/
+        let gpu_archs = vec![
+            "-gencode",
+            "arch=compute_50,code=sm_50", // GTX 940M
+            "-gencode",
+            "arch=compute_52,code=sm_52", // GTX 980
+            "-gencode",
+            "arch=compute_53,code=sm_53", // Jetson Nano
+            "-gencode",
+            "arch=compute_61,code=sm_61", // GTX 1080
+            "-gencode",
+            "arch=compute_70,code=sm_70", // Tesla V100
+        ];
 
-    let output = Command::new("nvcc")
-        .args(cuda_files.as_slice())
-        .args(nvcc_host_compiler_args.as_slice())
-        .args(nvcc_build_args.as_slice())
-        .args(gpu_archs.as_slice())
-        .output()
-        .expect("Couldn't execute nvcc");
+        let output = Command::new("nvcc")
+            .args(cuda_files.as_slice())
+            .args(nvcc_host_compiler_args.as_slice())
+            .args(nvcc_build_args.as_slice())
+            .args(gpu_archs.as_slice())
+            .output()
+            .expect("Couldn't execute nvcc");
 
-    if !output.status.success() {
-        eprintln!("status: {}", output.status);
-        eprintln!("stdout: {}", String::from_utf8_lossy(&output.stdout));
-        eprintln!("stderr: {}", String::from_utf8_lossy(&output.stderr));
-        panic!();
-    }
+        if !output.status.success() {
+            eprintln!("status: {}", output.status);
+            eprintln!("stdout: {}", String::from_utf8_lossy(&output.stdout));
+            eprintln!("stderr: {}", String::from_utf8_lossy(&output.stderr));
+            panic!();
+        }
 
-    let cuda_object_files: Vec<_> = cuda_files
-        .as_slice()
-        .iter()
-        .map(|f| {
-            let p = Path::new(f);
-            let mut obj = PathBuf::new();
-            obj.push(&out_dir);
-            obj.push(p.file_stem().unwrap());
-            obj.set_extension("o");
-            obj
-        })
-        .collect();
+        let cuda_object_files: Vec<_> = cuda_files
+            .as_slice()
+            .iter()
+            .map(|f| {
+                let p = Path::new(f);
+                let mut obj = PathBuf::new();
+                obj.push(&out_dir);
+                obj.push(p.file_stem().unwrap());
+                obj.set_extension("o");
+                obj
+            })
+            .collect();
 
-    let output = Command::new("nvcc")
-        .args(cuda_object_files.as_slice())
-        .args(nvcc_link_args.as_slice())
-        .args(gpu_archs.as_slice())
-        .output()
-        .expect("Couldn't execute nvcc");
+        let output = Command::new("nvcc")
+            .args(cuda_object_files.as_slice())
+            .args(nvcc_link_args.as_slice())
+            .args(gpu_archs.as_slice())
+            .output()
+            .expect("Couldn't execute nvcc");
 
-    if !output.status.success() {
-        eprintln!("status: {}", output.status);
-        eprintln!("stdout: {}", String::from_utf8_lossy(&output.stdout));
-        eprintln!("stderr: {}", String::from_utf8_lossy(&output.stderr));
-        panic!();
-    }
+        if !output.status.success() {
+            eprintln!("status: {}", output.status);
+            eprintln!("stdout: {}", String::from_utf8_lossy(&output.stdout));
+            eprintln!("stderr: {}", String::from_utf8_lossy(&output.stderr));
+            panic!();
+        }
 
-    println!(
-        "cargo:rustc-env=CUDAUTILS_PATH={}/cudautils.fatbin",
-        out_dir
-    );
-    println!("cargo:rustc-link-search=native=/opt/cuda/lib64");
-    println!("cargo:rustc-link-search=native=/usr/local/cuda/lib64");
-    println!("cargo:rustc-link-lib=cudart");
+        println!(
+            "cargo:rustc-env=CUDAUTILS_PATH={}/cudautils.fatbin",
+            out_dir
+        );
+        println!("cargo:rustc-link-search=native=/opt/cuda/lib64");
+        println!("cargo:rustc-link-search=native=/usr/local/cuda/lib64");
+        println!("cargo:rustc-link-lib=cudart");
+    }
 
     // Add CPP utils
     cc::Build::new()
@@ -104,9 +107,6 @@ fn main() {
         .flag_if_supported("-mcpu=native")
         .flag_if_supported("-march=native")
         .flag("-mtune=native")
-        // Note: Enables x86 intrinsic translations on POWER9
-        // See also "Linux on Power Porting Guide - Vector Intrinsics"
-        .define("NO_WARN_X86_INTRINSICS", None)
         .pic(true)
         .file("cpputils/queries.cpp")
         .compile("libcpputils.a");
@@ -21,37 +21,13 @@
 // Disable strided prefetch and set maximum prefetch depth
 #define PPC_TUNE_DSCR 7ULL
 
-extern "C" void tpch_q6_branching(uint64_t length, int32_t *l_shipdate,
-                                  int32_t *l_discount, int32_t *l_quantity,
-                                  int32_t *l_extendedprice, int64_t *revenue) {
-#if defined(__powerpc64__)
-  __mtspr(PPC_DSCR, PPC_TUNE_DSCR);
-#endif
-
-  *revenue = 0;
-  for (uint64_t i = 0; i < length; ++i) {
-    if (l_shipdate[i] >= 366 + 365 + 1 && l_shipdate[i] < 366 + 365 + 365 + 1 &&
-        l_discount[i] >= 5 && l_discount[i] <= 7 && l_quantity[i] < 24) {
-      *revenue += l_extendedprice[i] * l_discount[i];
-    }
-  }
-}
-
-extern "C" void tpch_q6_predication(uint64_t length, int32_t *l_shipdate,
-                                    int32_t *l_discount, int32_t *l_quantity,
-                                    int32_t *l_extendedprice,
-                                    int64_t *revenue) {
+// Tunes the hardware prefetcher of the calling thread for scans.
+//
+// The data stream control register is a per-thread register. Thus, each
+// worker thread must call this function before it scans its chunk. Has no
+// effect on architectures other than POWER.
+extern "C" void tpch_tune_prefetch() {
 #if defined(__powerpc64__)
   __mtspr(PPC_DSCR, PPC_TUNE_DSCR);
 #endif
-
-  *revenue = 0;
-  for (uint64_t i = 0; i < length; ++i) {
-    int condition = (l_shipdate[i] >= 366 + 365 + 1) &
-                    (l_shipdate[i] < 366 + 365 + 365 + 1) &
-                    (l_discount[i] >= 5) & (l_discount[i] <= 7) &
-                    (l_quantity[i] < 24);
-    condition = ((!condition) << 31) >> 31;
-    *revenue += condition & (l_extendedprice[i] * l_discount[i]);
-  }
 }
@@ -30,8 +30,10 @@ pub struct DataPoint {
     pub relation_memory_type: Option<ArgMemType>,
     pub relation_memory_location: Option<u16>,
     pub page_type: Option<ArgPageType>,
+    pub huge_page_fallback: Option<ArgHugePageFallback>,
     pub tuples: Option<usize>,
     pub bytes: Option<usize>,
+    pub huge_page_bytes: Option<usize>,
     pub warm_up: Option<bool>,
     pub ns: Option<f64>,
 }
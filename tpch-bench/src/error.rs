@@ -15,7 +15,9 @@
 use csv::Error as CsvError;
 use datagen::error::{Error as DataGenError, ErrorKind as DataGenErrorKind};
 use numa_gpu::error::Error as NumaGpuError;
+#[cfg(feature = "cuda")]
 use rustacuda::error::CudaError;
+use sql_ops::error::Error as SqlOpsError;
 use std::convert::From;
 use std::io::Error as IoError;
 
@@ -24,6 +26,7 @@ pub type Result<T> = std::result::Result<T, Error>;
 #[derive(Debug)]
 pub enum ErrorKind {
     CsvError(CsvError),
+    #[cfg(feature = "cuda")]
     CudaError(CudaError),
     IntegerOverflow(String),
     InvalidArgument(String),
@@ -32,6 +35,7 @@ pub enum ErrorKind {
     NulCharError(String),
     NumaGpuError(NumaGpuError),
     RuntimeError(String),
+    SqlOpsError(SqlOpsError),
 }
 
 #[derive(Debug)]
@@ -63,6 +67,7 @@ impl std::fmt::Display for ErrorKind {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             ErrorKind::CsvError(ref e) => e.fmt(f),
+            #[cfg(feature = "cuda")]
             ErrorKind::CudaError(ref e) => e.fmt(f),
             ErrorKind::IntegerOverflow(ref s) => write!(f, "IntegerOverflow: {}", s),
             ErrorKind::InvalidArgument(ref s) => write!(f, "InvalidArgument: {}", s),
@@ -71,6 +76,7 @@ impl std::fmt::Display for ErrorKind {
             ErrorKind::NumaGpuError(ref e) => e.fmt(f),
             ErrorKind::Msg(ref s) => write!(f, "Msg: {}", s),
             ErrorKind::RuntimeError(ref s) => write!(f, "Runtime: {}", s),
+            ErrorKind::SqlOpsError(ref e) => e.fmt(f),
         }
     }
 }
@@ -95,6 +101,7 @@ impl From<CsvError> for Error {
     }
 }
 
+#[cfg(feature = "cuda")]
 impl From<CudaError> for Error {
     fn from(e: CudaError) -> Self {
         Self {
@@ -119,12 +126,21 @@ impl From<NumaGpuError> for Error {
     }
 }
 
+impl From<SqlOpsError> for Error {
+    fn from(e: SqlOpsError) -> Self {
+        Self {
+            kind: ErrorKind::SqlOpsError(e),
+        }
+    }
+}
+
 impl From<DataGenError> for Error {
     fn from(e: DataGenError) -> Self {
         let kind = match e.kind {
             DataGenErrorKind::Msg(s) => ErrorKind::Msg(s),
             DataGenErrorKind::IntegerOverflow(o) => ErrorKind::IntegerOverflow(o),
             DataGenErrorKind::InvalidArgument(a) => ErrorKind::InvalidArgument(a),
+            DataGenErrorKind::IoError(e) => ErrorKind::IoError(e),
         };
 
         Self { kind }
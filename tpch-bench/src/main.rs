@@ -19,19 +19,29 @@ mod query_6;
 mod types;
 
 use crate::data_point::DataPoint;
+#[cfg(not(feature = "cuda"))]
+use crate::error::ErrorKind;
 use crate::error::Result;
 use crate::query_6::cpu::Query6Cpu;
+#[cfg(feature = "cuda")]
 use crate::query_6::gpu::Query6Gpu;
 use crate::query_6::tables::{LineItem, LineItemTuple};
 use crate::types::*;
 use num_rational::Ratio;
 use numa_gpu::runtime::allocator::DerefMemType;
 use numa_gpu::runtime::cpu_affinity::CpuAffinity;
-use numa_gpu::runtime::hw_info::{cpu_codename, NvidiaDriverInfo};
+use numa_gpu::runtime::huge_pages;
+use numa_gpu::runtime::hw_info::cpu_codename;
+#[cfg(feature = "cuda")]
+use numa_gpu::runtime::hw_info::NvidiaDriverInfo;
+#[cfg(feature = "cuda")]
 use numa_gpu::runtime::linux_wrapper;
 use numa_gpu::runtime::numa::NodeRatio;
+#[cfg(feature = "cuda")]
 use rustacuda::device::DeviceAttribute;
+#[cfg(feature = "cuda")]
 use rustacuda::function::{BlockSize, GridSize};
+#[cfg(feature = "cuda")]
 use rustacuda::prelude::*;
 use std::mem;
 use std::path::PathBuf;
@@ -42,6 +52,18 @@ fn main() -> Result<()> {
     // Parse commandline arguments
     let cmd = CmdOpt::from_args();
 
+    // Fall back to another page type if huge pages are unavailable
+    huge_pages::set_fallback(cmd.huge_page_fallback.into());
+
+    #[cfg(not(feature = "cuda"))]
+    if cmd.execution_method != ArgExecutionMethod::Cpu {
+        Err(ErrorKind::InvalidArgument(format!(
+            "Execution method {:?} requires the `cuda` feature",
+            cmd.execution_method
+        )))?;
+    }
+
+    #[cfg(feature = "cuda")]
     let _context = if cmd.execution_method != ArgExecutionMethod::Cpu {
         // Initialize CUDA
         rustacuda::init(CudaFlags::empty())?;
@@ -81,11 +103,7 @@ fn main() -> Result<()> {
     }
     .into();
 
-    let cpu_affinity = if let Some(ref cpu_affinity_file) = cmd.cpu_affinity {
-        CpuAffinity::from_file(cpu_affinity_file.as_path())?
-    } else {
-        CpuAffinity::default()
-    };
+    let cpu_affinity = cmd.load_cpu_affinity()?;
 
     let csv_file = cmd
         .csv
@@ -97,14 +115,16 @@ fn main() -> Result<()> {
 
     match cmd.query {
         6 => {
-            let lineitem = LineItem::new(cmd.scale_factor, mem_type)?;
+            let lineitem = LineItem::new(cmd.scale_factor, mem_type, cmd.seed)?;
             template.tuples = Some(lineitem.len());
             template.bytes = Some(mem::size_of::<LineItemTuple>() * lineitem.len());
+            template.huge_page_bytes = Some(lineitem.huge_page_bytes()?);
             let query: Box<dyn FnMut() -> Result<(i64, Duration)>> = match cmd.execution_method {
                 ArgExecutionMethod::Cpu => {
                     let q = Query6Cpu::new(cmd.threads, &cpu_affinity, cmd.selection_variant);
                     Box::new(move || q.run(&lineitem))
                 }
+                #[cfg(feature = "cuda")]
                 ArgExecutionMethod::Gpu => {
                     // Device tuning
                     let device = Device::get_device(cmd.device_id.into())?;
@@ -131,6 +151,12 @@ fn main() -> Result<()> {
     Ok(())
 }
 
+/// CUDA unified memory is only available with the `cuda` feature
+#[cfg(feature = "cuda")]
+const DEFAULT_REL_MEM_TYPE: &str = "Unified";
+#[cfg(not(feature = "cuda"))]
+const DEFAULT_REL_MEM_TYPE: &str = "System";
+
 #[derive(StructOpt)]
 #[structopt(rename_all = "kebab-case")]
 struct CmdOpt {
@@ -141,6 +167,10 @@ struct CmdOpt {
     #[structopt(long, default_value = "1")]
     scale_factor: u32,
 
+    /// Seed of the data generator's random number generator (default: random)
+    #[structopt(long)]
+    seed: Option<u64>,
+
     /// Selection variant
     #[structopt(
         long,
@@ -163,8 +193,11 @@ struct CmdOpt {
     /// Memory type with which to allocate data
     #[structopt(
         long,
-        default_value = "Unified",
-        raw(possible_values = "&ArgMemType::variants()", case_insensitive = "true")
+        raw(
+            default_value = "DEFAULT_REL_MEM_TYPE",
+            possible_values = "&ArgMemType::variants()",
+            case_insensitive = "true"
+        )
     )]
     rel_mem_type: ArgMemType,
 
@@ -183,6 +216,20 @@ struct CmdOpt {
     )]
     page_type: ArgPageType,
 
+    /// Page type to allocate if the requested huge pages are unavailable
+    //   fail: abort with an error
+    //   transparenthuge: fall back to transparent huge pages
+    //   small: fall back to small pages
+    #[structopt(
+        long = "huge-page-fallback",
+        default_value = "Fail",
+        raw(
+            possible_values = "&ArgHugePageFallback::variants()",
+            case_insensitive = "true"
+        )
+    )]
+    huge_page_fallback: ArgHugePageFallback,
+
     /// Execute on device(s) with in-place or streaming-transfer method
     #[structopt(
         long,
@@ -204,21 +251,65 @@ struct CmdOpt {
     /// Path to CPU affinity map file for CPU workers
     #[structopt(long, parse(from_os_str))]
     cpu_affinity: Option<PathBuf>,
+
+    /// Generate the CPU affinities from the NUMA topology (in place of a map file)
+    //   compact: fill all hardware threads of a core before the next core
+    //   scatter: round-robin over NUMA nodes, physical cores first
+    //   nodelocal: only CPUs of the node specified with cpu-affinity-node
+    //   physicalcores: one hardware thread per physical core
+    #[structopt(
+        long = "cpu-affinity-policy",
+        conflicts_with = "cpu-affinity",
+        raw(
+            possible_values = "&ArgCpuAffinityPolicy::variants()",
+            case_insensitive = "true"
+        )
+    )]
+    cpu_affinity_policy: Option<ArgCpuAffinityPolicy>,
+
+    /// NUMA node of the node-local CPU affinity policy
+    #[structopt(long, default_value = "0")]
+    cpu_affinity_node: u16,
 }
 
 impl CmdOpt {
+    fn load_cpu_affinity(&self) -> Result<CpuAffinity> {
+        let cpu_affinity = if let Some(ref cpu_affinity_file) = self.cpu_affinity {
+            CpuAffinity::from_file(cpu_affinity_file.as_path())?
+        } else if let Some(policy) = self.cpu_affinity_policy {
+            CpuAffinity::from_policy(
+                ArgCpuAffinityPolicyHelper {
+                    policy,
+                    node: self.cpu_affinity_node,
+                }
+                .into(),
+            )?
+        } else {
+            CpuAffinity::default()
+        };
+
+        Ok(cpu_affinity)
+    }
+
     fn fill_data_point(&self, data_point: &DataPoint) -> Result<DataPoint> {
         // Get device information
         let dev_codename_str = match self.execution_method {
             ArgExecutionMethod::Cpu => vec![cpu_codename()?],
+            #[cfg(feature = "cuda")]
             ArgExecutionMethod::Gpu | ArgExecutionMethod::GpuStream => {
                 let device = Device::get_device(self.device_id.into())?;
                 vec![device.name()?]
             }
+            #[cfg(feature = "cuda")]
             ArgExecutionMethod::Het | ArgExecutionMethod::GpuBuildHetProbe => {
                 let device = Device::get_device(self.device_id.into())?;
                 vec![cpu_codename()?, device.name()?]
             }
+            #[cfg(not(feature = "cuda"))]
+            em @ _ => Err(ErrorKind::InvalidArgument(format!(
+                "Execution method {:?} requires the `cuda` feature",
+                em
+            )))?,
         };
 
         let dp = DataPoint {
@@ -237,6 +328,7 @@ impl CmdOpt {
             relation_memory_type: Some(self.rel_mem_type),
             relation_memory_location: Some(self.rel_location),
             page_type: Some(self.page_type),
+            huge_page_fallback: Some(self.huge_page_fallback),
             ..data_point.clone()
         };
 
@@ -13,5 +13,6 @@
 // limitations under the License.
 
 pub mod cpu;
+#[cfg(feature = "cuda")]
 pub mod gpu;
 pub mod tables;
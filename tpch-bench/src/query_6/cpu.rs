@@ -17,28 +17,20 @@ use crate::error::{ErrorKind, Result};
 use crate::types::ArgSelectionVariant;
 use numa_gpu::runtime::cpu_affinity::CpuAffinity;
 use numa_gpu::utils::CachePadded;
+use sql_ops::select::{ColumnPredicate, CpuSelection, CpuSelectionAlgorithm, Predicate};
+use std::ops::Bound;
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 
 extern "C" {
-    fn tpch_q6_branching(
-        length: u64,
-        l_shipdate: *const i32,
-        l_discount: *const i32,
-        l_quantity: *const i32,
-        l_extendedprice: *const i32,
-        revenue: *mut i64,
-    );
-    fn tpch_q6_predication(
-        length: u64,
-        l_shipdate: *const i32,
-        l_discount: *const i32,
-        l_quantity: *const i32,
-        l_extendedprice: *const i32,
-        revenue: *mut i64,
-    );
+    fn tpch_tune_prefetch();
 }
 
+/// Number of tuples that are selected at a time.
+///
+/// The batch's selection vector should fit into the L1 cache.
+const BATCH_LEN: usize = 4096;
+
 pub struct Query6Cpu {
     threads: usize,
     cpu_affinity: CpuAffinity,
@@ -60,6 +52,7 @@ impl Query6Cpu {
 
     pub fn run(&self, lineitem: &LineItem) -> Result<(i64, Duration)> {
         let mut thread_revenue = vec![CachePadded { value: 0_i64 }; self.threads];
+        let mut thread_results: Vec<Result<()>> = (0..self.threads).map(|_| Ok(())).collect();
 
         let boxed_cpu_affinity = Arc::new(self.cpu_affinity.clone());
         let thread_pool = rayon::ThreadPoolBuilder::new()
@@ -83,39 +76,89 @@ impl Query6Cpu {
             .chunks(chunk_len)
             .collect();
 
-        let q6_f = match self.selection_variant {
-            ArgSelectionVariant::Branching => tpch_q6_branching,
-            ArgSelectionVariant::Predication => tpch_q6_predication,
-        };
+        let selection = CpuSelection::new(CpuSelectionAlgorithm::from(self.selection_variant));
+        let selection = &selection;
 
         let timer = Instant::now();
         thread_pool.scope(|s| {
-            for (((((_tid, l_shipdate), l_discount), l_quantity), l_extendedprice), revenue) in (0
-                ..self.threads)
+            for (
+                (((((_tid, l_shipdate), l_discount), l_quantity), l_extendedprice), revenue),
+                result,
+            ) in (0..self.threads)
                 .zip(l_shipdate_chunks)
                 .zip(l_discount_chunks)
                 .zip(l_quantity_chunks)
                 .zip(l_extendedprice_chunks)
                 .zip(thread_revenue.iter_mut())
+                .zip(thread_results.iter_mut())
             {
                 s.spawn(move |_| {
-                    unsafe {
-                        q6_f(
-                            l_shipdate.len() as u64,
-                            l_shipdate.as_ptr(),
-                            l_discount.as_ptr(),
-                            l_quantity.as_ptr(),
-                            l_extendedprice.as_ptr(),
-                            &mut revenue.value,
-                        )
-                    };
+                    *result = Self::run_chunk(
+                        selection,
+                        l_shipdate,
+                        l_discount,
+                        l_quantity,
+                        l_extendedprice,
+                        &mut revenue.value,
+                    );
                 });
             }
         });
         let time = timer.elapsed();
 
+        thread_results.into_iter().collect::<Result<()>>()?;
         let revenue = thread_revenue.iter().map(|padded| padded.value).sum();
 
         Ok((revenue, time))
     }
+
+    /// Computes the revenue of a chunk of lineitem.
+    fn run_chunk(
+        selection: &CpuSelection,
+        l_shipdate: &[i32],
+        l_discount: &[i32],
+        l_quantity: &[i32],
+        l_extendedprice: &[i32],
+        revenue: &mut i64,
+    ) -> Result<()> {
+        let mut selection_vector = vec![0_u32; BATCH_LEN];
+        *revenue = 0;
+
+        unsafe {
+            tpch_tune_prefetch();
+        }
+
+        for (((l_shipdate, l_discount), l_quantity), l_extendedprice) in l_shipdate
+            .chunks(BATCH_LEN)
+            .zip(l_discount.chunks(BATCH_LEN))
+            .zip(l_quantity.chunks(BATCH_LEN))
+            .zip(l_extendedprice.chunks(BATCH_LEN))
+        {
+            let predicates = [
+                ColumnPredicate::new(
+                    l_shipdate,
+                    Predicate::Range(
+                        Bound::Included(366 + 365 + 1),
+                        Bound::Excluded(366 + 365 + 365 + 1),
+                    ),
+                ),
+                ColumnPredicate::new(
+                    l_discount,
+                    Predicate::Range(Bound::Included(5), Bound::Included(7)),
+                ),
+                ColumnPredicate::new(
+                    l_quantity,
+                    Predicate::Range(Bound::Unbounded, Bound::Excluded(24)),
+                ),
+            ];
+
+            let selected = selection.select(&predicates, &mut selection_vector)?;
+            *revenue += selection_vector[0..selected]
+                .iter()
+                .map(|&i| l_extendedprice[i as usize] as i64 * l_discount[i as usize] as i64)
+                .sum::<i64>();
+        }
+
+        Ok(())
+    }
 }
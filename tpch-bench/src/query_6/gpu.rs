@@ -97,6 +97,9 @@ impl Query6Gpu {
                     )
                 )?
             },
+            ArgSelectionVariant::Simd => Err(ErrorKind::InvalidArgument(
+                "SIMD selection is not supported on the GPU".to_string(),
+            ))?,
         }
 
         stop_event.record(&stream)?;
@@ -13,11 +13,14 @@
 // limitations under the License.
 
 use crate::error::Result;
-use datagen::relation::UniformRelation;
+use datagen::relation::{derive_seed, UniformRelation, PAR_BLOCK_LEN};
 use numa_gpu::runtime::allocator::{Allocator, DerefMemType};
+use numa_gpu::runtime::huge_pages;
 use numa_gpu::runtime::memory::DerefMem;
 // use rand::distributions::weighted::alias_method::WeightedIndex;
 use rand::distributions::{Distribution, Uniform};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
 use rand_distr::Normal;
 use rayon::prelude::*;
 
@@ -78,16 +81,21 @@ pub struct LineItem {
 }
 
 impl LineItem {
-    pub fn new(scale_factor: u32, mem_type: DerefMemType) -> Result<LineItem> {
+    /// Generates the LineItem table.
+    ///
+    /// `seed` seeds the random number generators. Generating with the same
+    /// seed results in the same table. By default (`None`), the seed is random.
+    pub fn new(scale_factor: u32, mem_type: DerefMemType, seed: Option<u64>) -> Result<LineItem> {
         let alloc = Allocator::deref_mem_alloc_fn::<i32>(mem_type.clone());
         let len_orders: usize = scale_factor as usize * 1_500_000;
+        let seed = Some(seed.unwrap_or_else(|| rand::thread_rng().gen()));
 
         // Calculate lineitem length based on central limit theorem
         let len_lineitem_mean: f64 = len_orders as f64 * 4.0;
         let len_lineitem_variance: f64 = 3.0;
         let normal_distribution = Normal::new(len_lineitem_mean, len_lineitem_variance.sqrt())
             .expect("Failed because standard deviation is too small; Looks like a bug!");
-        let mut rng = rand::thread_rng();
+        let mut rng = stream_rng(seed, 0);
         let len_lineitem: usize = normal_distribution.sample(&mut rng) as usize;
 
         // Generate l_shipdate
@@ -138,17 +146,25 @@ impl LineItem {
 
         let order_date_distribution = Uniform::from(0..=(2557 - 151));
         let shipdate_distribution = Uniform::from(1..=121);
-        shipdate.par_iter_mut().for_each_init(
-            || rand::thread_rng(),
-            |mut rng, date| {
-                *date = order_date_distribution.sample(&mut rng)
-                    + shipdate_distribution.sample(&mut rng)
-            },
-        );
-
-        UniformRelation::gen_attr_par(&mut discount, 0..11)?;
-        UniformRelation::gen_attr_par(&mut quantity, 1..51)?;
-        UniformRelation::gen_attr_par(&mut extendedprice, 1..(i32::max_value() as usize + 1))?;
+        let shipdate_seed = derive_seed(seed, 1);
+        shipdate
+            .par_chunks_mut(PAR_BLOCK_LEN)
+            .enumerate()
+            .for_each(|(block, chunk)| {
+                let mut rng = stream_rng(shipdate_seed, block as u64);
+                chunk.iter_mut().for_each(|date| {
+                    *date = order_date_distribution.sample(&mut rng)
+                        + shipdate_distribution.sample(&mut rng)
+                });
+            });
+
+        UniformRelation::gen_attr_par(&mut discount, 0..11, derive_seed(seed, 2))?;
+        UniformRelation::gen_attr_par(&mut quantity, 1..51, derive_seed(seed, 3))?;
+        UniformRelation::gen_attr_par(
+            &mut extendedprice,
+            1..(i32::max_value() as usize + 1),
+            derive_seed(seed, 4),
+        )?;
 
         // Finalize lineitem relation
         Ok(Self {
@@ -162,4 +178,19 @@ impl LineItem {
     pub fn len(&self) -> usize {
         self.shipdate.len()
     }
+
+    /// Returns the number of bytes of the table that are backed by huge pages.
+    pub fn huge_page_bytes(&self) -> Result<usize> {
+        let bytes = huge_pages::huge_page_bytes(&self.shipdate)?
+            + huge_pages::huge_page_bytes(&self.discount)?
+            + huge_pages::huge_page_bytes(&self.quantity)?
+            + huge_pages::huge_page_bytes(&self.extendedprice)?;
+
+        Ok(bytes)
+    }
+}
+
+/// Creates a random number generator for the stream with ID `stream`.
+fn stream_rng(seed: Option<u64>, stream: u64) -> StdRng {
+    StdRng::seed_from_u64(derive_seed(seed, stream).expect("Seed is missing"))
 }
@@ -13,8 +13,11 @@
 // limitations under the License.
 
 use numa_gpu::runtime::allocator;
+use numa_gpu::runtime::huge_pages::HugePageFallback;
 use numa_gpu::runtime::numa::{NodeRatio, PageType};
+pub use numa_gpu::runtime::topology::{ArgCpuAffinityPolicy, ArgCpuAffinityPolicyHelper};
 use serde_derive::Serialize;
+use sql_ops::select::CpuSelectionAlgorithm;
 use structopt::clap::arg_enum;
 
 arg_enum! {
@@ -42,6 +45,15 @@ arg_enum! {
     }
 }
 
+arg_enum! {
+    #[derive(Copy, Clone, Debug, PartialEq, Serialize)]
+    pub enum ArgHugePageFallback {
+        Fail,
+        TransparentHuge,
+        Small,
+    }
+}
+
 arg_enum! {
     #[derive(Copy, Clone, Debug, PartialEq, Serialize)]
     pub enum ArgExecutionMethod {
@@ -58,6 +70,7 @@ arg_enum! {
     pub enum ArgSelectionVariant {
         Branching,
         Predication,
+        Simd,
     }
 }
 
@@ -82,6 +95,7 @@ impl From<ArgMemTypeHelper> for allocator::DerefMemType {
                 node: node_ratios[0].node,
                 page_type: page_type.into(),
             },
+            #[cfg(feature = "cuda")]
             ArgMemType::NumaPinned => allocator::DerefMemType::NumaPinnedMem {
                 node: node_ratios[0].node,
                 page_type: page_type.into(),
@@ -90,8 +104,14 @@ impl From<ArgMemTypeHelper> for allocator::DerefMemType {
                 nodes: node_ratios,
                 page_type: page_type.into(),
             },
+            #[cfg(feature = "cuda")]
             ArgMemType::Pinned => allocator::DerefMemType::CudaPinnedMem,
+            #[cfg(feature = "cuda")]
             ArgMemType::Unified => allocator::DerefMemType::CudaUniMem,
+            #[cfg(not(feature = "cuda"))]
+            ArgMemType::NumaPinned | ArgMemType::Pinned | ArgMemType::Unified => {
+                panic!("Error: {:?} memory requires the `cuda` feature!", mem_type)
+            }
         }
     }
 }
@@ -109,3 +129,23 @@ impl From<ArgPageType> for PageType {
         }
     }
 }
+
+impl From<ArgHugePageFallback> for HugePageFallback {
+    fn from(fallback: ArgHugePageFallback) -> Self {
+        match fallback {
+            ArgHugePageFallback::Fail => HugePageFallback::Fail,
+            ArgHugePageFallback::TransparentHuge => HugePageFallback::TransparentHuge,
+            ArgHugePageFallback::Small => HugePageFallback::Small,
+        }
+    }
+}
+
+impl From<ArgSelectionVariant> for CpuSelectionAlgorithm {
+    fn from(variant: ArgSelectionVariant) -> Self {
+        match variant {
+            ArgSelectionVariant::Branching => CpuSelectionAlgorithm::Branching,
+            ArgSelectionVariant::Predication => CpuSelectionAlgorithm::Predication,
+            ArgSelectionVariant::Simd => CpuSelectionAlgorithm::Simd,
+        }
+    }
+}